@@ -0,0 +1,22 @@
+#!/usr/bin/env bash
+# Script for compile-checking the crates which are supported on non-Linux targets.
+set -e
+
+source ci/common.bash
+
+# $1 {path} = Path to cross/cargo executable
+CROSS=$1
+# $2 {string} = <Target Triple>
+TARGET_TRIPLE=$2
+
+required_arg "$CROSS" 'CROSS'
+required_arg "$TARGET_TRIPLE" '<Target Triple>'
+
+# $3.. {string} = Packages to check
+shift 2
+PACKAGES=()
+for package in "$@"; do
+    PACKAGES+=(-p "$package")
+done
+
+$CROSS clippy --target "$TARGET_TRIPLE" "${PACKAGES[@]}" -- -D warnings
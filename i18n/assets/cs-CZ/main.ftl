@@ -53,6 +53,40 @@ expand-ui = Nastavení rozhraní
 error-no-server-name = Není zadána adresa serveru
 error-no-auth = Není vybrána metoda ověřování
 error-file-not-exist = Soubor neexistuje: {$path}
+error-invalid-config-file = Neplatný konfigurační soubor {$path}: {$message}
+error-profile-not-found = Profil {$profile} nebyl v konfiguraci nalezen
+error-in-profile = Profil {$profile}: {$message}
+error-invalid-env-var = Neplatná hodnota proměnné prostředí {$name}: {$message}
+error-invalid-configuration = Neplatná konfigurace:
+error-invalid-server-name = Neplatná adresa serveru: {$server}
+error-route-host-bits = {$option}: {$route} má nastavené bity hostitele, použijte {$network}
+error-options-conflict = {$first} nelze kombinovat s {$second}
+error-insecure-permissions = Do {$path} mohou zapisovat ostatní uživatelé
+error-secret-file-permissions = {$path} obsahuje tajné údaje, ale mají k němu přístup i jiní uživatelé (režim {$mode}), omezte jej pomocí chmod 600 nebo použijte --insecure-permissions
+error-secret-file-symlink = {$path} obsahuje tajné údaje, ale odkazuje na {$target} v jiném adresáři, použijte přímo soubor nebo --insecure-permissions
+error-script-not-executable = Skript není spustitelný: {$path}
+error-value-out-of-range = {$option} musí být mezi {$min} a {$max}, zadáno {$value}
+error-timeout-exceeds-total = {$option} s hodnotou {$value} sekund je delší než {$total} s hodnotou {$limit} sekund, žádný pokus by nemohl skončit včas
+error-reserved-routing-table = Směrovací tabulka {$table} je vyhrazena systémem
+error-control-socket-in-use = Řídicí soket {$path} používá jiný proces
+error-invalid-control-command = Neplatný řídicí příkaz: {$message}
+error-invalid-log-level = Neplatná úroveň protokolování: {$level}
+error-tunnel-not-running = Tunel neběží
+error-invalid-dbus-service = Neplatná sběrnice služby D-Bus
+error-invalid-notify-event = Neplatná událost oznámení
+error-invalid-log-format = Neplatný formát protokolu
+error-invalid-log-target = Neplatný cíl protokolu
+error-invalid-log-filter = Neplatný filtr protokolu {$filter}: {$error}
+error-profile-not-served = Tunel běží pro profil {$current}, profil {$profile} vyžaduje jinou instanci
+error-profile-running = Tunel pro profil {$profile} již běží jako proces {$pid}, pro jeho zastavení použijte --replace
+error-instance-not-stopped = Tunel profilu {$profile} v procesu {$pid} se nezastavil
+error-tunnel-not-stopped = Tunel procesu {$pid} se neukončil do {$timeout} sekund, použijte --force k jeho ukončení a obnovení síťových nastavení
+error-dbus-not-authorized = Akce {$action} není povolena
+error-pid-file-locked = Soubor PID {$path} je uzamčen jinou spuštěnou instancí
+error-unknown-run-as = Neznámý uživatel nebo skupina pro běh bez oprávnění: {$name}
+error-run-as-not-root = Volba run-as-user vyžaduje spuštění pod uživatelem root
+error-run-as-dns = Obnovení resolv.conf a nástroj resolvconf vyžadují root, s volbou run-as-user použijte dns-backend=resolved nebo no-dns
+error-privilege-drop = Nelze přepnout na uživatele bez oprávnění: {$error}
 error-invalid-cert-id = ID certifikátu není v hexadecimálním formátu: {$id}
 error-ca-root-not-exist = Cesta ke kořenovému certifikátu CA neexistuje: {$path}
 error-validation = Chyba ověření
@@ -60,13 +94,15 @@ error-user-input-canceled = Vstup uživatele zrušen
 error-connection-cancelled = Připojení zrušeno
 error-unknown-event = Neznámá událost: {$event}
 error-no-service-connection = Žádné připojení ke službě
+error-session-not-owned = Relace patří uživateli s ID {$uid}, ovládat ji může pouze tento uživatel nebo root
+error-option-not-permitted = Služba tomuto uživateli nepovoluje volbu {$option}
 error-empty-input = Vstup nemůže být prázdný
 error-invalid-object = Neplatný objekt
 error-no-connector = Žádný konektor tunelu
 error-tunnel-disconnected = Tunel odpojen, poslední zpráva: {$message}
 error-unexpected-reply = Neočekávaná odpověď
 error-auth-failed = Ověření selhalo
-error-no-login-type = Chybí povinný parametr: login-type
+error-missing-parameter = Chybí povinný parametr: { $option }
 error-connection-timeout = Časový limit připojení
 error-invalid-response = Neplatná odpověď
 error-cannot-send-request = Nelze odeslat požadavek na službu
@@ -80,7 +116,13 @@ error-no-pkcs8 = Žádná cesta PKCS8 PEM není poskytnuta
 error-no-pkcs11 = Žádný PIN PKCS11 není poskytnut
 error-no-ipsec-session = Žádná IPSEC relace
 error-request-failed-error-code = Požadavek selhal, kód chyby: {$error_code}
+error-gateway-info-refused = Brána {$server} neodpovídá na neověřené dotazy, kód chyby: {$error_code}
 error-no-root-privileges = Tento program musí být spuštěn jako root uživatel!
+error-missing-privileges = Zvolené možnosti vyžadují oprávnění, která tento proces nemá (kontrolu lze přeskočit pomocí --skip-permission-check):
+privilege-net-admin = CAP_NET_ADMIN je potřeba k vytvoření zařízení tun a nastavení adres, tras a DNS: spusťte program jako root, udělte oprávnění příkazem sudo setcap cap_net_admin+ep { $exe }, spusťte jej ze služby systemd s AmbientCapabilities=CAP_NET_ADMIN, nebo předejte předem vytvořené zařízení pomocí tun-fd spolu s no-device-config, no-routing a no-dns
+privilege-resolv-conf = K nastavení DNS je potřeba oprávnění k zápisu do { $path }: spusťte program jako root, zvolte jiný dns-backend nebo nastavte no-dns=true
+privilege-resolvconf-tool = Nástroj resolvconf může používat pouze root: zvolte jiný dns-backend nebo nastavte no-dns=true
+privilege-nft = Příkaz nft je potřeba pro kill-switch a forward-source: nainstalujte balíček nftables nebo tyto možnosti vypněte
 error-missing-required-parameters = Chybí povinné parametry: název serveru a/nebo typ přístupu!
 error-missing-server-name = Chybí povinný parametr: název serveru!
 error-no-connector-for-challenge-code = Žádný konektor pro odeslání kódu výzvy!
@@ -89,11 +131,13 @@ error-invalid-sexpr = Neplatný sexpr: {$value}
 error-invalid-value = Neplatná hodnota
 error-udp-request-failed = Chyba při odesílání UDP požadavku
 error-no-tty = Žádný TTY připojen pro vstup uživatele
+error-service-mfa = Služba nemůže odpovídat na výzvy MFA ani otevřít prohlížeč poskytovatele identity, použijte uložené heslo s volbou password nebo ověření certifikátem s volbou cert-type
 error-invalid-auth-response = Neplatná odpověď ověření
 error-invalid-client-settings = Neplatná nastavení klienta
 error-invalid-otp-reply = Neplatná odpověď OTP
 error-udp-encap-failed = Nelze nastavit možnost soketu UDP_ENCAP, kód chyby: {$code}
 error-so-no-check-failed = Nelze nastavit možnost soketu SO_NO_CHECK, kód chyby: {$code}
+error-so-mark-failed = Nelze nastavit možnost soketu SO_MARK, kód chyby: {$code}
 error-keepalive-failed = Keepalive selhal
 error-receive-failed = Příjem selhal
 error-unknown-color-scheme = Neznámá hodnota barevného schématu
@@ -104,12 +148,55 @@ error-invalid-operation-mode = Neplatný provozní režim
 error-invalid-tunnel-type = Neplatný typ tunelu
 error-invalid-cert-type = Neplatný typ certifikátu
 error-invalid-icon-theme = Neplatný motiv ikon
+error-invalid-dns-backend = Neplatný způsob konfigurace DNS
+error-invalid-dns-mode = Neplatný režim DNS
+error-invalid-dns-override = Neplatná priorita DNS
+error-invalid-address-conflict-action = Neplatná akce při konfliktu adres
+error-resolv-conf-symlink = {$path} je symbolický odkaz, přímý režim DNS jej nebude měnit; zvolte jiný dns-backend
+error-resolv-conf-read-only = {$path} je jen pro čtení nebo připojený běhovým prostředím kontejneru: nastavte dns-resolv-path pro zápis jinam, nebo dns-backend=print pro použití ze skriptu
+error-invalid-device-name = Neplatný název rozhraní: {$name}
+error-invalid-netns-name = Neplatný název síťového jmenného prostoru: {$name}
+error-device-name-in-use = Rozhraní {$name} již existuje
+error-invalid-tun-fd = Popisovač souboru musí patřit zařízení tun otevřenému s IFF_TUN a IFF_NO_PI: {$message}
+error-unknown-tun-owner = Neznámý uživatel nebo skupina {$name}
+error-tun-ioctl = Nelze použít {$request} na {$name}: {$message}
+error-tun-ioctl-permission = Použití {$request} na {$name} není povoleno, zařízení musí připravit root nebo proces s oprávněním CAP_NET_ADMIN
+error-invalid-network-manager-mode = Neplatný režim NetworkManageru
+error-invalid-ipv6-block-mode = Neplatný režim blokování IPv6
+error-invalid-mss-clamp-mode = Neplatný režim omezení MSS
+error-no-free-device-name = Pro šablonu {$name} není volný název rozhraní
+error-invalid-trusted-network = Neplatné kritérium důvěryhodné sítě: {$value}
+error-invalid-network-match = Neplatné kritérium shody sítě: {$value}
+error-invalid-health-listen = Neplatná adresa koncových bodů stavu: {$value}
+error-invalid-proxy = Neplatný proxy server, očekáváno none, auto nebo http://[user:password@]host[:port]: {$value}
+error-proxy-connect-failed = Proxy server {$proxy} odmítl spojení s {$destination}: {$status}
+error-invalid-route = Neplatná trasa: {$value}
 error-no-natt-reply = Žádná odpověď NATT
 error-not-implemented = Neimplementováno
 error-unknown-packet-type = Neznámý typ paketu
 error-no-sender = Žádný odesílatel
 error-empty-ccc-session = Prázdná CCC relace
 error-identity-timeout = Časový limit při čekání na odpověď identity, je typ přístupu správný?
+error-no-office-mode-address = Brána nepřidělila použitelnou adresu: {$address}
+error-gateway-error = Chyba brány, kód: {$code}
+error-gateway-reconnect = Brána opakovaně požadovala nové připojení: {$message}
+error-hello-rejected = Brána odmítla úvodní zprávu klienta: {$message}
+error-roaming-timeout = Tunel se nepodařilo obnovit do {$seconds} sekund po změně sítě
+error-kill-switch-unavailable = Kill switch vyžaduje nástroj nft: {$message}
+error-forwarding-unavailable = Přeposílání z LAN vyžaduje nástroj nft: {$message}
+error-mss-clamp-unavailable = Omezení MSS vyžaduje nástroj nft: {$message}
+error-app-routing-unavailable = Směrování aplikací vyžaduje nástroj nft: {$message}
+error-dns-redirect-unavailable = Přesměrování DNS vyžaduje nástroj nft: {$message}
+error-forward-source-conflict = Zdrojová síť přeposílání {$source} se překrývá s adresou nebo trasami tunelu
+error-setup-missing-option = V neinteraktivním režimu je volba { $option } povinná
+error-setup-config-exists = { $path } již existuje, pro přepsání použijte --force
+error-setup-invalid-choice = Neplatná volba: { $value }
+error-setup-unreachable = Nelze se spojit s { $server }: { $error }
+error-setup-certificate-rejected = Certifikát brány není důvěryhodný, přidejte jeho CA volbou ca-cert nebo jej přijměte pomocí --ignore-server-cert true
+error-import-read = Nelze přečíst { $path }: { $error }
+error-import-empty = { $path } neobsahuje žádná nastavení, která lze importovat
+error-import-not-toml = Nastavení se importují do konfigurace TOML, { $path } nemá příponu .toml
+error-import-profile-exists = Profil { $profile } již v { $path } existuje
 
 # Placeholder texts
 placeholder-domains = Domény oddělené čárkami
@@ -134,18 +221,42 @@ icon-theme-light = Světlý
 
 # Connection info
 info-connected-since = Připojeno od
+info-profile = Profil
 info-server-name = Název serveru
 info-user-name = Uživatelské jméno
 info-login-type = Typ přihlášení
 info-tunnel-type = Typ tunelu
 info-transport-type = Typ transportu
+info-protocol-version = Verze protokolu
 info-ip-address = IP adresa
+info-ipv6-address = IPv6 adresa
 info-dns-servers = DNS servery
 info-search-domains = Vyhledávací domény
+info-wins-servers = WINS servery
 info-interface = Rozhraní
+info-interface-mode = Režim rozhraní
+info-interface-mode-p2p = point-to-point, protějšek { $peer }
+info-interface-mode-local = pouze místní adresa
 info-dns-configured = DNS nakonfigurováno
+info-dns-mode = Režim DNS
+info-dns-override = Priorita DNS
 info-routing-configured = Směrování nakonfigurováno
 info-default-route = Výchozí trasa
+info-reauth-interval = Interval opětovného ověření
+info-forwarding = Přeposílání z LAN
+info-dns-check = Test úniku DNS
+info-dns-check-passed = úspěch
+info-dns-check-failed = selhání
+info-routes = Trasy
+info-received = Přijato
+info-sent = Odesláno
+info-traffic-amount = {$bytes}, paketů: {$packets}
+info-stats-since = Statistiky od
+info-connected-time = Doba připojení
+info-reconnects = Opětovná připojení
+info-reauths = Opětovná ověření
+info-rekeys = Výměny klíčů
+info-last-error = Poslední chyba
 
 # Application
 app-title = SNX-RS VPN klient pro Linux
@@ -175,8 +286,57 @@ tray-menu-exit = Ukončit
 cli-identity-provider-auth = Pro ověření přes poskytovatele identity otevřete následující URL ve vašem prohlížeči:
 cli-tunnel-connected = Tunel připojen, stiskněte Ctrl+C pro ukončení.
 cli-tunnel-disconnected = Tunel odpojen
+notify-connected = VPN připojena ({$address})
+notify-reconnecting = VPN se znovu připojuje (pokus {$attempt})
+notify-disconnected = VPN odpojena
+notify-disconnected-reason = VPN odpojena: {$reason}
+notify-reauth-failed = Opětovné ověření VPN selhalo: {$error}
+cli-waiting-for-connect = Čekání na příkaz k připojení
+cli-config-reloaded = Konfigurace byla znovu načtena
+cli-reload-on-reconnect = Tyto volby se použijí při příštím opětovném připojení: { $options }
+cli-reload-on-restart = Tyto volby se použijí po restartu snx-rs: { $options }
+cli-reload-failed = Konfiguraci nelze znovu načíst, zůstává aktuální: { $error }
+cli-daemon-terminated = Procesu {$pid} byl odeslán signál k ukončení
+cli-tunnel-stopping = Čekání na ukončení tunelu procesu {$pid}
+cli-tunnel-killed = Ukončování procesu {$pid} a obnova síťových nastavení, která zanechal
+cli-network-restored = Síťová nastavení obnovena
 cli-another-instance-running = Jiná instance snx-rs již běží
+cli-replacing-instance = Zastavuje se běžící tunel procesu {$pid}
 cli-app-terminated = Aplikace ukončena signálem
+cli-session-expiring = Relace VPN vyprší za {$minutes} min, tunel bude odpojen
+cli-dns-check-failed = Varování: test úniku DNS selhal, dotazy DNS nemusí procházet tunelem
+cli-trusted-network-lost = Důvěryhodná síť již není k dispozici, obnovuji připojení VPN
+cli-gateway-failover = Brána {$failed} je nedostupná, zkouší se {$server}
+cli-gateway-failback = Brána {$server} je opět dostupná, přepíná se zpět na ni
+cli-service-installed = Služba {$name} byla nainstalována, spusťte ji příkazem: sc.exe start {$name}
+cli-service-uninstalled = Služba {$name} byla odinstalována
+cli-profile-switch = Síť se změnila, přepíná se na profil {$profile}
+cli-dry-run-auth-failed = Zkušební běh: ověření selhalo: { $error }
+cli-dry-run-negotiation-failed = Zkušební běh: vyjednání tunelu selhalo: { $error }
+cli-dry-run-complete = Zkušební běh dokončen, konfigurace sítě nebyla změněna.
+setup-server-prompt = Adresa brány
+setup-probing = Kontroluje se brána { $server }...
+setup-certificate = Certifikát: { $subject }, vydal { $issuer }, platný do { $expires }
+setup-fingerprint = Otisk SHA-256: { $fingerprint }
+setup-certificate-untrusted = Certifikát není důvěryhodný: { $error }
+setup-ignore-cert-prompt = Přesto tento certifikát přijmout
+setup-login-options = Typy přihlášení nabízené bránou:
+setup-login-type-prompt = Typ přihlášení, číslo nebo id [{ $default }]
+setup-user-prompt = Uživatelské jméno
+setup-password-prompt = Heslo
+setup-store-password-prompt = Uložit heslo do klíčenky
+setup-default-route-prompt = Směrovat veškerý provoz tunelem (plný tunel)
+setup-dns-mode-prompt = Režim DNS, split nebo full [split]
+setup-overwrite-prompt = { $path } již existuje, přepsat
+setup-cancelled = Nastavení zrušeno, stávající konfigurace zůstává beze změny
+setup-config-written = Konfigurace zapsána do { $path }
+setup-test-connect-prompt = Otestovat nyní přihlášení
+setup-test-connect-ok = Přihlášení proběhlo úspěšně
+import-config-written = Importováno nastavení: { $count } z { $source } do { $path }
+import-unmapped = Neimportováno, řádek { $line }: { $key }
+import-login-type-missing = Nebyl importován žádný typ přihlášení, "snx-rs info -s { $server }" vypíše typy přihlášení brány pro volbu login-type
+prompt-missing-parameters = V konfiguraci chybí povinné parametry: { $options }
+prompt-save-answers = Uložit odpovědi do { $path }
 
 # Connection Messages
 connection-connected-to = Připojeno k {$server}
@@ -204,6 +364,7 @@ connection-status-disconnected = Odpojeno
 connection-status-connecting = Probíhá připojování
 connection-status-connected-since = Připojeno od: {$since}
 connection-status-mfa-pending = Čeká se na MFA: {$mfa_type}
+connection-status-trusted-network = Zjištěna důvěryhodná síť, VPN pozastavena
 
 # Login options
 login-options-server-address = Adresa serveru
@@ -214,3 +375,11 @@ login-options-preferred-protocol = Preferovaný protokol
 login-options-tcpt-port = Port TCPT
 login-options-natt-port = Port NATT
 login-options-internal-ca-fingerprint = Otisk interního CA
+login-options-protocol-version = Verze protokolu
+login-options-ipsec-transport = Přenos IPSec
+login-options-saml-enabled = SAML povoleno
+login-options-certificate-subject = Subjekt certifikátu
+login-options-certificate-issuer = Vydavatel certifikátu
+login-options-certificate-expires = Platnost certifikátu do
+login-options-certificate-fingerprint = Otisk SHA-256 certifikátu
+login-options-certificate-error = Certifikát není důvěryhodný
@@ -53,6 +53,40 @@ expand-ui = Brugergrænseflade-indstillinger
 error-no-server-name = Ingen serveradresse angivet
 error-no-auth = Ingen godkendelsesmetode valgt
 error-file-not-exist = Filen findes ikke: {$path}
+error-invalid-config-file = Ugyldig konfigurationsfil {$path}: {$message}
+error-profile-not-found = Profilen {$profile} blev ikke fundet i konfigurationen
+error-in-profile = Profil {$profile}: {$message}
+error-invalid-env-var = Ugyldig værdi i miljøvariablen {$name}: {$message}
+error-invalid-configuration = Ugyldig konfiguration:
+error-invalid-server-name = Ugyldig serveradresse: {$server}
+error-route-host-bits = {$option}: {$route} har værtsbits sat, brug {$network}
+error-options-conflict = {$first} kan ikke kombineres med {$second}
+error-insecure-permissions = {$path} kan skrives af andre brugere
+error-secret-file-permissions = {$path} indeholder hemmeligheder, men andre brugere har adgang til den (tilstand {$mode}), begræns den med chmod 600 eller brug --insecure-permissions
+error-secret-file-symlink = {$path} indeholder hemmeligheder, men linker til {$target} i en anden mappe, brug selve filen eller --insecure-permissions
+error-script-not-executable = Scriptet er ikke eksekverbart: {$path}
+error-value-out-of-range = {$option} skal være mellem {$min} og {$max}, fik {$value}
+error-timeout-exceeds-total = {$option} på {$value} sekunder er længere end {$total} på {$limit} sekunder, intet forsøg kan nå at blive færdigt
+error-reserved-routing-table = Routingtabel {$table} er reserveret af systemet
+error-control-socket-in-use = Kontrolsoklen {$path} bruges af en anden proces
+error-invalid-control-command = Ugyldig kontrolkommando: {$message}
+error-invalid-log-level = Ugyldigt logniveau: {$level}
+error-tunnel-not-running = Tunnelen kører ikke
+error-invalid-dbus-service = Ugyldig bus til D-Bus-tjenesten
+error-invalid-notify-event = Ugyldig notifikationshændelse
+error-invalid-log-format = Ugyldigt logformat
+error-invalid-log-target = Ugyldigt logmål
+error-invalid-log-filter = Ugyldigt logfilter {$filter}: {$error}
+error-profile-not-served = Tunnelen kører for profilen {$current}, profilen {$profile} kræver en anden instans
+error-profile-running = En tunnel for profilen {$profile} kører allerede som processen {$pid}, brug --replace for at stoppe den
+error-instance-not-stopped = Tunnelen for profilen {$profile} i processen {$pid} stoppede ikke
+error-tunnel-not-stopped = Tunnelen i proces {$pid} afsluttede ikke inden for {$timeout} sekunder, brug --force for at dræbe den og gendanne netværksindstillingerne
+error-dbus-not-authorized = Handlingen {$action} er ikke tilladt
+error-pid-file-locked = PID-filen {$path} er låst af en anden kørende instans
+error-unknown-run-as = Ukendt bruger eller gruppe til kørsel uden rettigheder: {$name}
+error-run-as-not-root = Indstillingen run-as-user kræver start som root
+error-run-as-dns = Gendannelse af resolv.conf og resolvconf-værktøjet kræver root, brug dns-backend=resolved eller no-dns med run-as-user
+error-privilege-drop = Kan ikke skifte til den uprivilegerede bruger: {$error}
 error-invalid-cert-id = Certifikat-ID er ikke i hexadecimalt format: {$id}
 error-ca-root-not-exist = CA-rodsti findes ikke: {$path}
 error-validation = Valideringsfejl
@@ -60,13 +94,15 @@ error-user-input-canceled = Brugerinput annulleret
 error-connection-cancelled = Forbindelse annulleret
 error-unknown-event = Ukendt begivenhed: {$event}
 error-no-service-connection = Ingen forbindelse til tjenesten
+error-session-not-owned = Sessionen tilhører brugeren med ID {$uid}, kun denne bruger eller root kan styre den
+error-option-not-permitted = Tjenesten tillader ikke indstillingen {$option} for denne bruger
 error-empty-input = Input kan ikke være tomt
 error-invalid-object = Ugyldigt objekt
 error-no-connector = Ingen tunnelforbindelse
 error-tunnel-disconnected = Tunnel afbrudt, sidste besked: {$message}
 error-unexpected-reply = Uventet svar
 error-auth-failed = Godkendelse mislykkedes
-error-no-login-type = Manglende påkrævet parameter: login-type
+error-missing-parameter = Manglende påkrævet parameter: { $option }
 error-connection-timeout = Forbindelsestimeout
 error-invalid-response = Ugyldigt svar
 error-cannot-send-request = Kan ikke sende anmodning til tjenesten
@@ -80,18 +116,26 @@ error-no-pkcs8 = Ingen PKCS8 PEM-sti angivet
 error-no-pkcs11 = Ingen PKCS11 PIN angivet
 error-no-ipsec-session = Ingen IPSEC-session
 error-request-failed-error-code = Anmodning mislykkedes, feilkode: {$error_code}
+error-gateway-info-refused = Gatewayen {$server} besvarer ikke uautentificerede forespørgsler, fejlkode: {$error_code}
 error-no-root-privileges = Dette program skal køres som root-bruger!
+error-missing-privileges = De valgte indstillinger kræver rettigheder, som denne proces ikke har (kontrollen kan springes over med --skip-permission-check):
+privilege-net-admin = CAP_NET_ADMIN kræves for at oprette tun-enheden og konfigurere adresser, ruter og DNS: kør som root, tildel rettigheden med sudo setcap cap_net_admin+ep { $exe }, start fra en systemd-enhed med AmbientCapabilities=CAP_NET_ADMIN, eller overfør en forudoprettet enhed med tun-fd sammen med no-device-config, no-routing og no-dns
+privilege-resolv-conf = Skriveadgang til { $path } kræves for at konfigurere DNS: kør som root, vælg en anden dns-backend eller angiv no-dns=true
+privilege-resolvconf-tool = Værktøjet resolvconf kan kun bruges af root: vælg en anden dns-backend eller angiv no-dns=true
+privilege-nft = Kommandoen nft kræves af kill-switch og forward-source: installer pakken nftables eller slå disse indstillinger fra
 error-missing-required-parameters = Manglende påkrævede parametre: servernavn og/eller adgangstype!
 error-missing-server-name = Manglende påkrævet parameter: servernavn!
 error-invalid-sexpr = Ugyldig sexpr: {$value}
 error-invalid-value = Ugyldig værdi
 error-udp-request-failed = Fejl ved afsendelse af UDP-anmodning
 error-no-tty = Ingen TTY tilsluttet til brugerinput
+error-service-mfa = Tjenesten kan ikke besvare MFA-udfordringer eller åbne browseren for en identitetsudbyder, brug en gemt adgangskode med indstillingen password eller certifikatgodkendelse med cert-type
 error-invalid-auth-response = Ugyldigt godkendelsessvar
 error-invalid-client-settings = Ugyldige klientindstillinger
 error-invalid-otp-reply = Ugyldigt OTP-svar
 error-udp-encap-failed = Kan ikke indstille UDP_ENCAP socket-option, feilkode: {$code}
 error-so-no-check-failed = Kan ikke indstille SO_NO_CHECK socket-option, feilkode: {$code}
+error-so-mark-failed = Kan ikke indstille SO_MARK socket-option, feilkode: {$code}
 error-keepalive-failed = Keepalive mislykkedes
 error-receive-failed = Modtagelse mislykkedes
 error-unknown-color-scheme = Ukendt farveskema-værdi
@@ -102,12 +146,55 @@ error-invalid-operation-mode = Ugyldig driftsmåde
 error-invalid-tunnel-type = Ugyldig tunneltype
 error-invalid-cert-type = Ugyldig certifikattype
 error-invalid-icon-theme = Ugyldigt ikon-tema
+error-invalid-dns-backend = Ugyldig DNS-backend
+error-invalid-dns-mode = Ugyldig DNS-tilstand
+error-invalid-dns-override = Ugyldig DNS-prioritet
+error-invalid-address-conflict-action = Ugyldig handling ved adressekonflikt
+error-resolv-conf-symlink = {$path} er et symbolsk link, direkte DNS-tilstand ændrer det ikke; vælg en anden dns-backend
+error-resolv-conf-read-only = {$path} er skrivebeskyttet eller monteret af containerens runtime: angiv dns-resolv-path for at skrive et andet sted, eller dns-backend=print for at anvende det fra et script
+error-invalid-device-name = Ugyldigt interfacenavn: {$name}
+error-invalid-netns-name = Ugyldigt navn på netværksnavnerum: {$name}
+error-device-name-in-use = Interfacet {$name} findes allerede
+error-invalid-tun-fd = Fildeskriptoren skal tilhøre en tun-enhed åbnet med IFF_TUN og IFF_NO_PI: {$message}
+error-unknown-tun-owner = Ukendt bruger eller gruppe {$name}
+error-tun-ioctl = Kan ikke anvende {$request} på {$name}: {$message}
+error-tun-ioctl-permission = {$request} på {$name} er ikke tilladt, enheden skal klargøres af root eller en proces med CAP_NET_ADMIN
+error-invalid-network-manager-mode = Ugyldig NetworkManager-tilstand
+error-invalid-ipv6-block-mode = Ugyldig IPv6-blokeringstilstand
+error-invalid-mss-clamp-mode = Ugyldig MSS-begrænsningstilstand
+error-no-free-device-name = Intet ledigt interfacenavn for skabelonen {$name}
+error-invalid-trusted-network = Ugyldigt kriterium for betroet netværk: {$value}
+error-invalid-network-match = Ugyldigt kriterium for netværksmatch: {$value}
+error-invalid-health-listen = Ugyldig adresse for sundhedsendepunkterne: {$value}
+error-invalid-proxy = Ugyldig proxy, forventede none, auto eller http://[user:password@]host[:port]: {$value}
+error-proxy-connect-failed = Proxyen {$proxy} afviste forbindelsen til {$destination}: {$status}
+error-invalid-route = Ugyldig rute: {$value}
 error-no-natt-reply = Intet NATT-svar
 error-not-implemented = Ikke implementeret
 error-unknown-packet-type = Ukendt pakketype
 error-no-sender = Ingen afsender
 error-empty-ccc-session = Tom CCC-session
 error-identity-timeout = Timeout ved venten på identitetssvar, er adgangstypen korrekt?
+error-no-office-mode-address = Gatewayen tildelte ikke en brugbar adresse: {$address}
+error-gateway-error = Gatewayfejl, kode: {$code}
+error-gateway-reconnect = Gatewayen anmodede gentagne gange om genforbindelse: {$message}
+error-hello-rejected = Gatewayen afviste klientens hello-besked: {$message}
+error-roaming-timeout = Tunnelen kunne ikke genetableres inden for {$seconds} sekunder efter netværksændringen
+error-kill-switch-unavailable = Kill switch kræver værktøjet nft: {$message}
+error-forwarding-unavailable = LAN-videresendelse kræver nft-værktøjet: {$message}
+error-mss-clamp-unavailable = MSS-begrænsning kræver nft-værktøjet: {$message}
+error-app-routing-unavailable = Applikationsrouting kræver nft-værktøjet: {$message}
+error-dns-redirect-unavailable = DNS-omdirigering kræver nft-værktøjet: {$message}
+error-forward-source-conflict = Kildenetværket {$source} for videresendelse overlapper tunnelens adresse eller ruter
+error-setup-missing-option = Indstillingen { $option } er påkrævet i ikke-interaktiv tilstand
+error-setup-config-exists = { $path } findes allerede, brug --force for at overskrive den
+error-setup-invalid-choice = Ugyldigt valg: { $value }
+error-setup-unreachable = Kan ikke nå { $server }: { $error }
+error-setup-certificate-rejected = Gatewayens certifikat er ikke betroet, tilføj dets CA med indstillingen ca-cert eller accepter det med --ignore-server-cert true
+error-import-read = Kan ikke læse { $path }: { $error }
+error-import-empty = { $path } indeholder ingen indstillinger, der kan importeres
+error-import-not-toml = Indstillingerne importeres til en TOML-konfiguration, { $path } har ikke filtypen .toml
+error-import-profile-exists = Profilen { $profile } findes allerede i { $path }
 
 # Placeholder texts
 placeholder-domains = Domæner adskilt med komma
@@ -132,18 +219,42 @@ icon-theme-light = Lys
 
 # Connection info
 info-connected-since = Forbundet siden
+info-profile = Profil
 info-server-name = Servernavn
 info-user-name = Brugernavn
 info-login-type = Logintype
 info-tunnel-type = Tunneltype
 info-transport-type = Transporttype
+info-protocol-version = Protokolversion
 info-ip-address = IP-adresse
+info-ipv6-address = IPv6-adresse
 info-dns-servers = DNS-servere
 info-search-domains = Søgedomæner
+info-wins-servers = WINS-servere
 info-interface = Interface
+info-interface-mode = Interfacetilstand
+info-interface-mode-p2p = punkt-til-punkt, modpart { $peer }
+info-interface-mode-local = kun lokal adresse
 info-dns-configured = DNS konfigureret
+info-dns-mode = DNS-tilstand
+info-dns-override = DNS-prioritet
 info-routing-configured = Routing konfigureret
 info-default-route = Standardrute
+info-reauth-interval = Genautentificeringsinterval
+info-forwarding = LAN-videresendelse
+info-dns-check = DNS-lækagetest
+info-dns-check-passed = bestået
+info-dns-check-failed = fejlet
+info-routes = Ruter
+info-received = Modtaget
+info-sent = Sendt
+info-traffic-amount = {$bytes}, {$packets} pakker
+info-stats-since = Statistik siden
+info-connected-time = Forbundet tid
+info-reconnects = Genforbindelser
+info-reauths = Genautentificeringer
+info-rekeys = Nøgleudskiftninger
+info-last-error = Seneste fejl
 
 # Application
 app-title = SNX-RS VPN-klient til Linux
@@ -173,8 +284,57 @@ tray-menu-exit = Afslut
 cli-identity-provider-auth = For godkendelse via identitetsudbyder, åbn følgende URL i din browser:
 cli-tunnel-connected = Tunnel forbundet, tryk Ctrl+C for at afslutte.
 cli-tunnel-disconnected = Tunnel forbindelse afbrudt
+notify-connected = VPN forbundet ({$address})
+notify-reconnecting = VPN genforbinder (forsøg {$attempt})
+notify-disconnected = VPN afbrudt
+notify-disconnected-reason = VPN afbrudt: {$reason}
+notify-reauth-failed = Fornyet godkendelse af VPN mislykkedes: {$error}
+cli-waiting-for-connect = Venter på kommandoen til at forbinde
+cli-config-reloaded = Konfigurationen er genindlæst
+cli-reload-on-reconnect = Disse indstillinger anvendes ved næste genforbindelse: { $options }
+cli-reload-on-restart = Disse indstillinger anvendes efter genstart af snx-rs: { $options }
+cli-reload-failed = Konfigurationen kan ikke genindlæses, den nuværende bevares: { $error }
+cli-daemon-terminated = Afslutningssignalet er sendt til proces {$pid}
+cli-tunnel-stopping = Venter på at tunnelen i proces {$pid} afslutter
+cli-tunnel-killed = Dræber proces {$pid} og gendanner de netværksindstillinger, den efterlod
+cli-network-restored = Netværksindstillinger gendannet
 cli-another-instance-running = En anden forekomst af snx-rs kører allerede
+cli-replacing-instance = Stopper den kørende tunnel i processen {$pid}
 cli-app-terminated = Applikation afsluttet af signal
+cli-session-expiring = VPN-sessionen udløber om {$minutes} min, tunnelen vil blive afbrudt
+cli-dns-check-failed = Advarsel: DNS-lækagetesten fejlede, DNS-forespørgsler går muligvis ikke gennem tunnelen
+cli-trusted-network-lost = Betroet netværk ikke længere registreret, genoptager VPN-forbindelsen
+cli-gateway-failover = Gatewayen {$failed} kan ikke nås, prøver {$server}
+cli-gateway-failback = Gatewayen {$server} kan nås igen, skifter tilbage til den
+cli-service-installed = Tjenesten {$name} er installeret, start den med: sc.exe start {$name}
+cli-service-uninstalled = Tjenesten {$name} er afinstalleret
+cli-profile-switch = Netværket er ændret, skifter til profilen {$profile}
+cli-dry-run-auth-failed = Prøvekørsel: godkendelse mislykkedes: { $error }
+cli-dry-run-negotiation-failed = Prøvekørsel: forhandling af tunnelen mislykkedes: { $error }
+cli-dry-run-complete = Prøvekørsel fuldført, netværkskonfigurationen blev ikke ændret.
+setup-server-prompt = Gatewayadresse
+setup-probing = Kontrollerer gatewayen { $server }...
+setup-certificate = Certifikat: { $subject }, udstedt af { $issuer }, gyldigt til { $expires }
+setup-fingerprint = SHA-256-fingeraftryk: { $fingerprint }
+setup-certificate-untrusted = Certifikatet er ikke betroet: { $error }
+setup-ignore-cert-prompt = Accepter alligevel dette certifikat
+setup-login-options = Logintyper tilbudt af gatewayen:
+setup-login-type-prompt = Logintype, nummer eller id [{ $default }]
+setup-user-prompt = Brugernavn
+setup-password-prompt = Adgangskode
+setup-store-password-prompt = Gem adgangskoden i nøgleringen
+setup-default-route-prompt = Send al trafik gennem tunnelen (fuld tunnel)
+setup-dns-mode-prompt = DNS-tilstand, split eller full [split]
+setup-overwrite-prompt = { $path } findes allerede, overskriv den
+setup-cancelled = Opsætningen er annulleret, den eksisterende konfiguration er uændret
+setup-config-written = Konfigurationen er skrevet til { $path }
+setup-test-connect-prompt = Test login nu
+setup-test-connect-ok = Login lykkedes
+import-config-written = Importerede { $count } indstillinger fra { $source } til { $path }
+import-unmapped = Ikke importeret, linje { $line }: { $key }
+import-login-type-missing = Ingen logintype blev importeret, "snx-rs info -s { $server }" viser gatewayens logintyper til indstillingen login-type
+prompt-missing-parameters = Konfigurationen mangler påkrævede parametre: { $options }
+prompt-save-answers = Gem svarene i { $path }
 
 # Connection Messages
 connection-connected-to = Forbundet til {$server}
@@ -202,6 +362,7 @@ connection-status-disconnected = Afbrudt
 connection-status-connecting = Forbinder
 connection-status-connected-since = Forbundet siden: {$since}
 connection-status-mfa-pending = Afventer MFA: {$mfa_type}
+connection-status-trusted-network = Betroet netværk registreret, VPN sat på pause
 
 # Login options
 login-options-server-address = Serveradresse
@@ -212,3 +373,11 @@ login-options-preferred-protocol = Foretrukken protokol
 login-options-tcpt-port = TCPT-port
 login-options-natt-port = NATT-port
 login-options-internal-ca-fingerprint = Internt CA-fingeraftryk
+login-options-protocol-version = Protokolversion
+login-options-ipsec-transport = IPSec-transport
+login-options-saml-enabled = SAML aktiveret
+login-options-certificate-subject = Certifikatets emne
+login-options-certificate-issuer = Certifikatets udsteder
+login-options-certificate-expires = Certifikatet udløber
+login-options-certificate-fingerprint = Certifikatets SHA-256-fingeraftryk
+login-options-certificate-error = Certifikatet er ikke betroet
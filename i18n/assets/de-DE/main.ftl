@@ -53,6 +53,40 @@ expand-ui = Benutzeroberfläche
 error-no-server-name = Keine Serveradresse angegeben
 error-no-auth = Keine Authentifizierungsmethode ausgewählt
 error-file-not-exist = Datei existiert nicht: {$path}
+error-invalid-config-file = Ungültige Konfigurationsdatei {$path}: {$message}
+error-profile-not-found = Profil {$profile} wurde in der Konfiguration nicht gefunden
+error-in-profile = Profil {$profile}: {$message}
+error-invalid-env-var = Ungültiger Wert der Umgebungsvariable {$name}: {$message}
+error-invalid-configuration = Ungültige Konfiguration:
+error-invalid-server-name = Ungültige Serveradresse: {$server}
+error-route-host-bits = {$option}: {$route} hat gesetzte Host-Bits, verwenden Sie {$network}
+error-options-conflict = {$first} kann nicht mit {$second} kombiniert werden
+error-insecure-permissions = {$path} ist für andere Benutzer beschreibbar
+error-secret-file-permissions = {$path} enthält Geheimnisse, ist aber für andere Benutzer zugänglich (Modus {$mode}), schränken Sie sie mit chmod 600 ein oder verwenden Sie --insecure-permissions
+error-secret-file-symlink = {$path} enthält Geheimnisse, verweist aber auf {$target} in einem anderen Verzeichnis, verwenden Sie die Datei selbst oder --insecure-permissions
+error-script-not-executable = Skript ist nicht ausführbar: {$path}
+error-value-out-of-range = {$option} muss zwischen {$min} und {$max} liegen, erhalten: {$value}
+error-timeout-exceeds-total = {$option} mit {$value} Sekunden ist länger als {$total} mit {$limit} Sekunden, kein Versuch könnte rechtzeitig abgeschlossen werden
+error-reserved-routing-table = Routing-Tabelle {$table} ist vom System reserviert
+error-control-socket-in-use = Steuersocket {$path} wird von einem anderen Prozess verwendet
+error-invalid-control-command = Ungültiger Steuerbefehl: {$message}
+error-invalid-log-level = Ungültige Protokollstufe: {$level}
+error-tunnel-not-running = Der Tunnel läuft nicht
+error-invalid-dbus-service = Ungültiger Bus für den D-Bus-Dienst
+error-invalid-notify-event = Ungültiges Benachrichtigungsereignis
+error-invalid-log-format = Ungültiges Protokollformat
+error-invalid-log-target = Ungültiges Protokollziel
+error-invalid-log-filter = Ungültiger Protokollfilter {$filter}: {$error}
+error-profile-not-served = Der Tunnel läuft für das Profil {$current}, das Profil {$profile} benötigt eine andere Instanz
+error-profile-running = Ein Tunnel für das Profil {$profile} läuft bereits als Prozess {$pid}, mit --replace wird er beendet
+error-instance-not-stopped = Der Tunnel des Profils {$profile} im Prozess {$pid} wurde nicht beendet
+error-tunnel-not-stopped = Der Tunnel im Prozess {$pid} wurde nicht innerhalb von {$timeout} Sekunden beendet, --force beendet ihn und stellt die Netzwerkeinstellungen wieder her
+error-dbus-not-authorized = Die Aktion {$action} ist nicht erlaubt
+error-pid-file-locked = Die PID-Datei {$path} ist von einer anderen laufenden Instanz gesperrt
+error-unknown-run-as = Unbekannter Benutzer oder unbekannte Gruppe für den Betrieb ohne Rechte: {$name}
+error-run-as-not-root = Die Option run-as-user erfordert den Start als root
+error-run-as-dns = Die Wiederherstellung von resolv.conf und das resolvconf-Werkzeug erfordern root, verwenden Sie dns-backend=resolved oder no-dns mit run-as-user
+error-privilege-drop = Wechsel zum Benutzer ohne Rechte nicht möglich: {$error}
 error-invalid-cert-id = Zertifikats-ID nicht im Hex-Format: {$id}
 error-ca-root-not-exist = CA-Stammpfad existiert nicht: {$path}
 error-validation = Validierungsfehler
@@ -60,13 +94,15 @@ error-user-input-canceled = Benutzereingabe abgebrochen
 error-connection-cancelled = Verbindung abgebrochen
 error-unknown-event = Unbekanntes Ereignis: {$event}
 error-no-service-connection = Keine Verbindung zum Dienst
+error-session-not-owned = Die Sitzung gehört dem Benutzer mit der ID {$uid}, nur dieser Benutzer oder root darf sie steuern
+error-option-not-permitted = Der Dienst erlaubt diesem Benutzer die Option {$option} nicht
 error-empty-input = Eingabe darf nicht leer sein
 error-invalid-object = Ungültiges Objekt
 error-no-connector = Kein Tunnel-Connector
 error-tunnel-disconnected = Tunnel getrennt, letzte Nachricht: {$message}
 error-unexpected-reply = Unerwartete Antwort
 error-auth-failed = Authentifizierung fehlgeschlagen
-error-no-login-type = Erforderlicher Parameter fehlt: login-type
+error-missing-parameter = Erforderlicher Parameter fehlt: { $option }
 error-connection-timeout = Verbindungszeitüberschreitung
 error-invalid-response = Ungültige Antwort!
 error-cannot-send-request = Anfrage kann nicht an den Dienst gesendet werden
@@ -80,7 +116,13 @@ error-no-pkcs8 = Kein PKCS8 PEM-Pfad angegeben
 error-no-pkcs11 = Kein PKCS11-PIN angegeben
 error-no-ipsec-session = Keine IPSEC-Sitzung
 error-request-failed-error-code = Anfrage fehlgeschlagen, Fehlercode: {$error_code}
+error-gateway-info-refused = Das Gateway {$server} beantwortet keine nicht authentifizierten Anfragen, Fehlercode: {$error_code}
 error-no-root-privileges = Dieses Programm muss als Root-Benutzer ausgeführt werden!
+error-missing-privileges = Die gewählten Optionen erfordern Berechtigungen, die dieser Prozess nicht hat (die Prüfung kann mit --skip-permission-check übersprungen werden):
+privilege-net-admin = CAP_NET_ADMIN wird benötigt, um das tun-Gerät zu erstellen und Adressen, Routen und DNS zu konfigurieren: als root ausführen, die Berechtigung mit sudo setcap cap_net_admin+ep { $exe } erteilen, aus einer systemd-Unit mit AmbientCapabilities=CAP_NET_ADMIN starten oder ein vorab erstelltes Gerät mit tun-fd zusammen mit no-device-config, no-routing und no-dns übergeben
+privilege-resolv-conf = Schreibzugriff auf { $path } wird benötigt, um DNS zu konfigurieren: als root ausführen, ein anderes dns-backend wählen oder no-dns=true setzen
+privilege-resolvconf-tool = Das Werkzeug resolvconf kann nur von root verwendet werden: ein anderes dns-backend wählen oder no-dns=true setzen
+privilege-nft = Der Befehl nft wird für kill-switch und forward-source benötigt: das Paket nftables installieren oder diese Optionen deaktivieren
 error-missing-required-parameters = Erforderliche Parameter fehlen: Servername und/oder Anmeldetyp!
 error-missing-server-name = Erforderlicher Parameter fehlt: Servername!
 error-no-connector-for-challenge-code = Kein Connector zum Senden des Challenge-Codes!
@@ -89,11 +131,13 @@ error-invalid-sexpr = Ungültiger sexpr: {$value}
 error-invalid-value = Ungültiger Wert
 error-udp-request-failed = Fehler beim Senden der UDP-Anfrage
 error-no-tty = Kein angeschlossenes TTY für Benutzereingabe
+error-service-mfa = Der Dienst kann keine MFA-Abfragen beantworten oder den Browser eines Identitätsanbieters öffnen, verwenden Sie ein gespeichertes Passwort mit der Option password oder die Zertifikatsauthentifizierung mit cert-type
 error-invalid-auth-response = Ungültige Authentifizierungsantwort
 error-invalid-client-settings = Ungültige Client-Einstellungsantwort
 error-invalid-otp-reply = Ungültige OTP-Antwort
 error-udp-encap-failed = Socket-Option UDP_ENCAP konnte nicht gesetzt werden, Fehlercode: {$code}
 error-so-no-check-failed = Socket-Option SO_NO_CHECK konnte nicht gesetzt werden, Fehlercode: {$code}
+error-so-mark-failed = Socket-Option SO_MARK konnte nicht gesetzt werden, Fehlercode: {$code}
 error-keepalive-failed = Keepalive fehlgeschlagen
 error-receive-failed = Empfang fehlgeschlagen
 error-unknown-color-scheme = Unbekannter Farb-Schema-Wert
@@ -104,12 +148,55 @@ error-invalid-operation-mode = Ungültiger Betriebsmodus
 error-invalid-tunnel-type = Ungültiger Tunneltyp
 error-invalid-cert-type = Ungültiger Zertifikatstyp
 error-invalid-icon-theme = Ungültiges Symbolthema
+error-invalid-dns-backend = Ungültiges DNS-Backend
+error-invalid-dns-mode = Ungültiger DNS-Modus
+error-invalid-dns-override = Ungültige DNS-Priorität
+error-invalid-address-conflict-action = Ungültige Aktion bei Adresskonflikten
+error-resolv-conf-symlink = {$path} ist ein symbolischer Link, der direkte DNS-Modus ändert ihn nicht; wählen Sie ein anderes dns-backend
+error-resolv-conf-read-only = {$path} ist schreibgeschützt oder von der Container-Laufzeit eingehängt: dns-resolv-path setzen, um an einen anderen Ort zu schreiben, oder dns-backend=print, um es per Skript anzuwenden
+error-invalid-device-name = Ungültiger Schnittstellenname: {$name}
+error-invalid-netns-name = Ungültiger Name des Netzwerk-Namespace: {$name}
+error-device-name-in-use = Die Schnittstelle {$name} existiert bereits
+error-invalid-tun-fd = Der Dateideskriptor muss zu einem mit IFF_TUN und IFF_NO_PI geöffneten tun-Gerät gehören: {$message}
+error-unknown-tun-owner = Unbekannter Benutzer oder unbekannte Gruppe {$name}
+error-tun-ioctl = {$request} kann nicht auf {$name} angewendet werden: {$message}
+error-tun-ioctl-permission = {$request} auf {$name} ist nicht erlaubt, das Gerät muss von root oder einem Prozess mit CAP_NET_ADMIN eingerichtet werden
+error-invalid-network-manager-mode = Ungültiger NetworkManager-Modus
+error-invalid-ipv6-block-mode = Ungültiger IPv6-Sperrmodus
+error-invalid-mss-clamp-mode = Ungültiger MSS-Begrenzungsmodus
+error-no-free-device-name = Kein freier Schnittstellenname für die Vorlage {$name}
+error-invalid-trusted-network = Ungültiges Kriterium für vertrauenswürdiges Netzwerk: {$value}
+error-invalid-network-match = Ungültiges Netzwerk-Übereinstimmungskriterium: {$value}
+error-invalid-health-listen = Ungültige Adresse der Health-Endpunkte: {$value}
+error-invalid-proxy = Ungültiger Proxy, erwartet none, auto oder http://[user:password@]host[:port]: {$value}
+error-proxy-connect-failed = Der Proxy {$proxy} hat die Verbindung zu {$destination} abgelehnt: {$status}
+error-invalid-route = Ungültige Route: {$value}
 error-no-natt-reply = Keine NATT-Antwort
 error-not-implemented = Nicht implementiert
 error-unknown-packet-type = Unbekannter Pakettyp
 error-no-sender = Kein Absender
 error-empty-ccc-session = Leere CCC-Sitzung
 error-identity-timeout = Timeout beim Warten auf Identitätsantwort, ist der Anmeldetyp korrekt?
+error-no-office-mode-address = Das Gateway hat keine verwendbare Adresse zugewiesen: {$address}
+error-gateway-error = Gateway-Fehler, Code: {$code}
+error-gateway-reconnect = Das Gateway hat wiederholt eine erneute Verbindung angefordert: {$message}
+error-hello-rejected = Das Gateway hat die Client-Hello-Nachricht abgelehnt: {$message}
+error-roaming-timeout = Tunnel konnte nicht innerhalb von {$seconds} Sekunden nach dem Netzwerkwechsel wiederhergestellt werden
+error-kill-switch-unavailable = Der Kill-Switch benötigt das Werkzeug nft: {$message}
+error-forwarding-unavailable = LAN-Weiterleitung erfordert das Programm nft: {$message}
+error-mss-clamp-unavailable = Die MSS-Begrenzung erfordert das Programm nft: {$message}
+error-app-routing-unavailable = Das Anwendungsrouting erfordert das Programm nft: {$message}
+error-dns-redirect-unavailable = Die DNS-Umleitung erfordert das Programm nft: {$message}
+error-forward-source-conflict = Das Quellnetz {$source} der Weiterleitung überschneidet sich mit der Adresse oder den Routen des Tunnels
+error-setup-missing-option = Die Option { $option } ist im nicht-interaktiven Modus erforderlich
+error-setup-config-exists = { $path } existiert bereits, mit --force wird sie überschrieben
+error-setup-invalid-choice = Ungültige Auswahl: { $value }
+error-setup-unreachable = { $server } ist nicht erreichbar: { $error }
+error-setup-certificate-rejected = Das Zertifikat des Gateways ist nicht vertrauenswürdig, die CA mit der Option ca-cert hinzufügen oder es mit --ignore-server-cert true akzeptieren
+error-import-read = { $path } kann nicht gelesen werden: { $error }
+error-import-empty = { $path } enthält keine Einstellungen, die importiert werden können
+error-import-not-toml = Die Einstellungen werden in eine TOML-Konfiguration importiert, { $path } hat nicht die Endung .toml
+error-import-profile-exists = Das Profil { $profile } existiert bereits in { $path }
 
 # Placeholder texts
 placeholder-domains = Durch Komma getrennte Domänen
@@ -158,25 +245,98 @@ tray-menu-exit = Beenden
 
 # Connection info
 info-connected-since = Verbunden seit
+info-profile = Profil
 info-server-name = Servername
 info-user-name = Benutzername
 info-login-type = Anmeldetyp
 info-tunnel-type = Tunneltyp
 info-transport-type = Transporttyp
+info-protocol-version = Protokollversion
 info-ip-address = IP-Adresse
+info-ipv6-address = IPv6-Adresse
 info-dns-servers = DNS-Server
 info-search-domains = Suchdomänen
+info-wins-servers = WINS-Server
 info-interface = Schnittstelle
+info-interface-mode = Schnittstellenmodus
+info-interface-mode-p2p = Punkt-zu-Punkt, Gegenstelle { $peer }
+info-interface-mode-local = nur lokale Adresse
 info-dns-configured = DNS konfiguriert
+info-dns-mode = DNS-Modus
+info-dns-override = DNS-Priorität
 info-routing-configured = Routing konfiguriert
 info-default-route = Standardroute
+info-reauth-interval = Intervall der erneuten Authentifizierung
+info-forwarding = LAN-Weiterleitung
+info-dns-check = DNS-Lecktest
+info-dns-check-passed = bestanden
+info-dns-check-failed = fehlgeschlagen
+info-routes = Routen
+info-received = Empfangen
+info-sent = Gesendet
+info-traffic-amount = {$bytes}, {$packets} Pakete
+info-stats-since = Statistik seit
+info-connected-time = Verbindungsdauer
+info-reconnects = Wiederverbindungen
+info-reauths = Erneute Authentifizierungen
+info-rekeys = Schlüsselerneuerungen
+info-last-error = Letzter Fehler
 
 # CLI Messages
 cli-identity-provider-auth = Für die Authentifizierung über den Identitätsanbieter öffnen Sie die folgende URL in Ihrem Browser:
 cli-tunnel-connected = Tunnel verbunden, drücken Sie Strg+C zum Beenden.
 cli-tunnel-disconnected = Tunnel getrennt
+notify-connected = VPN verbunden ({$address})
+notify-reconnecting = VPN verbindet neu (Versuch {$attempt})
+notify-disconnected = VPN getrennt
+notify-disconnected-reason = VPN getrennt: {$reason}
+notify-reauth-failed = Erneute VPN-Authentifizierung fehlgeschlagen: {$error}
+cli-waiting-for-connect = Warte auf den Verbindungsbefehl
+cli-config-reloaded = Konfiguration neu geladen
+cli-reload-on-reconnect = Diese Optionen werden bei der nächsten erneuten Verbindung übernommen: { $options }
+cli-reload-on-restart = Diese Optionen werden nach einem Neustart von snx-rs übernommen: { $options }
+cli-reload-failed = Die Konfiguration kann nicht neu geladen werden, die aktuelle bleibt erhalten: { $error }
+cli-daemon-terminated = Das Beendigungssignal wurde an den Prozess {$pid} gesendet
+cli-tunnel-stopping = Warten auf das Beenden des Tunnels im Prozess {$pid}
+cli-tunnel-killed = Der Prozess {$pid} wird beendet und die hinterlassenen Netzwerkeinstellungen werden wiederhergestellt
+cli-network-restored = Netzwerkeinstellungen wiederhergestellt
 cli-another-instance-running = Eine andere Instanz von snx-rs läuft bereits
+cli-replacing-instance = Der laufende Tunnel des Prozesses {$pid} wird beendet
 cli-app-terminated = Anwendung durch Signal beendet
+cli-session-expiring = Die VPN-Sitzung läuft in {$minutes} Min. ab, der Tunnel wird getrennt
+cli-dns-check-failed = Warnung: DNS-Lecktest fehlgeschlagen, DNS-Anfragen gehen möglicherweise nicht durch den Tunnel
+cli-trusted-network-lost = Vertrauenswürdiges Netzwerk nicht mehr erkannt, VPN-Verbindung wird fortgesetzt
+cli-gateway-failover = Gateway {$failed} ist nicht erreichbar, versuche {$server}
+cli-gateway-failback = Gateway {$server} ist wieder erreichbar, es wird zurückgewechselt
+cli-service-installed = Dienst {$name} installiert, starten Sie ihn mit: sc.exe start {$name}
+cli-service-uninstalled = Dienst {$name} deinstalliert
+cli-profile-switch = Das Netzwerk hat sich geändert, wechsle zum Profil {$profile}
+cli-dry-run-auth-failed = Probelauf: Authentifizierung fehlgeschlagen: { $error }
+cli-dry-run-negotiation-failed = Probelauf: Aushandlung des Tunnels fehlgeschlagen: { $error }
+cli-dry-run-complete = Probelauf abgeschlossen, die Netzwerkkonfiguration wurde nicht geändert.
+setup-server-prompt = Gateway-Adresse
+setup-probing = Gateway { $server } wird geprüft...
+setup-certificate = Zertifikat: { $subject }, ausgestellt von { $issuer }, gültig bis { $expires }
+setup-fingerprint = SHA-256-Fingerabdruck: { $fingerprint }
+setup-certificate-untrusted = Das Zertifikat ist nicht vertrauenswürdig: { $error }
+setup-ignore-cert-prompt = Dieses Zertifikat trotzdem akzeptieren
+setup-login-options = Vom Gateway angebotene Anmeldetypen:
+setup-login-type-prompt = Anmeldetyp, Nummer oder ID [{ $default }]
+setup-user-prompt = Benutzername
+setup-password-prompt = Passwort
+setup-store-password-prompt = Passwort im Schlüsselbund speichern
+setup-default-route-prompt = Gesamten Datenverkehr durch den Tunnel leiten (Full Tunnel)
+setup-dns-mode-prompt = DNS-Modus, split oder full [split]
+setup-overwrite-prompt = { $path } existiert bereits, überschreiben
+setup-cancelled = Einrichtung abgebrochen, die vorhandene Konfiguration bleibt unverändert
+setup-config-written = Konfiguration in { $path } geschrieben
+setup-test-connect-prompt = Anmeldung jetzt testen
+setup-test-connect-ok = Anmeldung erfolgreich
+import-config-written = { $count } Einstellungen aus { $source } nach { $path } importiert
+import-unmapped = Nicht importiert, Zeile { $line }: { $key }
+import-login-type-missing = Es wurde kein Anmeldetyp importiert, "snx-rs info -s { $server }" listet die Anmeldetypen des Gateways für die Option login-type auf
+prompt-missing-parameters = In der Konfiguration fehlen erforderliche Parameter: { $options }
+prompt-save-answers = Antworten in { $path } speichern
 
 # Connection Messages
 connection-connected-to = Verbunden mit {$server}
@@ -204,6 +364,7 @@ connection-status-disconnected = Getrennt
 connection-status-connecting = Verbindung wird hergestellt
 connection-status-connected-since = Verbunden seit: {$since}
 connection-status-mfa-pending = Warte auf MFA: {$mfa_type}
+connection-status-trusted-network = Vertrauenswürdiges Netzwerk erkannt, VPN pausiert
 
 # Login options
 login-options-server-address = Serveradresse
@@ -214,3 +375,11 @@ login-options-preferred-protocol = Bevorzugtes Protokoll
 login-options-tcpt-port = TCPT-Port
 login-options-natt-port = NATT-Port
 login-options-internal-ca-fingerprint = Interner CA-Fingerabdruck
+login-options-protocol-version = Protokollversion
+login-options-ipsec-transport = IPSec-Transport
+login-options-saml-enabled = SAML aktiviert
+login-options-certificate-subject = Zertifikatsinhaber
+login-options-certificate-issuer = Zertifikatsaussteller
+login-options-certificate-expires = Zertifikat gültig bis
+login-options-certificate-fingerprint = SHA-256-Fingerabdruck des Zertifikats
+login-options-certificate-error = Zertifikat nicht vertrauenswürdig
@@ -53,6 +53,40 @@ expand-ui = UI Settings
 error-no-server-name = No server address specified
 error-no-auth = No authentication method selected
 error-file-not-exist = File does not exist: {$path}
+error-invalid-config-file = Invalid configuration file {$path}: {$message}
+error-profile-not-found = Profile {$profile} not found in the configuration
+error-in-profile = Profile {$profile}: {$message}
+error-invalid-env-var = Invalid value of environment variable {$name}: {$message}
+error-invalid-configuration = Invalid configuration:
+error-invalid-server-name = Invalid server address: {$server}
+error-route-host-bits = {$option}: {$route} has host bits set, use {$network}
+error-options-conflict = {$first} cannot be combined with {$second}
+error-insecure-permissions = {$path} is writable by other users
+error-secret-file-permissions = {$path} holds secrets but other users can access it (mode {$mode}), restrict it with chmod 600 or use --insecure-permissions
+error-secret-file-symlink = {$path} holds secrets but links to {$target} in another directory, use the file itself or --insecure-permissions
+error-script-not-executable = Script is not executable: {$path}
+error-value-out-of-range = {$option} must be between {$min} and {$max}, got {$value}
+error-timeout-exceeds-total = {$option} of {$value} seconds is longer than {$total} of {$limit} seconds, no attempt could complete in time
+error-reserved-routing-table = Routing table {$table} is reserved by the system
+error-control-socket-in-use = Control socket {$path} is used by another process
+error-invalid-control-command = Invalid control command: {$message}
+error-invalid-log-level = Invalid log level: {$level}
+error-tunnel-not-running = The tunnel is not running
+error-invalid-dbus-service = Invalid D-Bus service bus
+error-invalid-notify-event = Invalid notification event
+error-invalid-log-format = Invalid log format
+error-invalid-log-target = Invalid log target
+error-invalid-log-filter = Invalid log filter {$filter}: {$error}
+error-profile-not-served = The tunnel runs for the profile {$current}, the profile {$profile} needs another instance
+error-profile-running = A tunnel for the profile {$profile} is already running as the process {$pid}, use --replace to stop it
+error-instance-not-stopped = The tunnel of the profile {$profile} in the process {$pid} did not stop
+error-tunnel-not-stopped = The tunnel of the process {$pid} did not exit in {$timeout} seconds, use --force to kill it and restore the network settings
+error-dbus-not-authorized = The action {$action} is not authorized
+error-pid-file-locked = The pidfile {$path} is locked by another running instance
+error-unknown-run-as = Unknown user or group to run as: {$name}
+error-run-as-not-root = The run-as-user option needs the tunnel to be started as root
+error-run-as-dns = Restoring resolv.conf and the resolvconf tool need root, use dns-backend=resolved or no-dns with run-as-user
+error-privilege-drop = Unable to switch to the unprivileged user: {$error}
 error-invalid-cert-id = Certificate ID not in hex format: {$id}
 error-ca-root-not-exist = CA root path does not exist: {$path}
 error-validation = Validation error
@@ -60,6 +94,8 @@ error-user-input-canceled = User input canceled
 error-connection-cancelled = Connection cancelled
 error-unknown-event = Unknown event: {$event}
 error-no-service-connection = No connection to service
+error-session-not-owned = The session belongs to the user with ID {$uid}, only that user or root may control it
+error-option-not-permitted = The option {$option} is not permitted for this user by the service
 error-empty-input = Input cannot be empty
 error-invalid-response = Invalid response!
 
@@ -69,7 +105,7 @@ error-no-connector = No tunnel connector
 error-tunnel-disconnected = Tunnel disconnected, last message: {$message}
 error-unexpected-reply = Unexpected reply
 error-auth-failed = Authentication failed
-error-no-login-type = Missing required parameter: login-type
+error-missing-parameter = Missing required parameter: { $option }
 error-connection-timeout = Connection timeout
 error-cannot-send-request = Cannot send request to the service
 error-cannot-read-reply = Cannot read reply from the service
@@ -82,7 +118,13 @@ error-no-pkcs8 = No PKCS8 PEM path provided
 error-no-pkcs11 = No PKCS11 pin provided
 error-no-ipsec-session = No IPSEC session
 error-request-failed-error-code = Request failed, error code: {$error_code}
+error-gateway-info-refused = The gateway {$server} does not answer unauthenticated queries, error code: {$error_code}
 error-no-root-privileges = This program should be run as a root user!
+error-missing-privileges = The selected options need privileges which this process does not have (the check can be skipped with --skip-permission-check):
+privilege-net-admin = CAP_NET_ADMIN is needed to create the tun device and configure addresses, routes and DNS: run as root, grant it with sudo setcap cap_net_admin+ep { $exe }, start from a systemd unit with AmbientCapabilities=CAP_NET_ADMIN, or pass a pre-created device with tun-fd together with no-device-config, no-routing and no-dns
+privilege-resolv-conf = Write access to { $path } is needed to configure DNS: run as root, select another dns-backend or set no-dns=true
+privilege-resolvconf-tool = The resolvconf tool can only be used by root: select another dns-backend or set no-dns=true
+privilege-nft = The nft command is needed by kill-switch and forward-source: install the nftables package or disable these options
 error-missing-required-parameters = Missing required parameters: server name and/or login type!
 error-missing-server-name = Missing required parameter: server name!
 error-no-connector-for-challenge-code = No connector to send the challenge code to!
@@ -91,11 +133,13 @@ error-invalid-sexpr = Invalid sexpr: {$value}
 error-invalid-value = Invalid value
 error-udp-request-failed = Error sending UDP request
 error-no-tty = No attached TTY to get user input
+error-service-mfa = The service cannot answer MFA challenges or open the browser of an identity provider, use a stored password with the password option or certificate authentication with cert-type
 error-invalid-auth-response = Invalid authentication response
 error-invalid-client-settings = Invalid client settings response
 error-invalid-otp-reply = Invalid OTP reply
 error-udp-encap-failed = Cannot set UDP_ENCAP socket option, error code: {$code}
 error-so-no-check-failed = Cannot set SO_NO_CHECK socket option, error code: {$code}
+error-so-mark-failed = Cannot set SO_MARK socket option, error code: {$code}
 error-keepalive-failed = Keepalive failed
 error-receive-failed = Receive failed
 error-unknown-color-scheme = Unknown color-scheme value
@@ -106,12 +150,55 @@ error-invalid-operation-mode = Invalid operation mode
 error-invalid-tunnel-type = Invalid tunnel type
 error-invalid-cert-type = Invalid cert type
 error-invalid-icon-theme = Invalid icon theme
+error-invalid-dns-backend = Invalid DNS backend
+error-invalid-dns-mode = Invalid DNS mode
+error-invalid-dns-override = Invalid DNS override
+error-invalid-address-conflict-action = Invalid address conflict action
+error-resolv-conf-symlink = {$path} is a symbolic link, refusing to modify it in direct DNS mode; select another dns-backend
+error-resolv-conf-read-only = {$path} is read-only or mounted by the container runtime: set dns-resolv-path to write it elsewhere, or dns-backend=print to apply it from a script
+error-invalid-device-name = Invalid interface name: {$name}
+error-invalid-netns-name = Invalid network namespace name: {$name}
+error-device-name-in-use = Interface {$name} already exists
+error-invalid-tun-fd = The file descriptor must belong to a tun device opened with IFF_TUN and IFF_NO_PI: {$message}
+error-unknown-tun-owner = Unknown user or group {$name}
+error-tun-ioctl = Unable to apply {$request} to {$name}: {$message}
+error-tun-ioctl-permission = Not permitted to apply {$request} to {$name}, the device must be set up by root or by a process with CAP_NET_ADMIN
+error-invalid-network-manager-mode = Invalid NetworkManager mode
+error-invalid-ipv6-block-mode = Invalid IPv6 block mode
+error-invalid-mss-clamp-mode = Invalid MSS clamp mode
+error-no-free-device-name = No free interface name for template {$name}
+error-invalid-trusted-network = Invalid trusted network criterion: {$value}
+error-invalid-network-match = Invalid network match criterion: {$value}
+error-invalid-health-listen = Invalid address of the health endpoints: {$value}
+error-invalid-proxy = Invalid proxy, expected none, auto or http://[user:password@]host[:port]: {$value}
+error-proxy-connect-failed = The proxy {$proxy} refused the connection to {$destination}: {$status}
+error-invalid-route = Invalid route: {$value}
 error-no-natt-reply = No NAT-T reply
 error-not-implemented = Not implemented
 error-unknown-packet-type = Unknown packet type
 error-no-sender = No sender
 error-empty-ccc-session = Empty CCC session
 error-identity-timeout = Timeout while waiting for identity response, is the login type correct?
+error-no-office-mode-address = Gateway did not assign a usable address: {$address}
+error-gateway-error = Gateway error, code: {$code}
+error-gateway-reconnect = Gateway repeatedly requested a reconnect: {$message}
+error-hello-rejected = Gateway rejected the client hello: {$message}
+error-roaming-timeout = Unable to re-establish the tunnel within {$seconds} seconds after the network change
+error-kill-switch-unavailable = Kill switch requires the nft utility: {$message}
+error-forwarding-unavailable = LAN forwarding requires the nft utility: {$message}
+error-mss-clamp-unavailable = MSS clamping requires the nft utility: {$message}
+error-app-routing-unavailable = Application routing requires the nft utility: {$message}
+error-dns-redirect-unavailable = DNS redirection requires the nft utility: {$message}
+error-forward-source-conflict = Forwarding source {$source} overlaps the tunnel address or routes
+error-setup-missing-option = The { $option } option is required in the non-interactive mode
+error-setup-config-exists = { $path } already exists, use --force to overwrite it
+error-setup-invalid-choice = Invalid choice: { $value }
+error-setup-unreachable = Unable to reach { $server }: { $error }
+error-setup-certificate-rejected = The gateway certificate is not trusted, add its CA with the ca-cert option or accept it with --ignore-server-cert true
+error-import-read = Unable to read { $path }: { $error }
+error-import-empty = { $path } holds no settings which can be imported
+error-import-not-toml = The settings are imported into a TOML configuration, { $path } does not have the .toml extension
+error-import-profile-exists = The profile { $profile } already exists in { $path }
 
 # Placeholder texts
 placeholder-domains = Comma-separated domains
@@ -160,25 +247,98 @@ tray-menu-exit = Exit
 
 # Connection info
 info-connected-since = Connected since
+info-profile = Profile
 info-server-name = Server name
 info-user-name = User name
 info-login-type = Login type
 info-tunnel-type = Tunnel type
 info-transport-type = Transport type
+info-protocol-version = Protocol version
 info-ip-address = IP address
+info-ipv6-address = IPv6 address
 info-dns-servers = DNS servers
 info-search-domains = Search domains
+info-wins-servers = WINS servers
 info-interface = Interface
+info-interface-mode = Interface mode
+info-interface-mode-p2p = point-to-point, peer { $peer }
+info-interface-mode-local = local address only
 info-dns-configured = DNS configured
+info-dns-mode = DNS mode
+info-dns-override = DNS override
 info-routing-configured = Routing configured
 info-default-route = Default route
+info-reauth-interval = Re-authentication interval
+info-forwarding = LAN forwarding
+info-dns-check = DNS leak test
+info-dns-check-passed = passed
+info-dns-check-failed = failed
+info-routes = Routes
+info-received = Received
+info-sent = Sent
+info-traffic-amount = {$bytes}, {$packets} packets
+info-stats-since = Statistics since
+info-connected-time = Connected time
+info-reconnects = Reconnects
+info-reauths = Re-authentications
+info-rekeys = Rekeys
+info-last-error = Last error
 
 # CLI Messages
 cli-identity-provider-auth = For identity provider authentication, open the following URL in your browser:
 cli-tunnel-connected = Tunnel connected, press Ctrl-C to exit.
 cli-tunnel-disconnected = Tunnel disconnected
+notify-connected = VPN connected ({$address})
+notify-reconnecting = VPN reconnecting (attempt {$attempt})
+notify-disconnected = VPN disconnected
+notify-disconnected-reason = VPN disconnected: {$reason}
+notify-reauth-failed = VPN reauthentication failed: {$error}
+cli-waiting-for-connect = Waiting for the connect command
+cli-config-reloaded = Configuration reloaded
+cli-reload-on-reconnect = These options will apply on the next reconnect: { $options }
+cli-reload-on-restart = These options will apply after a restart of snx-rs: { $options }
+cli-reload-failed = Unable to reload the configuration, keeping the current one: { $error }
+cli-daemon-terminated = Sent the termination signal to the process {$pid}
+cli-tunnel-stopping = Waiting for the tunnel of the process {$pid} to exit
+cli-tunnel-killed = Killing the process {$pid} and restoring the network settings it left behind
+cli-network-restored = Network settings restored
 cli-another-instance-running = Another instance of snx-rs is already running
+cli-replacing-instance = Stopping the running tunnel of the process {$pid}
 cli-app-terminated = Application terminated due to a signal
+cli-session-expiring = VPN session expires in {$minutes} min, the tunnel will be disconnected
+cli-dns-check-failed = Warning: DNS leak test failed, DNS queries may not go through the tunnel
+cli-trusted-network-lost = Trusted network no longer detected, resuming VPN connection
+cli-gateway-failover = Gateway {$failed} is unreachable, trying {$server}
+cli-gateway-failback = Gateway {$server} is reachable again, switching back to it
+cli-service-installed = Service {$name} installed, start it with: sc.exe start {$name}
+cli-service-uninstalled = Service {$name} uninstalled
+cli-profile-switch = The network has changed, switching to profile {$profile}
+cli-dry-run-auth-failed = Dry run: authentication failed: { $error }
+cli-dry-run-negotiation-failed = Dry run: tunnel negotiation failed: { $error }
+cli-dry-run-complete = Dry run complete, the network configuration was not changed.
+setup-server-prompt = Gateway address
+setup-probing = Checking the gateway { $server }...
+setup-certificate = Certificate: { $subject }, issued by { $issuer }, valid until { $expires }
+setup-fingerprint = SHA-256 fingerprint: { $fingerprint }
+setup-certificate-untrusted = The certificate is not trusted: { $error }
+setup-ignore-cert-prompt = Accept this certificate anyway
+setup-login-options = Login types offered by the gateway:
+setup-login-type-prompt = Login type, number or id [{ $default }]
+setup-user-prompt = User name
+setup-password-prompt = Password
+setup-store-password-prompt = Store the password in the keychain
+setup-default-route-prompt = Route all traffic through the tunnel (full tunnel)
+setup-dns-mode-prompt = DNS mode, split or full [split]
+setup-overwrite-prompt = { $path } already exists, overwrite it
+setup-cancelled = Setup cancelled, the existing configuration is unchanged
+setup-config-written = Configuration written to { $path }
+setup-test-connect-prompt = Test the login now
+setup-test-connect-ok = Login succeeded
+import-config-written = Imported { $count } settings from { $source } to { $path }
+import-unmapped = Not imported, line { $line }: { $key }
+import-login-type-missing = No login type was imported, "snx-rs info -s { $server }" lists the login types of the gateway for the login-type option
+prompt-missing-parameters = The configuration is missing required parameters: { $options }
+prompt-save-answers = Save the answers to { $path }
 
 # Connection Messages
 connection-connected-to = Connected to {$server}
@@ -206,6 +366,7 @@ connection-status-disconnected = Disconnected
 connection-status-connecting = Connecting in progress
 connection-status-connected-since = Connected since: {$since}
 connection-status-mfa-pending = MFA pending: {$mfa_type}
+connection-status-trusted-network = Trusted network detected, VPN suspended
 
 # Login options
 login-options-server-address = Server address
@@ -216,3 +377,11 @@ login-options-preferred-protocol = Preferred protocol
 login-options-tcpt-port = TCPT port
 login-options-natt-port = NATT port
 login-options-internal-ca-fingerprint = Internal CA fingerprint
+login-options-protocol-version = Protocol version
+login-options-ipsec-transport = IPSec transport
+login-options-saml-enabled = SAML enabled
+login-options-certificate-subject = Certificate subject
+login-options-certificate-issuer = Certificate issuer
+login-options-certificate-expires = Certificate expires
+login-options-certificate-fingerprint = Certificate SHA-256 fingerprint
+login-options-certificate-error = Certificate not trusted
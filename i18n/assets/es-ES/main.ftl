@@ -53,6 +53,40 @@ expand-ui = Interfaz de usuario
 error-no-server-name = No se ha especificado dirección de servidor
 error-no-auth = No se ha seleccionado método de autenticación
 error-file-not-exist = El archivo no existe: {$path}
+error-invalid-config-file = Archivo de configuración no válido {$path}: {$message}
+error-profile-not-found = No se encontró el perfil {$profile} en la configuración
+error-in-profile = Perfil {$profile}: {$message}
+error-invalid-env-var = Valor no válido de la variable de entorno {$name}: {$message}
+error-invalid-configuration = Configuración no válida:
+error-invalid-server-name = Dirección del servidor no válida: {$server}
+error-route-host-bits = {$option}: {$route} tiene bits de host activados, use {$network}
+error-options-conflict = {$first} no se puede combinar con {$second}
+error-insecure-permissions = {$path} puede ser modificado por otros usuarios
+error-secret-file-permissions = {$path} contiene secretos pero otros usuarios pueden acceder a él (modo {$mode}), restrínjalo con chmod 600 o use --insecure-permissions
+error-secret-file-symlink = {$path} contiene secretos pero enlaza a {$target} en otro directorio, use el propio archivo o --insecure-permissions
+error-script-not-executable = El script no es ejecutable: {$path}
+error-value-out-of-range = {$option} debe estar entre {$min} y {$max}, se obtuvo {$value}
+error-timeout-exceeds-total = {$option} de {$value} segundos es mayor que {$total} de {$limit} segundos, ningún intento podría completarse a tiempo
+error-reserved-routing-table = La tabla de enrutamiento {$table} está reservada por el sistema
+error-control-socket-in-use = El socket de control {$path} está en uso por otro proceso
+error-invalid-control-command = Comando de control no válido: {$message}
+error-invalid-log-level = Nivel de registro no válido: {$level}
+error-tunnel-not-running = El túnel no está en ejecución
+error-invalid-dbus-service = Bus del servicio D-Bus no válido
+error-invalid-notify-event = Evento de notificación no válido
+error-invalid-log-format = Formato de registro no válido
+error-invalid-log-target = Destino de registro no válido
+error-invalid-log-filter = Filtro de registro no válido {$filter}: {$error}
+error-profile-not-served = El túnel se ejecuta para el perfil {$current}, el perfil {$profile} necesita otra instancia
+error-profile-running = Ya hay un túnel del perfil {$profile} en ejecución como el proceso {$pid}, use --replace para detenerlo
+error-instance-not-stopped = El túnel del perfil {$profile} en el proceso {$pid} no se detuvo
+error-tunnel-not-stopped = El túnel del proceso {$pid} no terminó en {$timeout} segundos, use --force para matarlo y restaurar la configuración de red
+error-dbus-not-authorized = La acción {$action} no está autorizada
+error-pid-file-locked = El archivo PID {$path} está bloqueado por otra instancia en ejecución
+error-unknown-run-as = Usuario o grupo desconocido para la ejecución sin privilegios: {$name}
+error-run-as-not-root = La opción run-as-user requiere iniciar el túnel como root
+error-run-as-dns = Restaurar resolv.conf y la herramienta resolvconf requieren root, use dns-backend=resolved o no-dns con run-as-user
+error-privilege-drop = No se puede cambiar al usuario sin privilegios: {$error}
 error-invalid-cert-id = ID de certificado no en formato hexadecimal: {$id}
 error-ca-root-not-exist = La ruta raíz CA no existe: {$path}
 error-validation = Error de validación
@@ -60,6 +94,8 @@ error-user-input-canceled = Entrada de usuario cancelada
 error-connection-cancelled = Conexión cancelada
 error-unknown-event = Evento desconocido: {$event}
 error-no-service-connection = No hay conexión al servicio
+error-session-not-owned = La sesión pertenece al usuario con ID {$uid}, solo ese usuario o root pueden controlarla
+error-option-not-permitted = El servicio no permite la opción {$option} a este usuario
 error-empty-input = La entrada no puede estar vacía
 error-invalid-response = ¡Respuesta inválida!
 error-invalid-object = Objeto inválido
@@ -67,10 +103,16 @@ error-no-connector = No hay conector de túnel
 error-tunnel-disconnected = Túnel desconectado, último mensaje: {$message}
 error-unexpected-reply = Respuesta inesperada
 error-auth-failed = Error de autenticación
-error-no-login-type = Falta el parámetro obligatorio: login-type
+error-missing-parameter = Falta el parámetro obligatorio: { $option }
 error-connection-timeout = Tiempo de conexión agotado
 error-request-failed-error-code = Error en la solicitud, código de error: {$error_code}
+error-gateway-info-refused = La pasarela {$server} no responde a consultas sin autenticar, código de error: {$error_code}
 error-no-root-privileges = ¡Este programa debe ejecutarse como usuario root!
+error-missing-privileges = Las opciones seleccionadas requieren privilegios que este proceso no tiene (la comprobación se puede omitir con --skip-permission-check):
+privilege-net-admin = Se necesita CAP_NET_ADMIN para crear el dispositivo tun y configurar direcciones, rutas y DNS: ejecute como root, conceda la capacidad con sudo setcap cap_net_admin+ep { $exe }, inicie desde una unidad systemd con AmbientCapabilities=CAP_NET_ADMIN, o pase un dispositivo creado previamente con tun-fd junto con no-device-config, no-routing y no-dns
+privilege-resolv-conf = Se necesita acceso de escritura a { $path } para configurar el DNS: ejecute como root, seleccione otro dns-backend o establezca no-dns=true
+privilege-resolvconf-tool = La herramienta resolvconf solo puede ser usada por root: seleccione otro dns-backend o establezca no-dns=true
+privilege-nft = El comando nft es necesario para kill-switch y forward-source: instale el paquete nftables o desactive estas opciones
 error-missing-required-parameters = ¡Faltan parámetros obligatorios: nombre del servidor y/o tipo de inicio de sesión!
 error-missing-server-name = ¡Falta el parámetro obligatorio: nombre del servidor!
 error-no-connector-for-challenge-code = ¡No hay conector para enviar el código de desafío!
@@ -79,11 +121,13 @@ error-invalid-sexpr = sexpr inválido: {$value}
 error-invalid-value = Valor inválido
 error-udp-request-failed = Error al enviar la solicitud UDP
 error-no-tty = No hay TTY conectado para obtener la entrada del usuario
+error-service-mfa = El servicio no puede responder a los desafíos MFA ni abrir el navegador de un proveedor de identidad, use una contraseña guardada con la opción password o la autenticación por certificado con cert-type
 error-invalid-auth-response = Respuesta de autenticación inválida
 error-invalid-client-settings = Respuesta de configuración del cliente inválida
 error-invalid-otp-reply = Respuesta OTP inválida
 error-udp-encap-failed = No se pudo establecer la opción de socket UDP_ENCAP, código de error: {$code}
 error-so-no-check-failed = No se pudo establecer la opción de socket SO_NO_CHECK, código de error: {$code}
+error-so-mark-failed = No se pudo establecer la opción de socket SO_MARK, código de error: {$code}
 error-keepalive-failed = Error en keepalive
 error-receive-failed = Error en la recepción
 error-unknown-color-scheme = Valor de esquema de color desconocido
@@ -94,12 +138,55 @@ error-invalid-operation-mode = Modo de operación inválido
 error-invalid-tunnel-type = Tipo de túnel inválido
 error-invalid-cert-type = Tipo de certificado inválido
 error-invalid-icon-theme = Tema de iconos inválido
+error-invalid-dns-backend = Backend de DNS no válido
+error-invalid-dns-mode = Modo DNS no válido
+error-invalid-dns-override = Prioridad de DNS no válida
+error-invalid-address-conflict-action = Acción de conflicto de direcciones no válida
+error-resolv-conf-symlink = {$path} es un enlace simbólico, el modo DNS directo no lo modificará; seleccione otro dns-backend
+error-resolv-conf-read-only = {$path} es de solo lectura o está montado por el entorno de contenedores: configure dns-resolv-path para escribir en otro lugar, o dns-backend=print para aplicarlo desde un script
+error-invalid-device-name = Nombre de interfaz no válido: {$name}
+error-invalid-netns-name = Nombre de espacio de nombres de red no válido: {$name}
+error-device-name-in-use = La interfaz {$name} ya existe
+error-invalid-tun-fd = El descriptor de archivo debe pertenecer a un dispositivo tun abierto con IFF_TUN e IFF_NO_PI: {$message}
+error-unknown-tun-owner = Usuario o grupo desconocido {$name}
+error-tun-ioctl = No se puede aplicar {$request} a {$name}: {$message}
+error-tun-ioctl-permission = No se permite aplicar {$request} a {$name}, el dispositivo debe prepararlo root o un proceso con CAP_NET_ADMIN
+error-invalid-network-manager-mode = Modo de NetworkManager no válido
+error-invalid-ipv6-block-mode = Modo de bloqueo de IPv6 no válido
+error-invalid-mss-clamp-mode = Modo de ajuste de MSS no válido
+error-no-free-device-name = No hay ningún nombre de interfaz libre para la plantilla {$name}
+error-invalid-trusted-network = Criterio de red de confianza no válido: {$value}
+error-invalid-network-match = Criterio de coincidencia de red no válido: {$value}
+error-invalid-health-listen = Dirección de los puntos de estado no válida: {$value}
+error-invalid-proxy = Proxy no válido, se esperaba none, auto o http://[user:password@]host[:port]: {$value}
+error-proxy-connect-failed = El proxy {$proxy} rechazó la conexión a {$destination}: {$status}
+error-invalid-route = Ruta no válida: {$value}
 error-no-natt-reply = No hay respuesta NATT
 error-not-implemented = No implementado
 error-unknown-packet-type = Tipo de paquete desconocido
 error-no-sender = No hay remitente
 error-empty-ccc-session = Sesión CCC vacía
 error-identity-timeout = Tiempo de espera al esperar la respuesta de identidad, ¿es correcto el tipo de inicio de sesión?
+error-no-office-mode-address = La puerta de enlace no asignó una dirección utilizable: {$address}
+error-gateway-error = Error de la puerta de enlace, código: {$code}
+error-gateway-reconnect = La puerta de enlace solicitó reconectar repetidamente: {$message}
+error-hello-rejected = La puerta de enlace rechazó el saludo del cliente: {$message}
+error-roaming-timeout = No se pudo restablecer el túnel en {$seconds} segundos tras el cambio de red
+error-kill-switch-unavailable = El kill switch requiere la utilidad nft: {$message}
+error-forwarding-unavailable = El reenvío de LAN requiere la utilidad nft: {$message}
+error-mss-clamp-unavailable = El ajuste de MSS requiere la utilidad nft: {$message}
+error-app-routing-unavailable = El enrutamiento por aplicación requiere la utilidad nft: {$message}
+error-dns-redirect-unavailable = La redirección de DNS requiere la utilidad nft: {$message}
+error-forward-source-conflict = El origen de reenvío {$source} se solapa con la dirección o las rutas del túnel
+error-setup-missing-option = La opción { $option } es obligatoria en el modo no interactivo
+error-setup-config-exists = { $path } ya existe, use --force para sobrescribirlo
+error-setup-invalid-choice = Opción no válida: { $value }
+error-setup-unreachable = No se puede contactar con { $server }: { $error }
+error-setup-certificate-rejected = El certificado de la puerta de enlace no es de confianza, añada su CA con la opción ca-cert o acéptelo con --ignore-server-cert true
+error-import-read = No se puede leer { $path }: { $error }
+error-import-empty = { $path } no contiene ninguna configuración que se pueda importar
+error-import-not-toml = La configuración se importa a una configuración TOML, { $path } no tiene la extensión .toml
+error-import-profile-exists = El perfil { $profile } ya existe en { $path }
 
 # Placeholder texts
 placeholder-domains = Dominios separados por comas
@@ -124,18 +211,42 @@ icon-theme-light = Claro
 
 # Connection info
 info-connected-since = Conectado desde
+info-profile = Perfil
 info-server-name = Nombre del servidor
 info-user-name = Nombre de usuario
 info-login-type = Tipo de inicio de sesión
 info-tunnel-type = Tipo de túnel
 info-transport-type = Tipo de transporte
+info-protocol-version = Versión del protocolo
 info-ip-address = Dirección IP
+info-ipv6-address = Dirección IPv6
 info-dns-servers = Servidores DNS
 info-search-domains = Dominios de búsqueda
+info-wins-servers = Servidores WINS
 info-interface = Interfaz
+info-interface-mode = Modo de la interfaz
+info-interface-mode-p2p = punto a punto, par { $peer }
+info-interface-mode-local = solo dirección local
 info-dns-configured = DNS configurado
+info-dns-mode = Modo DNS
+info-dns-override = Prioridad de DNS
 info-routing-configured = Enrutamiento configurado
 info-default-route = Ruta predeterminada
+info-reauth-interval = Intervalo de reautenticación
+info-forwarding = Reenvío de LAN
+info-dns-check = Prueba de fuga DNS
+info-dns-check-passed = superada
+info-dns-check-failed = fallida
+info-routes = Rutas
+info-received = Recibido
+info-sent = Enviado
+info-traffic-amount = {$bytes}, {$packets} paquetes
+info-stats-since = Estadísticas desde
+info-connected-time = Tiempo conectado
+info-reconnects = Reconexiones
+info-reauths = Reautenticaciones
+info-rekeys = Renovaciones de claves
+info-last-error = Último error
 
 # Application
 app-title = Cliente VPN SNX-RS para Linux
@@ -165,8 +276,57 @@ tray-menu-exit = Salir
 cli-identity-provider-auth = Para la autenticación a través del proveedor de identidad, abra la siguiente URL en su navegador:
 cli-tunnel-connected = Túnel conectado, presione Ctrl+C para salir.
 cli-tunnel-disconnected = Túnel desconectado
+notify-connected = VPN conectada ({$address})
+notify-reconnecting = VPN reconectando (intento {$attempt})
+notify-disconnected = VPN desconectada
+notify-disconnected-reason = VPN desconectada: {$reason}
+notify-reauth-failed = Falló la reautenticación de la VPN: {$error}
+cli-waiting-for-connect = Esperando el comando de conexión
+cli-config-reloaded = Configuración recargada
+cli-reload-on-reconnect = Estas opciones se aplicarán en la próxima reconexión: { $options }
+cli-reload-on-restart = Estas opciones se aplicarán tras reiniciar snx-rs: { $options }
+cli-reload-failed = No se puede recargar la configuración, se mantiene la actual: { $error }
+cli-daemon-terminated = Se envió la señal de terminación al proceso {$pid}
+cli-tunnel-stopping = Esperando a que termine el túnel del proceso {$pid}
+cli-tunnel-killed = Matando el proceso {$pid} y restaurando la configuración de red que dejó
+cli-network-restored = Configuración de red restaurada
 cli-another-instance-running = Ya hay otra instancia de snx-rs en ejecución
+cli-replacing-instance = Deteniendo el túnel en ejecución del proceso {$pid}
 cli-app-terminated = Aplicación terminada por señal
+cli-session-expiring = La sesión VPN caduca en {$minutes} min, el túnel se desconectará
+cli-dns-check-failed = Advertencia: la prueba de fuga DNS ha fallado, las consultas DNS pueden no pasar por el túnel
+cli-trusted-network-lost = Ya no se detecta la red de confianza, reanudando la conexión VPN
+cli-gateway-failover = La puerta de enlace {$failed} no está accesible, probando {$server}
+cli-gateway-failback = La puerta de enlace {$server} vuelve a estar accesible, volviendo a ella
+cli-service-installed = Servicio {$name} instalado, inícielo con: sc.exe start {$name}
+cli-service-uninstalled = Servicio {$name} desinstalado
+cli-profile-switch = La red ha cambiado, cambiando al perfil {$profile}
+cli-dry-run-auth-failed = Ejecución de prueba: la autenticación ha fallado: { $error }
+cli-dry-run-negotiation-failed = Ejecución de prueba: la negociación del túnel ha fallado: { $error }
+cli-dry-run-complete = Ejecución de prueba completada, la configuración de red no se ha modificado.
+setup-server-prompt = Dirección de la puerta de enlace
+setup-probing = Comprobando la puerta de enlace { $server }...
+setup-certificate = Certificado: { $subject }, emitido por { $issuer }, válido hasta { $expires }
+setup-fingerprint = Huella SHA-256: { $fingerprint }
+setup-certificate-untrusted = El certificado no es de confianza: { $error }
+setup-ignore-cert-prompt = Aceptar este certificado de todos modos
+setup-login-options = Tipos de inicio de sesión ofrecidos por la puerta de enlace:
+setup-login-type-prompt = Tipo de inicio de sesión, número o id [{ $default }]
+setup-user-prompt = Nombre de usuario
+setup-password-prompt = Contraseña
+setup-store-password-prompt = Guardar la contraseña en el llavero
+setup-default-route-prompt = Enrutar todo el tráfico por el túnel (túnel completo)
+setup-dns-mode-prompt = Modo DNS, split o full [split]
+setup-overwrite-prompt = { $path } ya existe, sobrescribirlo
+setup-cancelled = Configuración cancelada, la configuración existente no cambia
+setup-config-written = Configuración escrita en { $path }
+setup-test-connect-prompt = Probar el inicio de sesión ahora
+setup-test-connect-ok = Inicio de sesión correcto
+import-config-written = Se importaron { $count } ajustes de { $source } a { $path }
+import-unmapped = No importado, línea { $line }: { $key }
+import-login-type-missing = No se importó ningún tipo de inicio de sesión, "snx-rs info -s { $server }" muestra los tipos de la pasarela para la opción login-type
+prompt-missing-parameters = Faltan parámetros obligatorios en la configuración: { $options }
+prompt-save-answers = Guardar las respuestas en { $path }
 
 # Connection Messages
 connection-connected-to = Conectado a {$server}
@@ -194,6 +354,7 @@ connection-status-disconnected = Desconectado
 connection-status-connecting = Conectando
 connection-status-connected-since = Conectado desde: {$since}
 connection-status-mfa-pending = Esperando MFA: {$mfa_type}
+connection-status-trusted-network = Red de confianza detectada, VPN suspendida
 
 # Login options
 login-options-server-address = Dirección del servidor
@@ -204,3 +365,11 @@ login-options-preferred-protocol = Protocolo preferido
 login-options-tcpt-port = Puerto TCPT
 login-options-natt-port = Puerto NATT
 login-options-internal-ca-fingerprint = Huella digital CA interna
+login-options-protocol-version = Versión del protocolo
+login-options-ipsec-transport = Transporte IPSec
+login-options-saml-enabled = SAML habilitado
+login-options-certificate-subject = Sujeto del certificado
+login-options-certificate-issuer = Emisor del certificado
+login-options-certificate-expires = Caducidad del certificado
+login-options-certificate-fingerprint = Huella SHA-256 del certificado
+login-options-certificate-error = Certificado no fiable
@@ -53,6 +53,40 @@ expand-ui = Käyttöliittymän asetukset
 error-no-server-name = Palvelimen osoitetta ei ole määritetty
 error-no-auth = Tunnistusmenetelmää ei ole valittu
 error-file-not-exist = Tiedostoa ei löydy: {$path}
+error-invalid-config-file = Virheellinen asetustiedosto {$path}: {$message}
+error-profile-not-found = Profiilia {$profile} ei löytynyt asetuksista
+error-in-profile = Profiili {$profile}: {$message}
+error-invalid-env-var = Virheellinen ympäristömuuttujan {$name} arvo: {$message}
+error-invalid-configuration = Virheellinen määritys:
+error-invalid-server-name = Virheellinen palvelimen osoite: {$server}
+error-route-host-bits = {$option}: {$route} sisältää isäntäbittejä, käytä {$network}
+error-options-conflict = {$first} ei voi olla käytössä yhdessä asetuksen {$second} kanssa
+error-insecure-permissions = Muut käyttäjät voivat kirjoittaa tiedostoon {$path}
+error-secret-file-permissions = {$path} sisältää salaisuuksia, mutta muut käyttäjät voivat käyttää sitä (tila {$mode}), rajoita sitä komennolla chmod 600 tai käytä valitsinta --insecure-permissions
+error-secret-file-symlink = {$path} sisältää salaisuuksia, mutta linkittää tiedostoon {$target} toisessa hakemistossa, käytä itse tiedostoa tai valitsinta --insecure-permissions
+error-script-not-executable = Skripti ei ole suoritettava: {$path}
+error-value-out-of-range = {$option} on oltava välillä {$min}–{$max}, saatiin {$value}
+error-timeout-exceeds-total = {$option} ({$value} sekuntia) on pidempi kuin {$total} ({$limit} sekuntia), mikään yritys ei ehtisi valmistua
+error-reserved-routing-table = Reititystaulu {$table} on järjestelmän varaama
+error-control-socket-in-use = Ohjaussoketti {$path} on toisen prosessin käytössä
+error-invalid-control-command = Virheellinen ohjauskomento: {$message}
+error-invalid-log-level = Virheellinen lokitaso: {$level}
+error-tunnel-not-running = Tunneli ei ole käynnissä
+error-invalid-dbus-service = Virheellinen D-Bus-palvelun väylä
+error-invalid-notify-event = Virheellinen ilmoitustapahtuma
+error-invalid-log-format = Virheellinen lokimuoto
+error-invalid-log-target = Virheellinen lokin kohde
+error-invalid-log-filter = Virheellinen lokisuodatin {$filter}: {$error}
+error-profile-not-served = Tunneli on käynnissä profiilille {$current}, profiili {$profile} tarvitsee toisen instanssin
+error-profile-running = Profiilin {$profile} tunneli on jo käynnissä prosessina {$pid}, pysäytä se valitsimella --replace
+error-instance-not-stopped = Profiilin {$profile} tunneli prosessissa {$pid} ei pysähtynyt
+error-tunnel-not-stopped = Prosessin {$pid} tunneli ei päättynyt {$timeout} sekunnissa, --force lopettaa sen ja palauttaa verkkoasetukset
+error-dbus-not-authorized = Toimintoa {$action} ei ole valtuutettu
+error-pid-file-locked = PID-tiedosto {$path} on toisen käynnissä olevan instanssin lukitsema
+error-unknown-run-as = Tuntematon käyttäjä tai ryhmä ilman oikeuksia ajoon: {$name}
+error-run-as-not-root = Asetus run-as-user vaatii tunnelin käynnistämisen root-käyttäjänä
+error-run-as-dns = resolv.conf-tiedoston palautus ja resolvconf-työkalu vaativat root-oikeudet, käytä run-as-user-asetuksen kanssa dns-backend=resolved tai no-dns
+error-privilege-drop = Vaihto käyttäjään ilman oikeuksia epäonnistui: {$error}
 error-invalid-cert-id = Varmennetunniste ei ole heksadesimaalimuodossa: {$id}
 error-ca-root-not-exist = CA-juuripolkua ei löydy: {$path}
 error-validation = Validoinnin virhe
@@ -60,6 +94,8 @@ error-user-input-canceled = Käyttäjän syöte peruttu
 error-connection-cancelled = Yhteys peruttu
 error-unknown-event = Tuntematon tapahtuma: {$event}
 error-no-service-connection = Ei yhteyttä palveluun
+error-session-not-owned = Istunto kuuluu käyttäjälle, jonka tunnus on {$uid}, vain tämä käyttäjä tai root voi hallita sitä
+error-option-not-permitted = Palvelu ei salli asetusta {$option} tälle käyttäjälle
 error-empty-input = Syöte ei voi olla tyhjä
 error-invalid-response = Virheellinen vastaus!
 error-invalid-object = Virheellinen objekti
@@ -67,7 +103,7 @@ error-no-connector = Ei tunneliyhteyttä
 error-tunnel-disconnected = Tunneli katkaistu, viimeisin viesti: {$message}
 error-unexpected-reply = Odottamaton vastaus
 error-auth-failed = Tunnistus epäonnistui
-error-no-login-type = Pakollinen parametri puuttuu: login-type
+error-missing-parameter = Pakollinen parametri puuttuu: { $option }
 error-connection-timeout = Yhteyden aikakatkaisu
 error-cannot-send-request = Pyyntöä ei voi lähettää palveluun
 error-cannot-read-reply = Vastausta ei voi lukea palvelusta
@@ -80,7 +116,13 @@ error-no-pkcs8 = Ei PKCS8 PEM-polku annettu
 error-no-pkcs11 = Ei PKCS11 PIN-koodia annettu
 error-no-ipsec-session = Ei IPSEC-istuntoa
 error-request-failed-error-code = Pyyntö epäonnistui, virhekoodi: {$error_code}
+error-gateway-info-refused = Yhdyskäytävä {$server} ei vastaa todentamattomiin kyselyihin, virhekoodi: {$error_code}
 error-no-root-privileges = Tämä ohjelma pitää ajaa root-käyttäjänä!
+error-missing-privileges = Valitut asetukset vaativat oikeuksia, joita tällä prosessilla ei ole (tarkistuksen voi ohittaa valitsimella --skip-permission-check):
+privilege-net-admin = CAP_NET_ADMIN tarvitaan tun-laitteen luomiseen sekä osoitteiden, reittien ja DNS:n määrittämiseen: suorita root-käyttäjänä, myönnä oikeus komennolla sudo setcap cap_net_admin+ep { $exe }, käynnistä systemd-yksiköstä asetuksella AmbientCapabilities=CAP_NET_ADMIN, tai välitä valmiiksi luotu laite tun-fd:llä yhdessä asetusten no-device-config, no-routing ja no-dns kanssa
+privilege-resolv-conf = DNS:n määrittäminen vaatii kirjoitusoikeuden tiedostoon { $path }: suorita root-käyttäjänä, valitse toinen dns-backend tai aseta no-dns=true
+privilege-resolvconf-tool = Vain root voi käyttää resolvconf-työkalua: valitse toinen dns-backend tai aseta no-dns=true
+privilege-nft = nft-komento tarvitaan asetuksille kill-switch ja forward-source: asenna nftables-paketti tai poista nämä asetukset käytöstä
 error-missing-required-parameters = Pakolliset parametrit puuttuvat: palvelimen nimi ja/tai kirjautumistyyppi!
 error-missing-server-name = Pakollinen parametri puuttuu: palvelimen nimi!
 error-no-connector-for-challenge-code = Ei konektoria haastekoodin lähettämiseen!
@@ -89,11 +131,13 @@ error-invalid-sexpr = Virheellinen sexpr: {$value}
 error-invalid-value = Virheellinen arvo
 error-udp-request-failed = Virhe UDP-pyynnön lähetyksessä
 error-no-tty = Ei TTY-yhteyttä käyttäjän syötteeseen
+error-service-mfa = Palvelu ei voi vastata MFA-haasteisiin eikä avata identiteetin tarjoajan selainta, käytä tallennettua salasanaa password-asetuksella tai varmennetodennusta cert-type-asetuksella
 error-invalid-auth-response = Virheellinen tunnistusvastaus
 error-invalid-client-settings = Virheelliset asiakasasetukset
 error-invalid-otp-reply = Virheellinen OTP-vastaus
 error-udp-encap-failed = UDP_ENCAP-soketin asetusta ei voi asettaa, virhekoodi: {$code}
 error-so-no-check-failed = SO_NO_CHECK-soketin asetusta ei voi asettaa, virhekoodi: {$code}
+error-so-mark-failed = SO_MARK-soketin asetusta ei voi asettaa, virhekoodi: {$code}
 error-keepalive-failed = Keepalive epäonnistui
 error-receive-failed = Vastaanotto epäonnistui
 error-unknown-color-scheme = Tuntematon väriteeman arvo
@@ -104,12 +148,55 @@ error-invalid-operation-mode = Virheellinen toimintatila
 error-invalid-tunnel-type = Virheellinen tunnelityyppi
 error-invalid-cert-type = Virheellinen varmennetyyppi
 error-invalid-icon-theme = Virheellinen kuvaketeema
+error-invalid-dns-backend = Virheellinen DNS-taustajärjestelmä
+error-invalid-dns-mode = Virheellinen DNS-tila
+error-invalid-dns-override = Virheellinen DNS-etusija
+error-invalid-address-conflict-action = Virheellinen osoiteristiriidan toiminto
+error-resolv-conf-symlink = {$path} on symbolinen linkki, suora DNS-tila ei muokkaa sitä; valitse toinen dns-backend
+error-resolv-conf-read-only = {$path} on vain luku -tilassa tai säiliön ajoympäristön liittämä: aseta dns-resolv-path kirjoittaaksesi muualle tai dns-backend=print soveltaaksesi sen skriptistä
+error-invalid-device-name = Virheellinen liitännän nimi: {$name}
+error-invalid-netns-name = Virheellinen verkkonimiavaruuden nimi: {$name}
+error-device-name-in-use = Liitäntä {$name} on jo olemassa
+error-invalid-tun-fd = Tiedostokahvan on kuuluttava tun-laitteeseen, joka on avattu lipuilla IFF_TUN ja IFF_NO_PI: {$message}
+error-unknown-tun-owner = Tuntematon käyttäjä tai ryhmä {$name}
+error-tun-ioctl = Kohteen {$request} soveltaminen laitteeseen {$name} epäonnistui: {$message}
+error-tun-ioctl-permission = Kohteen {$request} soveltaminen laitteeseen {$name} ei ole sallittua, laitteen on valmisteltava root tai prosessi, jolla on CAP_NET_ADMIN
+error-invalid-network-manager-mode = Virheellinen NetworkManager-tila
+error-invalid-ipv6-block-mode = Virheellinen IPv6-eston tila
+error-invalid-mss-clamp-mode = Virheellinen MSS-rajoituksen tila
+error-no-free-device-name = Mallille {$name} ei ole vapaata liitännän nimeä
+error-invalid-trusted-network = Virheellinen luotetun verkon ehto: {$value}
+error-invalid-network-match = Virheellinen verkon vastaavuusehto: {$value}
+error-invalid-health-listen = Virheellinen terveystarkistusten osoite: {$value}
+error-invalid-proxy = Virheellinen välityspalvelin, odotettiin none, auto tai http://[user:password@]host[:port]: {$value}
+error-proxy-connect-failed = Välityspalvelin {$proxy} hylkäsi yhteyden kohteeseen {$destination}: {$status}
+error-invalid-route = Virheellinen reitti: {$value}
 error-no-natt-reply = Ei NATT-vastausta
 error-not-implemented = Ei toteutettu
 error-unknown-packet-type = Tuntematon pakettityyppi
 error-no-sender = Ei lähettäjää
 error-empty-ccc-session = Tyhjä CCC-istunto
 error-identity-timeout = Aikakatkaisu odottaessa identiteettivastausta, onko kirjautumistyyppi oikea?
+error-no-office-mode-address = Yhdyskäytävä ei määrittänyt käyttökelpoista osoitetta: {$address}
+error-gateway-error = Yhdyskäytävän virhe, koodi: {$code}
+error-gateway-reconnect = Yhdyskäytävä pyysi toistuvasti yhdistämään uudelleen: {$message}
+error-hello-rejected = Yhdyskäytävä hylkäsi asiakkaan hello-viestin: {$message}
+error-roaming-timeout = Tunnelia ei voitu palauttaa {$seconds} sekunnin kuluessa verkon vaihdosta
+error-kill-switch-unavailable = Kill switch vaatii nft-työkalun: {$message}
+error-forwarding-unavailable = LAN-välitys vaatii nft-työkalun: {$message}
+error-mss-clamp-unavailable = MSS-rajoitus vaatii nft-työkalun: {$message}
+error-app-routing-unavailable = Sovelluskohtainen reititys vaatii nft-työkalun: {$message}
+error-dns-redirect-unavailable = DNS-uudelleenohjaus vaatii nft-työkalun: {$message}
+error-forward-source-conflict = Välityksen lähdeverkko {$source} menee päällekkäin tunnelin osoitteen tai reittien kanssa
+error-setup-missing-option = Valitsin { $option } on pakollinen ei-interaktiivisessa tilassa
+error-setup-config-exists = { $path } on jo olemassa, korvaa se valitsimella --force
+error-setup-invalid-choice = Virheellinen valinta: { $value }
+error-setup-unreachable = Yhteys kohteeseen { $server } ei onnistu: { $error }
+error-setup-certificate-rejected = Yhdyskäytävän varmenne ei ole luotettu, lisää sen CA asetuksella ca-cert tai hyväksy se valitsimella --ignore-server-cert true
+error-import-read = Tiedostoa { $path } ei voi lukea: { $error }
+error-import-empty = { $path } ei sisällä tuotavia asetuksia
+error-import-not-toml = Asetukset tuodaan TOML-määritykseen, tiedostolla { $path } ei ole .toml-päätettä
+error-import-profile-exists = Profiili { $profile } on jo tiedostossa { $path }
 
 # Placeholder texts
 placeholder-domains = Pilkulla erotetut verkkotunnukset
@@ -134,18 +221,42 @@ icon-theme-light = Vaalea
 
 # Connection info
 info-connected-since = Yhdistetty alkaen
+info-profile = Profiili
 info-server-name = Palvelimen nimi
 info-user-name = Käyttäjänimi
 info-login-type = Kirjautumistyyppi
 info-tunnel-type = Tunnelityyppi
 info-transport-type = Kuljetustyyppi
+info-protocol-version = Protokollan versio
 info-ip-address = IP-osoite
+info-ipv6-address = IPv6-osoite
 info-dns-servers = DNS-palvelimet
 info-search-domains = Hakualueet
+info-wins-servers = WINS-palvelimet
 info-interface = Käyttöliittymä
+info-interface-mode = Liitännän tila
+info-interface-mode-p2p = point-to-point, vastapää { $peer }
+info-interface-mode-local = vain paikallinen osoite
 info-dns-configured = DNS määritetty
+info-dns-mode = DNS-tila
+info-dns-override = DNS-etusija
 info-routing-configured = Reititys määritetty
 info-default-route = Oletusreitti
+info-reauth-interval = Uudelleentodennuksen väli
+info-forwarding = LAN-välitys
+info-dns-check = DNS-vuototesti
+info-dns-check-passed = läpäisty
+info-dns-check-failed = epäonnistui
+info-routes = Reitit
+info-received = Vastaanotettu
+info-sent = Lähetetty
+info-traffic-amount = {$bytes}, {$packets} pakettia
+info-stats-since = Tilastot alkaen
+info-connected-time = Yhteysaika
+info-reconnects = Uudelleenyhdistämiset
+info-reauths = Uudelleentodennukset
+info-rekeys = Avainten vaihdot
+info-last-error = Viimeisin virhe
 
 # Application
 app-title = SNX-RS VPN-asiakasohjelma Linuxille
@@ -175,8 +286,57 @@ tray-menu-exit = Lopeta
 cli-identity-provider-auth = Tunnistautumista varten tunnistepalvelun kautta, avaa seuraava URL-selaimessasi:
 cli-tunnel-connected = Tunneli yhdistetty, paina Ctrl+C lopettaaksesi.
 cli-tunnel-disconnected = Tunneli katkaistu
+notify-connected = VPN yhdistetty ({$address})
+notify-reconnecting = VPN yhdistää uudelleen (yritys {$attempt})
+notify-disconnected = VPN katkaistu
+notify-disconnected-reason = VPN katkaistu: {$reason}
+notify-reauth-failed = VPN:n uudelleentodennus epäonnistui: {$error}
+cli-waiting-for-connect = Odotetaan yhdistämiskomentoa
+cli-config-reloaded = Asetukset ladattu uudelleen
+cli-reload-on-reconnect = Nämä asetukset otetaan käyttöön seuraavassa uudelleenyhdistämisessä: { $options }
+cli-reload-on-restart = Nämä asetukset otetaan käyttöön, kun snx-rs käynnistetään uudelleen: { $options }
+cli-reload-failed = Asetuksia ei voi ladata uudelleen, nykyiset säilytetään: { $error }
+cli-daemon-terminated = Lopetussignaali lähetettiin prosessille {$pid}
+cli-tunnel-stopping = Odotetaan prosessin {$pid} tunnelin päättymistä
+cli-tunnel-killed = Lopetetaan prosessi {$pid} ja palautetaan sen jättämät verkkoasetukset
+cli-network-restored = Verkkoasetukset palautettu
 cli-another-instance-running = Toinen snx-rs-esiintymä on jo käynnissä
+cli-replacing-instance = Pysäytetään prosessin {$pid} käynnissä oleva tunneli
 cli-app-terminated = Sovellus päättyi signaalin vuoksi
+cli-session-expiring = VPN-istunto vanhenee {$minutes} min kuluttua, tunneli katkaistaan
+cli-dns-check-failed = Varoitus: DNS-vuototesti epäonnistui, DNS-kyselyt eivät ehkä kulje tunnelin kautta
+cli-trusted-network-lost = Luotettua verkkoa ei enää havaita, VPN-yhteys jatkuu
+cli-gateway-failover = Yhdyskäytävä {$failed} ei ole tavoitettavissa, yritetään {$server}
+cli-gateway-failback = Yhdyskäytävä {$server} on taas tavoitettavissa, vaihdetaan takaisin siihen
+cli-service-installed = Palvelu {$name} on asennettu, käynnistä se komennolla: sc.exe start {$name}
+cli-service-uninstalled = Palvelu {$name} on poistettu
+cli-profile-switch = Verkko on muuttunut, vaihdetaan profiiliin {$profile}
+cli-dry-run-auth-failed = Koeajo: todennus epäonnistui: { $error }
+cli-dry-run-negotiation-failed = Koeajo: tunnelin neuvottelu epäonnistui: { $error }
+cli-dry-run-complete = Koeajo valmis, verkkoasetuksia ei muutettu.
+setup-server-prompt = Yhdyskäytävän osoite
+setup-probing = Tarkistetaan yhdyskäytävä { $server }...
+setup-certificate = Varmenne: { $subject }, myöntäjä { $issuer }, voimassa { $expires } asti
+setup-fingerprint = SHA-256-sormenjälki: { $fingerprint }
+setup-certificate-untrusted = Varmenne ei ole luotettu: { $error }
+setup-ignore-cert-prompt = Hyväksytäänkö varmenne silti
+setup-login-options = Yhdyskäytävän tarjoamat kirjautumistavat:
+setup-login-type-prompt = Kirjautumistapa, numero tai tunnus [{ $default }]
+setup-user-prompt = Käyttäjätunnus
+setup-password-prompt = Salasana
+setup-store-password-prompt = Tallennetaanko salasana avainnippuun
+setup-default-route-prompt = Ohjataanko kaikki liikenne tunnelin kautta (täysi tunneli)
+setup-dns-mode-prompt = DNS-tila, split tai full [split]
+setup-overwrite-prompt = { $path } on jo olemassa, korvataanko
+setup-cancelled = Asennus peruttu, nykyinen määritys on ennallaan
+setup-config-written = Määritys kirjoitettu tiedostoon { $path }
+setup-test-connect-prompt = Testataanko kirjautuminen nyt
+setup-test-connect-ok = Kirjautuminen onnistui
+import-config-written = Tuotiin { $count } asetusta tiedostosta { $source } tiedostoon { $path }
+import-unmapped = Ei tuotu, rivi { $line }: { $key }
+import-login-type-missing = Kirjautumistyyppiä ei tuotu, "snx-rs info -s { $server }" luettelee yhdyskäytävän kirjautumistyypit login-type-asetusta varten
+prompt-missing-parameters = Asetuksista puuttuu pakollisia parametreja: { $options }
+prompt-save-answers = Tallennetaanko vastaukset tiedostoon { $path }
 
 # Connection Messages
 connection-connected-to = Yhdistetty palvelimeen {$server}
@@ -204,6 +364,7 @@ connection-status-disconnected = Yhteys katkaistu
 connection-status-connecting = Yhdistetään
 connection-status-connected-since = Yhdistetty: {$since}
 connection-status-mfa-pending = Odotetaan MFA: {$mfa_type}
+connection-status-trusted-network = Luotettu verkko havaittu, VPN keskeytetty
 
 # Login options
 login-options-server-address = Palvelimen osoite
@@ -214,3 +375,11 @@ login-options-preferred-protocol = Suositeltu protokolla
 login-options-tcpt-port = TCPT-portti
 login-options-natt-port = NATT-portti
 login-options-internal-ca-fingerprint = Sisäisen CA:n sormenjälki
+login-options-protocol-version = Protokollan versio
+login-options-ipsec-transport = IPSec-siirto
+login-options-saml-enabled = SAML käytössä
+login-options-certificate-subject = Varmenteen kohde
+login-options-certificate-issuer = Varmenteen myöntäjä
+login-options-certificate-expires = Varmenne vanhenee
+login-options-certificate-fingerprint = Varmenteen SHA-256-sormenjälki
+login-options-certificate-error = Varmenne ei ole luotettu
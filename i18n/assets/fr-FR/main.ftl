@@ -53,6 +53,40 @@ expand-ui = Interface utilisateur
 error-no-server-name = Aucune adresse de serveur spécifiée
 error-no-auth = Aucune méthode d'authentification sélectionnée
 error-file-not-exist = Le fichier n'existe pas : {$path}
+error-invalid-config-file = Fichier de configuration invalide {$path} : {$message}
+error-profile-not-found = Profil {$profile} introuvable dans la configuration
+error-in-profile = Profil {$profile} : {$message}
+error-invalid-env-var = Valeur invalide de la variable d'environnement {$name} : {$message}
+error-invalid-configuration = Configuration invalide :
+error-invalid-server-name = Adresse du serveur invalide : {$server}
+error-route-host-bits = {$option} : {$route} a des bits d'hôte définis, utilisez {$network}
+error-options-conflict = {$first} ne peut pas être combiné avec {$second}
+error-insecure-permissions = {$path} est modifiable par d'autres utilisateurs
+error-secret-file-permissions = {$path} contient des secrets mais d'autres utilisateurs peuvent y accéder (mode {$mode}), restreignez-le avec chmod 600 ou utilisez --insecure-permissions
+error-secret-file-symlink = {$path} contient des secrets mais pointe vers {$target} dans un autre répertoire, utilisez le fichier lui-même ou --insecure-permissions
+error-script-not-executable = Le script n'est pas exécutable : {$path}
+error-value-out-of-range = {$option} doit être compris entre {$min} et {$max}, reçu {$value}
+error-timeout-exceeds-total = {$option} de {$value} secondes est plus long que {$total} de {$limit} secondes, aucune tentative ne pourrait aboutir à temps
+error-reserved-routing-table = La table de routage {$table} est réservée par le système
+error-control-socket-in-use = Le socket de contrôle {$path} est utilisé par un autre processus
+error-invalid-control-command = Commande de contrôle invalide : {$message}
+error-invalid-log-level = Niveau de journalisation invalide : {$level}
+error-tunnel-not-running = Le tunnel n'est pas en cours d'exécution
+error-invalid-dbus-service = Bus du service D-Bus invalide
+error-invalid-notify-event = Événement de notification invalide
+error-invalid-log-format = Format de journal non valide
+error-invalid-log-target = Destination de journal non valide
+error-invalid-log-filter = Filtre de journal invalide {$filter} : {$error}
+error-profile-not-served = Le tunnel fonctionne pour le profil {$current}, le profil {$profile} nécessite une autre instance
+error-profile-running = Un tunnel du profil {$profile} est déjà en cours d'exécution dans le processus {$pid}, utilisez --replace pour l'arrêter
+error-instance-not-stopped = Le tunnel du profil {$profile} dans le processus {$pid} ne s'est pas arrêté
+error-tunnel-not-stopped = Le tunnel du processus {$pid} ne s'est pas arrêté en {$timeout} secondes, utilisez --force pour le tuer et restaurer les paramètres réseau
+error-dbus-not-authorized = L'action {$action} n'est pas autorisée
+error-pid-file-locked = Le fichier PID {$path} est verrouillé par une autre instance en cours d'exécution
+error-unknown-run-as = Utilisateur ou groupe inconnu pour l'exécution sans privilèges : {$name}
+error-run-as-not-root = L'option run-as-user nécessite de démarrer le tunnel en tant que root
+error-run-as-dns = La restauration de resolv.conf et l'outil resolvconf nécessitent root, utilisez dns-backend=resolved ou no-dns avec run-as-user
+error-privilege-drop = Impossible de passer à l'utilisateur sans privilèges : {$error}
 error-invalid-cert-id = ID de certificat non au format hexadécimal : {$id}
 error-ca-root-not-exist = Le chemin racine CA n'existe pas : {$path}
 error-validation = Erreur de validation
@@ -60,13 +94,15 @@ error-user-input-canceled = Saisie utilisateur annulée
 error-connection-cancelled = Connexion annulée
 error-unknown-event = Événement inconnu : {$event}
 error-no-service-connection = Pas de connexion au service
+error-session-not-owned = La session appartient à l'utilisateur d'ID {$uid}, seuls cet utilisateur ou root peuvent la contrôler
+error-option-not-permitted = Le service n'autorise pas l'option {$option} pour cet utilisateur
 error-empty-input = La saisie ne peut pas être vide
 error-invalid-object = Objet invalide
 error-no-connector = Pas de connecteur de tunnel
 error-tunnel-disconnected = Tunnel déconnecté, dernier message : {$message}
 error-unexpected-reply = Réponse inattendue
 error-auth-failed = Échec de l'authentification
-error-no-login-type = Paramètre obligatoire manquant : login-type
+error-missing-parameter = Paramètre obligatoire manquant : { $option }
 error-connection-timeout = Délai de connexion dépassé
 error-invalid-response = Réponse invalide !
 error-cannot-send-request = Impossible d'envoyer la requête au service
@@ -80,7 +116,13 @@ error-no-pkcs8 = Pas de chemin PKCS8 PEM fourni
 error-no-pkcs11 = Pas de code PIN PKCS11 fourni
 error-no-ipsec-session = Pas de session IPSEC
 error-request-failed-error-code = Échec de la requête, code d'erreur : {$error_code}
+error-gateway-info-refused = La passerelle {$server} ne répond pas aux requêtes non authentifiées, code d'erreur : {$error_code}
 error-no-root-privileges = Ce programme doit être exécuté en tant qu'utilisateur root !
+error-missing-privileges = Les options sélectionnées nécessitent des privilèges que ce processus ne possède pas (la vérification peut être ignorée avec --skip-permission-check) :
+privilege-net-admin = CAP_NET_ADMIN est nécessaire pour créer le périphérique tun et configurer les adresses, les routes et le DNS : exécutez en tant que root, accordez la capacité avec sudo setcap cap_net_admin+ep { $exe }, démarrez depuis une unité systemd avec AmbientCapabilities=CAP_NET_ADMIN, ou passez un périphérique pré-créé avec tun-fd accompagné de no-device-config, no-routing et no-dns
+privilege-resolv-conf = L'accès en écriture à { $path } est nécessaire pour configurer le DNS : exécutez en tant que root, choisissez un autre dns-backend ou définissez no-dns=true
+privilege-resolvconf-tool = L'outil resolvconf ne peut être utilisé que par root : choisissez un autre dns-backend ou définissez no-dns=true
+privilege-nft = La commande nft est nécessaire pour kill-switch et forward-source : installez le paquet nftables ou désactivez ces options
 error-missing-required-parameters = Paramètres obligatoires manquants : nom du serveur et/ou type de connexion !
 error-missing-server-name = Paramètre obligatoire manquant : nom du serveur !
 error-no-connector-for-challenge-code = Pas de connecteur pour envoyer le code de défi !
@@ -89,11 +131,13 @@ error-invalid-sexpr = sexpr invalide : {$value}
 error-invalid-value = Valeur invalide
 error-udp-request-failed = Erreur lors de l'envoi de la requête UDP
 error-no-tty = Pas de TTY attaché pour obtenir la saisie utilisateur
+error-service-mfa = Le service ne peut pas répondre aux défis MFA ni ouvrir le navigateur d'un fournisseur d'identité, utilisez un mot de passe enregistré avec l'option password ou l'authentification par certificat avec cert-type
 error-invalid-auth-response = Réponse d'authentification invalide
 error-invalid-client-settings = Réponse des paramètres client invalide
 error-invalid-otp-reply = Réponse OTP invalide
 error-udp-encap-failed = Impossible de définir l'option de socket UDP_ENCAP, code d'erreur : {$code}
 error-so-no-check-failed = Impossible de définir l'option de socket SO_NO_CHECK, code d'erreur : {$code}
+error-so-mark-failed = Impossible de définir l'option de socket SO_MARK, code d'erreur : {$code}
 error-keepalive-failed = Échec du keepalive
 error-receive-failed = Échec de la réception
 error-unknown-color-scheme = Valeur de schéma de couleur inconnue
@@ -104,12 +148,55 @@ error-invalid-operation-mode = Mode d'opération invalide
 error-invalid-tunnel-type = Type de tunnel invalide
 error-invalid-cert-type = Type de certificat invalide
 error-invalid-icon-theme = Thème d'icônes invalide
+error-invalid-dns-backend = Backend DNS invalide
+error-invalid-dns-mode = Mode DNS invalide
+error-invalid-dns-override = Priorité DNS invalide
+error-invalid-address-conflict-action = Action de conflit d'adresses invalide
+error-resolv-conf-symlink = {$path} est un lien symbolique, le mode DNS direct refuse de le modifier ; choisissez un autre dns-backend
+error-resolv-conf-read-only = {$path} est en lecture seule ou monté par l'environnement du conteneur : définissez dns-resolv-path pour écrire ailleurs, ou dns-backend=print pour l'appliquer depuis un script
+error-invalid-device-name = Nom d'interface invalide : {$name}
+error-invalid-netns-name = Nom d'espace de noms réseau invalide : {$name}
+error-device-name-in-use = L'interface {$name} existe déjà
+error-invalid-tun-fd = Le descripteur de fichier doit appartenir à un périphérique tun ouvert avec IFF_TUN et IFF_NO_PI : {$message}
+error-unknown-tun-owner = Utilisateur ou groupe inconnu {$name}
+error-tun-ioctl = Impossible d'appliquer {$request} à {$name} : {$message}
+error-tun-ioctl-permission = Application de {$request} à {$name} non autorisée, le périphérique doit être préparé par root ou par un processus disposant de CAP_NET_ADMIN
+error-invalid-network-manager-mode = Mode NetworkManager invalide
+error-invalid-ipv6-block-mode = Mode de blocage IPv6 invalide
+error-invalid-mss-clamp-mode = Mode de limitation du MSS invalide
+error-no-free-device-name = Aucun nom d'interface libre pour le modèle {$name}
+error-invalid-trusted-network = Critère de réseau de confiance invalide : {$value}
+error-invalid-network-match = Critère de correspondance réseau non valide : {$value}
+error-invalid-health-listen = Adresse des points de santé non valide : {$value}
+error-invalid-proxy = Proxy invalide, attendu none, auto ou http://[user:password@]host[:port] : {$value}
+error-proxy-connect-failed = Le proxy {$proxy} a refusé la connexion à {$destination} : {$status}
+error-invalid-route = Route invalide : {$value}
 error-no-natt-reply = Pas de réponse NATT
 error-not-implemented = Non implémenté
 error-unknown-packet-type = Type de paquet inconnu
 error-no-sender = Pas d'expéditeur
 error-empty-ccc-session = Session CCC vide
 error-identity-timeout = Délai d'attente lors de l'attente de la réponse d'identité, le type de connexion est-il correct ?
+error-no-office-mode-address = La passerelle n'a pas attribué d'adresse utilisable : {$address}
+error-gateway-error = Erreur de la passerelle, code : {$code}
+error-gateway-reconnect = La passerelle a demandé une reconnexion à plusieurs reprises : {$message}
+error-hello-rejected = La passerelle a rejeté le message hello du client : {$message}
+error-roaming-timeout = Impossible de rétablir le tunnel dans les {$seconds} secondes suivant le changement de réseau
+error-kill-switch-unavailable = Le kill switch nécessite l'utilitaire nft : {$message}
+error-forwarding-unavailable = Le transfert LAN nécessite l'utilitaire nft : {$message}
+error-mss-clamp-unavailable = La limitation du MSS nécessite l'utilitaire nft : {$message}
+error-app-routing-unavailable = Le routage par application nécessite l'utilitaire nft : {$message}
+error-dns-redirect-unavailable = La redirection DNS nécessite l'utilitaire nft : {$message}
+error-forward-source-conflict = La source de transfert {$source} chevauche l'adresse ou les routes du tunnel
+error-setup-missing-option = L'option { $option } est obligatoire en mode non interactif
+error-setup-config-exists = { $path } existe déjà, utilisez --force pour l'écraser
+error-setup-invalid-choice = Choix invalide : { $value }
+error-setup-unreachable = Impossible de joindre { $server } : { $error }
+error-setup-certificate-rejected = Le certificat de la passerelle n'est pas approuvé, ajoutez son CA avec l'option ca-cert ou acceptez-le avec --ignore-server-cert true
+error-import-read = Impossible de lire { $path } : { $error }
+error-import-empty = { $path } ne contient aucun paramètre importable
+error-import-not-toml = Les paramètres sont importés dans une configuration TOML, { $path } n'a pas l'extension .toml
+error-import-profile-exists = Le profil { $profile } existe déjà dans { $path }
 
 # Placeholder texts
 placeholder-domains = Domaines séparés par des virgules
@@ -158,25 +245,98 @@ tray-menu-exit = Quitter
 
 # Connection info
 info-connected-since = Connecté depuis
+info-profile = Profil
 info-server-name = Nom du serveur
 info-user-name = Nom d'utilisateur
 info-login-type = Type de connexion
 info-tunnel-type = Type de tunnel
 info-transport-type = Type de transport
+info-protocol-version = Version du protocole
 info-ip-address = Adresse IP
+info-ipv6-address = Adresse IPv6
 info-dns-servers = Serveurs DNS
 info-search-domains = Domaines de recherche
+info-wins-servers = Serveurs WINS
 info-interface = Interface
+info-interface-mode = Mode de l'interface
+info-interface-mode-p2p = point à point, pair { $peer }
+info-interface-mode-local = adresse locale uniquement
 info-dns-configured = DNS configuré
+info-dns-mode = Mode DNS
+info-dns-override = Priorité DNS
 info-routing-configured = Routage configuré
 info-default-route = Route par défaut
+info-reauth-interval = Intervalle de réauthentification
+info-forwarding = Transfert LAN
+info-dns-check = Test de fuite DNS
+info-dns-check-passed = réussi
+info-dns-check-failed = échoué
+info-routes = Routes
+info-received = Reçu
+info-sent = Envoyé
+info-traffic-amount = {$bytes}, {$packets} paquets
+info-stats-since = Statistiques depuis
+info-connected-time = Durée de connexion
+info-reconnects = Reconnexions
+info-reauths = Réauthentifications
+info-rekeys = Renouvellements de clés
+info-last-error = Dernière erreur
 
 # CLI Messages
 cli-identity-provider-auth = Pour l'authentification via le fournisseur d'identité, ouvrez l'URL suivante dans votre navigateur :
 cli-tunnel-connected = Tunnel connecté, appuyez sur Ctrl+C pour quitter.
 cli-tunnel-disconnected = Tunnel déconnecté
+notify-connected = VPN connecté ({$address})
+notify-reconnecting = VPN en reconnexion (tentative {$attempt})
+notify-disconnected = VPN déconnecté
+notify-disconnected-reason = VPN déconnecté : {$reason}
+notify-reauth-failed = Échec de la réauthentification VPN : {$error}
+cli-waiting-for-connect = En attente de la commande de connexion
+cli-config-reloaded = Configuration rechargée
+cli-reload-on-reconnect = Ces options s'appliqueront à la prochaine reconnexion : { $options }
+cli-reload-on-restart = Ces options s'appliqueront après un redémarrage de snx-rs : { $options }
+cli-reload-failed = Impossible de recharger la configuration, la configuration actuelle est conservée : { $error }
+cli-daemon-terminated = Signal de terminaison envoyé au processus {$pid}
+cli-tunnel-stopping = Attente de l'arrêt du tunnel du processus {$pid}
+cli-tunnel-killed = Arrêt forcé du processus {$pid} et restauration des paramètres réseau laissés
+cli-network-restored = Paramètres réseau restaurés
 cli-another-instance-running = Une autre instance de snx-rs est déjà en cours d'exécution
+cli-replacing-instance = Arrêt du tunnel en cours d'exécution du processus {$pid}
 cli-app-terminated = Application terminée par un signal
+cli-session-expiring = La session VPN expire dans {$minutes} min, le tunnel sera déconnecté
+cli-dns-check-failed = Attention : le test de fuite DNS a échoué, les requêtes DNS peuvent ne pas passer par le tunnel
+cli-trusted-network-lost = Réseau de confiance non détecté, reprise de la connexion VPN
+cli-gateway-failover = La passerelle {$failed} est injoignable, essai de {$server}
+cli-gateway-failback = La passerelle {$server} est de nouveau joignable, retour vers celle-ci
+cli-service-installed = Service {$name} installé, démarrez-le avec : sc.exe start {$name}
+cli-service-uninstalled = Service {$name} désinstallé
+cli-profile-switch = Le réseau a changé, passage au profil {$profile}
+cli-dry-run-auth-failed = Essai à blanc : échec de l'authentification : { $error }
+cli-dry-run-negotiation-failed = Essai à blanc : échec de la négociation du tunnel : { $error }
+cli-dry-run-complete = Essai à blanc terminé, la configuration réseau n'a pas été modifiée.
+setup-server-prompt = Adresse de la passerelle
+setup-probing = Vérification de la passerelle { $server }...
+setup-certificate = Certificat : { $subject }, émis par { $issuer }, valide jusqu'au { $expires }
+setup-fingerprint = Empreinte SHA-256 : { $fingerprint }
+setup-certificate-untrusted = Le certificat n'est pas approuvé : { $error }
+setup-ignore-cert-prompt = Accepter quand même ce certificat
+setup-login-options = Types de connexion proposés par la passerelle :
+setup-login-type-prompt = Type de connexion, numéro ou identifiant [{ $default }]
+setup-user-prompt = Nom d'utilisateur
+setup-password-prompt = Mot de passe
+setup-store-password-prompt = Enregistrer le mot de passe dans le trousseau
+setup-default-route-prompt = Faire passer tout le trafic par le tunnel (tunnel complet)
+setup-dns-mode-prompt = Mode DNS, split ou full [split]
+setup-overwrite-prompt = { $path } existe déjà, l'écraser
+setup-cancelled = Configuration annulée, la configuration existante est inchangée
+setup-config-written = Configuration écrite dans { $path }
+setup-test-connect-prompt = Tester la connexion maintenant
+setup-test-connect-ok = Connexion réussie
+import-config-written = { $count } paramètres importés de { $source } vers { $path }
+import-unmapped = Non importé, ligne { $line } : { $key }
+import-login-type-missing = Aucun type de connexion n'a été importé, "snx-rs info -s { $server }" liste les types de connexion de la passerelle pour l'option login-type
+prompt-missing-parameters = Il manque des paramètres obligatoires dans la configuration : { $options }
+prompt-save-answers = Enregistrer les réponses dans { $path }
 
 # Connection Messages
 connection-connected-to = Connecté à {$server}
@@ -204,6 +364,7 @@ connection-status-disconnected = Déconnecté
 connection-status-connecting = Connexion en cours
 connection-status-connected-since = Connecté depuis: {$since}
 connection-status-mfa-pending = En attente de MFA: {$mfa_type}
+connection-status-trusted-network = Réseau de confiance détecté, VPN suspendu
 
 # Login options
 login-options-server-address = Adresse du serveur
@@ -214,3 +375,11 @@ login-options-preferred-protocol = Protocole préféré
 login-options-tcpt-port = Port TCPT
 login-options-natt-port = Port NATT
 login-options-internal-ca-fingerprint = Empreinte CA interne
+login-options-protocol-version = Version du protocole
+login-options-ipsec-transport = Transport IPSec
+login-options-saml-enabled = SAML activé
+login-options-certificate-subject = Sujet du certificat
+login-options-certificate-issuer = Émetteur du certificat
+login-options-certificate-expires = Expiration du certificat
+login-options-certificate-fingerprint = Empreinte SHA-256 du certificat
+login-options-certificate-error = Certificat non approuvé
@@ -53,6 +53,40 @@ expand-ui = Interfaccia utente
 error-no-server-name = Nessun indirizzo server specificato
 error-no-auth = Nessun metodo di autenticazione selezionato
 error-file-not-exist = Il file non esiste: {$path}
+error-invalid-config-file = File di configurazione non valido {$path}: {$message}
+error-profile-not-found = Profilo {$profile} non trovato nella configurazione
+error-in-profile = Profilo {$profile}: {$message}
+error-invalid-env-var = Valore non valido della variabile d'ambiente {$name}: {$message}
+error-invalid-configuration = Configurazione non valida:
+error-invalid-server-name = Indirizzo del server non valido: {$server}
+error-route-host-bits = {$option}: {$route} ha bit di host impostati, usare {$network}
+error-options-conflict = {$first} non può essere combinato con {$second}
+error-insecure-permissions = {$path} è scrivibile da altri utenti
+error-secret-file-permissions = {$path} contiene segreti ma altri utenti possono accedervi (modalità {$mode}), limitalo con chmod 600 o usa --insecure-permissions
+error-secret-file-symlink = {$path} contiene segreti ma è un collegamento a {$target} in un'altra directory, usa il file stesso o --insecure-permissions
+error-script-not-executable = Lo script non è eseguibile: {$path}
+error-value-out-of-range = {$option} deve essere compreso tra {$min} e {$max}, ricevuto {$value}
+error-timeout-exceeds-total = {$option} di {$value} secondi è più lungo di {$total} di {$limit} secondi, nessun tentativo potrebbe completarsi in tempo
+error-reserved-routing-table = La tabella di routing {$table} è riservata dal sistema
+error-control-socket-in-use = Il socket di controllo {$path} è usato da un altro processo
+error-invalid-control-command = Comando di controllo non valido: {$message}
+error-invalid-log-level = Livello di log non valido: {$level}
+error-tunnel-not-running = Il tunnel non è in esecuzione
+error-invalid-dbus-service = Bus del servizio D-Bus non valido
+error-invalid-notify-event = Evento di notifica non valido
+error-invalid-log-format = Formato di log non valido
+error-invalid-log-target = Destinazione del log non valida
+error-invalid-log-filter = Filtro di log non valido {$filter}: {$error}
+error-profile-not-served = Il tunnel è in esecuzione per il profilo {$current}, il profilo {$profile} richiede un'altra istanza
+error-profile-running = Un tunnel per il profilo {$profile} è già in esecuzione come processo {$pid}, usare --replace per fermarlo
+error-instance-not-stopped = Il tunnel del profilo {$profile} nel processo {$pid} non si è fermato
+error-tunnel-not-stopped = Il tunnel del processo {$pid} non è terminato in {$timeout} secondi, usare --force per terminarlo e ripristinare le impostazioni di rete
+error-dbus-not-authorized = L'azione {$action} non è autorizzata
+error-pid-file-locked = Il file PID {$path} è bloccato da un'altra istanza in esecuzione
+error-unknown-run-as = Utente o gruppo sconosciuto per l'esecuzione senza privilegi: {$name}
+error-run-as-not-root = L'opzione run-as-user richiede l'avvio del tunnel come root
+error-run-as-dns = Il ripristino di resolv.conf e lo strumento resolvconf richiedono root, usare dns-backend=resolved o no-dns con run-as-user
+error-privilege-drop = Impossibile passare all'utente senza privilegi: {$error}
 error-invalid-cert-id = ID certificato non in formato esadecimale: {$id}
 error-ca-root-not-exist = Il percorso root CA non esiste: {$path}
 error-validation = Errore di validazione
@@ -60,17 +94,25 @@ error-user-input-canceled = Input utente annullato
 error-connection-cancelled = Connessione annullata
 error-unknown-event = Evento sconosciuto: {$event}
 error-no-service-connection = Nessuna connessione al servizio
+error-session-not-owned = La sessione appartiene all'utente con ID {$uid}, solo quell'utente o root possono controllarla
+error-option-not-permitted = Il servizio non consente l'opzione {$option} a questo utente
 error-empty-input = L'input non può essere vuoto
 error-invalid-object = Oggetto non valido
 error-no-connector = Nessun connettore tunnel
 error-tunnel-disconnected = Tunnel disconnesso, ultimo messaggio: {$message}
 error-unexpected-reply = Risposta inaspettata
 error-auth-failed = Autenticazione fallita
-error-no-login-type = Parametro obbligatorio mancante: login-type
+error-missing-parameter = Parametro obbligatorio mancante: { $option }
 error-connection-timeout = Timeout della connessione
 error-invalid-response = Risposta non valida!
 error-request-failed-error-code = Richiesta fallita, codice di errore: {$error_code}
+error-gateway-info-refused = Il gateway {$server} non risponde alle richieste non autenticate, codice di errore: {$error_code}
 error-no-root-privileges = Questo programma deve essere eseguito come utente root!
+error-missing-privileges = Le opzioni selezionate richiedono privilegi che questo processo non possiede (il controllo può essere saltato con --skip-permission-check):
+privilege-net-admin = CAP_NET_ADMIN è necessario per creare il dispositivo tun e configurare indirizzi, rotte e DNS: eseguire come root, concedere la capacità con sudo setcap cap_net_admin+ep { $exe }, avviare da un'unità systemd con AmbientCapabilities=CAP_NET_ADMIN, oppure passare un dispositivo già creato con tun-fd insieme a no-device-config, no-routing e no-dns
+privilege-resolv-conf = È necessario l'accesso in scrittura a { $path } per configurare il DNS: eseguire come root, selezionare un altro dns-backend o impostare no-dns=true
+privilege-resolvconf-tool = Lo strumento resolvconf può essere usato solo da root: selezionare un altro dns-backend o impostare no-dns=true
+privilege-nft = Il comando nft è necessario per kill-switch e forward-source: installare il pacchetto nftables o disattivare queste opzioni
 error-missing-required-parameters = Parametri obbligatori mancanti: nome server e/o tipo di accesso!
 error-missing-server-name = Parametro obbligatorio mancante: nome server!
 error-no-connector-for-challenge-code = Nessun connettore per inviare il codice di sfida!
@@ -79,11 +121,13 @@ error-invalid-sexpr = sexpr non valido: {$value}
 error-invalid-value = Valore non valido
 error-udp-request-failed = Errore nell'invio della richiesta UDP
 error-no-tty = Nessun TTY collegato per ottenere l'input dell'utente
+error-service-mfa = Il servizio non può rispondere alle richieste MFA né aprire il browser di un provider di identità, usa una password salvata con l'opzione password o l'autenticazione con certificato con cert-type
 error-invalid-auth-response = Risposta di autenticazione non valida
 error-invalid-client-settings = Risposta delle impostazioni client non valida
 error-invalid-otp-reply = Risposta OTP non valida
 error-udp-encap-failed = Impossibile impostare l'opzione socket UDP_ENCAP, codice di errore: {$code}
 error-so-no-check-failed = Impossibile impostare l'opzione socket SO_NO_CHECK, codice di errore: {$code}
+error-so-mark-failed = Impossibile impostare l'opzione socket SO_MARK, codice di errore: {$code}
 error-keepalive-failed = Keepalive fallito
 error-receive-failed = Ricezione fallita
 error-unknown-color-scheme = Valore schema colori sconosciuto
@@ -94,12 +138,55 @@ error-invalid-operation-mode = Modalità operativa non valida
 error-invalid-tunnel-type = Tipo di tunnel non valido
 error-invalid-cert-type = Tipo di certificato non valido
 error-invalid-icon-theme = Tema icone non valido
+error-invalid-dns-backend = Backend DNS non valido
+error-invalid-dns-mode = Modalità DNS non valida
+error-invalid-dns-override = Priorità DNS non valida
+error-invalid-address-conflict-action = Azione per conflitto di indirizzi non valida
+error-resolv-conf-symlink = {$path} è un collegamento simbolico, la modalità DNS diretta non lo modificherà; selezionare un altro dns-backend
+error-resolv-conf-read-only = {$path} è di sola lettura o montato dal runtime del container: impostare dns-resolv-path per scrivere altrove, o dns-backend=print per applicarlo da uno script
+error-invalid-device-name = Nome interfaccia non valido: {$name}
+error-invalid-netns-name = Nome dello spazio dei nomi di rete non valido: {$name}
+error-device-name-in-use = L'interfaccia {$name} esiste già
+error-invalid-tun-fd = Il descrittore di file deve appartenere a un dispositivo tun aperto con IFF_TUN e IFF_NO_PI: {$message}
+error-unknown-tun-owner = Utente o gruppo sconosciuto {$name}
+error-tun-ioctl = Impossibile applicare {$request} a {$name}: {$message}
+error-tun-ioctl-permission = Non è consentito applicare {$request} a {$name}, il dispositivo deve essere preparato da root o da un processo con CAP_NET_ADMIN
+error-invalid-network-manager-mode = Modalità NetworkManager non valida
+error-invalid-ipv6-block-mode = Modalità di blocco IPv6 non valida
+error-invalid-mss-clamp-mode = Modalità di limitazione MSS non valida
+error-no-free-device-name = Nessun nome interfaccia libero per il modello {$name}
+error-invalid-trusted-network = Criterio di rete attendibile non valido: {$value}
+error-invalid-network-match = Criterio di corrispondenza di rete non valido: {$value}
+error-invalid-health-listen = Indirizzo degli endpoint di stato non valido: {$value}
+error-invalid-proxy = Proxy non valido, previsto none, auto o http://[user:password@]host[:port]: {$value}
+error-proxy-connect-failed = Il proxy {$proxy} ha rifiutato la connessione a {$destination}: {$status}
+error-invalid-route = Percorso non valido: {$value}
 error-no-natt-reply = Nessuna risposta NATT
 error-not-implemented = Non implementato
 error-unknown-packet-type = Tipo di pacchetto sconosciuto
 error-no-sender = Nessun mittente
 error-empty-ccc-session = Sessione CCC vuota
 error-identity-timeout = Timeout durante l'attesa della risposta di identità, il tipo di accesso è corretto?
+error-no-office-mode-address = Il gateway non ha assegnato un indirizzo utilizzabile: {$address}
+error-gateway-error = Errore del gateway, codice: {$code}
+error-gateway-reconnect = Il gateway ha richiesto ripetutamente una riconnessione: {$message}
+error-hello-rejected = Il gateway ha rifiutato il messaggio hello del client: {$message}
+error-roaming-timeout = Impossibile ristabilire il tunnel entro {$seconds} secondi dal cambio di rete
+error-kill-switch-unavailable = Il kill switch richiede l'utilità nft: {$message}
+error-forwarding-unavailable = L'inoltro LAN richiede l'utilità nft: {$message}
+error-mss-clamp-unavailable = La limitazione MSS richiede l'utilità nft: {$message}
+error-app-routing-unavailable = L'instradamento per applicazione richiede l'utilità nft: {$message}
+error-dns-redirect-unavailable = Il reindirizzamento DNS richiede l'utilità nft: {$message}
+error-forward-source-conflict = La sorgente di inoltro {$source} si sovrappone all'indirizzo o alle rotte del tunnel
+error-setup-missing-option = L'opzione { $option } è obbligatoria in modalità non interattiva
+error-setup-config-exists = { $path } esiste già, usare --force per sovrascriverlo
+error-setup-invalid-choice = Scelta non valida: { $value }
+error-setup-unreachable = Impossibile raggiungere { $server }: { $error }
+error-setup-certificate-rejected = Il certificato del gateway non è attendibile, aggiungere la sua CA con l'opzione ca-cert o accettarlo con --ignore-server-cert true
+error-import-read = Impossibile leggere { $path }: { $error }
+error-import-empty = { $path } non contiene impostazioni importabili
+error-import-not-toml = Le impostazioni vengono importate in una configurazione TOML, { $path } non ha l'estensione .toml
+error-import-profile-exists = Il profilo { $profile } esiste già in { $path }
 
 # Placeholder texts
 placeholder-domains = Domini separati da virgole
@@ -124,18 +211,42 @@ icon-theme-light = Chiaro
 
 # Connection info
 info-connected-since = Connesso da
+info-profile = Profilo
 info-server-name = Nome server
 info-user-name = Nome utente
 info-login-type = Tipo di accesso
 info-tunnel-type = Tipo di tunnel
 info-transport-type = Tipo di trasporto
+info-protocol-version = Versione del protocollo
 info-ip-address = Indirizzo IP
+info-ipv6-address = Indirizzo IPv6
 info-dns-servers = Server DNS
 info-search-domains = Domini di ricerca
+info-wins-servers = Server WINS
 info-interface = Interfaccia
+info-interface-mode = Modalità dell'interfaccia
+info-interface-mode-p2p = punto-punto, peer { $peer }
+info-interface-mode-local = solo indirizzo locale
 info-dns-configured = DNS configurato
+info-dns-mode = Modalità DNS
+info-dns-override = Priorità DNS
 info-routing-configured = Routing configurato
 info-default-route = Route predefinita
+info-reauth-interval = Intervallo di riautenticazione
+info-forwarding = Inoltro LAN
+info-dns-check = Test di perdita DNS
+info-dns-check-passed = superato
+info-dns-check-failed = fallito
+info-routes = Rotte
+info-received = Ricevuti
+info-sent = Inviati
+info-traffic-amount = {$bytes}, {$packets} pacchetti
+info-stats-since = Statistiche dal
+info-connected-time = Tempo di connessione
+info-reconnects = Riconnessioni
+info-reauths = Riautenticazioni
+info-rekeys = Rinnovi delle chiavi
+info-last-error = Ultimo errore
 
 # Application
 app-title = Client VPN SNX-RS per Linux
@@ -165,8 +276,57 @@ tray-menu-exit = Esci
 cli-identity-provider-auth = Per l'autenticazione tramite il provider di identità, apri il seguente URL nel tuo browser:
 cli-tunnel-connected = Tunnel connesso, premi Ctrl+C per uscire.
 cli-tunnel-disconnected = Tunnel disconnesso
+notify-connected = VPN connessa ({$address})
+notify-reconnecting = VPN in riconnessione (tentativo {$attempt})
+notify-disconnected = VPN disconnessa
+notify-disconnected-reason = VPN disconnessa: {$reason}
+notify-reauth-failed = Riautenticazione VPN non riuscita: {$error}
+cli-waiting-for-connect = In attesa del comando di connessione
+cli-config-reloaded = Configurazione ricaricata
+cli-reload-on-reconnect = Queste opzioni verranno applicate alla prossima riconnessione: { $options }
+cli-reload-on-restart = Queste opzioni verranno applicate dopo un riavvio di snx-rs: { $options }
+cli-reload-failed = Impossibile ricaricare la configurazione, viene mantenuta quella attuale: { $error }
+cli-daemon-terminated = Segnale di terminazione inviato al processo {$pid}
+cli-tunnel-stopping = In attesa che il tunnel del processo {$pid} termini
+cli-tunnel-killed = Terminazione del processo {$pid} e ripristino delle impostazioni di rete lasciate
+cli-network-restored = Impostazioni di rete ripristinate
 cli-another-instance-running = Un'altra istanza di snx-rs è già in esecuzione
+cli-replacing-instance = Arresto del tunnel in esecuzione del processo {$pid}
 cli-app-terminated = Applicazione terminata da un segnale
+cli-session-expiring = La sessione VPN scade tra {$minutes} min, il tunnel verrà disconnesso
+cli-dns-check-failed = Attenzione: test di perdita DNS fallito, le query DNS potrebbero non passare attraverso il tunnel
+cli-trusted-network-lost = Rete attendibile non più rilevata, ripresa della connessione VPN
+cli-gateway-failover = Il gateway {$failed} non è raggiungibile, si prova {$server}
+cli-gateway-failback = Il gateway {$server} è di nuovo raggiungibile, si ritorna a esso
+cli-service-installed = Servizio {$name} installato, avvialo con: sc.exe start {$name}
+cli-service-uninstalled = Servizio {$name} disinstallato
+cli-profile-switch = La rete è cambiata, si passa al profilo {$profile}
+cli-dry-run-auth-failed = Prova: autenticazione non riuscita: { $error }
+cli-dry-run-negotiation-failed = Prova: negoziazione del tunnel non riuscita: { $error }
+cli-dry-run-complete = Prova completata, la configurazione di rete non è stata modificata.
+setup-server-prompt = Indirizzo del gateway
+setup-probing = Verifica del gateway { $server }...
+setup-certificate = Certificato: { $subject }, emesso da { $issuer }, valido fino al { $expires }
+setup-fingerprint = Impronta SHA-256: { $fingerprint }
+setup-certificate-untrusted = Il certificato non è attendibile: { $error }
+setup-ignore-cert-prompt = Accettare comunque questo certificato
+setup-login-options = Tipi di accesso offerti dal gateway:
+setup-login-type-prompt = Tipo di accesso, numero o id [{ $default }]
+setup-user-prompt = Nome utente
+setup-password-prompt = Password
+setup-store-password-prompt = Salvare la password nel portachiavi
+setup-default-route-prompt = Instradare tutto il traffico nel tunnel (tunnel completo)
+setup-dns-mode-prompt = Modalità DNS, split o full [split]
+setup-overwrite-prompt = { $path } esiste già, sovrascriverlo
+setup-cancelled = Configurazione annullata, la configurazione esistente resta invariata
+setup-config-written = Configurazione scritta in { $path }
+setup-test-connect-prompt = Provare l'accesso ora
+setup-test-connect-ok = Accesso riuscito
+import-config-written = Importate { $count } impostazioni da { $source } in { $path }
+import-unmapped = Non importato, riga { $line }: { $key }
+import-login-type-missing = Nessun tipo di accesso importato, "snx-rs info -s { $server }" elenca i tipi di accesso del gateway per l'opzione login-type
+prompt-missing-parameters = Nella configurazione mancano parametri obbligatori: { $options }
+prompt-save-answers = Salvare le risposte in { $path }
 
 # Connection Messages
 connection-connected-to = Connesso a {$server}
@@ -194,6 +354,7 @@ connection-status-disconnected = Disconnesso
 connection-status-connecting = Connessione in corso
 connection-status-connected-since = Connesso da: {$since}
 connection-status-mfa-pending = In attesa di MFA: {$mfa_type}
+connection-status-trusted-network = Rete attendibile rilevata, VPN sospesa
 
 # Login options
 login-options-server-address = Indirizzo server
@@ -204,3 +365,11 @@ login-options-preferred-protocol = Protocollo preferito
 login-options-tcpt-port = Porta TCPT
 login-options-natt-port = Porta NATT
 login-options-internal-ca-fingerprint = Impronta CA interna
+login-options-protocol-version = Versione del protocollo
+login-options-ipsec-transport = Trasporto IPSec
+login-options-saml-enabled = SAML abilitato
+login-options-certificate-subject = Soggetto del certificato
+login-options-certificate-issuer = Emittente del certificato
+login-options-certificate-expires = Scadenza del certificato
+login-options-certificate-fingerprint = Impronta SHA-256 del certificato
+login-options-certificate-error = Certificato non attendibile
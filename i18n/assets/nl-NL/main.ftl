@@ -53,6 +53,40 @@ expand-ui = Gebruikersinterface
 error-no-server-name = Geen serveradres opgegeven
 error-no-auth = Geen authenticatiemethode geselecteerd
 error-file-not-exist = Bestand bestaat niet: {$path}
+error-invalid-config-file = Ongeldig configuratiebestand {$path}: {$message}
+error-profile-not-found = Profiel {$profile} niet gevonden in de configuratie
+error-in-profile = Profiel {$profile}: {$message}
+error-invalid-env-var = Ongeldige waarde van omgevingsvariabele {$name}: {$message}
+error-invalid-configuration = Ongeldige configuratie:
+error-invalid-server-name = Ongeldig serveradres: {$server}
+error-route-host-bits = {$option}: {$route} heeft hostbits ingesteld, gebruik {$network}
+error-options-conflict = {$first} kan niet worden gecombineerd met {$second}
+error-insecure-permissions = {$path} is beschrijfbaar door andere gebruikers
+error-secret-file-permissions = {$path} bevat geheimen maar andere gebruikers hebben er toegang toe (modus {$mode}), beperk het met chmod 600 of gebruik --insecure-permissions
+error-secret-file-symlink = {$path} bevat geheimen maar verwijst naar {$target} in een andere map, gebruik het bestand zelf of --insecure-permissions
+error-script-not-executable = Script is niet uitvoerbaar: {$path}
+error-value-out-of-range = {$option} moet tussen {$min} en {$max} liggen, kreeg {$value}
+error-timeout-exceeds-total = {$option} van {$value} seconden is langer dan {$total} van {$limit} seconden, geen enkele poging kan op tijd voltooid worden
+error-reserved-routing-table = Routeringstabel {$table} is gereserveerd door het systeem
+error-control-socket-in-use = Besturingssocket {$path} wordt door een ander proces gebruikt
+error-invalid-control-command = Ongeldige besturingsopdracht: {$message}
+error-invalid-log-level = Ongeldig logniveau: {$level}
+error-tunnel-not-running = De tunnel is niet actief
+error-invalid-dbus-service = Ongeldige bus voor de D-Bus-service
+error-invalid-notify-event = Ongeldige meldingsgebeurtenis
+error-invalid-log-format = Ongeldig logformaat
+error-invalid-log-target = Ongeldige logbestemming
+error-invalid-log-filter = Ongeldig logfilter {$filter}: {$error}
+error-profile-not-served = De tunnel draait voor het profiel {$current}, het profiel {$profile} heeft een andere instantie nodig
+error-profile-running = Een tunnel voor het profiel {$profile} draait al als proces {$pid}, gebruik --replace om deze te stoppen
+error-instance-not-stopped = De tunnel van het profiel {$profile} in proces {$pid} is niet gestopt
+error-tunnel-not-stopped = De tunnel van proces {$pid} is niet binnen {$timeout} seconden gestopt, gebruik --force om hem te beëindigen en de netwerkinstellingen te herstellen
+error-dbus-not-authorized = De actie {$action} is niet toegestaan
+error-pid-file-locked = Het PID-bestand {$path} is vergrendeld door een andere actieve instantie
+error-unknown-run-as = Onbekende gebruiker of groep voor uitvoering zonder rechten: {$name}
+error-run-as-not-root = De optie run-as-user vereist dat de tunnel als root wordt gestart
+error-run-as-dns = Het herstellen van resolv.conf en de resolvconf-tool vereisen root, gebruik dns-backend=resolved of no-dns met run-as-user
+error-privilege-drop = Kan niet overschakelen naar de gebruiker zonder rechten: {$error}
 error-invalid-cert-id = Certificaat-ID niet in hexadecimaal formaat: {$id}
 error-ca-root-not-exist = CA-rootpad bestaat niet: {$path}
 error-validation = Validatiefout
@@ -60,17 +94,25 @@ error-user-input-canceled = Gebruikersinvoer geannuleerd
 error-connection-cancelled = Verbinding geannuleerd
 error-unknown-event = Onbekende gebeurtenis: {$event}
 error-no-service-connection = Geen verbinding met de service
+error-session-not-owned = De sessie is van de gebruiker met ID {$uid}, alleen die gebruiker of root mag deze beheren
+error-option-not-permitted = De service staat de optie {$option} niet toe voor deze gebruiker
 error-empty-input = Invoer mag niet leeg zijn
 error-invalid-object = Ongeldig object
 error-no-connector = Geen tunnelconnector
 error-tunnel-disconnected = Tunnel verbroken, laatste bericht: {$message}
 error-unexpected-reply = Onverwachte reactie
 error-auth-failed = Authenticatie mislukt
-error-no-login-type = Verplichte parameter ontbreekt: login-type
+error-missing-parameter = Verplichte parameter ontbreekt: { $option }
 error-connection-timeout = Verbindingstimeout
 error-invalid-response = Ongeldige reactie!
 error-request-failed-error-code = Verzoek mislukt, foutcode: {$error_code}
+error-gateway-info-refused = De gateway {$server} beantwoordt geen niet-geauthenticeerde verzoeken, foutcode: {$error_code}
 error-no-root-privileges = Dit programma moet als root-gebruiker worden uitgevoerd!
+error-missing-privileges = De gekozen opties vereisen rechten die dit proces niet heeft (de controle kan worden overgeslagen met --skip-permission-check):
+privilege-net-admin = CAP_NET_ADMIN is nodig om het tun-apparaat te maken en adressen, routes en DNS te configureren: voer uit als root, ken de rechten toe met sudo setcap cap_net_admin+ep { $exe }, start vanuit een systemd-unit met AmbientCapabilities=CAP_NET_ADMIN, of geef een vooraf aangemaakt apparaat door met tun-fd samen met no-device-config, no-routing en no-dns
+privilege-resolv-conf = Schrijftoegang tot { $path } is nodig om DNS te configureren: voer uit als root, kies een andere dns-backend of stel no-dns=true in
+privilege-resolvconf-tool = Het hulpmiddel resolvconf kan alleen door root worden gebruikt: kies een andere dns-backend of stel no-dns=true in
+privilege-nft = Het commando nft is nodig voor kill-switch en forward-source: installeer het pakket nftables of schakel deze opties uit
 error-missing-required-parameters = Verplichte parameters ontbreken: servernaam en/of toegangstype!
 error-missing-server-name = Verplichte parameter ontbreekt: servernaam!
 error-no-connector-for-challenge-code = Geen connector voor het verzenden van de uitdagingscode!
@@ -79,11 +121,13 @@ error-invalid-sexpr = Ongeldige sexpr: {$value}
 error-invalid-value = Ongeldige waarde
 error-udp-request-failed = Fout bij het verzenden van UDP-verzoek
 error-no-tty = Geen TTY aangesloten voor gebruikersinvoer
+error-service-mfa = De service kan geen MFA-uitdagingen beantwoorden of de browser van een identiteitsprovider openen, gebruik een opgeslagen wachtwoord met de optie password of certificaatverificatie met cert-type
 error-invalid-auth-response = Ongeldige authenticatierespons
 error-invalid-client-settings = Ongeldige clientinstellingen
 error-invalid-otp-reply = Ongeldige OTP-reactie
 error-udp-encap-failed = Kan UDP_ENCAP socketoptie niet instellen, foutcode: {$code}
 error-so-no-check-failed = Kan SO_NO_CHECK socketoptie niet instellen, foutcode: {$code}
+error-so-mark-failed = Kan SO_MARK socketoptie niet instellen, foutcode: {$code}
 error-keepalive-failed = Keepalive mislukt
 error-receive-failed = Ontvangst mislukt
 error-unknown-color-scheme = Onbekende kleurenschema-waarde
@@ -94,12 +138,55 @@ error-invalid-operation-mode = Ongeldige bedrijfsmodus
 error-invalid-tunnel-type = Ongeldig tunneltype
 error-invalid-cert-type = Ongeldig certificaattype
 error-invalid-icon-theme = Ongeldig pictogramthema
+error-invalid-dns-backend = Ongeldige DNS-backend
+error-invalid-dns-mode = Ongeldige DNS-modus
+error-invalid-dns-override = Ongeldige DNS-voorrang
+error-invalid-address-conflict-action = Ongeldige actie bij adresconflict
+error-resolv-conf-symlink = {$path} is een symbolische koppeling, de directe DNS-modus wijzigt deze niet; kies een andere dns-backend
+error-resolv-conf-read-only = {$path} is alleen-lezen of gekoppeld door de container-runtime: stel dns-resolv-path in om elders te schrijven, of dns-backend=print om het vanuit een script toe te passen
+error-invalid-device-name = Ongeldige interfacenaam: {$name}
+error-invalid-netns-name = Ongeldige naam van netwerknaamruimte: {$name}
+error-device-name-in-use = Interface {$name} bestaat al
+error-invalid-tun-fd = De bestandsdescriptor moet horen bij een tun-apparaat geopend met IFF_TUN en IFF_NO_PI: {$message}
+error-unknown-tun-owner = Onbekende gebruiker of groep {$name}
+error-tun-ioctl = Kan {$request} niet toepassen op {$name}: {$message}
+error-tun-ioctl-permission = {$request} toepassen op {$name} is niet toegestaan, het apparaat moet worden ingesteld door root of een proces met CAP_NET_ADMIN
+error-invalid-network-manager-mode = Ongeldige NetworkManager-modus
+error-invalid-ipv6-block-mode = Ongeldige IPv6-blokkeermodus
+error-invalid-mss-clamp-mode = Ongeldige MSS-begrenzingsmodus
+error-no-free-device-name = Geen vrije interfacenaam voor sjabloon {$name}
+error-invalid-trusted-network = Ongeldig criterium voor vertrouwd netwerk: {$value}
+error-invalid-network-match = Ongeldig netwerkovereenkomstcriterium: {$value}
+error-invalid-health-listen = Ongeldig adres van de health-endpoints: {$value}
+error-invalid-proxy = Ongeldige proxy, verwacht none, auto of http://[user:password@]host[:port]: {$value}
+error-proxy-connect-failed = De proxy {$proxy} weigerde de verbinding met {$destination}: {$status}
+error-invalid-route = Ongeldige route: {$value}
 error-no-natt-reply = Geen NATT-reactie
 error-not-implemented = Niet geïmplementeerd
 error-unknown-packet-type = Onbekend pakkettype
 error-no-sender = Geen afzender
 error-empty-ccc-session = Lege CCC-sessie
 error-identity-timeout = Timeout tijdens wachten op identiteitsreactie, is het toegangstype correct?
+error-no-office-mode-address = De gateway heeft geen bruikbaar adres toegewezen: {$address}
+error-gateway-error = Gatewayfout, code: {$code}
+error-gateway-reconnect = De gateway vroeg herhaaldelijk om opnieuw te verbinden: {$message}
+error-hello-rejected = De gateway heeft de client-hello geweigerd: {$message}
+error-roaming-timeout = Kan de tunnel niet binnen {$seconds} seconden na de netwerkwijziging herstellen
+error-kill-switch-unavailable = De kill switch vereist het hulpprogramma nft: {$message}
+error-forwarding-unavailable = LAN-doorsturen vereist het hulpprogramma nft: {$message}
+error-mss-clamp-unavailable = MSS-begrenzing vereist het hulpprogramma nft: {$message}
+error-app-routing-unavailable = Routering per applicatie vereist het hulpprogramma nft: {$message}
+error-dns-redirect-unavailable = DNS-omleiding vereist het hulpprogramma nft: {$message}
+error-forward-source-conflict = Doorstuurbron {$source} overlapt met het adres of de routes van de tunnel
+error-setup-missing-option = De optie { $option } is verplicht in de niet-interactieve modus
+error-setup-config-exists = { $path } bestaat al, gebruik --force om het te overschrijven
+error-setup-invalid-choice = Ongeldige keuze: { $value }
+error-setup-unreachable = Kan { $server } niet bereiken: { $error }
+error-setup-certificate-rejected = Het certificaat van de gateway wordt niet vertrouwd, voeg de CA toe met de optie ca-cert of accepteer het met --ignore-server-cert true
+error-import-read = Kan { $path } niet lezen: { $error }
+error-import-empty = { $path } bevat geen instellingen die kunnen worden geïmporteerd
+error-import-not-toml = De instellingen worden geïmporteerd in een TOML-configuratie, { $path } heeft niet de extensie .toml
+error-import-profile-exists = Het profiel { $profile } bestaat al in { $path }
 error-cannot-send-request = Kan verzoek niet naar service sturen
 error-cannot-read-reply = Kan antwoord van service niet lezen
 error-no-ipv4 = Geen IPv4-adres voor {$server}
@@ -134,18 +221,42 @@ icon-theme-light = Licht
 
 # Connection info
 info-connected-since = Verbonden sinds
+info-profile = Profiel
 info-server-name = Servernaam
 info-user-name = Gebruikersnaam
 info-login-type = Inlogtype
 info-tunnel-type = Tunneltype
 info-transport-type = Transporttype
+info-protocol-version = Protocolversie
 info-ip-address = IP-adres
+info-ipv6-address = IPv6-adres
 info-dns-servers = DNS-servers
 info-search-domains = Zoekdomeinen
+info-wins-servers = WINS-servers
 info-interface = Interface
+info-interface-mode = Interfacemodus
+info-interface-mode-p2p = point-to-point, peer { $peer }
+info-interface-mode-local = alleen lokaal adres
 info-dns-configured = DNS geconfigureerd
+info-dns-mode = DNS-modus
+info-dns-override = DNS-voorrang
 info-routing-configured = Routering geconfigureerd
 info-default-route = Standaardroute
+info-reauth-interval = Herauthenticatie-interval
+info-forwarding = LAN-doorsturen
+info-dns-check = DNS-lektest
+info-dns-check-passed = geslaagd
+info-dns-check-failed = mislukt
+info-routes = Routes
+info-received = Ontvangen
+info-sent = Verzonden
+info-traffic-amount = {$bytes}, {$packets} pakketten
+info-stats-since = Statistieken sinds
+info-connected-time = Verbindingsduur
+info-reconnects = Herverbindingen
+info-reauths = Herauthenticaties
+info-rekeys = Sleutelvernieuwingen
+info-last-error = Laatste fout
 
 # Application
 app-title = SNX-RS VPN-client voor Linux
@@ -175,8 +286,57 @@ tray-menu-exit = Afsluiten
 cli-identity-provider-auth = Voor authenticatie via de identiteitsprovider, open de volgende URL in uw browser:
 cli-tunnel-connected = Tunnel verbonden, druk op Ctrl+C om af te sluiten.
 cli-tunnel-disconnected = Tunnel verbroken
+notify-connected = VPN verbonden ({$address})
+notify-reconnecting = VPN maakt opnieuw verbinding (poging {$attempt})
+notify-disconnected = VPN verbroken
+notify-disconnected-reason = VPN verbroken: {$reason}
+notify-reauth-failed = Opnieuw authenticeren van de VPN mislukt: {$error}
+cli-waiting-for-connect = Wachten op de verbindingsopdracht
+cli-config-reloaded = Configuratie opnieuw geladen
+cli-reload-on-reconnect = Deze opties worden toegepast bij de volgende herverbinding: { $options }
+cli-reload-on-restart = Deze opties worden toegepast na een herstart van snx-rs: { $options }
+cli-reload-failed = Kan de configuratie niet opnieuw laden, de huidige blijft behouden: { $error }
+cli-daemon-terminated = Het beëindigingssignaal is naar proces {$pid} gestuurd
+cli-tunnel-stopping = Wachten tot de tunnel van proces {$pid} stopt
+cli-tunnel-killed = Proces {$pid} wordt beëindigd en de achtergelaten netwerkinstellingen worden hersteld
+cli-network-restored = Netwerkinstellingen hersteld
 cli-another-instance-running = Er draait al een andere instantie van snx-rs
+cli-replacing-instance = De draaiende tunnel van proces {$pid} wordt gestopt
 cli-app-terminated = Applicatie beëindigd door signaal
+cli-session-expiring = De VPN-sessie verloopt over {$minutes} min, de tunnel wordt verbroken
+cli-dns-check-failed = Waarschuwing: DNS-lektest mislukt, DNS-query's gaan mogelijk niet door de tunnel
+cli-trusted-network-lost = Vertrouwd netwerk niet meer gedetecteerd, VPN-verbinding wordt hervat
+cli-gateway-failover = Gateway {$failed} is onbereikbaar, {$server} wordt geprobeerd
+cli-gateway-failback = Gateway {$server} is weer bereikbaar, er wordt teruggeschakeld
+cli-service-installed = Service {$name} geïnstalleerd, start deze met: sc.exe start {$name}
+cli-service-uninstalled = Service {$name} verwijderd
+cli-profile-switch = Het netwerk is gewijzigd, er wordt overgeschakeld naar profiel {$profile}
+cli-dry-run-auth-failed = Proefrun: authenticatie mislukt: { $error }
+cli-dry-run-negotiation-failed = Proefrun: onderhandeling van de tunnel mislukt: { $error }
+cli-dry-run-complete = Proefrun voltooid, de netwerkconfiguratie is niet gewijzigd.
+setup-server-prompt = Gateway-adres
+setup-probing = Gateway { $server } wordt gecontroleerd...
+setup-certificate = Certificaat: { $subject }, uitgegeven door { $issuer }, geldig tot { $expires }
+setup-fingerprint = SHA-256-vingerafdruk: { $fingerprint }
+setup-certificate-untrusted = Het certificaat wordt niet vertrouwd: { $error }
+setup-ignore-cert-prompt = Dit certificaat toch accepteren
+setup-login-options = Aanmeldtypen aangeboden door de gateway:
+setup-login-type-prompt = Aanmeldtype, nummer of id [{ $default }]
+setup-user-prompt = Gebruikersnaam
+setup-password-prompt = Wachtwoord
+setup-store-password-prompt = Wachtwoord opslaan in de sleutelhanger
+setup-default-route-prompt = Al het verkeer via de tunnel leiden (volledige tunnel)
+setup-dns-mode-prompt = DNS-modus, split of full [split]
+setup-overwrite-prompt = { $path } bestaat al, overschrijven
+setup-cancelled = Installatie geannuleerd, de bestaande configuratie is ongewijzigd
+setup-config-written = Configuratie geschreven naar { $path }
+setup-test-connect-prompt = Aanmelding nu testen
+setup-test-connect-ok = Aanmelding geslaagd
+import-config-written = { $count } instellingen geïmporteerd uit { $source } naar { $path }
+import-unmapped = Niet geïmporteerd, regel { $line }: { $key }
+import-login-type-missing = Er is geen aanmeldtype geïmporteerd, "snx-rs info -s { $server }" toont de aanmeldtypen van de gateway voor de optie login-type
+prompt-missing-parameters = De configuratie mist verplichte parameters: { $options }
+prompt-save-answers = De antwoorden opslaan in { $path }
 
 # Connection Messages
 connection-connected-to = Verbonden met {$server}
@@ -204,6 +364,7 @@ connection-status-disconnected = Verbinding verbroken
 connection-status-connecting = Verbinding maken
 connection-status-connected-since = Verbonden sinds: {$since}
 connection-status-mfa-pending = Wachten op MFA: {$mfa_type}
+connection-status-trusted-network = Vertrouwd netwerk gedetecteerd, VPN gepauzeerd
 
 # Login options
 login-options-server-address = Serveradres
@@ -214,3 +375,11 @@ login-options-preferred-protocol = Voorkeursprotocol
 login-options-tcpt-port = TCPT-poort
 login-options-natt-port = NATT-poort
 login-options-internal-ca-fingerprint = Interne CA-vingerafdruk
+login-options-protocol-version = Protocolversie
+login-options-ipsec-transport = IPSec-transport
+login-options-saml-enabled = SAML ingeschakeld
+login-options-certificate-subject = Onderwerp van het certificaat
+login-options-certificate-issuer = Uitgever van het certificaat
+login-options-certificate-expires = Certificaat verloopt
+login-options-certificate-fingerprint = SHA-256-vingerafdruk van het certificaat
+login-options-certificate-error = Certificaat niet vertrouwd
@@ -53,6 +53,40 @@ expand-ui = Brukergrensesnitt
 error-no-server-name = Ingen serveradresse er angitt
 error-no-auth = Ingen autentiseringsmetode er valgt
 error-file-not-exist = Filen finnes ikke: {$path}
+error-invalid-config-file = Ugyldig konfigurasjonsfil {$path}: {$message}
+error-profile-not-found = Profilen {$profile} ble ikke funnet i konfigurasjonen
+error-in-profile = Profil {$profile}: {$message}
+error-invalid-env-var = Ugyldig verdi i miljøvariabelen {$name}: {$message}
+error-invalid-configuration = Ugyldig konfigurasjon:
+error-invalid-server-name = Ugyldig serveradresse: {$server}
+error-route-host-bits = {$option}: {$route} har vertsbiter satt, bruk {$network}
+error-options-conflict = {$first} kan ikke kombineres med {$second}
+error-insecure-permissions = {$path} kan skrives av andre brukere
+error-secret-file-permissions = {$path} inneholder hemmeligheter, men andre brukere har tilgang til den (modus {$mode}), begrens den med chmod 600 eller bruk --insecure-permissions
+error-secret-file-symlink = {$path} inneholder hemmeligheter, men lenker til {$target} i en annen katalog, bruk selve filen eller --insecure-permissions
+error-script-not-executable = Skriptet er ikke kjørbart: {$path}
+error-value-out-of-range = {$option} må være mellom {$min} og {$max}, fikk {$value}
+error-timeout-exceeds-total = {$option} på {$value} sekunder er lengre enn {$total} på {$limit} sekunder, ingen forsøk kan fullføres i tide
+error-reserved-routing-table = Rutingtabell {$table} er reservert av systemet
+error-control-socket-in-use = Kontrollsokkelen {$path} brukes av en annen prosess
+error-invalid-control-command = Ugyldig kontrollkommando: {$message}
+error-invalid-log-level = Ugyldig loggnivå: {$level}
+error-tunnel-not-running = Tunnelen kjører ikke
+error-invalid-dbus-service = Ugyldig buss for D-Bus-tjenesten
+error-invalid-notify-event = Ugyldig varslingshendelse
+error-invalid-log-format = Ugyldig loggformat
+error-invalid-log-target = Ugyldig loggmål
+error-invalid-log-filter = Ugyldig loggfilter {$filter}: {$error}
+error-profile-not-served = Tunnelen kjører for profilen {$current}, profilen {$profile} trenger en annen instans
+error-profile-running = En tunnel for profilen {$profile} kjører allerede som prosessen {$pid}, bruk --replace for å stoppe den
+error-instance-not-stopped = Tunnelen for profilen {$profile} i prosessen {$pid} stoppet ikke
+error-tunnel-not-stopped = Tunnelen i prosess {$pid} avsluttet ikke innen {$timeout} sekunder, bruk --force for å drepe den og gjenopprette nettverksinnstillingene
+error-dbus-not-authorized = Handlingen {$action} er ikke autorisert
+error-pid-file-locked = PID-filen {$path} er låst av en annen kjørende instans
+error-unknown-run-as = Ukjent bruker eller gruppe for kjøring uten rettigheter: {$name}
+error-run-as-not-root = Alternativet run-as-user krever at tunnelen startes som root
+error-run-as-dns = Gjenoppretting av resolv.conf og resolvconf-verktøyet krever root, bruk dns-backend=resolved eller no-dns med run-as-user
+error-privilege-drop = Kan ikke bytte til brukeren uten rettigheter: {$error}
 error-invalid-cert-id = Sertifikat-ID er ikke i heksadesimalt format: {$id}
 error-ca-root-not-exist = CA-rotsti finnes ikke: {$path}
 error-validation = Valideringsfeil
@@ -60,13 +94,15 @@ error-user-input-canceled = Brukerinndata avbrutt
 error-connection-cancelled = Tilkobling avbrutt
 error-unknown-event = Ukjent hendelse: {$event}
 error-no-service-connection = Ingen tilkobling til tjenesten
+error-session-not-owned = Økten tilhører brukeren med ID {$uid}, bare denne brukeren eller root kan styre den
+error-option-not-permitted = Tjenesten tillater ikke alternativet {$option} for denne brukeren
 error-empty-input = Inndata kan ikke være tom
 error-invalid-object = Ugyldig objekt
 error-no-connector = Ingen tunnelkobling
 error-tunnel-disconnected = Tunnel frakoblet, siste melding: {$message}
 error-unexpected-reply = Uventet svar
 error-auth-failed = Autentisering mislyktes
-error-no-login-type = Manglende påkrevd parameter: login-type
+error-missing-parameter = Manglende påkrevd parameter: { $option }
 error-connection-timeout = Tilkoblingstimeout
 error-invalid-response = Ugyldig svar
 error-cannot-send-request = Kan ikke sende forespørsel til tjenesten
@@ -80,7 +116,13 @@ error-no-pkcs8 = Ingen PKCS8 PEM-sti oppgitt
 error-no-pkcs11 = Ingen PKCS11 PIN oppgitt
 error-no-ipsec-session = Ingen IPSEC-økt
 error-request-failed-error-code = Forespørsel mislyktes, feilkode: {$error_code}
+error-gateway-info-refused = Gatewayen {$server} svarer ikke på uautentiserte forespørsler, feilkode: {$error_code}
 error-no-root-privileges = Dette programmet må kjøres som root-bruker!
+error-missing-privileges = De valgte alternativene krever rettigheter som denne prosessen ikke har (kontrollen kan hoppes over med --skip-permission-check):
+privilege-net-admin = CAP_NET_ADMIN kreves for å opprette tun-enheten og konfigurere adresser, ruter og DNS: kjør som root, tildel rettigheten med sudo setcap cap_net_admin+ep { $exe }, start fra en systemd-enhet med AmbientCapabilities=CAP_NET_ADMIN, eller send en forhåndsopprettet enhet med tun-fd sammen med no-device-config, no-routing og no-dns
+privilege-resolv-conf = Skrivetilgang til { $path } kreves for å konfigurere DNS: kjør som root, velg en annen dns-backend eller sett no-dns=true
+privilege-resolvconf-tool = Verktøyet resolvconf kan bare brukes av root: velg en annen dns-backend eller sett no-dns=true
+privilege-nft = Kommandoen nft kreves av kill-switch og forward-source: installer pakken nftables eller slå av disse alternativene
 error-missing-required-parameters = Manglende påkrevde parametere: servernavn og/eller tilgangstype!
 error-missing-server-name = Manglende påkrevd parameter: servernavn!
 error-no-connector-for-challenge-code = Ingen kobling for å sende utfordringskode!
@@ -89,11 +131,13 @@ error-invalid-sexpr = Ugyldig sexpr: {$value}
 error-invalid-value = Ugyldig verdi
 error-udp-request-failed = Feil ved sending av UDP-forespørsel
 error-no-tty = Ingen TTY tilkoblet for brukerinndata
+error-service-mfa = Tjenesten kan ikke svare på MFA-utfordringer eller åpne nettleseren til en identitetsleverandør, bruk et lagret passord med alternativet password eller sertifikatautentisering med cert-type
 error-invalid-auth-response = Ugyldig autentiseringssvar
 error-invalid-client-settings = Ugyldige klientinnstillinger
 error-invalid-otp-reply = Ugyldig OTP-svar
 error-udp-encap-failed = Kan ikke sette UDP_ENCAP socket-opsjon, feilkode: {$code}
 error-so-no-check-failed = Kan ikke sette SO_NO_CHECK socket-opsjon, feilkode: {$code}
+error-so-mark-failed = Kan ikke sette SO_MARK socket-opsjon, feilkode: {$code}
 error-keepalive-failed = Keepalive mislyktes
 error-receive-failed = Mottak mislyktes
 error-unknown-color-scheme = Ukjent fargeskjema-verdi
@@ -104,12 +148,55 @@ error-invalid-operation-mode = Ugyldig driftsmodus
 error-invalid-tunnel-type = Ugyldig tunneltype
 error-invalid-cert-type = Ugyldig sertifikattype
 error-invalid-icon-theme = Ugyldig ikon-tema
+error-invalid-dns-backend = Ugyldig DNS-backend
+error-invalid-dns-mode = Ugyldig DNS-modus
+error-invalid-dns-override = Ugyldig DNS-prioritet
+error-invalid-address-conflict-action = Ugyldig handling ved adressekonflikt
+error-resolv-conf-symlink = {$path} er en symbolsk lenke, direkte DNS-modus endrer den ikke; velg en annen dns-backend
+error-resolv-conf-read-only = {$path} er skrivebeskyttet eller montert av containerens kjøremiljø: angi dns-resolv-path for å skrive et annet sted, eller dns-backend=print for å bruke det fra et skript
+error-invalid-device-name = Ugyldig grensesnittnavn: {$name}
+error-invalid-netns-name = Ugyldig navn på nettverksnavnerom: {$name}
+error-device-name-in-use = Grensesnittet {$name} finnes allerede
+error-invalid-tun-fd = Fildeskriptoren må tilhøre en tun-enhet åpnet med IFF_TUN og IFF_NO_PI: {$message}
+error-unknown-tun-owner = Ukjent bruker eller gruppe {$name}
+error-tun-ioctl = Kan ikke bruke {$request} på {$name}: {$message}
+error-tun-ioctl-permission = Ikke tillatt å bruke {$request} på {$name}, enheten må klargjøres av root eller en prosess med CAP_NET_ADMIN
+error-invalid-network-manager-mode = Ugyldig NetworkManager-modus
+error-invalid-ipv6-block-mode = Ugyldig IPv6-blokkeringsmodus
+error-invalid-mss-clamp-mode = Ugyldig MSS-begrensningsmodus
+error-no-free-device-name = Ingen ledige grensesnittnavn for malen {$name}
+error-invalid-trusted-network = Ugyldig kriterium for klarert nettverk: {$value}
+error-invalid-network-match = Ugyldig kriterium for nettverkssamsvar: {$value}
+error-invalid-health-listen = Ugyldig adresse for helseendepunktene: {$value}
+error-invalid-proxy = Ugyldig proxy, forventet none, auto eller http://[user:password@]host[:port]: {$value}
+error-proxy-connect-failed = Proxyen {$proxy} avviste tilkoblingen til {$destination}: {$status}
+error-invalid-route = Ugyldig rute: {$value}
 error-no-natt-reply = Ingen NATT-svar
 error-not-implemented = Ikke implementert
 error-unknown-packet-type = Ukjent pakketype
 error-no-sender = Ingen avsender
 error-empty-ccc-session = Tom CCC-økt
 error-identity-timeout = Timeout mens du venter på identitetssvar, er tilgangstypen korrekt?
+error-no-office-mode-address = Gatewayen tildelte ikke en brukbar adresse: {$address}
+error-gateway-error = Gatewayfeil, kode: {$code}
+error-gateway-reconnect = Gatewayen ba gjentatte ganger om ny tilkobling: {$message}
+error-hello-rejected = Gatewayen avviste klientens hello-melding: {$message}
+error-roaming-timeout = Kunne ikke gjenopprette tunnelen innen {$seconds} sekunder etter nettverksendringen
+error-kill-switch-unavailable = Kill switch krever verktøyet nft: {$message}
+error-forwarding-unavailable = LAN-videresending krever verktøyet nft: {$message}
+error-mss-clamp-unavailable = MSS-begrensning krever verktøyet nft: {$message}
+error-app-routing-unavailable = Applikasjonsruting krever verktøyet nft: {$message}
+error-dns-redirect-unavailable = DNS-omdirigering krever verktøyet nft: {$message}
+error-forward-source-conflict = Videresendingskilden {$source} overlapper tunnelens adresse eller ruter
+error-setup-missing-option = Valget { $option } er påkrevd i ikke-interaktiv modus
+error-setup-config-exists = { $path } finnes allerede, bruk --force for å overskrive den
+error-setup-invalid-choice = Ugyldig valg: { $value }
+error-setup-unreachable = Kan ikke nå { $server }: { $error }
+error-setup-certificate-rejected = Gatewayens sertifikat er ikke klarert, legg til CA-en med valget ca-cert eller godta det med --ignore-server-cert true
+error-import-read = Kan ikke lese { $path }: { $error }
+error-import-empty = { $path } inneholder ingen innstillinger som kan importeres
+error-import-not-toml = Innstillingene importeres til en TOML-konfigurasjon, { $path } har ikke filtypen .toml
+error-import-profile-exists = Profilen { $profile } finnes allerede i { $path }
 
 # Placeholder texts
 placeholder-domains = Domener separert med komma
@@ -134,18 +221,42 @@ icon-theme-light = Lys
 
 # Connection info
 info-connected-since = Tilkoblet siden
+info-profile = Profil
 info-server-name = Servernavn
 info-user-name = Brukernavn
 info-login-type = Innloggingstype
 info-tunnel-type = Tunneltype
 info-transport-type = Transporttype
+info-protocol-version = Protokollversjon
 info-ip-address = IP-adresse
+info-ipv6-address = IPv6-adresse
 info-dns-servers = DNS-servere
 info-search-domains = Søkedomener
+info-wins-servers = WINS-servere
 info-interface = Grensesnitt
+info-interface-mode = Grensesnittmodus
+info-interface-mode-p2p = punkt-til-punkt, motpart { $peer }
+info-interface-mode-local = kun lokal adresse
 info-dns-configured = DNS konfigurert
+info-dns-mode = DNS-modus
+info-dns-override = DNS-prioritet
 info-routing-configured = Ruting konfigurert
 info-default-route = Standardrute
+info-reauth-interval = Reautentiseringsintervall
+info-forwarding = LAN-videresending
+info-dns-check = DNS-lekkasjetest
+info-dns-check-passed = bestått
+info-dns-check-failed = feilet
+info-routes = Ruter
+info-received = Mottatt
+info-sent = Sendt
+info-traffic-amount = {$bytes}, {$packets} pakker
+info-stats-since = Statistikk siden
+info-connected-time = Tilkoblet tid
+info-reconnects = Gjentilkoblinger
+info-reauths = Reautentiseringer
+info-rekeys = Nøkkelfornyelser
+info-last-error = Siste feil
 
 # Application
 app-title = SNX-RS VPN-klient for Linux
@@ -175,8 +286,57 @@ tray-menu-exit = Avslutt
 cli-identity-provider-auth = For autentisering via identitetsleverandør, åpne følgende URL i nettleseren:
 cli-tunnel-connected = Tunnel tilkoblet, trykk Ctrl+C for å avslutte.
 cli-tunnel-disconnected = Tunnel frakoblet
+notify-connected = VPN tilkoblet ({$address})
+notify-reconnecting = VPN kobler til på nytt (forsøk {$attempt})
+notify-disconnected = VPN frakoblet
+notify-disconnected-reason = VPN frakoblet: {$reason}
+notify-reauth-failed = Ny autentisering av VPN mislyktes: {$error}
+cli-waiting-for-connect = Venter på kommandoen for å koble til
+cli-config-reloaded = Konfigurasjonen er lastet inn på nytt
+cli-reload-on-reconnect = Disse alternativene brukes ved neste gjentilkobling: { $options }
+cli-reload-on-restart = Disse alternativene brukes etter en omstart av snx-rs: { $options }
+cli-reload-failed = Kan ikke laste inn konfigurasjonen på nytt, beholder den gjeldende: { $error }
+cli-daemon-terminated = Avslutningssignalet ble sendt til prosess {$pid}
+cli-tunnel-stopping = Venter på at tunnelen i prosess {$pid} avslutter
+cli-tunnel-killed = Dreper prosess {$pid} og gjenoppretter nettverksinnstillingene den etterlot
+cli-network-restored = Nettverksinnstillinger gjenopprettet
 cli-another-instance-running = En annen forekomst av snx-rs kjører allerede
+cli-replacing-instance = Stopper den kjørende tunnelen i prosessen {$pid}
 cli-app-terminated = Applikasjon avsluttet av signal
+cli-session-expiring = VPN-økten utløper om {$minutes} min, tunnelen vil bli koblet fra
+cli-dns-check-failed = Advarsel: DNS-lekkasjetesten feilet, DNS-forespørsler går kanskje ikke gjennom tunnelen
+cli-trusted-network-lost = Klarert nettverk ikke lenger oppdaget, gjenopptar VPN-tilkoblingen
+cli-gateway-failover = Gatewayen {$failed} kan ikke nås, prøver {$server}
+cli-gateway-failback = Gatewayen {$server} kan nås igjen, bytter tilbake til den
+cli-service-installed = Tjenesten {$name} er installert, start den med: sc.exe start {$name}
+cli-service-uninstalled = Tjenesten {$name} er avinstallert
+cli-profile-switch = Nettverket er endret, bytter til profilen {$profile}
+cli-dry-run-auth-failed = Prøvekjøring: autentisering mislyktes: { $error }
+cli-dry-run-negotiation-failed = Prøvekjøring: forhandling av tunnelen mislyktes: { $error }
+cli-dry-run-complete = Prøvekjøring fullført, nettverkskonfigurasjonen ble ikke endret.
+setup-server-prompt = Gatewayadresse
+setup-probing = Kontrollerer gatewayen { $server }...
+setup-certificate = Sertifikat: { $subject }, utstedt av { $issuer }, gyldig til { $expires }
+setup-fingerprint = SHA-256-fingeravtrykk: { $fingerprint }
+setup-certificate-untrusted = Sertifikatet er ikke klarert: { $error }
+setup-ignore-cert-prompt = Godta dette sertifikatet likevel
+setup-login-options = Innloggingstyper som gatewayen tilbyr:
+setup-login-type-prompt = Innloggingstype, nummer eller id [{ $default }]
+setup-user-prompt = Brukernavn
+setup-password-prompt = Passord
+setup-store-password-prompt = Lagre passordet i nøkkelringen
+setup-default-route-prompt = Send all trafikk gjennom tunnelen (full tunnel)
+setup-dns-mode-prompt = DNS-modus, split eller full [split]
+setup-overwrite-prompt = { $path } finnes allerede, overskrive den
+setup-cancelled = Oppsettet er avbrutt, den eksisterende konfigurasjonen er uendret
+setup-config-written = Konfigurasjonen er skrevet til { $path }
+setup-test-connect-prompt = Test innloggingen nå
+setup-test-connect-ok = Innloggingen lyktes
+import-config-written = Importerte { $count } innstillinger fra { $source } til { $path }
+import-unmapped = Ikke importert, linje { $line }: { $key }
+import-login-type-missing = Ingen påloggingstype ble importert, "snx-rs info -s { $server }" viser gatewayens påloggingstyper for innstillingen login-type
+prompt-missing-parameters = Konfigurasjonen mangler påkrevde parametere: { $options }
+prompt-save-answers = Lagre svarene i { $path }
 
 # Connection Messages
 connection-connected-to = Koblet til {$server}
@@ -204,6 +364,7 @@ connection-status-disconnected = Koplet fra
 connection-status-connecting = Kobler til
 connection-status-connected-since = Koblet til siden: {$since}
 connection-status-mfa-pending = Venter på MFA: {$mfa_type}
+connection-status-trusted-network = Klarert nettverk oppdaget, VPN satt på pause
 
 # Login options
 login-options-server-address = Serveradresse
@@ -214,3 +375,11 @@ login-options-preferred-protocol = Foretrukket protokoll
 login-options-tcpt-port = TCPT-port
 login-options-natt-port = NATT-port
 login-options-internal-ca-fingerprint = Intern CA-fingeravtrykk
+login-options-protocol-version = Protokollversjon
+login-options-ipsec-transport = IPSec-transport
+login-options-saml-enabled = SAML aktivert
+login-options-certificate-subject = Sertifikatets emne
+login-options-certificate-issuer = Sertifikatets utsteder
+login-options-certificate-expires = Sertifikatet utløper
+login-options-certificate-fingerprint = Sertifikatets SHA-256-fingeravtrykk
+login-options-certificate-error = Sertifikatet er ikke klarert
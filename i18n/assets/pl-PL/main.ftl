@@ -53,6 +53,40 @@ expand-ui = Interfejs użytkownika
 error-no-server-name = Nie podano adresu serwera
 error-no-auth = Nie wybrano metody uwierzytelniania
 error-file-not-exist = Plik nie istnieje: {$path}
+error-invalid-config-file = Nieprawidłowy plik konfiguracyjny {$path}: {$message}
+error-profile-not-found = Nie znaleziono profilu {$profile} w konfiguracji
+error-in-profile = Profil {$profile}: {$message}
+error-invalid-env-var = Nieprawidłowa wartość zmiennej środowiskowej {$name}: {$message}
+error-invalid-configuration = Nieprawidłowa konfiguracja:
+error-invalid-server-name = Nieprawidłowy adres serwera: {$server}
+error-route-host-bits = {$option}: {$route} ma ustawione bity hosta, użyj {$network}
+error-options-conflict = {$first} nie może być łączone z {$second}
+error-insecure-permissions = {$path} jest zapisywalny przez innych użytkowników
+error-secret-file-permissions = {$path} zawiera sekrety, ale inni użytkownicy mają do niego dostęp (tryb {$mode}), ogranicz go poleceniem chmod 600 lub użyj --insecure-permissions
+error-secret-file-symlink = {$path} zawiera sekrety, ale wskazuje na {$target} w innym katalogu, użyj samego pliku lub --insecure-permissions
+error-script-not-executable = Skrypt nie jest wykonywalny: {$path}
+error-value-out-of-range = {$option} musi być pomiędzy {$min} a {$max}, otrzymano {$value}
+error-timeout-exceeds-total = {$option} wynoszący {$value} sekund jest dłuższy niż {$total} wynoszący {$limit} sekund, żadna próba nie zdąży się zakończyć
+error-reserved-routing-table = Tablica routingu {$table} jest zarezerwowana przez system
+error-control-socket-in-use = Gniazdo sterujące {$path} jest używane przez inny proces
+error-invalid-control-command = Nieprawidłowe polecenie sterujące: {$message}
+error-invalid-log-level = Nieprawidłowy poziom logowania: {$level}
+error-tunnel-not-running = Tunel nie jest uruchomiony
+error-invalid-dbus-service = Nieprawidłowa magistrala usługi D-Bus
+error-invalid-notify-event = Nieprawidłowe zdarzenie powiadomienia
+error-invalid-log-format = Nieprawidłowy format dziennika
+error-invalid-log-target = Nieprawidłowy cel dziennika
+error-invalid-log-filter = Nieprawidłowy filtr dziennika {$filter}: {$error}
+error-profile-not-served = Tunel działa dla profilu {$current}, profil {$profile} wymaga innej instancji
+error-profile-running = Tunel dla profilu {$profile} już działa jako proces {$pid}, użyj --replace, aby go zatrzymać
+error-instance-not-stopped = Tunel profilu {$profile} w procesie {$pid} nie zatrzymał się
+error-tunnel-not-stopped = Tunel procesu {$pid} nie zakończył się w ciągu {$timeout} sekund, użyj --force, aby go zabić i przywrócić ustawienia sieci
+error-dbus-not-authorized = Akcja {$action} nie jest dozwolona
+error-pid-file-locked = Plik PID {$path} jest zablokowany przez inną działającą instancję
+error-unknown-run-as = Nieznany użytkownik lub grupa do działania bez uprawnień: {$name}
+error-run-as-not-root = Opcja run-as-user wymaga uruchomienia tunelu jako root
+error-run-as-dns = Przywracanie resolv.conf i narzędzie resolvconf wymagają roota, z run-as-user użyj dns-backend=resolved lub no-dns
+error-privilege-drop = Nie można przełączyć na użytkownika bez uprawnień: {$error}
 error-invalid-cert-id = Identyfikator certyfikatu nie w formacie szesnastkowym: {$id}
 error-ca-root-not-exist = Ścieżka główna CA nie istnieje: {$path}
 error-validation = Błąd walidacji
@@ -60,13 +94,15 @@ error-user-input-canceled = Wprowadzanie danych przez użytkownika anulowane
 error-connection-cancelled = Połączenie anulowane
 error-unknown-event = Nieznane zdarzenie: {$event}
 error-no-service-connection = Brak połączenia z usługą
+error-session-not-owned = Sesja należy do użytkownika o ID {$uid}, tylko ten użytkownik lub root może nią sterować
+error-option-not-permitted = Usługa nie zezwala temu użytkownikowi na opcję {$option}
 error-empty-input = Dane wejściowe nie mogą być puste
 error-invalid-object = Nieprawidłowy obiekt
 error-no-connector = Brak łącznika tunelu
 error-tunnel-disconnected = Tunel rozłączony, ostatnia wiadomość: {$message}
 error-unexpected-reply = Nieoczekiwana odpowiedź
 error-auth-failed = Uwierzytelnianie nie powiodło się
-error-no-login-type = Brak wymaganego parametru: login-type
+error-missing-parameter = Brak wymaganego parametru: { $option }
 error-connection-timeout = Przekroczenie czasu połączenia
 error-invalid-response = Nieprawidłowa odpowiedź
 error-cannot-send-request = Nie można wysłać żądania do usługi
@@ -80,7 +116,13 @@ error-no-pkcs8 = Nie podano ścieżki PKCS8 PEM
 error-no-pkcs11 = Nie podano kodu PIN PKCS11
 error-no-ipsec-session = Brak sesji IPSEC
 error-request-failed-error-code = Żądanie nie powiodło się, kod błędu: {$error_code}
+error-gateway-info-refused = Brama {$server} nie odpowiada na nieuwierzytelnione zapytania, kod błędu: {$error_code}
 error-no-root-privileges = Ten program musi być uruchomiony jako użytkownik root!
+error-missing-privileges = Wybrane opcje wymagają uprawnień, których ten proces nie ma (sprawdzanie można pominąć za pomocą --skip-permission-check):
+privilege-net-admin = CAP_NET_ADMIN jest potrzebne do utworzenia urządzenia tun i konfiguracji adresów, tras i DNS: uruchom jako root, nadaj uprawnienie poleceniem sudo setcap cap_net_admin+ep { $exe }, uruchom z jednostki systemd z AmbientCapabilities=CAP_NET_ADMIN lub przekaż wcześniej utworzone urządzenie przez tun-fd razem z no-device-config, no-routing i no-dns
+privilege-resolv-conf = Do konfiguracji DNS potrzebny jest dostęp do zapisu w { $path }: uruchom jako root, wybierz inny dns-backend lub ustaw no-dns=true
+privilege-resolvconf-tool = Narzędzia resolvconf może używać tylko root: wybierz inny dns-backend lub ustaw no-dns=true
+privilege-nft = Polecenie nft jest potrzebne dla kill-switch i forward-source: zainstaluj pakiet nftables lub wyłącz te opcje
 error-missing-required-parameters = Brak wymaganych parametrów: nazwa serwera i/lub typ dostępu!
 error-missing-server-name = Brak wymaganego parametru: nazwa serwera!
 error-no-connector-for-challenge-code = Brak łącznika do wysłania kodu wyzwania!
@@ -89,11 +131,13 @@ error-invalid-sexpr = Nieprawidłowy sexpr: {$value}
 error-invalid-value = Nieprawidłowa wartość
 error-udp-request-failed = Błąd podczas wysyłania żądania UDP
 error-no-tty = Brak podłączonego TTY do wprowadzania danych przez użytkownika
+error-service-mfa = Usługa nie może odpowiadać na wyzwania MFA ani otworzyć przeglądarki dostawcy tożsamości, użyj zapisanego hasła z opcją password lub uwierzytelniania certyfikatem z opcją cert-type
 error-invalid-auth-response = Nieprawidłowa odpowiedź uwierzytelniania
 error-invalid-client-settings = Nieprawidłowe ustawienia klienta
 error-invalid-otp-reply = Nieprawidłowa odpowiedź OTP
 error-udp-encap-failed = Nie można ustawić opcji gniazda UDP_ENCAP, kod błędu: {$code}
 error-so-no-check-failed = Nie można ustawić opcji gniazda SO_NO_CHECK, kod błędu: {$code}
+error-so-mark-failed = Nie można ustawić opcji gniazda SO_MARK, kod błędu: {$code}
 error-keepalive-failed = Keepalive nie powiódł się
 error-receive-failed = Odbieranie nie powiodło się
 error-unknown-color-scheme = Nieznana wartość schematu kolorów
@@ -104,12 +148,55 @@ error-invalid-operation-mode = Nieprawidłowy tryb działania
 error-invalid-tunnel-type = Nieprawidłowy typ tunelu
 error-invalid-cert-type = Nieprawidłowy typ certyfikatu
 error-invalid-icon-theme = Nieprawidłowy motyw ikon
+error-invalid-dns-backend = Nieprawidłowy backend DNS
+error-invalid-dns-mode = Nieprawidłowy tryb DNS
+error-invalid-dns-override = Nieprawidłowy priorytet DNS
+error-invalid-address-conflict-action = Nieprawidłowa akcja konfliktu adresów
+error-resolv-conf-symlink = {$path} jest dowiązaniem symbolicznym, tryb bezpośredni DNS go nie zmieni; wybierz inny dns-backend
+error-resolv-conf-read-only = {$path} jest tylko do odczytu lub zamontowany przez środowisko kontenera: ustaw dns-resolv-path, aby zapisać w innym miejscu, lub dns-backend=print, aby zastosować go ze skryptu
+error-invalid-device-name = Nieprawidłowa nazwa interfejsu: {$name}
+error-invalid-netns-name = Nieprawidłowa nazwa sieciowej przestrzeni nazw: {$name}
+error-device-name-in-use = Interfejs {$name} już istnieje
+error-invalid-tun-fd = Deskryptor pliku musi należeć do urządzenia tun otwartego z IFF_TUN i IFF_NO_PI: {$message}
+error-unknown-tun-owner = Nieznany użytkownik lub grupa {$name}
+error-tun-ioctl = Nie można zastosować {$request} do {$name}: {$message}
+error-tun-ioctl-permission = Brak uprawnień do zastosowania {$request} do {$name}, urządzenie musi przygotować root lub proces z CAP_NET_ADMIN
+error-invalid-network-manager-mode = Nieprawidłowy tryb NetworkManagera
+error-invalid-ipv6-block-mode = Nieprawidłowy tryb blokowania IPv6
+error-invalid-mss-clamp-mode = Nieprawidłowy tryb ograniczania MSS
+error-no-free-device-name = Brak wolnej nazwy interfejsu dla szablonu {$name}
+error-invalid-trusted-network = Nieprawidłowe kryterium zaufanej sieci: {$value}
+error-invalid-network-match = Nieprawidłowe kryterium dopasowania sieci: {$value}
+error-invalid-health-listen = Nieprawidłowy adres punktów kontroli stanu: {$value}
+error-invalid-proxy = Nieprawidłowy serwer proxy, oczekiwano none, auto lub http://[user:password@]host[:port]: {$value}
+error-proxy-connect-failed = Serwer proxy {$proxy} odrzucił połączenie z {$destination}: {$status}
+error-invalid-route = Nieprawidłowa trasa: {$value}
 error-no-natt-reply = Brak odpowiedzi NATT
 error-not-implemented = Nie zaimplementowano
 error-unknown-packet-type = Nieznany typ pakietu
 error-no-sender = Brak nadawcy
 error-empty-ccc-session = Pusta sesja CCC
 error-identity-timeout = Przekroczenie czasu oczekiwania na odpowiedź tożsamości, czy typ dostępu jest poprawny?
+error-no-office-mode-address = Brama nie przydzieliła użytecznego adresu: {$address}
+error-gateway-error = Błąd bramy, kod: {$code}
+error-gateway-reconnect = Brama wielokrotnie zażądała ponownego połączenia: {$message}
+error-hello-rejected = Brama odrzuciła komunikat hello klienta: {$message}
+error-roaming-timeout = Nie udało się przywrócić tunelu w ciągu {$seconds} sekund po zmianie sieci
+error-kill-switch-unavailable = Kill switch wymaga narzędzia nft: {$message}
+error-forwarding-unavailable = Przekazywanie LAN wymaga narzędzia nft: {$message}
+error-mss-clamp-unavailable = Ograniczanie MSS wymaga narzędzia nft: {$message}
+error-app-routing-unavailable = Routing aplikacji wymaga narzędzia nft: {$message}
+error-dns-redirect-unavailable = Przekierowanie DNS wymaga narzędzia nft: {$message}
+error-forward-source-conflict = Źródło przekazywania {$source} pokrywa się z adresem lub trasami tunelu
+error-setup-missing-option = Opcja { $option } jest wymagana w trybie nieinteraktywnym
+error-setup-config-exists = { $path } już istnieje, użyj --force, aby go nadpisać
+error-setup-invalid-choice = Nieprawidłowy wybór: { $value }
+error-setup-unreachable = Nie można połączyć się z { $server }: { $error }
+error-setup-certificate-rejected = Certyfikat bramy nie jest zaufany, dodaj jego CA opcją ca-cert lub zaakceptuj go za pomocą --ignore-server-cert true
+error-import-read = Nie można odczytać { $path }: { $error }
+error-import-empty = { $path } nie zawiera ustawień, które można zaimportować
+error-import-not-toml = Ustawienia są importowane do konfiguracji TOML, { $path } nie ma rozszerzenia .toml
+error-import-profile-exists = Profil { $profile } już istnieje w { $path }
 
 # Placeholder texts
 placeholder-domains = Domeny oddzielone przecinkami
@@ -134,18 +221,42 @@ icon-theme-light = Jasny
 
 # Connection info
 info-connected-since = Połączono od
+info-profile = Profil
 info-server-name = Nazwa serwera
 info-user-name = Nazwa użytkownika
 info-login-type = Typ logowania
 info-tunnel-type = Typ tunelu
 info-transport-type = Typ transportu
+info-protocol-version = Wersja protokołu
 info-ip-address = Adres IP
+info-ipv6-address = Adres IPv6
 info-dns-servers = Serwery DNS
 info-search-domains = Domeny wyszukiwania
+info-wins-servers = Serwery WINS
 info-interface = Interfejs
+info-interface-mode = Tryb interfejsu
+info-interface-mode-p2p = punkt-punkt, partner { $peer }
+info-interface-mode-local = tylko adres lokalny
 info-dns-configured = DNS skonfigurowany
+info-dns-mode = Tryb DNS
+info-dns-override = Priorytet DNS
 info-routing-configured = Routing skonfigurowany
 info-default-route = Trasa domyślna
+info-reauth-interval = Interwał ponownego uwierzytelnienia
+info-forwarding = Przekazywanie LAN
+info-dns-check = Test wycieku DNS
+info-dns-check-passed = zaliczony
+info-dns-check-failed = niezaliczony
+info-routes = Trasy
+info-received = Odebrano
+info-sent = Wysłano
+info-traffic-amount = {$bytes}, pakietów: {$packets}
+info-stats-since = Statystyki od
+info-connected-time = Czas połączenia
+info-reconnects = Ponowne połączenia
+info-reauths = Ponowne uwierzytelnienia
+info-rekeys = Wymiany kluczy
+info-last-error = Ostatni błąd
 
 # Application
 app-title = Klient VPN SNX-RS dla Linux
@@ -175,8 +286,57 @@ tray-menu-exit = Zakończ
 cli-identity-provider-auth = Aby uwierzytelnić się przez dostawcę tożsamości, otwórz następujący adres URL w przeglądarce:
 cli-tunnel-connected = Tunel połączony, naciśnij Ctrl+C aby zakończyć.
 cli-tunnel-disconnected = Tunel rozłączony
+notify-connected = VPN połączony ({$address})
+notify-reconnecting = VPN łączy się ponownie (próba {$attempt})
+notify-disconnected = VPN rozłączony
+notify-disconnected-reason = VPN rozłączony: {$reason}
+notify-reauth-failed = Ponowne uwierzytelnienie VPN nie powiodło się: {$error}
+cli-waiting-for-connect = Oczekiwanie na polecenie połączenia
+cli-config-reloaded = Konfiguracja została ponownie wczytana
+cli-reload-on-reconnect = Te opcje zostaną zastosowane przy następnym ponownym połączeniu: { $options }
+cli-reload-on-restart = Te opcje zostaną zastosowane po ponownym uruchomieniu snx-rs: { $options }
+cli-reload-failed = Nie można ponownie wczytać konfiguracji, zachowano bieżącą: { $error }
+cli-daemon-terminated = Wysłano sygnał zakończenia do procesu {$pid}
+cli-tunnel-stopping = Oczekiwanie na zakończenie tunelu procesu {$pid}
+cli-tunnel-killed = Zabijanie procesu {$pid} i przywracanie pozostawionych ustawień sieci
+cli-network-restored = Przywrócono ustawienia sieci
 cli-another-instance-running = Inna instancja snx-rs jest już uruchomiona
+cli-replacing-instance = Zatrzymywanie działającego tunelu procesu {$pid}
 cli-app-terminated = Aplikacja zakończona przez sygnał
+cli-session-expiring = Sesja VPN wygaśnie za {$minutes} min, tunel zostanie rozłączony
+cli-dns-check-failed = Ostrzeżenie: test wycieku DNS nie powiódł się, zapytania DNS mogą nie przechodzić przez tunel
+cli-trusted-network-lost = Zaufana sieć nie jest już wykrywana, wznawianie połączenia VPN
+cli-gateway-failover = Brama {$failed} jest nieosiągalna, próba połączenia z {$server}
+cli-gateway-failback = Brama {$server} jest ponownie osiągalna, powrót do niej
+cli-service-installed = Usługa {$name} została zainstalowana, uruchom ją poleceniem: sc.exe start {$name}
+cli-service-uninstalled = Usługa {$name} została odinstalowana
+cli-profile-switch = Sieć się zmieniła, przełączanie na profil {$profile}
+cli-dry-run-auth-failed = Próbne uruchomienie: uwierzytelnienie nie powiodło się: { $error }
+cli-dry-run-negotiation-failed = Próbne uruchomienie: negocjacja tunelu nie powiodła się: { $error }
+cli-dry-run-complete = Próbne uruchomienie zakończone, konfiguracja sieci nie została zmieniona.
+setup-server-prompt = Adres bramy
+setup-probing = Sprawdzanie bramy { $server }...
+setup-certificate = Certyfikat: { $subject }, wystawiony przez { $issuer }, ważny do { $expires }
+setup-fingerprint = Odcisk SHA-256: { $fingerprint }
+setup-certificate-untrusted = Certyfikat nie jest zaufany: { $error }
+setup-ignore-cert-prompt = Mimo to zaakceptować ten certyfikat
+setup-login-options = Typy logowania oferowane przez bramę:
+setup-login-type-prompt = Typ logowania, numer lub id [{ $default }]
+setup-user-prompt = Nazwa użytkownika
+setup-password-prompt = Hasło
+setup-store-password-prompt = Zapisać hasło w pęku kluczy
+setup-default-route-prompt = Kierować cały ruch przez tunel (pełny tunel)
+setup-dns-mode-prompt = Tryb DNS, split lub full [split]
+setup-overwrite-prompt = { $path } już istnieje, nadpisać
+setup-cancelled = Konfiguracja anulowana, istniejąca konfiguracja pozostaje bez zmian
+setup-config-written = Konfiguracja zapisana w { $path }
+setup-test-connect-prompt = Przetestować logowanie teraz
+setup-test-connect-ok = Logowanie powiodło się
+import-config-written = Zaimportowano ustawienia ({ $count }) z { $source } do { $path }
+import-unmapped = Nie zaimportowano, wiersz { $line }: { $key }
+import-login-type-missing = Nie zaimportowano typu logowania, "snx-rs info -s { $server }" wyświetla typy logowania bramy dla opcji login-type
+prompt-missing-parameters = W konfiguracji brakuje wymaganych parametrów: { $options }
+prompt-save-answers = Zapisać odpowiedzi w { $path }
 
 # Connection Messages
 connection-connected-to = Połączono z {$server}
@@ -204,6 +364,7 @@ connection-status-disconnected = Rozłączono
 connection-status-connecting = Łączenie
 connection-status-connected-since = Połączono od: {$since}
 connection-status-mfa-pending = Oczekiwanie na MFA: {$mfa_type}
+connection-status-trusted-network = Wykryto zaufaną sieć, VPN wstrzymany
 
 # Login options
 login-options-server-address = Adres serwera
@@ -214,3 +375,11 @@ login-options-preferred-protocol = Preferowany protokół
 login-options-tcpt-port = Port TCPT
 login-options-natt-port = Port NATT
 login-options-internal-ca-fingerprint = Odcisk wewnętrznego CA
+login-options-protocol-version = Wersja protokołu
+login-options-ipsec-transport = Transport IPSec
+login-options-saml-enabled = SAML włączony
+login-options-certificate-subject = Podmiot certyfikatu
+login-options-certificate-issuer = Wystawca certyfikatu
+login-options-certificate-expires = Wygaśnięcie certyfikatu
+login-options-certificate-fingerprint = Odcisk SHA-256 certyfikatu
+login-options-certificate-error = Certyfikat niezaufany
@@ -53,6 +53,40 @@ expand-ui = Interface do usuário
 error-no-server-name = Nenhum endereço de servidor especificado
 error-no-auth = Nenhum método de autenticação selecionado
 error-file-not-exist = O arquivo não existe: {$path}
+error-invalid-config-file = Arquivo de configuração inválido {$path}: {$message}
+error-profile-not-found = Perfil {$profile} não encontrado na configuração
+error-in-profile = Perfil {$profile}: {$message}
+error-invalid-env-var = Valor inválido da variável de ambiente {$name}: {$message}
+error-invalid-configuration = Configuração inválida:
+error-invalid-server-name = Endereço do servidor inválido: {$server}
+error-route-host-bits = {$option}: {$route} tem bits de host definidos, use {$network}
+error-options-conflict = {$first} não pode ser combinado com {$second}
+error-insecure-permissions = {$path} pode ser alterado por outros usuários
+error-secret-file-permissions = {$path} contém segredos, mas outros usuários podem acessá-lo (modo {$mode}), restrinja-o com chmod 600 ou use --insecure-permissions
+error-secret-file-symlink = {$path} contém segredos, mas aponta para {$target} em outro diretório, use o próprio arquivo ou --insecure-permissions
+error-script-not-executable = O script não é executável: {$path}
+error-value-out-of-range = {$option} deve estar entre {$min} e {$max}, recebido {$value}
+error-timeout-exceeds-total = {$option} de {$value} segundos é maior que {$total} de {$limit} segundos, nenhuma tentativa poderia terminar a tempo
+error-reserved-routing-table = A tabela de roteamento {$table} é reservada pelo sistema
+error-control-socket-in-use = O socket de controle {$path} está em uso por outro processo
+error-invalid-control-command = Comando de controle inválido: {$message}
+error-invalid-log-level = Nível de log inválido: {$level}
+error-tunnel-not-running = O túnel não está em execução
+error-invalid-dbus-service = Barramento do serviço D-Bus inválido
+error-invalid-notify-event = Evento de notificação inválido
+error-invalid-log-format = Formato de log inválido
+error-invalid-log-target = Destino de log inválido
+error-invalid-log-filter = Filtro de log inválido {$filter}: {$error}
+error-profile-not-served = O túnel está em execução para o perfil {$current}, o perfil {$profile} precisa de outra instância
+error-profile-running = Um túnel do perfil {$profile} já está em execução como o processo {$pid}, use --replace para pará-lo
+error-instance-not-stopped = O túnel do perfil {$profile} no processo {$pid} não parou
+error-tunnel-not-stopped = O túnel do processo {$pid} não terminou em {$timeout} segundos, use --force para encerrá-lo e restaurar as configurações de rede
+error-dbus-not-authorized = A ação {$action} não está autorizada
+error-pid-file-locked = O arquivo PID {$path} está bloqueado por outra instância em execução
+error-unknown-run-as = Usuário ou grupo desconhecido para a execução sem privilégios: {$name}
+error-run-as-not-root = A opção run-as-user exige que o túnel seja iniciado como root
+error-run-as-dns = Restaurar o resolv.conf e a ferramenta resolvconf exigem root, use dns-backend=resolved ou no-dns com run-as-user
+error-privilege-drop = Não foi possível mudar para o usuário sem privilégios: {$error}
 error-invalid-cert-id = ID do certificado não está em formato hexadecimal: {$id}
 error-ca-root-not-exist = O caminho do certificado raiz não existe: {$path}
 error-validation = Erro de validação
@@ -60,13 +94,15 @@ error-user-input-canceled = Entrada de usuário cancelada
 error-connection-cancelled = Conexão cancelada
 error-unknown-event = Evento desconhecido: {$event}
 error-no-service-connection = Sem conexão ao serviço
+error-session-not-owned = A sessão pertence ao usuário com ID {$uid}, somente esse usuário ou o root podem controlá-la
+error-option-not-permitted = O serviço não permite a opção {$option} para este usuário
 error-empty-input = O campo não pode estar vazio
 error-invalid-object = Objeto inválido
 error-no-connector = Sem conector de túnel
 error-tunnel-disconnected = Túnel desconectado, última mensagem: {$message}
 error-unexpected-reply = Resposta inesperada
 error-auth-failed = A autenticação falhou
-error-no-login-type = Falta um parâmetro obrigatório: login-type
+error-missing-parameter = Falta um parâmetro obrigatório: { $option }
 error-connection-timeout = Tempo limite da conexão
 error-invalid-response = Resposta inválida!
 error-cannot-send-request = Impossível enviar pedido ao serviço
@@ -80,7 +116,13 @@ error-no-pkcs8 = Não foi informado um caminho para o certificado PEM PKCS8
 error-no-pkcs11 = Não foi informado um PIN PKCS11
 error-no-ipsec-session = Sem sessão IPSec
 error-request-failed-error-code = Falha na requisição, código de erro: {$error_code}
+error-gateway-info-refused = O gateway {$server} não responde a consultas não autenticadas, código de erro: {$error_code}
 error-no-root-privileges = Este programa deve ser executado como usuário root!
+error-missing-privileges = As opções selecionadas exigem privilégios que este processo não possui (a verificação pode ser ignorada com --skip-permission-check):
+privilege-net-admin = CAP_NET_ADMIN é necessário para criar o dispositivo tun e configurar endereços, rotas e DNS: execute como root, conceda a capacidade com sudo setcap cap_net_admin+ep { $exe }, inicie a partir de uma unidade systemd com AmbientCapabilities=CAP_NET_ADMIN, ou passe um dispositivo pré-criado com tun-fd junto com no-device-config, no-routing e no-dns
+privilege-resolv-conf = É necessário acesso de escrita a { $path } para configurar o DNS: execute como root, selecione outro dns-backend ou defina no-dns=true
+privilege-resolvconf-tool = A ferramenta resolvconf só pode ser usada pelo root: selecione outro dns-backend ou defina no-dns=true
+privilege-nft = O comando nft é necessário para kill-switch e forward-source: instale o pacote nftables ou desative essas opções
 error-missing-required-parameters = Parâmetros obrigatórios ausentes: nome do servidor e/ou tipo de acesso!
 error-missing-server-name = Parâmetro obrigatório ausente: nome do servidor!
 error-no-connector-for-challenge-code = Sem conector para enviar o código de desafio!
@@ -89,11 +131,13 @@ error-invalid-sexpr = sexpr inválido: {$value}
 error-invalid-value = Valor inválido
 error-udp-request-failed = Erro ao enviar requisição UDP
 error-no-tty = Sem TTY conectado para entrada do usuário
+error-service-mfa = O serviço não pode responder a desafios MFA nem abrir o navegador de um provedor de identidade, use uma senha armazenada com a opção password ou a autenticação por certificado com cert-type
 error-invalid-auth-response = Resposta de autenticação inválida
 error-invalid-client-settings = Configurações do cliente inválidas
 error-invalid-otp-reply = Resposta OTP inválida
 error-udp-encap-failed = Não foi possível definir a opção de socket UDP_ENCAP, código de erro: {$code}
 error-so-no-check-failed = Não foi possível definir a opção de socket SO_NO_CHECK, código de erro: {$code}
+error-so-mark-failed = Não foi possível definir a opção de socket SO_MARK, código de erro: {$code}
 error-keepalive-failed = Keepalive falhou
 error-receive-failed = Recebimento falhou
 error-unknown-color-scheme = Valor de esquema de cores desconhecido
@@ -104,12 +148,55 @@ error-invalid-operation-mode = Modo de operação inválido
 error-invalid-tunnel-type = Tipo de túnel inválido
 error-invalid-cert-type = Tipo de certificado inválido
 error-invalid-icon-theme = Tema de ícones inválido
+error-invalid-dns-backend = Backend de DNS inválido
+error-invalid-dns-mode = Modo DNS inválido
+error-invalid-dns-override = Prioridade de DNS inválida
+error-invalid-address-conflict-action = Ação de conflito de endereços inválida
+error-resolv-conf-symlink = {$path} é um link simbólico, o modo DNS direto não irá modificá-lo; selecione outro dns-backend
+error-resolv-conf-read-only = {$path} é somente leitura ou montado pelo runtime do contêiner: defina dns-resolv-path para gravar em outro lugar, ou dns-backend=print para aplicá-lo a partir de um script
+error-invalid-device-name = Nome de interface inválido: {$name}
+error-invalid-netns-name = Nome de namespace de rede inválido: {$name}
+error-device-name-in-use = A interface {$name} já existe
+error-invalid-tun-fd = O descritor de arquivo deve pertencer a um dispositivo tun aberto com IFF_TUN e IFF_NO_PI: {$message}
+error-unknown-tun-owner = Usuário ou grupo desconhecido {$name}
+error-tun-ioctl = Não foi possível aplicar {$request} a {$name}: {$message}
+error-tun-ioctl-permission = Não é permitido aplicar {$request} a {$name}, o dispositivo deve ser preparado pelo root ou por um processo com CAP_NET_ADMIN
+error-invalid-network-manager-mode = Modo do NetworkManager inválido
+error-invalid-ipv6-block-mode = Modo de bloqueio de IPv6 inválido
+error-invalid-mss-clamp-mode = Modo de limitação de MSS inválido
+error-no-free-device-name = Nenhum nome de interface livre para o modelo {$name}
+error-invalid-trusted-network = Critério de rede confiável inválido: {$value}
+error-invalid-network-match = Critério de correspondência de rede inválido: {$value}
+error-invalid-health-listen = Endereço dos endpoints de saúde inválido: {$value}
+error-invalid-proxy = Proxy inválido, esperado none, auto ou http://[user:password@]host[:port]: {$value}
+error-proxy-connect-failed = O proxy {$proxy} recusou a conexão com {$destination}: {$status}
+error-invalid-route = Rota inválida: {$value}
 error-no-natt-reply = Sem resposta NATT
 error-not-implemented = Não implementado
 error-unknown-packet-type = Tipo de pacote desconhecido
 error-no-sender = Sem remetente
 error-empty-ccc-session = Sessão CCC vazia
 error-identity-timeout = Timeout ao aguardar resposta de identidade, o tipo de acesso está correto?
+error-no-office-mode-address = O gateway não atribuiu um endereço utilizável: {$address}
+error-gateway-error = Erro do gateway, código: {$code}
+error-gateway-reconnect = O gateway solicitou reconexão repetidamente: {$message}
+error-hello-rejected = O gateway rejeitou o hello do cliente: {$message}
+error-roaming-timeout = Não foi possível restabelecer o túnel em {$seconds} segundos após a mudança de rede
+error-kill-switch-unavailable = O kill switch requer o utilitário nft: {$message}
+error-forwarding-unavailable = O encaminhamento de LAN requer o utilitário nft: {$message}
+error-mss-clamp-unavailable = A limitação de MSS requer o utilitário nft: {$message}
+error-app-routing-unavailable = O roteamento por aplicativo requer o utilitário nft: {$message}
+error-dns-redirect-unavailable = O redirecionamento de DNS requer o utilitário nft: {$message}
+error-forward-source-conflict = A origem de encaminhamento {$source} se sobrepõe ao endereço ou às rotas do túnel
+error-setup-missing-option = A opção { $option } é obrigatória no modo não interativo
+error-setup-config-exists = { $path } já existe, use --force para sobrescrevê-lo
+error-setup-invalid-choice = Escolha inválida: { $value }
+error-setup-unreachable = Não foi possível alcançar { $server }: { $error }
+error-setup-certificate-rejected = O certificado do gateway não é confiável, adicione sua CA com a opção ca-cert ou aceite-o com --ignore-server-cert true
+error-import-read = Não foi possível ler { $path }: { $error }
+error-import-empty = { $path } não contém configurações que possam ser importadas
+error-import-not-toml = As configurações são importadas para uma configuração TOML, { $path } não tem a extensão .toml
+error-import-profile-exists = O perfil { $profile } já existe em { $path }
 
 # Placeholder texts
 placeholder-domains = Domínios separados por vírgulas
@@ -158,25 +245,98 @@ tray-menu-exit = Sair
 
 # Connection info
 info-connected-since = Conectado desde
+info-profile = Perfil
 info-server-name = Nome do servidor
 info-user-name = Usuário
 info-login-type = Tipo de login
 info-tunnel-type = Tipo de túnel
 info-transport-type = Tipo de transporte
+info-protocol-version = Versão do protocolo
 info-ip-address = Endereço IP
+info-ipv6-address = Endereço IPv6
 info-dns-servers = Servidores DNS
 info-search-domains = Domínios para busca
+info-wins-servers = Servidores WINS
 info-interface = Interface
+info-interface-mode = Modo da interface
+info-interface-mode-p2p = ponto a ponto, par { $peer }
+info-interface-mode-local = somente endereço local
 info-dns-configured = DNS configurado
+info-dns-mode = Modo DNS
+info-dns-override = Prioridade de DNS
 info-routing-configured = Roteamento configurado
 info-default-route = Rota padrão
+info-reauth-interval = Intervalo de reautenticação
+info-forwarding = Encaminhamento de LAN
+info-dns-check = Teste de vazamento de DNS
+info-dns-check-passed = aprovado
+info-dns-check-failed = falhou
+info-routes = Rotas
+info-received = Recebido
+info-sent = Enviado
+info-traffic-amount = {$bytes}, {$packets} pacotes
+info-stats-since = Estatísticas desde
+info-connected-time = Tempo conectado
+info-reconnects = Reconexões
+info-reauths = Reautenticações
+info-rekeys = Renovações de chaves
+info-last-error = Último erro
 
 # CLI Messages
 cli-identity-provider-auth = Para autenticação com o provedor de identidade, abra a seguinte URL no seu navegador:
 cli-tunnel-connected = Túnel conectado, pressione Control+c para sair.
 cli-tunnel-disconnected = Túnel desconectado
+notify-connected = VPN conectada ({$address})
+notify-reconnecting = VPN reconectando (tentativa {$attempt})
+notify-disconnected = VPN desconectada
+notify-disconnected-reason = VPN desconectada: {$reason}
+notify-reauth-failed = Falha na reautenticação da VPN: {$error}
+cli-waiting-for-connect = Aguardando o comando de conexão
+cli-config-reloaded = Configuração recarregada
+cli-reload-on-reconnect = Estas opções serão aplicadas na próxima reconexão: { $options }
+cli-reload-on-restart = Estas opções serão aplicadas após reiniciar o snx-rs: { $options }
+cli-reload-failed = Não foi possível recarregar a configuração, mantendo a atual: { $error }
+cli-daemon-terminated = O sinal de término foi enviado ao processo {$pid}
+cli-tunnel-stopping = Aguardando o término do túnel do processo {$pid}
+cli-tunnel-killed = Encerrando o processo {$pid} e restaurando as configurações de rede deixadas
+cli-network-restored = Configurações de rede restauradas
 cli-another-instance-running = Outra instância do SNX-RS está em execução
+cli-replacing-instance = Parando o túnel em execução do processo {$pid}
 cli-app-terminated = A aplicação terminou devido a um sinal
+cli-session-expiring = A sessão VPN expira em {$minutes} min, o túnel será desconectado
+cli-dns-check-failed = Aviso: o teste de vazamento de DNS falhou, as consultas DNS podem não passar pelo túnel
+cli-trusted-network-lost = Rede confiável não detectada, retomando a conexão VPN
+cli-gateway-failover = O gateway {$failed} está inacessível, tentando {$server}
+cli-gateway-failback = O gateway {$server} está acessível novamente, voltando para ele
+cli-service-installed = Serviço {$name} instalado, inicie-o com: sc.exe start {$name}
+cli-service-uninstalled = Serviço {$name} desinstalado
+cli-profile-switch = A rede mudou, mudando para o perfil {$profile}
+cli-dry-run-auth-failed = Execução de teste: falha na autenticação: { $error }
+cli-dry-run-negotiation-failed = Execução de teste: falha na negociação do túnel: { $error }
+cli-dry-run-complete = Execução de teste concluída, a configuração de rede não foi alterada.
+setup-server-prompt = Endereço do gateway
+setup-probing = Verificando o gateway { $server }...
+setup-certificate = Certificado: { $subject }, emitido por { $issuer }, válido até { $expires }
+setup-fingerprint = Impressão digital SHA-256: { $fingerprint }
+setup-certificate-untrusted = O certificado não é confiável: { $error }
+setup-ignore-cert-prompt = Aceitar este certificado mesmo assim
+setup-login-options = Tipos de login oferecidos pelo gateway:
+setup-login-type-prompt = Tipo de login, número ou id [{ $default }]
+setup-user-prompt = Nome de usuário
+setup-password-prompt = Senha
+setup-store-password-prompt = Salvar a senha no chaveiro
+setup-default-route-prompt = Rotear todo o tráfego pelo túnel (túnel completo)
+setup-dns-mode-prompt = Modo DNS, split ou full [split]
+setup-overwrite-prompt = { $path } já existe, sobrescrever
+setup-cancelled = Configuração cancelada, a configuração existente não foi alterada
+setup-config-written = Configuração gravada em { $path }
+setup-test-connect-prompt = Testar o login agora
+setup-test-connect-ok = Login bem-sucedido
+import-config-written = { $count } configurações importadas de { $source } para { $path }
+import-unmapped = Não importado, linha { $line }: { $key }
+import-login-type-missing = Nenhum tipo de login foi importado, "snx-rs info -s { $server }" lista os tipos de login do gateway para a opção login-type
+prompt-missing-parameters = Faltam parâmetros obrigatórios na configuração: { $options }
+prompt-save-answers = Salvar as respostas em { $path }
 
 # Connection Messages
 connection-connected-to = Conectado à {$server}
@@ -204,6 +364,7 @@ connection-status-disconnected = Desconectado
 connection-status-connecting = Conexão em progresso
 connection-status-connected-since = Conectado desde: {$since}
 connection-status-mfa-pending = Autenticação multifator pendente: {$mfa_type}
+connection-status-trusted-network = Rede confiável detectada, VPN suspensa
 
 # Login options
 login-options-server-address = Endereço do servidor
@@ -214,3 +375,11 @@ login-options-preferred-protocol = Protocolos preferidos
 login-options-tcpt-port = Porta TCPT
 login-options-natt-port = Porta NAT-T
 login-options-internal-ca-fingerprint = Impressão digital da CA interna
+login-options-protocol-version = Versão do protocolo
+login-options-ipsec-transport = Transporte IPSec
+login-options-saml-enabled = SAML habilitado
+login-options-certificate-subject = Titular do certificado
+login-options-certificate-issuer = Emissor do certificado
+login-options-certificate-expires = Validade do certificado
+login-options-certificate-fingerprint = Impressão digital SHA-256 do certificado
+login-options-certificate-error = Certificado não confiável
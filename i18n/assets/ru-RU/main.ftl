@@ -53,6 +53,40 @@ expand-ui = Настройки интерфейса
 error-no-server-name = Не указан адрес сервера
 error-no-auth = Не выбран метод аутентификации
 error-file-not-exist = Файл не существует: {$path}
+error-invalid-config-file = Недопустимый файл конфигурации {$path}: {$message}
+error-profile-not-found = Профиль {$profile} не найден в конфигурации
+error-in-profile = Профиль {$profile}: {$message}
+error-invalid-env-var = Недопустимое значение переменной окружения {$name}: {$message}
+error-invalid-configuration = Недопустимая конфигурация:
+error-invalid-server-name = Недопустимый адрес сервера: {$server}
+error-route-host-bits = {$option}: в {$route} установлены биты узла, используйте {$network}
+error-options-conflict = {$first} нельзя использовать вместе с {$second}
+error-insecure-permissions = {$path} доступен для записи другим пользователям
+error-secret-file-permissions = {$path} содержит секреты, но доступен другим пользователям (режим {$mode}), ограничьте доступ командой chmod 600 или используйте --insecure-permissions
+error-secret-file-symlink = {$path} содержит секреты, но ссылается на {$target} в другом каталоге, используйте сам файл или --insecure-permissions
+error-script-not-executable = Скрипт не является исполняемым: {$path}
+error-value-out-of-range = {$option} должно быть от {$min} до {$max}, получено {$value}
+error-timeout-exceeds-total = {$option} в {$value} секунд больше, чем {$total} в {$limit} секунд, ни одна попытка не успеет завершиться
+error-reserved-routing-table = Таблица маршрутизации {$table} зарезервирована системой
+error-control-socket-in-use = Управляющий сокет {$path} используется другим процессом
+error-invalid-control-command = Недопустимая управляющая команда: {$message}
+error-invalid-log-level = Недопустимый уровень журнала: {$level}
+error-tunnel-not-running = Туннель не запущен
+error-invalid-dbus-service = Недопустимая шина службы D-Bus
+error-invalid-notify-event = Недопустимое событие уведомления
+error-invalid-log-format = Недопустимый формат журнала
+error-invalid-log-target = Недопустимое назначение журнала
+error-invalid-log-filter = Недопустимый фильтр журнала {$filter}: {$error}
+error-profile-not-served = Туннель работает для профиля {$current}, для профиля {$profile} нужен другой экземпляр
+error-profile-running = Туннель профиля {$profile} уже запущен как процесс {$pid}, используйте --replace, чтобы остановить его
+error-instance-not-stopped = Туннель профиля {$profile} в процессе {$pid} не остановился
+error-tunnel-not-stopped = Туннель процесса {$pid} не завершился за {$timeout} секунд, используйте --force, чтобы завершить его и восстановить сетевые настройки
+error-dbus-not-authorized = Действие {$action} не разрешено
+error-pid-file-locked = PID-файл {$path} заблокирован другим запущенным экземпляром
+error-unknown-run-as = Неизвестный пользователь или группа для работы без привилегий: {$name}
+error-run-as-not-root = Параметр run-as-user требует запуска туннеля от имени root
+error-run-as-dns = Восстановление resolv.conf и утилита resolvconf требуют root, используйте dns-backend=resolved или no-dns вместе с run-as-user
+error-privilege-drop = Не удалось переключиться на непривилегированного пользователя: {$error}
 error-invalid-cert-id = ID сертификата не в шестнадцатеричном формате: {$id}
 error-ca-root-not-exist = Путь к корневому сертификату CA не существует: {$path}
 error-validation = Ошибка проверки
@@ -60,6 +94,8 @@ error-user-input-canceled = Ввод пользователя отменён
 error-connection-cancelled = Соединение отменено
 error-unknown-event = Неизвестное событие: {$event}
 error-no-service-connection = Нет соединения со службой
+error-session-not-owned = Сеанс принадлежит пользователю с ID {$uid}, управлять им может только этот пользователь или root
+error-option-not-permitted = Служба не разрешает этому пользователю параметр {$option}
 error-empty-input = Ввод не может быть пустым
 error-invalid-response = Недопустимый ответ!
 error-invalid-object = Недопустимый объект
@@ -67,7 +103,7 @@ error-no-connector = Нет коннектора туннеля
 error-tunnel-disconnected = Туннель отключен, последнее сообщение: {$message}
 error-unexpected-reply = Неожиданный ответ
 error-auth-failed = Ошибка аутентификации
-error-no-login-type = Отсутствует обязательный параметр: login-type
+error-missing-parameter = Отсутствует обязательный параметр: { $option }
 error-connection-timeout = Таймаут соединения
 error-cannot-send-request = Невозможно отправить запрос в службу
 error-cannot-read-reply = Невозможно прочитать ответ от службы
@@ -80,7 +116,13 @@ error-no-pkcs8 = Не указан путь к PKCS8 PEM
 error-no-pkcs11 = Не указан PIN-код PKCS11
 error-no-ipsec-session = Нет сессии IPSEC
 error-request-failed-error-code = Ошибка запроса, код ошибки: {$error_code}
+error-gateway-info-refused = Шлюз {$server} не отвечает на запросы без аутентификации, код ошибки: {$error_code}
 error-no-root-privileges = Эта программа должна быть запущена с правами root!
+error-missing-privileges = Выбранные параметры требуют привилегий, которых нет у этого процесса (проверку можно пропустить с помощью --skip-permission-check):
+privilege-net-admin = CAP_NET_ADMIN требуется для создания устройства tun и настройки адресов, маршрутов и DNS: запустите от root, выдайте право командой sudo setcap cap_net_admin+ep { $exe }, запускайте из юнита systemd с AmbientCapabilities=CAP_NET_ADMIN или передайте заранее созданное устройство через tun-fd вместе с no-device-config, no-routing и no-dns
+privilege-resolv-conf = Для настройки DNS требуется доступ на запись к { $path }: запустите от root, выберите другой dns-backend или установите no-dns=true
+privilege-resolvconf-tool = Утилиту resolvconf может использовать только root: выберите другой dns-backend или установите no-dns=true
+privilege-nft = Команда nft нужна для kill-switch и forward-source: установите пакет nftables или отключите эти параметры
 error-missing-required-parameters = Отсутствуют обязательные параметры: имя сервера и/или тип входа!
 error-missing-server-name = Отсутствует обязательный параметр: имя сервера!
 error-no-connector-for-challenge-code = Нет коннектора для отправки кода запроса!
@@ -89,11 +131,13 @@ error-invalid-sexpr = Недопустимый sexpr: {$value}
 error-invalid-value = Недопустимое значение
 error-udp-request-failed = Ошибка отправки UDP-запроса
 error-no-tty = Нет подключенного TTY для получения ввода пользователя
+error-service-mfa = Служба не может отвечать на запросы MFA или открывать браузер поставщика удостоверений, используйте сохранённый пароль с параметром password или аутентификацию по сертификату с параметром cert-type
 error-invalid-auth-response = Недопустимый ответ аутентификации
 error-invalid-client-settings = Недопустимый ответ настроек клиента
 error-invalid-otp-reply = Недопустимый ответ OTP
 error-udp-encap-failed = Не удалось установить опцию сокета UDP_ENCAP, код ошибки: {$code}
 error-so-no-check-failed = Не удалось установить опцию сокета SO_NO_CHECK, код ошибки: {$code}
+error-so-mark-failed = Не удалось установить опцию сокета SO_MARK, код ошибки: {$code}
 error-keepalive-failed = Ошибка keepalive
 error-receive-failed = Ошибка получения
 error-unknown-color-scheme = Неизвестное значение цветовой схемы
@@ -104,12 +148,55 @@ error-invalid-operation-mode = Недопустимый режим работы
 error-invalid-tunnel-type = Недопустимый тип туннеля
 error-invalid-cert-type = Недопустимый тип сертификата
 error-invalid-icon-theme = Недопустимая тема иконок
+error-invalid-dns-backend = Недопустимый механизм настройки DNS
+error-invalid-dns-mode = Недопустимый режим DNS
+error-invalid-dns-override = Недопустимый приоритет DNS
+error-invalid-address-conflict-action = Недопустимое действие при конфликте адресов
+error-resolv-conf-symlink = {$path} является символической ссылкой, прямой режим DNS не будет её изменять; выберите другой dns-backend
+error-resolv-conf-read-only = {$path} доступен только для чтения или смонтирован средой контейнера: задайте dns-resolv-path для записи в другое место или dns-backend=print для применения из скрипта
+error-invalid-device-name = Недопустимое имя интерфейса: {$name}
+error-invalid-netns-name = Недопустимое имя сетевого пространства имён: {$name}
+error-device-name-in-use = Интерфейс {$name} уже существует
+error-invalid-tun-fd = Дескриптор файла должен принадлежать устройству tun, открытому с IFF_TUN и IFF_NO_PI: {$message}
+error-unknown-tun-owner = Неизвестный пользователь или группа {$name}
+error-tun-ioctl = Не удалось применить {$request} к {$name}: {$message}
+error-tun-ioctl-permission = Нет прав на применение {$request} к {$name}, устройство должен подготовить root или процесс с CAP_NET_ADMIN
+error-invalid-network-manager-mode = Недопустимый режим NetworkManager
+error-invalid-ipv6-block-mode = Недопустимый режим блокировки IPv6
+error-invalid-mss-clamp-mode = Недопустимый режим ограничения MSS
+error-no-free-device-name = Нет свободного имени интерфейса для шаблона {$name}
+error-invalid-trusted-network = Неверный критерий доверенной сети: {$value}
+error-invalid-network-match = Недопустимый критерий соответствия сети: {$value}
+error-invalid-health-listen = Недопустимый адрес конечных точек проверки состояния: {$value}
+error-invalid-proxy = Неверный прокси, ожидается none, auto или http://[user:password@]host[:port]: {$value}
+error-proxy-connect-failed = Прокси {$proxy} отклонил подключение к {$destination}: {$status}
+error-invalid-route = Недопустимый маршрут: {$value}
 error-no-natt-reply = Нет ответа NAT-T
 error-not-implemented = Не реализовано
 error-unknown-packet-type = Неизвестный тип пакета
 error-no-sender = Нет отправителя
 error-empty-ccc-session = Пустая сессия CCC
 error-identity-timeout = Таймаут при ожидании ответа идентификации, правильный ли тип входа?
+error-no-office-mode-address = Шлюз не назначил пригодный адрес: {$address}
+error-gateway-error = Ошибка шлюза, код: {$code}
+error-gateway-reconnect = Шлюз неоднократно запросил переподключение: {$message}
+error-hello-rejected = Шлюз отклонил приветствие клиента: {$message}
+error-roaming-timeout = Не удалось восстановить туннель в течение {$seconds} секунд после смены сети
+error-kill-switch-unavailable = Для kill switch требуется утилита nft: {$message}
+error-forwarding-unavailable = Для пересылки из LAN требуется утилита nft: {$message}
+error-mss-clamp-unavailable = Для ограничения MSS требуется утилита nft: {$message}
+error-app-routing-unavailable = Для маршрутизации приложений требуется утилита nft: {$message}
+error-dns-redirect-unavailable = Для перенаправления DNS требуется утилита nft: {$message}
+error-forward-source-conflict = Исходная сеть пересылки {$source} пересекается с адресом или маршрутами туннеля
+error-setup-missing-option = В неинтерактивном режиме параметр { $option } обязателен
+error-setup-config-exists = { $path } уже существует, для перезаписи используйте --force
+error-setup-invalid-choice = Недопустимый выбор: { $value }
+error-setup-unreachable = Не удаётся связаться с { $server }: { $error }
+error-setup-certificate-rejected = Сертификат шлюза не является доверенным, добавьте его CA параметром ca-cert или примите его с помощью --ignore-server-cert true
+error-import-read = Не удалось прочитать { $path }: { $error }
+error-import-empty = { $path } не содержит настроек, которые можно импортировать
+error-import-not-toml = Настройки импортируются в конфигурацию TOML, у { $path } нет расширения .toml
+error-import-profile-exists = Профиль { $profile } уже существует в { $path }
 
 # Placeholder texts
 placeholder-domains = Домены через запятую
@@ -158,25 +245,98 @@ tray-menu-exit = Выход
 
 # Connection info
 info-connected-since = Подключено с
+info-profile = Профиль
 info-server-name = Имя сервера
 info-user-name = Имя пользователя
 info-login-type = Тип входа
 info-tunnel-type = Тип туннеля
 info-transport-type = Тип транспорта
+info-protocol-version = Версия протокола
 info-ip-address = IP-адрес
+info-ipv6-address = IPv6-адрес
 info-dns-servers = DNS-серверы
 info-search-domains = Домены поиска
+info-wins-servers = WINS-серверы
 info-interface = Интерфейс
+info-interface-mode = Режим интерфейса
+info-interface-mode-p2p = точка-точка, узел { $peer }
+info-interface-mode-local = только локальный адрес
 info-dns-configured = DNS настроен
+info-dns-mode = Режим DNS
+info-dns-override = Приоритет DNS
 info-routing-configured = Маршрутизация настроена
 info-default-route = Маршрут по умолчанию
+info-reauth-interval = Интервал повторной аутентификации
+info-forwarding = Пересылка из LAN
+info-dns-check = Проверка утечки DNS
+info-dns-check-passed = пройдена
+info-dns-check-failed = не пройдена
+info-routes = Маршруты
+info-received = Получено
+info-sent = Отправлено
+info-traffic-amount = {$bytes}, пакетов: {$packets}
+info-stats-since = Статистика с
+info-connected-time = Время подключения
+info-reconnects = Переподключения
+info-reauths = Повторные аутентификации
+info-rekeys = Смены ключей
+info-last-error = Последняя ошибка
 
 # CLI Messages
 cli-identity-provider-auth = Для аутентификации через провайдера идентификации откройте следующий URL в браузере:
 cli-tunnel-connected = Туннель подключен, нажмите Ctrl-C для выхода.
 cli-tunnel-disconnected = Туннель отключен
+notify-connected = VPN подключен ({$address})
+notify-reconnecting = VPN переподключается (попытка {$attempt})
+notify-disconnected = VPN отключен
+notify-disconnected-reason = VPN отключен: {$reason}
+notify-reauth-failed = Повторная аутентификация VPN не удалась: {$error}
+cli-waiting-for-connect = Ожидание команды подключения
+cli-config-reloaded = Конфигурация перезагружена
+cli-reload-on-reconnect = Эти параметры вступят в силу при следующем переподключении: { $options }
+cli-reload-on-restart = Эти параметры вступят в силу после перезапуска snx-rs: { $options }
+cli-reload-failed = Не удалось перезагрузить конфигурацию, сохраняется текущая: { $error }
+cli-daemon-terminated = Процессу {$pid} отправлен сигнал завершения
+cli-tunnel-stopping = Ожидание завершения туннеля процесса {$pid}
+cli-tunnel-killed = Принудительное завершение процесса {$pid} и восстановление оставленных им сетевых настроек
+cli-network-restored = Сетевые настройки восстановлены
 cli-another-instance-running = Другая копия snx-rs уже запущена
+cli-replacing-instance = Остановка работающего туннеля процесса {$pid}
 cli-app-terminated = Приложение завершено по сигналу
+cli-session-expiring = Сеанс VPN истекает через {$minutes} мин, туннель будет отключён
+cli-dns-check-failed = Внимание: проверка утечки DNS не пройдена, DNS-запросы могут идти мимо туннеля
+cli-trusted-network-lost = Доверенная сеть больше не обнаружена, возобновление VPN-подключения
+cli-gateway-failover = Шлюз {$failed} недоступен, попытка подключения к {$server}
+cli-gateway-failback = Шлюз {$server} снова доступен, возврат к нему
+cli-service-installed = Служба {$name} установлена, запустите её командой: sc.exe start {$name}
+cli-service-uninstalled = Служба {$name} удалена
+cli-profile-switch = Сеть изменилась, переключение на профиль {$profile}
+cli-dry-run-auth-failed = Пробный запуск: ошибка аутентификации: { $error }
+cli-dry-run-negotiation-failed = Пробный запуск: ошибка согласования туннеля: { $error }
+cli-dry-run-complete = Пробный запуск завершён, сетевая конфигурация не изменялась.
+setup-server-prompt = Адрес шлюза
+setup-probing = Проверка шлюза { $server }...
+setup-certificate = Сертификат: { $subject }, выдан { $issuer }, действителен до { $expires }
+setup-fingerprint = Отпечаток SHA-256: { $fingerprint }
+setup-certificate-untrusted = Сертификат не является доверенным: { $error }
+setup-ignore-cert-prompt = Всё равно принять этот сертификат
+setup-login-options = Типы входа, предлагаемые шлюзом:
+setup-login-type-prompt = Тип входа, номер или id [{ $default }]
+setup-user-prompt = Имя пользователя
+setup-password-prompt = Пароль
+setup-store-password-prompt = Сохранить пароль в связке ключей
+setup-default-route-prompt = Направлять весь трафик через туннель (полный туннель)
+setup-dns-mode-prompt = Режим DNS, split или full [split]
+setup-overwrite-prompt = { $path } уже существует, перезаписать
+setup-cancelled = Настройка отменена, существующая конфигурация не изменена
+setup-config-written = Конфигурация записана в { $path }
+setup-test-connect-prompt = Проверить вход сейчас
+setup-test-connect-ok = Вход выполнен успешно
+import-config-written = Импортировано настроек: { $count } из { $source } в { $path }
+import-unmapped = Не импортировано, строка { $line }: { $key }
+import-login-type-missing = Тип входа не импортирован, "snx-rs info -s { $server }" выводит типы входа шлюза для параметра login-type
+prompt-missing-parameters = В конфигурации отсутствуют обязательные параметры: { $options }
+prompt-save-answers = Сохранить ответы в { $path }
 
 # Connection Messages
 connection-connected-to = Подключено к {$server}
@@ -204,6 +364,7 @@ connection-status-disconnected = Отключено
 connection-status-connecting = Выполняется подключение
 connection-status-connected-since = Подключено с: {$since}
 connection-status-mfa-pending = Ожидание MFA: {$mfa_type}
+connection-status-trusted-network = Обнаружена доверенная сеть, VPN приостановлен
 
 # Login options
 login-options-server-address = Адрес сервера
@@ -214,3 +375,11 @@ login-options-preferred-protocol = Предпочтительный проток
 login-options-tcpt-port = Порт TCPT
 login-options-natt-port = Порт NATT
 login-options-internal-ca-fingerprint = Отпечаток внутреннего CA
+login-options-protocol-version = Версия протокола
+login-options-ipsec-transport = Транспорт IPSec
+login-options-saml-enabled = SAML включён
+login-options-certificate-subject = Субъект сертификата
+login-options-certificate-issuer = Издатель сертификата
+login-options-certificate-expires = Сертификат действителен до
+login-options-certificate-fingerprint = Отпечаток SHA-256 сертификата
+login-options-certificate-error = Сертификат не доверенный
@@ -53,6 +53,40 @@ expand-ui = Používateľské rozhranie
 error-no-server-name = Nie je zadaná adresa servera
 error-no-auth = Nie je vybraná metóda overovania
 error-file-not-exist = Súbor neexistuje: {$path}
+error-invalid-config-file = Neplatný konfiguračný súbor {$path}: {$message}
+error-profile-not-found = Profil {$profile} sa v konfigurácii nenašiel
+error-in-profile = Profil {$profile}: {$message}
+error-invalid-env-var = Neplatná hodnota premennej prostredia {$name}: {$message}
+error-invalid-configuration = Neplatná konfigurácia:
+error-invalid-server-name = Neplatná adresa servera: {$server}
+error-route-host-bits = {$option}: {$route} má nastavené bity hostiteľa, použite {$network}
+error-options-conflict = {$first} nie je možné kombinovať s {$second}
+error-insecure-permissions = Do {$path} môžu zapisovať ostatní používatelia
+error-secret-file-permissions = {$path} obsahuje tajné údaje, ale majú k nemu prístup aj iní používatelia (režim {$mode}), obmedzte ho pomocou chmod 600 alebo použite --insecure-permissions
+error-secret-file-symlink = {$path} obsahuje tajné údaje, ale odkazuje na {$target} v inom adresári, použite priamo súbor alebo --insecure-permissions
+error-script-not-executable = Skript nie je spustiteľný: {$path}
+error-value-out-of-range = {$option} musí byť medzi {$min} a {$max}, zadané {$value}
+error-timeout-exceeds-total = {$option} s hodnotou {$value} sekúnd je dlhší ako {$total} s hodnotou {$limit} sekúnd, žiadny pokus by nemohol skončiť včas
+error-reserved-routing-table = Smerovacia tabuľka {$table} je vyhradená systémom
+error-control-socket-in-use = Riadiaci soket {$path} používa iný proces
+error-invalid-control-command = Neplatný riadiaci príkaz: {$message}
+error-invalid-log-level = Neplatná úroveň protokolovania: {$level}
+error-tunnel-not-running = Tunel nebeží
+error-invalid-dbus-service = Neplatná zbernica služby D-Bus
+error-invalid-notify-event = Neplatná udalosť oznámenia
+error-invalid-log-format = Neplatný formát protokolu
+error-invalid-log-target = Neplatný cieľ protokolu
+error-invalid-log-filter = Neplatný filter protokolu {$filter}: {$error}
+error-profile-not-served = Tunel beží pre profil {$current}, profil {$profile} vyžaduje inú inštanciu
+error-profile-running = Tunel pre profil {$profile} už beží ako proces {$pid}, na jeho zastavenie použite --replace
+error-instance-not-stopped = Tunel profilu {$profile} v procese {$pid} sa nezastavil
+error-tunnel-not-stopped = Tunel procesu {$pid} sa neukončil do {$timeout} sekúnd, použite --force na jeho ukončenie a obnovenie sieťových nastavení
+error-dbus-not-authorized = Akcia {$action} nie je povolená
+error-pid-file-locked = Súbor PID {$path} je uzamknutý inou spustenou inštanciou
+error-unknown-run-as = Neznámy používateľ alebo skupina pre beh bez oprávnení: {$name}
+error-run-as-not-root = Voľba run-as-user vyžaduje spustenie tunela pod používateľom root
+error-run-as-dns = Obnovenie resolv.conf a nástroj resolvconf vyžadujú root, s voľbou run-as-user použite dns-backend=resolved alebo no-dns
+error-privilege-drop = Nie je možné prepnúť na používateľa bez oprávnení: {$error}
 error-invalid-cert-id = ID certifikátu nie je v hexadecimálnom formáte: {$id}
 error-ca-root-not-exist = Cesta ku koreňovému certifikátu CA neexistuje: {$path}
 error-validation = Chyba overenia
@@ -60,13 +94,15 @@ error-user-input-canceled = Vstup používateľa zrušený
 error-connection-cancelled = Pripojenie zrušené
 error-unknown-event = Neznáma udalosť: {$event}
 error-no-service-connection = Žiadne pripojenie k službe
+error-session-not-owned = Relácia patrí používateľovi s ID {$uid}, ovládať ju môže iba tento používateľ alebo root
+error-option-not-permitted = Služba tomuto používateľovi nepovoľuje voľbu {$option}
 error-empty-input = Vstup nemôže byť prázdny
 error-invalid-object = Neplatný objekt
 error-no-connector = Žiadny konektor tunela
 error-tunnel-disconnected = Tunel odpojený, posledná správa: {$message}
 error-unexpected-reply = Neočakávaná odpoveď
 error-auth-failed = Overenie zlyhalo
-error-no-login-type = Chýba povinný parameter: login-type
+error-missing-parameter = Chýba povinný parameter: { $option }
 error-connection-timeout = Časový limit pripojenia
 error-invalid-response = Neplatná odpoveď
 error-cannot-send-request = Nie je možné odoslať požiadavku na službu
@@ -80,7 +116,13 @@ error-no-pkcs8 = Žiadna cesta PKCS8 PEM nie je poskytnutá
 error-no-pkcs11 = Žiadny PIN PKCS11 nie je poskytnutý
 error-no-ipsec-session = Žiadna IPSEC relácia
 error-request-failed-error-code = Požiadavka zlyhala, kód chyby: {$error_code}
+error-gateway-info-refused = Brána {$server} neodpovedá na neoverené dopyty, kód chyby: {$error_code}
 error-no-root-privileges = Tento program musí byť spustený ako root používateľ!
+error-missing-privileges = Zvolené možnosti vyžadujú oprávnenia, ktoré tento proces nemá (kontrolu možno preskočiť pomocou --skip-permission-check):
+privilege-net-admin = CAP_NET_ADMIN je potrebné na vytvorenie zariadenia tun a nastavenie adries, trás a DNS: spustite program ako root, udeľte oprávnenie príkazom sudo setcap cap_net_admin+ep { $exe }, spustite ho zo služby systemd s AmbientCapabilities=CAP_NET_ADMIN, alebo odovzdajte vopred vytvorené zariadenie pomocou tun-fd spolu s no-device-config, no-routing a no-dns
+privilege-resolv-conf = Na nastavenie DNS je potrebný prístup na zápis do { $path }: spustite program ako root, zvoľte iný dns-backend alebo nastavte no-dns=true
+privilege-resolvconf-tool = Nástroj resolvconf môže používať iba root: zvoľte iný dns-backend alebo nastavte no-dns=true
+privilege-nft = Príkaz nft je potrebný pre kill-switch a forward-source: nainštalujte balík nftables alebo tieto možnosti vypnite
 error-missing-required-parameters = Chýbajú povinné parametre: názov servera a/alebo typ prístupu!
 error-missing-server-name = Chýba povinný parameter: názov servera!
 error-no-connector-for-challenge-code = Žiadny konektor pre odoslanie kódu výzvy!
@@ -89,11 +131,13 @@ error-invalid-sexpr = Neplatný sexpr: {$value}
 error-invalid-value = Neplatná hodnota
 error-udp-request-failed = Chyba pri odosielaní UDP požiadavky
 error-no-tty = Žiadny TTY pripojený pre vstup používateľa
+error-service-mfa = Služba nemôže odpovedať na výzvy MFA ani otvoriť prehliadač poskytovateľa identity, použite uložené heslo s voľbou password alebo overenie certifikátom s voľbou cert-type
 error-invalid-auth-response = Neplatná odpoveď overenia
 error-invalid-client-settings = Neplatné nastavenia klienta
 error-invalid-otp-reply = Neplatná odpoveď OTP
 error-udp-encap-failed = Nie je možné nastaviť možnosť soketu UDP_ENCAP, kód chyby: {$code}
 error-so-no-check-failed = Nie je možné nastaviť možnosť soketu SO_NO_CHECK, kód chyby: {$code}
+error-so-mark-failed = Nie je možné nastaviť možnosť soketu SO_MARK, kód chyby: {$code}
 error-keepalive-failed = Keepalive zlyhal
 error-receive-failed = Prijatie zlyhalo
 error-unknown-color-scheme = Neznáma hodnota farebnej schémy
@@ -104,12 +148,55 @@ error-invalid-operation-mode = Neplatný režim prevádzky
 error-invalid-tunnel-type = Neplatný typ tunela
 error-invalid-cert-type = Neplatný typ certifikátu
 error-invalid-icon-theme = Neplatný motív ikon
+error-invalid-dns-backend = Neplatný spôsob konfigurácie DNS
+error-invalid-dns-mode = Neplatný režim DNS
+error-invalid-dns-override = Neplatná priorita DNS
+error-invalid-address-conflict-action = Neplatná akcia pri konflikte adries
+error-resolv-conf-symlink = {$path} je symbolický odkaz, priamy režim DNS ho nezmení; zvoľte iný dns-backend
+error-resolv-conf-read-only = {$path} je iba na čítanie alebo pripojený behovým prostredím kontajnera: nastavte dns-resolv-path na zápis inam, alebo dns-backend=print na použitie zo skriptu
+error-invalid-device-name = Neplatný názov rozhrania: {$name}
+error-invalid-netns-name = Neplatný názov sieťového menného priestoru: {$name}
+error-device-name-in-use = Rozhranie {$name} už existuje
+error-invalid-tun-fd = Deskriptor súboru musí patriť zariadeniu tun otvorenému s IFF_TUN a IFF_NO_PI: {$message}
+error-unknown-tun-owner = Neznámy používateľ alebo skupina {$name}
+error-tun-ioctl = Nie je možné použiť {$request} na {$name}: {$message}
+error-tun-ioctl-permission = Použitie {$request} na {$name} nie je povolené, zariadenie musí pripraviť root alebo proces s oprávnením CAP_NET_ADMIN
+error-invalid-network-manager-mode = Neplatný režim NetworkManagera
+error-invalid-ipv6-block-mode = Neplatný režim blokovania IPv6
+error-invalid-mss-clamp-mode = Neplatný režim obmedzenia MSS
+error-no-free-device-name = Pre šablónu {$name} nie je voľný názov rozhrania
+error-invalid-trusted-network = Neplatné kritérium dôveryhodnej siete: {$value}
+error-invalid-network-match = Neplatné kritérium zhody siete: {$value}
+error-invalid-health-listen = Neplatná adresa koncových bodov stavu: {$value}
+error-invalid-proxy = Neplatný proxy server, očakáva sa none, auto alebo http://[user:password@]host[:port]: {$value}
+error-proxy-connect-failed = Proxy server {$proxy} odmietol spojenie s {$destination}: {$status}
+error-invalid-route = Neplatná trasa: {$value}
 error-no-natt-reply = Žiadna odpoveď NATT
 error-not-implemented = Neimplementované
 error-unknown-packet-type = Neznámy typ paketu
 error-no-sender = Žiadny odosielateľ
 error-empty-ccc-session = Prázdna CCC relácia
 error-identity-timeout = Časový limit pri čakaní na odpoveď identity, je typ prístupu správny?
+error-no-office-mode-address = Brána nepridelila použiteľnú adresu: {$address}
+error-gateway-error = Chyba brány, kód: {$code}
+error-gateway-reconnect = Brána opakovane požadovala nové pripojenie: {$message}
+error-hello-rejected = Brána odmietla úvodnú správu klienta: {$message}
+error-roaming-timeout = Tunel sa nepodarilo obnoviť do {$seconds} sekúnd po zmene siete
+error-kill-switch-unavailable = Kill switch vyžaduje nástroj nft: {$message}
+error-forwarding-unavailable = Preposielanie z LAN vyžaduje nástroj nft: {$message}
+error-mss-clamp-unavailable = Obmedzenie MSS vyžaduje nástroj nft: {$message}
+error-app-routing-unavailable = Smerovanie aplikácií vyžaduje nástroj nft: {$message}
+error-dns-redirect-unavailable = Presmerovanie DNS vyžaduje nástroj nft: {$message}
+error-forward-source-conflict = Zdrojová sieť preposielania {$source} sa prekrýva s adresou alebo trasami tunela
+error-setup-missing-option = V neinteraktívnom režime je voľba { $option } povinná
+error-setup-config-exists = { $path } už existuje, na prepísanie použite --force
+error-setup-invalid-choice = Neplatná voľba: { $value }
+error-setup-unreachable = Nie je možné spojiť sa s { $server }: { $error }
+error-setup-certificate-rejected = Certifikát brány nie je dôveryhodný, pridajte jeho CA voľbou ca-cert alebo ho prijmite pomocou --ignore-server-cert true
+error-import-read = Nie je možné prečítať { $path }: { $error }
+error-import-empty = { $path } neobsahuje žiadne nastavenia, ktoré je možné importovať
+error-import-not-toml = Nastavenia sa importujú do konfigurácie TOML, { $path } nemá príponu .toml
+error-import-profile-exists = Profil { $profile } už v { $path } existuje
 
 # Placeholder texts
 placeholder-domains = Domény oddelené čiarkami
@@ -134,18 +221,42 @@ icon-theme-light = Svetlý
 
 # Connection info
 info-connected-since = Pripojené od
+info-profile = Profil
 info-server-name = Názov servera
 info-user-name = Používateľské meno
 info-login-type = Typ prihlásenia
 info-tunnel-type = Typ tunela
 info-transport-type = Typ transportu
+info-protocol-version = Verzia protokolu
 info-ip-address = IP adresa
+info-ipv6-address = IPv6 adresa
 info-dns-servers = DNS servery
 info-search-domains = Vyhľadávacie domény
+info-wins-servers = WINS servery
 info-interface = Rozhranie
+info-interface-mode = Režim rozhrania
+info-interface-mode-p2p = point-to-point, protistrana { $peer }
+info-interface-mode-local = iba lokálna adresa
 info-dns-configured = DNS nakonfigurované
+info-dns-mode = Režim DNS
+info-dns-override = Priorita DNS
 info-routing-configured = Smerovanie nakonfigurované
 info-default-route = Predvolená trasa
+info-reauth-interval = Interval opätovného overenia
+info-forwarding = Preposielanie z LAN
+info-dns-check = Test úniku DNS
+info-dns-check-passed = úspech
+info-dns-check-failed = zlyhanie
+info-routes = Trasy
+info-received = Prijaté
+info-sent = Odoslané
+info-traffic-amount = {$bytes}, paketov: {$packets}
+info-stats-since = Štatistiky od
+info-connected-time = Doba pripojenia
+info-reconnects = Opätovné pripojenia
+info-reauths = Opätovné overenia
+info-rekeys = Výmeny kľúčov
+info-last-error = Posledná chyba
 
 # Application
 app-title = SNX-RS VPN klient pre Linux
@@ -175,8 +286,57 @@ tray-menu-exit = Ukončiť
 cli-identity-provider-auth = Pre autentifikáciu cez poskytovateľa identity otvorte nasledujúcu URL adresu vo vašom prehliadači:
 cli-tunnel-connected = Tunel pripojený, stlačte Ctrl+C pre ukončenie.
 cli-tunnel-disconnected = Tunel odpojený
+notify-connected = VPN pripojená ({$address})
+notify-reconnecting = VPN sa znova pripája (pokus {$attempt})
+notify-disconnected = VPN odpojená
+notify-disconnected-reason = VPN odpojená: {$reason}
+notify-reauth-failed = Opätovné overenie VPN zlyhalo: {$error}
+cli-waiting-for-connect = Čaká sa na príkaz na pripojenie
+cli-config-reloaded = Konfigurácia bola znovu načítaná
+cli-reload-on-reconnect = Tieto voľby sa použijú pri ďalšom opätovnom pripojení: { $options }
+cli-reload-on-restart = Tieto voľby sa použijú po reštarte snx-rs: { $options }
+cli-reload-failed = Konfiguráciu nie je možné znovu načítať, zostáva aktuálna: { $error }
+cli-daemon-terminated = Procesu {$pid} bol odoslaný signál na ukončenie
+cli-tunnel-stopping = Čaká sa na ukončenie tunela procesu {$pid}
+cli-tunnel-killed = Ukončovanie procesu {$pid} a obnova sieťových nastavení, ktoré zanechal
+cli-network-restored = Sieťové nastavenia obnovené
 cli-another-instance-running = Iná inštancia snx-rs už beží
+cli-replacing-instance = Zastavuje sa bežiaci tunel procesu {$pid}
 cli-app-terminated = Aplikácia ukončená signálom
+cli-session-expiring = Relácia VPN vyprší o {$minutes} min, tunel bude odpojený
+cli-dns-check-failed = Upozornenie: test úniku DNS zlyhal, dotazy DNS nemusia prechádzať tunelom
+cli-trusted-network-lost = Dôveryhodná sieť už nie je zistená, obnovuje sa pripojenie VPN
+cli-gateway-failover = Brána {$failed} je nedostupná, skúša sa {$server}
+cli-gateway-failback = Brána {$server} je opäť dostupná, prepína sa späť na ňu
+cli-service-installed = Služba {$name} bola nainštalovaná, spustite ju príkazom: sc.exe start {$name}
+cli-service-uninstalled = Služba {$name} bola odinštalovaná
+cli-profile-switch = Sieť sa zmenila, prepína sa na profil {$profile}
+cli-dry-run-auth-failed = Skúšobný beh: overenie zlyhalo: { $error }
+cli-dry-run-negotiation-failed = Skúšobný beh: vyjednanie tunela zlyhalo: { $error }
+cli-dry-run-complete = Skúšobný beh dokončený, konfigurácia siete nebola zmenená.
+setup-server-prompt = Adresa brány
+setup-probing = Kontroluje sa brána { $server }...
+setup-certificate = Certifikát: { $subject }, vydal { $issuer }, platný do { $expires }
+setup-fingerprint = Odtlačok SHA-256: { $fingerprint }
+setup-certificate-untrusted = Certifikát nie je dôveryhodný: { $error }
+setup-ignore-cert-prompt = Napriek tomu prijať tento certifikát
+setup-login-options = Typy prihlásenia ponúkané bránou:
+setup-login-type-prompt = Typ prihlásenia, číslo alebo id [{ $default }]
+setup-user-prompt = Používateľské meno
+setup-password-prompt = Heslo
+setup-store-password-prompt = Uložiť heslo do kľúčenky
+setup-default-route-prompt = Smerovať všetku prevádzku tunelom (úplný tunel)
+setup-dns-mode-prompt = Režim DNS, split alebo full [split]
+setup-overwrite-prompt = { $path } už existuje, prepísať
+setup-cancelled = Nastavenie zrušené, existujúca konfigurácia zostáva bez zmeny
+setup-config-written = Konfigurácia zapísaná do { $path }
+setup-test-connect-prompt = Otestovať teraz prihlásenie
+setup-test-connect-ok = Prihlásenie bolo úspešné
+import-config-written = Importované nastavenia: { $count } z { $source } do { $path }
+import-unmapped = Neimportované, riadok { $line }: { $key }
+import-login-type-missing = Nebol importovaný žiadny typ prihlásenia, "snx-rs info -s { $server }" vypíše typy prihlásenia brány pre voľbu login-type
+prompt-missing-parameters = V konfigurácii chýbajú povinné parametre: { $options }
+prompt-save-answers = Uložiť odpovede do { $path }
 
 # Connection Messages
 connection-connected-to = Pripojené k {$server}
@@ -204,6 +364,7 @@ connection-status-disconnected = Odpojené
 connection-status-connecting = Prebieha pripájanie
 connection-status-connected-since = Pripojené od: {$since}
 connection-status-mfa-pending = Čakanie na MFA: {$mfa_type}
+connection-status-trusted-network = Zistená dôveryhodná sieť, VPN pozastavená
 
 # Login options
 login-options-server-address = Adresa servera
@@ -214,3 +375,11 @@ login-options-preferred-protocol = Preferovaný protokol
 login-options-tcpt-port = Port TCPT
 login-options-natt-port = Port NATT
 login-options-internal-ca-fingerprint = Odtlačok interného CA
+login-options-protocol-version = Verzia protokolu
+login-options-ipsec-transport = Prenos IPSec
+login-options-saml-enabled = SAML povolené
+login-options-certificate-subject = Subjekt certifikátu
+login-options-certificate-issuer = Vydavateľ certifikátu
+login-options-certificate-expires = Platnosť certifikátu do
+login-options-certificate-fingerprint = Odtlačok SHA-256 certifikátu
+login-options-certificate-error = Certifikát nie je dôveryhodný
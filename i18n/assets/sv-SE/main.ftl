@@ -53,6 +53,40 @@ expand-ui = Användargränssnitt
 error-no-server-name = Ingen serveradress angiven
 error-no-auth = Ingen autentiseringsmetod vald
 error-file-not-exist = Filen finns inte: {$path}
+error-invalid-config-file = Ogiltig konfigurationsfil {$path}: {$message}
+error-profile-not-found = Profilen {$profile} hittades inte i konfigurationen
+error-in-profile = Profil {$profile}: {$message}
+error-invalid-env-var = Ogiltigt värde i miljövariabeln {$name}: {$message}
+error-invalid-configuration = Ogiltig konfiguration:
+error-invalid-server-name = Ogiltig serveradress: {$server}
+error-route-host-bits = {$option}: {$route} har värdbitar satta, använd {$network}
+error-options-conflict = {$first} kan inte kombineras med {$second}
+error-insecure-permissions = {$path} är skrivbar för andra användare
+error-secret-file-permissions = {$path} innehåller hemligheter men andra användare har åtkomst till den (läge {$mode}), begränsa den med chmod 600 eller använd --insecure-permissions
+error-secret-file-symlink = {$path} innehåller hemligheter men länkar till {$target} i en annan katalog, använd själva filen eller --insecure-permissions
+error-script-not-executable = Skriptet är inte körbart: {$path}
+error-value-out-of-range = {$option} måste vara mellan {$min} och {$max}, fick {$value}
+error-timeout-exceeds-total = {$option} på {$value} sekunder är längre än {$total} på {$limit} sekunder, inget försök skulle hinna slutföras
+error-reserved-routing-table = Routningstabell {$table} är reserverad av systemet
+error-control-socket-in-use = Kontrollsocketen {$path} används av en annan process
+error-invalid-control-command = Ogiltigt kontrollkommando: {$message}
+error-invalid-log-level = Ogiltig loggnivå: {$level}
+error-tunnel-not-running = Tunneln körs inte
+error-invalid-dbus-service = Ogiltig buss för D-Bus-tjänsten
+error-invalid-notify-event = Ogiltig aviseringshändelse
+error-invalid-log-format = Ogiltigt loggformat
+error-invalid-log-target = Ogiltigt loggmål
+error-invalid-log-filter = Ogiltigt loggfilter {$filter}: {$error}
+error-profile-not-served = Tunneln körs för profilen {$current}, profilen {$profile} behöver en annan instans
+error-profile-running = En tunnel för profilen {$profile} körs redan som processen {$pid}, använd --replace för att stoppa den
+error-instance-not-stopped = Tunneln för profilen {$profile} i processen {$pid} stoppades inte
+error-tunnel-not-stopped = Tunneln i process {$pid} avslutades inte inom {$timeout} sekunder, använd --force för att döda den och återställa nätverksinställningarna
+error-dbus-not-authorized = Åtgärden {$action} är inte tillåten
+error-pid-file-locked = PID-filen {$path} är låst av en annan körande instans
+error-unknown-run-as = Okänd användare eller grupp för körning utan behörighet: {$name}
+error-run-as-not-root = Alternativet run-as-user kräver att tunneln startas som root
+error-run-as-dns = Återställning av resolv.conf och verktyget resolvconf kräver root, använd dns-backend=resolved eller no-dns med run-as-user
+error-privilege-drop = Det går inte att byta till användaren utan behörighet: {$error}
 error-invalid-cert-id = Certifikat-ID är inte i hexadecimalt format: {$id}
 error-ca-root-not-exist = CA-rotpath finns inte: {$path}
 error-validation = Valideringsfel
@@ -60,13 +94,15 @@ error-user-input-canceled = Användarinput avbruten
 error-connection-cancelled = Anslutning avbruten
 error-unknown-event = Okänd händelse: {$event}
 error-no-service-connection = Ingen anslutning till tjänsten
+error-session-not-owned = Sessionen tillhör användaren med ID {$uid}, bara den användaren eller root får styra den
+error-option-not-permitted = Tjänsten tillåter inte alternativet {$option} för den här användaren
 error-empty-input = Input kan inte vara tom
 error-invalid-object = Ogiltigt objekt
 error-no-connector = Ingen tunnelanslutning
 error-tunnel-disconnected = Tunnel frånkopplad, sista meddelande: {$message}
 error-unexpected-reply = Oväntat svar
 error-auth-failed = Autentisering misslyckades
-error-no-login-type = Saknad obligatorisk parameter: login-type
+error-missing-parameter = Saknad obligatorisk parameter: { $option }
 error-connection-timeout = Anslutningstimeout
 error-invalid-response = Ogiltigt svar!
 error-cannot-send-request = Kan inte skicka förfrågan till tjänsten
@@ -80,7 +116,13 @@ error-no-pkcs8 = Ingen PKCS8 PEM-sökväg angiven
 error-no-pkcs11 = Ingen PKCS11 PIN angiven
 error-no-ipsec-session = Ingen IPSEC-session
 error-request-failed-error-code = Förfrågan misslyckades, felkod: {$error_code}
+error-gateway-info-refused = Gatewayen {$server} svarar inte på oautentiserade förfrågningar, felkod: {$error_code}
 error-no-root-privileges = Detta program måste köras som root-användare!
+error-missing-privileges = De valda alternativen kräver behörigheter som den här processen saknar (kontrollen kan hoppas över med --skip-permission-check):
+privilege-net-admin = CAP_NET_ADMIN krävs för att skapa tun-enheten och konfigurera adresser, rutter och DNS: kör som root, tilldela behörigheten med sudo setcap cap_net_admin+ep { $exe }, starta från en systemd-enhet med AmbientCapabilities=CAP_NET_ADMIN, eller skicka en förskapad enhet med tun-fd tillsammans med no-device-config, no-routing och no-dns
+privilege-resolv-conf = Skrivåtkomst till { $path } krävs för att konfigurera DNS: kör som root, välj en annan dns-backend eller ange no-dns=true
+privilege-resolvconf-tool = Verktyget resolvconf kan bara användas av root: välj en annan dns-backend eller ange no-dns=true
+privilege-nft = Kommandot nft behövs för kill-switch och forward-source: installera paketet nftables eller stäng av dessa alternativ
 error-missing-required-parameters = Saknade obligatoriska parametrar: servernamn och/eller åtkomsttyp!
 error-missing-server-name = Saknad obligatorisk parameter: servernamn!
 error-no-connector-for-challenge-code = Ingen anslutning för att skicka utmaningskod!
@@ -89,11 +131,13 @@ error-invalid-sexpr = Ogiltig sexpr: {$value}
 error-invalid-value = Ogiltigt värde
 error-udp-request-failed = Fel vid sändning av UDP-förfrågan
 error-no-tty = Ingen TTY ansluten för användarinput
+error-service-mfa = Tjänsten kan inte besvara MFA-utmaningar eller öppna webbläsaren för en identitetsleverantör, använd ett sparat lösenord med alternativet password eller certifikatautentisering med cert-type
 error-invalid-auth-response = Ogiltigt autentiseringssvar
 error-invalid-client-settings = Ogiltiga klientinställningar
 error-invalid-otp-reply = Ogiltigt OTP-svar
 error-udp-encap-failed = Kan inte ställa in UDP_ENCAP socket-option, felkod: {$code}
 error-so-no-check-failed = Kan inte ställa in SO_NO_CHECK socket-option, felkod: {$code}
+error-so-mark-failed = Kan inte ställa in SO_MARK socket-option, felkod: {$code}
 error-keepalive-failed = Keepalive misslyckades
 error-receive-failed = Mottagning misslyckades
 error-unknown-color-scheme = Okänt färgschema-värde
@@ -104,12 +148,55 @@ error-invalid-operation-mode = Ogiltigt driftläge
 error-invalid-tunnel-type = Ogiltig tunneltyp
 error-invalid-cert-type = Ogiltig certifikattyp
 error-invalid-icon-theme = Ogiltigt ikon-tema
+error-invalid-dns-backend = Ogiltig DNS-backend
+error-invalid-dns-mode = Ogiltigt DNS-läge
+error-invalid-dns-override = Ogiltig DNS-prioritet
+error-invalid-address-conflict-action = Ogiltig åtgärd vid adresskonflikt
+error-resolv-conf-symlink = {$path} är en symbolisk länk, direkt DNS-läge ändrar den inte; välj en annan dns-backend
+error-resolv-conf-read-only = {$path} är skrivskyddad eller monterad av containerns körmiljö: ange dns-resolv-path för att skriva någon annanstans, eller dns-backend=print för att tillämpa den från ett skript
+error-invalid-device-name = Ogiltigt gränssnittsnamn: {$name}
+error-invalid-netns-name = Ogiltigt namn på nätverksnamnrymd: {$name}
+error-device-name-in-use = Gränssnittet {$name} finns redan
+error-invalid-tun-fd = Filbeskrivaren måste tillhöra en tun-enhet öppnad med IFF_TUN och IFF_NO_PI: {$message}
+error-unknown-tun-owner = Okänd användare eller grupp {$name}
+error-tun-ioctl = Det går inte att tillämpa {$request} på {$name}: {$message}
+error-tun-ioctl-permission = Inte tillåtet att tillämpa {$request} på {$name}, enheten måste förberedas av root eller en process med CAP_NET_ADMIN
+error-invalid-network-manager-mode = Ogiltigt NetworkManager-läge
+error-invalid-ipv6-block-mode = Ogiltigt IPv6-blockeringsläge
+error-invalid-mss-clamp-mode = Ogiltigt läge för MSS-begränsning
+error-no-free-device-name = Inget ledigt gränssnittsnamn för mallen {$name}
+error-invalid-trusted-network = Ogiltigt kriterium för betrott nätverk: {$value}
+error-invalid-network-match = Ogiltigt kriterium för nätverksmatchning: {$value}
+error-invalid-health-listen = Ogiltig adress för hälsoändpunkterna: {$value}
+error-invalid-proxy = Ogiltig proxy, förväntade none, auto eller http://[user:password@]host[:port]: {$value}
+error-proxy-connect-failed = Proxyn {$proxy} nekade anslutningen till {$destination}: {$status}
+error-invalid-route = Ogiltig rutt: {$value}
 error-no-natt-reply = Inget NATT-svar
 error-not-implemented = Inte implementerat
 error-unknown-packet-type = Okänd pakettyp
 error-no-sender = Ingen avsändare
 error-empty-ccc-session = Tom CCC-session
 error-identity-timeout = Timeout vid väntan på identitetssvar, är åtkomsttypen korrekt?
+error-no-office-mode-address = Gatewayen tilldelade ingen användbar adress: {$address}
+error-gateway-error = Gatewayfel, kod: {$code}
+error-gateway-reconnect = Gatewayen begärde upprepade gånger återanslutning: {$message}
+error-hello-rejected = Gatewayen avvisade klientens hello-meddelande: {$message}
+error-roaming-timeout = Det gick inte att återupprätta tunneln inom {$seconds} sekunder efter nätverksändringen
+error-kill-switch-unavailable = Kill switch kräver verktyget nft: {$message}
+error-forwarding-unavailable = LAN-vidarebefordran kräver verktyget nft: {$message}
+error-mss-clamp-unavailable = MSS-begränsning kräver verktyget nft: {$message}
+error-app-routing-unavailable = Applikationsroutning kräver verktyget nft: {$message}
+error-dns-redirect-unavailable = DNS-omdirigering kräver verktyget nft: {$message}
+error-forward-source-conflict = Vidarebefordringskällan {$source} överlappar tunnelns adress eller rutter
+error-setup-missing-option = Alternativet { $option } krävs i icke-interaktivt läge
+error-setup-config-exists = { $path } finns redan, använd --force för att skriva över den
+error-setup-invalid-choice = Ogiltigt val: { $value }
+error-setup-unreachable = Kan inte nå { $server }: { $error }
+error-setup-certificate-rejected = Gatewayens certifikat är inte betrott, lägg till dess CA med alternativet ca-cert eller godkänn det med --ignore-server-cert true
+error-import-read = Kan inte läsa { $path }: { $error }
+error-import-empty = { $path } innehåller inga inställningar som kan importeras
+error-import-not-toml = Inställningarna importeras till en TOML-konfiguration, { $path } har inte filändelsen .toml
+error-import-profile-exists = Profilen { $profile } finns redan i { $path }
 
 # Placeholder texts
 placeholder-domains = Domäner separerade med kommatecken
@@ -134,18 +221,42 @@ icon-theme-light = Ljust
 
 # Connection info
 info-connected-since = Ansluten sedan
+info-profile = Profil
 info-server-name = Servernamn
 info-user-name = Användarnamn
 info-login-type = Inloggningstyp
 info-tunnel-type = Tunneltyp
 info-transport-type = Transporttyp
+info-protocol-version = Protokollversion
 info-ip-address = IP-adress
+info-ipv6-address = IPv6-adress
 info-dns-servers = DNS-servrar
 info-search-domains = Sökdomäner
+info-wins-servers = WINS-servrar
 info-interface = Gränssnitt
+info-interface-mode = Gränssnittsläge
+info-interface-mode-p2p = punkt-till-punkt, motpart { $peer }
+info-interface-mode-local = endast lokal adress
 info-dns-configured = DNS konfigurerad
+info-dns-mode = DNS-läge
+info-dns-override = DNS-prioritet
 info-routing-configured = Routning konfigurerad
 info-default-route = Standardrutt
+info-reauth-interval = Omautentiseringsintervall
+info-forwarding = LAN-vidarebefordran
+info-dns-check = DNS-läckagetest
+info-dns-check-passed = godkänt
+info-dns-check-failed = underkänt
+info-routes = Rutter
+info-received = Mottaget
+info-sent = Skickat
+info-traffic-amount = {$bytes}, {$packets} paket
+info-stats-since = Statistik sedan
+info-connected-time = Ansluten tid
+info-reconnects = Återanslutningar
+info-reauths = Omautentiseringar
+info-rekeys = Nyckelförnyelser
+info-last-error = Senaste fel
 
 # Application
 app-title = SNX-RS VPN-klient för Linux
@@ -175,8 +286,57 @@ tray-menu-exit = Avsluta
 cli-identity-provider-auth = För autentisering via identitetsleverantören, öppna följande URL i din webbläsare:
 cli-tunnel-connected = Tunnel ansluten, tryck Ctrl+C för att avsluta.
 cli-tunnel-disconnected = Tunnel frånkopplad
+notify-connected = VPN ansluten ({$address})
+notify-reconnecting = VPN återansluter (försök {$attempt})
+notify-disconnected = VPN frånkopplad
+notify-disconnected-reason = VPN frånkopplad: {$reason}
+notify-reauth-failed = Ny autentisering av VPN misslyckades: {$error}
+cli-waiting-for-connect = Väntar på anslutningskommandot
+cli-config-reloaded = Konfigurationen har lästs in på nytt
+cli-reload-on-reconnect = Dessa alternativ tillämpas vid nästa återanslutning: { $options }
+cli-reload-on-restart = Dessa alternativ tillämpas efter en omstart av snx-rs: { $options }
+cli-reload-failed = Det går inte att läsa in konfigurationen på nytt, den nuvarande behålls: { $error }
+cli-daemon-terminated = Avslutningssignalen skickades till process {$pid}
+cli-tunnel-stopping = Väntar på att tunneln i process {$pid} avslutas
+cli-tunnel-killed = Dödar process {$pid} och återställer nätverksinställningarna den lämnade
+cli-network-restored = Nätverksinställningar återställda
 cli-another-instance-running = En annan instans av snx-rs körs redan
+cli-replacing-instance = Stoppar den körande tunneln i processen {$pid}
 cli-app-terminated = Applikation avslutad av signal
+cli-session-expiring = VPN-sessionen går ut om {$minutes} min, tunneln kommer att kopplas från
+cli-dns-check-failed = Varning: DNS-läckagetestet misslyckades, DNS-frågor går kanske inte genom tunneln
+cli-trusted-network-lost = Betrott nätverk upptäcks inte längre, återupptar VPN-anslutningen
+cli-gateway-failover = Gatewayen {$failed} går inte att nå, försöker {$server}
+cli-gateway-failback = Gatewayen {$server} går att nå igen, byter tillbaka till den
+cli-service-installed = Tjänsten {$name} är installerad, starta den med: sc.exe start {$name}
+cli-service-uninstalled = Tjänsten {$name} är avinstallerad
+cli-profile-switch = Nätverket har ändrats, byter till profilen {$profile}
+cli-dry-run-auth-failed = Testkörning: autentiseringen misslyckades: { $error }
+cli-dry-run-negotiation-failed = Testkörning: förhandlingen av tunneln misslyckades: { $error }
+cli-dry-run-complete = Testkörningen är klar, nätverkskonfigurationen ändrades inte.
+setup-server-prompt = Gatewayadress
+setup-probing = Kontrollerar gatewayen { $server }...
+setup-certificate = Certifikat: { $subject }, utfärdat av { $issuer }, giltigt till { $expires }
+setup-fingerprint = SHA-256-fingeravtryck: { $fingerprint }
+setup-certificate-untrusted = Certifikatet är inte betrott: { $error }
+setup-ignore-cert-prompt = Godkänn certifikatet ändå
+setup-login-options = Inloggningstyper som gatewayen erbjuder:
+setup-login-type-prompt = Inloggningstyp, nummer eller id [{ $default }]
+setup-user-prompt = Användarnamn
+setup-password-prompt = Lösenord
+setup-store-password-prompt = Spara lösenordet i nyckelringen
+setup-default-route-prompt = Dirigera all trafik genom tunneln (full tunnel)
+setup-dns-mode-prompt = DNS-läge, split eller full [split]
+setup-overwrite-prompt = { $path } finns redan, skriva över den
+setup-cancelled = Installationen avbröts, den befintliga konfigurationen är oförändrad
+setup-config-written = Konfigurationen har skrivits till { $path }
+setup-test-connect-prompt = Testa inloggningen nu
+setup-test-connect-ok = Inloggningen lyckades
+import-config-written = Importerade { $count } inställningar från { $source } till { $path }
+import-unmapped = Inte importerad, rad { $line }: { $key }
+import-login-type-missing = Ingen inloggningstyp importerades, "snx-rs info -s { $server }" listar gatewayens inloggningstyper för alternativet login-type
+prompt-missing-parameters = Konfigurationen saknar obligatoriska parametrar: { $options }
+prompt-save-answers = Spara svaren i { $path }
 
 # Connection Messages
 connection-connected-to = Ansluten till {$server}
@@ -204,6 +364,7 @@ connection-status-disconnected = Frånkopplad
 connection-status-connecting = Ansluter
 connection-status-connected-since = Ansluten sedan: {$since}
 connection-status-mfa-pending = Väntar på MFA: {$mfa_type}
+connection-status-trusted-network = Betrott nätverk upptäckt, VPN pausat
 
 # Login options
 login-options-server-address = Serveradress
@@ -214,3 +375,11 @@ login-options-preferred-protocol = Föredragen protokoll
 login-options-tcpt-port = TCPT-port
 login-options-natt-port = NATT-port
 login-options-internal-ca-fingerprint = Internt CA-fingeravtryck
+login-options-protocol-version = Protokollversion
+login-options-ipsec-transport = IPSec-transport
+login-options-saml-enabled = SAML aktiverat
+login-options-certificate-subject = Certifikatets ämne
+login-options-certificate-issuer = Certifikatets utfärdare
+login-options-certificate-expires = Certifikatet upphör
+login-options-certificate-fingerprint = Certifikatets SHA-256-fingeravtryck
+login-options-certificate-error = Certifikatet är inte betrott
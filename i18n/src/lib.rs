@@ -23,11 +23,11 @@ macro_rules! tr {
         i18n::translate($message_id)
     };
 
-    ($message_id:literal, $($key:ident = $value:expr),*) => {
+    ($message_id:literal, $($key:ident = $value:expr),* $(,)?) => {
         {
                 i18n::translate_with_args(
                     $message_id,
-                    [$((std::borrow::Cow::Borrowed(stringify!($key)), $value.to_string().into()))*])
+                    [$((std::borrow::Cow::Borrowed(stringify!($key)), $value.to_string().into())),*])
         }
     };
 }
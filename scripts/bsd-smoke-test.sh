@@ -0,0 +1,53 @@
+#!/bin/sh
+
+# Smoke test for the FreeBSD/OpenBSD backend, must be run as root with a working profile:
+#   scripts/bsd-smoke-test.sh /path/to/snx-rs /path/to/profile.conf [probe-host]
+# The tunnel is started in the standalone mode, the device, routes and resolv.conf are checked
+# while it is running and again after it is stopped.
+
+set -u
+
+snx="$1"
+conf="$2"
+probe="${3:-}"
+log="$(mktemp)"
+failed=0
+
+check() {
+    if eval "$2" >/dev/null 2>&1; then
+        echo "PASS: $1"
+    else
+        echo "FAIL: $1"
+        failed=1
+    fi
+}
+
+cp /etc/resolv.conf /tmp/resolv.conf.before
+
+"$snx" -m standalone -c "$conf" -l debug >"$log" 2>&1 &
+pid=$!
+
+sleep 15
+
+dev="$(grep -o 'Created tun device: [a-z0-9]*' "$log" | awk '{print $4}')"
+
+check "tun device created" "test -n '$dev' && ifconfig '$dev'"
+check "device has an inet address" "ifconfig '$dev' | grep -q 'inet '"
+check "routes via the device" "netstat -rn -f inet | grep -q '$dev'"
+check "resolv.conf replaced" "grep -q 'Generated by snx-rs' /etc/resolv.conf"
+if [ -n "$probe" ]; then
+    check "probe host reachable" "ping -c 3 '$probe'"
+fi
+
+kill "$pid"
+wait "$pid"
+
+check "tun device removed" "! ifconfig '$dev'"
+check "routes removed" "! netstat -rn -f inet | grep -q '$dev'"
+check "resolv.conf restored" "cmp /etc/resolv.conf /tmp/resolv.conf.before"
+
+if [ "$failed" -ne 0 ]; then
+    echo "Log: $log"
+fi
+
+exit "$failed"
@@ -7,5 +7,5 @@ else
     targets="$1"
 fi
 for target in $targets; do
-    cargo zigbuild --target=${target}.2.17 --profile=lto --features vendored-openssl
+    cargo zigbuild --target=${target}.2.17 --profile=lto --features vendored-openssl --features snx-rs/dbus-service --features snx-rs/desktop-notifications --features snx-rs/journald
 done
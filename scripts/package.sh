@@ -5,7 +5,7 @@ target="$basedir/target"
 version="$(git -C "$basedir" describe)"
 arches="x86_64 aarch64"
 apps="snx-rs snxctl snx-rs-gui"
-assets="snx-rs.conf snx-rs.service snx-rs-gui.desktop"
+assets="snx-rs.conf snx-rs.service snx-rs-gui.desktop org.snx_rs.policy org.snx_rs.rules org.snx_rs.Tunnel1.conf"
 
 for arch in $arches; do
     name="snx-rs-$version-linux-$arch"
@@ -301,10 +301,14 @@ async fn do_connect(
         let message = tr!("app-connection-error");
         let _ = GtkPrompt.show_notification(&message, &e.to_string()).await;
         status = controller.command(ServiceCommand::Status, params).await;
-    } else if let Ok(ConnectionStatus::Connected(_)) = status {
+    } else if let Ok(ConnectionStatus::Connected(ref info)) = status {
+        // with a failover list the gateway may be another one than the first
         let message = tr!("app-connection-success");
         let _ = GtkPrompt
-            .show_notification(&message, &tr!("connection-connected-to", server = params.server_name))
+            .show_notification(
+                &message,
+                &tr!("connection-connected-to", server = info.server_name.as_str()),
+            )
             .await;
     };
 
@@ -185,7 +185,10 @@ impl SettingsDialog {
         button_box.append(&apply_button);
         button_box.append(&cancel_button);
 
-        let server_name = gtk4::Entry::builder().text(&params.server_name).hexpand(true).build();
+        let server_name = gtk4::Entry::builder()
+            .text(params.gateways().join(","))
+            .hexpand(true)
+            .build();
 
         let fetch_info = gtk4::Button::builder()
             .label(tr!("button-fetch-info"))
@@ -389,11 +392,11 @@ impl SettingsDialog {
                     auth_type.set_sensitive(false);
                 } else {
                     dialog.set_sensitive(false);
-                    let params = TunnelParams {
-                        server_name: server_name.text().into(),
+                    let mut params = TunnelParams {
                         ignore_server_cert: no_cert_check.is_active(),
                         ..(*params2).clone()
                     };
+                    params.set_server_names(&server_name.text());
                     let params2 = params2.clone();
 
                     let (tx, rx) = async_channel::bounded(1);
@@ -553,7 +556,7 @@ impl SettingsDialog {
 
     pub fn save(&self) -> anyhow::Result<TunnelParams> {
         let mut params = (*self.params).clone();
-        params.server_name = self.widgets.server_name.text().into();
+        params.set_server_names(&self.widgets.server_name.text());
         params.login_type = self.widgets.auth_type.active_id().unwrap_or_default().into();
         params.tunnel_type = match self.widgets.tunnel_type.active().unwrap_or_default() {
             0 => TunnelType::Ipsec,
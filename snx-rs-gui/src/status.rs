@@ -144,7 +144,10 @@ pub async fn show_status_dialog(sender: Sender<TrayEvent>, params: Arc<TunnelPar
             connect.set_sensitive(matches!(*status, Ok(ConnectionStatus::Disconnected)));
             disconnect.set_sensitive(matches!(
                 *status,
-                Ok(ConnectionStatus::Connected(_) | ConnectionStatus::Connecting | ConnectionStatus::Mfa(_))
+                Ok(ConnectionStatus::Connected(_)
+                    | ConnectionStatus::Connecting
+                    | ConnectionStatus::Mfa(_)
+                    | ConnectionStatus::TrustedNetwork)
             ));
 
             let mut child = inner.first_child();
@@ -124,7 +124,7 @@ impl AppTray {
 
         let data = match &*self.status {
             Ok(ConnectionStatus::Connected(_)) => theme.connected.clone(),
-            Ok(ConnectionStatus::Disconnected) => theme.disconnected.clone(),
+            Ok(ConnectionStatus::Disconnected | ConnectionStatus::TrustedNetwork) => theme.disconnected.clone(),
             Ok(ConnectionStatus::Mfa(_) | ConnectionStatus::Connecting) => theme.acquiring.clone(),
             _ => theme.error.clone(),
         };
@@ -139,7 +139,9 @@ impl AppTray {
     fn icon_name(&self) -> &'static str {
         match &*self.status {
             Ok(ConnectionStatus::Connected(_)) => "network-vpn-symbolic",
-            Ok(ConnectionStatus::Disconnected) => "network-vpn-disconnected-symbolic",
+            Ok(ConnectionStatus::Disconnected | ConnectionStatus::TrustedNetwork) => {
+                "network-vpn-disconnected-symbolic"
+            }
             Ok(ConnectionStatus::Mfa(_) | ConnectionStatus::Connecting) => "network-vpn-acquiring-symbolic",
             _ => "network-vpn-disabled-symbolic",
         }
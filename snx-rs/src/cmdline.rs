@@ -1,15 +1,63 @@
-use std::{net::Ipv4Addr, path::PathBuf, time::Duration};
+use std::{
+    mem,
+    net::{Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    time::Duration,
+};
 
-use clap::Parser;
-use ipnet::Ipv4Net;
-use snxcore::model::params::{CertType, OperationMode, TunnelParams, TunnelType};
+use anyhow::Context;
+
+use clap::{Args, CommandFactory, Parser, Subcommand, error::ErrorKind};
+use ipnet::{IpNet, Ipv4Net};
+use snxcore::{
+    model::params::{
+        AddressConflictAction, CertType, DbusService, DnsBackend, DnsMode, DnsOverride, Ipv6BlockMode, LogFormat,
+        LogTarget, MssClampMode, NetworkManagerMode, NotifyEvent, OperationMode, ProxyMode, Retries, Timeouts,
+        TrustedNetwork, TunnelParams, TunnelType,
+    },
+    util,
+};
 use tracing::level_filters::LevelFilter;
 
+use crate::exit_code::EXIT_CODES_HELP;
+
+fn parse_device_name(name: &str) -> anyhow::Result<String> {
+    util::validate_device_name(name)?;
+    Ok(name.to_owned())
+}
+
+fn parse_namespace_name(name: &str) -> anyhow::Result<String> {
+    util::validate_namespace_name(name)?;
+    Ok(name.to_owned())
+}
+
+fn parse_log_filter(filter: &str) -> anyhow::Result<String> {
+    util::parse_log_filter(filter)?;
+    Ok(filter.to_owned())
+}
+
+fn parse_timeout(option: &str) -> anyhow::Result<(String, String)> {
+    let (name, value) = option.split_once('=').context("expected <name>=<seconds>")?;
+    Timeouts::default().set(name, value)?;
+    Ok((name.to_owned(), value.to_owned()))
+}
+
+fn parse_retry(option: &str) -> anyhow::Result<(String, String)> {
+    let (name, value) = option.split_once('=').context("expected <name>=<value>")?;
+    Retries::default().set(name, value)?;
+    Ok((name.to_owned(), value.to_owned()))
+}
+
 #[derive(Parser)]
-#[clap(about = "VPN client for Check Point security gateway", name = "snx-rs", version = env!("CARGO_PKG_VERSION"))]
+#[clap(
+    about = "VPN client for Check Point security gateway",
+    name = "snx-rs",
+    version = env!("CARGO_PKG_VERSION"),
+    after_help = EXIT_CODES_HELP
+)]
 pub struct CmdlineParams {
-    #[clap(long = "server-name", short = 's', help = "Server name")]
-    pub server_name: Option<String>,
+    #[clap(subcommand)]
+    pub command: Option<CliCommand>,
 
     #[clap(
         long = "mode",
@@ -19,34 +67,710 @@ pub struct CmdlineParams {
     )]
     pub mode: OperationMode,
 
+    #[clap(
+        long = "config-file",
+        short = 'c',
+        visible_alias = "config",
+        help = "Read parameters from config file instead of the layered config.toml files, TOML format is used for the .toml extension",
+        global = true
+    )]
+    pub config_file: Option<PathBuf>,
+
+    #[clap(
+        long = "profile",
+        short = 'P',
+        help = "Named profile of the TOML configuration to use instead of its default-profile",
+        global = true
+    )]
+    pub profile: Option<String>,
+
+    #[clap(
+        long = "cleanup",
+        help = "Restore the DNS, routes and devices left behind by a crashed session of the selected profile, or of all profiles without a configuration file or profile, and exit"
+    )]
+    pub cleanup: bool,
+
+    #[clap(
+        long = "daemon",
+        help = "Detach from the terminal and run in the background with a pidfile, for the init systems without service supervision",
+        global = true
+    )]
+    pub daemon: bool,
+
+    #[clap(
+        long = "dry-run",
+        help = "Authenticate and negotiate the tunnel, print the office mode address, DNS and routes it would configure, then disconnect without touching the network",
+        global = true
+    )]
+    pub dry_run: bool,
+
+    #[clap(
+        long = "replace",
+        help = "Stop the standalone tunnel already running for the same profile instead of refusing to start",
+        global = true
+    )]
+    pub replace: bool,
+
+    #[clap(
+        long = "non-interactive",
+        help = "Never ask for the required parameters missing from the configuration, fail with their names instead"
+    )]
+    pub non_interactive: bool,
+
+    #[clap(long = "completions", help = "Generate shell completions for the given shell")]
+    pub completions: Option<clap_complete::Shell>,
+
+    #[clap(flatten)]
+    pub tunnel: TunnelArgs,
+}
+
+#[derive(Subcommand)]
+pub enum CliCommand {
+    #[clap(
+        name = "connect",
+        about = "Connect a tunnel through the command mode service, or in this process with --foreground"
+    )]
+    Connect {
+        #[clap(
+            long = "foreground",
+            short = 'F',
+            help = "Run the tunnel in this process like the standalone mode"
+        )]
+        foreground: bool,
+
+        #[clap(flatten)]
+        tunnel: Box<TunnelArgs>,
+    },
+    #[clap(
+        name = "disconnect",
+        about = "Disconnect the tunnel of the command mode service, or stop the standalone tunnel of the profile through its control socket or pidfile"
+    )]
+    Disconnect {
+        #[clap(
+            long = "timeout",
+            default_value = "30",
+            help = "Seconds to wait for the standalone tunnel to restore the network settings and exit"
+        )]
+        timeout: u64,
+
+        #[clap(
+            long = "force",
+            help = "Kill the standalone tunnel which does not exit in time and restore the network settings it left behind"
+        )]
+        force: bool,
+    },
+    #[clap(name = "status", about = "Show the connection status of the command mode service")]
+    Status {
+        #[clap(long = "json", help = "Print the status in JSON format")]
+        json: bool,
+    },
+    #[clap(
+        name = "info",
+        about = "Show the login options, protocols and certificate of the gateway without logging in"
+    )]
+    Info {
+        #[clap(long = "json", help = "Print the gateway information in JSON format")]
+        json: bool,
+        #[clap(flatten)]
+        tunnel: Box<TunnelArgs>,
+    },
+    #[clap(
+        name = "service",
+        about = "Run the privileged service which owns the devices, routes and DNS, same as -m command"
+    )]
+    Service,
+    #[clap(
+        name = "cleanup",
+        about = "Restore the DNS, routes and devices left behind by a crashed session and exit"
+    )]
+    Cleanup,
+    #[clap(
+        name = "setup",
+        about = "Create the configuration step by step, the options answer the questions in advance"
+    )]
+    Setup {
+        #[clap(flatten)]
+        setup: SetupArgs,
+    },
+    #[clap(
+        name = "config",
+        about = "Inspect the effective configuration or import the legacy settings"
+    )]
+    Config {
+        #[clap(subcommand)]
+        command: ConfigCommand,
+    },
+    #[cfg(windows)]
+    #[clap(
+        name = "install-service",
+        about = "Register the standalone tunnel of the selected configuration and profile as a Windows service started at boot"
+    )]
+    InstallService {
+        #[clap(
+            long = "service-name",
+            default_value = "snx-rs",
+            help = "Name of the Windows service"
+        )]
+        name: String,
+    },
+    #[cfg(windows)]
+    #[clap(name = "uninstall-service", about = "Stop and remove the Windows service")]
+    UninstallService {
+        #[clap(
+            long = "service-name",
+            default_value = "snx-rs",
+            help = "Name of the Windows service"
+        )]
+        name: String,
+    },
+    /// Entry point of the Windows service, started by the service control manager.
+    #[cfg(windows)]
+    #[clap(name = "run-service", hide = true)]
+    RunService {
+        #[clap(long = "service-name", default_value = "snx-rs")]
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum ConfigCommand {
+    #[clap(
+        name = "dump",
+        about = "Print the merged configuration file, environment and command line options as TOML, with the secrets redacted and the source of every value"
+    )]
+    Dump,
+    #[clap(
+        name = "import",
+        about = "Convert the settings of the legacy snx client to a commented TOML configuration and report the ones without an equivalent"
+    )]
+    Import {
+        #[clap(
+            long = "from-snxrc",
+            value_name = "PATH",
+            num_args = 0..=1,
+            required = true,
+            help = "Legacy settings file [default: ~/.snxrc]"
+        )]
+        from_snxrc: Option<Option<PathBuf>>,
+
+        #[clap(
+            long = "force",
+            help = "Overwrite the existing configuration file, a profile is added to the existing file instead"
+        )]
+        force: bool,
+    },
+}
+
+/// Answers to the questions of the setup wizard, every one of them can be given in advance.
+#[derive(Args, Default)]
+pub struct SetupArgs {
+    #[clap(long = "server-name", short = 's', help = "Gateway address")]
+    pub server_name: Option<String>,
+
     #[clap(long = "user-name", short = 'u', help = "User name")]
     pub user_name: Option<String>,
 
-    #[clap(long = "password", short = 'p', help = "Password in base64-encoded form")]
+    #[clap(
+        long = "login-type",
+        short = 'o',
+        help = "Login type, one of the ids listed by the gateway"
+    )]
+    pub login_type: Option<String>,
+
+    #[clap(
+        long = "ignore-server-cert",
+        help = "Accept a gateway certificate which is not trusted by the system"
+    )]
+    pub ignore_server_cert: Option<bool>,
+
+    #[clap(long = "store-password", help = "Store the password in the OS keychain")]
+    pub store_password: Option<bool>,
+
+    #[clap(
+        long = "password",
+        short = 'p',
+        help = "Password in base64-encoded form, stored in the keychain, never in the configuration file"
+    )]
     pub password: Option<String>,
 
     #[clap(
-        long = "password-factor",
-        short = 'Y',
-        help = "Numerical index of the password factor, 1..N [default: 1]"
+        long = "default-route",
+        short = 't',
+        help = "Full tunnel: route all traffic through the tunnel instead of the gateway routes only"
     )]
-    pub password_factor: Option<usize>,
+    pub default_route: Option<bool>,
 
-    #[clap(long = "config-file", short = 'c', help = "Read parameters from config file")]
-    pub config_file: Option<PathBuf>,
+    #[clap(long = "dns-mode", help = "DNS mode, one of: split, full")]
+    pub dns_mode: Option<DnsMode>,
+
+    #[clap(long = "test-connect", help = "Log in with the new configuration and log out again")]
+    pub test_connect: Option<bool>,
+
+    #[clap(
+        long = "force",
+        short = 'f',
+        help = "Overwrite an existing configuration without asking"
+    )]
+    pub force: bool,
 
+    #[clap(
+        long = "non-interactive",
+        help = "Never ask, fail when the gateway address is missing and use the defaults for the other answers"
+    )]
+    pub non_interactive: bool,
+}
+
+/// Request to the running command mode service.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ServiceAction {
+    Connect,
+    Disconnect { timeout: Duration, force: bool },
+    Status { json: bool },
+}
+
+impl CliCommand {
+    pub fn service_action(&self) -> Option<ServiceAction> {
+        match self {
+            Self::Connect { foreground: false, .. } => Some(ServiceAction::Connect),
+            Self::Disconnect { timeout, force } => Some(ServiceAction::Disconnect {
+                timeout: Duration::from_secs(*timeout),
+                force: *force,
+            }),
+            Self::Status { json } => Some(ServiceAction::Status { json: *json }),
+            _ => None,
+        }
+    }
+}
+
+/// Options which override the parameters from the configuration, grouped by topic in the help output.
+#[derive(Args, Default)]
+pub struct TunnelArgs {
     #[clap(
         long = "log-level",
         short = 'l',
-        help = "Enable logging to stdout, one of: off, info, warn, error, debug, trace"
+        help = "Enable logging to stdout, one of: off, info, warn, error, debug, trace",
+        help_heading = "General"
     )]
     pub log_level: Option<LevelFilter>,
 
+    #[clap(
+        long = "log-filter",
+        value_parser = parse_log_filter,
+        help = "Log levels per module, as comma-separated target=level directives, overrides the log level",
+        help_heading = "General"
+    )]
+    pub log_filter: Option<String>,
+
+    #[clap(
+        long = "no-keychain",
+        short = 'K',
+        help = "Do not use OS keychain to store or retrieve user password",
+        help_heading = "General"
+    )]
+    pub no_keychain: Option<bool>,
+
+    #[clap(
+        long = "skip-permission-check",
+        help = "Do not check the privileges needed by the selected options before connecting",
+        help_heading = "General"
+    )]
+    pub skip_permission_check: Option<bool>,
+
+    #[clap(
+        long = "insecure-permissions",
+        help = "Use the configuration files, private keys and sessions holding secrets even if other users can access them",
+        help_heading = "General"
+    )]
+    pub insecure_permissions: bool,
+
+    #[clap(
+        long = "control-socket",
+        help = "Path of the control socket of the standalone mode",
+        help_heading = "General"
+    )]
+    pub control_socket: Option<PathBuf>,
+
+    #[clap(
+        long = "control-socket-group",
+        help = "Group name or gid allowed to use the control socket",
+        help_heading = "General"
+    )]
+    pub control_socket_group: Option<String>,
+
+    #[clap(
+        long = "no-control-socket",
+        help = "Do not listen on the control socket in the standalone mode",
+        help_heading = "General"
+    )]
+    pub no_control_socket: Option<bool>,
+
+    #[clap(
+        long = "state-file",
+        help = "Path of the state file of the standalone mode [default: /run/snx-rs-state.json or $XDG_RUNTIME_DIR/snx-rs-state.json]",
+        help_heading = "General"
+    )]
+    pub state_file: Option<PathBuf>,
+
+    #[clap(
+        long = "no-state-file",
+        help = "Do not write the state file in the standalone mode",
+        help_heading = "General"
+    )]
+    pub no_state_file: Option<bool>,
+
+    #[clap(
+        long = "health-listen",
+        help = "Address and port of the HTTP health endpoints of the standalone mode, disabled by default",
+        help_heading = "General"
+    )]
+    pub health_listen: Option<SocketAddr>,
+
+    #[clap(
+        long = "idle-start",
+        help = "Start the standalone mode disconnected and connect on the first connect command of the control socket",
+        help_heading = "General"
+    )]
+    pub idle_start: Option<bool>,
+
+    #[clap(
+        long = "idle-timeout",
+        help = "Exit the standalone mode after the given number of seconds without a tunnel and without control clients, 0 to never exit",
+        help_heading = "General"
+    )]
+    pub idle_timeout: Option<u64>,
+
+    #[clap(
+        long = "dbus-service",
+        help = "Register the D-Bus service interface of the standalone mode, one of: none, session, system [default: none]",
+        help_heading = "General"
+    )]
+    pub dbus_service: Option<DbusService>,
+
+    #[clap(
+        long = "notify-events",
+        value_delimiter = ',',
+        help = "Show desktop notifications for these tunnel events: connected, reconnecting, disconnected, reauth-failed",
+        help_heading = "General"
+    )]
+    pub notify_events: Vec<NotifyEvent>,
+
+    #[clap(
+        long = "pid-file",
+        help = "Pidfile of the daemon mode [default: /run/snx-rs.pid or $XDG_RUNTIME_DIR/snx-rs.pid]",
+        help_heading = "General"
+    )]
+    pub pid_file: Option<PathBuf>,
+
+    #[clap(
+        long = "log-file",
+        help = "Write the log to this file instead of the standard error output",
+        help_heading = "General"
+    )]
+    pub log_file: Option<PathBuf>,
+
+    #[clap(
+        long = "log-max-size",
+        help = "Rotate the log file at this size in MiB, 0 disables the rotation [default: 10]",
+        help_heading = "General"
+    )]
+    pub log_max_size: Option<u64>,
+
+    #[clap(
+        long = "log-keep",
+        help = "Number of the rotated log files to keep [default: 5]",
+        help_heading = "General"
+    )]
+    pub log_keep: Option<u32>,
+
+    #[clap(
+        long = "log-format",
+        help = "Log output format, one of: text, json [default: text]",
+        help_heading = "General"
+    )]
+    pub log_format: Option<LogFormat>,
+
+    #[clap(
+        long = "log-target",
+        help = "Log destination without a log file, one of: auto, stderr, journald [default: auto]",
+        help_heading = "General"
+    )]
+    pub log_target: Option<LogTarget>,
+
+    #[clap(
+        long = "run-as-user",
+        help = "Switch the standalone tunnel to this user after the network setup",
+        help_heading = "General"
+    )]
+    pub run_as_user: Option<String>,
+
+    #[clap(
+        long = "run-as-group",
+        help = "Group of the unprivileged tunnel [default: primary group of the run-as-user]",
+        help_heading = "General"
+    )]
+    pub run_as_group: Option<String>,
+
+    #[clap(
+        long = "server-name",
+        short = 's',
+        help = "Server name, a comma separated list tries the gateways in order until one of them answers",
+        help_heading = "Connection"
+    )]
+    pub server_name: Option<String>,
+
+    #[clap(
+        long = "failback",
+        help = "Switch back to an earlier gateway of the server name list with the next reconnect once it answers again",
+        help_heading = "Connection"
+    )]
+    pub failback: Option<bool>,
+
+    #[clap(
+        long = "failback-probe-interval",
+        help = "Interval in seconds to probe the earlier gateways while a failover gateway is in use, 0 to never probe [default: 300]",
+        help_heading = "Connection"
+    )]
+    pub failback_probe_interval: Option<u64>,
+
+    #[clap(
+        long = "proxy",
+        help = "HTTP proxy of the connections to the gateway, http://[user:password@]host[:port], or none to ignore the https_proxy environment variable [default: auto]",
+        help_heading = "Connection"
+    )]
+    pub proxy: Option<ProxyMode>,
+
+    #[clap(
+        long = "tunnel-type",
+        short = 'e',
+        help = "Tunnel type, one of: ssl, ipsec",
+        help_heading = "Connection"
+    )]
+    pub tunnel_type: Option<TunnelType>,
+
+    #[clap(
+        long = "ipsec",
+        conflicts_with = "tunnel_type",
+        help = "Shortcut for --tunnel-type ipsec",
+        help_heading = "Connection"
+    )]
+    pub ipsec: bool,
+
+    #[clap(
+        long = "client-mode",
+        short = 'C',
+        help = "Custom client mode [default: secure_connect]",
+        help_heading = "Connection"
+    )]
+    pub client_mode: Option<String>,
+
+    #[clap(
+        long = "ike-lifetime",
+        short = 'L',
+        help = "IPSec IKE lifetime in seconds",
+        help_heading = "Connection"
+    )]
+    pub ike_lifetime: Option<u64>,
+
+    #[clap(
+        long = "ike-persist",
+        short = 'W',
+        help = "Store IKE session to disk and load it automatically",
+        help_heading = "Connection"
+    )]
+    pub ike_persist: Option<bool>,
+
+    #[clap(
+        long = "no-keepalive",
+        short = 'A',
+        help = "Disable IPSec keepalive packets",
+        help_heading = "Connection"
+    )]
+    pub no_keepalive: Option<bool>,
+
+    #[clap(
+        long = "port-knock",
+        short = 'R',
+        help = "Enable port knock workaround for NAT-T probing",
+        help_heading = "Connection"
+    )]
+    pub port_knock: Option<bool>,
+
+    #[clap(
+        long = "timeout",
+        value_parser = parse_timeout,
+        help = "Timeout of a connection phase as <name>=<seconds>, the names are connect, handshake, request, info, hello, keepalive, reconnect and network, can be repeated",
+        help_heading = "Connection"
+    )]
+    pub timeouts: Vec<(String, String)>,
+
+    #[clap(
+        long = "retry",
+        value_parser = parse_retry,
+        help = "Retry option as <name>=<value>, the names are hello, hello-reconnect and reconnect-delay, can be repeated",
+        help_heading = "Connection"
+    )]
+    pub retries: Vec<(String, String)>,
+
+    #[clap(
+        long = "hello-retries",
+        help = "Number of SSL hello retries when the gateway assigns no usable address, same as --retry hello=<n> [default: 3]",
+        help_heading = "Connection"
+    )]
+    pub hello_retries: Option<u32>,
+
+    #[clap(
+        long = "hello-reconnect-attempts",
+        help = "Number of SSL reconnect attempts when the gateway asks the client to reconnect, same as --retry hello-reconnect=<n> [default: 3]",
+        help_heading = "Connection"
+    )]
+    pub hello_reconnect_attempts: Option<u32>,
+
+    #[clap(
+        long = "keepalive-jitter",
+        help = "Random jitter of keepalive interval in percent, 0..50 [default: 10]",
+        help_heading = "Connection"
+    )]
+    pub keepalive_jitter: Option<u8>,
+
+    #[clap(
+        long = "reconnect-on-network-change",
+        help = "Reconnect SSL tunnel when the default network address changes [default: true]",
+        help_heading = "Connection"
+    )]
+    pub reconnect_on_network_change: Option<bool>,
+
+    #[clap(
+        long = "trusted-networks",
+        value_delimiter = ',',
+        help = "Trusted network criteria which suspend the VPN: domain:<name>, gateway-mac:<mac>, probe:<https url>",
+        help_heading = "Connection"
+    )]
+    pub trusted_networks: Vec<TrustedNetwork>,
+
+    #[clap(
+        long = "roaming-timeout",
+        help = "Maximum time in seconds to re-establish the SSL tunnel after a network change, same as --timeout reconnect=<seconds> [default: 60]",
+        help_heading = "Connection"
+    )]
+    pub roaming_timeout: Option<u64>,
+
+    #[clap(
+        long = "wait-for-network",
+        help = "Wait up to the given number of seconds for a default route and the gateway name to resolve before connecting, 0 to connect at once, same as --timeout network=<seconds> [default: 30]",
+        help_heading = "Connection"
+    )]
+    pub wait_for_network: Option<u64>,
+
+    #[clap(
+        long = "reauth-interval",
+        help = "Re-authenticate the SSL tunnel after the given number of seconds, at most the gateway timeout, 0 to follow the gateway",
+        help_heading = "Connection"
+    )]
+    pub reauth_interval: Option<u64>,
+
+    #[clap(
+        long = "dump-hello",
+        help = "Dump the SSL hello reply to the log with credentials redacted",
+        help_heading = "Connection"
+    )]
+    pub dump_hello: Option<bool>,
+
+    #[clap(
+        long = "hello-client-type",
+        help = "Client type reported in the SSL hello [default: 4]",
+        help_heading = "Connection"
+    )]
+    pub hello_client_type: Option<String>,
+
+    #[clap(
+        long = "hello-client-version",
+        help = "Client version reported in the SSL hello [default: 1]",
+        help_heading = "Connection"
+    )]
+    pub hello_client_version: Option<String>,
+
+    #[clap(long = "user-name", short = 'u', help = "User name", help_heading = "Authentication")]
+    pub user_name: Option<String>,
+
+    #[clap(
+        long = "password",
+        short = 'p',
+        help = "Password in base64-encoded form",
+        help_heading = "Authentication"
+    )]
+    pub password: Option<String>,
+
+    #[clap(
+        long = "password-factor",
+        short = 'Y',
+        help = "Numerical index of the password factor, 1..N [default: 1]",
+        help_heading = "Authentication"
+    )]
+    pub password_factor: Option<usize>,
+
+    #[clap(
+        long = "login-type",
+        short = 'o',
+        help = "Login type, obtained from running the 'snx-rs -m info -s address', login_options_list::id field",
+        help_heading = "Authentication"
+    )]
+    pub login_type: Option<String>,
+
+    #[clap(
+        long = "cert-type",
+        short = 'y',
+        help = "Enable certificate authentication via the provided method, one of: pkcs8, pkcs11, pkcs12, none",
+        help_heading = "Authentication"
+    )]
+    pub cert_type: Option<CertType>,
+
+    #[clap(
+        long = "cert-path",
+        short = 'z',
+        help = "Path to PEM file for PKCS8, path to PFX file for PKCS12, path to driver file for PKCS11 token",
+        help_heading = "Authentication"
+    )]
+    pub cert_path: Option<PathBuf>,
+
+    #[clap(
+        long = "cert-password",
+        short = 'x',
+        help = "Password for PKCS12 file or PIN for PKCS11 token",
+        help_heading = "Authentication"
+    )]
+    pub cert_password: Option<String>,
+
+    #[clap(
+        long = "cert-id",
+        short = 'w',
+        help = "Certificate ID in hexadecimal form",
+        help_heading = "Authentication"
+    )]
+    pub cert_id: Option<String>,
+
+    #[clap(
+        long = "ca-cert",
+        short = 'k',
+        value_delimiter = ',',
+        help = "Custom CA certificates in PEM or DER format",
+        help_heading = "Authentication"
+    )]
+    pub ca_cert: Vec<PathBuf>,
+
+    #[clap(
+        long = "ignore-server-cert",
+        short = 'X',
+        help = "Disable all certificate validations (NOT SECURE!)",
+        help_heading = "Authentication"
+    )]
+    pub ignore_server_cert: Option<bool>,
+
     #[clap(
         long = "search-domains",
         short = 'd',
         value_delimiter = ',',
-        help = "Additional search domains"
+        help = "Additional search domains",
+        help_heading = "DNS"
     )]
     pub search_domains: Vec<String>,
 
@@ -54,7 +778,8 @@ pub struct CmdlineParams {
         long = "ignore-search-domains",
         short = 'i',
         value_delimiter = ',',
-        help = "Ignore specified search domains from the acquired list"
+        help = "Ignore specified search domains from the acquired list",
+        help_heading = "DNS"
     )]
     pub ignore_search_domains: Vec<String>,
 
@@ -62,7 +787,8 @@ pub struct CmdlineParams {
         long = "dns-servers",
         short = 'D',
         value_delimiter = ',',
-        help = "Additional DNS servers"
+        help = "Additional DNS servers",
+        help_heading = "DNS"
     )]
     pub dns_servers: Vec<Ipv4Addr>,
 
@@ -70,32 +796,91 @@ pub struct CmdlineParams {
         long = "ignore-dns-servers",
         short = 'G',
         value_delimiter = ',',
-        help = "Ignore specified DNS servers from the acquired list"
+        help = "Ignore specified DNS servers from the acquired list",
+        help_heading = "DNS"
     )]
     pub ignore_dns_servers: Vec<Ipv4Addr>,
 
     #[clap(
         long = "set-routing-domains",
         short = 'Z',
-        help = "Treat received search domains as routing domains"
+        help = "Treat received search domains as routing domains",
+        help_heading = "DNS"
     )]
     pub set_routing_domains: Option<bool>,
 
+    #[clap(
+        long = "no-dns",
+        short = 'N',
+        help = "Do not change DNS resolver configuration",
+        help_heading = "DNS"
+    )]
+    pub no_dns: Option<bool>,
+
+    #[clap(
+        long = "dns-backend",
+        help = "DNS configuration backend, one of: auto, resolved, resolvconf, direct, none, print [default: auto]",
+        help_heading = "DNS"
+    )]
+    pub dns_backend: Option<DnsBackend>,
+
+    #[clap(
+        long = "dns-mode",
+        help = "DNS mode, one of: split (only tunnel domains use tunnel resolvers), full (all queries) [default: split]",
+        help_heading = "DNS"
+    )]
+    pub dns_mode: Option<DnsMode>,
+
+    #[clap(
+        long = "dns-resolv-path",
+        help = "File written by the direct DNS backend [default: /etc/resolv.conf]",
+        help_heading = "DNS"
+    )]
+    pub dns_resolv_path: Option<PathBuf>,
+
+    #[clap(
+        long = "dns-override",
+        help = "Precedence of the gateway and configured DNS servers and search domains, one of: gateway, config, merged [default: merged]",
+        help_heading = "DNS"
+    )]
+    pub dns_override: Option<DnsOverride>,
+
+    #[clap(
+        long = "dns-probe-name",
+        help = "Internal host name resolved after connecting to check that DNS queries go through the tunnel",
+        help_heading = "DNS"
+    )]
+    pub dns_probe_name: Option<String>,
+
+    #[clap(
+        long = "dns-redirect",
+        help = "Redirect all DNS queries on port 53 to the first tunnel DNS server (Linux only)",
+        help_heading = "DNS"
+    )]
+    pub dns_redirect: Option<bool>,
+
     #[clap(
         long = "default-route",
         short = 't',
-        help = "Set the default route through the tunnel"
+        help = "Set the default route through the tunnel",
+        help_heading = "Routing"
     )]
     pub default_route: Option<bool>,
 
-    #[clap(long = "no-routing", short = 'n', help = "Ignore all routes from the acquired list")]
+    #[clap(
+        long = "no-routing",
+        short = 'n',
+        help = "Ignore all routes from the acquired list",
+        help_heading = "Routing"
+    )]
     pub no_routing: Option<bool>,
 
     #[clap(
         long = "add-routes",
         short = 'a',
         value_delimiter = ',',
-        help = "Additional routes through the tunnel"
+        help = "Additional routes through the tunnel",
+        help_heading = "Routing"
     )]
     pub add_routes: Vec<Ipv4Net>,
 
@@ -103,106 +888,386 @@ pub struct CmdlineParams {
         long = "ignore-routes",
         short = 'I',
         value_delimiter = ',',
-        help = "Ignore specified routes from the acquired list"
+        help = "Ignore specified routes from the acquired list",
+        help_heading = "Routing"
     )]
     pub ignore_routes: Vec<Ipv4Net>,
 
-    #[clap(long = "no-dns", short = 'N', help = "Do not change DNS resolver configuration")]
-    pub no_dns: Option<bool>,
+    #[clap(
+        long = "include-routes",
+        value_delimiter = ',',
+        help = "Route only these prefixes through the tunnel instead of the gateway-provided ranges",
+        help_heading = "Routing"
+    )]
+    pub include_routes: Vec<IpNet>,
 
     #[clap(
-        long = "ignore-server-cert",
-        short = 'X',
-        help = "Disable all certificate validations (NOT SECURE!)"
+        long = "include-routes-merge",
+        help = "Merge include routes with the gateway-provided ranges instead of replacing them",
+        help_heading = "Routing"
     )]
-    pub ignore_server_cert: Option<bool>,
+    pub include_routes_merge: Option<bool>,
 
-    #[clap(long = "tunnel-type", short = 'e', help = "Tunnel type, one of: ssl, ipsec")]
-    pub tunnel_type: Option<TunnelType>,
+    #[clap(
+        long = "exclude-routes",
+        value_delimiter = ',',
+        help = "Prefixes which must never be routed through the tunnel",
+        help_heading = "Routing"
+    )]
+    pub exclude_routes: Vec<Ipv4Net>,
 
     #[clap(
-        long = "ca-cert",
-        short = 'k',
+        long = "route-metric",
+        help = "Metric of the routes installed through the tunnel, default is the system default",
+        help_heading = "Routing"
+    )]
+    pub route_metric: Option<u32>,
+
+    #[clap(
+        long = "routing-table",
+        help = "Routing table for the default route mode [default: 18234]",
+        help_heading = "Routing"
+    )]
+    pub routing_table: Option<u32>,
+
+    #[clap(
+        long = "fwmark",
+        help = "Mark the SSL tunnel socket and select the default route table by the mark instead of the gateway address, Linux only",
+        help_heading = "Routing"
+    )]
+    pub fwmark: Option<u32>,
+
+    #[clap(
+        long = "route-monitor",
+        help = "Re-install the tunnel routes when another process removes them [default: true]",
+        help_heading = "Routing"
+    )]
+    pub route_monitor: Option<bool>,
+
+    #[clap(
+        long = "route-domains",
         value_delimiter = ',',
-        help = "Custom CA certificates in PEM or DER format"
+        help = "Host names which are resolved through the tunnel DNS and routed through the tunnel",
+        help_heading = "Routing"
     )]
-    pub ca_cert: Vec<PathBuf>,
+    pub route_domains: Vec<String>,
 
     #[clap(
-        long = "login-type",
-        short = 'o',
-        help = "Login type, obtained from running the 'snx-rs -m info -s address', login_options_list::id field"
+        long = "route-domains-refresh",
+        help = "Interval in seconds to re-resolve the route domains, 0 to resolve them only once [default: 0]",
+        help_heading = "Routing"
     )]
-    pub login_type: Option<String>,
+    pub route_domains_refresh: Option<u64>,
 
     #[clap(
-        long = "cert-type",
-        short = 'y',
-        help = "Enable certificate authentication via the provided method, one of: pkcs8, pkcs11, pkcs12, none"
+        long = "address-conflict",
+        help = "Action when the office mode address or routes overlap local networks, one of: warn, skip-routes, renew [default: warn]",
+        help_heading = "Routing"
     )]
-    pub cert_type: Option<CertType>,
+    pub address_conflict: Option<AddressConflictAction>,
 
     #[clap(
-        long = "cert-path",
-        short = 'z',
-        help = "Path to PEM file for PKCS8, path to PFX file for PKCS12, path to driver file for PKCS11 token"
+        long = "forward-source",
+        help = "Share the tunnel with the LAN hosts from this prefix, they are masqueraded behind the tunnel address",
+        help_heading = "Routing"
     )]
-    pub cert_path: Option<PathBuf>,
+    pub forward_source: Option<Ipv4Net>,
 
     #[clap(
-        long = "cert-password",
-        short = 'x',
-        help = "Password for PKCS12 file or PIN for PKCS11 token"
+        long = "app-route-cgroups",
+        value_delimiter = ',',
+        help = "Route only the traffic of these cgroup v2 paths through the tunnel (Linux only)",
+        help_heading = "Routing"
     )]
-    pub cert_password: Option<String>,
+    pub app_route_cgroups: Vec<String>,
 
-    #[clap(long = "cert-id", short = 'w', help = "Certificate ID in hexadecimal form")]
-    pub cert_id: Option<String>,
+    #[clap(
+        long = "app-route-uids",
+        value_delimiter = ',',
+        help = "Route only the traffic of these user ids through the tunnel (Linux only)",
+        help_heading = "Routing"
+    )]
+    pub app_route_uids: Vec<u32>,
 
-    #[clap(long = "if-name", short = 'f', help = "Interface name for tun or xfrm device")]
+    #[clap(
+        long = "kill-switch",
+        help = "Block traffic to the tunneled prefixes outside of the tunnel until explicit disconnect",
+        help_heading = "Routing"
+    )]
+    pub kill_switch: Option<bool>,
+
+    #[clap(
+        long = "block-ipv6",
+        help = "Block IPv6 outside the tunnel while connected, one of: off, route, sysctl [default: off]",
+        help_heading = "Routing"
+    )]
+    pub block_ipv6: Option<Ipv6BlockMode>,
+
+    #[clap(
+        long = "if-name",
+        short = 'f',
+        value_parser = parse_device_name,
+        help = "Interface name for tun or xfrm device, %d is replaced with the first free index",
+        help_heading = "Device"
+    )]
     pub if_name: Option<String>,
 
     #[clap(
-        long = "no-keychain",
-        short = 'K',
-        help = "Do not use OS keychain to store or retrieve user password"
+        long = "mtu",
+        help = "MTU of the tunnel device, default is derived from the encapsulation overhead",
+        help_heading = "Device"
+    )]
+    pub mtu: Option<u16>,
+
+    #[clap(
+        long = "persistent-if",
+        help = "Keep the tun device across reconnects and reattach to it, a pre-existing persistent device is reused",
+        help_heading = "Device"
+    )]
+    pub persistent_if: Option<bool>,
+
+    #[clap(
+        long = "tun-queues",
+        help = "Number of tun device queues read in parallel, Linux only [default: 1]",
+        help_heading = "Device"
+    )]
+    pub tun_queues: Option<u16>,
+
+    #[clap(
+        long = "tun-fd",
+        help = "Use an already open tun device file descriptor instead of creating the device, Linux only",
+        help_heading = "Device"
+    )]
+    pub tun_fd: Option<i32>,
+
+    #[clap(
+        long = "no-device-config",
+        help = "Do not configure the addresses and MTU of the tun device, they are managed externally",
+        help_heading = "Device"
+    )]
+    pub no_device_config: Option<bool>,
+
+    #[clap(
+        long = "use-iproute2",
+        help = "Change addresses, routes and MTU with the ip command instead of rtnetlink",
+        help_heading = "Device"
+    )]
+    pub use_iproute2: Option<bool>,
+
+    #[clap(
+        long = "network-manager",
+        help = "NetworkManager integration over D-Bus, one of: auto, on, off [default: auto]",
+        help_heading = "Device"
+    )]
+    pub network_manager: Option<NetworkManagerMode>,
+
+    #[clap(
+        long = "tun-owner",
+        help = "User name or uid which owns the created tun device, Linux only",
+        help_heading = "Device"
+    )]
+    pub tun_owner: Option<String>,
+
+    #[clap(
+        long = "tun-group",
+        help = "Group name or gid which owns the created tun device, Linux only",
+        help_heading = "Device"
+    )]
+    pub tun_group: Option<String>,
+
+    #[clap(
+        long = "tun-persist",
+        help = "Make the created tun device persistent, it is kept after the tunnel exits, Linux only",
+        help_heading = "Device"
+    )]
+    pub tun_persist: Option<bool>,
+
+    #[clap(
+        long = "mss-clamp",
+        help = "Clamp the TCP MSS of the traffic routed through the tunnel, one of: auto, on, off [default: auto]",
+        help_heading = "Device"
     )]
-    pub no_keychain: Option<bool>,
+    pub mss_clamp: Option<MssClampMode>,
 
-    #[clap(long = "ike-lifetime", short = 'L', help = "IPSec IKE lifetime in seconds")]
-    pub ike_lifetime: Option<u64>,
+    #[clap(
+        long = "netns",
+        value_parser = parse_namespace_name,
+        help = "Run the tunnel device in the given network namespace, created if it does not exist (Linux only)",
+        help_heading = "Device"
+    )]
+    pub netns: Option<String>,
 
     #[clap(
-        long = "ike-persist",
-        short = 'W',
-        help = "Store IKE session to disk and load it automatically"
+        long = "script-up",
+        help = "Script which is executed after the tunnel is configured",
+        help_heading = "Scripts"
     )]
-    pub ike_persist: Option<bool>,
+    pub script_up: Option<PathBuf>,
 
     #[clap(
-        long = "client-mode",
-        short = 'C',
-        help = "Custom client mode [default: secure_connect]"
+        long = "script-down",
+        help = "Script which is executed before the tunnel is torn down",
+        help_heading = "Scripts"
     )]
-    pub client_mode: Option<String>,
+    pub script_down: Option<PathBuf>,
 
-    #[clap(long = "no-keepalive", short = 'A', help = "Disable IPSec keepalive packets")]
-    pub no_keepalive: Option<bool>,
+    #[clap(
+        long = "script-up-fatal",
+        help = "Disconnect when the up script fails instead of logging a warning",
+        help_heading = "Scripts"
+    )]
+    pub script_up_fatal: Option<bool>,
 
     #[clap(
-        long = "port-knock",
-        short = 'R',
-        help = "Enable port knock workaround for NAT-T probing"
+        long = "script-timeout",
+        help = "Maximum time in seconds the up and down scripts may run, default is 30",
+        help_heading = "Scripts"
     )]
-    pub port_knock: Option<bool>,
-    #[clap(long = "completions", help = "Generate shell completions for the given shell")]
-    pub completions: Option<clap_complete::Shell>,
+    pub script_timeout: Option<u64>,
 }
 
 impl CmdlineParams {
+    /// Parse the command line of the process, with the legacy conventions applied.
+    pub fn from_env() -> Self {
+        let mut params = Self::parse();
+        params.apply_legacy_certificate();
+        params
+    }
+
+    // The legacy snx client takes the PKCS12 certificate with -c, which is the configuration file here.
+    // A .p12 or .pfx file given with it is the certificate, unless --cert-path is also given.
+    fn apply_legacy_certificate(&mut self) {
+        let is_pkcs12 = self
+            .config_file
+            .as_ref()
+            .and_then(|path| path.extension())
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("p12") || ext.eq_ignore_ascii_case("pfx"));
+        if !is_pkcs12 {
+            return;
+        }
+
+        let tunnel = match self.command {
+            Some(CliCommand::Connect { ref mut tunnel, .. }) => tunnel.as_mut(),
+            _ => &mut self.tunnel,
+        };
+        if tunnel.cert_path.is_some() {
+            return;
+        }
+
+        tunnel.cert_path = self.config_file.take();
+        tunnel.cert_type.get_or_insert(CertType::Pkcs12);
+    }
+
+    /// The operation mode and cleanup flag selected by the subcommand or by the older -m and --cleanup options.
+    pub fn mode(&self) -> (OperationMode, bool) {
+        match self.command {
+            Some(CliCommand::Connect { foreground: true, .. }) => (OperationMode::Standalone, false),
+            Some(CliCommand::Info { .. }) => (OperationMode::Info, false),
+            Some(CliCommand::Service) => (OperationMode::Command, false),
+            Some(CliCommand::Cleanup) => (OperationMode::Standalone, true),
+            #[cfg(windows)]
+            Some(CliCommand::RunService { .. }) => (OperationMode::Standalone, false),
+            _ => (self.mode, self.cleanup),
+        }
+    }
+
+    /// Reject the options which contradict each other, clap only knows the conflicts of single flags.
+    pub fn validate(&self) -> Result<(), clap::Error> {
+        if self.command.is_some() && (self.cleanup || self.mode != OperationMode::Standalone) {
+            return Err(conflict("--mode and --cleanup cannot be combined with a subcommand"));
+        }
+
+        // only the tunnel itself and the command mode service run in the background
+        let (mode, cleanup) = self.mode();
+        let service_action = self.command.as_ref().and_then(CliCommand::service_action);
+        let setup = matches!(self.command, Some(CliCommand::Setup { .. } | CliCommand::Config { .. }));
+        if self.daemon && (cleanup || mode == OperationMode::Info || service_action.is_some() || setup) {
+            return Err(conflict(
+                "--daemon cannot be combined with --cleanup, the info mode, the setup, config or the service commands",
+            ));
+        }
+
+        // the dry run needs the tunnel in this process, and it exits right after the negotiation
+        if self.dry_run
+            && (self.daemon || cleanup || mode != OperationMode::Standalone || service_action.is_some() || setup)
+        {
+            return Err(conflict(
+                "--dry-run only works with the standalone tunnel, without --daemon or --cleanup",
+            ));
+        }
+
+        // only the standalone tunnel holds the lock of its profile
+        if self.replace
+            && (self.dry_run || cleanup || mode != OperationMode::Standalone || service_action.is_some() || setup)
+        {
+            return Err(conflict(
+                "--replace only works with the standalone tunnel, without --dry-run or --cleanup",
+            ));
+        }
+
+        self.tunnel.validate()?;
+
+        match self.command {
+            Some(CliCommand::Connect { ref tunnel, .. }) | Some(CliCommand::Info { ref tunnel, .. }) => {
+                tunnel.validate()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Merge the options given before and after the subcommand, the latter win.
+    pub fn merge_into_tunnel_params(&mut self, other: &mut TunnelParams) {
+        mem::take(&mut self.tunnel).merge_into_tunnel_params(other);
+
+        match self.command {
+            Some(CliCommand::Connect { ref mut tunnel, .. }) | Some(CliCommand::Info { ref mut tunnel, .. }) => {
+                mem::take(tunnel.as_mut()).merge_into_tunnel_params(other);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn conflict(message: &str) -> clap::Error {
+    CmdlineParams::command().error(ErrorKind::ArgumentConflict, message)
+}
+
+impl TunnelArgs {
+    fn validate(&self) -> Result<(), clap::Error> {
+        if self.no_routing == Some(true) {
+            if self.default_route == Some(true) {
+                return Err(conflict("--no-routing cannot be combined with --default-route"));
+            }
+            if !self.add_routes.is_empty() || !self.include_routes.is_empty() {
+                return Err(conflict(
+                    "--no-routing cannot be combined with --add-routes or --include-routes",
+                ));
+            }
+        }
+
+        if self.no_dns == Some(true) && self.dns_redirect == Some(true) {
+            return Err(conflict("--no-dns cannot be combined with --dns-redirect"));
+        }
+
+        Ok(())
+    }
+
     pub fn merge_into_tunnel_params(self, other: &mut TunnelParams) {
         if let Some(server_name) = self.server_name {
-            other.server_name = server_name;
+            other.set_server_names(&server_name);
+        }
+
+        if let Some(failback) = self.failback {
+            other.failback = failback;
+        }
+
+        if let Some(failback_probe_interval) = self.failback_probe_interval {
+            other.failback_probe_interval = Duration::from_secs(failback_probe_interval);
+        }
+
+        if let Some(proxy) = self.proxy {
+            other.proxy = proxy;
         }
 
         if let Some(user_name) = self.user_name {
@@ -222,6 +1287,10 @@ impl CmdlineParams {
             other.log_level = log_level.to_string();
         }
 
+        if let Some(log_filter) = self.log_filter {
+            other.log_filter = Some(log_filter);
+        }
+
         if !self.search_domains.is_empty() {
             other.search_domains = self.search_domains;
         }
@@ -266,6 +1335,10 @@ impl CmdlineParams {
             other.tunnel_type = tunnel_type;
         }
 
+        if self.ipsec {
+            other.tunnel_type = TunnelType::Ipsec;
+        }
+
         if !self.ca_cert.is_empty() {
             other.ca_cert = self.ca_cert;
         }
@@ -321,5 +1394,604 @@ impl CmdlineParams {
         if let Some(port_knock) = self.port_knock {
             other.port_knock = port_knock;
         }
+
+        if let Some(hello_retries) = self.hello_retries {
+            other.retries.hello = hello_retries;
+        }
+
+        if let Some(hello_reconnect_attempts) = self.hello_reconnect_attempts {
+            other.retries.hello_reconnect = hello_reconnect_attempts;
+        }
+
+        if let Some(keepalive_jitter) = self.keepalive_jitter {
+            other.keepalive_jitter = keepalive_jitter;
+        }
+
+        if let Some(reconnect_on_network_change) = self.reconnect_on_network_change {
+            other.reconnect_on_network_change = reconnect_on_network_change;
+        }
+
+        if !self.trusted_networks.is_empty() {
+            other.trusted_networks = self.trusted_networks;
+        }
+
+        if let Some(roaming_timeout) = self.roaming_timeout {
+            other.timeouts.reconnect = Duration::from_secs(roaming_timeout);
+        }
+
+        if let Some(wait_for_network) = self.wait_for_network {
+            other.timeouts.network = Duration::from_secs(wait_for_network);
+        }
+
+        // the values are checked by the parser
+        for (name, value) in self.timeouts {
+            let _ = other.timeouts.set(&name, &value);
+        }
+
+        for (name, value) in self.retries {
+            let _ = other.retries.set(&name, &value);
+        }
+
+        if let Some(reauth_interval) = self.reauth_interval {
+            other.reauth_interval = (reauth_interval > 0).then(|| Duration::from_secs(reauth_interval));
+        }
+
+        if let Some(dump_hello) = self.dump_hello {
+            other.dump_hello = dump_hello;
+        }
+
+        if let Some(hello_client_type) = self.hello_client_type {
+            other.hello_client_type = hello_client_type;
+        }
+
+        if let Some(hello_client_version) = self.hello_client_version {
+            other.hello_client_version = hello_client_version;
+        }
+
+        if let Some(dns_backend) = self.dns_backend {
+            other.dns_backend = dns_backend;
+        }
+
+        if let Some(dns_mode) = self.dns_mode {
+            other.dns_mode = dns_mode;
+        }
+
+        if let Some(dns_resolv_path) = self.dns_resolv_path {
+            other.dns_resolv_path = Some(dns_resolv_path);
+        }
+
+        if !self.include_routes.is_empty() {
+            other.include_routes = self.include_routes;
+        }
+
+        if let Some(include_routes_merge) = self.include_routes_merge {
+            other.include_routes_merge = include_routes_merge;
+        }
+
+        if !self.exclude_routes.is_empty() {
+            other.exclude_routes = self.exclude_routes;
+        }
+
+        if let Some(mtu) = self.mtu {
+            other.mtu = Some(mtu);
+        }
+
+        if let Some(route_metric) = self.route_metric {
+            other.route_metric = Some(route_metric);
+        }
+
+        if let Some(kill_switch) = self.kill_switch {
+            other.kill_switch = kill_switch;
+        }
+
+        if let Some(script_up) = self.script_up {
+            other.script_up = Some(script_up);
+        }
+
+        if let Some(script_down) = self.script_down {
+            other.script_down = Some(script_down);
+        }
+
+        if let Some(script_up_fatal) = self.script_up_fatal {
+            other.script_up_fatal = script_up_fatal;
+        }
+
+        if let Some(script_timeout) = self.script_timeout {
+            other.script_timeout = Duration::from_secs(script_timeout);
+        }
+
+        if let Some(address_conflict) = self.address_conflict {
+            other.address_conflict = address_conflict;
+        }
+
+        if let Some(persistent_if) = self.persistent_if {
+            other.persistent_if = persistent_if;
+        }
+
+        if let Some(tun_queues) = self.tun_queues {
+            other.tun_queues = tun_queues;
+        }
+
+        if let Some(tun_fd) = self.tun_fd {
+            other.tun_fd = Some(tun_fd);
+        }
+
+        if let Some(no_device_config) = self.no_device_config {
+            other.no_device_config = no_device_config;
+        }
+
+        if let Some(use_iproute2) = self.use_iproute2 {
+            other.use_iproute2 = use_iproute2;
+        }
+
+        if let Some(network_manager) = self.network_manager {
+            other.network_manager = network_manager;
+        }
+
+        if let Some(routing_table) = self.routing_table {
+            other.routing_table = routing_table;
+        }
+
+        if let Some(fwmark) = self.fwmark {
+            other.fwmark = Some(fwmark);
+        }
+
+        if let Some(route_monitor) = self.route_monitor {
+            other.route_monitor = route_monitor;
+        }
+
+        if let Some(dns_probe_name) = self.dns_probe_name {
+            other.dns_probe_name = Some(dns_probe_name);
+        }
+
+        if let Some(forward_source) = self.forward_source {
+            other.forward_source = Some(forward_source);
+        }
+
+        if !self.route_domains.is_empty() {
+            other.route_domains = self.route_domains;
+        }
+
+        if let Some(route_domains_refresh) = self.route_domains_refresh {
+            other.route_domains_refresh = Duration::from_secs(route_domains_refresh);
+        }
+
+        if let Some(tun_owner) = self.tun_owner {
+            other.tun_owner = Some(tun_owner);
+        }
+
+        if let Some(tun_group) = self.tun_group {
+            other.tun_group = Some(tun_group);
+        }
+
+        if let Some(tun_persist) = self.tun_persist {
+            other.tun_persist = tun_persist;
+        }
+
+        if let Some(block_ipv6) = self.block_ipv6 {
+            other.block_ipv6 = block_ipv6;
+        }
+
+        if let Some(dns_override) = self.dns_override {
+            other.dns_override = dns_override;
+        }
+
+        if let Some(skip_permission_check) = self.skip_permission_check {
+            other.skip_permission_check = skip_permission_check;
+        }
+
+        if self.insecure_permissions {
+            other.insecure_permissions = true;
+        }
+
+        if let Some(control_socket) = self.control_socket {
+            other.control_socket = Some(control_socket);
+        }
+
+        if let Some(control_socket_group) = self.control_socket_group {
+            other.control_socket_group = Some(control_socket_group);
+        }
+
+        if let Some(no_control_socket) = self.no_control_socket {
+            other.no_control_socket = no_control_socket;
+        }
+
+        if let Some(state_file) = self.state_file {
+            other.state_file = Some(state_file);
+        }
+
+        if let Some(no_state_file) = self.no_state_file {
+            other.no_state_file = no_state_file;
+        }
+
+        if let Some(health_listen) = self.health_listen {
+            other.health_listen = Some(health_listen);
+        }
+
+        if let Some(idle_start) = self.idle_start {
+            other.idle_start = idle_start;
+        }
+
+        if let Some(idle_timeout) = self.idle_timeout {
+            other.idle_timeout = (idle_timeout > 0).then(|| Duration::from_secs(idle_timeout));
+        }
+
+        if let Some(dbus_service) = self.dbus_service {
+            other.dbus_service = dbus_service;
+        }
+
+        if !self.notify_events.is_empty() {
+            other.notify_events = self.notify_events;
+        }
+
+        if let Some(pid_file) = self.pid_file {
+            other.pid_file = Some(pid_file);
+        }
+
+        if let Some(log_file) = self.log_file {
+            other.log_file = Some(log_file);
+        }
+
+        if let Some(log_max_size) = self.log_max_size {
+            other.log_max_size = log_max_size;
+        }
+
+        if let Some(log_keep) = self.log_keep {
+            other.log_keep = log_keep;
+        }
+
+        if let Some(log_format) = self.log_format {
+            other.log_format = log_format;
+        }
+
+        if let Some(log_target) = self.log_target {
+            other.log_target = log_target;
+        }
+
+        if let Some(run_as_user) = self.run_as_user {
+            other.run_as_user = Some(run_as_user);
+        }
+
+        if let Some(run_as_group) = self.run_as_group {
+            other.run_as_group = Some(run_as_group);
+        }
+
+        if let Some(mss_clamp) = self.mss_clamp {
+            other.mss_clamp = mss_clamp;
+        }
+
+        if let Some(netns) = self.netns {
+            other.netns = Some(netns);
+        }
+
+        if !self.app_route_cgroups.is_empty() {
+            other.app_route_cgroups = self.app_route_cgroups;
+        }
+
+        if !self.app_route_uids.is_empty() {
+            other.app_route_uids = self.app_route_uids;
+        }
+
+        if let Some(dns_redirect) = self.dns_redirect {
+            other.dns_redirect = dns_redirect;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CmdlineParams, clap::Error> {
+        CmdlineParams::try_parse_from(std::iter::once("snx-rs").chain(args.iter().copied()))
+    }
+
+    fn tunnel_params(args: &[&str]) -> TunnelParams {
+        let mut params = TunnelParams::default();
+        parse(args).unwrap().merge_into_tunnel_params(&mut params);
+        params
+    }
+
+    #[test]
+    fn test_command_definition() {
+        CmdlineParams::command().debug_assert();
+    }
+
+    #[test]
+    fn test_flag_mapping() {
+        let params = tunnel_params(&[
+            "-s",
+            "vpn.example.com",
+            "-u",
+            "007",
+            "--password",
+            "c2VjcmV0",
+            "--search-domains",
+            "a.com,b.com",
+            "--dns-servers",
+            "10.0.0.1",
+            "--default-route",
+            "true",
+            "--add-routes",
+            "10.10.0.0/16",
+            "--tunnel-type",
+            "ipsec",
+            "--ike-lifetime",
+            "3600",
+            "--mtu",
+            "1400",
+            "--roaming-timeout",
+            "30",
+            "--timeout",
+            "connect=5",
+            "--timeout",
+            "hello=20",
+            "--retry",
+            "reconnect-delay=1",
+            "--reauth-interval",
+            "3600",
+            "--block-ipv6",
+            "route",
+            "--app-route-uids",
+            "1001,1002",
+            "--dns-redirect",
+            "true",
+            "--notify-events",
+            "disconnected,reauth-failed",
+            "--log-filter",
+            "info,snxcore::tunnel=debug",
+            "--insecure-permissions",
+        ]);
+
+        assert_eq!(params.server_name, "vpn.example.com");
+        assert_eq!(params.user_name, "007");
+        assert_eq!(params.password, "secret");
+        assert_eq!(params.search_domains, vec!["a.com", "b.com"]);
+        assert_eq!(params.dns_servers, vec![Ipv4Addr::new(10, 0, 0, 1)]);
+        assert!(params.default_route);
+        assert_eq!(params.add_routes, vec!["10.10.0.0/16".parse::<Ipv4Net>().unwrap()]);
+        assert_eq!(params.tunnel_type, TunnelType::Ipsec);
+        assert_eq!(params.ike_lifetime, Duration::from_secs(3600));
+        assert_eq!(params.mtu, Some(1400));
+        assert_eq!(params.timeouts.reconnect, Duration::from_secs(30));
+        assert_eq!(params.timeouts.connect, Duration::from_secs(5));
+        assert_eq!(params.timeouts.hello, Duration::from_secs(20));
+        assert_eq!(params.retries.reconnect_delay, Duration::from_secs(1));
+        assert_eq!(params.reauth_interval, Some(Duration::from_secs(3600)));
+        assert_eq!(params.block_ipv6, Ipv6BlockMode::Route);
+        assert_eq!(params.app_route_uids, vec![1001, 1002]);
+        assert!(params.dns_redirect);
+        assert_eq!(
+            params.notify_events,
+            vec![NotifyEvent::Disconnected, NotifyEvent::ReauthFailed]
+        );
+        assert_eq!(params.log_filter.as_deref(), Some("info,snxcore::tunnel=debug"));
+        assert!(params.insecure_permissions);
+
+        // the options which are not given keep the configured values
+        assert_eq!(params.login_type, TunnelParams::default().login_type);
+        assert_eq!(params.mss_clamp, MssClampMode::Auto);
+    }
+
+    #[test]
+    fn test_legacy_certificate() {
+        let mut cmdline = parse(&[
+            "connect",
+            "-s",
+            "vpn.example.com",
+            "-u",
+            "john",
+            "-c",
+            "/home/john/john.P12",
+        ])
+        .unwrap();
+        cmdline.apply_legacy_certificate();
+        assert!(cmdline.config_file.is_none());
+
+        let mut params = TunnelParams::default();
+        cmdline.merge_into_tunnel_params(&mut params);
+        assert_eq!(params.server_name, "vpn.example.com");
+        assert_eq!(params.user_name, "john");
+        assert_eq!(params.cert_path.as_deref(), Some(Path::new("/home/john/john.P12")));
+        assert_eq!(params.cert_type, CertType::Pkcs12);
+
+        // the configuration file and an explicit certificate are left alone
+        let mut cmdline = parse(&["-c", "/etc/snx-rs/office.toml", "connect"]).unwrap();
+        cmdline.apply_legacy_certificate();
+        assert!(cmdline.config_file.is_some());
+
+        let mut cmdline = parse(&["-c", "a.pfx", "-z", "b.pfx", "-y", "pkcs12"]).unwrap();
+        cmdline.apply_legacy_certificate();
+        assert_eq!(cmdline.config_file.as_deref(), Some(Path::new("a.pfx")));
+    }
+
+    #[test]
+    fn test_gateway_list() {
+        let params = tunnel_params(&[
+            "-s",
+            "vpn.example.com, dr.example.com",
+            "--failback",
+            "true",
+            "--failback-probe-interval",
+            "60",
+        ]);
+
+        assert_eq!(params.server_name, "vpn.example.com");
+        assert_eq!(params.failover_servers, ["dr.example.com"]);
+        assert!(params.failback);
+        assert_eq!(params.failback_probe_interval, Duration::from_secs(60));
+
+        let params = tunnel_params(&["--proxy", "none"]);
+        assert_eq!(params.proxy, ProxyMode::None);
+        let params = tunnel_params(&["--proxy", "proxy.example.com:3128"]);
+        assert!(matches!(params.proxy, ProxyMode::Server(ref server) if server.port == 3128));
+        assert!(parse(&["--proxy", "socks5://proxy.example.com"]).is_err());
+    }
+
+    #[test]
+    fn test_subcommands() {
+        let mut cmdline = parse(&[
+            "-s",
+            "vpn.example.com",
+            "connect",
+            "--profile",
+            "lab",
+            "--foreground",
+            "--ipsec",
+        ])
+        .unwrap();
+        assert_eq!(cmdline.profile.as_deref(), Some("lab"));
+        assert_eq!(cmdline.mode(), (OperationMode::Standalone, false));
+        assert_eq!(cmdline.command.as_ref().unwrap().service_action(), None);
+
+        let mut params = TunnelParams::default();
+        cmdline.merge_into_tunnel_params(&mut params);
+        assert_eq!(params.server_name, "vpn.example.com");
+        assert_eq!(params.tunnel_type, TunnelType::Ipsec);
+
+        let cmdline = parse(&["connect"]).unwrap();
+        assert_eq!(cmdline.command.unwrap().service_action(), Some(ServiceAction::Connect));
+
+        let cmdline = parse(&["-s", "vpn.example.com", "config", "dump"]).unwrap();
+        assert!(matches!(
+            cmdline.command,
+            Some(CliCommand::Config {
+                command: ConfigCommand::Dump
+            })
+        ));
+        assert_eq!(cmdline.tunnel.server_name.as_deref(), Some("vpn.example.com"));
+
+        let cmdline = parse(&["config", "import", "--from-snxrc"]).unwrap();
+        assert!(matches!(
+            cmdline.command,
+            Some(CliCommand::Config {
+                command: ConfigCommand::Import {
+                    from_snxrc: Some(None),
+                    force: false
+                }
+            })
+        ));
+        let cmdline = parse(&["config", "import", "--from-snxrc", "/tmp/snxrc", "--force"]).unwrap();
+        assert!(matches!(
+            cmdline.command,
+            Some(CliCommand::Config {
+                command: ConfigCommand::Import {
+                    from_snxrc: Some(Some(_)),
+                    force: true
+                }
+            })
+        ));
+        assert!(parse(&["config", "import"]).is_err());
+
+        let cmdline = parse(&["disconnect", "--timeout", "5", "--force"]).unwrap();
+        assert_eq!(
+            cmdline.command.unwrap().service_action(),
+            Some(ServiceAction::Disconnect {
+                timeout: Duration::from_secs(5),
+                force: true
+            })
+        );
+
+        let cmdline = parse(&["status", "--json"]).unwrap();
+        assert_eq!(
+            cmdline.command.unwrap().service_action(),
+            Some(ServiceAction::Status { json: true })
+        );
+
+        assert_eq!(
+            parse(&["info", "-s", "vpn"]).unwrap().mode(),
+            (OperationMode::Info, false)
+        );
+        assert!(matches!(
+            parse(&["info", "--json", "-s", "vpn"]).unwrap().command,
+            Some(CliCommand::Info { json: true, .. })
+        ));
+        assert_eq!(parse(&["cleanup"]).unwrap().mode(), (OperationMode::Standalone, true));
+        assert_eq!(parse(&["service"]).unwrap().mode(), (OperationMode::Command, false));
+        assert_eq!(
+            parse(&["-m", "command"]).unwrap().mode(),
+            (OperationMode::Command, false)
+        );
+
+        let cmdline = parse(&[
+            "setup",
+            "-s",
+            "vpn.example.com",
+            "--dns-mode",
+            "full",
+            "--store-password",
+            "false",
+            "--non-interactive",
+        ])
+        .unwrap();
+        // the option of the setup is its own
+        assert!(!cmdline.non_interactive);
+        let Some(CliCommand::Setup { setup }) = cmdline.command else {
+            panic!("setup expected");
+        };
+        assert_eq!(setup.server_name.as_deref(), Some("vpn.example.com"));
+        assert_eq!(setup.dns_mode, Some(DnsMode::Full));
+        assert_eq!(setup.store_password, Some(false));
+        assert_eq!(setup.default_route, None);
+        assert!(setup.non_interactive);
+        assert!(!setup.force);
+    }
+
+    #[test]
+    fn test_non_interactive() {
+        assert!(
+            parse(&["--non-interactive", "-s", "vpn.example.com"])
+                .unwrap()
+                .non_interactive
+        );
+        assert!(!parse(&["-s", "vpn.example.com"]).unwrap().non_interactive);
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let error = parse(&["--ipsec", "--tunnel-type", "ssl"]).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::ArgumentConflict);
+
+        let cmdline = parse(&["--no-routing", "true", "--default-route", "true"]).unwrap();
+        assert_eq!(cmdline.validate().unwrap_err().kind(), ErrorKind::ArgumentConflict);
+
+        let cmdline = parse(&["connect", "--no-routing", "true", "--add-routes", "10.0.0.0/8"]).unwrap();
+        assert_eq!(cmdline.validate().unwrap_err().kind(), ErrorKind::ArgumentConflict);
+
+        let cmdline = parse(&["--cleanup", "status"]).unwrap();
+        assert_eq!(cmdline.validate().unwrap_err().kind(), ErrorKind::ArgumentConflict);
+
+        let cmdline = parse(&["--daemon", "disconnect"]).unwrap();
+        assert_eq!(cmdline.validate().unwrap_err().kind(), ErrorKind::ArgumentConflict);
+
+        let cmdline = parse(&["--daemon", "setup"]).unwrap();
+        assert_eq!(cmdline.validate().unwrap_err().kind(), ErrorKind::ArgumentConflict);
+
+        let cmdline = parse(&["--dry-run", "config", "dump"]).unwrap();
+        assert_eq!(cmdline.validate().unwrap_err().kind(), ErrorKind::ArgumentConflict);
+
+        let cmdline = parse(&["--daemon", "connect", "--foreground"]).unwrap();
+        assert!(cmdline.validate().is_ok());
+
+        let cmdline = parse(&["--dry-run", "--daemon"]).unwrap();
+        assert_eq!(cmdline.validate().unwrap_err().kind(), ErrorKind::ArgumentConflict);
+
+        let cmdline = parse(&["--dry-run", "-m", "command"]).unwrap();
+        assert_eq!(cmdline.validate().unwrap_err().kind(), ErrorKind::ArgumentConflict);
+
+        let cmdline = parse(&["connect", "--foreground", "--dry-run"]).unwrap();
+        assert!(cmdline.validate().is_ok());
+
+        let cmdline = parse(&["--replace", "-m", "command"]).unwrap();
+        assert_eq!(cmdline.validate().unwrap_err().kind(), ErrorKind::ArgumentConflict);
+
+        let cmdline = parse(&["--replace", "--daemon"]).unwrap();
+        assert!(cmdline.validate().is_ok());
+
+        // disabled options do not conflict
+        let cmdline = parse(&["--no-routing", "false", "--default-route", "true"]).unwrap();
+        assert!(cmdline.validate().is_ok());
     }
 }
@@ -0,0 +1,160 @@
+//! Classic daemon mode for the init systems without service supervision: double fork, new session,
+//! console output discarded and a locked pidfile. Must run before the tokio runtime starts,
+//! forking a multithreaded process keeps only the calling thread.
+
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Seek, Write},
+    os::{fd::AsRawFd, unix::fs::OpenOptionsExt},
+    path::{Path, PathBuf},
+};
+
+use anyhow::anyhow;
+use i18n::tr;
+use snxcore::model::params::TunnelParams;
+use tracing::debug;
+
+const PID_FILE_NAME: &str = "snx-rs.pid";
+
+/// Configured pidfile, otherwise in /run for root and in the runtime directory of the user for the unprivileged tunnels.
+pub fn pid_file_path(params: &TunnelParams) -> PathBuf {
+    if let Some(ref path) = params.pid_file {
+        return path.clone();
+    }
+
+    match env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) if unsafe { libc::geteuid() } != 0 => PathBuf::from(dir).join(PID_FILE_NAME),
+        _ => PathBuf::from("/run").join(PID_FILE_NAME),
+    }
+}
+
+/// Exclusively locked pidfile, removed on drop. The lock is inherited by the forked children.
+pub struct PidFile {
+    path: PathBuf,
+    file: File,
+}
+
+impl PidFile {
+    pub fn lock(path: &Path) -> anyhow::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .mode(0o644)
+            .open(path)?;
+
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+            return Err(anyhow!(tr!("error-pid-file-locked", path = path.display().to_string())));
+        }
+
+        Ok(Self {
+            path: path.to_owned(),
+            file,
+        })
+    }
+
+    fn write_pid(&mut self) -> io::Result<()> {
+        self.file.set_len(0)?;
+        self.file.rewind()?;
+        writeln!(self.file, "{}", std::process::id())?;
+        self.file.flush()
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Pid of the running daemon, `None` if the pidfile is missing or nobody holds its lock.
+pub fn running_pid(path: &Path) -> Option<i32> {
+    let mut file = File::open(path).ok()?;
+
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_SH | libc::LOCK_NB) } == 0 {
+        debug!("Stale pidfile {}", path.display());
+        return None;
+    }
+
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).ok()?;
+    contents.trim().parse().ok()
+}
+
+fn fork_and_exit_parent() -> io::Result<()> {
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => Ok(()),
+        _ => unsafe { libc::_exit(0) },
+    }
+}
+
+fn redirect(file: &File, fd: libc::c_int) -> io::Result<()> {
+    if unsafe { libc::dup2(file.as_raw_fd(), fd) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Detach from the terminal. The pidfile is locked first, so that a running instance is still reported
+/// to the terminal. The log goes to the `log-file`, which is opened after the fork.
+pub fn daemonize(params: &TunnelParams) -> anyhow::Result<PidFile> {
+    let mut pid_file = PidFile::lock(&pid_file_path(params))?;
+
+    let output = OpenOptions::new().write(true).open("/dev/null")?;
+    let input = File::open("/dev/null")?;
+
+    fork_and_exit_parent()?;
+
+    if unsafe { libc::setsid() } == -1 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    // the session leader exits, so that the daemon can never acquire a controlling terminal again
+    fork_and_exit_parent()?;
+
+    env::set_current_dir("/")?;
+
+    redirect(&input, libc::STDIN_FILENO)?;
+    redirect(&output, libc::STDOUT_FILENO)?;
+    redirect(&output, libc::STDERR_FILENO)?;
+
+    pid_file.write_pid()?;
+
+    debug!("Running as a daemon with pid {}", std::process::id());
+
+    Ok(pid_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snx-rs.pid");
+
+        let mut pid_file = PidFile::lock(&path).unwrap();
+        pid_file.write_pid().unwrap();
+
+        // the lock is taken per open file, a second instance in the same process is refused as well
+        assert!(PidFile::lock(&path).is_err());
+        assert_eq!(running_pid(&path), Some(std::process::id() as i32));
+
+        drop(pid_file);
+        assert!(!path.exists());
+        assert_eq!(running_pid(&path), None);
+    }
+
+    #[test]
+    fn test_stale_pid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snx-rs.pid");
+        fs::write(&path, "12345\n").unwrap();
+
+        assert_eq!(running_pid(&path), None);
+    }
+}
@@ -0,0 +1,101 @@
+//! Process exit codes: the failures which need a human, like the rejected credentials, are told apart from
+//! the transient ones, like an unreachable gateway, so that a supervisor retries only the latter.
+
+use snxcore::error::FailureKind;
+
+/// Shown at the end of `--help`, kept in sync with [`ExitCode::code`] by the tests.
+pub const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0  success or clean disconnect
+  2  configuration error
+  3  authentication rejected, or the command mode service is not running for the service commands
+  4  MFA required but no terminal to answer it
+  5  gateway unreachable
+  6  TLS or certificate failure
+  7  tunnel failure
+  8  missing privileges or capabilities";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    Success,
+    ConfigError,
+    AuthRejected,
+    MfaRequired,
+    GatewayUnreachable,
+    CertificateError,
+    TunnelFailure,
+    PermissionDenied,
+    /// "program is not running" of the LSB init script status action, only for the service commands.
+    NotRunning,
+}
+
+impl ExitCode {
+    pub fn code(self) -> i32 {
+        match self {
+            Self::Success => 0,
+            Self::ConfigError => 2,
+            Self::AuthRejected | Self::NotRunning => 3,
+            Self::MfaRequired => 4,
+            Self::GatewayUnreachable => 5,
+            Self::CertificateError => 6,
+            Self::TunnelFailure => 7,
+            Self::PermissionDenied => 8,
+        }
+    }
+
+    pub fn of(error: &anyhow::Error) -> Self {
+        FailureKind::of(error).into()
+    }
+
+    pub fn exit(self) -> ! {
+        std::process::exit(self.code())
+    }
+}
+
+impl From<FailureKind> for ExitCode {
+    fn from(kind: FailureKind) -> Self {
+        match kind {
+            FailureKind::Config => Self::ConfigError,
+            FailureKind::AuthRejected => Self::AuthRejected,
+            FailureKind::MfaRequired => Self::MfaRequired,
+            FailureKind::GatewayUnreachable => Self::GatewayUnreachable,
+            FailureKind::Certificate => Self::CertificateError,
+            FailureKind::Permission => Self::PermissionDenied,
+            FailureKind::Tunnel => Self::TunnelFailure,
+        }
+    }
+}
+
+/// Print the error the same way as the default handler of `main` and exit with the code of its class.
+pub fn fail(error: anyhow::Error, code: ExitCode) -> ! {
+    eprintln!("Error: {error:?}");
+    code.exit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes() {
+        let cases = [
+            (ExitCode::Success, 0, None),
+            (ExitCode::ConfigError, 2, Some(FailureKind::Config)),
+            (ExitCode::AuthRejected, 3, Some(FailureKind::AuthRejected)),
+            (ExitCode::MfaRequired, 4, Some(FailureKind::MfaRequired)),
+            (ExitCode::GatewayUnreachable, 5, Some(FailureKind::GatewayUnreachable)),
+            (ExitCode::CertificateError, 6, Some(FailureKind::Certificate)),
+            (ExitCode::TunnelFailure, 7, Some(FailureKind::Tunnel)),
+            (ExitCode::PermissionDenied, 8, Some(FailureKind::Permission)),
+            (ExitCode::NotRunning, 3, None),
+        ];
+
+        for (exit_code, code, kind) in cases {
+            assert_eq!(exit_code.code(), code);
+            if let Some(kind) = kind {
+                assert_eq!(ExitCode::from(kind), exit_code);
+            }
+            assert!(EXIT_CODES_HELP.contains(&format!("\n  {code}  ")));
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Import of the legacy snx client settings: the `.snxrc` file becomes a new TOML configuration, or a profile
+//! appended to the existing one so that its comments are kept.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow};
+use i18n::tr;
+use snxcore::{model::params::TunnelParams, snxrc, util};
+
+pub fn run(
+    source: Option<PathBuf>,
+    config_file: Option<PathBuf>,
+    profile: Option<String>,
+    force: bool,
+) -> anyhow::Result<()> {
+    let source = source.or_else(snxrc::default_path).context("No home directory")?;
+    let config_file = config_file.unwrap_or_else(TunnelParams::user_config_path);
+
+    let imported =
+        snxrc::load(&source).map_err(|e| anyhow!(tr!("error-import-read", path = source.display(), error = e)))?;
+    if imported.options.is_empty() {
+        anyhow::bail!(tr!("error-import-empty", path = source.display()));
+    }
+
+    let data = import_data(
+        &imported.to_toml(&source, profile.as_deref()),
+        &config_file,
+        profile.as_deref(),
+        force,
+    )?;
+
+    if let Some(dir) = config_file.parent() {
+        let _ = util::create_private_dir(dir);
+    }
+    util::write_private_file(&config_file, data)?;
+
+    println!(
+        "{}",
+        tr!(
+            "import-config-written",
+            count = imported.options.len(),
+            source = source.display(),
+            path = config_file.display()
+        )
+    );
+    for unmapped in &imported.unmapped {
+        println!("{}", tr!("import-unmapped", line = unmapped.line, key = unmapped.key));
+    }
+    if imported.value("login-type").is_none() {
+        println!(
+            "{}",
+            tr!(
+                "import-login-type-missing",
+                server = imported.value("server-name").unwrap_or_default()
+            )
+        );
+    }
+
+    Ok(())
+}
+
+// The whole file is replaced only with --force, a profile is appended to the existing file instead.
+fn import_data(config: &str, config_file: &Path, profile: Option<&str>, force: bool) -> anyhow::Result<String> {
+    let path = config_file.display();
+
+    if config_file.extension().is_none_or(|ext| ext != "toml") {
+        anyhow::bail!(tr!("error-import-not-toml", path = path));
+    }
+
+    if !config_file.exists() {
+        return Ok(config.to_owned());
+    }
+
+    match profile {
+        Some(profile) => {
+            let existing = fs::read_to_string(config_file)?;
+            if util::parse_toml_config(&existing)?.profiles.contains_key(profile) {
+                anyhow::bail!(tr!("error-import-profile-exists", profile = profile, path = path));
+            }
+            Ok(format!("{}\n\n{config}", existing.trim_end()))
+        }
+        None if force => Ok(config.to_owned()),
+        None => anyhow::bail!(tr!("error-setup-config-exists", path = path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join(".snxrc");
+        let config_file = dir.path().join("config.toml");
+        fs::write(&source, "server vpn.example.com\nusername john\nreauth yes\n").unwrap();
+
+        run(Some(source.clone()), Some(config_file.clone()), None, false).unwrap();
+        let config = util::parse_toml_config(fs::read_to_string(&config_file).unwrap()).unwrap();
+        assert_eq!(config.values["server-name"], "vpn.example.com");
+        assert_eq!(config.values["user-name"], "john");
+
+        // the existing file is only replaced with --force, a profile is appended to it
+        assert!(run(Some(source.clone()), Some(config_file.clone()), None, false).is_err());
+        run(
+            Some(source.clone()),
+            Some(config_file.clone()),
+            Some("lab".to_owned()),
+            false,
+        )
+        .unwrap();
+        let data = fs::read_to_string(&config_file).unwrap();
+        assert!(data.contains("# Not imported, line 3: reauth\n"));
+        let config = util::parse_toml_config(&data).unwrap();
+        assert_eq!(config.values["server-name"], "vpn.example.com");
+        assert_eq!(config.profiles["lab"]["user-name"], "john");
+        assert!(
+            run(
+                Some(source.clone()),
+                Some(config_file.clone()),
+                Some("lab".to_owned()),
+                false
+            )
+            .is_err()
+        );
+
+        run(Some(source.clone()), Some(config_file.clone()), None, true).unwrap();
+        assert!(
+            util::parse_toml_config(fs::read_to_string(&config_file).unwrap())
+                .unwrap()
+                .profiles
+                .is_empty()
+        );
+
+        assert!(run(Some(source.clone()), Some(dir.path().join("snx-rs.conf")), None, false).is_err());
+        assert!(run(Some(dir.path().join("missing")), Some(config_file), None, true).is_err());
+    }
+}
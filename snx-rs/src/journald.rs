@@ -0,0 +1,242 @@
+//! Native journal logging: every event is a datagram to the journal socket with the syslog priority, and the
+//! fields of the event and of the enclosing spans are kept as separate journal fields with the `SNX_` prefix,
+//! so that `journalctl SNX_GATEWAY=vpn.example.com` selects the messages of a single tunnel.
+
+use std::{fmt, io, os::unix::net::UnixDatagram, path::Path};
+
+use tracing::{
+    Event, Level, Subscriber,
+    field::{Field, Visit},
+    span,
+};
+use tracing_subscriber::{Layer, layer::Context, registry::LookupSpan};
+
+const JOURNAL_SOCKET: &str = "/run/systemd/journal/socket";
+const SYSLOG_IDENTIFIER: &str = "snx-rs";
+const FIELD_PREFIX: &str = "SNX_";
+
+// bigger datagrams need a memfd, the long traces of the server replies are cut instead
+const MAX_MESSAGE_SIZE: usize = 64 * 1024;
+
+pub fn is_available() -> bool {
+    Path::new(JOURNAL_SOCKET).exists()
+}
+
+fn priority(level: &Level) -> &'static str {
+    match *level {
+        Level::ERROR => "3",
+        Level::WARN => "4",
+        Level::INFO => "6",
+        Level::DEBUG | Level::TRACE => "7",
+    }
+}
+
+// journal field names are uppercase letters, digits and underscores
+fn field_name(name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    format!("{FIELD_PREFIX}{name}")
+}
+
+// the values with newlines use the binary form with the explicit length
+fn put_field(buf: &mut Vec<u8>, name: &str, value: &[u8]) {
+    buf.extend_from_slice(name.as_bytes());
+    if value.contains(&b'\n') {
+        buf.push(b'\n');
+        buf.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        buf.push(b'=');
+    }
+    buf.extend_from_slice(value);
+    buf.push(b'\n');
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: Vec<(String, String)>,
+}
+
+impl FieldVisitor {
+    fn add(&mut self, field: &Field, value: String) {
+        if field.name() == "message" {
+            self.message = value;
+        } else {
+            let name = field_name(field.name());
+            self.fields.retain(|(existing, _)| *existing != name);
+            self.fields.push((name, value));
+        }
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.add(field, value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.add(field, format!("{value:?}"));
+    }
+}
+
+// kept in the span extensions, updated by the later records
+struct SpanFields(Vec<(String, String)>);
+
+pub struct JournaldLayer {
+    socket: UnixDatagram,
+}
+
+impl JournaldLayer {
+    pub fn connect() -> io::Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(JOURNAL_SOCKET)?;
+        Ok(Self { socket })
+    }
+}
+
+fn format_entry(level: &Level, target: &str, spans: &[(String, String)], event: &FieldVisitor) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(256);
+
+    let mut message = event.message.clone();
+    if message.len() > MAX_MESSAGE_SIZE {
+        let mut end = MAX_MESSAGE_SIZE;
+        while !message.is_char_boundary(end) {
+            end -= 1;
+        }
+        message.truncate(end);
+        message.push_str("...");
+    }
+
+    put_field(&mut buf, "PRIORITY", priority(level).as_bytes());
+    put_field(&mut buf, "SYSLOG_IDENTIFIER", SYSLOG_IDENTIFIER.as_bytes());
+    put_field(&mut buf, "MESSAGE", message.as_bytes());
+    put_field(&mut buf, "SNX_TARGET", target.as_bytes());
+
+    for (name, value) in spans.iter().chain(&event.fields) {
+        put_field(&mut buf, name, value.as_bytes());
+    }
+
+    buf
+}
+
+impl<S> Layer<S> for JournaldLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        span.extensions_mut().insert(SpanFields(visitor.fields));
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(SpanFields(fields)) = extensions.get_mut::<SpanFields>() {
+            let mut visitor = FieldVisitor {
+                message: String::new(),
+                fields: std::mem::take(fields),
+            };
+            values.record(&mut visitor);
+            *fields = visitor.fields;
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        // the outer spans first, the inner ones override the fields with the same name
+        let mut spans = Vec::new();
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                if let Some(SpanFields(fields)) = span.extensions().get::<SpanFields>() {
+                    for (name, value) in fields {
+                        spans.retain(|(existing, _): &(String, String)| existing != name);
+                        spans.push((name.clone(), value.clone()));
+                    }
+                }
+            }
+        }
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        let entry = format_entry(metadata.level(), metadata.target(), &spans, &visitor);
+
+        // nothing sensible can be done about a lost log line
+        let _ = self.socket.send(&entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    #[test]
+    fn test_field_name() {
+        assert_eq!(field_name("session_id"), "SNX_SESSION_ID");
+        assert_eq!(field_name("gateway"), "SNX_GATEWAY");
+        assert_eq!(field_name("peer.addr"), "SNX_PEER_ADDR");
+    }
+
+    #[test]
+    fn test_format_entry() {
+        let spans = vec![("SNX_SESSION_ID".to_owned(), "1234".to_owned())];
+        let event = FieldVisitor {
+            message: "Tunnel connected".to_owned(),
+            fields: vec![("SNX_DETAILS".to_owned(), "a\nb".to_owned())],
+        };
+
+        let entry = format_entry(&Level::WARN, "snxcore::tunnel", &spans, &event);
+
+        let mut expected =
+            b"PRIORITY=4\nSYSLOG_IDENTIFIER=snx-rs\nMESSAGE=Tunnel connected\nSNX_TARGET=snxcore::tunnel\n\
+            SNX_SESSION_ID=1234\nSNX_DETAILS\n"
+                .to_vec();
+        expected.extend_from_slice(&3u64.to_le_bytes());
+        expected.extend_from_slice(b"a\nb\n");
+
+        assert_eq!(entry, expected);
+    }
+
+    #[test]
+    fn test_send() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.sock");
+        let journal = UnixDatagram::bind(&path).unwrap();
+
+        let socket = UnixDatagram::unbound().unwrap();
+        socket.connect(&path).unwrap();
+
+        let subscriber = tracing_subscriber::registry().with(JournaldLayer { socket });
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("session", gateway = "vpn.example.com");
+            let _entered = span.enter();
+            tracing::error!(bytes = 84, "Keepalive failed");
+        });
+
+        let mut buf = [0u8; 1024];
+        let size = journal.recv(&mut buf).unwrap();
+        let entry = String::from_utf8_lossy(&buf[..size]);
+
+        assert!(entry.contains("PRIORITY=3\n"));
+        assert!(entry.contains("MESSAGE=Keepalive failed\n"));
+        assert!(entry.contains("SNX_GATEWAY=vpn.example.com\n"));
+        assert!(entry.contains("SNX_BYTES=84\n"));
+    }
+}
@@ -0,0 +1,266 @@
+//! Log file of the long-running deployments, rotated by size. The lines are written by a dedicated thread,
+//! so that the tunnel tasks never wait for the disk and the events of concurrent tasks are never interleaved.
+//! The thread must be started after the fork of the daemon mode.
+
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+    },
+    thread::{self, JoinHandle},
+};
+
+use tracing_subscriber::fmt::MakeWriter;
+
+// beyond that the lines are dropped instead of stalling the tunnel on a slow disk
+const QUEUE_SIZE: usize = 65536;
+
+const MIB: u64 = 1024 * 1024;
+
+enum Message {
+    Write(Vec<u8>),
+    Reopen,
+    Shutdown,
+}
+
+fn open_file(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.append(true).create(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options.open(path)
+}
+
+fn rotated_path(path: &Path, index: u32) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(format!(".{index}"));
+    name.into()
+}
+
+struct RotatingFile {
+    path: PathBuf,
+    max_size: u64,
+    keep: u32,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn open(path: &Path, max_size: u64, keep: u32) -> io::Result<Self> {
+        let file = open_file(path)?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            path: path.to_owned(),
+            max_size,
+            keep,
+            file,
+            size,
+        })
+    }
+
+    // also picks up the file moved away by an external logrotate
+    fn reopen(&mut self) -> io::Result<()> {
+        self.file = open_file(&self.path)?;
+        self.size = self.file.metadata()?.len();
+        Ok(())
+    }
+
+    // the most recent file gets the suffix .1, the oldest one is removed
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.keep == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            let _ = fs::remove_file(rotated_path(&self.path, self.keep));
+            for index in (1..self.keep).rev() {
+                let _ = fs::rename(rotated_path(&self.path, index), rotated_path(&self.path, index + 1));
+            }
+            fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        }
+
+        self.reopen()
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        let len = data.len() as u64;
+
+        // an oversized line still goes into a fresh file as a whole
+        if self.max_size > 0 && self.size > 0 && self.size + len > self.max_size {
+            if let Err(e) = self.rotate() {
+                // writing to the current file goes on, it is retried with the next line
+                eprintln!("Unable to rotate the log file {}: {}", self.path.display(), e);
+            }
+        }
+
+        self.file.write_all(data)?;
+        self.size += len;
+
+        Ok(())
+    }
+}
+
+/// Cheap handle queueing the formatted events for the writer thread.
+#[derive(Clone)]
+pub struct LogWriter {
+    sender: SyncSender<Message>,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl LogWriter {
+    /// Reopen the file, for example after it was moved away by logrotate.
+    pub fn reopen(&self) {
+        let _ = self.sender.send(Message::Reopen);
+    }
+}
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.sender.try_send(Message::Write(buf.to_vec())) {
+            Err(TrySendError::Full(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(TrySendError::Disconnected(_)) | Ok(()) => {}
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// every event is formatted into a single buffer and written with a single call
+impl MakeWriter<'_> for LogWriter {
+    type Writer = LogWriter;
+
+    fn make_writer(&self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// The queued lines are written out when the guard is dropped.
+pub struct LogGuard {
+    sender: SyncSender<Message>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for LogGuard {
+    fn drop(&mut self) {
+        let _ = self.sender.send(Message::Shutdown);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn run_writer(mut file: RotatingFile, receiver: Receiver<Message>, dropped: Arc<AtomicUsize>) {
+    while let Ok(message) = receiver.recv() {
+        let result = match message {
+            Message::Write(data) => file.write(&data),
+            Message::Reopen => file.reopen(),
+            Message::Shutdown => break,
+        };
+
+        if let Err(e) = result {
+            eprintln!("Unable to write the log file {}: {}", file.path.display(), e);
+        }
+
+        let count = dropped.swap(0, Ordering::Relaxed);
+        if count > 0 {
+            let _ = file.write(format!("... {count} log lines dropped\n").as_bytes());
+        }
+    }
+
+    let _ = file.file.flush();
+}
+
+/// Open the log file and start the writer thread, `max_size` is in MiB.
+pub fn open(path: &Path, max_size: u64, keep: u32) -> io::Result<(LogWriter, LogGuard)> {
+    let file = RotatingFile::open(path, max_size.saturating_mul(MIB), keep)?;
+
+    let (sender, receiver) = mpsc::sync_channel(QUEUE_SIZE);
+    let dropped = Arc::new(AtomicUsize::new(0));
+
+    let thread = thread::Builder::new().name("snx-rs-log".to_owned()).spawn({
+        let dropped = dropped.clone();
+        move || run_writer(file, receiver, dropped)
+    })?;
+
+    let writer = LogWriter {
+        sender: sender.clone(),
+        dropped,
+    };
+    let guard = LogGuard {
+        sender,
+        thread: Some(thread),
+    };
+
+    Ok((writer, guard))
+}
+
+/// Reopen the log file on SIGHUP, so that it can also be rotated externally.
+#[cfg(unix)]
+pub fn reopen_on_hangup(writer: LogWriter) {
+    match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+        Ok(mut hangup) => {
+            tokio::spawn(async move {
+                while hangup.recv().await.is_some() {
+                    writer.reopen();
+                }
+            });
+        }
+        Err(e) => tracing::warn!("Unable to handle SIGHUP: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snx-rs.log");
+
+        let mut file = RotatingFile::open(&path, 10, 2).unwrap();
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            file.write(line.as_bytes()).unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fourth\n");
+        assert_eq!(fs::read_to_string(rotated_path(&path, 1)).unwrap(), "third\n");
+        assert_eq!(fs::read_to_string(rotated_path(&path, 2)).unwrap(), "second\n");
+        assert!(!rotated_path(&path, 3).exists());
+    }
+
+    #[test]
+    fn test_writer() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snx-rs.log");
+
+        let (mut writer, guard) = open(&path, 10, 5).unwrap();
+        writer.write_all(b"connected\n").unwrap();
+
+        // external rotation: the file is moved away and the writer is told to reopen it
+        fs::rename(&path, dir.path().join("moved.log")).unwrap();
+        writer.reopen();
+        writer.write_all(b"disconnected\n").unwrap();
+        drop(guard);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "disconnected\n");
+        assert_eq!(fs::read_to_string(dir.path().join("moved.log")).unwrap(), "connected\n");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Formatting of the log output: the human-readable text by default, JSON lines for the log collectors,
+//! or the native journal under systemd.
+
+use std::io;
+
+use snxcore::model::params::{LogFormat, LogTarget};
+use tracing::Subscriber;
+use tracing_subscriber::{Layer, fmt::writer::BoxMakeWriter, registry::LookupSpan};
+
+/// One JSON object per event: timestamp, level, target, message and the event fields at the top level,
+/// the fields of the enclosing spans (session_id, gateway, profile) in the `spans` list.
+pub fn format_layer<S>(format: LogFormat, writer: BoxMakeWriter, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    match format {
+        LogFormat::Text => tracing_subscriber::fmt::layer()
+            .with_ansi(ansi)
+            .with_writer(writer)
+            .boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(false)
+            .with_span_list(true)
+            .with_writer(writer)
+            .boxed(),
+    }
+}
+
+/// Journal layer selected by the `log-target`, `None` for the formatted output to stderr.
+/// `auto` picks the journal when stderr is not a terminal and the journal socket exists.
+#[cfg(all(target_os = "linux", feature = "journald"))]
+pub fn journald_layer<S>(target: LogTarget) -> io::Result<Option<Box<dyn Layer<S> + Send + Sync>>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    use std::io::IsTerminal;
+
+    let enabled = match target {
+        LogTarget::Auto => !io::stderr().is_terminal() && crate::journald::is_available(),
+        LogTarget::Stderr => false,
+        LogTarget::Journald => true,
+    };
+
+    if !enabled {
+        return Ok(None);
+    }
+
+    Ok(Some(crate::journald::JournaldLayer::connect()?.boxed()))
+}
+
+#[cfg(not(all(target_os = "linux", feature = "journald")))]
+pub fn journald_layer<S>(target: LogTarget) -> io::Result<Option<Box<dyn Layer<S> + Send + Sync>>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    match target {
+        LogTarget::Journald => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "this build has no journald support",
+        )),
+        LogTarget::Auto | LogTarget::Stderr => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_format() {
+        let capture = Capture::default();
+        let writer = BoxMakeWriter::new({
+            let capture = capture.clone();
+            move || capture.clone()
+        });
+        let subscriber = tracing_subscriber::registry().with(format_layer(LogFormat::Json, writer, false));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("session", session_id = "1234", gateway = "vpn.example.com");
+            let _entered = span.enter();
+            tracing::info!(bytes = 1400, "Received packet");
+            tracing::warn!("Keepalive failed");
+        });
+
+        let output = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        let lines = output
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 2);
+
+        let event = &lines[0];
+        assert!(event["timestamp"].is_string());
+        assert_eq!(event["level"], "INFO");
+        assert_eq!(event["target"], module_path!());
+        assert_eq!(event["message"], "Received packet");
+        assert_eq!(event["bytes"], 1400);
+        assert_eq!(event["spans"][0]["name"], "session");
+        assert_eq!(event["spans"][0]["session_id"], "1234");
+        assert_eq!(event["spans"][0]["gateway"], "vpn.example.com");
+
+        assert_eq!(lines[1]["level"], "WARN");
+    }
+}
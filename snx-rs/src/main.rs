@@ -1,35 +1,95 @@
-use std::{future::Future, sync::Arc};
+#[cfg(unix)]
+use std::time::{Duration, Instant};
+use std::{future::Future, mem, sync::Arc};
 
-use clap::{CommandFactory, Parser};
+use clap::CommandFactory;
 use futures::pin_mut;
 use i18n::tr;
+#[cfg(all(target_os = "linux", feature = "dbus-service"))]
+use snxcore::control::dbus;
+#[cfg(all(target_os = "linux", feature = "desktop-notifications"))]
+use snxcore::control::notify;
+#[cfg(unix)]
+use snxcore::{
+    browser::SystemBrowser,
+    control::{
+        self, ControlRequest, ControlSocket, ControlTask,
+        state_file::{self, StateFileTask},
+    },
+    controller::{ServiceCommand, ServiceController, ServiceNotRunning},
+    model::StatusReport,
+    platform::SingleInstance,
+    server::CommandServer,
+};
 use snxcore::{
     browser::spawn_otp_listener,
     ccc::CccHttpClient,
+    control::{
+        ControlAction, ControlHandle, ControlState, LifecycleEvent, LogFormatSetter, LogLevelSetter,
+        health::{self, HealthTask},
+    },
+    error::{FailureKind, NoTty, PermissionDenied},
+    failover::{self, GatewayFailover},
     model::{
-        MfaType, PromptInfo, SessionState,
-        params::{OperationMode, TunnelParams, TunnelType},
+        ConnectionInfo, ConnectionStatus, MfaType, PromptInfo, SessionState, VpnSession,
+        params::{OperationMode, OptionSource, ProfileCriteria, TunnelParams, TunnelType},
     },
-    platform::{self, NetworkInterface, SingleInstance},
+    network_gate::NetworkGate,
+    network_match::{self, ProfileSelection, ProfileWatch},
+    platform::{self, KillSwitch, NetworkInterface},
     prompt::{SecurePrompt, TtyPrompt},
-    server::CommandServer,
-    server_info, tunnel,
-    tunnel::TunnelEvent,
+    sd_notify::{self, Watchdog},
+    server_info,
+    trusted::TrustedNetworkDetector,
+    tunnel,
+    tunnel::{TunnelCommand, TunnelConnector, TunnelEvent},
 };
-use tokio::{
-    signal::unix,
-    sync::{mpsc, oneshot},
-};
-use tracing::{debug, metadata::LevelFilter, warn};
+#[cfg(unix)]
+use tokio::signal::unix;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{Instrument, debug, info, warn};
+use tracing_subscriber::{fmt::writer::BoxMakeWriter, layer::SubscriberExt, reload, util::SubscriberInitExt};
 
-use crate::cmdline::CmdlineParams;
+use crate::{
+    cmdline::{CliCommand, CmdlineParams, ConfigCommand, ServiceAction},
+    exit_code::ExitCode,
+};
 
 mod cmdline;
+#[cfg(unix)]
+mod daemon;
+mod exit_code;
+mod import;
+#[cfg(all(target_os = "linux", feature = "journald"))]
+mod journald;
+mod logfile;
+mod logging;
+mod missing;
+mod setup;
+#[cfg(windows)]
+mod winservice;
 
+#[cfg(unix)]
 fn is_root() -> bool {
     unsafe { libc::geteuid() == 0 }
 }
 
+// Creating the wintun adapter fails with an access error when not running as administrator.
+#[cfg(windows)]
+fn is_root() -> bool {
+    true
+}
+
+#[cfg(unix)]
+fn is_windows_service() -> bool {
+    false
+}
+
+#[cfg(windows)]
+fn is_windows_service() -> bool {
+    winservice::is_running()
+}
+
 async fn await_termination<F, R>(f: F) -> anyhow::Result<()>
 where
     F: Future<Output = anyhow::Result<R>>,
@@ -37,8 +97,12 @@ where
     let ctrl_c = tokio::signal::ctrl_c();
     pin_mut!(ctrl_c);
 
+    #[cfg(unix)]
     let mut sig = unix::signal(unix::SignalKind::terminate())?;
+    #[cfg(unix)]
     let term = sig.recv();
+    #[cfg(windows)]
+    let term = winservice::stop_requested();
     pin_mut!(term);
 
     let select = futures::future::select(ctrl_c, term);
@@ -56,9 +120,115 @@ where
     }
 }
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    let cmdline_params = CmdlineParams::parse();
+fn load_file_params(cmdline_params: &CmdlineParams) -> anyhow::Result<TunnelParams> {
+    let profile = cmdline_params.profile.as_deref();
+
+    if let Some(ref config_file) = cmdline_params.config_file {
+        TunnelParams::load_profile(config_file, profile)
+    } else {
+        TunnelParams::load_layered(profile)
+    }
+}
+
+fn load_params(mut cmdline_params: CmdlineParams) -> anyhow::Result<TunnelParams> {
+    let mut params = load_file_params(&cmdline_params)?;
+    params.load_env(std::env::vars())?;
+    params.match_profile = cmdline_params.profile.is_none();
+    cmdline_params.merge_into_tunnel_params(&mut params);
+
+    Ok(params)
+}
+
+// The configuration of the profile selected by the network, the command line options still apply.
+fn load_matched_params(mut cmdline_params: CmdlineParams, profile: Option<&str>) -> anyhow::Result<TunnelParams> {
+    cmdline_params.profile = profile.map(ToOwned::to_owned);
+
+    let mut params = load_params(cmdline_params)?;
+    params.match_profile = true;
+
+    Ok(params)
+}
+
+// The command line is parsed again for the configuration file, like for a reload.
+fn load_profile_criteria(params: &TunnelParams) -> ProfileCriteria {
+    if !params.match_profile {
+        return ProfileCriteria::default();
+    }
+
+    let config_file = CmdlineParams::from_env().config_file;
+    TunnelParams::load_profile_criteria(config_file.as_deref()).unwrap_or_else(|e| {
+        warn!("Unable to load the match criteria of the profiles: {}", e);
+        ProfileCriteria::default()
+    })
+}
+
+// The profile for the current network, `None` when the command line names the profile
+// or no profile has `match` criteria.
+async fn select_network_profile(params: &TunnelParams) -> Option<ProfileSelection> {
+    let criteria = load_profile_criteria(params);
+    if criteria.profiles.is_empty() {
+        return None;
+    }
+
+    Some(network_match::select(&criteria, params.tunnel_device()).await)
+}
+
+// Every stage of the merge is kept, so that the dump tells where each value comes from.
+fn dump_config(mut cmdline_params: CmdlineParams) -> anyhow::Result<()> {
+    let file = load_file_params(&cmdline_params)?;
+    let mut env = file.clone();
+    env.load_env(std::env::vars())?;
+    let mut flag = env.clone();
+    cmdline_params.merge_into_tunnel_params(&mut flag);
+
+    println!(
+        "# snx-rs {}, {} {}",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    println!("# profile: {}", cmdline_params.profile.as_deref().unwrap_or("default"));
+    println!("# DNS backend: {}", platform::dns_backend_name(&flag));
+    print!(
+        "{}",
+        TunnelParams::to_annotated_toml(&[
+            (OptionSource::File, &file),
+            (OptionSource::Env, &env),
+            (OptionSource::Flag, &flag),
+        ])?
+    );
+
+    Ok(())
+}
+
+// The command line is parsed again, so that its options still override the reloaded configuration.
+// A profile selected by the network is kept, the next connection selects it again.
+fn reload_params(running: &TunnelParams) -> anyhow::Result<TunnelParams> {
+    let cmdline_params = CmdlineParams::from_env();
+    cmdline_params.validate()?;
+
+    let params = if running.match_profile {
+        load_matched_params(cmdline_params, running.profile.as_deref())?
+    } else {
+        load_params(cmdline_params)?
+    };
+    params.validate()?;
+
+    Ok(params)
+}
+
+fn main() {
+    if let Err(e) = run() {
+        let code = ExitCode::of(&e);
+        exit_code::fail(e, code);
+    }
+}
+
+fn run() -> anyhow::Result<()> {
+    let mut cmdline_params = CmdlineParams::from_env();
+    if let Err(e) = cmdline_params.validate() {
+        e.exit();
+    }
 
     // Handle completions immediately and exit
     if let Some(shell) = cmdline_params.completions {
@@ -66,50 +236,364 @@ async fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
-    if cmdline_params.mode != OperationMode::Info && !is_root() {
-        anyhow::bail!(tr!("error-no-root-privileges"));
+    if let Some(CliCommand::Config { ref command }) = cmdline_params.command {
+        platform::init();
+        return match command.clone() {
+            ConfigCommand::Dump => dump_config(cmdline_params),
+            ConfigCommand::Import { from_snxrc, force } => import::run(
+                from_snxrc.flatten(),
+                cmdline_params.config_file,
+                cmdline_params.profile,
+                force,
+            ),
+        };
+    }
+
+    // the wizard creates the configuration, there is nothing to load yet
+    if let Some(CliCommand::Setup { ref mut setup }) = cmdline_params.command {
+        platform::init();
+        let setup = mem::take(setup);
+        return tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(setup::run(setup, cmdline_params.config_file, cmdline_params.profile));
+    }
+
+    #[cfg(windows)]
+    match cmdline_params.command {
+        Some(CliCommand::InstallService { ref name }) => return winservice::install(name, &cmdline_params),
+        Some(CliCommand::UninstallService { ref name }) => return winservice::uninstall(name),
+        Some(CliCommand::RunService { ref name }) => return winservice::run_dispatcher(name),
+        _ => {}
+    }
+
+    run_tunnel(cmdline_params, Arc::default())
+}
+
+// Loads the configuration, sets up the log and runs the selected mode. The Windows service reports its status
+// from the connection state.
+fn run_tunnel(cmdline_params: CmdlineParams, state: Arc<ControlState>) -> anyhow::Result<()> {
+    let (mode, cleanup) = cmdline_params.mode();
+    let service_action = cmdline_params.command.as_ref().and_then(CliCommand::service_action);
+    let info_json = matches!(cmdline_params.command, Some(CliCommand::Info { json: true, .. }));
+
+    // the standalone mode checks the privileges needed by the selected options before connecting
+    if (mode == OperationMode::Command || cleanup) && !is_root() {
+        return Err(PermissionDenied(tr!("error-no-root-privileges")).into());
     }
 
     platform::init();
 
-    let mode = cmdline_params.mode;
+    let profile_selected = cmdline_params.config_file.is_some() || cmdline_params.profile.is_some();
+    let daemon = cmdline_params.daemon;
+    let replace = cmdline_params.replace;
+    let dry_run = cmdline_params.dry_run;
+    let non_interactive = cmdline_params.non_interactive;
+    let mut params = load_params(cmdline_params).unwrap_or_else(|e| exit_code::fail(e, ExitCode::ConfigError));
 
-    let mut params = if let Some(ref config_file) = cmdline_params.config_file {
-        TunnelParams::load(config_file)?
+    // the profile matching the network replaces the default one, the decision is logged once the log is set up
+    let selection = if mode == OperationMode::Standalone && !cleanup && service_action.is_none() {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(select_network_profile(&params))
     } else {
-        TunnelParams::default()
+        None
     };
-    cmdline_params.merge_into_tunnel_params(&mut params);
+    if let Some(profile) = selection.as_ref().map(ProfileSelection::profile) {
+        if profile != params.profile.as_deref() {
+            params = load_matched_params(CmdlineParams::from_env(), profile)
+                .unwrap_or_else(|e| exit_code::fail(e, ExitCode::ConfigError));
+        }
+    }
+
+    // the service has no console to log to
+    #[cfg(windows)]
+    if winservice::is_running() && params.log_file.is_none() {
+        params.log_file = Some(winservice::default_log_file());
+    }
+
+    // the dry run must leave the IKE session of a running tunnel alone
+    if dry_run {
+        params.dry_run = true;
+        params.ike_persist = false;
+    }
+
+    let needs_validation =
+        service_action == Some(ServiceAction::Connect) || (mode == OperationMode::Standalone && !cleanup);
+
+    // asked before the log is set up, so that the questions are not mixed with the log output
+    if needs_validation && missing::is_interactive(non_interactive) {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(missing::complete(&mut params, &TtyPrompt))?;
+    }
 
-    let subscriber = tracing_subscriber::fmt()
-        .with_max_level(params.log_level.parse::<LevelFilter>().unwrap_or(LevelFilter::OFF))
-        .finish();
-    tracing::subscriber::set_global_default(subscriber)?;
+    // all configuration problems are reported together before anything touches the network
+    if let Some(Err(e)) = needs_validation.then(|| params.validate()) {
+        eprintln!("{e}");
+        ExitCode::ConfigError.exit();
+    }
+
+    // forking is only safe while the process has a single thread, before the runtime starts
+    #[cfg(unix)]
+    let _pid_file = daemon.then(|| daemon::daemonize(&params)).transpose()?;
+    #[cfg(windows)]
+    if daemon {
+        anyhow::bail!("The daemon mode is not supported on Windows, use a service wrapper");
+    }
+
+    // the writer thread of the log file would not survive the fork of the daemon mode
+    let log_file = params
+        .log_file
+        .as_ref()
+        .map(|path| logfile::open(path, params.log_max_size, params.log_keep));
+    let (log_writer, _log_guard, log_error) = match log_file {
+        Some(Ok((writer, guard))) => (Some(writer), Some(guard), None),
+        Some(Err(e)) => (None, None, Some(e)),
+        None => (None, None, None),
+    };
+
+    // the log file takes precedence over the log target
+    let journald = match log_writer {
+        Some(_) => Ok(None),
+        None => logging::journald_layer(params.log_target),
+    };
+    let (journald, journald_error) = match journald {
+        Ok(layer) => (layer, None),
+        Err(e) => (None, Some(e)),
+    };
+
+    let ansi = log_writer.is_none();
+    let make_writer = {
+        let log_writer = log_writer.clone();
+        move || match log_writer {
+            Some(ref writer) => BoxMakeWriter::new(writer.clone()),
+            None => BoxMakeWriter::new(std::io::stderr),
+        }
+    };
+
+    // the level can be changed at runtime through the control socket, the format with a configuration reload
+    let (level_filter, level_handle) = reload::Layer::new(params.log_targets());
+    let (format_layer, format_handle) = reload::Layer::new(
+        journald
+            .is_none()
+            .then(|| logging::format_layer(params.log_format, make_writer(), ansi)),
+    );
+    tracing_subscriber::registry()
+        .with(level_filter)
+        .with(format_layer)
+        .with(journald)
+        .try_init()?;
+
+    if let (Some(path), Some(e)) = (&params.log_file, log_error) {
+        warn!(
+            "Unable to open the log file {}, logging to stderr: {}",
+            path.display(),
+            e
+        );
+    }
+
+    if let Some(e) = journald_error {
+        warn!("Unable to log to the journal, logging to stderr: {}", e);
+    }
 
     debug!(">>> Starting snx-rs client version {}", env!("CARGO_PKG_VERSION"));
+    if let Some(selection) = selection {
+        info!("Profile selection: {}", selection);
+    }
+    debug!("Effective configuration: {}", params.to_redacted_json());
+
+    let set_log_level: LogLevelSetter = Box::new(move |level| Ok(level_handle.reload(level)?));
+
+    // the journal keeps its own format
+    let set_log_format: LogFormatSetter = Box::new(move |format| {
+        Ok(format_handle.modify(|layer| {
+            if layer.is_some() {
+                *layer = Some(logging::format_layer(format, make_writer(), ansi));
+            }
+        })?)
+    });
+
+    // the capabilities retained by the privilege drop are per thread, the tunnel must run on a single one
+    let mut runtime = if params.run_as_user.is_some() && mode == OperationMode::Standalone {
+        tokio::runtime::Builder::new_current_thread()
+    } else {
+        tokio::runtime::Builder::new_multi_thread()
+    };
+
+    runtime.enable_all().build()?.block_on(async move {
+        #[cfg(unix)]
+        if let Some(writer) = log_writer {
+            logfile::reopen_on_hangup(writer);
+        }
+        let invocation = Invocation {
+            mode,
+            cleanup,
+            service_action,
+            profile_selected,
+            info_json,
+            replace,
+        };
+        async_main(params, invocation, state, set_log_level, set_log_format).await
+    })
+}
+
+// What the command line selected besides the tunnel parameters.
+struct Invocation {
+    mode: OperationMode,
+    cleanup: bool,
+    service_action: Option<ServiceAction>,
+    profile_selected: bool,
+    info_json: bool,
+    replace: bool,
+}
+
+async fn async_main(
+    params: TunnelParams,
+    invocation: Invocation,
+    state: Arc<ControlState>,
+    set_log_level: LogLevelSetter,
+    set_log_format: LogFormatSetter,
+) -> anyhow::Result<()> {
+    let Invocation {
+        mode,
+        cleanup,
+        service_action,
+        profile_selected,
+        info_json,
+        replace,
+    } = invocation;
+
+    // the service commands only talk to the running command mode service
+    if let Some(action) = service_action {
+        return main_service(action, params).await;
+    }
+
+    // nothing is restored or configured, the dry run works without the privileges
+    if params.dry_run {
+        return main_dry_run(params).await;
+    }
+
+    // a second tunnel of the same profile would fight over the routes and DNS, and restore them under the first one
+    #[cfg(unix)]
+    let _instance = if mode == OperationMode::Standalone && !cleanup {
+        lock_profile(&params, replace).await?
+    } else {
+        None
+    };
+
+    if mode != OperationMode::Info {
+        platform::restore_stale_resolv_conf(&params);
+        platform::restore_stale_routing(&params).await;
+    }
+
+    if cleanup {
+        // only the selected profile when a configuration file or profile is given, otherwise all of them
+        if profile_selected {
+            tunnel::journal::restore_stale(&params).await;
+        } else {
+            tunnel::journal::restore_all_stale().await;
+        }
+        let _ = platform::new_kill_switch().disable().await;
+        return Ok(());
+    }
 
     match mode {
         OperationMode::Standalone => {
             debug!("Running in standalone mode");
-            main_standalone(params).await
+            main_standalone(params, state, set_log_level, set_log_format).await
         }
         OperationMode::Command => {
             debug!("Running in command mode");
             main_command().await
         }
-        OperationMode::Info => main_info(params).await,
+        OperationMode::Info => main_info(params, info_json).await,
     }
 }
 
-async fn main_info(params: TunnelParams) -> anyhow::Result<()> {
+async fn main_info(params: TunnelParams, json: bool) -> anyhow::Result<()> {
     if params.server_name.is_empty() {
         anyhow::bail!(tr!("error-missing-server-name"));
     }
-    snxcore::util::print_login_options(&params).await?;
+
+    let info = server_info::get_gateway_info(&params).await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+    } else {
+        println!("{}", info.print());
+    }
 
     Ok(())
 }
 
+async fn negotiate_dry_run(
+    params: &TunnelParams,
+    connector: &mut (dyn TunnelConnector + Send + Sync),
+    session: Arc<VpnSession>,
+) -> anyhow::Result<ConnectionInfo> {
+    let (command_sender, command_receiver) = mpsc::channel(16);
+    let (event_sender, mut event_receiver) = mpsc::channel(16);
+
+    let tunnel = connector.create_tunnel(session.clone(), command_sender).await?;
+    tunnel
+        .run(command_receiver, event_sender)
+        .instrument(tunnel::session_span(params, &session))
+        .await?;
+
+    while let Some(event) = event_receiver.recv().await {
+        if let TunnelEvent::Connected(info) = event {
+            return Ok(info);
+        }
+    }
+
+    anyhow::bail!("The tunnel reported no connection")
+}
+
+// The tunnel is negotiated as usual but never configured, the gateway session is closed before exiting.
+async fn main_dry_run(params: TunnelParams) -> anyhow::Result<()> {
+    let params = Arc::new(params);
+
+    let (mut connector, session) = match authenticate(params.clone()).await {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{}", tr!("cli-dry-run-auth-failed", error = e.to_string()));
+            ExitCode::of(&e).exit();
+        }
+    };
+
+    let result = negotiate_dry_run(&params, connector.as_mut(), session.clone()).await;
+
+    // the IPSec SA is deleted with the disconnect event, the SSL tunnel has already sent its disconnect request
+    if let Err(e) = connector.handle_tunnel_event(TunnelEvent::Disconnected).await {
+        debug!("Unable to close the tunnel: {}", e);
+    }
+
+    debug!("Signing out");
+    if let Err(e) = CccHttpClient::new(params.clone(), Some(session)).signout().await {
+        warn!("Unable to sign out: {}", e);
+    }
+
+    match result {
+        Ok(info) => {
+            println!("{}", info.print());
+            println!("{}", tr!("cli-dry-run-complete"));
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("{}", tr!("cli-dry-run-negotiation-failed", error = e.to_string()));
+            ExitCode::of(&e).exit();
+        }
+    }
+}
+
+#[cfg(windows)]
+async fn main_command() -> anyhow::Result<()> {
+    anyhow::bail!("Command mode is not supported on Windows, use the standalone mode")
+}
+
+#[cfg(unix)]
 async fn main_command() -> anyhow::Result<()> {
     let instance = SingleInstance::new("/var/run/snx-rs.lock")?;
     if !instance.is_single() {
@@ -117,6 +601,9 @@ async fn main_command() -> anyhow::Result<()> {
         return Ok(());
     }
 
+    // no tunnel is up yet, everything left in the journals belongs to a session which did not exit cleanly
+    tunnel::journal::restore_all_stale().await;
+
     if let Err(e) = platform::new_network_interface().start_network_state_monitoring().await {
         warn!("Unable to start network monitoring: {}", e);
     }
@@ -125,31 +612,631 @@ async fn main_command() -> anyhow::Result<()> {
     await_termination(server.run()).await
 }
 
-async fn main_standalone(params: TunnelParams) -> anyhow::Result<()> {
-    // TODO: reuse code from CommandServer and ServiceController
+#[cfg(windows)]
+async fn main_service(_action: ServiceAction, _params: TunnelParams) -> anyhow::Result<()> {
+    anyhow::bail!("Command mode is not supported on Windows, use connect --foreground")
+}
 
-    let (command_sender, command_receiver) = mpsc::channel(16);
+#[cfg(unix)]
+async fn main_service(action: ServiceAction, params: TunnelParams) -> anyhow::Result<()> {
+    let command = match action {
+        ServiceAction::Connect => ServiceCommand::Connect,
+        ServiceAction::Disconnect { .. } => ServiceCommand::Disconnect,
+        ServiceAction::Status { .. } => ServiceCommand::Status,
+    };
+
+    let mut service_controller = ServiceController::new(TtyPrompt, SystemBrowser);
+    let status = match service_controller.command(command, Arc::new(params.clone())).await {
+        Err(e) if e.is::<ServiceNotRunning>() => {
+            let stopped = match action {
+                ServiceAction::Disconnect { timeout, force } => stop_standalone(&params, timeout, force).await?,
+                _ => false,
+            };
+            if stopped {
+                return Ok(());
+            }
+            eprintln!("{e}");
+            ExitCode::NotRunning.exit();
+        }
+        result => result?,
+    };
+
+    if action == (ServiceAction::Status { json: true }) {
+        println!("{}", serde_json::to_string_pretty(&StatusReport::new(status))?);
+    } else {
+        println!("{}", status.print());
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+#[cfg(unix)]
+const KILL_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Pid of the standalone tunnel of the profile from its lock, otherwise of the daemon from its pidfile.
+#[cfg(unix)]
+fn standalone_pid(params: &TunnelParams) -> Option<i32> {
+    control::lock_path(params)
+        .and_then(|path| SingleInstance::new(path.to_string_lossy()).ok())
+        .and_then(|instance| instance.owner())
+        .or_else(|| daemon::running_pid(&daemon::pid_file_path(params)))
+}
+
+// A process of another user is alive as well, it cannot be signaled only.
+#[cfg(unix)]
+fn is_alive(pid: i32) -> bool {
+    let rc = unsafe { libc::kill(pid, 0) };
+    rc == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(unix)]
+async fn wait_for_exit(pid: i32, timeout: Duration) -> bool {
+    let started = Instant::now();
+    while is_alive(pid) {
+        if started.elapsed() >= timeout {
+            return false;
+        }
+        tokio::time::sleep(EXIT_POLL_INTERVAL).await;
+    }
+    true
+}
+
+// Without the command mode service the standalone tunnel of the profile is asked to shut down through its control
+// socket, or terminated when it does not listen. Either way it signs out and restores the network settings before
+// exiting, which is awaited. With `force` a tunnel which does not exit in time is killed and its leftovers are
+// restored here. Returns false if no tunnel was found.
+#[cfg(unix)]
+async fn stop_standalone(params: &TunnelParams, timeout: Duration, force: bool) -> anyhow::Result<bool> {
+    let pid = standalone_pid(params);
+    let profile = params.profile_name();
+
+    let request = ControlRequest::Shutdown {
+        profile: Some(profile.clone()),
+    };
+    let accepted = match control::socket_path(params) {
+        Some(path) => match control::send_request(&path, &request).await {
+            Ok(response) => {
+                if let Some(error) = response.error {
+                    anyhow::bail!(error);
+                }
+                response.ok
+            }
+            Err(e) => {
+                debug!("Control socket {} is not available: {}", path.display(), e);
+                false
+            }
+        },
+        None => false,
+    };
+
+    // without a lock or pidfile the exit cannot be awaited
+    let Some(pid) = pid else {
+        if accepted {
+            println!("{}", tr!("cli-tunnel-disconnected"));
+        }
+        return Ok(accepted);
+    };
+
+    if !accepted {
+        if unsafe { libc::kill(pid, libc::SIGTERM) } != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        println!("{}", tr!("cli-daemon-terminated", pid = pid));
+    }
+
+    println!("{}", tr!("cli-tunnel-stopping", pid = pid));
+    if wait_for_exit(pid, timeout).await {
+        println!("{}", tr!("cli-tunnel-disconnected"));
+        return Ok(true);
+    }
+
+    if !force {
+        anyhow::bail!(tr!("error-tunnel-not-stopped", pid = pid, timeout = timeout.as_secs()));
+    }
+
+    // the network settings of the killed tunnel can only be restored with the privileges it had
+    if !is_root() {
+        return Err(PermissionDenied(tr!("error-no-root-privileges")).into());
+    }
+
+    println!("{}", tr!("cli-tunnel-killed", pid = pid));
+    if unsafe { libc::kill(pid, libc::SIGKILL) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    if !wait_for_exit(pid, KILL_TIMEOUT).await {
+        anyhow::bail!(tr!("error-instance-not-stopped", profile = profile, pid = pid));
+    }
 
-    if params.server_name.is_empty() || params.login_type.is_empty() {
-        anyhow::bail!(tr!("error-missing-required-parameters"));
+    platform::restore_stale_resolv_conf(params);
+    platform::restore_stale_routing(params).await;
+    tunnel::journal::restore_stale(params).await;
+    let _ = platform::new_kill_switch().disable().await;
+    println!("{}", tr!("cli-network-restored"));
+
+    Ok(true)
+}
+
+#[cfg(unix)]
+const REPLACE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Polls the lock until the running instance releases it.
+#[cfg(unix)]
+async fn wait_for_lock(path: &str, timeout: Duration) -> anyhow::Result<Option<SingleInstance>> {
+    let started = Instant::now();
+    while started.elapsed() < timeout {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let instance = SingleInstance::new(path)?;
+        if instance.is_single() {
+            return Ok(Some(instance));
+        }
+    }
+    Ok(None)
+}
+
+// Takes the lock of the profile, `None` if there is no runtime directory for it. With `replace` the running
+// instance is asked to disconnect and exit through its control socket, or terminated when it does not listen.
+#[cfg(unix)]
+async fn lock_profile(params: &TunnelParams, replace: bool) -> anyhow::Result<Option<SingleInstance>> {
+    let Some(path) = control::lock_path(params) else {
+        debug!("No runtime directory for the lock file, not checking for another instance");
+        return Ok(None);
+    };
+    let path = path.to_string_lossy().into_owned();
+
+    let instance = SingleInstance::new(&path)?;
+    if instance.is_single() {
+        return Ok(Some(instance));
+    }
+
+    let profile = params.profile_name();
+    let pid = instance
+        .owner()
+        .map(|pid| pid.to_string())
+        .unwrap_or_else(|| "?".to_owned());
+    if !replace {
+        anyhow::bail!(tr!("error-profile-running", profile = profile, pid = pid));
+    }
+
+    println!("{}", tr!("cli-replacing-instance", pid = pid.as_str()));
+
+    let request = ControlRequest::Shutdown {
+        profile: Some(profile.clone()),
+    };
+    let shutdown = match control::socket_path(params) {
+        Some(socket) => control::send_request(&socket, &request)
+            .await
+            .map(|response| response.ok),
+        None => Ok(false),
+    };
+    if !matches!(shutdown, Ok(true)) {
+        debug!(
+            "The running instance did not accept the shutdown request: {:?}",
+            shutdown
+        );
+    } else if let Some(instance) = wait_for_lock(&path, REPLACE_TIMEOUT).await? {
+        return Ok(Some(instance));
+    }
+
+    // stopped by the signal handler, which cleans up like the shutdown request
+    if let Some(pid) = instance.owner() {
+        debug!("Terminating the running instance {}", pid);
+        unsafe { libc::kill(pid, libc::SIGTERM) };
+    }
+
+    match wait_for_lock(&path, REPLACE_TIMEOUT).await? {
+        Some(instance) => Ok(Some(instance)),
+        None => anyhow::bail!(tr!("error-instance-not-stopped", profile = profile, pid = pid)),
+    }
+}
+
+#[cfg(unix)]
+fn start_control_socket(params: &TunnelParams, handle: Arc<ControlHandle>) -> Option<ControlTask> {
+    match ControlSocket::bind(params) {
+        Ok(socket) => socket.map(|socket| socket.spawn(handle)),
+        Err(e) => {
+            warn!("Unable to start the control socket: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(windows)]
+fn start_control_socket(_params: &TunnelParams, _handle: Arc<ControlHandle>) -> Option<()> {
+    None
+}
+
+#[cfg(unix)]
+fn start_state_file(params: &TunnelParams, handle: Arc<ControlHandle>) -> Option<StateFileTask> {
+    state_file::start(params, handle)
+}
+
+#[cfg(windows)]
+fn start_state_file(_params: &TunnelParams, _handle: Arc<ControlHandle>) -> Option<()> {
+    None
+}
+
+#[cfg(all(target_os = "linux", feature = "dbus-service"))]
+async fn start_dbus_service(params: &TunnelParams, handle: Arc<ControlHandle>) -> Option<dbus::DbusTask> {
+    dbus::start(params.dbus_service, handle).await.unwrap_or_else(|e| {
+        warn!("Unable to register the D-Bus service: {}", e);
+        None
+    })
+}
+
+#[cfg(not(all(target_os = "linux", feature = "dbus-service")))]
+async fn start_dbus_service(params: &TunnelParams, _handle: Arc<ControlHandle>) -> Option<()> {
+    if params.dbus_service != snxcore::model::params::DbusService::None {
+        warn!("The D-Bus service is not supported by this build");
+    }
+    None
+}
+
+#[cfg(all(target_os = "linux", feature = "desktop-notifications"))]
+async fn start_notifier(params: &TunnelParams, state: &ControlState) -> Option<notify::NotifyTask> {
+    notify::start(&params.notify_events, state).await
+}
+
+#[cfg(all(target_os = "linux", feature = "desktop-notifications"))]
+async fn stop_notifier(notifier: Option<notify::NotifyTask>) {
+    if let Some(notifier) = notifier {
+        notifier.shutdown().await;
+    }
+}
+
+#[cfg(not(all(target_os = "linux", feature = "desktop-notifications")))]
+async fn start_notifier(params: &TunnelParams, _state: &ControlState) -> Option<()> {
+    if !params.notify_events.is_empty() {
+        warn!("Desktop notifications are not supported by this build");
+    }
+    None
+}
+
+#[cfg(not(all(target_os = "linux", feature = "desktop-notifications")))]
+async fn stop_notifier(_notifier: Option<()>) {}
+
+// The lifecycle event of a finished session, `None` when the loop goes on with a new one.
+fn session_end_event(end: &anyhow::Result<SessionEnd>, reauthenticating: bool) -> Option<LifecycleEvent> {
+    match end {
+        Ok(SessionEnd::Reconnect | SessionEnd::Reauth) => None,
+        Ok(_) => Some(LifecycleEvent::Disconnected { reason: None }),
+        Err(e)
+            if reauthenticating
+                && matches!(FailureKind::of(e), FailureKind::AuthRejected | FailureKind::MfaRequired) =>
+        {
+            Some(LifecycleEvent::ReauthFailed { error: e.to_string() })
+        }
+        Err(e) => Some(LifecycleEvent::Disconnected {
+            reason: Some(e.to_string()),
+        }),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SessionEnd {
+    /// The tunnel stopped by itself or due to a signal.
+    Terminated,
+    /// Disconnect requested by a control client.
+    Disconnected,
+    /// A trusted network was detected.
+    Suspended,
+    Reconnect,
+    Reauth,
+}
+
+#[cfg(unix)]
+fn spawn_reload_on_hangup(actions: mpsc::Sender<ControlAction>) {
+    match unix::signal(unix::SignalKind::hangup()) {
+        Ok(mut hangup) => {
+            tokio::spawn(async move {
+                while hangup.recv().await.is_some() {
+                    if actions.send(ControlAction::Reload).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        Err(e) => warn!("Unable to handle SIGHUP: {}", e),
+    }
+}
+
+#[cfg(windows)]
+fn spawn_reload_on_hangup(_actions: mpsc::Sender<ControlAction>) {}
+
+async fn start_health_endpoints(params: &TunnelParams, state: Arc<ControlState>) -> Option<HealthTask> {
+    let address = params.health_listen?;
+
+    match health::start(address, state).await {
+        Ok(task) => Some(task),
+        Err(e) => {
+            warn!("Unable to serve the health endpoints on {}: {}", address, e);
+            None
+        }
+    }
+}
+
+// The status line of the systemd unit follows the connection status.
+async fn set_status(state: &ControlState, status: ConnectionStatus) {
+    sd_notify::status(&status.to_string());
+    state.set_status(status).await;
+}
+
+async fn main_standalone(
+    params: TunnelParams,
+    state: Arc<ControlState>,
+    set_log_level: LogLevelSetter,
+    set_log_format: LogFormatSetter,
+) -> anyhow::Result<()> {
+    platform::check_privilege_drop(&params)?;
+
+    if let Err(e) = platform::new_network_interface().start_network_state_monitoring().await {
+        warn!("Unable to start network monitoring: {}", e);
+    }
+
+    let (action_sender, mut actions) = mpsc::channel(4);
+    spawn_reload_on_hangup(action_sender.clone());
+    let handle = Arc::new(ControlHandle {
+        profile: params.profile_name(),
+        state: state.clone(),
+        actions: action_sender,
+        set_log_level,
+    });
+    let _control = start_control_socket(&params, handle.clone());
+    let _state_file = start_state_file(&params, handle.clone());
+    let _health = start_health_endpoints(&params, state.clone()).await;
+    let _dbus = start_dbus_service(&params, handle.clone()).await;
+    let notifier = start_notifier(&params, &state).await;
+
+    let result = run_standalone_sessions(Arc::new(params), &handle, &mut actions, &set_log_format).await;
+
+    // the notification of the final disconnect is shown before exiting
+    stop_notifier(notifier).await;
+
+    result
+}
+
+async fn run_standalone_sessions(
+    mut params: Arc<TunnelParams>,
+    handle: &ControlHandle,
+    actions: &mut mpsc::Receiver<ControlAction>,
+    set_log_format: &LogFormatSetter,
+) -> anyhow::Result<()> {
+    let state = &*handle.state;
+    let mut detector = TrustedNetworkDetector::new(params.clone());
+    let mut failover = GatewayFailover::new(params.clone());
+    let mut watchdog = Watchdog::from_env();
+
+    let mut reloaded = None;
+    let mut reconnects = 0;
+    let mut reauth = false;
+
+    // a socket activated service connects on the first request
+    if params.idle_start {
+        set_status(state, ConnectionStatus::Disconnected).await;
+        sd_notify::ready();
+        if !wait_for_connect(&params, handle, actions, &mut reloaded, set_log_format, &mut watchdog).await? {
+            return Ok(());
+        }
+    }
+
+    loop {
+        // a configuration reloaded during the previous session applies from the next connection on
+        if let Some(new_params) = reloaded.take() {
+            params = Arc::new(new_params);
+            detector = TrustedNetworkDetector::new(params.clone());
+            failover = GatewayFailover::new(params.clone());
+        }
+
+        // the profile follows the network when the command line names none
+        if let Some(selection) = select_network_profile(&params).await {
+            if selection.profile() != params.profile.as_deref() {
+                let loaded =
+                    load_matched_params(CmdlineParams::from_env(), selection.profile()).and_then(|new_params| {
+                        new_params.validate()?;
+                        Ok(new_params)
+                    });
+                match loaded {
+                    Ok(new_params) => {
+                        info!("Profile selection: {}", selection);
+                        println!("{}", tr!("cli-profile-switch", profile = new_params.profile_name()));
+                        // the kill switch kept by a reconnect only lets the gateway of the previous profile through
+                        tunnel::remove_kill_switch(&params).await;
+                        params = Arc::new(new_params);
+                        detector = TrustedNetworkDetector::new(params.clone());
+                        failover = GatewayFailover::new(params.clone());
+                    }
+                    Err(e) => warn!("Unable to switch to the profile selected by the network: {}", e),
+                }
+            }
+        }
+
+        if detector.is_trusted().await {
+            println!("{}", tr!("connection-status-trusted-network"));
+            set_status(state, ConnectionStatus::TrustedNetwork).await;
+
+            let mut resumed = false;
+            await_termination(watchdog.keep_alive(async {
+                detector.wait_for_change(true).await;
+                resumed = true;
+                Ok(())
+            }))
+            .await?;
+
+            if !resumed {
+                return Ok(());
+            }
+            println!("{}", tr!("cli-trusted-network-lost"));
+        }
+
+        if failover.failback() {
+            println!("{}", tr!("cli-gateway-failback", server = failover.gateway()));
+            tunnel::remove_kill_switch(&params).await;
+        }
+
+        // at boot and after an outage the network manager may still be setting up the route and DNS
+        let gate = NetworkGate::new(failover.params());
+        if gate.is_enabled() {
+            let mut waited = false;
+            await_termination(watchdog.keep_alive(async {
+                gate.wait().await;
+                waited = true;
+                Ok(())
+            }))
+            .await?;
+
+            if !waited {
+                return Ok(());
+            }
+        }
+
+        let _probe = failover.start_probe();
+        let _profile_watch =
+            ProfileWatch::start(params.clone(), load_profile_criteria(&params), handle.actions.clone());
+
+        let end = run_standalone_session(
+            failover.params(),
+            &detector,
+            handle,
+            set_log_format,
+            &mut reloaded,
+            actions,
+            &mut watchdog,
+        )
+        .await;
+
+        let connected = matches!(*state.status.read().await, ConnectionStatus::Connected(_));
+        if connected {
+            reconnects = 0;
+            failover.connected();
+        }
+        // the state file shows why the session ended
+        if let Err(ref e) = end {
+            state.stats.write().await.on_error(e.to_string());
+        }
+        // requested with the control action or by the reauth interval
+        if matches!(end, Ok(SessionEnd::Reauth)) {
+            state.stats.write().await.on_reauth();
+        }
+        set_status(state, ConnectionStatus::Disconnected).await;
+
+        // a gateway which could not be reached is skipped for the next one of the list
+        if !connected && end.as_ref().is_err_and(failover::is_failover_error) {
+            let failed = failover.gateway().to_owned();
+            if failover.next_gateway() {
+                if let Err(ref e) = end {
+                    warn!("Connection to gateway {} failed: {}", failed, e);
+                }
+                // the kill switch kept by a reconnect only lets the previous gateway through
+                tunnel::remove_kill_switch(&params).await;
+                println!(
+                    "{}",
+                    tr!("cli-gateway-failover", failed = failed, server = failover.gateway())
+                );
+                reconnects += 1;
+                state.emit(LifecycleEvent::Reconnecting { attempt: reconnects });
+                continue;
+            }
+        }
+
+        let reauthenticating = mem::replace(&mut reauth, matches!(end, Ok(SessionEnd::Reauth)));
+        if let Some(event) = session_end_event(&end, reauthenticating) {
+            state.emit(event);
+        }
+
+        // the kill switch and the devices stay in place while reconnecting on request
+        if matches!(end, Ok(SessionEnd::Reconnect | SessionEnd::Reauth)) {
+            reconnects += 1;
+            state.emit(LifecycleEvent::Reconnecting { attempt: reconnects });
+            continue;
+        }
+
+        tunnel::remove_kill_switch(&params).await;
+        tunnel::remove_persistent_devices().await;
+
+        match end? {
+            SessionEnd::Suspended => {}
+            SessionEnd::Disconnected => {
+                if !wait_for_connect(&params, handle, actions, &mut reloaded, set_log_format, &mut watchdog).await? {
+                    return Ok(());
+                }
+            }
+            _ => return Ok(()),
+        }
     }
+}
+
+// Stays idle until a control client connects again. Returns false on a shutdown request, on termination
+// and after `idle-timeout` without control clients.
+async fn wait_for_connect(
+    params: &TunnelParams,
+    handle: &ControlHandle,
+    actions: &mut mpsc::Receiver<ControlAction>,
+    reloaded: &mut Option<TunnelParams>,
+    set_log_format: &LogFormatSetter,
+    watchdog: &mut Watchdog,
+) -> anyhow::Result<bool> {
+    println!("{}", tr!("cli-waiting-for-connect"));
+
+    let idle = async {
+        match params.idle_timeout {
+            Some(timeout) => handle.state.wait_idle(timeout).await,
+            None => futures::future::pending().await,
+        }
+    };
+    pin_mut!(idle);
+
+    let mut connect = false;
+    await_termination(watchdog.keep_alive(async {
+        loop {
+            tokio::select! {
+                action = actions.recv() => match action {
+                    Some(ControlAction::Connect) => {
+                        connect = true;
+                        break;
+                    }
+                    Some(ControlAction::Reload) => {
+                        let current = reloaded.as_ref().unwrap_or(params);
+                        if let Some(new_params) = reload_config(current, handle, set_log_format) {
+                            *reloaded = Some(new_params);
+                        }
+                    }
+                    Some(ControlAction::Shutdown) | None => break,
+                    Some(_) => {}
+                },
+                _ = &mut idle => {
+                    info!("No tunnel and no control clients, exiting");
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }))
+    .await?;
 
+    Ok(connect)
+}
+
+async fn authenticate(
+    params: Arc<TunnelParams>,
+) -> anyhow::Result<(Box<dyn TunnelConnector + Send + Sync>, Arc<VpnSession>)> {
     let mut mfa_prompts = server_info::get_login_prompts(&params).await.unwrap_or_default();
 
-    let params = Arc::new(params);
     let mut connector = tunnel::new_tunnel_connector(params.clone()).await?;
 
+    let auth_span = tunnel::auth_span(&params);
+
     let mut session = if params.ike_persist {
         debug!("Attempting to load IKE session");
-        match connector.restore_session().await {
+        match connector.restore_session().instrument(auth_span.clone()).await {
             Ok(session) => session,
             Err(_) => {
                 connector = tunnel::new_tunnel_connector(params.clone()).await?;
-                connector.authenticate().await?
+                connector.authenticate().instrument(auth_span.clone()).await?
             }
         }
     } else {
-        connector.authenticate().await?
+        connector.authenticate().instrument(auth_span.clone()).await?
     };
 
     let mut mfa_index = 0;
@@ -171,7 +1258,10 @@ async fn main_standalone(params: TunnelParams) -> anyhow::Result<()> {
 
                 match input {
                     Ok(input) => {
-                        session = connector.challenge_code(session, &input).await?;
+                        session = connector
+                            .challenge_code(session, &input)
+                            .instrument(auth_span.clone())
+                            .await?;
                     }
                     Err(e) => {
                         return Err(e);
@@ -179,41 +1269,171 @@ async fn main_standalone(params: TunnelParams) -> anyhow::Result<()> {
                 }
             }
             MfaType::IdentityProvider => {
+                // nobody could open the browser of the service
+                if is_windows_service() {
+                    return Err(NoTty.into());
+                }
                 println!("{}", tr!("cli-identity-provider-auth"));
                 println!("{}", challenge.prompt);
                 let (_tx, rx) = oneshot::channel();
                 let receiver = spawn_otp_listener(rx);
                 let otp = receiver.await??;
-                session = connector.challenge_code(session, &otp).await?;
+                session = connector
+                    .challenge_code(session, &otp)
+                    .instrument(auth_span.clone())
+                    .await?;
             }
             MfaType::UserNameInput => {
                 let prompt = PromptInfo::new(tr!("label-username-required"), &challenge.prompt);
                 let input = TtyPrompt.get_plain_input(prompt).await?;
-                session = connector.challenge_code(session, &input).await?;
+                session = connector
+                    .challenge_code(session, &input)
+                    .instrument(auth_span.clone())
+                    .await?;
             }
         }
     }
 
-    let tunnel = connector.create_tunnel(session.clone(), command_sender).await?;
+    Ok((connector, session))
+}
 
-    if let Err(e) = platform::new_network_interface().start_network_state_monitoring().await {
-        warn!("Unable to start network monitoring: {}", e);
+// Applies the log options of the reloaded configuration and reports the options which wait for a reconnect.
+// A configuration with errors is rejected as a whole.
+fn reload_config(
+    running: &TunnelParams,
+    handle: &ControlHandle,
+    set_log_format: &LogFormatSetter,
+) -> Option<TunnelParams> {
+    sd_notify::reloading();
+
+    let new_params = match reload_params(running) {
+        Ok(new_params) => new_params,
+        Err(e) => {
+            warn!("Unable to reload the configuration, keeping the current one: {}", e);
+            eprintln!("{}", tr!("cli-reload-failed", error = e.to_string()));
+            sd_notify::ready();
+            return None;
+        }
+    };
+    debug!("Reloaded configuration: {}", new_params.to_redacted_json());
+
+    let changes = running.changes(&new_params);
+
+    if changes.is_live("log-level") || changes.is_live("log-filter") {
+        if let Err(e) = (handle.set_log_level)(new_params.log_targets()) {
+            warn!("Unable to change the log level: {}", e);
+        }
+    }
+
+    if changes.is_live("log-format") {
+        if let Err(e) = set_log_format(new_params.log_format) {
+            warn!("Unable to change the log format: {}", e);
+        }
     }
 
+    println!("{}", tr!("cli-config-reloaded"));
+
+    if !changes.reconnect.is_empty() {
+        println!(
+            "{}",
+            tr!("cli-reload-on-reconnect", options = changes.reconnect.join(", "))
+        );
+    }
+
+    if !changes.restart.is_empty() {
+        println!("{}", tr!("cli-reload-on-restart", options = changes.restart.join(", ")));
+    }
+
+    sd_notify::ready();
+
+    Some(new_params)
+}
+
+async fn run_standalone_session(
+    params: Arc<TunnelParams>,
+    detector: &TrustedNetworkDetector,
+    handle: &ControlHandle,
+    set_log_format: &LogFormatSetter,
+    reloaded: &mut Option<TunnelParams>,
+    actions: &mut mpsc::Receiver<ControlAction>,
+    watchdog: &mut Watchdog,
+) -> anyhow::Result<SessionEnd> {
+    // TODO: reuse code from CommandServer and ServiceController
+
+    let state = &*handle.state;
+
+    // the requests which arrived while suspended do not apply to the new session
+    while actions.try_recv().is_ok() {}
+
+    set_status(state, ConnectionStatus::Connecting).await;
+
+    let (command_sender, command_receiver) = mpsc::channel(16);
+
+    // the prompts and the identity provider may take a while
+    let (mut connector, session) = watchdog.keep_alive(authenticate(params.clone())).await?;
+
+    // the options of a reloaded configuration which the tunnel applies in place
+    let tunnel_commands = command_sender.clone();
+    let mut running = params.clone();
+
+    let tunnel = connector.create_tunnel(session.clone(), command_sender).await?;
+
     let (event_sender, mut event_receiver) = mpsc::channel(16);
-    let tunnel_fut = await_termination(tunnel.run(command_receiver, event_sender));
+    let tunnel_fut = await_termination(
+        tunnel
+            .run(command_receiver, event_sender)
+            .instrument(tunnel::session_span(&params, &session)),
+    );
 
     pin_mut!(tunnel_fut);
 
-    loop {
+    let trusted_fut = detector.wait_for_change(false);
+
+    pin_mut!(trusted_fut);
+
+    let mut failure = None;
+
+    let end = loop {
         tokio::select! {
             event = event_receiver.recv() => {
                 if let Some(event) = event {
                     let _ = connector.handle_tunnel_event(event.clone()).await;
 
-                    if let TunnelEvent::Connected(info) = event {
-                        println!("{}", info.print());
-                        println!("{}", tr!("cli-tunnel-connected"));
+                    match event {
+                        TunnelEvent::Connected(info) => {
+                            // the network is set up, nothing else needs root
+                            if let Err(e) = platform::drop_privileges(&params) {
+                                failure = Some(e);
+                                break SessionEnd::Terminated;
+                            }
+                            println!("{}", info.print());
+                            println!("{}", tr!("cli-tunnel-connected"));
+                            state.emit(LifecycleEvent::Connected {
+                                address: info.ip_address.addr().to_string(),
+                            });
+                            state.stats.write().await.on_connected();
+                            set_status(state, ConnectionStatus::connected(info)).await;
+                            sd_notify::ready();
+                        }
+                        TunnelEvent::SessionExpiring(remaining) => {
+                            println!("{}", tr!("cli-session-expiring", minutes = remaining.as_secs().div_ceil(60)));
+                        }
+                        TunnelEvent::Rekeyed(address) => {
+                            state.stats.write().await.on_rekey();
+                            if let ConnectionStatus::Connected(ref mut info) = *state.status.write().await {
+                                info.ip_address = address;
+                            }
+                        }
+                        TunnelEvent::DnsChecked(results) => {
+                            if results.iter().any(|r| !r.passed) {
+                                eprintln!("{}", tr!("cli-dns-check-failed"));
+                            }
+                            if let ConnectionStatus::Connected(ref mut info) = *state.status.write().await {
+                                info.dns_check = results;
+                            }
+                        }
+                        TunnelEvent::Keepalive(ok) => state.set_keepalive(ok),
+                        _ => {}
                     }
                 }
             }
@@ -224,8 +1444,64 @@ async fn main_standalone(params: TunnelParams) -> anyhow::Result<()> {
                     let _ = client.signout().await;
                 }
                 println!("\n{}", tr!("cli-tunnel-disconnected"));
-                break result;
+
+                // the error is recorded with the end of the session
+                state.stats.write().await.on_disconnected();
+                return result.map(|_| SessionEnd::Terminated);
+            }
+            _ = &mut trusted_fut, if detector.is_enabled() => {
+                println!("{}", tr!("connection-status-trusted-network"));
+                break SessionEnd::Suspended;
+            }
+            _ = watchdog.tick() => {}
+            Some(action) = actions.recv() => {
+                debug!("Control action: {:?}", action);
+                let end = match action {
+                    ControlAction::Connect => continue,
+                    ControlAction::Reload => {
+                        if let Some(new_params) = reload_config(&running, handle, set_log_format) {
+                            running = Arc::new(running.with_live_options(&new_params));
+                            let _ = tunnel_commands.send(TunnelCommand::Reconfigure(running.clone())).await;
+                            *reloaded = Some(new_params);
+                        }
+                        continue;
+                    }
+                    ControlAction::Disconnect => SessionEnd::Disconnected,
+                    ControlAction::Reconnect => SessionEnd::Reconnect,
+                    ControlAction::Reauth => SessionEnd::Reauth,
+                    ControlAction::Shutdown => SessionEnd::Terminated,
+                };
+                println!("\n{}", tr!("cli-tunnel-disconnected"));
+                break end;
             }
         }
+    };
+
+    // a reconnect with ike-persist reuses the IKE session, everything else signs out
+    let keep_session = end == SessionEnd::Reconnect && params.ike_persist && params.tunnel_type == TunnelType::Ipsec;
+
+    let _ = connector.terminate_tunnel(!keep_session).await;
+    let result = (&mut tunnel_fut).await;
+
+    while let Ok(event) = event_receiver.try_recv() {
+        let _ = connector.handle_tunnel_event(event).await;
     }
+
+    if end == SessionEnd::Reauth {
+        connector.delete_session().await;
+    }
+
+    if !keep_session && (params.tunnel_type == TunnelType::Ssl || !params.ike_persist) {
+        debug!("Signing out");
+        let client = CccHttpClient::new(params.clone(), Some(session));
+        let _ = client.signout().await;
+    }
+
+    state.stats.write().await.on_disconnected();
+
+    if let Some(e) = failure {
+        return Err(e);
+    }
+
+    result.map(|_| end)
 }
@@ -0,0 +1,170 @@
+//! The required parameters missing from the configuration are asked for when the tunnel is started from a terminal,
+//! the answers can be saved to the configuration file. Otherwise the validation reports them by name.
+
+use std::io::{IsTerminal, stderr, stdin};
+
+use i18n::tr;
+use snxcore::{
+    model::{
+        PromptInfo,
+        params::{self, CertType, TunnelParams},
+    },
+    prompt::SecurePrompt,
+    server_info,
+};
+
+use crate::setup;
+
+/// Names of the required parameters which are not set. The user name is asked for by the password logins only.
+fn missing_params(params: &TunnelParams) -> Vec<&'static str> {
+    let mut missing = Vec::new();
+
+    if params.server_name.is_empty() {
+        missing.push("server-name");
+    }
+    if params.login_type.is_empty() {
+        missing.push("login-type");
+    }
+    if params.cert_type == CertType::None && params.user_name.is_empty() {
+        missing.push("user-name");
+    }
+
+    missing
+}
+
+/// Whether the missing parameters can be asked for: both the input and the prompts must be a terminal.
+pub fn is_interactive(non_interactive: bool) -> bool {
+    !non_interactive && stdin().is_terminal() && stderr().is_terminal()
+}
+
+struct Questions<'a, P> {
+    prompt: &'a P,
+}
+
+impl<P: SecurePrompt + Sync> Questions<'_, P> {
+    async fn ask(&self, prompt: String) -> anyhow::Result<String> {
+        self.prompt
+            .get_plain_input(PromptInfo::new("", format!("{prompt}: ")))
+            .await
+    }
+
+    async fn server_name(&self) -> anyhow::Result<String> {
+        loop {
+            let answer = self.ask(tr!("setup-server-prompt")).await?;
+            if params::is_valid_server_name(&answer) {
+                return Ok(answer);
+            }
+            eprintln!("{}", tr!("error-invalid-server-name", server = answer));
+        }
+    }
+
+    // the login options are only known to the gateway
+    async fn login_type(&self, params: &TunnelParams) -> anyhow::Result<String> {
+        let info = server_info::get_uncached(params)
+            .await
+            .map_err(|e| setup::unreachable_error(params, e))?;
+        let options = setup::login_options(info);
+
+        println!("{}", tr!("setup-login-options"));
+        for (index, option) in options.iter().enumerate() {
+            println!("  {}) {} ({})", index + 1, option.display_name, option.id);
+        }
+
+        loop {
+            let answer = self
+                .ask(tr!(
+                    "setup-login-type-prompt",
+                    default = options[0].display_name.as_str()
+                ))
+                .await?;
+            match setup::select_login_option(&options, &answer) {
+                Some(option) => return Ok(option.id.clone()),
+                None => eprintln!("{}", tr!("error-setup-invalid-choice", value = answer)),
+            }
+        }
+    }
+
+    async fn user_name(&self) -> anyhow::Result<String> {
+        loop {
+            let answer = self.ask(tr!("setup-user-prompt")).await?;
+            if !answer.is_empty() {
+                return Ok(answer);
+            }
+        }
+    }
+
+    async fn save(&self, params: &TunnelParams, answers: &[(&str, String)]) -> anyhow::Result<()> {
+        let path = if params.config_file.as_os_str().is_empty() {
+            TunnelParams::user_config_path()
+        } else {
+            params.config_file.clone()
+        };
+        let prompt = format!(
+            "{} [y/N]",
+            tr!("prompt-save-answers", path = path.display().to_string())
+        );
+
+        let save = loop {
+            if let Some(answer) = setup::parse_yes_no(&self.ask(prompt.clone()).await?, false) {
+                break answer;
+            }
+        };
+
+        if save {
+            let options = answers.iter().map(|(k, v)| (*k, v.as_str())).collect::<Vec<_>>();
+            let path = params.save_options(&options)?;
+            println!("{}", tr!("setup-config-written", path = path.display().to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Ask for the required parameters missing from the configuration.
+pub async fn complete<P>(params: &mut TunnelParams, prompt: &P) -> anyhow::Result<()>
+where
+    P: SecurePrompt + Sync,
+{
+    let missing = missing_params(params);
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    eprintln!("{}", tr!("prompt-missing-parameters", options = missing.join(", ")));
+
+    let questions = Questions { prompt };
+    let mut answers = Vec::new();
+
+    if params.server_name.is_empty() {
+        params.server_name = questions.server_name().await?;
+        answers.push(("server-name", params.server_name.clone()));
+    }
+    if params.login_type.is_empty() {
+        params.login_type = questions.login_type(params).await?;
+        answers.push(("login-type", params.login_type.clone()));
+    }
+    if params.cert_type == CertType::None && params.user_name.is_empty() {
+        params.user_name = questions.user_name().await?;
+        answers.push(("user-name", params.user_name.clone()));
+    }
+
+    questions.save(params, &answers).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_params() {
+        let mut params = TunnelParams::default();
+        assert_eq!(missing_params(&params), ["server-name", "login-type", "user-name"]);
+
+        params.server_name = "vpn.example.com".to_owned();
+        params.login_type = "vpn_Username_Password".to_owned();
+        assert_eq!(missing_params(&params), ["user-name"]);
+
+        params.cert_type = CertType::Pkcs12;
+        assert!(missing_params(&params).is_empty());
+    }
+}
@@ -0,0 +1,344 @@
+//! First-run setup: the gateway is probed for its certificate and login options, the questions which are not
+//! answered by the command line options are asked, then the validated configuration is written. In the
+//! non-interactive mode the defaults are taken instead of asking, so that the setup can be scripted.
+
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::anyhow;
+use i18n::tr;
+use snxcore::{
+    model::{
+        PromptInfo,
+        params::{DnsMode, TunnelParams},
+        proto::{LoginOption, ServerInfoResponse},
+    },
+    platform::{self, Keychain},
+    prompt::{SecurePrompt, TtyPrompt},
+    server_info,
+};
+
+use crate::cmdline::SetupArgs;
+
+struct Wizard {
+    args: SetupArgs,
+}
+
+impl Wizard {
+    async fn ask(&self, prompt: String) -> anyhow::Result<String> {
+        TtyPrompt
+            .get_plain_input(PromptInfo::new("", format!("{prompt}: ")))
+            .await
+    }
+
+    // the given answer wins, then the default in the non-interactive mode, otherwise the question is repeated
+    async fn confirm(&self, answer: Option<bool>, prompt: String, default: bool) -> anyhow::Result<bool> {
+        if let Some(answer) = answer {
+            return Ok(answer);
+        }
+        if self.args.non_interactive {
+            return Ok(default);
+        }
+        let prompt = format!("{prompt} [{}]", if default { "Y/n" } else { "y/N" });
+        loop {
+            if let Some(answer) = parse_yes_no(&self.ask(prompt.clone()).await?, default) {
+                return Ok(answer);
+            }
+        }
+    }
+
+    fn required(&self, option: &str) -> anyhow::Error {
+        anyhow!(tr!("error-setup-missing-option", option = option))
+    }
+
+    async fn server_name(&self) -> anyhow::Result<String> {
+        let server_name = match self.args.server_name {
+            Some(ref server_name) => server_name.clone(),
+            None if self.args.non_interactive => return Err(self.required("--server-name")),
+            None => self.ask(tr!("setup-server-prompt")).await?,
+        };
+
+        if server_name.is_empty() {
+            anyhow::bail!(tr!("error-missing-server-name"));
+        }
+
+        Ok(server_name)
+    }
+
+    async fn login_option(&self, options: &[LoginOption]) -> anyhow::Result<LoginOption> {
+        if let Some(ref login_type) = self.args.login_type {
+            return options
+                .iter()
+                .find(|option| option.id == *login_type)
+                .cloned()
+                .ok_or_else(|| anyhow!(tr!("error-setup-invalid-choice", value = login_type.as_str())));
+        }
+
+        if self.args.non_interactive {
+            return Ok(options[0].clone());
+        }
+
+        loop {
+            let answer = self
+                .ask(tr!(
+                    "setup-login-type-prompt",
+                    default = options[0].display_name.as_str()
+                ))
+                .await?;
+            match select_login_option(options, &answer) {
+                Some(option) => return Ok(option.clone()),
+                None => eprintln!("{}", tr!("error-setup-invalid-choice", value = answer)),
+            }
+        }
+    }
+
+    async fn user_name(&self) -> anyhow::Result<String> {
+        match self.args.user_name {
+            Some(ref user_name) => Ok(user_name.clone()),
+            None if self.args.non_interactive => Ok(String::new()),
+            None => self.ask(tr!("setup-user-prompt")).await,
+        }
+    }
+
+    async fn password(&self) -> anyhow::Result<String> {
+        match self.args.password {
+            Some(ref password) => {
+                let mut params = TunnelParams {
+                    password: password.clone(),
+                    ..Default::default()
+                };
+                params.decode_password()?;
+                Ok(params.password)
+            }
+            None if self.args.non_interactive => Err(self.required("--password")),
+            None => {
+                TtyPrompt
+                    .get_secure_input(PromptInfo::new("", format!("{}: ", tr!("setup-password-prompt"))))
+                    .await
+            }
+        }
+    }
+
+    async fn dns_mode(&self) -> anyhow::Result<DnsMode> {
+        if let Some(dns_mode) = self.args.dns_mode {
+            return Ok(dns_mode);
+        }
+        if self.args.non_interactive {
+            return Ok(DnsMode::default());
+        }
+        loop {
+            let answer = self.ask(tr!("setup-dns-mode-prompt")).await?;
+            if answer.is_empty() {
+                return Ok(DnsMode::default());
+            }
+            match answer.parse() {
+                Ok(dns_mode) => return Ok(dns_mode),
+                Err(e) => eprintln!("{e}"),
+            }
+        }
+    }
+}
+
+pub(crate) fn parse_yes_no(answer: &str, default: bool) -> Option<bool> {
+    match answer.trim().to_lowercase().as_str() {
+        "" => Some(default),
+        "y" | "yes" => Some(true),
+        "n" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+// by the number in the list or by the id, the first option is the default
+pub(crate) fn select_login_option<'a>(options: &'a [LoginOption], answer: &str) -> Option<&'a LoginOption> {
+    let answer = answer.trim();
+    if answer.is_empty() {
+        return options.first();
+    }
+    if let Ok(index) = answer.parse::<usize>() {
+        return index.checked_sub(1).and_then(|index| options.get(index));
+    }
+    options.iter().find(|option| option.id == answer)
+}
+
+fn has_password_factor(option: &LoginOption) -> bool {
+    option.factors.values().any(|factor| factor.factor_type == "password")
+}
+
+pub(crate) fn login_options(info: ServerInfoResponse) -> Vec<LoginOption> {
+    let options = info
+        .login_options_data
+        .map(|data| data.login_options_list)
+        .unwrap_or_default()
+        .into_values()
+        .filter(|option| option.show_realm != 0)
+        .collect::<Vec<_>>();
+
+    if options.is_empty() {
+        vec![LoginOption::unspecified()]
+    } else {
+        options
+    }
+}
+
+pub(crate) fn unreachable_error(params: &TunnelParams, e: anyhow::Error) -> anyhow::Error {
+    anyhow!(tr!(
+        "error-setup-unreachable",
+        server = params.server_name.as_str(),
+        error = e.to_string()
+    ))
+}
+
+pub async fn run(args: SetupArgs, config_file: Option<PathBuf>, profile: Option<String>) -> anyhow::Result<()> {
+    let wizard = Wizard { args };
+
+    let server_name = wizard.server_name().await?;
+
+    // asked before the probe, so that declining costs nothing
+    let config_file = config_file.unwrap_or_else(TunnelParams::user_config_path);
+    if config_file.exists() && !wizard.args.force {
+        let path = config_file.display().to_string();
+        if wizard.args.non_interactive {
+            anyhow::bail!(tr!("error-setup-config-exists", path = path));
+        }
+        if !wizard
+            .confirm(None, tr!("setup-overwrite-prompt", path = path), false)
+            .await?
+        {
+            println!("{}", tr!("setup-cancelled"));
+            return Ok(());
+        }
+    }
+
+    let mut params = TunnelParams {
+        server_name,
+        config_file,
+        profile,
+        ignore_server_cert: wizard.args.ignore_server_cert.unwrap_or_default(),
+        ..Default::default()
+    };
+
+    println!("{}", tr!("setup-probing", server = params.server_name.as_str()));
+
+    let cert = server_info::get_certificate(&params)
+        .await
+        .map_err(|e| unreachable_error(&params, e))?;
+    println!(
+        "{}",
+        tr!(
+            "setup-certificate",
+            subject = cert.subject,
+            issuer = cert.issuer,
+            expires = cert.not_after
+        )
+    );
+    println!("{}", tr!("setup-fingerprint", fingerprint = cert.fingerprint));
+
+    if let (Some(error), false) = (cert.verify_error, params.ignore_server_cert) {
+        println!("{}", tr!("setup-certificate-untrusted", error = error));
+        params.ignore_server_cert = wizard
+            .confirm(wizard.args.ignore_server_cert, tr!("setup-ignore-cert-prompt"), false)
+            .await?;
+        if !params.ignore_server_cert {
+            anyhow::bail!(tr!("error-setup-certificate-rejected"));
+        }
+    }
+
+    let info = server_info::get_uncached(&params)
+        .await
+        .map_err(|e| unreachable_error(&params, e))?;
+    let options = login_options(info);
+
+    println!("{}", tr!("setup-login-options"));
+    for (index, option) in options.iter().enumerate() {
+        println!("  {}) {} ({})", index + 1, option.display_name, option.id);
+    }
+
+    let option = wizard.login_option(&options).await?;
+    params.login_type = option.id.clone();
+    params.user_name = wizard.user_name().await?;
+
+    let store_password = has_password_factor(&option)
+        && !params.user_name.is_empty()
+        && wizard
+            .confirm(wizard.args.store_password, tr!("setup-store-password-prompt"), false)
+            .await?;
+    let password = if store_password {
+        Some(wizard.password().await?)
+    } else {
+        None
+    };
+
+    params.default_route = wizard
+        .confirm(wizard.args.default_route, tr!("setup-default-route-prompt"), false)
+        .await?;
+    params.dns_mode = wizard.dns_mode().await?;
+
+    params.validate()?;
+    params.save()?;
+    println!(
+        "{}",
+        tr!("setup-config-written", path = params.config_file.display().to_string())
+    );
+
+    if let Some(ref password) = password {
+        platform::new_keychain()
+            .store_password(&params.user_name, password)
+            .await?;
+    }
+
+    if wizard
+        .confirm(wizard.args.test_connect, tr!("setup-test-connect-prompt"), false)
+        .await?
+    {
+        // only the login is tested, bringing up the tunnel would need the network privileges
+        let params = Arc::new(TunnelParams {
+            password: password.unwrap_or_default(),
+            skip_permission_check: true,
+            ..params
+        });
+        let (mut connector, _) = crate::authenticate(params).await?;
+        connector.delete_session().await;
+        println!("{}", tr!("setup-test-connect-ok"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn option(id: &str) -> LoginOption {
+        LoginOption {
+            id: id.to_owned(),
+            ..LoginOption::unspecified()
+        }
+    }
+
+    #[test]
+    fn test_parse_yes_no() {
+        assert_eq!(parse_yes_no("", true), Some(true));
+        assert_eq!(parse_yes_no(" Y ", false), Some(true));
+        assert_eq!(parse_yes_no("no", true), Some(false));
+        assert_eq!(parse_yes_no("maybe", false), None);
+    }
+
+    #[test]
+    fn test_select_login_option() {
+        let options = vec![option("vpn_Username_Password"), option("vpn_Microsoft_Authenticator")];
+
+        assert_eq!(select_login_option(&options, "").unwrap().id, "vpn_Username_Password");
+        assert_eq!(
+            select_login_option(&options, "2").unwrap().id,
+            "vpn_Microsoft_Authenticator"
+        );
+        assert_eq!(
+            select_login_option(&options, "vpn_Microsoft_Authenticator").unwrap().id,
+            "vpn_Microsoft_Authenticator"
+        );
+        assert!(select_login_option(&options, "0").is_none());
+        assert!(select_login_option(&options, "3").is_none());
+        assert!(select_login_option(&options, "vpn").is_none());
+
+        assert!(has_password_factor(&options[0]));
+    }
+}
@@ -0,0 +1,331 @@
+//! Windows service of the standalone tunnel: the service control manager starts it at boot, independently of the
+//! logged on users, and stops it the way SIGTERM stops the tunnel on Unix. There is no console, the log goes to a file.
+
+use std::{
+    ffi::OsString,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, LazyLock, Mutex, OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use i18n::tr;
+use snxcore::{control::ControlState, error::FailureKind};
+use tokio::sync::watch;
+use tracing::{debug, warn};
+use windows_service::{
+    define_windows_service,
+    service::{
+        ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode, ServiceInfo,
+        ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+    },
+    service_control_handler::{self, ServiceControlHandlerResult, ServiceStatusHandle},
+    service_dispatcher,
+    service_manager::{ServiceManager, ServiceManagerAccess},
+};
+
+use crate::{cmdline::CmdlineParams, exit_code::ExitCode};
+
+pub const DEFAULT_SERVICE_NAME: &str = "snx-rs";
+const DISPLAY_NAME: &str = "snx-rs VPN tunnel";
+const DESCRIPTION: &str = "Check Point VPN tunnel of snx-rs";
+
+// the checkpoints tell the service control manager that the slow connect phase is still making progress
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+const PENDING_WAIT_HINT: Duration = Duration::from_secs(10);
+// the services which depend on this one are not held back forever by a gateway which does not answer
+const START_PENDING_LIMIT: Duration = Duration::from_secs(120);
+// the network settings are restored before the process exits
+const STOP_WAIT_HINT: Duration = Duration::from_secs(15);
+const UNINSTALL_TIMEOUT: Duration = Duration::from_secs(20);
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static SERVICE_NAME: OnceLock<String> = OnceLock::new();
+static STATUS: OnceLock<StatusReporter> = OnceLock::new();
+static STOP: LazyLock<watch::Sender<bool>> = LazyLock::new(|| watch::Sender::new(false));
+
+define_windows_service!(ffi_service_main, service_main);
+
+/// Whether this process runs as the Windows service, there is nobody to answer a prompt then.
+pub fn is_running() -> bool {
+    RUNNING.load(Ordering::SeqCst)
+}
+
+/// Completes when the service control manager stops the service, never outside of the service.
+pub async fn stop_requested() {
+    let mut stop = STOP.subscribe();
+    let _ = stop.wait_for(|stop| *stop).await;
+}
+
+/// Log file of the service when the configuration does not select one.
+pub fn default_log_file() -> PathBuf {
+    let program_data = std::env::var_os("ProgramData").unwrap_or_else(|| OsString::from(r"C:\ProgramData"));
+    Path::new(&program_data).join("snx-rs").join("snx-rs.log")
+}
+
+// Reports the status transitions, there is no way back from stopping to starting or running.
+struct StatusReporter {
+    handle: ServiceStatusHandle,
+    state: Mutex<ServiceState>,
+}
+
+impl StatusReporter {
+    fn set(&self, state: ServiceState, checkpoint: u32, exit_code: ServiceExitCode) {
+        let mut current = self.state.lock().unwrap();
+        if matches!(*current, ServiceState::StopPending | ServiceState::Stopped)
+            && matches!(state, ServiceState::StartPending | ServiceState::Running)
+        {
+            return;
+        }
+        *current = state;
+
+        // the connect phase may take minutes, the service can be stopped before it completes
+        let (controls_accepted, wait_hint) = match state {
+            ServiceState::StartPending => (
+                ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+                PENDING_WAIT_HINT,
+            ),
+            ServiceState::Running => (
+                ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+                Duration::ZERO,
+            ),
+            ServiceState::StopPending => (ServiceControlAccept::empty(), STOP_WAIT_HINT),
+            _ => (ServiceControlAccept::empty(), Duration::ZERO),
+        };
+
+        let status = ServiceStatus {
+            service_type: ServiceType::OWN_PROCESS,
+            current_state: state,
+            controls_accepted,
+            exit_code,
+            checkpoint,
+            wait_hint,
+            process_id: None,
+        };
+
+        if let Err(e) = self.handle.set_service_status(status) {
+            warn!("Unable to report the service status {:?}: {}", state, e);
+        }
+    }
+
+    fn report(&self, state: ServiceState, checkpoint: u32) {
+        self.set(state, checkpoint, ServiceExitCode::Win32(0));
+    }
+}
+
+/// Hand the process over to the service control manager, which runs the tunnel on its own thread.
+pub fn run_dispatcher(name: &str) -> anyhow::Result<()> {
+    let _ = SERVICE_NAME.set(name.to_owned());
+    service_dispatcher::start(name, ffi_service_main)
+        .context("run-service is started by the service control manager, register it with install-service")
+}
+
+fn service_main(_arguments: Vec<OsString>) {
+    RUNNING.store(true, Ordering::SeqCst);
+
+    let name = SERVICE_NAME.get().map_or(DEFAULT_SERVICE_NAME, String::as_str);
+    let handler = |control| match control {
+        ServiceControl::Stop | ServiceControl::Shutdown => {
+            if let Some(status) = STATUS.get() {
+                status.report(ServiceState::StopPending, 1);
+            }
+            STOP.send_replace(true);
+            ServiceControlHandlerResult::NoError
+        }
+        ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+        _ => ServiceControlHandlerResult::NotImplemented,
+    };
+
+    // without the handle nothing can be reported, the service control manager gives up on its own
+    let handle = match service_control_handler::register(name, handler) {
+        Ok(handle) => handle,
+        Err(e) => {
+            log_startup_error(&anyhow::Error::from(e).context("Unable to register the service control handler"));
+            return;
+        }
+    };
+    let status = STATUS.get_or_init(|| StatusReporter {
+        handle,
+        state: Mutex::new(ServiceState::StartPending),
+    });
+    status.report(ServiceState::StartPending, 0);
+
+    let exit_code = match run_service(status) {
+        Ok(()) => ServiceExitCode::Win32(0),
+        Err(e) => {
+            warn!("The service stopped with an error: {:#}", e);
+            ServiceExitCode::ServiceSpecific(ExitCode::of(&e).code() as u32)
+        }
+    };
+
+    status.set(ServiceState::Stopped, 0, exit_code);
+}
+
+fn run_service(status: &'static StatusReporter) -> anyhow::Result<()> {
+    // the tunnel sets up the log, the configuration errors are found before that
+    let config = crate::load_params(CmdlineParams::from_env()).and_then(|params| Ok(params.validate()?));
+    if let Err(e) = config {
+        log_startup_error(&e);
+        return Err(e);
+    }
+
+    let state = Arc::new(ControlState::default());
+    let done = Arc::new(AtomicBool::new(false));
+    let progress = thread::spawn({
+        let states = state.subscribe();
+        let done = done.clone();
+        move || report_progress(status, states, &done)
+    });
+
+    let result = crate::run_tunnel(CmdlineParams::from_env(), state);
+
+    done.store(true, Ordering::SeqCst);
+    let _ = progress.join();
+
+    result.map_err(|e| match FailureKind::of(&e) {
+        FailureKind::MfaRequired => e.context(tr!("error-service-mfa")),
+        _ => e,
+    })
+}
+
+// The service is running once the tunnel is up, or a trusted network makes it unnecessary.
+fn report_progress(status: &StatusReporter, states: watch::Receiver<&'static str>, done: &AtomicBool) {
+    let started = Instant::now();
+    let mut checkpoint = 1;
+
+    while !done.load(Ordering::SeqCst) {
+        let state = *states.borrow();
+        if matches!(state, "connected" | "trusted-network") {
+            debug!("Service is running, the tunnel state is {}", state);
+            status.report(ServiceState::Running, 0);
+            return;
+        }
+
+        if started.elapsed() >= START_PENDING_LIMIT {
+            warn!("The tunnel is still connecting, reporting the service as running");
+            status.report(ServiceState::Running, 0);
+            return;
+        }
+
+        status.report(ServiceState::StartPending, checkpoint);
+        checkpoint += 1;
+        thread::sleep(PROGRESS_INTERVAL);
+    }
+}
+
+fn log_startup_error(error: &anyhow::Error) {
+    let path = default_log_file();
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Ok(mut file) = fs::OpenOptions::new().append(true).create(true).open(&path) {
+        let _ = writeln!(file, "ERROR The service cannot start: {error:#}");
+    }
+}
+
+// The service runs the standalone tunnel of the same configuration and profile, the paths are absolute
+// because the service starts in the system directory.
+fn service_arguments(name: &str, cmdline_params: &CmdlineParams) -> anyhow::Result<Vec<OsString>> {
+    let mut arguments = Vec::new();
+
+    if let Some(ref config_file) = cmdline_params.config_file {
+        arguments.push(OsString::from("--config-file"));
+        arguments.push(std::path::absolute(config_file)?.into_os_string());
+    }
+
+    if let Some(ref profile) = cmdline_params.profile {
+        arguments.push(OsString::from("--profile"));
+        arguments.push(OsString::from(profile));
+    }
+
+    arguments.push(OsString::from("run-service"));
+    arguments.push(OsString::from("--service-name"));
+    arguments.push(OsString::from(name));
+
+    Ok(arguments)
+}
+
+/// Register the service which starts the standalone tunnel at boot.
+pub fn install(name: &str, cmdline_params: &CmdlineParams) -> anyhow::Result<()> {
+    let manager = ServiceManager::local_computer(
+        None::<&str>,
+        ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE,
+    )?;
+
+    let info = ServiceInfo {
+        name: OsString::from(name),
+        display_name: OsString::from(DISPLAY_NAME),
+        service_type: ServiceType::OWN_PROCESS,
+        start_type: ServiceStartType::AutoStart,
+        error_control: ServiceErrorControl::Normal,
+        executable_path: std::env::current_exe()?,
+        launch_arguments: service_arguments(name, cmdline_params)?,
+        dependencies: Vec::new(),
+        account_name: None,
+        account_password: None,
+    };
+
+    let service = manager
+        .create_service(&info, ServiceAccess::CHANGE_CONFIG)
+        .with_context(|| format!("Unable to install the service {name}"))?;
+    service.set_description(DESCRIPTION)?;
+
+    println!("{}", tr!("cli-service-installed", name = name));
+
+    Ok(())
+}
+
+/// Stop the service if it is running and remove it.
+pub fn uninstall(name: &str) -> anyhow::Result<()> {
+    let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)?;
+    let service = manager
+        .open_service(
+            name,
+            ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE,
+        )
+        .with_context(|| format!("Unable to open the service {name}"))?;
+
+    if service.query_status()?.current_state != ServiceState::Stopped {
+        service.stop()?;
+
+        let deadline = Instant::now() + UNINSTALL_TIMEOUT;
+        while service.query_status()?.current_state != ServiceState::Stopped {
+            if Instant::now() >= deadline {
+                anyhow::bail!("The service {name} did not stop in {}s", UNINSTALL_TIMEOUT.as_secs());
+            }
+            thread::sleep(PROGRESS_INTERVAL);
+        }
+    }
+
+    service.delete()?;
+
+    println!("{}", tr!("cli-service-uninstalled", name = name));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[test]
+    fn test_service_arguments() {
+        let cmdline = CmdlineParams::parse_from(["snx-rs", "-c", "work.toml", "-P", "lab", "install-service"]);
+        let arguments = service_arguments("snx-rs-lab", &cmdline).unwrap();
+
+        assert_eq!(arguments[0], "--config-file");
+        assert!(Path::new(&arguments[1]).is_absolute());
+        assert_eq!(
+            arguments[2..],
+            ["--profile", "lab", "run-service", "--service-name", "snx-rs-lab"].map(OsString::from)
+        );
+    }
+}
@@ -17,13 +17,11 @@ use crate::{
         params::{CertType, TunnelParams},
         proto::*,
     },
+    proxy,
     sexpr::SExpression,
 };
 
 static REQUEST_ID: AtomicU32 = AtomicU32::new(2);
-const REQUEST_TIMEOUT: Duration = Duration::from_secs(600);
-const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
-const INFO_TIMEOUT: Duration = Duration::from_secs(10);
 
 fn new_request_id() -> u32 {
     REQUEST_ID.fetch_add(1, Ordering::SeqCst)
@@ -138,7 +136,13 @@ impl CccHttpClient {
         let with_cert = matches!(request.data, RequestData::Auth(_));
         let expr = SExpression::from(CccClientRequest { data: request });
 
-        let mut builder = reqwest::Client::builder().connect_timeout(CONNECT_TIMEOUT);
+        let mut builder = reqwest::Client::builder().connect_timeout(self.params.timeouts.connect);
+
+        // the proxy is selected here, so that no_proxy and `proxy = none` apply as for the tunnel
+        builder = match proxy::select(&self.params) {
+            Some(server) => builder.proxy(reqwest::Proxy::all(server.to_string())?),
+            None => builder.no_proxy(),
+        };
 
         for ca_cert in &self.params.ca_cert {
             let data = tokio::fs::read(ca_cert).await?;
@@ -190,7 +194,7 @@ impl CccHttpClient {
     }
 
     async fn send_ccc_request(&self, req: CccClientRequestData) -> anyhow::Result<ResponseData> {
-        self.send_request(req, REQUEST_TIMEOUT)
+        self.send_request(req, self.params.timeouts.request)
             .await?
             .try_into::<CccServerResponse>()?
             .data
@@ -225,7 +229,8 @@ impl CccHttpClient {
     }
 
     pub async fn get_server_info(&self) -> anyhow::Result<SExpression> {
-        self.send_request(self.new_client_hello_request(), INFO_TIMEOUT).await
+        self.send_request(self.new_client_hello_request(), self.params.timeouts.info)
+            .await
     }
 
     pub async fn signout(&self) -> anyhow::Result<()> {
@@ -0,0 +1,713 @@
+//! Control socket of a running standalone tunnel: newline-delimited JSON commands for the status bars and scripts,
+//! answered with one JSON line each.
+
+#[cfg(unix)]
+use std::{
+    env,
+    fs::{self, Permissions},
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+#[cfg(unix)]
+use futures::{SinkExt, StreamExt};
+use i18n::tr;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{RwLock, broadcast, mpsc, watch};
+#[cfg(unix)]
+use tokio::{
+    net::{UnixListener, UnixStream},
+    task::JoinHandle,
+};
+#[cfg(unix)]
+use tokio_util::codec::{Framed, LinesCodec, LinesCodecError};
+use tracing::debug;
+#[cfg(unix)]
+use tracing::warn;
+use tracing_subscriber::filter::Targets;
+
+#[cfg(unix)]
+use crate::{model::params::TunnelParams, sd_listen, tunnel::device::resolve_group};
+use crate::{
+    model::{
+        ConnectionStatus, StatusReport, TunnelStats,
+        params::{LogFormat, NotifyEvent},
+    },
+    platform::{self, NetworkInterface},
+    util,
+};
+
+#[cfg(all(target_os = "linux", feature = "dbus-service"))]
+pub mod dbus;
+pub mod health;
+#[cfg(all(target_os = "linux", feature = "desktop-notifications"))]
+pub mod notify;
+#[cfg(unix)]
+pub mod state_file;
+
+pub const PROTOCOL_VERSION: u32 = 1;
+
+#[cfg(unix)]
+const SOCKET_NAME: &str = "snx-rs-control.sock";
+// FileDescriptorName= of the socket unit, needed only when the unit passes several sockets
+#[cfg(unix)]
+const SOCKET_FD_NAME: &str = "control";
+#[cfg(unix)]
+const MAX_LINE_LENGTH: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+pub enum ControlRequest {
+    Status,
+    Connect {
+        #[serde(default)]
+        profile: Option<String>,
+    },
+    Disconnect,
+    Reconnect,
+    Reauth,
+    Reload,
+    SetLogLevel {
+        level: String,
+    },
+    /// Disconnect and exit, the profile must be empty or the one the tunnel was started with.
+    Shutdown {
+        #[serde(default)]
+        profile: Option<String>,
+    },
+}
+
+/// Request to the session loop of the tunnel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControlAction {
+    /// Connect again after a disconnect request, ignored while connected.
+    Connect,
+    Disconnect,
+    /// Tear down the tunnel and connect again, the IKE session is reused with `ike-persist`.
+    Reconnect,
+    /// Sign out and authenticate again.
+    Reauth,
+    /// Reload the configuration, sent on SIGHUP as well. The options which need a new connection
+    /// are kept for the next one.
+    Reload,
+    /// Disconnect and exit the process, sent to the running instance by `--replace`.
+    Shutdown,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ControlResponse {
+    pub version: u32,
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<StatusReport>,
+}
+
+impl ControlResponse {
+    fn ok() -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            ok: true,
+            error: None,
+            status: None,
+        }
+    }
+
+    fn error(message: String) -> Self {
+        Self {
+            ok: false,
+            error: Some(message),
+            ..Self::ok()
+        }
+    }
+
+    fn with_status(status: StatusReport) -> Self {
+        Self {
+            status: Some(status),
+            ..Self::ok()
+        }
+    }
+}
+
+/// Transition of the tunnel lifecycle, emitted by the session loop once per occurrence.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LifecycleEvent {
+    Connected {
+        address: String,
+    },
+    /// The tunnel is torn down and set up again, the attempts are counted from the last stable session.
+    Reconnecting {
+        attempt: u32,
+    },
+    Disconnected {
+        reason: Option<String>,
+    },
+    ReauthFailed {
+        error: String,
+    },
+}
+
+impl LifecycleEvent {
+    pub fn kind(&self) -> NotifyEvent {
+        match self {
+            Self::Connected { .. } => NotifyEvent::Connected,
+            Self::Reconnecting { .. } => NotifyEvent::Reconnecting,
+            Self::Disconnected { .. } => NotifyEvent::Disconnected,
+            Self::ReauthFailed { .. } => NotifyEvent::ReauthFailed,
+        }
+    }
+}
+
+/// Connection state of the tunnel, updated by the session loop.
+pub struct ControlState {
+    pub status: RwLock<ConnectionStatus>,
+    pub stats: RwLock<TunnelStats>,
+    state_name: watch::Sender<&'static str>,
+    lifecycle: broadcast::Sender<LifecycleEvent>,
+    clients: watch::Sender<usize>,
+    keepalive_ok: AtomicBool,
+}
+
+impl Default for ControlState {
+    fn default() -> Self {
+        Self {
+            status: RwLock::default(),
+            stats: RwLock::new(TunnelStats::new()),
+            state_name: watch::Sender::new(ConnectionStatus::default().state_name()),
+            lifecycle: broadcast::channel(16).0,
+            clients: watch::Sender::new(0),
+            keepalive_ok: AtomicBool::new(true),
+        }
+    }
+}
+
+impl ControlState {
+    /// Replace the connection status, the subscribers are notified when the state name changes.
+    pub async fn set_status(&self, status: ConnectionStatus) {
+        let name = status.state_name();
+        if !matches!(status, ConnectionStatus::Connected(_)) {
+            // the next tunnel starts without a failed keepalive
+            self.keepalive_ok.store(true, Ordering::SeqCst);
+        }
+        *self.status.write().await = status;
+        self.state_name.send_if_modified(|current| {
+            let changed = *current != name;
+            *current = name;
+            changed
+        });
+    }
+
+    /// Record the outcome of the last keepalive round trip of the tunnel.
+    pub fn set_keepalive(&self, ok: bool) {
+        self.keepalive_ok.store(ok, Ordering::SeqCst);
+    }
+
+    pub fn is_keepalive_ok(&self) -> bool {
+        self.keepalive_ok.load(Ordering::SeqCst)
+    }
+
+    /// Whether the tunnel is established and the last keepalive round trip succeeded.
+    pub async fn is_ready(&self) -> bool {
+        matches!(*self.status.read().await, ConnectionStatus::Connected(_)) && self.is_keepalive_ok()
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<&'static str> {
+        self.state_name.subscribe()
+    }
+
+    /// Publish a lifecycle event, it is dropped when nobody listens.
+    pub fn emit(&self, event: LifecycleEvent) {
+        debug!("Lifecycle event: {:?}", event);
+        let _ = self.lifecycle.send(event);
+    }
+
+    pub fn lifecycle(&self) -> broadcast::Receiver<LifecycleEvent> {
+        self.lifecycle.subscribe()
+    }
+
+    fn client_connected(&self) {
+        self.clients.send_modify(|clients| *clients += 1);
+    }
+
+    fn client_disconnected(&self) {
+        self.clients.send_modify(|clients| *clients = clients.saturating_sub(1));
+    }
+
+    /// Completes once no control client was connected for the given period.
+    pub async fn wait_idle(&self, period: Duration) {
+        let mut clients = self.clients.subscribe();
+        loop {
+            let _ = clients.wait_for(|clients| *clients == 0).await;
+            let connected = tokio::time::timeout(period, clients.wait_for(|clients| *clients > 0))
+                .await
+                .is_ok();
+            if !connected {
+                return;
+            }
+        }
+    }
+
+    pub async fn report(&self) -> StatusReport {
+        let mut status = self.status.read().await.clone();
+        if let ConnectionStatus::Connected(ref mut info) = status {
+            info.stats = self.stats.read().await.clone();
+            info.traffic = platform::new_network_interface()
+                .get_traffic_counters(&info.interface_name)
+                .await;
+        }
+        StatusReport::new(status)
+    }
+}
+
+pub type LogLevelSetter = Box<dyn Fn(Targets) -> anyhow::Result<()> + Send + Sync>;
+
+pub type LogFormatSetter = Box<dyn Fn(LogFormat) -> anyhow::Result<()> + Send + Sync>;
+
+/// Everything the control socket acts on: the state for the queries, the action channel of the session loop
+/// and the reload hook of the log filter.
+pub struct ControlHandle {
+    /// Name of the profile the tunnel was started with, the only one it connects.
+    pub profile: String,
+    pub state: Arc<ControlState>,
+    pub actions: mpsc::Sender<ControlAction>,
+    pub set_log_level: LogLevelSetter,
+}
+
+impl ControlHandle {
+    pub async fn handle_request(&self, request: ControlRequest) -> ControlResponse {
+        debug!("Control request: {:?}", request);
+
+        let action = match request {
+            ControlRequest::Status => return ControlResponse::with_status(self.state.report().await),
+            ControlRequest::SetLogLevel { level } => return self.handle_log_level(&level),
+            ControlRequest::Connect { profile: Some(profile) }
+            | ControlRequest::Shutdown { profile: Some(profile) }
+                if !profile.is_empty() && profile != self.profile =>
+            {
+                return ControlResponse::error(tr!(
+                    "error-profile-not-served",
+                    profile = profile,
+                    current = self.profile.clone()
+                ));
+            }
+            ControlRequest::Connect { .. } => ControlAction::Connect,
+            ControlRequest::Shutdown { .. } => ControlAction::Shutdown,
+            ControlRequest::Disconnect => ControlAction::Disconnect,
+            ControlRequest::Reconnect => ControlAction::Reconnect,
+            ControlRequest::Reauth => ControlAction::Reauth,
+            ControlRequest::Reload => ControlAction::Reload,
+        };
+
+        match self.actions.send(action).await {
+            Ok(()) => ControlResponse::ok(),
+            Err(_) => ControlResponse::error(tr!("error-tunnel-not-running")),
+        }
+    }
+
+    // a single level or the directives of the log-filter option
+    fn handle_log_level(&self, level: &str) -> ControlResponse {
+        let targets = match util::parse_log_filter(level) {
+            Ok(targets) => targets,
+            Err(e) => return ControlResponse::error(e.to_string()),
+        };
+
+        match (self.set_log_level)(targets) {
+            Ok(()) => ControlResponse::ok(),
+            Err(e) => ControlResponse::error(e.to_string()),
+        }
+    }
+}
+
+// The runtime directory of the user is private to it, /run is writable only by root.
+#[cfg(unix)]
+pub(crate) fn runtime_path(name: &str) -> Option<PathBuf> {
+    if nix::unistd::geteuid().is_root() {
+        Some(PathBuf::from("/run").join(name))
+    } else {
+        env::var_os("XDG_RUNTIME_DIR").map(|dir| PathBuf::from(dir).join(name))
+    }
+}
+
+/// Location of the control socket selected by the parameters, `None` if it is disabled or there is no default.
+#[cfg(unix)]
+pub fn socket_path(params: &TunnelParams) -> Option<PathBuf> {
+    if params.no_control_socket {
+        None
+    } else {
+        params.control_socket.clone().or_else(|| runtime_path(SOCKET_NAME))
+    }
+}
+
+/// Location of the lock file which allows a single standalone tunnel per profile, `None` if there is no runtime directory.
+#[cfg(unix)]
+pub fn lock_path(params: &TunnelParams) -> Option<PathBuf> {
+    runtime_path(&lock_file_name(&params.profile_name()))
+}
+
+#[cfg(unix)]
+fn lock_file_name(profile: &str) -> String {
+    let profile = if profile.is_empty() { "default" } else { profile };
+    let profile = profile
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    format!("snx-rs-{profile}.lock")
+}
+
+/// Send a single request to the control socket of a running tunnel.
+#[cfg(unix)]
+pub async fn send_request(path: &Path, request: &ControlRequest) -> anyhow::Result<ControlResponse> {
+    let stream = UnixStream::connect(path).await?;
+    let mut framed = Framed::new(stream, LinesCodec::new_with_max_length(MAX_LINE_LENGTH));
+
+    framed.send(serde_json::to_string(request)?).await?;
+
+    match framed.next().await {
+        Some(line) => Ok(serde_json::from_str(&line?)?),
+        None => anyhow::bail!(tr!("error-tunnel-not-running")),
+    }
+}
+
+#[cfg(unix)]
+pub struct ControlSocket {
+    // the activated socket belongs to the service manager and stays in place
+    path: Option<PathBuf>,
+    listener: UnixListener,
+}
+
+#[cfg(unix)]
+impl ControlSocket {
+    /// Bind the socket selected by the parameters, `None` if it is disabled or there is no default location.
+    /// Only the owner, and the configured group if any, may connect. A socket passed by systemd socket activation
+    /// is used as it is, its unit sets the permissions.
+    pub fn bind(params: &TunnelParams) -> anyhow::Result<Option<Self>> {
+        let activated = if params.no_control_socket {
+            None
+        } else {
+            sd_listen::take_listener(SOCKET_FD_NAME)?
+        };
+
+        if let Some(listener) = activated {
+            listener.set_nonblocking(true)?;
+            return Ok(Some(Self {
+                path: None,
+                listener: UnixListener::from_std(listener)?,
+            }));
+        }
+
+        let Some(path) = socket_path(params) else {
+            debug!("No control socket");
+            return Ok(None);
+        };
+
+        let group = params.control_socket_group.as_deref().map(resolve_group).transpose()?;
+
+        // a socket which accepts connections belongs to another running tunnel
+        if std::os::unix::net::UnixStream::connect(&path).is_ok() {
+            anyhow::bail!(tr!("error-control-socket-in-use", path = path.display().to_string()));
+        }
+        let _ = fs::remove_file(&path);
+
+        let listener = UnixListener::bind(&path)?;
+
+        let mode = match group {
+            Some(gid) => {
+                nix::unistd::chown(&path, None, Some(nix::unistd::Gid::from_raw(gid)))?;
+                0o660
+            }
+            None => 0o600,
+        };
+        fs::set_permissions(&path, Permissions::from_mode(mode))?;
+
+        debug!("Listening on the control socket {}", path.display());
+
+        Ok(Some(Self {
+            path: Some(path),
+            listener,
+        }))
+    }
+
+    /// Serve the commands on a separate task until the returned guard is dropped.
+    pub fn spawn(self, handle: Arc<ControlHandle>) -> ControlTask {
+        let task = tokio::spawn(serve(self.listener, handle));
+        ControlTask { path: self.path, task }
+    }
+}
+
+/// Stops the control socket and removes it from the file system on drop.
+#[cfg(unix)]
+pub struct ControlTask {
+    path: Option<PathBuf>,
+    task: JoinHandle<()>,
+}
+
+#[cfg(unix)]
+impl Drop for ControlTask {
+    fn drop(&mut self) {
+        self.task.abort();
+        if let Some(ref path) = self.path {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn serve(listener: UnixListener, handle: Arc<ControlHandle>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let handle = handle.clone();
+                handle.state.client_connected();
+                tokio::spawn(async move {
+                    if let Err(e) = serve_client(stream, &handle).await {
+                        debug!("Control client error: {}", e);
+                    }
+                    handle.state.client_disconnected();
+                });
+            }
+            Err(e) => {
+                warn!("Control socket stopped: {}", e);
+                break;
+            }
+        }
+    }
+}
+
+// Malformed commands are answered with an error, the connection stays open.
+#[cfg(unix)]
+async fn serve_client(stream: UnixStream, handle: &ControlHandle) -> anyhow::Result<()> {
+    let mut framed = Framed::new(stream, LinesCodec::new_with_max_length(MAX_LINE_LENGTH));
+
+    while let Some(line) = framed.next().await {
+        let response = match line {
+            Ok(line) if line.trim().is_empty() => continue,
+            Ok(line) => match serde_json::from_str::<ControlRequest>(&line) {
+                Ok(request) => handle.handle_request(request).await,
+                Err(e) => ControlResponse::error(tr!("error-invalid-control-command", message = e.to_string())),
+            },
+            Err(LinesCodecError::MaxLineLengthExceeded) => ControlResponse::error(tr!(
+                "error-invalid-control-command",
+                message = LinesCodecError::MaxLineLengthExceeded.to_string()
+            )),
+            Err(e) => return Err(e.into()),
+        };
+
+        framed.send(serde_json::to_string(&response)?).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    #[cfg(unix)]
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    use tracing::{Level, level_filters::LevelFilter};
+
+    use super::*;
+
+    fn handle(levels: Arc<Mutex<Vec<Targets>>>) -> (ControlHandle, mpsc::Receiver<ControlAction>) {
+        let (sender, receiver) = mpsc::channel(4);
+        let handle = ControlHandle {
+            profile: "work".to_owned(),
+            state: Arc::new(ControlState::default()),
+            actions: sender,
+            set_log_level: Box::new(move |level| {
+                levels.lock().unwrap().push(level);
+                Ok(())
+            }),
+        };
+        (handle, receiver)
+    }
+
+    #[test]
+    fn test_parse_request() {
+        assert_eq!(
+            serde_json::from_str::<ControlRequest>(r#"{"command":"status"}"#).unwrap(),
+            ControlRequest::Status
+        );
+        assert_eq!(
+            serde_json::from_str::<ControlRequest>(r#"{"command":"set-log-level","level":"debug"}"#).unwrap(),
+            ControlRequest::SetLogLevel {
+                level: "debug".to_owned()
+            }
+        );
+        assert_eq!(
+            serde_json::from_str::<ControlRequest>(r#"{"command":"connect"}"#).unwrap(),
+            ControlRequest::Connect { profile: None }
+        );
+        assert_eq!(
+            serde_json::from_str::<ControlRequest>(r#"{"command":"shutdown"}"#).unwrap(),
+            ControlRequest::Shutdown { profile: None }
+        );
+        assert!(serde_json::from_str::<ControlRequest>(r#"{"command":"restart"}"#).is_err());
+        assert!(serde_json::from_str::<ControlRequest>("status").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_lock_file_name() {
+        assert_eq!(lock_file_name("work"), "snx-rs-work.lock");
+        assert_eq!(lock_file_name(""), "snx-rs-default.lock");
+        assert_eq!(lock_file_name("../etc/passwd"), "snx-rs-___etc_passwd.lock");
+    }
+
+    #[tokio::test]
+    async fn test_handle_request() {
+        let levels = Arc::new(Mutex::new(Vec::new()));
+        let (handle, mut receiver) = handle(levels.clone());
+
+        let response = handle.handle_request(ControlRequest::Status).await;
+        assert!(response.ok);
+        assert_eq!(response.version, PROTOCOL_VERSION);
+        assert_eq!(response.status.unwrap().state, "disconnected");
+
+        assert!(handle.handle_request(ControlRequest::Reauth).await.ok);
+        assert_eq!(receiver.recv().await, Some(ControlAction::Reauth));
+
+        assert!(handle.handle_request(ControlRequest::Reload).await.ok);
+        assert_eq!(receiver.recv().await, Some(ControlAction::Reload));
+
+        let request = ControlRequest::Connect {
+            profile: Some("work".to_owned()),
+        };
+        assert!(handle.handle_request(request).await.ok);
+        assert_eq!(receiver.recv().await, Some(ControlAction::Connect));
+
+        let request = ControlRequest::Connect {
+            profile: Some("home".to_owned()),
+        };
+        assert!(!handle.handle_request(request).await.ok);
+
+        let request = ControlRequest::Shutdown {
+            profile: Some("home".to_owned()),
+        };
+        assert!(!handle.handle_request(request).await.ok);
+
+        let request = ControlRequest::Shutdown {
+            profile: Some("work".to_owned()),
+        };
+        assert!(handle.handle_request(request).await.ok);
+        assert_eq!(receiver.recv().await, Some(ControlAction::Shutdown));
+
+        let request = ControlRequest::SetLogLevel {
+            level: "trace".to_owned(),
+        };
+        assert!(handle.handle_request(request).await.ok);
+        assert_eq!(levels.lock().unwrap()[0].default_level(), Some(LevelFilter::TRACE));
+
+        let request = ControlRequest::SetLogLevel {
+            level: "info,snxcore::tunnel=debug".to_owned(),
+        };
+        assert!(handle.handle_request(request).await.ok);
+        let targets = levels.lock().unwrap()[1].clone();
+        assert!(targets.would_enable("snxcore::tunnel::ssl", &Level::DEBUG));
+        assert!(!targets.would_enable("snxcore::ccc", &Level::DEBUG));
+
+        let request = ControlRequest::SetLogLevel {
+            level: "loud".to_owned(),
+        };
+        assert!(!handle.handle_request(request).await.ok);
+        assert_eq!(levels.lock().unwrap().len(), 2);
+
+        drop(receiver);
+        let response = handle.handle_request(ControlRequest::Disconnect).await;
+        assert_eq!(response.error, Some(tr!("error-tunnel-not-running")));
+    }
+
+    #[tokio::test]
+    async fn test_state_changes() {
+        let state = ControlState::default();
+        let mut changes = state.subscribe();
+
+        state.set_status(ConnectionStatus::Connecting).await;
+        assert!(changes.has_changed().unwrap());
+        assert_eq!(*changes.borrow_and_update(), "connecting");
+
+        // same state, no notification
+        state.set_status(ConnectionStatus::Connecting).await;
+        assert!(!changes.has_changed().unwrap());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_idle() {
+        let state = Arc::new(ControlState::default());
+        state.client_connected();
+
+        let idle = tokio::spawn({
+            let state = state.clone();
+            async move { state.wait_idle(Duration::from_secs(60)).await }
+        });
+
+        tokio::time::sleep(Duration::from_secs(120)).await;
+        assert!(!idle.is_finished());
+
+        state.client_disconnected();
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        assert!(!idle.is_finished());
+
+        tokio::time::sleep(Duration::from_secs(31)).await;
+        assert!(idle.await.is_ok());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let params = TunnelParams {
+            control_socket: Some(dir.path().join("control.sock")),
+            ..Default::default()
+        };
+
+        let socket = ControlSocket::bind(&params).unwrap().unwrap();
+        let mode = fs::metadata(socket.path.as_ref().unwrap())
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let (handle, mut receiver) = handle(Arc::default());
+        let task = socket.spawn(Arc::new(handle));
+
+        // the running socket is not taken over
+        assert!(ControlSocket::bind(&params).is_err());
+
+        let response = send_request(&dir.path().join("control.sock"), &ControlRequest::Status)
+            .await
+            .unwrap();
+        assert_eq!(response.status.unwrap().state, "disconnected");
+
+        let mut stream = UnixStream::connect(dir.path().join("control.sock")).await.unwrap();
+        stream
+            .write_all(b"garbage\n\n{\"command\":\"reconnect\"}\n")
+            .await
+            .unwrap();
+
+        let mut lines = BufReader::new(stream).lines();
+        let response: ControlResponse = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert!(!response.ok);
+        let response: ControlResponse = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert!(response.ok);
+        assert_eq!(receiver.recv().await, Some(ControlAction::Reconnect));
+
+        drop(task);
+        assert!(!dir.path().join("control.sock").exists());
+    }
+}
@@ -0,0 +1,146 @@
+//! D-Bus service interface of the standalone tunnel for the desktop applets, a thin adapter over the control handle.
+
+use std::{collections::HashMap, sync::Arc};
+
+use i18n::tr;
+use tokio::task::JoinHandle;
+use tracing::debug;
+use zbus::{Connection, fdo, message::Header, object_server::SignalEmitter, zvariant};
+
+pub use crate::platform::{POLKIT_ACTION_CONNECT, POLKIT_ACTION_DISCONNECT};
+use crate::{
+    control::{ControlHandle, ControlRequest},
+    model::params::DbusService,
+    platform::polkit::{POLKIT_ALLOW_USER_INTERACTION, PolkitAuthorityProxy},
+};
+
+pub const BUS_NAME: &str = "org.snx_rs.Tunnel1";
+pub const OBJECT_PATH: &str = "/org/snx_rs/Tunnel1";
+
+struct TunnelInterface {
+    handle: Arc<ControlHandle>,
+    // only the system bus is shared with the other users
+    use_polkit: bool,
+}
+
+impl TunnelInterface {
+    async fn authorize(&self, connection: &Connection, header: &Header<'_>, action_id: &str) -> fdo::Result<()> {
+        if !self.use_polkit {
+            return Ok(());
+        }
+
+        let denied = || fdo::Error::AccessDenied(tr!("error-dbus-not-authorized", action = action_id));
+
+        let sender = header.sender().ok_or_else(denied)?;
+        let subject = (
+            "system-bus-name",
+            HashMap::from([("name", zvariant::Value::from(sender.as_str()))]),
+        );
+
+        let authority = PolkitAuthorityProxy::new(connection).await?;
+        let (authorized, _, _) = authority
+            .check_authorization(&subject, action_id, &HashMap::new(), POLKIT_ALLOW_USER_INTERACTION, "")
+            .await?;
+
+        debug!("Polkit authorization of {} for {}: {}", action_id, sender, authorized);
+
+        if authorized { Ok(()) } else { Err(denied()) }
+    }
+
+    async fn request(&self, request: ControlRequest) -> fdo::Result<()> {
+        let response = self.handle.handle_request(request).await;
+        match response.error {
+            Some(error) if !response.ok => Err(fdo::Error::Failed(error)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[zbus::interface(name = "org.snx_rs.Tunnel1")]
+impl TunnelInterface {
+    /// Connect the tunnel, the profile must be empty or the one the tunnel was started with.
+    async fn connect(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+        profile: String,
+    ) -> fdo::Result<()> {
+        self.authorize(connection, &header, POLKIT_ACTION_CONNECT).await?;
+        let profile = (!profile.is_empty()).then_some(profile);
+        self.request(ControlRequest::Connect { profile }).await
+    }
+
+    async fn disconnect(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+    ) -> fdo::Result<()> {
+        self.authorize(connection, &header, POLKIT_ACTION_DISCONNECT).await?;
+        self.request(ControlRequest::Disconnect).await
+    }
+
+    /// Status report in the JSON format of the control socket.
+    #[zbus(property)]
+    async fn status(&self) -> String {
+        serde_json::to_string(&self.handle.state.report().await).unwrap_or_default()
+    }
+
+    #[zbus(signal)]
+    async fn state_changed(emitter: &SignalEmitter<'_>, state: &str) -> zbus::Result<()>;
+}
+
+/// Registered service, the name is released and the signals are stopped on drop.
+pub struct DbusTask {
+    _connection: Connection,
+    task: JoinHandle<()>,
+}
+
+impl Drop for DbusTask {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Register the service on the selected bus, `None` if it is disabled.
+pub async fn start(bus: DbusService, handle: Arc<ControlHandle>) -> anyhow::Result<Option<DbusTask>> {
+    let builder = match bus {
+        DbusService::None => return Ok(None),
+        DbusService::Session => zbus::connection::Builder::session()?,
+        DbusService::System => zbus::connection::Builder::system()?,
+    };
+
+    let mut changes = handle.state.subscribe();
+
+    let interface = TunnelInterface {
+        handle,
+        use_polkit: bus == DbusService::System,
+    };
+
+    let connection = builder
+        .name(BUS_NAME)?
+        .serve_at(OBJECT_PATH, interface)?
+        .build()
+        .await?;
+
+    let interface_ref = connection
+        .object_server()
+        .interface::<_, TunnelInterface>(OBJECT_PATH)
+        .await?;
+
+    // the state changes of the session loop are forwarded as signals
+    let task = tokio::spawn(async move {
+        while changes.changed().await.is_ok() {
+            let state = *changes.borrow_and_update();
+            let emitter = interface_ref.signal_emitter();
+            let _ = TunnelInterface::state_changed(emitter, state).await;
+            let _ = interface_ref.get().await.status_changed(emitter).await;
+        }
+    });
+
+    debug!("Registered D-Bus service {} on the {} bus", BUS_NAME, bus);
+
+    Ok(Some(DbusTask {
+        _connection: connection,
+        task,
+    }))
+}
@@ -0,0 +1,354 @@
+//! HTTP health endpoints for the container orchestrators: `/healthz` answers while the process is alive, `/readyz`
+//! only while the tunnel is established and its last keepalive round trip succeeded, and `/metrics` serves the
+//! tunnel statistics in the Prometheus text format when built with the `prometheus` feature. The handler is
+//! deliberately minimal, it runs on its own task away from the data path and never exposes the session or the
+//! credentials.
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+use tracing::debug;
+
+use crate::control::ControlState;
+
+const MAX_REQUEST_SIZE: usize = 8192;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq)]
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn text(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: format!("{body}\n"),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            503 => "Service Unavailable",
+            _ => "Unknown",
+        }
+    }
+
+    fn to_bytes(&self, with_body: bool) -> Vec<u8> {
+        let mut data = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+            self.status,
+            self.reason(),
+            self.content_type,
+            self.body.len()
+        )
+        .into_bytes();
+
+        if with_body {
+            data.extend_from_slice(self.body.as_bytes());
+        }
+        data
+    }
+}
+
+/// Stops the listener on drop.
+pub struct HealthTask {
+    local_addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl HealthTask {
+    /// Address the listener is bound to, it differs from the configured one for port 0.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for HealthTask {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Serve the health endpoints on the address.
+pub async fn start(address: SocketAddr, state: Arc<ControlState>) -> anyhow::Result<HealthTask> {
+    let listener = TcpListener::bind(address).await?;
+    let local_addr = listener.local_addr()?;
+
+    debug!("Serving the health endpoints on {}", local_addr);
+
+    let task = tokio::spawn(serve(listener, state));
+
+    Ok(HealthTask { local_addr, task })
+}
+
+async fn serve(listener: TcpListener, state: Arc<ControlState>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = serve_client(stream, &state).await {
+                        debug!("Health request from {} failed: {}", peer, e);
+                    }
+                });
+            }
+            Err(e) => debug!("Unable to accept the health connection: {}", e),
+        }
+    }
+}
+
+async fn serve_client(mut stream: TcpStream, state: &ControlState) -> anyhow::Result<()> {
+    let head = tokio::time::timeout(REQUEST_TIMEOUT, read_head(&mut stream)).await??;
+
+    let (response, with_body) = match parse_request_line(&head) {
+        Some((method, path)) => (respond(method, path, state).await, method != "HEAD"),
+        None => (Response::text(400, "bad request"), true),
+    };
+
+    stream.write_all(&response.to_bytes(with_body)).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+// Only the request head matters, a body is never expected.
+async fn read_head(stream: &mut TcpStream) -> anyhow::Result<String> {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 1024];
+
+    while !data.windows(4).any(|w| w == b"\r\n\r\n") {
+        if data.len() > MAX_REQUEST_SIZE {
+            anyhow::bail!("Request too large");
+        }
+        let size = stream.read(&mut buf).await?;
+        if size == 0 {
+            anyhow::bail!("Connection closed");
+        }
+        data.extend_from_slice(&buf[..size]);
+    }
+
+    Ok(String::from_utf8_lossy(&data).into_owned())
+}
+
+fn parse_request_line(head: &str) -> Option<(&str, &str)> {
+    let mut parts = head.lines().next()?.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    if !parts.next()?.starts_with("HTTP/") {
+        return None;
+    }
+
+    let path = target.split_once('?').map_or(target, |(path, _)| path);
+
+    Some((method, path))
+}
+
+async fn respond(method: &str, path: &str, state: &ControlState) -> Response {
+    if method != "GET" && method != "HEAD" {
+        return Response::text(405, "method not allowed");
+    }
+
+    match path {
+        "/healthz" => Response::text(200, "ok"),
+        "/readyz" => {
+            if state.is_ready().await {
+                Response::text(200, "ready")
+            } else {
+                Response::text(503, "not ready")
+            }
+        }
+        #[cfg(feature = "prometheus")]
+        "/metrics" => Response {
+            status: 200,
+            content_type: "text/plain; version=0.0.4; charset=utf-8",
+            body: metrics(state).await,
+        },
+        _ => Response::text(404, "not found"),
+    }
+}
+
+#[cfg(feature = "prometheus")]
+async fn metrics(state: &ControlState) -> String {
+    use std::fmt::Write;
+
+    let connected = matches!(*state.status.read().await, crate::model::ConnectionStatus::Connected(_));
+    let stats = state.stats.read().await.clone();
+
+    let metrics = [
+        (
+            "snx_rs_connected",
+            "gauge",
+            "Whether the tunnel is established.",
+            u64::from(connected),
+        ),
+        (
+            "snx_rs_keepalive_ok",
+            "gauge",
+            "Whether the last keepalive round trip of the tunnel succeeded.",
+            u64::from(connected && state.is_keepalive_ok()),
+        ),
+        (
+            "snx_rs_connections_total",
+            "counter",
+            "Number of established tunnels.",
+            u64::from(stats.connections),
+        ),
+        (
+            "snx_rs_reconnects_total",
+            "counter",
+            "Number of reconnects.",
+            u64::from(stats.reconnects),
+        ),
+        (
+            "snx_rs_rekeys_total",
+            "counter",
+            "Number of tunnel rekeys.",
+            u64::from(stats.rekeys),
+        ),
+        (
+            "snx_rs_connected_seconds_total",
+            "counter",
+            "Time the tunnel was established.",
+            stats.total_connected_time().as_secs(),
+        ),
+    ];
+
+    let mut body = String::new();
+    for (name, kind, help, value) in metrics {
+        let _ = write!(body, "# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {value}\n");
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{ConnectionInfo, ConnectionStatus};
+
+    async fn status_of(path: &str, state: &ControlState) -> u16 {
+        respond("GET", path, state).await.status
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle() {
+        let state = ControlState::default();
+
+        assert_eq!(status_of("/healthz", &state).await, 200);
+        assert_eq!(status_of("/readyz", &state).await, 503);
+
+        state.set_status(ConnectionStatus::Connecting).await;
+        assert_eq!(status_of("/readyz", &state).await, 503);
+
+        state
+            .set_status(ConnectionStatus::connected(ConnectionInfo::default()))
+            .await;
+        assert_eq!(status_of("/healthz", &state).await, 200);
+        assert_eq!(status_of("/readyz", &state).await, 200);
+
+        state.set_keepalive(false);
+        assert_eq!(status_of("/healthz", &state).await, 200);
+        assert_eq!(status_of("/readyz", &state).await, 503);
+
+        state.set_keepalive(true);
+        assert_eq!(status_of("/readyz", &state).await, 200);
+
+        // a lost keepalive does not carry over to the next tunnel
+        state.set_keepalive(false);
+        state.set_status(ConnectionStatus::TrustedNetwork).await;
+        assert_eq!(status_of("/readyz", &state).await, 503);
+        state
+            .set_status(ConnectionStatus::connected(ConnectionInfo::default()))
+            .await;
+        assert_eq!(status_of("/readyz", &state).await, 200);
+    }
+
+    #[tokio::test]
+    async fn test_respond() {
+        let state = ControlState::default();
+
+        assert_eq!(status_of("/", &state).await, 404);
+        assert_eq!(respond("POST", "/healthz", &state).await.status, 405);
+        assert_eq!(respond("HEAD", "/healthz", &state).await.status, 200);
+
+        #[cfg(not(feature = "prometheus"))]
+        assert_eq!(status_of("/metrics", &state).await, 404);
+    }
+
+    #[cfg(feature = "prometheus")]
+    #[tokio::test]
+    async fn test_metrics() {
+        let state = ControlState::default();
+        state.stats.write().await.on_connected();
+        state.stats.write().await.on_connected();
+        state
+            .set_status(ConnectionStatus::connected(ConnectionInfo::default()))
+            .await;
+
+        let response = respond("GET", "/metrics", &state).await;
+        assert_eq!(response.status, 200);
+        assert!(
+            response
+                .body
+                .contains("# TYPE snx_rs_connected gauge\nsnx_rs_connected 1\n")
+        );
+        assert!(response.body.contains("snx_rs_connections_total 2\n"));
+        assert!(response.body.contains("snx_rs_reconnects_total 1\n"));
+    }
+
+    #[test]
+    fn test_parse_request_line() {
+        assert_eq!(
+            parse_request_line("GET /readyz?verbose=1 HTTP/1.1\r\nHost: localhost\r\n\r\n"),
+            Some(("GET", "/readyz"))
+        );
+        assert_eq!(parse_request_line("GET /readyz\r\n\r\n"), None);
+        assert_eq!(parse_request_line("\r\n\r\n"), None);
+    }
+
+    async fn request(address: SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_server() {
+        let state = Arc::new(ControlState::default());
+        let task = start("127.0.0.1:0".parse().unwrap(), state.clone()).await.unwrap();
+        let address = task.local_addr();
+
+        let response = request(address, "GET /healthz HTTP/1.1\r\nHost: localhost\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\nok\n"));
+
+        let response = request(address, "GET /readyz HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+
+        state
+            .set_status(ConnectionStatus::connected(ConnectionInfo::default()))
+            .await;
+        let response = request(address, "HEAD /readyz HTTP/1.0\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Length: 6\r\n"));
+        assert!(response.ends_with("\r\n\r\n"));
+
+        let response = request(address, "garbage\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+
+        drop(task);
+    }
+}
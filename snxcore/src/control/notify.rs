@@ -0,0 +1,247 @@
+//! Desktop notifications of the tunnel lifecycle over the org.freedesktop.Notifications service of the session bus.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use i18n::tr;
+use tokio::{
+    sync::{
+        broadcast::{self, error::RecvError},
+        oneshot,
+    },
+    task::JoinHandle,
+};
+use tracing::{debug, warn};
+use zbus::{Connection, zvariant};
+
+use crate::{
+    control::{ControlState, LifecycleEvent},
+    model::params::NotifyEvent,
+};
+
+const APP_NAME: &str = "snx-rs";
+const APP_ICON: &str = "network-vpn";
+
+// the same kind of event is shown at most once in this interval, a flapping link would flood the desktop otherwise
+const MIN_INTERVAL: Duration = Duration::from_secs(30);
+
+// a notification service which does not answer must not hold up the exit
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+// the server decides how long to show the notification
+const DEFAULT_TIMEOUT: i32 = -1;
+
+#[zbus::proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications"
+)]
+trait Notifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: &HashMap<&str, zvariant::Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum Urgency {
+    Low = 0,
+    Normal = 1,
+    Critical = 2,
+}
+
+fn urgency(event: &LifecycleEvent) -> Urgency {
+    match event {
+        LifecycleEvent::Connected { .. } => Urgency::Low,
+        LifecycleEvent::Reconnecting { .. } => Urgency::Normal,
+        LifecycleEvent::Disconnected { reason: None } => Urgency::Normal,
+        LifecycleEvent::Disconnected { reason: Some(_) } | LifecycleEvent::ReauthFailed { .. } => Urgency::Critical,
+    }
+}
+
+fn summary(event: &LifecycleEvent) -> String {
+    match event {
+        LifecycleEvent::Connected { address } => tr!("notify-connected", address = address.as_str()),
+        LifecycleEvent::Reconnecting { attempt } => tr!("notify-reconnecting", attempt = *attempt),
+        LifecycleEvent::Disconnected { reason: None } => tr!("notify-disconnected"),
+        LifecycleEvent::Disconnected { reason: Some(reason) } => {
+            tr!("notify-disconnected-reason", reason = reason.as_str())
+        }
+        LifecycleEvent::ReauthFailed { error } => tr!("notify-reauth-failed", error = error.as_str()),
+    }
+}
+
+#[derive(Debug, Default)]
+struct RateLimiter {
+    last_shown: HashMap<NotifyEvent, Instant>,
+}
+
+impl RateLimiter {
+    fn allow(&mut self, kind: NotifyEvent, now: Instant) -> bool {
+        let limited = self
+            .last_shown
+            .get(&kind)
+            .is_some_and(|last| now.duration_since(*last) < MIN_INTERVAL);
+        if !limited {
+            self.last_shown.insert(kind, now);
+        }
+        !limited
+    }
+}
+
+struct Notifier {
+    proxy: NotificationsProxy<'static>,
+    events: Vec<NotifyEvent>,
+    limiter: RateLimiter,
+    // every notification replaces the previous one instead of stacking up
+    replaces_id: u32,
+}
+
+impl Notifier {
+    async fn show(&mut self, event: LifecycleEvent) {
+        if !self.events.contains(&event.kind()) || !self.limiter.allow(event.kind(), Instant::now()) {
+            return;
+        }
+
+        let hints = HashMap::from([("urgency", zvariant::Value::from(urgency(&event) as u8))]);
+        match self
+            .proxy
+            .notify(
+                APP_NAME,
+                self.replaces_id,
+                APP_ICON,
+                &summary(&event),
+                "",
+                &[],
+                &hints,
+                DEFAULT_TIMEOUT,
+            )
+            .await
+        {
+            Ok(id) => self.replaces_id = id,
+            Err(e) => warn!("Unable to show the desktop notification: {}", e),
+        }
+    }
+
+    async fn run(mut self, mut lifecycle: broadcast::Receiver<LifecycleEvent>, mut stop: oneshot::Receiver<()>) {
+        loop {
+            tokio::select! {
+                event = lifecycle.recv() => match event {
+                    Ok(event) => self.show(event).await,
+                    Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => break,
+                },
+                _ = &mut stop => {
+                    while let Ok(event) = lifecycle.try_recv() {
+                        self.show(event).await;
+                    }
+                    break;
+                }
+            }
+        }
+    }
+}
+
+pub struct NotifyTask {
+    stop: Option<oneshot::Sender<()>>,
+    task: JoinHandle<()>,
+}
+
+impl NotifyTask {
+    /// Show the pending events and stop.
+    pub async fn shutdown(mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+        let _ = tokio::time::timeout(SHUTDOWN_TIMEOUT, &mut self.task).await;
+    }
+}
+
+impl Drop for NotifyTask {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Show the selected lifecycle events as desktop notifications, `None` if none is selected
+/// or there is no session bus to show them on.
+pub async fn start(events: &[NotifyEvent], state: &ControlState) -> Option<NotifyTask> {
+    if events.is_empty() {
+        return None;
+    }
+
+    let connection = match Connection::session().await {
+        Ok(connection) => connection,
+        Err(e) => {
+            debug!("No session bus, desktop notifications are disabled: {}", e);
+            return None;
+        }
+    };
+
+    let proxy = match NotificationsProxy::new(&connection).await {
+        Ok(proxy) => proxy,
+        Err(e) => {
+            debug!("No notification service, desktop notifications are disabled: {}", e);
+            return None;
+        }
+    };
+
+    debug!("Desktop notifications enabled for: {:?}", events);
+
+    let notifier = Notifier {
+        proxy,
+        events: events.to_vec(),
+        limiter: RateLimiter::default(),
+        replaces_id: 0,
+    };
+    let (stop, stop_receiver) = oneshot::channel();
+    let task = tokio::spawn(notifier.run(state.lifecycle(), stop_receiver));
+
+    Some(NotifyTask { stop: Some(stop), task })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = RateLimiter::default();
+        let start = Instant::now();
+
+        assert!(limiter.allow(NotifyEvent::Reconnecting, start));
+        assert!(!limiter.allow(NotifyEvent::Reconnecting, start + Duration::from_secs(5)));
+        assert!(limiter.allow(NotifyEvent::Disconnected, start + Duration::from_secs(5)));
+        assert!(limiter.allow(NotifyEvent::Reconnecting, start + MIN_INTERVAL));
+        assert!(!limiter.allow(NotifyEvent::Reconnecting, start + MIN_INTERVAL + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_urgency() {
+        assert_eq!(
+            urgency(&LifecycleEvent::Connected {
+                address: "10.1.2.3".to_owned()
+            }),
+            Urgency::Low
+        );
+        assert_eq!(urgency(&LifecycleEvent::Reconnecting { attempt: 2 }), Urgency::Normal);
+        assert_eq!(urgency(&LifecycleEvent::Disconnected { reason: None }), Urgency::Normal);
+        assert_eq!(
+            urgency(&LifecycleEvent::Disconnected {
+                reason: Some("session expired".to_owned())
+            }),
+            Urgency::Critical
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! State file of the standalone tunnel for the status bars and shell prompts which must not block on a query.
+//! The file is replaced atomically on every state change and touched on a heartbeat, so that a file left behind
+//! by a killed process is recognized by its modification time.
+
+use std::{
+    fs,
+    io::Write,
+    os::unix::fs::OpenOptionsExt,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::{
+    control::{self, ControlHandle, ControlState},
+    model::{ConnectionStatus, params::TunnelParams},
+    tunnel::device::resolve_group,
+};
+
+const FILE_NAME: &str = "snx-rs-state.json";
+
+/// Interval of the modification time updates, a file older than three intervals is stale.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Contents of the state file. Fields are only ever added, the existing ones keep their name and meaning.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TunnelStateFile {
+    pub version: u32,
+    /// Incremented on every rewrite, a reader sees whether anything changed since its last read.
+    pub sequence: u64,
+    pub pid: u32,
+    /// One of `disconnected`, `connecting`, `connected`, `mfa-pending` or `trusted-network`.
+    pub state: String,
+    pub profile: String,
+    pub ip_address: Option<String>,
+    /// Gateway of the tunnel, one of the `server-name` list.
+    pub gateway: Option<String>,
+    pub connected_since: Option<DateTime<Local>>,
+    pub last_error: Option<String>,
+    /// Resolvers of the tunnel, for the entrypoint scripts which apply them with `dns-backend=print`.
+    #[serde(default)]
+    pub dns_servers: Vec<String>,
+    #[serde(default)]
+    pub search_domains: Vec<String>,
+}
+
+impl TunnelStateFile {
+    pub const VERSION: u32 = 1;
+
+    async fn snapshot(state: &ControlState, profile: &str, sequence: u64) -> Self {
+        let status = state.status.read().await;
+        let (ip_address, gateway, connected_since, dns_servers, search_domains) = match *status {
+            ConnectionStatus::Connected(ref info) => (
+                Some(info.ip_address.addr().to_string()),
+                Some(info.server_name.clone()),
+                info.since,
+                info.dns_servers.iter().map(ToString::to_string).collect(),
+                info.search_domains.clone(),
+            ),
+            _ => (None, None, None, Vec::new(), Vec::new()),
+        };
+
+        Self {
+            version: Self::VERSION,
+            sequence,
+            pid: std::process::id(),
+            state: status.state_name().to_owned(),
+            profile: profile.to_owned(),
+            ip_address,
+            gateway,
+            connected_since,
+            last_error: state.stats.read().await.last_error.clone(),
+            dns_servers,
+            search_domains,
+        }
+    }
+}
+
+/// Location of the state file selected by the parameters, `None` if it is disabled or there is no default.
+pub fn state_file_path(params: &TunnelParams) -> Option<PathBuf> {
+    if params.no_state_file {
+        None
+    } else {
+        params.state_file.clone().or_else(|| control::runtime_path(FILE_NAME))
+    }
+}
+
+// The readers never see a partially written file. Like the control socket, the file is private to the owner
+// and the configured group, the permissions are set on creation.
+fn write_atomic(path: &Path, contents: &TunnelStateFile, group: Option<u32>) -> anyhow::Result<()> {
+    let mut temp_path = path.to_owned().into_os_string();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    let _ = fs::remove_file(&temp_path);
+    let mut file = fs::File::options()
+        .write(true)
+        .create_new(true)
+        .mode(if group.is_some() { 0o640 } else { 0o600 })
+        .open(&temp_path)?;
+    if let Some(gid) = group {
+        std::os::unix::fs::fchown(&file, None, Some(gid))?;
+    }
+    file.write_all(serde_json::to_string(contents)?.as_bytes())?;
+    file.write_all(b"\n")?;
+    file.sync_all()?;
+
+    fs::rename(&temp_path, path)?;
+
+    Ok(())
+}
+
+fn touch(path: &Path) -> anyhow::Result<()> {
+    fs::File::options()
+        .write(true)
+        .open(path)?
+        .set_modified(SystemTime::now())?;
+    Ok(())
+}
+
+/// Stops the updates and removes the state file on drop.
+pub struct StateFileTask {
+    path: PathBuf,
+    task: JoinHandle<()>,
+}
+
+impl Drop for StateFileTask {
+    fn drop(&mut self) {
+        self.task.abort();
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Keep the state file selected by the parameters up to date, `None` if it is disabled.
+pub fn start(params: &TunnelParams, handle: Arc<ControlHandle>) -> Option<StateFileTask> {
+    let Some(path) = state_file_path(params) else {
+        debug!("No state file");
+        return None;
+    };
+
+    let group = match params.control_socket_group.as_deref().map(resolve_group).transpose() {
+        Ok(group) => group,
+        Err(e) => {
+            warn!("Unable to resolve the group of the state file: {}", e);
+            None
+        }
+    };
+
+    debug!("Writing the tunnel state to {}", path.display());
+
+    let task = tokio::spawn(update(path.clone(), group, handle));
+
+    Some(StateFileTask { path, task })
+}
+
+async fn update(path: PathBuf, group: Option<u32>, handle: Arc<ControlHandle>) {
+    let mut changes = handle.state.subscribe();
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+    let mut sequence = 0;
+
+    loop {
+        sequence += 1;
+        let contents = TunnelStateFile::snapshot(&handle.state, &handle.profile, sequence).await;
+        if let Err(e) = write_atomic(&path, &contents, group) {
+            warn!("Unable to write the state file {}: {}", path.display(), e);
+        }
+
+        // the first tick completes immediately
+        heartbeat.reset();
+
+        loop {
+            tokio::select! {
+                changed = changes.changed() => {
+                    if changed.is_err() {
+                        return;
+                    }
+                    changes.borrow_and_update();
+                    break;
+                }
+                _ = heartbeat.tick() => {
+                    if let Err(e) = touch(&path) {
+                        debug!("Unable to touch the state file {}: {}", path.display(), e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+    use crate::model::ConnectionInfo;
+
+    #[tokio::test]
+    async fn test_snapshot() {
+        let state = ControlState::default();
+        state.stats.write().await.on_error("Session expired");
+
+        let contents = TunnelStateFile::snapshot(&state, "work", 1).await;
+        assert_eq!(contents.state, "disconnected");
+        assert_eq!(contents.ip_address, None);
+        assert_eq!(contents.last_error.as_deref(), Some("Session expired"));
+
+        let info = ConnectionInfo {
+            since: Some(Local::now()),
+            server_name: "dr.example.com".to_owned(),
+            ip_address: "10.1.2.3/24".parse().unwrap(),
+            dns_servers: vec!["10.1.0.53".parse().unwrap()],
+            search_domains: vec!["corp.example.com".to_owned()],
+            ..Default::default()
+        };
+        state.set_status(ConnectionStatus::connected(info.clone())).await;
+
+        let contents = TunnelStateFile::snapshot(&state, "work", 2).await;
+        assert_eq!(contents.sequence, 2);
+        assert_eq!(contents.state, "connected");
+        assert_eq!(contents.profile, "work");
+        assert_eq!(contents.ip_address.as_deref(), Some("10.1.2.3"));
+        assert_eq!(contents.gateway.as_deref(), Some("dr.example.com"));
+        assert_eq!(contents.connected_since, info.since);
+        assert_eq!(contents.dns_servers, vec!["10.1.0.53"]);
+        assert_eq!(contents.search_domains, vec!["corp.example.com"]);
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(FILE_NAME);
+        let contents = TunnelStateFile {
+            version: TunnelStateFile::VERSION,
+            sequence: 7,
+            pid: 1,
+            state: "connecting".to_owned(),
+            profile: "default".to_owned(),
+            ip_address: None,
+            gateway: None,
+            connected_since: None,
+            last_error: None,
+            dns_servers: Vec::new(),
+            search_domains: Vec::new(),
+        };
+
+        write_atomic(&path, &contents, None).unwrap();
+        touch(&path).unwrap();
+
+        let read: TunnelStateFile = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(read, contents);
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert!(!dir.path().join(format!("{FILE_NAME}.tmp")).exists());
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::VecDeque, str::FromStr, sync::Arc, time::Duration};
+use std::{collections::VecDeque, fmt, str::FromStr, sync::Arc, time::Duration};
 
 use anyhow::anyhow;
 use futures::{SinkExt, StreamExt};
@@ -24,6 +24,18 @@ const SEND_TIMEOUT: Duration = Duration::from_secs(2);
 const CONNECT_TIMEOUT: Duration = Duration::from_secs(120);
 const SERVICE_CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
 
+/// The command mode service does not accept connections, the caller tells it apart from a failed request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ServiceNotRunning;
+
+impl fmt::Display for ServiceNotRunning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", tr!("error-no-service-connection"))
+    }
+}
+
+impl std::error::Error for ServiceNotRunning {}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ServiceCommand {
     Status,
@@ -31,6 +43,7 @@ pub enum ServiceCommand {
     Disconnect,
     Reconnect,
     Info,
+    ResetStats,
 }
 
 impl FromStr for ServiceCommand {
@@ -43,6 +56,7 @@ impl FromStr for ServiceCommand {
             "disconnect" => Ok(Self::Disconnect),
             "reconnect" => Ok(Self::Reconnect),
             "info" => Ok(Self::Info),
+            "reset-stats" => Ok(Self::ResetStats),
             other => Err(anyhow!(tr!("error-invalid-command", command = other))),
         }
     }
@@ -100,6 +114,7 @@ where
                 self.do_connect(params).await
             }
             ServiceCommand::Info => self.do_info(params).await,
+            ServiceCommand::ResetStats => self.do_reset_stats(params).await,
         }
     }
 
@@ -204,13 +219,7 @@ where
     }
 
     async fn do_connect(&mut self, params: Arc<TunnelParams>) -> anyhow::Result<ConnectionStatus> {
-        if params.server_name.is_empty() {
-            anyhow::bail!(tr!("error-no-server-name"));
-        }
-
-        if params.login_type.is_empty() {
-            anyhow::bail!(tr!("error-no-login-type"));
-        }
+        params.validate()?;
 
         if !params.user_name.is_empty() && !params.no_keychain && params.password.is_empty() {
             if let Ok(password) = platform::new_keychain().acquire_password(&params.user_name).await {
@@ -275,6 +284,17 @@ where
         self.do_status(params, false).await
     }
 
+    async fn do_reset_stats(&mut self, params: Arc<TunnelParams>) -> anyhow::Result<ConnectionStatus> {
+        match self
+            .send_receive(TunnelServiceRequest::ResetStats, RECV_TIMEOUT)
+            .await?
+        {
+            TunnelServiceResponse::Ok => self.do_status(params, false).await,
+            TunnelServiceResponse::Error(e) => Err(anyhow!(e)),
+            _ => anyhow::bail!(tr!("error-invalid-response")),
+        }
+    }
+
     async fn send_receive(
         &mut self,
         request: TunnelServiceRequest,
@@ -283,7 +303,7 @@ where
         let mut stream = self
             .get_stream()
             .await
-            .map_err(|_| anyhow!(tr!("error-no-service-connection")))?;
+            .map_err(|_| anyhow::Error::new(ServiceNotRunning))?;
 
         let mut codec = LengthDelimitedCodec::new().framed(&mut stream);
 
@@ -0,0 +1,149 @@
+//! Typed failures which the callers tell apart, for example to decide whether retrying the connection makes sense.
+
+use std::{fmt, io};
+
+use i18n::tr;
+
+use crate::model::params::ConfigError;
+
+/// The gateway rejected the credentials, retrying with the same ones will not help.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthRejected(pub String);
+
+impl fmt::Display for AuthRejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for AuthRejected {}
+
+/// A challenge must be answered, but there is no terminal to ask on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoTty;
+
+impl fmt::Display for NoTty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", tr!("error-no-tty"))
+    }
+}
+
+impl std::error::Error for NoTty {}
+
+/// The process lacks the privileges or capabilities needed to configure the network.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PermissionDenied(pub String);
+
+impl fmt::Display for PermissionDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for PermissionDenied {}
+
+/// Broad class of a failure, found by walking the chain of causes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    Config,
+    AuthRejected,
+    MfaRequired,
+    GatewayUnreachable,
+    Certificate,
+    Permission,
+    /// Everything else, including the failures of the established tunnel.
+    Tunnel,
+}
+
+fn is_tls_error(cause: &(dyn std::error::Error + 'static)) -> bool {
+    cause.is::<tokio_native_tls::native_tls::Error>()
+        || cause.is::<openssl::ssl::Error>()
+        || cause.is::<openssl::error::ErrorStack>()
+}
+
+fn is_unreachable(cause: &(dyn std::error::Error + 'static)) -> bool {
+    if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+        return e.is_connect() || e.is_timeout();
+    }
+    if let Some(e) = cause.downcast_ref::<io::Error>() {
+        return matches!(
+            e.kind(),
+            io::ErrorKind::ConnectionRefused
+                | io::ErrorKind::ConnectionReset
+                | io::ErrorKind::TimedOut
+                | io::ErrorKind::HostUnreachable
+                | io::ErrorKind::NetworkUnreachable
+                | io::ErrorKind::AddrNotAvailable
+        );
+    }
+    false
+}
+
+impl FailureKind {
+    pub fn of(error: &anyhow::Error) -> Self {
+        // the typed errors are the most specific ones, then the TLS errors which the HTTP client
+        // wraps into its connection errors
+        for cause in error.chain() {
+            if cause.is::<ConfigError>() {
+                return Self::Config;
+            }
+            if cause.is::<AuthRejected>() {
+                return Self::AuthRejected;
+            }
+            if cause.is::<NoTty>() {
+                return Self::MfaRequired;
+            }
+            if cause.is::<PermissionDenied>() {
+                return Self::Permission;
+            }
+            if cause
+                .downcast_ref::<io::Error>()
+                .is_some_and(|e| e.kind() == io::ErrorKind::PermissionDenied)
+            {
+                return Self::Permission;
+            }
+        }
+
+        if error.chain().any(is_tls_error) {
+            Self::Certificate
+        } else if error.chain().any(is_unreachable) {
+            Self::GatewayUnreachable
+        } else {
+            Self::Tunnel
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{Context, anyhow};
+
+    use super::*;
+
+    #[test]
+    fn test_failure_kind() {
+        let config = ConfigError {
+            profile: None,
+            problems: vec!["server-name".to_owned()],
+        };
+        assert_eq!(FailureKind::of(&config.into()), FailureKind::Config);
+
+        let auth = anyhow::Error::from(AuthRejected("Access denied".to_owned())).context("Login failed");
+        assert_eq!(FailureKind::of(&auth), FailureKind::AuthRejected);
+
+        assert_eq!(FailureKind::of(&NoTty.into()), FailureKind::MfaRequired);
+
+        let refused = Err::<(), _>(io::Error::from(io::ErrorKind::ConnectionRefused))
+            .context("Cannot connect")
+            .unwrap_err();
+        assert_eq!(FailureKind::of(&refused), FailureKind::GatewayUnreachable);
+
+        let denied = anyhow::Error::from(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert_eq!(FailureKind::of(&denied), FailureKind::Permission);
+
+        let tls = anyhow::Error::from(openssl::error::ErrorStack::get());
+        assert_eq!(FailureKind::of(&tls), FailureKind::Certificate);
+
+        assert_eq!(FailureKind::of(&anyhow!("Tunnel closed")), FailureKind::Tunnel);
+    }
+}
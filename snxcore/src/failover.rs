@@ -0,0 +1,232 @@
+//! Failover between the gateways of the `server-name` list. The connection attempts go through the list in order,
+//! starting with the gateway which worked last, and while a later gateway is in use the preferred ones are probed,
+//! so that the tunnel can fail back to them with the next reconnect.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+use crate::{error::FailureKind, model::params::TunnelParams, server_info};
+
+const NOT_RECOVERED: usize = usize::MAX;
+
+/// Whether another gateway may succeed where this one failed. All gateways share the authentication backend,
+/// so the rejected credentials and the configuration errors fail on every one of them.
+pub fn is_failover_error(error: &anyhow::Error) -> bool {
+    matches!(
+        FailureKind::of(error),
+        FailureKind::GatewayUnreachable | FailureKind::Tunnel
+    )
+}
+
+/// Selects the gateway of the next connection attempt.
+pub struct GatewayFailover {
+    params: Arc<TunnelParams>,
+    current: usize,
+    // the failed attempts since the last connection
+    failures: usize,
+    recovered: Arc<AtomicUsize>,
+}
+
+impl GatewayFailover {
+    pub fn new(params: Arc<TunnelParams>) -> Self {
+        Self {
+            params,
+            current: 0,
+            failures: 0,
+            recovered: Arc::new(AtomicUsize::new(NOT_RECOVERED)),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.params.failover_servers.is_empty()
+    }
+
+    /// Gateway of the next connection attempt.
+    pub fn gateway(&self) -> &str {
+        self.params.gateways().get(self.current).copied().unwrap_or_default()
+    }
+
+    /// Parameters of the next connection attempt, they name a single gateway.
+    pub fn params(&self) -> Arc<TunnelParams> {
+        if self.is_enabled() {
+            Arc::new(self.params.with_gateway(self.gateway()))
+        } else {
+            self.params.clone()
+        }
+    }
+
+    /// Move on to the next gateway after a failed attempt. Returns false when every gateway failed since
+    /// the last connection, the next attempt starts with the same gateway again.
+    pub fn next_gateway(&mut self) -> bool {
+        let count = self.params.gateways().len();
+
+        self.failures += 1;
+        if self.failures >= count {
+            self.failures = 0;
+            return false;
+        }
+
+        self.current = (self.current + 1) % count;
+        true
+    }
+
+    /// The gateway of the connection is kept for the reconnects.
+    pub fn connected(&mut self) {
+        self.failures = 0;
+    }
+
+    /// Switch to a preferred gateway which the probe found reachable again, if `failback` is enabled.
+    /// Returns whether the gateway changed.
+    pub fn failback(&mut self) -> bool {
+        let recovered = self.recovered.swap(NOT_RECOVERED, Ordering::SeqCst);
+        if !self.params.failback || recovered >= self.current {
+            return false;
+        }
+
+        self.current = recovered;
+        self.failures = 0;
+        true
+    }
+
+    /// Probe the gateways preferred to the one in use every `failback-probe-interval`, until one of them
+    /// answers. `None` if the first gateway is in use or the probe is disabled.
+    pub fn start_probe(&self) -> Option<FailbackProbe> {
+        if self.current == 0 || self.params.failback_probe_interval.is_zero() {
+            return None;
+        }
+
+        let preferred = self.params.gateways()[..self.current]
+            .iter()
+            .map(|gateway| (*gateway).to_owned())
+            .collect();
+
+        Some(FailbackProbe(tokio::spawn(probe(
+            self.params.clone(),
+            preferred,
+            self.recovered.clone(),
+        ))))
+    }
+}
+
+/// Stops the probe on drop.
+pub struct FailbackProbe(JoinHandle<()>);
+
+impl Drop for FailbackProbe {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+// The server information query is the cheapest request which tells that the gateway serves clients,
+// it is limited by `timeouts.info` of every gateway.
+async fn probe(params: Arc<TunnelParams>, preferred: Vec<String>, recovered: Arc<AtomicUsize>) {
+    let mut interval = tokio::time::interval(params.failback_probe_interval);
+
+    // the first tick completes immediately
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        for (index, gateway) in preferred.iter().enumerate() {
+            match server_info::get_uncached(&params.with_gateway(gateway)).await {
+                Ok(_) => {
+                    if params.failback {
+                        info!(
+                            "Gateway {} is reachable again, failing back with the next reconnect",
+                            gateway
+                        );
+                    } else {
+                        info!("Gateway {} is reachable again", gateway);
+                    }
+                    recovered.store(index, Ordering::SeqCst);
+                    return;
+                }
+                Err(e) => debug!("Gateway {} is still unreachable: {}", gateway, e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use anyhow::Context;
+
+    use super::*;
+    use crate::error::AuthRejected;
+
+    fn new_failover(failback: bool) -> GatewayFailover {
+        let mut params = TunnelParams {
+            failback,
+            ..Default::default()
+        };
+        params.set_server_names("vpn.example.com,dr1.example.com,dr2.example.com:8443");
+        GatewayFailover::new(Arc::new(params))
+    }
+
+    #[test]
+    fn test_next_gateway() {
+        let mut failover = new_failover(false);
+        assert!(failover.is_enabled());
+        assert_eq!(failover.gateway(), "vpn.example.com");
+
+        assert!(failover.next_gateway());
+        assert_eq!(failover.params().server_name, "dr1.example.com");
+        assert!(failover.params().failover_servers.is_empty());
+
+        // the gateway which worked is tried first on the reconnect
+        failover.connected();
+        assert!(failover.next_gateway());
+        assert_eq!(failover.gateway(), "dr2.example.com:8443");
+        assert!(failover.next_gateway());
+        assert_eq!(failover.gateway(), "vpn.example.com");
+        assert!(!failover.next_gateway());
+        assert_eq!(failover.gateway(), "vpn.example.com");
+
+        let mut single = GatewayFailover::new(Arc::new(TunnelParams {
+            server_name: "vpn.example.com".to_owned(),
+            ..Default::default()
+        }));
+        assert!(!single.is_enabled());
+        assert!(!single.next_gateway());
+        assert!(single.start_probe().is_none());
+    }
+
+    #[test]
+    fn test_failback() {
+        let mut failover = new_failover(true);
+        failover.next_gateway();
+        failover.next_gateway();
+        failover.connected();
+
+        assert!(!failover.failback());
+
+        failover.recovered.store(1, Ordering::SeqCst);
+        assert!(failover.failback());
+        assert_eq!(failover.gateway(), "dr1.example.com");
+        assert!(!failover.failback());
+
+        let mut failover = new_failover(false);
+        failover.next_gateway();
+        failover.recovered.store(0, Ordering::SeqCst);
+        assert!(!failover.failback());
+        assert_eq!(failover.gateway(), "dr1.example.com");
+    }
+
+    #[test]
+    fn test_failover_error() {
+        let refused = Err::<(), _>(io::Error::from(io::ErrorKind::ConnectionRefused))
+            .context("Cannot connect")
+            .unwrap_err();
+        assert!(is_failover_error(&refused));
+        assert!(is_failover_error(&anyhow::anyhow!("Tunnel closed")));
+        assert!(!is_failover_error(&AuthRejected("Access denied".to_owned()).into()));
+    }
+}
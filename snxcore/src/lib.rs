@@ -2,12 +2,25 @@
 
 pub mod browser;
 pub mod ccc;
+pub mod control;
+#[cfg(unix)]
 pub mod controller;
+pub mod error;
+pub mod failover;
 pub mod model;
+pub mod network_gate;
+pub mod network_match;
 pub mod platform;
 pub mod prompt;
+pub mod proxy;
+#[cfg(unix)]
+pub mod sd_listen;
+pub mod sd_notify;
+#[cfg(unix)]
 pub mod server;
 pub mod server_info;
 pub mod sexpr;
+pub mod snxrc;
+pub mod trusted;
 pub mod tunnel;
 pub mod util;
@@ -1,11 +1,16 @@
-use std::{fmt, net::Ipv4Addr, sync::Arc, time::Duration};
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr},
+    sync::Arc,
+    time::Duration,
+};
 
 use chrono::{DateTime, Local};
-use ipnet::Ipv4Net;
+use ipnet::{IpNet, Ipv4Net, Ipv6Net};
 use isakmp::model::EspCryptMaterial;
 use serde::{Deserialize, Serialize};
 
-use crate::model::params::{TransportType, TunnelParams, TunnelType};
+use crate::model::params::{DnsMode, DnsOverride, TransportType, TunnelParams, TunnelType};
 
 pub mod params;
 pub mod proto;
@@ -26,6 +31,7 @@ pub struct IpsecSession {
     pub netmask: Ipv4Addr,
     pub dns: Vec<Ipv4Addr>,
     pub domains: Vec<String>,
+    pub wins: Vec<Ipv4Addr>,
     pub esp_in: Arc<EspCryptMaterial>,
     pub esp_out: Arc<EspCryptMaterial>,
     pub transport_type: TransportType,
@@ -40,6 +46,7 @@ impl Default for IpsecSession {
             netmask: Ipv4Addr::new(0, 0, 0, 0),
             dns: Vec::new(),
             domains: Vec::new(),
+            wins: Vec::new(),
             esp_in: Arc::default(),
             esp_out: Arc::default(),
             transport_type: TransportType::default(),
@@ -98,6 +105,68 @@ pub struct MfaChallenge {
     pub prompt: String,
 }
 
+/// Outcome of the post-connect DNS self-test for one resolver.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DnsCheckResult {
+    pub resolver: String,
+    pub passed: bool,
+}
+
+impl fmt::Display for DnsCheckResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome = if self.passed {
+            i18n::tr!("info-dns-check-passed")
+        } else {
+            i18n::tr!("info-dns-check-failed")
+        };
+        write!(f, "{}: {}", self.resolver, outcome)
+    }
+}
+
+/// Byte and packet counters of the tunnel device.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+pub struct TrafficCounters {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+impl TrafficCounters {
+    pub fn received(&self) -> String {
+        i18n::tr!(
+            "info-traffic-amount",
+            bytes = format_bytes(self.rx_bytes),
+            packets = self.rx_packets
+        )
+    }
+
+    pub fn sent(&self) -> String {
+        i18n::tr!(
+            "info-traffic-amount",
+            bytes = format_bytes(self.tx_bytes),
+            packets = self.tx_packets
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct ConnectionInfo {
     pub since: Option<DateTime<Local>>,
@@ -107,12 +176,35 @@ pub struct ConnectionInfo {
     pub tunnel_type: TunnelType,
     pub transport_type: TransportType,
     pub ip_address: Ipv4Net,
-    pub dns_servers: Vec<Ipv4Addr>,
+    pub ipv6_address: Option<Ipv6Net>,
+    pub dns_servers: Vec<IpAddr>,
     pub search_domains: Vec<String>,
+    pub wins_servers: Vec<Ipv4Addr>,
     pub interface_name: String,
     pub dns_configured: bool,
+    pub dns_mode: Option<DnsMode>,
+    #[serde(default)]
+    pub dns_override: Option<DnsOverride>,
     pub routing_configured: bool,
     pub default_route: bool,
+    #[serde(default)]
+    pub dns_check: Vec<DnsCheckResult>,
+    #[serde(default)]
+    pub forward_source: Option<Ipv4Net>,
+    #[serde(default)]
+    pub peer_address: Option<Ipv4Addr>,
+    pub stats: TunnelStats,
+    pub hello_reply: Option<serde_json::Value>,
+    pub protocol_version: Option<String>,
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub routes: Vec<IpNet>,
+    #[serde(default)]
+    pub traffic: Option<TrafficCounters>,
+    /// Effective session lifetime of the SSL tunnel, it re-authenticates when it expires.
+    #[serde(default)]
+    pub reauth_interval: Option<Duration>,
 }
 
 impl ConnectionInfo {
@@ -137,25 +229,103 @@ impl ConnectionInfo {
                     String::new()
                 },
             ),
+            (
+                "info-profile",
+                self.or_empty(|| self.profile.clone().unwrap_or_default()),
+            ),
             ("info-server-name", self.or_empty(|| self.server_name.clone())),
             ("info-user-name", self.or_empty(|| self.username.clone())),
             ("info-login-type", self.or_empty(|| self.login_type.clone())),
             ("info-tunnel-type", self.or_empty(|| self.tunnel_type.to_string())),
             ("info-transport-type", self.or_empty(|| self.transport_type.to_string())),
+            (
+                "info-protocol-version",
+                self.or_empty(|| self.protocol_version.clone().unwrap_or_default()),
+            ),
             ("info-ip-address", self.or_empty(|| self.ip_address.to_string())),
+            (
+                "info-ipv6-address",
+                self.or_empty(|| self.ipv6_address.map(|a| a.to_string()).unwrap_or_default()),
+            ),
             ("info-dns-servers", self.or_empty(|| format!("{:?}", self.dns_servers))),
             (
                 "info-search-domains",
                 self.or_empty(|| format!("[{}]", self.search_domains.join(", "))),
             ),
+            (
+                "info-wins-servers",
+                self.or_empty(|| format!("{:?}", self.wins_servers)),
+            ),
             ("info-interface", self.or_empty(|| self.interface_name.clone())),
+            (
+                "info-interface-mode",
+                self.or_empty(|| match self.peer_address {
+                    Some(peer) => i18n::tr!("info-interface-mode-p2p", peer = peer.to_string()),
+                    None => i18n::tr!("info-interface-mode-local"),
+                }),
+            ),
             ("info-dns-configured", self.or_empty(|| self.dns_configured.to_string())),
+            (
+                "info-dns-mode",
+                self.or_empty(|| self.dns_mode.map(|m| m.to_string()).unwrap_or_default()),
+            ),
+            (
+                "info-dns-override",
+                self.or_empty(|| self.dns_override.map(|o| o.to_string()).unwrap_or_default()),
+            ),
             (
                 "info-routing-configured",
                 self.or_empty(|| self.routing_configured.to_string()),
             ),
             ("info-default-route", self.or_empty(|| self.default_route.to_string())),
+            (
+                "info-reauth-interval",
+                self.or_empty(|| {
+                    self.reauth_interval
+                        .map(|interval| format_duration(interval.as_secs()))
+                        .unwrap_or_default()
+                }),
+            ),
+            (
+                "info-routes",
+                self.or_empty(|| {
+                    self.routes
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                }),
+            ),
+            (
+                "info-forwarding",
+                self.or_empty(|| {
+                    self.forward_source
+                        .map(|source| source.to_string())
+                        .unwrap_or_else(|| false.to_string())
+                }),
+            ),
+            (
+                "info-dns-check",
+                self.or_empty(|| {
+                    self.dns_check
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                }),
+            ),
+            (
+                "info-received",
+                self.or_empty(|| self.traffic.map(|t| t.received()).unwrap_or_default()),
+            ),
+            (
+                "info-sent",
+                self.or_empty(|| self.traffic.map(|t| t.sent()).unwrap_or_default()),
+            ),
         ]
+        .into_iter()
+        .chain(self.stats.to_values())
+        .collect()
     }
 
     pub fn print(&self) -> String {
@@ -176,6 +346,118 @@ impl ConnectionInfo {
     }
 }
 
+fn format_duration(secs: u64) -> String {
+    format!("{:02}:{:02}:{:02}", secs / 3600, (secs / 60) % 60, secs % 60)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct TunnelStats {
+    pub since: Option<DateTime<Local>>,
+    pub connected_at: Option<DateTime<Local>>,
+    pub connected_time: Duration,
+    pub connections: u32,
+    pub reconnects: u32,
+    #[serde(default)]
+    pub reauths: u32,
+    pub rekeys: u32,
+    pub last_error: Option<String>,
+    pub last_error_time: Option<DateTime<Local>>,
+}
+
+impl TunnelStats {
+    pub fn new() -> Self {
+        Self {
+            since: Some(Local::now()),
+            ..Default::default()
+        }
+    }
+
+    pub fn on_connected(&mut self) {
+        if self.connections > 0 {
+            self.reconnects += 1;
+        }
+        self.connections += 1;
+        self.connected_at = Some(Local::now());
+    }
+
+    pub fn on_disconnected(&mut self) {
+        if let Some(connected_at) = self.connected_at.take() {
+            self.connected_time += (Local::now() - connected_at).to_std().unwrap_or_default();
+        }
+    }
+
+    pub fn on_reauth(&mut self) {
+        self.reauths += 1;
+    }
+
+    pub fn on_rekey(&mut self) {
+        self.rekeys += 1;
+    }
+
+    pub fn on_error<S: AsRef<str>>(&mut self, error: S) {
+        self.last_error = Some(error.as_ref().to_owned());
+        self.last_error_time = Some(Local::now());
+    }
+
+    /// Reset all counters, keeping the current connection accounted for.
+    pub fn reset(&mut self) {
+        let connected = self.connected_at.is_some();
+        *self = Self::new();
+        if connected {
+            self.connections = 1;
+            self.connected_at = self.since;
+        }
+    }
+
+    pub fn total_connected_time(&self) -> Duration {
+        let current = self
+            .connected_at
+            .and_then(|at| (Local::now() - at).to_std().ok())
+            .unwrap_or_default();
+        self.connected_time + current
+    }
+
+    pub fn to_values(&self) -> Vec<(&'static str, String)> {
+        let Some(since) = self.since else {
+            return Vec::new();
+        };
+
+        vec![
+            ("info-stats-since", since.format("%Y-%m-%d %H:%M:%S").to_string()),
+            (
+                "info-connected-time",
+                format_duration(self.total_connected_time().as_secs()),
+            ),
+            ("info-reconnects", self.reconnects.to_string()),
+            ("info-reauths", self.reauths.to_string()),
+            ("info-rekeys", self.rekeys.to_string()),
+            (
+                "info-last-error",
+                match (&self.last_error, &self.last_error_time) {
+                    (Some(error), Some(time)) => format!("{}: {}", time.format("%Y-%m-%d %H:%M:%S"), error),
+                    _ => String::new(),
+                },
+            ),
+        ]
+    }
+}
+
+impl fmt::Display for TunnelStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.total_connected_time().as_secs();
+        write!(
+            f,
+            "connected time: {}s, connections: {}, reconnects: {}, reauths: {}, rekeys: {}, last error: {}",
+            secs,
+            self.connections,
+            self.reconnects,
+            self.reauths,
+            self.rekeys,
+            self.last_error.as_deref().unwrap_or("none")
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub enum ConnectionStatus {
     #[default]
@@ -183,6 +465,7 @@ pub enum ConnectionStatus {
     Connecting,
     Connected(ConnectionInfo),
     Mfa(MfaChallenge),
+    TrustedNetwork,
 }
 
 impl ConnectionStatus {
@@ -194,6 +477,17 @@ impl ConnectionStatus {
         Self::Mfa(challenge)
     }
 
+    /// Stable name of the state, not translated.
+    pub fn state_name(&self) -> &'static str {
+        match self {
+            Self::Disconnected => "disconnected",
+            Self::Connecting => "connecting",
+            Self::Connected(_) => "connected",
+            Self::Mfa(_) => "mfa-pending",
+            Self::TrustedNetwork => "trusted-network",
+        }
+    }
+
     pub fn print(&self) -> String {
         match self {
             Self::Connected(info) => info.print(),
@@ -225,6 +519,29 @@ impl fmt::Display for ConnectionStatus {
                     mfa_type = format!("{:?}", mfa.mfa_type)
                 )
             ),
+            ConnectionStatus::TrustedNetwork => {
+                write!(f, "{}", i18n::tr!("connection-status-trusted-network"))
+            }
+        }
+    }
+}
+
+/// Versioned status document for the scripts, the version is bumped on incompatible changes of the layout.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StatusReport {
+    pub version: u32,
+    pub state: String,
+    pub status: ConnectionStatus,
+}
+
+impl StatusReport {
+    pub const VERSION: u32 = 1;
+
+    pub fn new(status: ConnectionStatus) -> Self {
+        Self {
+            version: Self::VERSION,
+            state: status.state_name().to_owned(),
+            status,
         }
     }
 }
@@ -235,6 +552,7 @@ pub enum TunnelServiceRequest {
     ChallengeCode(String, TunnelParams),
     Disconnect,
     GetStatus,
+    ResetStats,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -264,3 +582,119 @@ impl PromptInfo {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::*;
+
+    #[test]
+    fn test_tunnel_stats_accumulation() {
+        let mut stats = TunnelStats::new();
+        stats.on_connected();
+        assert_eq!(stats.connections, 1);
+        assert_eq!(stats.reconnects, 0);
+
+        // pretend the tunnel has been up for ten seconds
+        stats.connected_at = Some(Local::now() - TimeDelta::seconds(10));
+        stats.on_disconnected();
+        assert!(stats.connected_at.is_none());
+        assert!(stats.connected_time >= Duration::from_secs(10));
+
+        // a repeated disconnect does not count the time again
+        let connected_time = stats.connected_time;
+        stats.on_disconnected();
+        assert_eq!(stats.connected_time, connected_time);
+
+        stats.on_connected();
+        stats.connected_at = Some(Local::now() - TimeDelta::seconds(5));
+        assert_eq!(stats.connections, 2);
+        assert_eq!(stats.reconnects, 1);
+        assert!(stats.total_connected_time() >= connected_time + Duration::from_secs(5));
+
+        stats.on_disconnected();
+        assert!(stats.connected_time >= connected_time + Duration::from_secs(5));
+
+        stats.on_rekey();
+        stats.on_error("Keepalive failed");
+        assert_eq!(stats.rekeys, 1);
+        assert_eq!(stats.reauths, 0);
+        assert_eq!(stats.last_error.as_deref(), Some("Keepalive failed"));
+        assert!(stats.last_error_time.is_some());
+    }
+
+    #[test]
+    fn test_tunnel_stats_reauth() {
+        let mut stats = TunnelStats::new();
+        stats.on_connected();
+        stats.on_reauth();
+        stats.on_connected();
+
+        // the session after a reauth is a reconnect as well
+        assert_eq!(stats.reauths, 1);
+        assert_eq!(stats.reconnects, 1);
+        assert_eq!(stats.rekeys, 0);
+        assert!(stats.to_string().contains("reconnects: 1, reauths: 1, rekeys: 0"));
+    }
+
+    #[test]
+    fn test_tunnel_stats_reset() {
+        let mut stats = TunnelStats::new();
+        stats.on_connected();
+        stats.on_connected();
+        stats.on_reauth();
+        stats.on_rekey();
+        stats.on_error("Keepalive failed");
+
+        // the current connection stays accounted for
+        stats.reset();
+        assert_eq!(stats.connections, 1);
+        assert_eq!(stats.reconnects, 0);
+        assert_eq!(stats.reauths, 0);
+        assert_eq!(stats.rekeys, 0);
+        assert!(stats.last_error.is_none());
+        assert!(stats.connected_at.is_some());
+        assert_eq!(stats.connected_at, stats.since);
+
+        stats.on_disconnected();
+        stats.reset();
+        assert_eq!(stats.connections, 0);
+        assert!(stats.connected_at.is_none());
+        assert_eq!(stats.connected_time, Duration::ZERO);
+        assert!(stats.since.is_some());
+    }
+
+    #[test]
+    fn test_tunnel_stats_values() {
+        assert!(TunnelStats::default().to_values().is_empty());
+
+        let mut stats = TunnelStats::new();
+        stats.on_connected();
+        stats.on_connected();
+        stats.on_reauth();
+        stats.on_reauth();
+        stats.on_rekey();
+        stats.on_error("Keepalive failed");
+        stats.connected_at = None;
+        stats.connected_time = Duration::from_secs(3725);
+
+        let values = stats.to_values();
+        assert_eq!(
+            values.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+            [
+                "info-stats-since",
+                "info-connected-time",
+                "info-reconnects",
+                "info-reauths",
+                "info-rekeys",
+                "info-last-error"
+            ]
+        );
+        assert_eq!(values[1].1, "01:02:05");
+        assert_eq!(values[2].1, "1");
+        assert_eq!(values[3].1, "2");
+        assert_eq!(values[4].1, "1");
+        assert!(values[5].1.ends_with(": Keepalive failed"));
+    }
+}
@@ -1,19 +1,49 @@
-use std::{collections::BTreeMap, net::Ipv4Addr};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    net::{Ipv4Addr, Ipv6Addr},
+};
 
+use ipnet::Ipv6Net;
 use serde::{Deserialize, Serialize};
 
-use crate::model::wrappers::*;
+use crate::{
+    model::{params::TunnelParams, wrappers::*},
+    sexpr::SExpression,
+};
+
+/// SNX protocol minor version offered first in the client hello.
+pub const PROTOCOL_MINOR_VERSION: u32 = 1;
+
+/// Older protocol dialect used as a fallback when the gateway rejects the current one.
+pub const LEGACY_PROTOCOL_MINOR_VERSION: u32 = 0;
+
+// The legacy dialect does not always carry the timeouts block.
+const LEGACY_KEEPALIVE_TIMEOUT: u64 = 20;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OfficeMode {
     pub ipaddr: String,
     pub keep_address: Option<bool>,
-    pub dns_servers: Option<Vec<Ipv4Addr>>,
+    pub dns_servers: Option<IpAddrList>,
     pub dns_suffix: Option<StringList>,
+    pub wins_servers: Option<Vec<Ipv4Addr>>,
+    pub ipv6_addr: Option<Ipv6Addr>,
+    pub ipv6_prefix_len: Option<u8>,
+    pub gw_internal_ip: Option<Ipv4Addr>,
+}
+
+impl OfficeMode {
+    /// IPv6 office-mode network, None when the gateway did not assign an IPv6 address.
+    pub fn ipv6_network(&self) -> Option<Ipv6Net> {
+        self.ipv6_addr
+            .and_then(|addr| Ipv6Net::new(addr, self.ipv6_prefix_len.unwrap_or(128)).ok())
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OptionalRequest {
+    #[serde(deserialize_with = "lenient_string")]
     pub client_type: String,
 }
 
@@ -25,15 +55,41 @@ pub struct ClientHello {
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ClientHelloData {
-    pub client_version: u32,
+    #[serde(deserialize_with = "lenient_string")]
+    pub client_version: String,
     pub protocol_version: u32,
     pub protocol_minor_version: u32,
     #[serde(rename = "OM")]
     pub office_mode: OfficeMode,
     pub optional: Option<OptionalRequest>,
+    #[serde(deserialize_with = "lenient_string")]
     pub cookie: String,
 }
 
+impl ClientHelloData {
+    pub fn new(params: &TunnelParams, ipaddr: &str, keep_address: bool, cookie: &str, minor_version: u32) -> Self {
+        Self {
+            client_version: params.hello_client_version.clone(),
+            protocol_version: 1,
+            protocol_minor_version: minor_version,
+            office_mode: OfficeMode {
+                ipaddr: ipaddr.to_owned(),
+                keep_address: Some(keep_address),
+                dns_servers: None,
+                dns_suffix: None,
+                wins_servers: None,
+                ipv6_addr: None,
+                ipv6_prefix_len: None,
+                gw_internal_ip: None,
+            },
+            optional: Some(OptionalRequest {
+                client_type: params.hello_client_type.clone(),
+            }),
+            cookie: cookie.to_owned(),
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HelloReply {
     #[serde(rename = "(hello_reply")]
@@ -46,9 +102,76 @@ pub struct HelloReplyData {
     pub protocol_version: u32,
     #[serde(rename = "OM")]
     pub office_mode: OfficeMode,
+    #[serde(default)]
     pub range: Vec<NetworkRange>,
+    #[serde(default)]
+    pub range_v6: Vec<Ipv6NetworkRange>,
+    #[serde(default)]
     pub timeouts: Timeouts,
     pub optional: Option<OptionalResponse>,
+    pub error: Option<String>,
+    #[serde(skip)]
+    pub protocol_minor_version: u32,
+}
+
+impl HelloReplyData {
+    /// Parse the hello reply according to the dialect of the given protocol minor version.
+    pub fn parse(value: serde_json::Value, minor_version: u32) -> anyhow::Result<Self> {
+        if minor_version >= PROTOCOL_MINOR_VERSION {
+            let data = value.get("(hello_reply");
+            for field in ["range", "timeouts"] {
+                anyhow::ensure!(
+                    data.and_then(|d| d.get(field)).is_some(),
+                    "Hello reply has no {} field for protocol minor version {}",
+                    field,
+                    minor_version
+                );
+            }
+        }
+
+        let mut reply = serde_json::from_value::<HelloReply>(value)?.data;
+
+        if minor_version < PROTOCOL_MINOR_VERSION && reply.timeouts.keepalive == 0 {
+            reply.timeouts.keepalive = LEGACY_KEEPALIVE_TIMEOUT;
+        }
+
+        reply.protocol_minor_version = minor_version;
+
+        Ok(reply)
+    }
+
+    pub fn protocol_version_string(&self) -> String {
+        format!("{}.{}", self.protocol_version, self.protocol_minor_version)
+    }
+}
+
+/// Gateway response to the client hello.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HelloResponse {
+    Reply(HelloReplyData),
+    Reconnect(String),
+    /// The gateway did not accept the hello in the offered dialect: it disconnected
+    /// or sent a reply which cannot be parsed.
+    Rejected(String),
+}
+
+impl HelloResponse {
+    pub fn parse(expr: &SExpression, minor_version: u32) -> anyhow::Result<Self> {
+        Self::from_packet(ControlPacket::parse(expr)?, minor_version)
+    }
+
+    pub fn from_packet(packet: ControlPacket, minor_version: u32) -> anyhow::Result<Self> {
+        match packet {
+            ControlPacket::HelloReply(value) => match HelloReplyData::parse(value, minor_version) {
+                Ok(reply) => Ok(Self::Reply(reply)),
+                Err(e) => Ok(Self::Rejected(e.to_string())),
+            },
+            ControlPacket::Reconnect(data) => Ok(Self::Reconnect(data.message.unwrap_or_default())),
+            ControlPacket::Error(data) => Err(data.to_error()),
+            ControlPacket::Disconnect(data) => Ok(Self::Rejected(data.to_string())),
+            other => Ok(Self::Rejected(format!("Unexpected {} packet", other.name()))),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -57,15 +180,24 @@ pub struct NetworkRange {
     pub to: Ipv4Addr,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ipv6NetworkRange {
+    pub from: Ipv6Addr,
+    pub to: Ipv6Addr,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Timeouts {
     pub authentication: u64,
     pub keepalive: u64,
+    /// Lifetime of the office mode address, the gateway reclaims it unless the hello is repeated.
+    pub om_lease: Option<u64>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OptionalResponse {
     pub subnet: String,
+    pub mtu: Option<u16>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -239,7 +371,9 @@ pub struct KeepaliveRequest {
     pub data: KeepaliveRequestData,
 }
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct KeepaliveRequestData {
+    #[serde(deserialize_with = "lenient_string")]
     pub id: String,
 }
 
@@ -250,11 +384,154 @@ pub struct DisconnectRequest {
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DisconnectRequestData {
+    #[serde(deserialize_with = "lenient_string")]
     pub code: String,
     pub message: Option<String>,
 }
 
+impl DisconnectRequestData {
+    pub const USER_DISCONNECT: &'static str = "28";
+
+    pub fn user_disconnect() -> Self {
+        Self {
+            code: Self::USER_DISCONNECT.to_owned(),
+            message: Some("User has disconnected.".to_owned()),
+        }
+    }
+}
+
+impl fmt::Display for DisconnectRequestData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.message {
+            Some(ref message) if !message.is_empty() => write!(f, "{}: {}", self.code, message),
+            _ => write!(f, "{}", self.code),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorReply {
+    #[serde(rename = "(error")]
+    pub data: ErrorReplyData,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorReplyData {
+    pub code: u32,
+    pub message: Option<String>,
+}
+
+impl ErrorReplyData {
+    // The meaning of the codes is not documented and no captured error reply is available to verify it,
+    // so the code is only reported together with the message of the gateway.
+    pub fn to_error(&self) -> anyhow::Error {
+        let description = i18n::tr!("error-gateway-error", code = self.code);
+
+        match self.message {
+            Some(ref message) if !message.is_empty() => anyhow::anyhow!("{}: {}", description, message),
+            _ => anyhow::anyhow!(description),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReconnectReply {
+    #[serde(rename = "(reconnect")]
+    pub data: ReconnectReplyData,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReconnectReplyData {
+    pub message: Option<String>,
+}
+
+/// Control packet of the SSL tunnel, told apart by the object name of its S-expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlPacket {
+    ClientHello(ClientHelloData),
+    /// The whole reply, it is parsed with [`HelloReplyData::parse`] in the dialect of the offered protocol version.
+    HelloReply(serde_json::Value),
+    /// Keepalive request of the client, the gateway answers with the same packet.
+    Keepalive(KeepaliveRequestData),
+    Disconnect(DisconnectRequestData),
+    Error(ErrorReplyData),
+    Reconnect(ReconnectReplyData),
+    ClientSettings(ClientSettingsData),
+    /// Packet without a typed equivalent, the value holds the whole packet.
+    Unknown {
+        name: String,
+        value: serde_json::Value,
+    },
+}
+
+impl ControlPacket {
+    /// Decode the packet, an error is returned only when a known packet has an unexpected content.
+    pub fn parse(expr: &SExpression) -> anyhow::Result<Self> {
+        let name = expr.object_name().unwrap_or_default();
+
+        let packet = match name {
+            "client_hello" => Self::ClientHello(expr.clone().try_into::<ClientHello>()?.data),
+            "hello_reply" => Self::HelloReply(expr.to_json()),
+            "keepalive" => Self::Keepalive(expr.clone().try_into::<KeepaliveRequest>()?.data),
+            "disconnect" => Self::Disconnect(expr.clone().try_into::<DisconnectRequest>()?.data),
+            "error" => Self::Error(expr.clone().try_into::<ErrorReply>()?.data),
+            "reconnect" => Self::Reconnect(expr.clone().try_into::<ReconnectReply>()?.data),
+            "ClientSettings" => Self::ClientSettings(expr.clone().try_into::<ClientSettingsRequest>()?.data),
+            _ => Self::Unknown {
+                name: name.to_owned(),
+                value: expr.to_json(),
+            },
+        };
+
+        Ok(packet)
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::ClientHello(_) => "client_hello",
+            Self::HelloReply(_) => "hello_reply",
+            Self::Keepalive(_) => "keepalive",
+            Self::Disconnect(_) => "disconnect",
+            Self::Error(_) => "error",
+            Self::Reconnect(_) => "reconnect",
+            Self::ClientSettings(_) => "ClientSettings",
+            Self::Unknown { name, .. } => name.as_str(),
+        }
+    }
+
+    pub fn into_sexpr(self) -> SExpression {
+        match self {
+            Self::ClientHello(data) => ClientHello { data }.into(),
+            Self::HelloReply(value) | Self::Unknown { value, .. } => value.into(),
+            Self::Keepalive(data) => KeepaliveRequest { data }.into(),
+            Self::Disconnect(data) => DisconnectRequest { data }.into(),
+            Self::Error(data) => ErrorReply { data }.into(),
+            Self::Reconnect(data) => ReconnectReply { data }.into(),
+            Self::ClientSettings(data) => ClientSettingsRequest { data }.into(),
+        }
+    }
+}
+
+impl From<ClientHelloData> for ControlPacket {
+    fn from(value: ClientHelloData) -> Self {
+        Self::ClientHello(value)
+    }
+}
+
+impl From<KeepaliveRequestData> for ControlPacket {
+    fn from(value: KeepaliveRequestData) -> Self {
+        Self::Keepalive(value)
+    }
+}
+
+impl From<DisconnectRequestData> for ControlPacket {
+    fn from(value: DisconnectRequestData) -> Self {
+        Self::Disconnect(value)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ClientInfo {
     pub client_type: String,
@@ -1,11 +1,11 @@
-use std::{fmt, marker::PhantomData};
+use std::{fmt, marker::PhantomData, net::IpAddr};
 
 use serde::{
     Deserialize, Deserializer, Serialize, Serializer,
     de::{Error, Visitor},
 };
 
-/// String separated with commas or semicolons
+/// String separated with commas, semicolons or whitespace, or a list of strings
 #[derive(Default, Clone, PartialEq)]
 pub struct StringList(pub Vec<String>);
 
@@ -23,10 +23,26 @@ impl<'de> Deserialize<'de> for StringList {
     where
         D: Deserializer<'de>,
     {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum StringOrList {
+            String(String),
+            List(Vec<String>),
+        }
+
+        let items = match StringOrList::deserialize(deserializer)? {
+            StringOrList::String(s) => vec![s],
+            StringOrList::List(list) => list,
+        };
+
         Ok(Self(
-            String::deserialize(deserializer)?
-                .trim_matches('"')
-                .split([',', ';'])
+            items
+                .iter()
+                .flat_map(|s| {
+                    s.trim_matches('"')
+                        .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+                })
+                .filter(|s| !s.is_empty())
                 .map(ToOwned::to_owned)
                 .collect(),
         ))
@@ -51,6 +67,55 @@ impl fmt::Debug for StringList {
     }
 }
 
+/// IP addresses given either as a list or as a single string separated with commas, semicolons or whitespace.
+/// The order is preserved.
+#[derive(Default, Clone, PartialEq)]
+pub struct IpAddrList(pub Vec<IpAddr>);
+
+impl Serialize for IpAddrList {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for IpAddrList {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let items = StringList::deserialize(deserializer)?;
+
+        Ok(Self(
+            items
+                .0
+                .iter()
+                .map(|s| s.parse().map_err(Error::custom))
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+}
+
+impl From<Vec<IpAddr>> for IpAddrList {
+    fn from(value: Vec<IpAddr>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<IpAddrList> for Vec<IpAddr> {
+    fn from(value: IpAddrList) -> Self {
+        value.0
+    }
+}
+
+impl fmt::Debug for IpAddrList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        std::fmt::Debug::fmt(&self.0, f)
+    }
+}
+
 /// Encrypted string. 'Encryption' here is a simple xor operation.
 #[derive(Default, Clone, PartialEq)]
 pub struct EncryptedString(pub String);
@@ -163,3 +228,56 @@ impl<T: TryFrom<u64>> Visitor<'_> for MaybeVisitor<T> {
         Ok(Maybe(None))
     }
 }
+
+/// String which the conversion from the S-expression may have turned into a number or a boolean
+pub fn lenient_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(LenientStringVisitor)
+}
+
+struct LenientStringVisitor;
+
+impl Visitor<'_> for LenientStringVisitor {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "string, number or boolean value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(v.to_string())
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(v.to_string())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(v.to_string())
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(v.to_owned())
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(v)
+    }
+}
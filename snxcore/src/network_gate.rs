@@ -0,0 +1,140 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tracing::{debug, info, warn};
+
+use crate::{
+    model::params::TunnelParams,
+    platform::{self, NetworkInterface},
+    proxy, util,
+};
+
+// DNS coming up is not reported as a network change, so the conditions are polled as well
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Why the network is not usable yet.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NetworkNotReady {
+    NoDefaultRoute,
+    Unresolved(String),
+}
+
+impl std::fmt::Display for NetworkNotReady {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoDefaultRoute => write!(f, "no default route"),
+            Self::Unresolved(host) => write!(f, "{host} does not resolve"),
+        }
+    }
+}
+
+/// Holds the connection attempts back until the network is usable: at boot the network manager may not have
+/// set up the default route and DNS yet, and the attempts would use up the retries of the tunnel.
+pub struct NetworkGate {
+    params: Arc<TunnelParams>,
+}
+
+impl NetworkGate {
+    pub fn new(params: Arc<TunnelParams>) -> Self {
+        Self { params }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.params.timeouts.network.is_zero()
+    }
+
+    pub async fn check(&self) -> Result<(), NetworkNotReady> {
+        let net = platform::new_network_interface();
+        if net.get_default_gateway(self.params.tunnel_device()).await.is_err() {
+            return Err(NetworkNotReady::NoDefaultRoute);
+        }
+
+        // behind a proxy only the proxy must resolve, it resolves the gateway itself
+        let (host, address) = match proxy::select(&self.params) {
+            Some(server) => (server.host.clone(), server.address()),
+            None => (self.params.server_host().to_owned(), self.params.server_address()),
+        };
+        let resolved = tokio::task::spawn_blocking(move || util::resolve_ipv4_host(&address)).await;
+
+        if !matches!(resolved, Ok(Ok(_))) {
+            return Err(NetworkNotReady::Unresolved(host));
+        }
+
+        Ok(())
+    }
+
+    /// Wait until the network is usable or the `timeouts.network` deadline passes, whichever is first.
+    /// Returns whether the network became usable.
+    pub async fn wait(&self) -> bool {
+        if !self.is_enabled() {
+            return true;
+        }
+
+        let started = Instant::now();
+        let deadline = started + self.params.timeouts.network;
+        let mut changes = platform::new_network_interface().network_changes();
+        let mut last_log = None;
+
+        loop {
+            let reason = match self.check().await {
+                Ok(()) => {
+                    if last_log.is_some() {
+                        info!("Network is ready after {}s", started.elapsed().as_secs());
+                    }
+                    return true;
+                }
+                Err(reason) => reason,
+            };
+
+            let now = Instant::now();
+            if now >= deadline {
+                warn!(
+                    "Network is not ready after {}s ({}), connecting anyway",
+                    self.params.timeouts.network.as_secs(),
+                    reason
+                );
+                return false;
+            }
+
+            if last_log.is_none_or(|last: Instant| now.duration_since(last) >= LOG_INTERVAL) {
+                info!("Waiting for the network: {}", reason);
+                last_log = Some(now);
+            } else {
+                debug!("Network is not ready: {}", reason);
+            }
+
+            let delay = POLL_INTERVAL.min(deadline - now);
+            if let Ok(Err(_)) = tokio::time::timeout(delay, changes.changed()).await {
+                // no network monitoring, only polling
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_disabled() {
+        let mut params = TunnelParams::default();
+        params.timeouts.network = Duration::ZERO;
+        let gate = NetworkGate::new(Arc::new(params));
+
+        assert!(!gate.is_enabled());
+        assert!(gate.wait().await);
+    }
+
+    #[test]
+    fn test_reason() {
+        assert_eq!(NetworkNotReady::NoDefaultRoute.to_string(), "no default route");
+        assert_eq!(
+            NetworkNotReady::Unresolved("vpn.example.com".to_owned()).to_string(),
+            "vpn.example.com does not resolve"
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! Selection of the profile by the network the machine is attached to. Every profile may have `match` criteria:
+//! the criteria of the same kind are alternatives, and all kinds must be met. When exactly one profile matches
+//! it is used, otherwise the default one.
+
+use std::{fmt, mem, sync::Arc};
+
+use ipnet::Ipv4Net;
+use tokio::{sync::mpsc, task::JoinHandle};
+use tracing::{debug, info};
+
+use crate::{
+    control::ControlAction,
+    model::params::{NetworkMatch, ProfileCriteria, TunnelParams},
+    platform::{self, NetworkInterface},
+    util,
+};
+
+/// Snapshot of the network the criteria are evaluated against.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct NetworkFacts {
+    pub ssids: Vec<String>,
+    pub domains: Vec<String>,
+    pub networks: Vec<Ipv4Net>,
+}
+
+impl NetworkFacts {
+    /// Collect the facts of the local networks, the tunnel device is left out.
+    pub async fn collect(exclude_device: &str) -> Self {
+        let net = platform::new_network_interface();
+
+        Self {
+            ssids: net.get_wireless_ssids().await,
+            domains: net.get_search_domains(exclude_device).await.unwrap_or_default(),
+            networks: net
+                .get_local_networks(exclude_device)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .map(|local| local.network)
+                .collect(),
+        }
+    }
+
+    pub fn has_ssid(&self, ssid: &str) -> bool {
+        self.ssids.iter().any(|s| s == ssid)
+    }
+
+    pub fn has_domain(&self, domain: &str) -> bool {
+        self.domains.iter().any(|d| d.eq_ignore_ascii_case(domain))
+    }
+
+    /// Whether a local network lies within the subnet, or the subnet within a local network.
+    pub fn has_subnet(&self, subnet: &Ipv4Net) -> bool {
+        self.networks
+            .iter()
+            .any(|network| subnet.contains(network) || network.contains(subnet))
+    }
+
+    pub fn is_met(&self, criterion: &NetworkMatch) -> bool {
+        match criterion {
+            NetworkMatch::Ssid(ssid) => self.has_ssid(ssid),
+            NetworkMatch::Domain(domain) => self.has_domain(domain),
+            NetworkMatch::Subnet(subnet) => self.has_subnet(subnet),
+        }
+    }
+}
+
+/// Whether the network meets the criteria: one criterion of every kind which is present. No criteria never match.
+pub fn matches(criteria: &[NetworkMatch], facts: &NetworkFacts) -> bool {
+    !criteria.is_empty()
+        && criteria.iter().all(|criterion| {
+            criteria
+                .iter()
+                .filter(|other| mem::discriminant(*other) == mem::discriminant(criterion))
+                .any(|other| facts.is_met(other))
+        })
+}
+
+/// Outcome of the profile selection.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProfileSelection {
+    /// Exactly one profile matches the network.
+    Matched(String),
+    /// No profile matches, the default one is used.
+    NoMatch(Option<String>),
+    /// Several profiles match, the default one is used.
+    Ambiguous {
+        matched: Vec<String>,
+        default: Option<String>,
+    },
+}
+
+impl ProfileSelection {
+    /// The selected profile, `None` for the top-level options.
+    pub fn profile(&self) -> Option<&str> {
+        match self {
+            Self::Matched(name) => Some(name.as_str()),
+            Self::NoMatch(default) | Self::Ambiguous { default, .. } => default.as_deref(),
+        }
+    }
+}
+
+impl fmt::Display for ProfileSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let default = |default: &Option<String>| match default {
+            Some(name) => format!("the default profile {name}"),
+            None => "the top-level options".to_owned(),
+        };
+
+        let s = match self {
+            Self::Matched(name) => format!("profile {name} matches the network"),
+            Self::NoMatch(name) => format!("no profile matches the network, using {}", default(name)),
+            Self::Ambiguous { matched, default: name } => format!(
+                "profiles {} all match the network, using {}",
+                matched.join(", "),
+                default(name)
+            ),
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Select the profile for the network facts.
+pub fn select_profile(criteria: &ProfileCriteria, facts: &NetworkFacts) -> ProfileSelection {
+    let mut matched = criteria
+        .profiles
+        .iter()
+        .filter(|(_, profile_criteria)| matches(profile_criteria, facts))
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+
+    let default = criteria.default_profile.clone();
+
+    match matched.len() {
+        0 => ProfileSelection::NoMatch(default),
+        1 => ProfileSelection::Matched(matched.remove(0)),
+        _ => ProfileSelection::Ambiguous { matched, default },
+    }
+}
+
+/// Collect the facts of the current network and select the profile.
+pub async fn select(criteria: &ProfileCriteria, exclude_device: &str) -> ProfileSelection {
+    let facts = NetworkFacts::collect(exclude_device).await;
+    debug!("Selecting the profile by the network: {:?}", facts);
+    select_profile(criteria, &facts)
+}
+
+/// Ends the session with a reconnect when a network change selects another profile. Stops on drop.
+pub struct ProfileWatch(JoinHandle<()>);
+
+impl ProfileWatch {
+    /// `None` if the profile was named on the command line, or no profile has `match` criteria.
+    pub fn start(
+        params: Arc<TunnelParams>,
+        criteria: ProfileCriteria,
+        actions: mpsc::Sender<ControlAction>,
+    ) -> Option<Self> {
+        if !params.match_profile || criteria.profiles.is_empty() {
+            return None;
+        }
+
+        Some(Self(tokio::spawn(async move {
+            let mut changes = platform::new_network_interface().network_changes();
+
+            while changes.changed().await.is_ok() {
+                util::wait_for_network_settle(&mut changes).await;
+
+                let selection = select(&criteria, params.tunnel_device()).await;
+                if selection.profile() != params.profile.as_deref() {
+                    info!("Switching the profile: {}", selection);
+                    let _ = actions.send(ControlAction::Reconnect).await;
+                    break;
+                }
+            }
+        })))
+    }
+}
+
+impl Drop for ProfileWatch {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn facts() -> NetworkFacts {
+        NetworkFacts {
+            ssids: vec!["Corp WiFi".to_owned()],
+            domains: vec!["corp.example.com".to_owned()],
+            networks: vec!["10.20.1.0/24".parse().unwrap(), "192.168.1.0/24".parse().unwrap()],
+        }
+    }
+
+    fn criteria(items: &[&str]) -> Vec<NetworkMatch> {
+        items.iter().map(|item| item.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_matches() {
+        let facts = facts();
+
+        assert!(matches(&criteria(&["ssid:Corp WiFi"]), &facts));
+        assert!(!matches(&criteria(&["ssid:corp wifi"]), &facts));
+        assert!(matches(&criteria(&["domain:Corp.Example.com"]), &facts));
+        assert!(matches(&criteria(&["subnet:10.0.0.0/8"]), &facts));
+        assert!(matches(&criteria(&["subnet:192.168.1.128/25"]), &facts));
+        assert!(!matches(&criteria(&["subnet:172.16.0.0/12"]), &facts));
+        assert!(!matches(&[], &facts));
+
+        // alternatives of the same kind, all kinds must be met
+        assert!(matches(
+            &criteria(&["ssid:Home", "ssid:Corp WiFi", "subnet:10.20.0.0/16"]),
+            &facts
+        ));
+        assert!(!matches(
+            &criteria(&["ssid:Corp WiFi", "domain:home.example.com"]),
+            &facts
+        ));
+    }
+
+    #[test]
+    fn test_select_profile() {
+        let facts = facts();
+        let mut profiles = ProfileCriteria {
+            default_profile: Some("remote".to_owned()),
+            profiles: vec![
+                ("lab".to_owned(), criteria(&["subnet:172.16.0.0/12"])),
+                ("office".to_owned(), criteria(&["ssid:Corp WiFi"])),
+            ],
+        };
+
+        let selection = select_profile(&profiles, &facts);
+        assert_eq!(selection, ProfileSelection::Matched("office".to_owned()));
+        assert_eq!(selection.profile(), Some("office"));
+
+        profiles
+            .profiles
+            .push(("lan".to_owned(), criteria(&["domain:corp.example.com"])));
+        let selection = select_profile(&profiles, &facts);
+        assert_eq!(selection.profile(), Some("remote"));
+        assert_eq!(
+            selection.to_string(),
+            "profiles office, lan all match the network, using the default profile remote"
+        );
+
+        let selection = select_profile(&profiles, &NetworkFacts::default());
+        assert_eq!(selection, ProfileSelection::NoMatch(Some("remote".to_owned())));
+
+        profiles.default_profile = None;
+        let selection = select_profile(&profiles, &NetworkFacts::default());
+        assert_eq!(selection.profile(), None);
+        assert_eq!(
+            selection.to_string(),
+            "no profile matches the network, using the top-level options"
+        );
+    }
+}
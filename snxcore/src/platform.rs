@@ -1,23 +1,60 @@
 use std::{
-    net::{Ipv4Addr, SocketAddr},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     time::Duration,
 };
 
 use anyhow::anyhow;
 use async_trait::async_trait;
-use ipnet::Ipv4Net;
+#[cfg(any(target_os = "freebsd", target_os = "openbsd"))]
+use bsd as platform_impl;
+use ipnet::{IpNet, Ipv4Net, Ipv6Net};
 #[cfg(target_os = "linux")]
 use linux as platform_impl;
+#[cfg(target_os = "macos")]
+use macos as platform_impl;
 pub use platform_impl::{
-    IpsecImpl, KeychainImpl, NetworkInterfaceImpl, RoutingImpl, SingleInstance, get_features, get_machine_uuid, init,
-    new_resolver_configurator,
+    AppRoutingImpl, AuthorityImpl, DnsRedirectImpl, ForwardingImpl, IpsecImpl, Ipv6BlockerImpl, KeychainImpl,
+    KillSwitchImpl, MssClampImpl, NetworkInterfaceImpl, NetworkNamespaceImpl, RoutingImpl, apply_params,
+    check_permissions, check_privilege_drop, dns_backend_name, drop_privileges, effective_dns_mode, get_features,
+    get_machine_uuid, init, new_resolver_configurator, restore_stale_resolv_conf, restore_stale_routing,
 };
-use tokio::net::UdpSocket;
+use serde::{Deserialize, Serialize};
+use tokio::{net::UdpSocket, sync::watch};
+use tracing::{info, warn};
+#[cfg(windows)]
+use windows as platform_impl;
 
-use crate::model::IpsecSession;
+use crate::model::{
+    IpsecSession, TrafficCounters,
+    params::{Ipv6BlockMode, TunnelParams},
+};
+
+#[cfg(target_os = "linux")]
+pub(crate) use linux::netlink;
+#[cfg(all(target_os = "linux", feature = "dbus"))]
+pub use linux::polkit;
+#[cfg(unix)]
+pub use single_instance::SingleInstance;
+#[cfg(windows)]
+pub(crate) use windows::iphelper;
 
+#[cfg(any(target_os = "freebsd", target_os = "openbsd"))]
+mod bsd;
+#[cfg(any(target_os = "macos", target_os = "freebsd", target_os = "openbsd"))]
+mod bsd_common;
 #[cfg(target_os = "linux")]
 mod linux;
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "openbsd"))]
+mod resolv_conf;
+#[cfg(unix)]
+mod single_instance;
+#[cfg(any(not(target_os = "linux"), not(feature = "dbus")))]
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+mod unsupported;
+#[cfg(windows)]
+mod windows;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct PlatformFeatures {
@@ -45,6 +82,10 @@ pub trait UdpSocketExt {
     async fn send_receive(&self, data: &[u8], timeout: Duration, target: SocketAddr) -> anyhow::Result<Vec<u8>>;
 }
 
+pub trait TcpSocketExt {
+    fn set_mark(&self, mark: u32) -> anyhow::Result<()>;
+}
+
 async fn udp_send_receive(
     socket: &UdpSocket,
     data: &[u8],
@@ -65,10 +106,84 @@ async fn udp_send_receive(
     }
 }
 
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ResolverConfig {
     pub search_domains: Vec<String>,
-    pub dns_servers: Vec<Ipv4Addr>,
+    pub dns_servers: Vec<IpAddr>,
+    #[serde(default)]
+    pub wins_servers: Vec<Ipv4Addr>,
+}
+
+impl ResolverConfig {
+    /// Lowercase the domains, strip the surrounding dots and remove duplicates, keeping the first occurrence.
+    /// The '~' prefix of routing domains is preserved. DNS servers keep their order, duplicates and unspecified
+    /// addresses are dropped.
+    pub fn normalized(self) -> Self {
+        let mut search_domains: Vec<String> = Vec::new();
+
+        for domain in &self.search_domains {
+            let (prefix, name) = match domain.trim().strip_prefix('~') {
+                Some(name) => ("~", name),
+                None => ("", domain.trim()),
+            };
+            let name = name.trim_matches('.').to_lowercase();
+
+            if !name.is_empty() && !search_domains.iter().any(|d| d.trim_start_matches('~') == name) {
+                search_domains.push(format!("{prefix}{name}"));
+            }
+        }
+
+        let mut dns_servers: Vec<IpAddr> = Vec::new();
+        for server in self.dns_servers {
+            if server.is_unspecified() {
+                warn!("Ignoring unspecified DNS server address {}", server);
+            } else if !dns_servers.contains(&server) {
+                dns_servers.push(server);
+            }
+        }
+
+        Self {
+            search_domains,
+            dns_servers,
+            wins_servers: self.wins_servers,
+        }
+    }
+
+    /// The `search` and `nameserver` lines of resolv.conf, which has no concept of routing domains.
+    pub fn resolv_conf_lines(&self) -> Vec<String> {
+        let search_domains = self
+            .search_domains
+            .iter()
+            .map(|s| s.trim_matches(|c: char| c.is_whitespace() || c == '.' || c == '~'))
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+
+        let mut lines = Vec::new();
+
+        if !search_domains.is_empty() {
+            lines.push(format!("search {}", search_domains.join(" ")));
+        }
+
+        lines.extend(self.dns_servers.iter().map(|s| format!("nameserver {}", s)));
+
+        lines
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DefaultGateway {
+    pub address: Ipv4Addr,
+    pub device: String,
+    pub mac: Option<String>,
+    /// Address of the local interface the gateway is reached through.
+    pub local_address: Option<Ipv4Addr>,
+}
+
+/// IPv4 network directly reachable through a local interface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalNetwork {
+    pub device: String,
+    pub network: Ipv4Net,
 }
 
 #[async_trait]
@@ -77,25 +192,337 @@ pub trait ResolverConfigurator {
     async fn cleanup(&self, config: &ResolverConfig) -> anyhow::Result<()>;
 }
 
+/// The `print` DNS backend: the resolver configuration is only logged in the resolv.conf format, for an entrypoint
+/// or hook script to apply it. Nothing on the system is changed.
+pub struct PrintConfigurator;
+
+#[async_trait]
+impl ResolverConfigurator for PrintConfigurator {
+    async fn configure(&self, config: &ResolverConfig) -> anyhow::Result<()> {
+        for line in config.resolv_conf_lines() {
+            info!("DNS configuration: {}", line);
+        }
+        Ok(())
+    }
+
+    async fn cleanup(&self, _config: &ResolverConfig) -> anyhow::Result<()> {
+        info!("DNS configuration withdrawn");
+        Ok(())
+    }
+}
+
 #[async_trait]
 pub trait Keychain {
     async fn acquire_password(&self, username: &str) -> anyhow::Result<String>;
     async fn store_password(&self, username: &str, password: &str) -> anyhow::Result<()>;
 }
 
+/// Routing table used by the default route mode. With a firewall mark the table is selected for all packets
+/// except the marked ones of the tunnel socket, otherwise for all packets not destined to the gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutingPolicy {
+    pub table: u32,
+    pub fwmark: Option<u32>,
+}
+
+impl Default for RoutingPolicy {
+    fn default() -> Self {
+        Self {
+            table: TunnelParams::IPSEC_KEEPALIVE_PORT.into(),
+            fwmark: None,
+        }
+    }
+}
+
+impl RoutingPolicy {
+    pub fn new(params: &TunnelParams, with_fwmark: bool) -> Self {
+        Self {
+            table: params.routing_table,
+            fwmark: params.fwmark.filter(|_| with_fwmark),
+        }
+    }
+}
+
+/// Host route which pins the gateway address to the next hop of the underlying network.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GatewayRoute {
+    pub destination: Ipv4Addr,
+    pub gateway: Option<Ipv4Addr>,
+    pub device: String,
+}
+
 #[async_trait]
 pub trait RoutingConfigurator {
+    /// Use the peer of a point-to-point device as the explicit next hop of the IPv4 routes.
+    fn with_peer(self, _peer: Option<Ipv4Addr>) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
     async fn add_routes(&self, routes: &[Ipv4Net], ignore_routes: &[Ipv4Net]) -> anyhow::Result<()>;
-    async fn setup_default_route(&self, destination: Ipv4Addr) -> anyhow::Result<()>;
-    async fn setup_keepalive_route(&self, destination: Ipv4Addr, with_table: bool) -> anyhow::Result<()>;
-    async fn remove_default_route(&self, destination: Ipv4Addr) -> anyhow::Result<()>;
-    async fn remove_keepalive_route(&self, destination: Ipv4Addr) -> anyhow::Result<()>;
+    async fn add_ipv6_routes(&self, routes: &[Ipv6Net]) -> anyhow::Result<()>;
+    async fn remove_routes(&self, routes: &[IpNet]) -> anyhow::Result<()>;
+    async fn setup_default_route(&self, destination: Ipv4Addr, policy: &RoutingPolicy) -> anyhow::Result<()>;
+    async fn setup_keepalive_route(
+        &self,
+        destination: Ipv4Addr,
+        with_table: bool,
+        policy: &RoutingPolicy,
+    ) -> anyhow::Result<()>;
+    async fn remove_default_route(&self, destination: Ipv4Addr, policy: &RoutingPolicy) -> anyhow::Result<()>;
+    async fn remove_keepalive_route(&self, destination: Ipv4Addr, policy: &RoutingPolicy) -> anyhow::Result<()>;
+    async fn setup_excluded_routes(&self, routes: &[Ipv4Net]) -> anyhow::Result<()>;
+    async fn check_default_route(&self, destination: Ipv4Addr, policy: &RoutingPolicy) -> anyhow::Result<bool>;
+    async fn remove_excluded_routes(&self, routes: &[Ipv4Net]) -> anyhow::Result<()>;
+    /// Find out the current route to the gateway outside the tunnel, so that it can be recorded before it is pinned.
+    async fn find_gateway_route(&self, destination: Ipv4Addr) -> anyhow::Result<GatewayRoute>;
+    async fn add_gateway_route(&self, route: &GatewayRoute) -> anyhow::Result<()>;
+    async fn remove_gateway_route(&self, route: &GatewayRoute) -> anyhow::Result<()>;
+}
+
+/// Prefixes which must never leave through another interface than the tunnel device.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KillSwitchConfig {
+    pub device: String,
+    pub tunneled: Vec<IpNet>,
+    pub allowed: Vec<IpNet>,
+}
+
+impl KillSwitchConfig {
+    /// In the default route mode all IPv4 traffic is tunneled, excluded prefixes and the gateway are always allowed.
+    pub fn new(device: &str, routes: &[Ipv4Net], default_route: bool, exclusions: &[Ipv4Net]) -> Self {
+        let mut tunneled = routes.iter().copied().map(IpNet::V4).collect::<Vec<_>>();
+        if default_route {
+            tunneled.push(IpNet::V4(Ipv4Net::default()));
+        }
+
+        Self {
+            device: device.to_owned(),
+            tunneled,
+            allowed: exclusions.iter().copied().map(IpNet::V4).collect(),
+        }
+    }
+}
+
+#[async_trait]
+pub trait KillSwitch {
+    async fn enable(&self, config: &KillSwitchConfig) -> anyhow::Result<()>;
+    async fn disable(&self) -> anyhow::Result<()>;
+}
+
+/// LAN hosts from the source prefix which are masqueraded behind the office-mode address of the tunnel device.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForwardingConfig {
+    pub device: String,
+    pub source: Ipv4Net,
+}
+
+impl ForwardingConfig {
+    /// The replies to the LAN hosts must not be routed into the tunnel, so the source prefix may overlap
+    /// neither the office-mode address nor the tunneled prefixes.
+    pub fn new(device: &str, source: Ipv4Net, address: Ipv4Addr, routes: &[Ipv4Net]) -> anyhow::Result<Self> {
+        let source = source.trunc();
+
+        if source.contains(&address) || routes.iter().any(|r| r.contains(&source) || source.contains(r)) {
+            anyhow::bail!(i18n::tr!("error-forward-source-conflict", source = source.to_string()));
+        }
+
+        Ok(Self {
+            device: device.to_owned(),
+            source,
+        })
+    }
+}
+
+/// Traffic of the selected applications marked and routed through the tunnel table, everything else stays outside.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppRouteConfig {
+    pub device: String,
+    pub table: u32,
+    pub mark: u32,
+    pub cgroups: Vec<String>,
+    pub uids: Vec<u32>,
+}
+
+impl AppRouteConfig {
+    /// Distinct from the `fwmark` of the outer sockets, which must never be routed into the tunnel.
+    pub const MARK: u32 = 0x736e78;
+
+    pub fn new(params: &TunnelParams, device: &str) -> Self {
+        Self {
+            device: device.to_owned(),
+            table: params.routing_table,
+            mark: Self::MARK,
+            cgroups: params.app_route_cgroups.clone(),
+            uids: params.app_route_uids.clone(),
+        }
+    }
+}
+
+#[async_trait]
+pub trait AppRouting {
+    async fn enable(&self, config: &AppRouteConfig) -> anyhow::Result<()>;
+    async fn disable(&self, config: &AppRouteConfig) -> anyhow::Result<()>;
+}
+
+/// DNS queries on port 53 from the local host and the forwarded LAN hosts sent to the tunnel DNS server
+/// whatever their destination.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DnsRedirectConfig {
+    pub device: String,
+    pub server: Option<Ipv4Addr>,
+    pub server_v6: Option<Ipv6Addr>,
+    pub forward_source: Option<Ipv4Net>,
+}
+
+impl DnsRedirectConfig {
+    /// The first tunnel DNS server of each address family, None when the tunnel has no DNS servers.
+    pub fn new(params: &TunnelParams, device: &str, dns_servers: &[IpAddr]) -> Option<Self> {
+        let server = dns_servers.iter().find_map(|server| match server {
+            IpAddr::V4(v4) => Some(*v4),
+            IpAddr::V6(_) => None,
+        });
+        let server_v6 = dns_servers.iter().find_map(|server| match server {
+            IpAddr::V4(_) => None,
+            IpAddr::V6(v6) => Some(*v6),
+        });
+
+        if server.is_none() && server_v6.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            device: device.to_owned(),
+            server,
+            server_v6,
+            forward_source: params.forward_source.map(|source| source.trunc()),
+        })
+    }
+}
+
+#[async_trait]
+pub trait DnsRedirect {
+    async fn enable(&self, config: &DnsRedirectConfig) -> anyhow::Result<()>;
+    async fn disable(&self) -> anyhow::Result<()>;
+}
+
+#[async_trait]
+pub trait Forwarding {
+    async fn enable(&self, config: &ForwardingConfig) -> anyhow::Result<()>;
+    async fn disable(&self, config: &ForwardingConfig) -> anyhow::Result<()>;
+}
+
+/// TCP MSS of the flows through the tunnel device clamped to the route MTU.
+#[async_trait]
+pub trait MssClamp {
+    async fn enable(&self, device: &str) -> anyhow::Result<()>;
+    async fn disable(&self, device: &str) -> anyhow::Result<()>;
+}
+
+/// IPv6 outside the tunnel blocked for the session, the interfaces are the ones whose IPv6 is disabled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ipv6BlockConfig {
+    pub mode: Ipv6BlockMode,
+    pub device: String,
+    #[serde(default)]
+    pub interfaces: Vec<String>,
+}
+
+#[async_trait]
+pub trait Ipv6Blocker {
+    /// Find out what is going to be changed, so that it can be recorded before the change is made.
+    async fn prepare(&self, mode: Ipv6BlockMode, device: &str) -> anyhow::Result<Ipv6BlockConfig>;
+    async fn block(&self, config: &Ipv6BlockConfig) -> anyhow::Result<()>;
+    async fn unblock(&self, config: &Ipv6BlockConfig) -> anyhow::Result<()>;
+}
+
+/// Tunnel device moved into a named network namespace, together with its addresses, routes and DNS.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamespaceConfig {
+    pub name: String,
+    pub device: String,
+    pub mtu: u16,
+    pub address: Ipv4Net,
+    pub peer: Option<Ipv4Addr>,
+    pub address_v6: Option<Ipv6Net>,
+    pub routes: Vec<IpNet>,
+    pub resolver: ResolverConfig,
+}
+
+impl NamespaceConfig {
+    /// Everything inside the namespace goes through the tunnel, the routes and DNS are left out when disabled.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        params: &TunnelParams,
+        name: &str,
+        device: &str,
+        mtu: u16,
+        address: Ipv4Net,
+        peer: Option<Ipv4Addr>,
+        address_v6: Option<Ipv6Net>,
+        resolver: &ResolverConfig,
+    ) -> Self {
+        let mut routes = Vec::new();
+        if !params.no_routing {
+            routes.push(IpNet::V4(Ipv4Net::default()));
+            if address_v6.is_some() {
+                routes.push(IpNet::V6(Ipv6Net::default()));
+            }
+        }
+
+        Self {
+            name: name.to_owned(),
+            device: device.to_owned(),
+            mtu,
+            address,
+            peer,
+            address_v6,
+            routes,
+            resolver: if params.no_dns {
+                ResolverConfig::default()
+            } else {
+                resolver.clone()
+            },
+        }
+    }
+}
+
+pub const POLKIT_ACTION_CONNECT: &str = "org.snx_rs.tunnel.connect";
+pub const POLKIT_ACTION_DISCONNECT: &str = "org.snx_rs.tunnel.disconnect";
+pub const POLKIT_ACTION_STATUS: &str = "org.snx_rs.tunnel.status";
+
+/// Local process which sent a request to the command mode service.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PeerCredentials {
+    pub uid: u32,
+    pub pid: Option<i32>,
+}
+
+/// Authorization of the command mode service requests by the polkit actions.
+#[async_trait]
+pub trait Authority {
+    async fn check_authorization(&self, peer: PeerCredentials, action_id: &str) -> anyhow::Result<bool>;
+}
+
+#[async_trait]
+pub trait NetworkNamespace {
+    async fn exists(&self, name: &str) -> bool;
+    async fn create(&self, name: &str) -> anyhow::Result<()>;
+    async fn delete(&self, name: &str) -> anyhow::Result<()>;
+    /// Move the device into the namespace and configure it there, the sockets of the tunnel stay outside.
+    async fn configure(&self, config: &NamespaceConfig) -> anyhow::Result<()>;
+    async fn cleanup(&self, config: &NamespaceConfig) -> anyhow::Result<()>;
 }
 
 #[async_trait]
 pub trait NetworkInterface {
     async fn start_network_state_monitoring(&self) -> anyhow::Result<()>;
     async fn get_default_ip(&self) -> anyhow::Result<Ipv4Addr>;
+    async fn get_default_gateway(&self, exclude_device: &str) -> anyhow::Result<DefaultGateway>;
+    async fn get_search_domains(&self, exclude_device: &str) -> anyhow::Result<Vec<String>>;
+    async fn get_local_networks(&self, exclude_device: &str) -> anyhow::Result<Vec<LocalNetwork>>;
     async fn delete_device(&self, device_name: &str) -> anyhow::Result<()>;
     async fn configure_device(&self, device_name: &str) -> anyhow::Result<()>;
     async fn replace_ip_address(
@@ -104,9 +531,23 @@ pub trait NetworkInterface {
         old_address: Ipv4Net,
         new_address: Ipv4Net,
     ) -> anyhow::Result<()>;
+    async fn add_ipv6_address(&self, device_name: &str, address: Ipv6Net) -> anyhow::Result<()>;
+    async fn set_mtu(&self, device_name: &str, mtu: u16) -> anyhow::Result<()>;
+    async fn update_connectivity(&self);
+
+    /// Byte and packet counters of the device, when the platform provides them.
+    async fn get_traffic_counters(&self, _device_name: &str) -> Option<TrafficCounters> {
+        None
+    }
+
+    /// SSIDs of the connected wireless networks, when the platform provides them.
+    async fn get_wireless_ssids(&self) -> Vec<String> {
+        Vec::new()
+    }
 
     fn is_online(&self) -> bool;
     fn poll_online(&self);
+    fn network_changes(&self) -> watch::Receiver<u64>;
 }
 
 pub fn new_ipsec_configurator(
@@ -115,18 +556,56 @@ pub fn new_ipsec_configurator(
     src_port: u16,
     dest_ip: Ipv4Addr,
     dest_port: u16,
+    mtu: u16,
+    policy: RoutingPolicy,
 ) -> anyhow::Result<impl IpsecConfigurator + use<>> {
-    IpsecImpl::new(name, ipsec_session, src_port, dest_ip, dest_port)
+    IpsecImpl::new(name, ipsec_session, src_port, dest_ip, dest_port, mtu, policy)
 }
 
 pub fn new_keychain() -> impl Keychain {
     KeychainImpl::new()
 }
 
-pub fn new_routing_configurator<S: AsRef<str>>(device: S, address: Ipv4Addr) -> impl RoutingConfigurator {
-    RoutingImpl::new(device, address)
+pub fn new_routing_configurator<S: AsRef<str>>(
+    device: S,
+    address: Ipv4Addr,
+    metric: Option<u32>,
+) -> impl RoutingConfigurator {
+    RoutingImpl::new(device, address, metric)
 }
 
 pub fn new_network_interface() -> impl NetworkInterface {
     NetworkInterfaceImpl::new()
 }
+
+pub fn new_kill_switch() -> impl KillSwitch {
+    KillSwitchImpl::new()
+}
+
+pub fn new_forwarding() -> impl Forwarding {
+    ForwardingImpl::new()
+}
+
+pub fn new_app_routing() -> impl AppRouting {
+    AppRoutingImpl::new()
+}
+
+pub fn new_dns_redirect() -> impl DnsRedirect {
+    DnsRedirectImpl::new()
+}
+
+pub fn new_ipv6_blocker() -> impl Ipv6Blocker {
+    Ipv6BlockerImpl::new()
+}
+
+pub fn new_mss_clamp() -> impl MssClamp {
+    MssClampImpl::new()
+}
+
+pub fn new_network_namespace() -> impl NetworkNamespace {
+    NetworkNamespaceImpl::new()
+}
+
+pub fn new_authority() -> impl Authority {
+    AuthorityImpl::new()
+}
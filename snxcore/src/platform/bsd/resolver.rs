@@ -0,0 +1,85 @@
+use async_trait::async_trait;
+use tracing::warn;
+
+use crate::{
+    model::params::{DnsBackend, DnsMode, TunnelParams},
+    platform::{
+        PrintConfigurator, ResolverConfig, ResolverConfigurator,
+        resolv_conf::{
+            DirectConfigurator, RESOLV_CONF, ResolvconfToolConfigurator, is_resolvconf_managed, resolv_conf_path,
+            resolvconf_flavor,
+        },
+    },
+};
+
+struct NoopConfigurator;
+
+#[async_trait]
+impl ResolverConfigurator for NoopConfigurator {
+    async fn configure(&self, _config: &ResolverConfig) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn cleanup(&self, _config: &ResolverConfig) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// There is no resolver daemon on the BSDs: resolv.conf is either managed by resolvconf(8)
+/// or replaced directly, which is also the fallback for the `auto` and `resolved` backends.
+/// The `auto` backend uses resolvconf(8) when it has generated the current resolv.conf.
+pub fn new_resolver_configurator<S>(
+    device: S,
+    params: &TunnelParams,
+) -> anyhow::Result<Box<dyn ResolverConfigurator + Send + Sync>>
+where
+    S: AsRef<str>,
+{
+    let device = device.as_ref().to_owned();
+
+    let resolvconf_managed = || {
+        resolvconf_flavor().is_some()
+            && std::fs::read_to_string(RESOLV_CONF).is_ok_and(|conf| is_resolvconf_managed(&conf))
+    };
+
+    match params.dns_backend {
+        DnsBackend::ResolvConf => Ok(Box::new(ResolvconfToolConfigurator::new(&device))),
+        DnsBackend::Auto if resolvconf_managed() => Ok(Box::new(ResolvconfToolConfigurator::new(&device))),
+        DnsBackend::None => Ok(Box::new(NoopConfigurator)),
+        DnsBackend::Print => Ok(Box::new(PrintConfigurator)),
+        _ => Ok(Box::new(
+            DirectConfigurator::new(resolv_conf_path(params)).with_profile(params.profile_key()),
+        )),
+    }
+}
+
+/// DNS backend selected by the parameters, the detected one for `auto`.
+pub fn dns_backend_name(params: &TunnelParams) -> String {
+    let resolvconf_managed = resolvconf_flavor().is_some()
+        && std::fs::read_to_string(RESOLV_CONF).is_ok_and(|conf| is_resolvconf_managed(&conf));
+
+    match params.dns_backend {
+        DnsBackend::Auto if resolvconf_managed => "auto: resolvconf".to_owned(),
+        DnsBackend::ResolvConf | DnsBackend::None | DnsBackend::Print => params.dns_backend.to_string(),
+        backend => format!("{backend}: {}", resolv_conf_path(params).display()),
+    }
+}
+
+/// resolv.conf has no per-domain name servers, split DNS is not available.
+pub fn effective_dns_mode(params: &TunnelParams) -> DnsMode {
+    if params.dns_mode != DnsMode::Full {
+        warn!("Split DNS is not supported on this platform, all DNS queries will be sent to the tunnel resolvers");
+    }
+    DnsMode::Full
+}
+
+/// Restore the resolv.conf left behind by the direct DNS backend if the previous run of the profile has crashed.
+pub fn restore_stale_resolv_conf(params: &TunnelParams) {
+    let path = resolv_conf_path(params);
+    if let Err(e) = DirectConfigurator::new(&path)
+        .with_profile(params.profile_key())
+        .restore_stale()
+    {
+        warn!("Unable to restore {}: {}", path.display(), e);
+    }
+}
@@ -0,0 +1,367 @@
+use std::{
+    net::Ipv4Addr,
+    process::Stdio,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use anyhow::{Context, anyhow};
+use async_trait::async_trait;
+use ipnet::{Ipv4Net, Ipv6Net};
+use once_cell::sync::Lazy;
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    sync::watch,
+};
+use tracing::{debug, trace, warn};
+
+use crate::{
+    platform::{DefaultGateway, LocalNetwork, NetworkInterface},
+    util,
+};
+
+static ONLINE_STATE: AtomicBool = AtomicBool::new(true);
+static NETWORK_CHANGES: Lazy<watch::Sender<u64>> = Lazy::new(|| watch::Sender::new(0));
+
+const ONLINE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+fn parse_default_route(output: &str, exclude_device: &str) -> Option<(Ipv4Addr, String)> {
+    let mut address = None;
+    let mut device = None;
+
+    for line in output.lines() {
+        match line.trim().split_once(':') {
+            Some(("gateway", value)) => address = value.trim().parse().ok(),
+            Some(("interface", value)) => device = Some(value.trim()),
+            _ => {}
+        }
+    }
+
+    match (address, device) {
+        (Some(address), Some(device)) if device != exclude_device => Some((address, device.to_owned())),
+        _ => None,
+    }
+}
+
+fn parse_arp_mac(output: &str) -> Option<String> {
+    let mut parts = output.split_whitespace();
+    while let Some(part) = parts.next() {
+        if part == "at" {
+            return parts
+                .next()
+                .filter(|mac| mac.contains(':'))
+                .map(|mac| mac.to_lowercase());
+        }
+    }
+    None
+}
+
+fn parse_ifconfig_address(output: &str) -> Option<Ipv4Addr> {
+    let mut parts = output.split_whitespace();
+    while let Some(part) = parts.next() {
+        if part == "inet" {
+            return parts.next().and_then(|s| s.parse().ok());
+        }
+    }
+    None
+}
+
+// Interface blocks of `ifconfig -a` start at the first column, the netmask is printed in hex.
+fn parse_ifconfig_networks(output: &str, exclude_device: &str) -> Vec<LocalNetwork> {
+    let mut device = "";
+    let mut result = Vec::new();
+
+    for line in output.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            device = line.split_once(':').map(|(name, _)| name).unwrap_or_default();
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        if parts.next() != Some("inet") || device == exclude_device || device.starts_with("lo") {
+            continue;
+        }
+
+        let Some(address) = parts.next().and_then(|s| s.parse::<Ipv4Addr>().ok()) else {
+            continue;
+        };
+
+        let prefix_len = parts
+            .skip_while(|&part| part != "netmask")
+            .nth(1)
+            .and_then(|mask| u32::from_str_radix(mask.trim_start_matches("0x"), 16).ok())
+            .map_or(32, |mask| mask.leading_ones() as u8);
+
+        if let Ok(network) = Ipv4Net::new(address, prefix_len) {
+            result.push(LocalNetwork {
+                device: device.to_owned(),
+                network: network.trunc(),
+            });
+        }
+    }
+
+    result
+}
+
+// scutil prints one block per resolver, the block of the excluded interface is skipped.
+#[cfg(target_os = "macos")]
+fn parse_scutil_domains(output: &str, exclude_device: &str) -> Vec<String> {
+    let exclude = format!("({exclude_device})");
+    let mut result = Vec::new();
+
+    for block in output.split("resolver #").skip(1) {
+        if block
+            .lines()
+            .any(|line| line.trim().starts_with("if_index") && line.contains(&exclude))
+        {
+            continue;
+        }
+
+        for line in block.lines() {
+            if let Some((key, value)) = line.split_once(':') {
+                let domain = value.trim().to_lowercase();
+                if key.trim().starts_with("search domain") && !result.contains(&domain) {
+                    result.push(domain);
+                }
+            }
+        }
+    }
+
+    result
+}
+
+#[derive(Default)]
+pub struct BsdNetworkInterface;
+
+impl BsdNetworkInterface {
+    pub fn new() -> Self {
+        Self
+    }
+
+    async fn monitor_route_changes() -> anyhow::Result<()> {
+        let mut child = tokio::process::Command::new("route")
+            .args(["-n", "monitor"])
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let stdout = child.stdout.take().context("No stdout")?;
+        let mut lines = BufReader::new(stdout).lines();
+
+        while let Some(line) = lines.next_line().await? {
+            // every routing socket message starts with a "got message of size" line
+            if line.starts_with("got message") {
+                trace!("Network change: {}", line);
+                NETWORK_CHANGES.send_modify(|v| *v = v.wrapping_add(1));
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn is_default_route_present() -> bool {
+        util::run_command("route", ["-n", "get", "default"])
+            .await
+            .is_ok_and(|output| parse_default_route(&output, "").is_some())
+    }
+}
+
+#[async_trait]
+impl NetworkInterface for BsdNetworkInterface {
+    async fn start_network_state_monitoring(&self) -> anyhow::Result<()> {
+        tokio::spawn(async move {
+            if let Err(e) = Self::monitor_route_changes().await {
+                warn!("Unable to monitor route changes: {}", e);
+            }
+        });
+
+        // there is no NetworkManager, the presence of the default route is used as an online indicator
+        tokio::spawn(async move {
+            loop {
+                let online = Self::is_default_route_present().await;
+                if ONLINE_STATE.swap(online, Ordering::SeqCst) != online {
+                    debug!("Network online state changed to {}", online);
+                }
+                tokio::time::sleep(ONLINE_POLL_INTERVAL).await;
+            }
+        });
+
+        Ok(())
+    }
+
+    async fn get_default_ip(&self) -> anyhow::Result<Ipv4Addr> {
+        let default_route = util::run_command("route", ["-n", "get", "default"]).await?;
+        let (_, device) = parse_default_route(&default_route, "").context(i18n::tr!("error-cannot-determine-ip"))?;
+
+        let output = util::run_command("ifconfig", [device.as_str(), "inet"]).await?;
+        parse_ifconfig_address(&output).ok_or_else(|| anyhow!(i18n::tr!("error-cannot-determine-ip")))
+    }
+
+    async fn get_default_gateway(&self, exclude_device: &str) -> anyhow::Result<DefaultGateway> {
+        let default_route = util::run_command("route", ["-n", "get", "default"]).await?;
+        let (address, device) = parse_default_route(&default_route, exclude_device).context("No default gateway")?;
+
+        let arp = util::run_command("arp", ["-n", &address.to_string()])
+            .await
+            .unwrap_or_default();
+
+        let local_address = util::run_command("ifconfig", [device.as_str(), "inet"])
+            .await
+            .ok()
+            .and_then(|output| parse_ifconfig_address(&output));
+
+        Ok(DefaultGateway {
+            address,
+            device,
+            mac: parse_arp_mac(&arp),
+            local_address,
+        })
+    }
+
+    #[cfg(target_os = "macos")]
+    async fn get_search_domains(&self, exclude_device: &str) -> anyhow::Result<Vec<String>> {
+        let output = util::run_command("scutil", ["--dns"]).await?;
+        Ok(parse_scutil_domains(&output, exclude_device))
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    async fn get_search_domains(&self, _exclude_device: &str) -> anyhow::Result<Vec<String>> {
+        let conf = tokio::fs::read_to_string(crate::platform::resolv_conf::RESOLV_CONF).await?;
+        Ok(crate::platform::resolv_conf::parse_search_domains(&conf))
+    }
+
+    async fn get_local_networks(&self, exclude_device: &str) -> anyhow::Result<Vec<LocalNetwork>> {
+        let output = util::run_command("ifconfig", ["-a"]).await?;
+        Ok(parse_ifconfig_networks(&output, exclude_device))
+    }
+
+    // utun devices are destroyed by the kernel when the owning file descriptor is closed.
+    #[cfg(target_os = "macos")]
+    async fn delete_device(&self, _device_name: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    // cloned tun interfaces may outlive the file descriptor on the BSDs
+    #[cfg(not(target_os = "macos"))]
+    async fn delete_device(&self, device_name: &str) -> anyhow::Result<()> {
+        util::run_command("ifconfig", [device_name, "destroy"]).await?;
+        Ok(())
+    }
+
+    async fn configure_device(&self, _device_name: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn replace_ip_address(
+        &self,
+        device_name: &str,
+        old_address: Ipv4Net,
+        new_address: Ipv4Net,
+    ) -> anyhow::Result<()> {
+        let old = old_address.addr().to_string();
+        let new = new_address.addr().to_string();
+        let netmask = new_address.netmask().to_string();
+
+        util::run_command("ifconfig", [device_name, "inet", &old, "-alias"]).await?;
+        util::run_command(
+            "ifconfig",
+            [device_name, "inet", &new, &new, "netmask", &netmask, "alias"],
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn add_ipv6_address(&self, device_name: &str, address: Ipv6Net) -> anyhow::Result<()> {
+        let prefix_len = address.prefix_len().to_string();
+        util::run_command(
+            "ifconfig",
+            [
+                device_name,
+                "inet6",
+                &address.addr().to_string(),
+                "prefixlen",
+                &prefix_len,
+                "alias",
+            ],
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn set_mtu(&self, device_name: &str, mtu: u16) -> anyhow::Result<()> {
+        util::run_command("ifconfig", [device_name, "mtu", &mtu.to_string()]).await?;
+        Ok(())
+    }
+
+    async fn update_connectivity(&self) {}
+
+    fn is_online(&self) -> bool {
+        ONLINE_STATE.load(Ordering::SeqCst)
+    }
+
+    fn network_changes(&self) -> watch::Receiver<u64> {
+        NETWORK_CHANGES.subscribe()
+    }
+
+    fn poll_online(&self) {
+        tokio::spawn(async move {
+            let online = Self::is_default_route_present().await;
+            debug!("Acquired network state via polling: {}", online);
+            ONLINE_STATE.store(online, Ordering::SeqCst);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_default_route() {
+        let output = "   route to: default\ndestination: default\n       mask: default\n    gateway: 192.168.1.1\n  interface: en0\n      flags: <UP,GATEWAY,DONE,STATIC,PRCLONING>\n";
+        assert_eq!(
+            parse_default_route(output, "utun4"),
+            Some(("192.168.1.1".parse().unwrap(), "en0".to_owned()))
+        );
+        assert_eq!(parse_default_route(output, "en0"), None);
+    }
+
+    #[test]
+    fn test_parse_arp_mac() {
+        let output = "? (192.168.1.1) at A4:91:B1:0:1:2 on en0 ifscope [ethernet]";
+        assert_eq!(parse_arp_mac(output), Some("a4:91:b1:0:1:2".to_owned()));
+        assert_eq!(
+            parse_arp_mac("? (192.168.1.1) at (incomplete) on en0 ifscope [ethernet]"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_ifconfig_address() {
+        let output = "em0: flags=8843<UP,BROADCAST,RUNNING,SIMPLEX,MULTICAST> metric 0 mtu 1500\n\tinet 192.168.1.10 netmask 0xffffff00 broadcast 192.168.1.255\n";
+        assert_eq!(parse_ifconfig_address(output), Some("192.168.1.10".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_parse_ifconfig_networks() {
+        let output = "lo0: flags=8049<UP,LOOPBACK,RUNNING,MULTICAST> mtu 16384\n\tinet 127.0.0.1 netmask 0xff000000\n\
+                      en0: flags=8863<UP,BROADCAST,SMART,RUNNING,SIMPLEX,MULTICAST> mtu 1500\n\tether a4:91:b1:00:01:02\n\
+                      \tinet 192.168.1.10 netmask 0xffffff00 broadcast 192.168.1.255\n\
+                      utun4: flags=8051<UP,POINTOPOINT,RUNNING,MULTICAST> mtu 1400\n\tinet 10.0.0.10 --> 10.0.0.10 netmask 0xff000000\n";
+
+        assert_eq!(
+            parse_ifconfig_networks(output, "utun4"),
+            vec![LocalNetwork {
+                device: "en0".to_owned(),
+                network: "192.168.1.0/24".parse().unwrap(),
+            }]
+        );
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_parse_scutil_domains() {
+        let output = "DNS configuration\n\nresolver #1\n  search domain[0] : Home.Lan\n  nameserver[0] : 192.168.1.1\n  if_index : 6 (en0)\n\nresolver #2\n  search domain[0] : corp.example.com\n  nameserver[0] : 10.0.0.1\n  if_index : 20 (utun4)\n";
+        assert_eq!(parse_scutil_domains(output, "utun4"), vec!["home.lan".to_owned()]);
+    }
+}
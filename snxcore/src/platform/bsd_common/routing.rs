@@ -0,0 +1,236 @@
+use std::{collections::HashSet, net::Ipv4Addr};
+
+use async_trait::async_trait;
+use ipnet::{IpNet, Ipv4Net, Ipv6Net};
+use tracing::{debug, warn};
+
+use crate::{
+    model::params::TunnelParams,
+    platform::{GatewayRoute, NetworkInterface, RoutingConfigurator, RoutingPolicy, new_network_interface},
+    util,
+};
+
+// Two halves of the address space take precedence over the system default route without replacing it.
+const DEFAULT_ROUTE_HALVES: [&str; 2] = ["0.0.0.0/1", "128.0.0.0/1"];
+
+// OpenBSD route(8) only knows the short form of the interface modifier.
+const INTERFACE_FLAG: &str = if cfg!(target_os = "openbsd") {
+    "-iface"
+} else {
+    "-interface"
+};
+
+/// Routes are not kept in separate tables, the network journal reverts them after a crash.
+pub async fn restore_stale_routing(_params: &TunnelParams) {}
+
+pub struct BsdRoutingConfigurator {
+    device: String,
+}
+
+impl BsdRoutingConfigurator {
+    pub fn new<S: AsRef<str>>(device: S, _address: Ipv4Addr, metric: Option<u32>) -> Self {
+        // route(8) has no metric, routes to the same prefix are ordered by specificity only
+        if metric.is_some() {
+            warn!("Route metric is not supported on this platform, ignoring it");
+        }
+        Self {
+            device: device.as_ref().to_string(),
+        }
+    }
+
+    async fn add_route(&self, route: Ipv4Net) -> anyhow::Result<()> {
+        debug!("Adding route: {} via {}", route, self.device);
+        util::run_command(
+            "route",
+            ["-n", "add", "-net", &route.to_string(), INTERFACE_FLAG, &self.device],
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn original_gateway(&self) -> anyhow::Result<Ipv4Addr> {
+        Ok(new_network_interface().get_default_gateway(&self.device).await?.address)
+    }
+}
+
+#[async_trait]
+impl RoutingConfigurator for BsdRoutingConfigurator {
+    async fn add_routes(&self, routes: &[Ipv4Net], ignore_routes: &[Ipv4Net]) -> anyhow::Result<()> {
+        let routes = routes.iter().collect::<HashSet<_>>();
+        debug!("Routes to add: {:?}", routes);
+
+        for route in routes {
+            if ignore_routes.iter().any(|ignore| ignore == route) {
+                debug!("Ignoring route: {}", route);
+                continue;
+            }
+            let _ = self.add_route(*route).await;
+        }
+
+        Ok(())
+    }
+
+    async fn add_ipv6_routes(&self, routes: &[Ipv6Net]) -> anyhow::Result<()> {
+        let routes = routes.iter().collect::<HashSet<_>>();
+        debug!("IPv6 routes to add: {:?}", routes);
+
+        for route in routes {
+            debug!("Adding route: {} via {}", route, self.device);
+            let _ = util::run_command(
+                "route",
+                [
+                    "-n",
+                    "add",
+                    "-inet6",
+                    "-net",
+                    &route.to_string(),
+                    INTERFACE_FLAG,
+                    &self.device,
+                ],
+            )
+            .await;
+        }
+
+        Ok(())
+    }
+
+    async fn remove_routes(&self, routes: &[IpNet]) -> anyhow::Result<()> {
+        for route in routes {
+            debug!("Removing route: {} via {}", route, self.device);
+            let family = if route.addr().is_ipv4() { "-inet" } else { "-inet6" };
+            let _ = util::run_command(
+                "route",
+                [
+                    "-n",
+                    "delete",
+                    family,
+                    "-net",
+                    &route.to_string(),
+                    INTERFACE_FLAG,
+                    &self.device,
+                ],
+            )
+            .await;
+        }
+        Ok(())
+    }
+
+    async fn setup_default_route(&self, _destination: Ipv4Addr, _policy: &RoutingPolicy) -> anyhow::Result<()> {
+        debug!("Setting up default route through {}", self.device);
+
+        // the VPN server itself stays reachable through the pinned gateway route
+        for half in DEFAULT_ROUTE_HALVES {
+            util::run_command("route", ["-n", "add", "-net", half, INTERFACE_FLAG, &self.device]).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn check_default_route(&self, _destination: Ipv4Addr, _policy: &RoutingPolicy) -> anyhow::Result<bool> {
+        for half in DEFAULT_ROUTE_HALVES {
+            let output = util::run_command("route", ["-n", "get", "-net", half]).await?;
+            if !is_route_via_device(&output, &self.device) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    async fn setup_keepalive_route(
+        &self,
+        _destination: Ipv4Addr,
+        _with_table: bool,
+        _policy: &RoutingPolicy,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn remove_default_route(&self, _destination: Ipv4Addr, _policy: &RoutingPolicy) -> anyhow::Result<()> {
+        for half in DEFAULT_ROUTE_HALVES {
+            let _ = util::run_command("route", ["-n", "delete", "-net", half, INTERFACE_FLAG, &self.device]).await;
+        }
+
+        Ok(())
+    }
+
+    async fn remove_keepalive_route(&self, _destination: Ipv4Addr, _policy: &RoutingPolicy) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    // Excluded prefixes are more specific than the default route halves and go through the original gateway.
+    async fn setup_excluded_routes(&self, routes: &[Ipv4Net]) -> anyhow::Result<()> {
+        if routes.is_empty() {
+            return Ok(());
+        }
+
+        let gateway = self.original_gateway().await?.to_string();
+
+        for route in routes {
+            debug!("Excluding route from the tunnel: {}", route);
+            util::run_command("route", ["-n", "add", "-net", &route.to_string(), &gateway]).await?;
+        }
+        Ok(())
+    }
+
+    async fn remove_excluded_routes(&self, routes: &[Ipv4Net]) -> anyhow::Result<()> {
+        for route in routes {
+            let _ = util::run_command("route", ["-n", "delete", "-net", &route.to_string()]).await;
+        }
+        Ok(())
+    }
+
+    async fn find_gateway_route(&self, destination: Ipv4Addr) -> anyhow::Result<GatewayRoute> {
+        let default = new_network_interface().get_default_gateway(&self.device).await?;
+
+        Ok(GatewayRoute {
+            destination,
+            gateway: Some(default.address),
+            device: default.device,
+        })
+    }
+
+    async fn add_gateway_route(&self, route: &GatewayRoute) -> anyhow::Result<()> {
+        debug!("Pinning gateway route: {:?}", route);
+        let destination = route.destination.to_string();
+
+        match route.gateway {
+            Some(gateway) => {
+                util::run_command("route", ["-n", "add", "-host", &destination, &gateway.to_string()]).await?;
+            }
+            None => {
+                util::run_command(
+                    "route",
+                    ["-n", "add", "-host", &destination, INTERFACE_FLAG, &route.device],
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn remove_gateway_route(&self, route: &GatewayRoute) -> anyhow::Result<()> {
+        debug!("Removing gateway route: {:?}", route);
+        util::run_command("route", ["-n", "delete", "-host", &route.destination.to_string()]).await?;
+        Ok(())
+    }
+}
+
+fn is_route_via_device(output: &str, device: &str) -> bool {
+    output
+        .lines()
+        .filter_map(|line| line.trim().split_once(':'))
+        .any(|(key, value)| key == "interface" && value.trim() == device)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_via_device() {
+        let output = "   route to: 0.0.0.0\ndestination: 0.0.0.0\n       mask: 128.0.0.0\n  interface: utun4\n";
+        assert!(is_route_via_device(output, "utun4"));
+        assert!(!is_route_via_device(output, "utun5"));
+    }
+}
@@ -1,31 +1,57 @@
-use std::{
-    fs,
-    net::SocketAddr,
-    os::fd::{AsFd, AsRawFd, OwnedFd},
-    time::Duration,
-};
+use std::{fs, net::SocketAddr, os::fd::AsRawFd, time::Duration};
 
 use anyhow::anyhow;
+pub use app_route::NftAppRouting as AppRoutingImpl;
 use cached::proc_macro::cached;
+pub use dns_redirect::NftDnsRedirect as DnsRedirectImpl;
+pub use forwarding::NftForwarding as ForwardingImpl;
+pub use ipv6_block::LinuxIpv6Blocker as Ipv6BlockerImpl;
+#[cfg(feature = "dbus")]
 pub use keychain::SecretServiceKeychain as KeychainImpl;
+pub use killswitch::NftKillSwitch as KillSwitchImpl;
+pub use mss_clamp::NftMssClamp as MssClampImpl;
 pub use net::LinuxNetworkInterface as NetworkInterfaceImpl;
-use nix::{
-    fcntl::{self, FcntlArg, OFlag},
-    sys::stat::Mode,
-    unistd,
-};
-pub use resolver::new_resolver_configurator;
-pub use routing::LinuxRoutingConfigurator as RoutingImpl;
-use tokio::net::UdpSocket;
+pub use netns::LinuxNetworkNamespace as NetworkNamespaceImpl;
+pub use permissions::check_permissions;
+#[cfg(feature = "dbus")]
+pub use polkit::PolkitProcessAuthority as AuthorityImpl;
+pub use privileges::{check_privilege_drop, drop_privileges};
+pub use resolver::{dns_backend_name, effective_dns_mode, new_resolver_configurator, restore_stale_resolv_conf};
+pub use routing::{LinuxRoutingConfigurator as RoutingImpl, restore_stale_routing};
+use tokio::net::{TcpSocket, UdpSocket};
 use tracing::debug;
+#[cfg(not(feature = "dbus"))]
+use tracing::warn;
 use uuid::Uuid;
 pub use xfrm::XfrmConfigurator as IpsecImpl;
 
-use crate::platform::{PlatformFeatures, UdpEncap, UdpSocketExt};
+#[cfg(not(feature = "dbus"))]
+pub use crate::platform::unsupported::{UnsupportedAuthority as AuthorityImpl, UnsupportedKeychain as KeychainImpl};
+use crate::{
+    model::params::TunnelParams,
+    platform::{PlatformFeatures, TcpSocketExt, UdpEncap, UdpSocketExt},
+};
 
+mod app_route;
+mod dns_redirect;
+mod forwarding;
+mod ipv6_block;
+#[cfg(feature = "dbus")]
 mod keychain;
+mod killswitch;
+mod mss_clamp;
 pub mod net;
+pub(crate) mod netlink;
+mod netns;
+mod nft;
+#[cfg(feature = "dbus")]
+mod nm;
+mod permissions;
+#[cfg(feature = "dbus")]
+pub mod polkit;
+mod privileges;
 pub mod resolver;
+mod route_monitor;
 mod routing;
 pub mod xfrm;
 
@@ -46,6 +72,39 @@ pub fn init() {
     }
 }
 
+/// Select the backend for address, route and MTU changes and the NetworkManager integration.
+pub fn apply_params(params: &TunnelParams) {
+    netlink::set_use_iproute2(params.use_iproute2);
+    #[cfg(feature = "dbus")]
+    nm::set_mode(params.network_manager);
+    #[cfg(not(feature = "dbus"))]
+    if params.network_manager == crate::model::params::NetworkManagerMode::On {
+        warn!("NetworkManager integration is enabled but this build has no D-Bus support");
+    }
+    route_monitor::set_enabled(params.route_monitor);
+    routing::set_state_file(params);
+}
+
+impl TcpSocketExt for TcpSocket {
+    fn set_mark(&self, mark: u32) -> anyhow::Result<()> {
+        let mark: libc::c_int = mark as _;
+        unsafe {
+            let rc = libc::setsockopt(
+                self.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_MARK,
+                &mark as *const libc::c_int as _,
+                size_of::<libc::c_int>() as _,
+            );
+            if rc != 0 {
+                Err(anyhow!(i18n::tr!("error-so-mark-failed", code = rc)))
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl UdpSocketExt for UdpSocket {
     fn set_encap(&self, encap: UdpEncap) -> anyhow::Result<()> {
@@ -92,59 +151,6 @@ impl UdpSocketExt for UdpSocket {
     }
 }
 
-pub struct SingleInstance {
-    name: String,
-    handle: Option<OwnedFd>,
-}
-
-unsafe impl Send for SingleInstance {}
-unsafe impl Sync for SingleInstance {}
-
-impl SingleInstance {
-    pub fn new<N: AsRef<str>>(name: N) -> anyhow::Result<Self> {
-        let fd = fcntl::open(
-            name.as_ref(),
-            OFlag::O_RDWR | OFlag::O_CREAT,
-            Mode::from_bits_truncate(0o600),
-        )?;
-
-        let fl = libc::flock {
-            l_type: libc::F_WRLCK as _,
-            l_whence: libc::SEEK_SET as _,
-            l_start: 0,
-            l_len: 0,
-            l_pid: 0,
-        };
-
-        match fcntl::fcntl(fd.as_fd(), FcntlArg::F_SETLK(&fl)) {
-            Ok(_) => Ok(SingleInstance {
-                name: name.as_ref().to_owned(),
-                handle: Some(fd),
-            }),
-            Err(_) => {
-                let _ = unistd::close(fd);
-                Ok(SingleInstance {
-                    name: name.as_ref().to_owned(),
-                    handle: None,
-                })
-            }
-        }
-    }
-
-    pub fn is_single(&self) -> bool {
-        self.handle.is_some()
-    }
-}
-
-impl Drop for SingleInstance {
-    fn drop(&mut self) {
-        if let Some(handle) = self.handle.take() {
-            let _ = unistd::close(handle);
-            let _ = fs::remove_file(&self.name);
-        }
-    }
-}
-
 #[cached(result = true)]
 pub fn get_machine_uuid() -> anyhow::Result<Uuid> {
     let data = fs::read_to_string("/etc/machine-id")?;
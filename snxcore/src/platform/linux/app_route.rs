@@ -0,0 +1,244 @@
+use std::{fmt::Write, fs, io, path::Path, sync::Arc};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use i18n::tr;
+use tracing::{debug, warn};
+
+use crate::{
+    platform::{
+        AppRouteConfig, AppRouting,
+        linux::{
+            netlink::{self, NetlinkOps, RouteSpec},
+            nft::run_nft,
+        },
+    },
+    util,
+};
+
+// Separate from the shared table like the forwarding, the masquerading needs the ip family NAT hook.
+const NFT_TABLE: &str = "ip snx-rs-apps";
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// Per-application routing: the sockets of the selected cgroups and users are marked, the mark selects
+/// the tunnel routing table. The source address was chosen for the default route, so the marked traffic
+/// is masqueraded behind the tunnel address.
+pub struct NftAppRouting {
+    ops: Arc<dyn NetlinkOps>,
+}
+
+impl NftAppRouting {
+    pub fn new() -> Self {
+        Self::with_ops(netlink::new_netlink_ops())
+    }
+
+    fn with_ops(ops: Arc<dyn NetlinkOps>) -> Self {
+        Self { ops }
+    }
+}
+
+impl Default for NftAppRouting {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn unavailable(e: io::Error) -> anyhow::Error {
+    anyhow!(tr!("error-app-routing-unavailable", message = e.to_string()))
+}
+
+// nft resolves the cgroup path when the rule is loaded, the level is the depth of the path below the root.
+fn cgroup_match(path: &str) -> String {
+    let path = path.trim_matches('/');
+    format!("socket cgroupv2 level {} \"{}\"", path.split('/').count(), path)
+}
+
+fn make_ruleset<F>(config: &AppRouteConfig, cgroup_exists: F) -> String
+where
+    F: Fn(&str) -> bool,
+{
+    let mut ruleset = format!("table {NFT_TABLE}\ndelete table {NFT_TABLE}\ntable {NFT_TABLE} {{\n");
+
+    let _ = writeln!(ruleset, "    chain output {{");
+    let _ = writeln!(
+        ruleset,
+        "        type route hook output priority mangle; policy accept;"
+    );
+    for cgroup in &config.cgroups {
+        if cgroup_exists(cgroup) {
+            let _ = writeln!(
+                ruleset,
+                "        {} meta mark set {:#x}",
+                cgroup_match(cgroup),
+                config.mark
+            );
+        } else {
+            warn!(
+                "Cgroup {} does not exist, its traffic is not routed through the tunnel",
+                cgroup
+            );
+        }
+    }
+    for uid in &config.uids {
+        let _ = writeln!(ruleset, "        meta skuid {} meta mark set {:#x}", uid, config.mark);
+    }
+    let _ = writeln!(ruleset, "    }}");
+
+    let _ = writeln!(ruleset, "    chain postrouting {{");
+    let _ = writeln!(
+        ruleset,
+        "        type nat hook postrouting priority srcnat; policy accept;"
+    );
+    let _ = writeln!(
+        ruleset,
+        "        oifname \"{}\" meta mark {:#x} masquerade",
+        config.device, config.mark
+    );
+    let _ = writeln!(ruleset, "    }}");
+    let _ = writeln!(ruleset, "}}");
+
+    ruleset
+}
+
+fn mark_rule_args(action: &str, config: &AppRouteConfig) -> Vec<String> {
+    [
+        "rule",
+        action,
+        "fwmark",
+        &format!("{:#x}", config.mark),
+        "table",
+        &config.table.to_string(),
+    ]
+    .map(ToOwned::to_owned)
+    .to_vec()
+}
+
+// The local networks of the main table stay reachable for the marked applications.
+fn suppress_rule_args(action: &str, config: &AppRouteConfig) -> Vec<String> {
+    [
+        "rule",
+        action,
+        "fwmark",
+        &format!("{:#x}", config.mark),
+        "table",
+        "main",
+        "suppress_prefixlength",
+        "0",
+    ]
+    .map(ToOwned::to_owned)
+    .to_vec()
+}
+
+fn default_route(config: &AppRouteConfig) -> RouteSpec {
+    RouteSpec::new(ipnet::Ipv4Net::default().into(), &config.device).table(config.table)
+}
+
+// The replies arrive through the tunnel while the route back to their source is the default one.
+fn loosen_rp_filter(device: &str) {
+    let path = format!("/proc/sys/net/ipv4/conf/{device}/rp_filter");
+    if let Err(e) = fs::write(&path, "2") {
+        warn!("Unable to set loose reverse path filtering for {}: {}", device, e);
+    }
+}
+
+#[async_trait]
+impl AppRouting for NftAppRouting {
+    async fn enable(&self, config: &AppRouteConfig) -> anyhow::Result<()> {
+        debug!(
+            "Routing cgroups {:?} and users {:?} through {}",
+            config.cgroups, config.uids, config.device
+        );
+
+        let ruleset = make_ruleset(config, |cgroup| {
+            Path::new(CGROUP_ROOT).join(cgroup.trim_matches('/')).is_dir()
+        });
+        run_nft(&ruleset, unavailable).await?;
+
+        loosen_rp_filter(&config.device);
+
+        self.ops.add_route(&default_route(config)).await?;
+        util::run_command("ip", mark_rule_args("add", config)).await?;
+        // added last so that it is evaluated first
+        util::run_command("ip", suppress_rule_args("add", config)).await?;
+
+        Ok(())
+    }
+
+    async fn disable(&self, config: &AppRouteConfig) -> anyhow::Result<()> {
+        debug!("Disabling application routing through {}", config.device);
+
+        let _ = util::run_command("ip", suppress_rule_args("del", config)).await;
+        let _ = util::run_command("ip", mark_rule_args("del", config)).await;
+        let _ = self.ops.delete_route(&default_route(config)).await;
+
+        run_nft(&format!("table {NFT_TABLE}\ndelete table {NFT_TABLE}\n"), unavailable).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AppRouteConfig {
+        AppRouteConfig {
+            device: "snx-tun".to_owned(),
+            table: 18234,
+            mark: AppRouteConfig::MARK,
+            cgroups: vec!["/work.slice".to_owned(), "missing.slice".to_owned()],
+            uids: vec![1001],
+        }
+    }
+
+    #[test]
+    fn test_cgroup_match() {
+        assert_eq!(cgroup_match("/work.slice"), "socket cgroupv2 level 1 \"work.slice\"");
+        assert_eq!(
+            cgroup_match("user.slice/user-1000.slice/user@1000.service/work.slice"),
+            "socket cgroupv2 level 4 \"user.slice/user-1000.slice/user@1000.service/work.slice\""
+        );
+    }
+
+    #[test]
+    fn test_make_ruleset() {
+        let ruleset = make_ruleset(&config(), |cgroup| cgroup != "missing.slice");
+
+        assert!(ruleset.starts_with("table ip snx-rs-apps\ndelete table ip snx-rs-apps\n"));
+        assert!(ruleset.contains("        type route hook output priority mangle; policy accept;\n"));
+        assert!(ruleset.contains("        socket cgroupv2 level 1 \"work.slice\" meta mark set 0x736e78\n"));
+        assert!(!ruleset.contains("missing.slice"));
+        assert!(ruleset.contains("        meta skuid 1001 meta mark set 0x736e78\n"));
+        assert!(ruleset.contains("        oifname \"snx-tun\" meta mark 0x736e78 masquerade\n"));
+    }
+
+    #[test]
+    fn test_rule_args() {
+        let config = config();
+
+        assert_eq!(
+            mark_rule_args("add", &config).join(" "),
+            "rule add fwmark 0x736e78 table 18234"
+        );
+        assert_eq!(
+            suppress_rule_args("del", &config).join(" "),
+            "rule del fwmark 0x736e78 table main suppress_prefixlength 0"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_default_route() {
+        let ops = Arc::new(netlink::mock::MockNetlinkOps::default());
+        // the route of the table goes through the selected netlink backend, the rules through the ip command
+        let routing = NftAppRouting::with_ops(ops.clone());
+        let config = config();
+
+        routing.ops.add_route(&default_route(&config)).await.unwrap();
+        assert_eq!(
+            *ops.calls.lock().unwrap(),
+            ["-4 route add table 18234 0.0.0.0/0 dev snx-tun"]
+        );
+
+        routing.ops.delete_route(&default_route(&config)).await.unwrap();
+        assert!(ops.routes.lock().unwrap().is_empty());
+    }
+}
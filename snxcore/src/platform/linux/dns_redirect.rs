@@ -0,0 +1,138 @@
+use std::{fmt::Write, io};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use i18n::tr;
+use tracing::debug;
+
+use crate::platform::{
+    DnsRedirect, DnsRedirectConfig,
+    linux::nft::{self, NFT_TABLE},
+};
+
+// NAT chains of the inet family need kernel 5.2 or newer.
+const OUTPUT_CHAIN: &str = "dns-redirect-output";
+const PREROUTING_CHAIN: &str = "dns-redirect-prerouting";
+
+/// DNS redirection in its own chains of the shared table: the queries on port 53 to any other server
+/// are translated to the tunnel DNS server. The local stub resolvers on the loopback are left alone,
+/// their upstream queries are redirected instead.
+#[derive(Default)]
+pub struct NftDnsRedirect;
+
+impl NftDnsRedirect {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn unavailable(e: io::Error) -> anyhow::Error {
+    anyhow!(tr!("error-dns-redirect-unavailable", message = e.to_string()))
+}
+
+fn make_ruleset(config: &DnsRedirectConfig) -> String {
+    let mut ruleset = nft::begin_ruleset(&[OUTPUT_CHAIN, PREROUTING_CHAIN], &[]);
+
+    let _ = writeln!(ruleset, "table {NFT_TABLE} {{");
+
+    let _ = writeln!(ruleset, "    chain {OUTPUT_CHAIN} {{");
+    let _ = writeln!(ruleset, "        type nat hook output priority dstnat; policy accept;");
+    if let Some(server) = config.server {
+        let _ = writeln!(
+            ruleset,
+            "        ip daddr != {{ 127.0.0.0/8, {server} }} meta l4proto {{ tcp, udp }} th dport 53 dnat ip to {server}"
+        );
+    }
+    if let Some(server) = config.server_v6 {
+        let _ = writeln!(
+            ruleset,
+            "        ip6 daddr != {{ ::1, {server} }} meta l4proto {{ tcp, udp }} th dport 53 dnat ip6 to {server}"
+        );
+    }
+    let _ = writeln!(ruleset, "    }}");
+
+    // the forwarded LAN hosts are masqueraded behind the tunnel address afterwards
+    if let (Some(source), Some(server)) = (config.forward_source, config.server) {
+        let _ = writeln!(ruleset, "    chain {PREROUTING_CHAIN} {{");
+        let _ = writeln!(
+            ruleset,
+            "        type nat hook prerouting priority dstnat; policy accept;"
+        );
+        let _ = writeln!(
+            ruleset,
+            "        ip saddr {source} ip daddr != {server} meta l4proto {{ tcp, udp }} th dport 53 dnat ip to {server}"
+        );
+        let _ = writeln!(ruleset, "    }}");
+    }
+
+    let _ = writeln!(ruleset, "}}");
+
+    ruleset
+}
+
+#[async_trait]
+impl DnsRedirect for NftDnsRedirect {
+    async fn enable(&self, config: &DnsRedirectConfig) -> anyhow::Result<()> {
+        debug!(
+            "Redirecting DNS queries to {:?} {:?} through {}",
+            config.server, config.server_v6, config.device
+        );
+        nft::run_nft(&make_ruleset(config), unavailable).await
+    }
+
+    async fn disable(&self) -> anyhow::Result<()> {
+        debug!("Disabling DNS redirection");
+        nft::run_nft(&nft::begin_ruleset(&[OUTPUT_CHAIN, PREROUTING_CHAIN], &[]), unavailable).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> DnsRedirectConfig {
+        DnsRedirectConfig {
+            device: "snx-tun".to_owned(),
+            server: Some("10.0.0.1".parse().unwrap()),
+            server_v6: None,
+            forward_source: None,
+        }
+    }
+
+    #[test]
+    fn test_make_ruleset() {
+        let ruleset = make_ruleset(&config());
+
+        assert!(ruleset.starts_with(
+            "add table inet snx-rs\n\
+             add chain inet snx-rs dns-redirect-output\n\
+             delete chain inet snx-rs dns-redirect-output\n\
+             add chain inet snx-rs dns-redirect-prerouting\n\
+             delete chain inet snx-rs dns-redirect-prerouting\n"
+        ));
+        assert!(ruleset.contains("        type nat hook output priority dstnat; policy accept;\n"));
+        assert!(ruleset.contains(
+            "        ip daddr != { 127.0.0.0/8, 10.0.0.1 } meta l4proto { tcp, udp } th dport 53 dnat ip to 10.0.0.1\n"
+        ));
+        assert!(!ruleset.contains("ip6"));
+        assert!(!ruleset.contains("chain dns-redirect-prerouting {"));
+    }
+
+    #[test]
+    fn test_make_ruleset_forwarding() {
+        let config = DnsRedirectConfig {
+            server_v6: Some("fd00::1".parse().unwrap()),
+            forward_source: Some("192.168.50.0/24".parse().unwrap()),
+            ..config()
+        };
+        let ruleset = make_ruleset(&config);
+
+        assert!(ruleset.contains(
+            "        ip6 daddr != { ::1, fd00::1 } meta l4proto { tcp, udp } th dport 53 dnat ip6 to fd00::1\n"
+        ));
+        assert!(ruleset.contains("        type nat hook prerouting priority dstnat; policy accept;\n"));
+        assert!(ruleset.contains(
+            "        ip saddr 192.168.50.0/24 ip daddr != 10.0.0.1 meta l4proto { tcp, udp } th dport 53 dnat ip to 10.0.0.1\n"
+        ));
+    }
+}
@@ -0,0 +1,120 @@
+use std::{fmt::Write, fs, io};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use i18n::tr;
+use tracing::{debug, warn};
+
+use crate::platform::{Forwarding, ForwardingConfig, linux::nft::run_nft};
+
+// Separate from the shared table, the masquerading needs the ip family NAT hook of the older kernels.
+const NFT_TABLE: &str = "ip snx-rs-forward";
+
+const IP_FORWARD: &str = "/proc/sys/net/ipv4/ip_forward";
+
+// Present only when the forwarding was disabled before the tunnel enabled it.
+const IP_FORWARD_STATE_FILE: &str = "/var/run/snx-rs.forwarding";
+
+/// LAN forwarding: IPv4 forwarding is enabled and the source prefix is masqueraded behind the tunnel device.
+#[derive(Default)]
+pub struct NftForwarding;
+
+impl NftForwarding {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn unavailable(e: io::Error) -> anyhow::Error {
+    anyhow!(tr!("error-forwarding-unavailable", message = e.to_string()))
+}
+
+// The gateway only accepts the office-mode address. The MSS of the forwarded TCP flows is clamped
+// by the MSS clamping which is enabled automatically with the forwarding.
+fn make_ruleset(config: &ForwardingConfig) -> String {
+    let mut ruleset = format!("table {NFT_TABLE}\ndelete table {NFT_TABLE}\ntable {NFT_TABLE} {{\n");
+
+    let _ = writeln!(ruleset, "    chain postrouting {{");
+    let _ = writeln!(
+        ruleset,
+        "        type nat hook postrouting priority srcnat; policy accept;"
+    );
+    let _ = writeln!(
+        ruleset,
+        "        oifname \"{}\" ip saddr {} masquerade",
+        config.device, config.source
+    );
+    let _ = writeln!(ruleset, "    }}");
+    let _ = writeln!(ruleset, "}}");
+
+    ruleset
+}
+
+fn enable_ip_forward() -> anyhow::Result<()> {
+    let current = fs::read_to_string(IP_FORWARD)?;
+    if current.trim() != "1" {
+        debug!("Enabling IPv4 forwarding");
+        crate::util::write_private_file(IP_FORWARD_STATE_FILE, current.trim())?;
+        fs::write(IP_FORWARD, "1")?;
+    }
+    Ok(())
+}
+
+fn restore_ip_forward() {
+    let Ok(previous) = fs::read_to_string(IP_FORWARD_STATE_FILE) else {
+        return;
+    };
+
+    debug!("Restoring IPv4 forwarding to {}", previous);
+    if let Err(e) = fs::write(IP_FORWARD, previous.trim()) {
+        warn!("Unable to restore IPv4 forwarding: {}", e);
+    }
+    let _ = fs::remove_file(IP_FORWARD_STATE_FILE);
+}
+
+#[async_trait]
+impl Forwarding for NftForwarding {
+    async fn enable(&self, config: &ForwardingConfig) -> anyhow::Result<()> {
+        debug!("Enabling forwarding from {} through {}", config.source, config.device);
+        run_nft(&make_ruleset(config), unavailable).await?;
+        enable_ip_forward()
+    }
+
+    async fn disable(&self, config: &ForwardingConfig) -> anyhow::Result<()> {
+        debug!("Disabling forwarding through {}", config.device);
+        restore_ip_forward();
+        run_nft(&format!("table {NFT_TABLE}\ndelete table {NFT_TABLE}\n"), unavailable).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forwarding_config() {
+        let address = "10.0.0.10".parse().unwrap();
+        let routes: [ipnet::Ipv4Net; 2] = ["10.0.0.0/8".parse().unwrap(), "172.16.0.0/12".parse().unwrap()];
+
+        let config = ForwardingConfig::new("snx-tun", "192.168.1.7/24".parse().unwrap(), address, &routes).unwrap();
+        assert_eq!(config.source, "192.168.1.0/24".parse().unwrap());
+
+        assert!(ForwardingConfig::new("snx-tun", "10.0.0.0/24".parse().unwrap(), address, &[]).is_err());
+        assert!(ForwardingConfig::new("snx-tun", "172.16.1.0/24".parse().unwrap(), address, &routes).is_err());
+        assert!(ForwardingConfig::new("snx-tun", "172.0.0.0/8".parse().unwrap(), address, &routes).is_err());
+    }
+
+    #[test]
+    fn test_make_ruleset() {
+        let config = ForwardingConfig {
+            device: "snx-tun".to_owned(),
+            source: "192.168.1.0/24".parse().unwrap(),
+        };
+
+        let ruleset = make_ruleset(&config);
+
+        assert!(ruleset.starts_with("table ip snx-rs-forward\ndelete table ip snx-rs-forward\n"));
+        assert!(ruleset.contains("        oifname \"snx-tun\" ip saddr 192.168.1.0/24 masquerade\n"));
+        assert!(!ruleset.contains("maxseg"));
+    }
+}
@@ -0,0 +1,153 @@
+use std::fs;
+
+use async_trait::async_trait;
+use tracing::{debug, info, warn};
+
+use crate::{
+    model::params::Ipv6BlockMode,
+    platform::{Ipv6BlockConfig, Ipv6Blocker},
+    util,
+};
+
+const IPV6_CONF: &str = "/proc/sys/net/ipv6/conf";
+
+// Preferred over the default routes from DHCPv6 and router advertisements, the more specific
+// on-link prefixes of the local network still work.
+const UNREACHABLE_METRIC: &str = "1";
+
+/// IPv6 leak prevention for IPv4-only tunnels: an unreachable default route or disabled IPv6 on the other interfaces.
+#[derive(Default)]
+pub struct LinuxIpv6Blocker;
+
+impl LinuxIpv6Blocker {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn route_args(action: &str) -> [&str; 7] {
+    [
+        "-6",
+        "route",
+        action,
+        "unreachable",
+        "default",
+        "metric",
+        UNREACHABLE_METRIC,
+    ]
+}
+
+fn disable_ipv6_path(interface: &str) -> String {
+    format!("{IPV6_CONF}/{interface}/disable_ipv6")
+}
+
+// The interfaces which currently pass IPv6, except the loopback, the tunnel device and the template entries.
+fn select_interfaces<I>(entries: I, device: &str) -> Vec<String>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let mut interfaces = entries
+        .into_iter()
+        .filter(|(name, disabled)| !["all", "default", "lo", device].contains(&name.as_str()) && disabled.trim() == "0")
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+    interfaces.sort();
+    interfaces
+}
+
+fn read_interfaces() -> anyhow::Result<Vec<(String, String)>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(IPV6_CONF)? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if let Ok(disabled) = fs::read_to_string(disable_ipv6_path(&name)) {
+            entries.push((name, disabled));
+        }
+    }
+    Ok(entries)
+}
+
+#[async_trait]
+impl Ipv6Blocker for LinuxIpv6Blocker {
+    async fn prepare(&self, mode: Ipv6BlockMode, device: &str) -> anyhow::Result<Ipv6BlockConfig> {
+        let interfaces = match mode {
+            Ipv6BlockMode::Sysctl => select_interfaces(read_interfaces()?, device),
+            Ipv6BlockMode::Off | Ipv6BlockMode::Route => Vec::new(),
+        };
+
+        Ok(Ipv6BlockConfig {
+            mode,
+            device: device.to_owned(),
+            interfaces,
+        })
+    }
+
+    async fn block(&self, config: &Ipv6BlockConfig) -> anyhow::Result<()> {
+        match config.mode {
+            Ipv6BlockMode::Off => {}
+            Ipv6BlockMode::Route => {
+                util::run_command("ip", route_args("add")).await?;
+                info!(
+                    "IPv6 is blocked while connected: added an unreachable IPv6 default route with metric {}",
+                    UNREACHABLE_METRIC
+                );
+            }
+            Ipv6BlockMode::Sysctl => {
+                for interface in &config.interfaces {
+                    debug!("Disabling IPv6 on {}", interface);
+                    fs::write(disable_ipv6_path(interface), "1")?;
+                }
+                info!(
+                    "IPv6 is blocked while connected: disabled IPv6 on {}",
+                    config.interfaces.join(", ")
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn unblock(&self, config: &Ipv6BlockConfig) -> anyhow::Result<()> {
+        match config.mode {
+            Ipv6BlockMode::Off => {}
+            Ipv6BlockMode::Route => {
+                util::run_command("ip", route_args("del")).await?;
+                info!("IPv6 is unblocked: removed the unreachable IPv6 default route");
+            }
+            Ipv6BlockMode::Sysctl => {
+                // an interface may have been removed in the meantime
+                for interface in &config.interfaces {
+                    if let Err(e) = fs::write(disable_ipv6_path(interface), "0") {
+                        warn!("Unable to re-enable IPv6 on {}: {}", interface, e);
+                    }
+                }
+                info!("IPv6 is unblocked: re-enabled IPv6 on {}", config.interfaces.join(", "));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_interfaces() {
+        let entries = [
+            ("all", "0\n"),
+            ("default", "0\n"),
+            ("lo", "0\n"),
+            ("wlan0", "0\n"),
+            ("snx-tun", "0\n"),
+            ("eth0", "0\n"),
+            ("docker0", "1\n"),
+        ]
+        .map(|(name, disabled)| (name.to_owned(), disabled.to_owned()));
+
+        assert_eq!(select_interfaces(entries, "snx-tun"), vec!["eth0", "wlan0"]);
+    }
+
+    #[test]
+    fn test_route_args() {
+        assert_eq!(route_args("add").join(" "), "-6 route add unreachable default metric 1");
+    }
+}
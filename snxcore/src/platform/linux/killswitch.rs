@@ -0,0 +1,135 @@
+use std::fmt::Write;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use i18n::tr;
+use ipnet::IpNet;
+use tracing::debug;
+
+use crate::platform::{
+    KillSwitch, KillSwitchConfig,
+    linux::nft::{self, NFT_TABLE},
+};
+
+const SETS: &[(&str, &str)] = &[
+    ("allowed4", "ipv4_addr"),
+    ("allowed6", "ipv6_addr"),
+    ("tunneled4", "ipv4_addr"),
+    ("tunneled6", "ipv6_addr"),
+];
+
+// Traffic which must pass even if it matches a tunneled prefix: DHCP, link-local and neighbor discovery.
+const ESSENTIAL_RULES: &[&str] = &[
+    "udp dport { 67, 547 } accept",
+    "ip daddr 169.254.0.0/16 accept",
+    "ip6 daddr fe80::/10 accept",
+    "icmpv6 type { nd-router-solicit, nd-neighbor-solicit, nd-neighbor-advert } accept",
+];
+
+/// Kill switch based on an nftables output chain which drops the tunneled prefixes on other interfaces.
+#[derive(Default)]
+pub struct NftKillSwitch;
+
+impl NftKillSwitch {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn write_set(ruleset: &mut String, name: &str, addr_type: &str, prefixes: &[&IpNet]) {
+    let _ = writeln!(ruleset, "    set {name} {{");
+    let _ = writeln!(ruleset, "        type {addr_type}");
+    let _ = writeln!(ruleset, "        flags interval");
+    let _ = writeln!(ruleset, "        auto-merge");
+    if !prefixes.is_empty() {
+        let elements = prefixes.iter().map(|p| p.trunc().to_string()).collect::<Vec<_>>();
+        let _ = writeln!(ruleset, "        elements = {{ {} }}", elements.join(", "));
+    }
+    let _ = writeln!(ruleset, "    }}");
+}
+
+fn make_ruleset(config: &KillSwitchConfig) -> String {
+    let v4 = |prefixes: &[IpNet]| prefixes.iter().filter(|p| p.addr().is_ipv4()).collect::<Vec<_>>();
+    let v6 = |prefixes: &[IpNet]| prefixes.iter().filter(|p| p.addr().is_ipv6()).collect::<Vec<_>>();
+
+    let mut ruleset = nft::begin_ruleset(&["output"], SETS);
+    let _ = writeln!(ruleset, "table {NFT_TABLE} {{");
+
+    write_set(&mut ruleset, "allowed4", "ipv4_addr", &v4(&config.allowed));
+    write_set(&mut ruleset, "allowed6", "ipv6_addr", &v6(&config.allowed));
+    write_set(&mut ruleset, "tunneled4", "ipv4_addr", &v4(&config.tunneled));
+    write_set(&mut ruleset, "tunneled6", "ipv6_addr", &v6(&config.tunneled));
+
+    let _ = writeln!(ruleset, "    chain output {{");
+    let _ = writeln!(ruleset, "        type filter hook output priority 0; policy accept;");
+    let _ = writeln!(ruleset, "        oifname \"lo\" accept");
+    let _ = writeln!(ruleset, "        oifname \"{}\" accept", config.device);
+    let _ = writeln!(ruleset, "        ip daddr @allowed4 accept");
+    let _ = writeln!(ruleset, "        ip6 daddr @allowed6 accept");
+    for rule in ESSENTIAL_RULES {
+        let _ = writeln!(ruleset, "        {rule}");
+    }
+    let _ = writeln!(ruleset, "        ip daddr @tunneled4 drop");
+    let _ = writeln!(ruleset, "        ip6 daddr @tunneled6 drop");
+    let _ = writeln!(ruleset, "    }}");
+    let _ = writeln!(ruleset, "}}");
+
+    ruleset
+}
+
+fn unavailable(e: std::io::Error) -> anyhow::Error {
+    anyhow!(tr!("error-kill-switch-unavailable", message = e.to_string()))
+}
+
+#[async_trait]
+impl KillSwitch for NftKillSwitch {
+    // The chain and the sets are replaced atomically, so that reconnects never leave a gap without the rules.
+    async fn enable(&self, config: &KillSwitchConfig) -> anyhow::Result<()> {
+        debug!(
+            "Enabling kill switch for {}, tunneled: {:?}, allowed: {:?}",
+            config.device, config.tunneled, config.allowed
+        );
+        nft::run_nft(&make_ruleset(config), unavailable).await
+    }
+
+    // Removed last on disconnect, the whole shared table goes with it.
+    async fn disable(&self) -> anyhow::Result<()> {
+        debug!("Disabling kill switch");
+        nft::run_nft(&nft::delete_table_ruleset(), unavailable).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_ruleset() {
+        let config = KillSwitchConfig {
+            device: "snx-tun".to_owned(),
+            tunneled: vec!["10.1.2.3/8".parse().unwrap(), "fd00::/64".parse().unwrap()],
+            allowed: vec!["1.2.3.4/32".parse().unwrap()],
+        };
+
+        let ruleset = make_ruleset(&config);
+
+        assert!(
+            ruleset
+                .starts_with("add table inet snx-rs\nadd chain inet snx-rs output\ndelete chain inet snx-rs output\n")
+        );
+        assert!(ruleset.contains("delete set inet snx-rs tunneled6\ntable inet snx-rs {\n"));
+        assert!(ruleset.contains(
+            "    set tunneled4 {\n        type ipv4_addr\n        flags interval\n        auto-merge\n        elements = { 10.0.0.0/8 }\n    }\n"
+        ));
+        assert!(ruleset.contains("        elements = { fd00::/64 }\n"));
+        assert!(ruleset.contains("        elements = { 1.2.3.4/32 }\n"));
+        assert!(ruleset.contains(
+            "    set allowed6 {\n        type ipv6_addr\n        flags interval\n        auto-merge\n    }\n"
+        ));
+
+        let accept = ruleset.find("oifname \"snx-tun\" accept").unwrap();
+        let allowed = ruleset.find("ip daddr @allowed4 accept").unwrap();
+        let drop = ruleset.find("ip daddr @tunneled4 drop").unwrap();
+        assert!(accept < drop && allowed < drop);
+    }
+}
@@ -0,0 +1,81 @@
+use std::{fmt::Write, io};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use i18n::tr;
+use tracing::debug;
+
+use crate::platform::{
+    MssClamp,
+    linux::nft::{self, NFT_TABLE},
+};
+
+const CHAIN: &str = "mss-clamp";
+
+/// MSS clamping in its own chain of the shared table. The local sockets already use the device MTU,
+/// the forwarded flows of the LAN hosts, containers and VMs are the ones which need it.
+#[derive(Default)]
+pub struct NftMssClamp;
+
+impl NftMssClamp {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn unavailable(e: io::Error) -> anyhow::Error {
+    anyhow!(tr!("error-mss-clamp-unavailable", message = e.to_string()))
+}
+
+fn make_ruleset(device: &str) -> String {
+    let mut ruleset = nft::begin_ruleset(&[CHAIN], &[]);
+
+    let _ = writeln!(ruleset, "table {NFT_TABLE} {{");
+    let _ = writeln!(ruleset, "    chain {CHAIN} {{");
+    let _ = writeln!(
+        ruleset,
+        "        type filter hook forward priority mangle; policy accept;"
+    );
+    let _ = writeln!(
+        ruleset,
+        "        oifname \"{device}\" tcp flags syn tcp option maxseg size set rt mtu"
+    );
+    let _ = writeln!(
+        ruleset,
+        "        iifname \"{device}\" tcp flags syn tcp option maxseg size set rt mtu"
+    );
+    let _ = writeln!(ruleset, "    }}");
+    let _ = writeln!(ruleset, "}}");
+
+    ruleset
+}
+
+#[async_trait]
+impl MssClamp for NftMssClamp {
+    async fn enable(&self, device: &str) -> anyhow::Result<()> {
+        debug!("Enabling MSS clamping through {}", device);
+        nft::run_nft(&make_ruleset(device), unavailable).await
+    }
+
+    async fn disable(&self, device: &str) -> anyhow::Result<()> {
+        debug!("Disabling MSS clamping through {}", device);
+        nft::run_nft(&nft::begin_ruleset(&[CHAIN], &[]), unavailable).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_ruleset() {
+        let ruleset = make_ruleset("snx-tun");
+
+        assert!(ruleset.starts_with(
+            "add table inet snx-rs\nadd chain inet snx-rs mss-clamp\ndelete chain inet snx-rs mss-clamp\n"
+        ));
+        assert!(ruleset.contains("        type filter hook forward priority mangle; policy accept;\n"));
+        assert!(ruleset.contains("        oifname \"snx-tun\" tcp flags syn tcp option maxseg size set rt mtu\n"));
+        assert!(ruleset.contains("        iifname \"snx-tun\" tcp flags syn tcp option maxseg size set rt mtu\n"));
+    }
+}
@@ -1,64 +1,117 @@
 use std::{
+    fs,
     net::Ipv4Addr,
+    path::Path,
     sync::atomic::{AtomicBool, Ordering},
 };
 
-use anyhow::anyhow;
+use anyhow::{Context, anyhow};
 use async_trait::async_trait;
-use futures::StreamExt;
-use ipnet::Ipv4Net;
-use tracing::debug;
-use zbus::Connection;
-
-use crate::{platform::NetworkInterface, util};
+use ipnet::{Ipv4Net, Ipv6Net};
+use once_cell::sync::Lazy;
+use tokio::sync::watch;
+use tracing::{debug, warn};
+
+#[cfg(feature = "dbus")]
+use crate::platform::linux::nm;
+use crate::{
+    model::TrafficCounters,
+    platform::{
+        DefaultGateway, LocalNetwork, NetworkInterface,
+        linux::{netlink, route_monitor},
+        resolv_conf,
+    },
+    util,
+};
 
 static ONLINE_STATE: AtomicBool = AtomicBool::new(true);
+static NETWORK_CHANGES: Lazy<watch::Sender<u64>> = Lazy::new(|| watch::Sender::new(0));
 
 const SNX_RS_CHAIN_NAME: &str = "filter_SNXRS_ICMP";
 const FIREWALLD_CHAIN_NAME: &str = "filter_INPUT";
 const FIREWALLD_TABLE_NAME: &str = "firewalld";
+const SYSFS_NET_DIR: &str = "/sys/class/net";
 
-#[derive(Debug, Copy, Clone, PartialEq)]
-enum NetworkManagerState {
-    Unknown,
-    Asleep,
-    Disconnected,
-    Disconnecting,
-    Connecting,
-    ConnectedLocal,
-    ConnectedSite,
-    ConnectedGlobal,
+fn parse_default_route(output: &str, exclude_device: &str) -> Option<(Ipv4Addr, String)> {
+    output.lines().find_map(|line| {
+        let mut address = None;
+        let mut device = None;
+        let mut parts = line.split_whitespace();
+        while let Some(part) = parts.next() {
+            match part {
+                "via" => address = parts.next().and_then(|s| s.parse().ok()),
+                "dev" => device = parts.next(),
+                _ => {}
+            }
+        }
+        match (address, device) {
+            (Some(address), Some(device)) if device != exclude_device => Some((address, device.to_owned())),
+            _ => None,
+        }
+    })
 }
 
-impl From<u32> for NetworkManagerState {
-    fn from(value: u32) -> Self {
-        match value {
-            10 => Self::Asleep,
-            20 => Self::Disconnected,
-            30 => Self::Disconnecting,
-            40 => Self::Connecting,
-            50 => Self::ConnectedLocal,
-            60 => Self::ConnectedSite,
-            70 => Self::ConnectedGlobal,
-            _ => Self::Unknown,
+// The first address of `ip -4 -o addr show dev <device>`.
+fn parse_addr_show(output: &str) -> Option<Ipv4Addr> {
+    let mut parts = output.split_whitespace();
+    while let Some(part) = parts.next() {
+        if part == "inet" {
+            let ip = parts.next()?;
+            return ip.split_once('/').map_or(ip, |(before, _)| before).parse().ok();
         }
     }
+    None
 }
 
-impl NetworkManagerState {
-    fn is_online(self) -> bool {
-        matches!(self, Self::ConnectedGlobal)
+fn parse_neighbor_mac(output: &str) -> Option<String> {
+    let mut parts = output.split_whitespace();
+    while let Some(part) = parts.next() {
+        if part == "lladdr" {
+            return parts.next().map(|s| s.to_lowercase());
+        }
     }
+    None
 }
 
-#[zbus::proxy(
-    interface = "org.freedesktop.NetworkManager",
-    default_service = "org.freedesktop.NetworkManager",
-    default_path = "/org/freedesktop/NetworkManager"
-)]
-pub trait NetworkManager {
-    #[zbus(property)]
-    fn state(&self) -> zbus::Result<u32>;
+fn parse_link_domains(output: &str, exclude_device: &str) -> Vec<String> {
+    let exclude = format!("({exclude_device})");
+    output
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(link, _)| !link.contains(&exclude))
+        .flat_map(|(_, domains)| domains.split_whitespace())
+        .filter(|domain| !domain.starts_with('~'))
+        .map(|domain| domain.to_lowercase())
+        .collect()
+}
+
+// Connected subnets and static routes of the main table, a bare address is a host route.
+fn parse_local_routes(output: &str, exclude_device: &str) -> Vec<LocalNetwork> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let destination = parts.next()?;
+            let network = destination
+                .parse::<Ipv4Net>()
+                .or_else(|_| destination.parse::<Ipv4Addr>().map(Ipv4Net::from))
+                .ok()?;
+            let device = parts.skip_while(|&part| part != "dev").nth(1)?;
+            (device != exclude_device && device != "lo" && !network.addr().is_loopback()).then(|| LocalNetwork {
+                device: device.to_owned(),
+                network: network.trunc(),
+            })
+        })
+        .collect()
+}
+
+// The `ssid` line is only present for the connected interfaces.
+fn parse_iw_ssids(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("ssid "))
+        .map(ToOwned::to_owned)
+        .collect()
 }
 
 #[derive(Default)]
@@ -109,6 +162,7 @@ impl LinuxNetworkInterface {
         .await?;
         Ok(())
     }
+
     async fn set_allow_firewalld_icmp_invalid_state(&self, device_name: &str) -> anyhow::Result<()> {
         if !self.is_firewalld_active().await {
             debug!("firewalld/nftables not active");
@@ -152,20 +206,15 @@ impl LinuxNetworkInterface {
 #[async_trait]
 impl NetworkInterface for LinuxNetworkInterface {
     async fn start_network_state_monitoring(&self) -> anyhow::Result<()> {
-        let connection = Connection::system().await?;
-        let proxy = NetworkManagerProxy::new(&connection).await?;
-
-        let mut stream = proxy.receive_state_changed().await;
         tokio::spawn(async move {
-            while let Some(signal) = stream.next().await {
-                let state: NetworkManagerState = signal.get().await?.into();
-                debug!("NetworkManager state changed to {:?}", state);
-                ONLINE_STATE.store(state.is_online(), Ordering::SeqCst);
+            if let Err(e) = route_monitor::watch_network_changes(&NETWORK_CHANGES).await {
+                warn!("Unable to monitor address changes: {}", e);
             }
-
-            Ok::<_, zbus::Error>(())
         });
 
+        #[cfg(feature = "dbus")]
+        nm::monitor_state(&ONLINE_STATE).await?;
+
         Ok(())
     }
 
@@ -176,17 +225,8 @@ impl NetworkInterface for LinuxNetworkInterface {
             if part == "dev" {
                 if let Some(dev) = parts.next() {
                     let addr = util::run_command("ip", ["-4", "-o", "addr", "show", "dev", dev]).await?;
-                    let mut parts = addr.split_whitespace();
-                    while let Some(part) = parts.next() {
-                        if part == "inet" {
-                            if let Some(ip) = parts.next() {
-                                return Ok(ip
-                                    .split_once('/')
-                                    .map_or(ip, |(before, _)| before)
-                                    .to_string()
-                                    .parse()?);
-                            }
-                        }
+                    if let Some(address) = parse_addr_show(&addr) {
+                        return Ok(address);
                     }
                 }
             }
@@ -194,42 +234,113 @@ impl NetworkInterface for LinuxNetworkInterface {
         Err(anyhow!(i18n::tr!("error-cannot-determine-ip")))
     }
 
+    async fn get_default_gateway(&self, exclude_device: &str) -> anyhow::Result<DefaultGateway> {
+        let default_route = util::run_command("ip", ["-4", "route", "show", "default"]).await?;
+        let (address, device) = parse_default_route(&default_route, exclude_device).context("No default gateway")?;
+
+        let neighbor = util::run_command("ip", &["neigh", "show", &address.to_string(), "dev", &device]).await?;
+
+        let local_address = util::run_command("ip", ["-4", "-o", "addr", "show", "dev", &device])
+            .await
+            .ok()
+            .and_then(|output| parse_addr_show(&output));
+
+        Ok(DefaultGateway {
+            address,
+            device,
+            mac: parse_neighbor_mac(&neighbor),
+            local_address,
+        })
+    }
+
+    async fn get_search_domains(&self, exclude_device: &str) -> anyhow::Result<Vec<String>> {
+        match util::run_command("resolvectl", ["domain"]).await {
+            Ok(output) => Ok(parse_link_domains(&output, exclude_device)),
+            Err(_) => Ok(resolv_conf::parse_search_domains(
+                &tokio::fs::read_to_string(resolv_conf::RESOLV_CONF).await?,
+            )),
+        }
+    }
+
+    async fn get_local_networks(&self, exclude_device: &str) -> anyhow::Result<Vec<LocalNetwork>> {
+        let output = util::run_command("ip", ["-4", "route", "show", "table", "main"]).await?;
+        Ok(parse_local_routes(&output, exclude_device))
+    }
+
+    async fn get_wireless_ssids(&self) -> Vec<String> {
+        util::run_command("iw", ["dev"])
+            .await
+            .map(|output| parse_iw_ssids(&output))
+            .unwrap_or_default()
+    }
+
     async fn delete_device(&self, device_name: &str) -> anyhow::Result<()> {
-        util::run_command("ip", ["link", "del", "name", device_name]).await?;
+        netlink::new_netlink_ops().delete_link(device_name).await?;
         Ok(())
     }
 
     async fn configure_device(&self, device_name: &str) -> anyhow::Result<()> {
-        util::run_command("nmcli", ["device", "set", device_name, "managed", "no"]).await?;
+        #[cfg(feature = "dbus")]
+        if let Err(e) = nm::set_unmanaged(device_name).await {
+            warn!("Unable to set {} as unmanaged: {}", device_name, e);
+        }
         let _ = self.set_allow_firewalld_icmp_invalid_state(device_name).await;
         Ok(())
     }
 
+    async fn update_connectivity(&self) {
+        #[cfg(feature = "dbus")]
+        nm::check_connectivity().await;
+    }
+
     async fn replace_ip_address(
         &self,
         device_name: &str,
         old_address: Ipv4Net,
         new_address: Ipv4Net,
     ) -> anyhow::Result<()> {
-        util::run_command("ip", &["addr", "add", &new_address.to_string(), "dev", device_name]).await?;
-        util::run_command("ip", &["addr", "del", &old_address.to_string(), "dev", device_name]).await?;
+        let ops = netlink::new_netlink_ops();
+        ops.add_address(device_name, new_address.into()).await?;
+        ops.delete_address(device_name, old_address.into()).await?;
+        Ok(())
+    }
+
+    async fn add_ipv6_address(&self, device_name: &str, address: Ipv6Net) -> anyhow::Result<()> {
+        // an existing address is tolerated, which keeps the call idempotent on a reattached persistent device
+        netlink::new_netlink_ops()
+            .add_address(device_name, address.into())
+            .await?;
+        Ok(())
+    }
+
+    async fn set_mtu(&self, device_name: &str, mtu: u16) -> anyhow::Result<()> {
+        netlink::new_netlink_ops().set_mtu(device_name, mtu).await?;
         Ok(())
     }
 
+    async fn get_traffic_counters(&self, device_name: &str) -> Option<TrafficCounters> {
+        let dir = Path::new(SYSFS_NET_DIR).join(device_name).join("statistics");
+        let read = |name: &str| -> Option<u64> { fs::read_to_string(dir.join(name)).ok()?.trim().parse().ok() };
+
+        Some(TrafficCounters {
+            rx_bytes: read("rx_bytes")?,
+            tx_bytes: read("tx_bytes")?,
+            rx_packets: read("rx_packets")?,
+            tx_packets: read("tx_packets")?,
+        })
+    }
+
     fn is_online(&self) -> bool {
         ONLINE_STATE.load(Ordering::SeqCst)
     }
 
+    fn network_changes(&self) -> watch::Receiver<u64> {
+        NETWORK_CHANGES.subscribe()
+    }
+
     fn poll_online(&self) {
-        tokio::spawn(async move {
-            let connection = Connection::system().await?;
-            let proxy = NetworkManagerProxy::new(&connection).await?;
-            let state = proxy.state().await?;
-            let state: NetworkManagerState = state.into();
-            debug!("Acquired network state via polling: {:?}", state);
-            ONLINE_STATE.store(state.is_online(), Ordering::SeqCst);
-            Ok::<_, anyhow::Error>(())
-        });
+        #[cfg(feature = "dbus")]
+        nm::poll_state(&ONLINE_STATE);
     }
 }
 
@@ -242,4 +353,69 @@ mod tests {
         let ip = LinuxNetworkInterface.get_default_ip().await.unwrap();
         println!("{ip}");
     }
+
+    #[test]
+    fn test_parse_default_route() {
+        let output = "default dev snx-tun scope link\ndefault via 192.168.1.1 dev wlan0 proto dhcp metric 600\n";
+        assert_eq!(
+            parse_default_route(output, "snx-tun"),
+            Some(("192.168.1.1".parse().unwrap(), "wlan0".to_owned()))
+        );
+        assert_eq!(parse_default_route(output, "wlan0"), None);
+    }
+
+    #[test]
+    fn test_parse_neighbor_mac() {
+        let output = "192.168.1.1 lladdr AA:BB:CC:DD:EE:FF REACHABLE";
+        assert_eq!(parse_neighbor_mac(output).as_deref(), Some("aa:bb:cc:dd:ee:ff"));
+        assert_eq!(parse_neighbor_mac("192.168.1.1 FAILED"), None);
+    }
+
+    #[test]
+    fn test_parse_addr_show() {
+        let output =
+            r"2: eth0    inet 192.168.1.10/24 brd 192.168.1.255 scope global dynamic eth0\       valid_lft 86000sec";
+        assert_eq!(parse_addr_show(output), Some(Ipv4Addr::new(192, 168, 1, 10)));
+        assert_eq!(parse_addr_show(""), None);
+    }
+
+    #[test]
+    fn test_parse_iw_ssids() {
+        let output = "phy#0\n\tInterface wlan0\n\t\tifindex 3\n\t\tssid Corp WiFi\n\t\ttype managed\n\
+                      phy#1\n\tInterface wlan1\n\t\tifindex 4\n\t\ttype managed\n";
+        assert_eq!(parse_iw_ssids(output), vec!["Corp WiFi"]);
+    }
+
+    #[test]
+    fn test_parse_link_domains() {
+        let output = "Global:\nLink 2 (wlan0): Corp.Example.com ~.\nLink 5 (snx-tun): vpn.example.com ~example.com\n";
+        assert_eq!(parse_link_domains(output, "snx-tun"), vec!["corp.example.com"]);
+    }
+
+    #[test]
+    fn test_parse_local_routes() {
+        let output = "default via 192.168.1.1 dev wlan0 proto dhcp metric 600\n\
+                      10.8.0.0/16 via 192.168.1.254 dev wlan0\n\
+                      172.17.0.0/16 dev docker0 proto kernel scope link src 172.17.0.1 linkdown\n\
+                      192.168.1.0/24 dev wlan0 proto kernel scope link src 192.168.1.10 metric 600\n\
+                      192.168.5.7 dev wlan0 scope link\n\
+                      10.0.0.0/8 dev snx-tun scope link\n\
+                      unreachable 10.99.0.0/16\n";
+
+        let networks = parse_local_routes(output, "snx-tun");
+        let pairs = networks
+            .iter()
+            .map(|n| (n.device.as_str(), n.network.to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            pairs,
+            vec![
+                ("wlan0", "10.8.0.0/16".to_owned()),
+                ("docker0", "172.17.0.0/16".to_owned()),
+                ("wlan0", "192.168.1.0/24".to_owned()),
+                ("wlan0", "192.168.5.7/32".to_owned()),
+            ]
+        );
+    }
 }
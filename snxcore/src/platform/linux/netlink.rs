@@ -0,0 +1,687 @@
+//! Link, address and route changes through rtnetlink, with the `ip` command as a fallback.
+
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use ipnet::{IpNet, Ipv4Net};
+use rtnetlink::{
+    Handle, LinkUnspec, RouteMessageBuilder,
+    packet_route::address::{AddressAttribute, AddressMessage},
+};
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+
+use crate::util;
+
+static USE_IPROUTE2: AtomicBool = AtomicBool::new(false);
+
+/// Select the `ip` command instead of rtnetlink for the following operations.
+pub fn set_use_iproute2(flag: bool) {
+    USE_IPROUTE2.store(flag, Ordering::SeqCst);
+}
+
+/// Backend selected with the `use-iproute2` option.
+pub fn new_netlink_ops() -> Arc<dyn NetlinkOps> {
+    if USE_IPROUTE2.load(Ordering::SeqCst) {
+        Arc::new(IpRoute2Ops)
+    } else {
+        Arc::new(RtNetlinkOps)
+    }
+}
+
+#[derive(Debug)]
+pub enum NetlinkError {
+    NoSuchDevice(String),
+    Exists,
+    NotFound,
+    Os(i32),
+    Other(String),
+}
+
+impl fmt::Display for NetlinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchDevice(name) => write!(f, "No such device: {name}"),
+            Self::Exists => write!(f, "Object already exists"),
+            Self::NotFound => write!(f, "No such object"),
+            Self::Os(code) => write!(f, "{}", std::io::Error::from_raw_os_error(*code)),
+            Self::Other(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for NetlinkError {}
+
+impl NetlinkError {
+    fn from_errno(code: i32) -> Self {
+        match code {
+            libc::EEXIST => Self::Exists,
+            libc::ESRCH | libc::ENOENT | libc::EADDRNOTAVAIL => Self::NotFound,
+            libc::ENODEV => Self::NoSuchDevice(String::new()),
+            code => Self::Os(code),
+        }
+    }
+
+    // iproute2 prints the strerror text of the netlink error
+    fn from_stderr(stderr: &str) -> Self {
+        if stderr.contains("File exists") {
+            Self::Exists
+        } else if stderr.contains("No such process") || stderr.contains("Cannot assign requested address") {
+            Self::NotFound
+        } else if stderr.contains("Cannot find device") {
+            Self::NoSuchDevice(String::new())
+        } else {
+            Self::Other(stderr.trim().to_owned())
+        }
+    }
+
+    fn for_device(self, device: &str) -> Self {
+        match self {
+            Self::NoSuchDevice(_) => Self::NoSuchDevice(device.to_owned()),
+            other => other,
+        }
+    }
+}
+
+/// Route through a device, in the main table unless another one is given.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteSpec {
+    pub destination: IpNet,
+    pub device: String,
+    pub table: Option<u32>,
+    pub metric: Option<u32>,
+    #[serde(default)]
+    pub gateway: Option<IpAddr>,
+}
+
+impl RouteSpec {
+    pub fn new<S: AsRef<str>>(destination: IpNet, device: S) -> Self {
+        Self {
+            destination,
+            device: device.as_ref().to_owned(),
+            table: None,
+            metric: None,
+            gateway: None,
+        }
+    }
+
+    pub fn table(mut self, table: u32) -> Self {
+        self.table = Some(table);
+        self
+    }
+
+    pub fn metric(mut self, metric: Option<u32>) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// Explicit next hop, e.g. the peer of a point-to-point device.
+    pub fn gateway(mut self, gateway: Option<IpAddr>) -> Self {
+        self.gateway = gateway;
+        self
+    }
+}
+
+/// Adding an existing object and deleting a missing one are not errors.
+#[async_trait]
+pub trait NetlinkOps: Send + Sync {
+    async fn add_address(&self, device: &str, address: IpNet) -> Result<(), NetlinkError>;
+    async fn delete_address(&self, device: &str, address: IpNet) -> Result<(), NetlinkError>;
+    /// Make the address the only IPv4 one of the device, the previous ones are removed once it is in place.
+    /// With a peer the address is the local end of a point-to-point link.
+    async fn replace_ipv4_address(
+        &self,
+        device: &str,
+        address: Ipv4Net,
+        peer: Option<Ipv4Addr>,
+    ) -> Result<(), NetlinkError>;
+    async fn set_mtu(&self, device: &str, mtu: u16) -> Result<(), NetlinkError>;
+    async fn set_link_up(&self, device: &str) -> Result<(), NetlinkError>;
+    async fn delete_link(&self, device: &str) -> Result<(), NetlinkError>;
+    async fn add_route(&self, route: &RouteSpec) -> Result<(), NetlinkError>;
+    async fn delete_route(&self, route: &RouteSpec) -> Result<(), NetlinkError>;
+}
+
+fn tolerate(result: Result<(), NetlinkError>, tolerated: fn(&NetlinkError) -> bool) -> Result<(), NetlinkError> {
+    match result {
+        Err(e) if tolerated(&e) => {
+            trace!("Ignoring netlink error: {}", e);
+            Ok(())
+        }
+        other => other,
+    }
+}
+
+fn on_add(result: Result<(), NetlinkError>) -> Result<(), NetlinkError> {
+    tolerate(result, |e| matches!(e, NetlinkError::Exists))
+}
+
+fn on_delete(result: Result<(), NetlinkError>) -> Result<(), NetlinkError> {
+    tolerate(result, |e| matches!(e, NetlinkError::NotFound))
+}
+
+pub struct RtNetlinkOps;
+
+impl RtNetlinkOps {
+    // The connection task ends when the handle is dropped.
+    fn handle() -> Result<Handle, NetlinkError> {
+        let (connection, handle, _) = rtnetlink::new_connection().map_err(|e| NetlinkError::Other(e.to_string()))?;
+        tokio::spawn(connection);
+        Ok(handle)
+    }
+
+    async fn link_index(handle: &Handle, device: &str) -> Result<u32, NetlinkError> {
+        let link = handle
+            .link()
+            .get()
+            .match_name(device.to_owned())
+            .execute()
+            .try_next()
+            .await
+            .map_err(|e| map_error(e).for_device(device))?;
+
+        link.map(|link| link.header.index)
+            .ok_or_else(|| NetlinkError::NoSuchDevice(device.to_owned()))
+    }
+
+    fn route_message(route: &RouteSpec, index: u32) -> rtnetlink::packet_route::route::RouteMessage {
+        fn build<T>(mut builder: RouteMessageBuilder<T>, route: &RouteSpec, index: u32) -> RouteMessageBuilder<T> {
+            builder = builder.output_interface(index);
+            if let Some(table) = route.table {
+                builder = builder.table_id(table);
+            }
+            if let Some(metric) = route.metric {
+                builder = builder.priority(metric);
+            }
+            builder
+        }
+
+        match route.destination {
+            IpNet::V4(net) => {
+                let mut builder = RouteMessageBuilder::<std::net::Ipv4Addr>::new()
+                    .destination_prefix(net.network(), net.prefix_len());
+                if let Some(IpAddr::V4(gateway)) = route.gateway {
+                    builder = builder.gateway(gateway);
+                }
+                build(builder, route, index).build()
+            }
+            IpNet::V6(net) => {
+                let mut builder = RouteMessageBuilder::<std::net::Ipv6Addr>::new()
+                    .destination_prefix(net.network(), net.prefix_len());
+                if let Some(IpAddr::V6(gateway)) = route.gateway {
+                    builder = builder.gateway(gateway);
+                }
+                build(builder, route, index).build()
+            }
+        }
+    }
+}
+
+// IFA_LOCAL is the own address of a point-to-point link, IFA_ADDRESS is the peer then.
+fn local_address(message: &AddressMessage) -> Option<IpAddr> {
+    let find = |local| {
+        message.attributes.iter().find_map(|attribute| match attribute {
+            AddressAttribute::Local(address) if local => Some(*address),
+            AddressAttribute::Address(address) if !local => Some(*address),
+            _ => None,
+        })
+    };
+    find(true).or_else(|| find(false))
+}
+
+fn map_error(error: rtnetlink::Error) -> NetlinkError {
+    match error {
+        rtnetlink::Error::NetlinkError(message) => NetlinkError::from_errno(-message.raw_code()),
+        other => NetlinkError::Other(other.to_string()),
+    }
+}
+
+#[async_trait]
+impl NetlinkOps for RtNetlinkOps {
+    async fn add_address(&self, device: &str, address: IpNet) -> Result<(), NetlinkError> {
+        let handle = Self::handle()?;
+        let index = Self::link_index(&handle, device).await?;
+
+        on_add(
+            handle
+                .address()
+                .add(index, address.addr(), address.prefix_len())
+                .execute()
+                .await
+                .map_err(map_error),
+        )
+    }
+
+    async fn delete_address(&self, device: &str, address: IpNet) -> Result<(), NetlinkError> {
+        let handle = Self::handle()?;
+        let index = Self::link_index(&handle, device).await?;
+
+        let mut addresses = handle
+            .address()
+            .get()
+            .set_link_index_filter(index)
+            .set_address_filter(address.addr())
+            .set_prefix_length_filter(address.prefix_len())
+            .execute();
+
+        while let Some(message) = addresses.try_next().await.map_err(map_error)? {
+            on_delete(handle.address().del(message).execute().await.map_err(map_error))?;
+        }
+
+        Ok(())
+    }
+
+    async fn replace_ipv4_address(
+        &self,
+        device: &str,
+        address: Ipv4Net,
+        peer: Option<Ipv4Addr>,
+    ) -> Result<(), NetlinkError> {
+        let handle = Self::handle()?;
+        let index = Self::link_index(&handle, device).await?;
+
+        let mut request = handle
+            .address()
+            .add(index, address.addr().into(), address.prefix_len())
+            .replace();
+        if let Some(peer) = peer {
+            let attributes = &mut request.message_mut().attributes;
+            attributes.retain(|a| !matches!(a, AddressAttribute::Address(_) | AddressAttribute::Broadcast(_)));
+            attributes.push(AddressAttribute::Address(peer.into()));
+        }
+        request.execute().await.map_err(map_error)?;
+
+        let mut addresses = handle.address().get().set_link_index_filter(index).execute();
+        let mut previous = Vec::new();
+        while let Some(message) = addresses.try_next().await.map_err(map_error)? {
+            let current = match local_address(&message) {
+                Some(IpAddr::V4(local)) => (local, message.header.prefix_len),
+                _ => continue,
+            };
+            if current != (address.addr(), address.prefix_len()) {
+                previous.push(message);
+            }
+        }
+
+        for message in previous {
+            on_delete(handle.address().del(message).execute().await.map_err(map_error))?;
+        }
+
+        Ok(())
+    }
+
+    async fn set_mtu(&self, device: &str, mtu: u16) -> Result<(), NetlinkError> {
+        let handle = Self::handle()?;
+        let index = Self::link_index(&handle, device).await?;
+
+        handle
+            .link()
+            .set(LinkUnspec::new_with_index(index).mtu(mtu.into()).build())
+            .execute()
+            .await
+            .map_err(map_error)
+    }
+
+    async fn set_link_up(&self, device: &str) -> Result<(), NetlinkError> {
+        let handle = Self::handle()?;
+        let index = Self::link_index(&handle, device).await?;
+
+        handle
+            .link()
+            .set(LinkUnspec::new_with_index(index).up().build())
+            .execute()
+            .await
+            .map_err(map_error)
+    }
+
+    async fn delete_link(&self, device: &str) -> Result<(), NetlinkError> {
+        let handle = Self::handle()?;
+        let index = Self::link_index(&handle, device).await?;
+
+        handle.link().del(index).execute().await.map_err(map_error)
+    }
+
+    async fn add_route(&self, route: &RouteSpec) -> Result<(), NetlinkError> {
+        let handle = Self::handle()?;
+        let index = Self::link_index(&handle, &route.device).await?;
+
+        on_add(
+            handle
+                .route()
+                .add(Self::route_message(route, index))
+                .execute()
+                .await
+                .map_err(map_error),
+        )
+    }
+
+    async fn delete_route(&self, route: &RouteSpec) -> Result<(), NetlinkError> {
+        let handle = Self::handle()?;
+        let index = match Self::link_index(&handle, &route.device).await {
+            Ok(index) => index,
+            // the routes of a deleted device are gone with it
+            Err(NetlinkError::NoSuchDevice(_)) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        on_delete(
+            handle
+                .route()
+                .del(Self::route_message(route, index))
+                .execute()
+                .await
+                .map_err(map_error),
+        )
+    }
+}
+
+/// Escape hatch for systems where the netlink backend misbehaves, to be removed in the next release.
+pub struct IpRoute2Ops;
+
+impl IpRoute2Ops {
+    async fn ip(args: Vec<String>) -> Result<(), NetlinkError> {
+        util::run_command("ip", args)
+            .await
+            .map(|_| ())
+            .map_err(|e| NetlinkError::from_stderr(&e.to_string()))
+    }
+}
+
+fn family(address: IpAddr) -> &'static str {
+    if address.is_ipv4() { "-4" } else { "-6" }
+}
+
+fn route_args(action: &str, route: &RouteSpec) -> Vec<String> {
+    let mut args = vec![
+        family(route.destination.addr()).to_owned(),
+        "route".to_owned(),
+        action.to_owned(),
+    ];
+    if let Some(table) = route.table {
+        args.extend(["table".to_owned(), table.to_string()]);
+    }
+    args.push(route.destination.trunc().to_string());
+    if let Some(gateway) = route.gateway {
+        args.extend(["via".to_owned(), gateway.to_string()]);
+    }
+    args.extend(["dev".to_owned(), route.device.clone()]);
+    // the same metric must be given when deleting a route, otherwise another route to the prefix may be removed
+    if let Some(metric) = route.metric {
+        args.extend(["metric".to_owned(), metric.to_string()]);
+    }
+    args
+}
+
+// The address as given to `ip addr`, "10.0.0.10/24" or "10.0.0.10 peer 10.0.0.1/32".
+fn ipv4_address_args(address: Ipv4Net, peer: Option<Ipv4Addr>) -> Vec<String> {
+    match peer {
+        Some(peer) => vec![
+            address.addr().to_string(),
+            "peer".to_owned(),
+            format!("{}/{}", peer, address.prefix_len()),
+        ],
+        None => vec![address.to_string()],
+    }
+}
+
+// The addresses listed by `ip -o -4 addr show`, in the same form.
+fn parse_ipv4_addresses(output: &str) -> Vec<Vec<String>> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace().skip_while(|&part| part != "inet").skip(1);
+            let mut address = vec![parts.next()?.to_owned()];
+            if let Some("peer") = parts.next() {
+                address.extend(["peer".to_owned(), parts.next()?.to_owned()]);
+            }
+            Some(address)
+        })
+        .collect()
+}
+
+#[async_trait]
+impl NetlinkOps for IpRoute2Ops {
+    async fn add_address(&self, device: &str, address: IpNet) -> Result<(), NetlinkError> {
+        let args = [
+            family(address.addr()),
+            "addr",
+            "add",
+            &address.to_string(),
+            "dev",
+            device,
+        ];
+        on_add(Self::ip(args.map(ToOwned::to_owned).to_vec()).await)
+    }
+
+    async fn delete_address(&self, device: &str, address: IpNet) -> Result<(), NetlinkError> {
+        let args = [
+            family(address.addr()),
+            "addr",
+            "del",
+            &address.to_string(),
+            "dev",
+            device,
+        ];
+        on_delete(Self::ip(args.map(ToOwned::to_owned).to_vec()).await)
+    }
+
+    async fn replace_ipv4_address(
+        &self,
+        device: &str,
+        address: Ipv4Net,
+        peer: Option<Ipv4Addr>,
+    ) -> Result<(), NetlinkError> {
+        let replaced = ipv4_address_args(address, peer);
+        let mut args = vec!["-4".to_owned(), "addr".to_owned(), "replace".to_owned()];
+        args.extend(replaced.iter().cloned());
+        args.extend(["dev".to_owned(), device.to_owned()]);
+        Self::ip(args).await?;
+
+        let output = util::run_command("ip", ["-o", "-4", "addr", "show", "dev", device])
+            .await
+            .map_err(|e| NetlinkError::from_stderr(&e.to_string()).for_device(device))?;
+
+        for previous in parse_ipv4_addresses(&output) {
+            if previous != replaced {
+                let mut args = vec!["-4".to_owned(), "addr".to_owned(), "del".to_owned()];
+                args.extend(previous);
+                args.extend(["dev".to_owned(), device.to_owned()]);
+                on_delete(Self::ip(args).await)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn set_mtu(&self, device: &str, mtu: u16) -> Result<(), NetlinkError> {
+        let args = ["link", "set", "dev", device, "mtu", &mtu.to_string()];
+        Self::ip(args.map(ToOwned::to_owned).to_vec()).await
+    }
+
+    async fn set_link_up(&self, device: &str) -> Result<(), NetlinkError> {
+        let args = ["link", "set", "dev", device, "up"];
+        Self::ip(args.map(ToOwned::to_owned).to_vec()).await
+    }
+
+    async fn delete_link(&self, device: &str) -> Result<(), NetlinkError> {
+        let args = ["link", "del", "name", device];
+        Self::ip(args.map(ToOwned::to_owned).to_vec()).await
+    }
+
+    async fn add_route(&self, route: &RouteSpec) -> Result<(), NetlinkError> {
+        on_add(Self::ip(route_args("add", route)).await)
+    }
+
+    async fn delete_route(&self, route: &RouteSpec) -> Result<(), NetlinkError> {
+        on_delete(Self::ip(route_args("del", route)).await)
+    }
+}
+
+#[cfg(test)]
+pub mod mock {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// Records the operations, adding the same route twice reports an existing object.
+    #[derive(Default)]
+    pub struct MockNetlinkOps {
+        pub calls: Mutex<Vec<String>>,
+        pub routes: Mutex<Vec<RouteSpec>>,
+        pub fail_replace: bool,
+    }
+
+    impl MockNetlinkOps {
+        fn record(&self, call: String) {
+            self.calls.lock().unwrap().push(call);
+        }
+    }
+
+    #[async_trait]
+    impl NetlinkOps for MockNetlinkOps {
+        async fn add_address(&self, device: &str, address: IpNet) -> Result<(), NetlinkError> {
+            self.record(format!("addr add {address} dev {device}"));
+            Ok(())
+        }
+
+        async fn delete_address(&self, device: &str, address: IpNet) -> Result<(), NetlinkError> {
+            self.record(format!("addr del {address} dev {device}"));
+            Ok(())
+        }
+
+        async fn replace_ipv4_address(
+            &self,
+            device: &str,
+            address: Ipv4Net,
+            peer: Option<Ipv4Addr>,
+        ) -> Result<(), NetlinkError> {
+            self.record(format!(
+                "addr replace {} dev {device}",
+                ipv4_address_args(address, peer).join(" ")
+            ));
+            if self.fail_replace {
+                return Err(NetlinkError::Os(libc::EPERM));
+            }
+            Ok(())
+        }
+
+        async fn set_mtu(&self, device: &str, mtu: u16) -> Result<(), NetlinkError> {
+            self.record(format!("link set {device} mtu {mtu}"));
+            Ok(())
+        }
+
+        async fn set_link_up(&self, device: &str) -> Result<(), NetlinkError> {
+            self.record(format!("link set {device} up"));
+            Ok(())
+        }
+
+        async fn delete_link(&self, device: &str) -> Result<(), NetlinkError> {
+            self.record(format!("link del {device}"));
+            Ok(())
+        }
+
+        async fn add_route(&self, route: &RouteSpec) -> Result<(), NetlinkError> {
+            self.record(route_args("add", route).join(" "));
+            let mut routes = self.routes.lock().unwrap();
+            let result = if routes.contains(route) {
+                Err(NetlinkError::Exists)
+            } else {
+                routes.push(route.clone());
+                Ok(())
+            };
+            on_add(result)
+        }
+
+        async fn delete_route(&self, route: &RouteSpec) -> Result<(), NetlinkError> {
+            self.record(route_args("del", route).join(" "));
+            let mut routes = self.routes.lock().unwrap();
+            let result = match routes.iter().position(|r| r == route) {
+                Some(index) => {
+                    routes.remove(index);
+                    Ok(())
+                }
+                None => Err(NetlinkError::NotFound),
+            };
+            on_delete(result)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_mapping() {
+        assert!(matches!(NetlinkError::from_errno(libc::EEXIST), NetlinkError::Exists));
+        assert!(matches!(NetlinkError::from_errno(libc::ESRCH), NetlinkError::NotFound));
+        assert!(matches!(
+            NetlinkError::from_errno(libc::EPERM),
+            NetlinkError::Os(libc::EPERM)
+        ));
+        assert!(matches!(
+            NetlinkError::from_stderr("RTNETLINK answers: File exists\n"),
+            NetlinkError::Exists
+        ));
+        assert!(matches!(
+            NetlinkError::from_stderr("RTNETLINK answers: No such process\n"),
+            NetlinkError::NotFound
+        ));
+
+        assert!(on_add(Err(NetlinkError::Exists)).is_ok());
+        assert!(on_add(Err(NetlinkError::NotFound)).is_err());
+        assert!(on_delete(Err(NetlinkError::NotFound)).is_ok());
+        assert!(on_delete(Err(NetlinkError::Exists)).is_err());
+    }
+
+    #[test]
+    fn test_ipv4_addresses() {
+        let output = "5: snx-tun    inet 10.0.0.10/24 brd 10.0.0.255 scope global snx-tun\\       valid_lft forever\n\
+                      5: snx-tun    inet 10.0.0.10 peer 10.0.0.1/32 scope global snx-tun\\       valid_lft forever\n";
+        assert_eq!(
+            parse_ipv4_addresses(output),
+            vec![
+                vec!["10.0.0.10/24".to_owned()],
+                vec!["10.0.0.10".to_owned(), "peer".to_owned(), "10.0.0.1/32".to_owned()],
+            ]
+        );
+
+        let address = "10.0.0.10/32".parse().unwrap();
+        assert_eq!(
+            ipv4_address_args(address, Some("10.0.0.1".parse().unwrap())),
+            parse_ipv4_addresses(output)[1]
+        );
+        assert_eq!(ipv4_address_args(address, None), ["10.0.0.10/32"]);
+    }
+
+    #[test]
+    fn test_route_args() {
+        let route = RouteSpec::new("10.1.2.3/8".parse().unwrap(), "snx-tun")
+            .table(4500)
+            .metric(Some(100));
+        assert_eq!(
+            route_args("add", &route).join(" "),
+            "-4 route add table 4500 10.0.0.0/8 dev snx-tun metric 100"
+        );
+
+        let route = RouteSpec::new("fd00::/64".parse().unwrap(), "snx-tun");
+        assert_eq!(
+            route_args("del", &route).join(" "),
+            "-6 route del fd00::/64 dev snx-tun"
+        );
+
+        let route = RouteSpec::new("10.0.0.0/8".parse().unwrap(), "snx-tun").gateway(Some("10.0.0.1".parse().unwrap()));
+        assert_eq!(
+            route_args("add", &route).join(" "),
+            "-4 route add 10.0.0.0/8 via 10.0.0.1 dev snx-tun"
+        );
+    }
+}
@@ -0,0 +1,240 @@
+//! Named network namespaces managed by iproute2, compatible with `ip netns exec`: the namespace resolver
+//! configuration lives in /etc/netns/<name>/resolv.conf which is bind-mounted over /etc/resolv.conf.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use async_trait::async_trait;
+use ipnet::IpNet;
+use tracing::{debug, warn};
+
+use crate::{
+    platform::{NamespaceConfig, NetworkNamespace, ResolverConfigurator, resolv_conf::DirectConfigurator},
+    util,
+};
+
+const NETNS_RUN_DIR: &str = "/run/netns";
+const NETNS_ETC_DIR: &str = "/etc/netns";
+
+/// The tunnel device is moved into the namespace, the transport sockets stay in the namespace of the process.
+#[derive(Default)]
+pub struct LinuxNetworkNamespace;
+
+impl LinuxNetworkNamespace {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn resolv_conf_path(name: &str) -> PathBuf {
+    Path::new(NETNS_ETC_DIR).join(name).join("resolv.conf")
+}
+
+async fn ip_netns<I, T>(name: &str, args: I) -> anyhow::Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<str>,
+{
+    let args = ["-n".to_owned(), name.to_owned()]
+        .into_iter()
+        .chain(args.into_iter().map(|arg| arg.as_ref().to_owned()))
+        .collect::<Vec<_>>();
+    util::run_command("ip", args).await?;
+    Ok(())
+}
+
+fn address_args(config: &NamespaceConfig) -> Vec<String> {
+    let address = match config.peer {
+        Some(peer) => format!("{} peer {}", config.address.addr(), peer),
+        None => config.address.to_string(),
+    };
+
+    ["addr", "add"]
+        .into_iter()
+        .map(ToOwned::to_owned)
+        .chain(address.split_whitespace().map(ToOwned::to_owned))
+        .chain(["dev".to_owned(), config.device.clone()])
+        .collect()
+}
+
+fn route_args(config: &NamespaceConfig, route: &IpNet) -> Vec<String> {
+    let family = if route.addr().is_ipv4() { "-4" } else { "-6" };
+    let mut args = vec![
+        family.to_owned(),
+        "route".to_owned(),
+        "replace".to_owned(),
+        route.to_string(),
+    ];
+    if let (IpNet::V4(_), Some(peer)) = (route, config.peer) {
+        args.extend(["via".to_owned(), peer.to_string()]);
+    }
+    args.extend(["dev".to_owned(), config.device.clone()]);
+    args
+}
+
+// A missing resolv.conf is created empty, so that the restored file can be told apart and removed.
+async fn configure_dns(config: &NamespaceConfig) -> anyhow::Result<()> {
+    let path = resolv_conf_path(&config.name);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    if !path.exists() {
+        fs::write(&path, "")?;
+    }
+
+    debug!("Configuring DNS of namespace {} in {}", config.name, path.display());
+    DirectConfigurator::new(&path).configure(&config.resolver).await
+}
+
+async fn cleanup_dns(config: &NamespaceConfig) -> anyhow::Result<()> {
+    let path = resolv_conf_path(&config.name);
+    DirectConfigurator::new(&path).cleanup(&config.resolver).await?;
+
+    if fs::read_to_string(&path).is_ok_and(|conf| conf.is_empty()) {
+        fs::remove_file(&path)?;
+        if let Some(dir) = path.parent() {
+            let _ = fs::remove_dir(dir);
+        }
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl NetworkNamespace for LinuxNetworkNamespace {
+    async fn exists(&self, name: &str) -> bool {
+        Path::new(NETNS_RUN_DIR).join(name).exists()
+    }
+
+    async fn create(&self, name: &str) -> anyhow::Result<()> {
+        debug!("Creating network namespace {}", name);
+        util::run_command("ip", ["netns", "add", name]).await?;
+        ip_netns(name, ["link", "set", "dev", "lo", "up"]).await
+    }
+
+    async fn delete(&self, name: &str) -> anyhow::Result<()> {
+        debug!("Deleting network namespace {}", name);
+        util::run_command("ip", ["netns", "del", name]).await?;
+        Ok(())
+    }
+
+    // The addresses are lost when the device changes the namespace, so they are set again inside.
+    async fn configure(&self, config: &NamespaceConfig) -> anyhow::Result<()> {
+        debug!("Moving {} into network namespace {}", config.device, config.name);
+        util::run_command(
+            "ip",
+            [
+                "link",
+                "set",
+                "dev",
+                config.device.as_str(),
+                "netns",
+                config.name.as_str(),
+            ],
+        )
+        .await?;
+
+        ip_netns(
+            &config.name,
+            [
+                "link",
+                "set",
+                "dev",
+                config.device.as_str(),
+                "mtu",
+                &config.mtu.to_string(),
+                "up",
+            ],
+        )
+        .await?;
+        ip_netns(&config.name, address_args(config)).await?;
+
+        if let Some(address_v6) = config.address_v6 {
+            if let Err(e) = ip_netns(
+                &config.name,
+                [
+                    "-6",
+                    "addr",
+                    "add",
+                    &address_v6.to_string(),
+                    "dev",
+                    config.device.as_str(),
+                ],
+            )
+            .await
+            {
+                warn!("Unable to configure IPv6 address {}: {}", address_v6, e);
+            }
+        }
+
+        for route in &config.routes {
+            ip_netns(&config.name, route_args(config, route)).await?;
+        }
+
+        if !config.resolver.dns_servers.is_empty() {
+            configure_dns(config).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn cleanup(&self, config: &NamespaceConfig) -> anyhow::Result<()> {
+        if !config.resolver.dns_servers.is_empty() {
+            if let Err(e) = cleanup_dns(config).await {
+                warn!("Unable to restore DNS of namespace {}: {}", config.name, e);
+            }
+        }
+
+        // the device may be gone already together with the tunnel file descriptor
+        let _ = ip_netns(&config.name, ["link", "del", "dev", config.device.as_str()]).await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::ResolverConfig;
+
+    fn config(peer: Option<&str>) -> NamespaceConfig {
+        NamespaceConfig {
+            name: "vpn".to_owned(),
+            device: "snx-tun".to_owned(),
+            mtu: 1350,
+            address: "10.0.0.10/24".parse().unwrap(),
+            peer: peer.map(|p| p.parse().unwrap()),
+            address_v6: None,
+            routes: vec!["0.0.0.0/0".parse().unwrap(), "::/0".parse().unwrap()],
+            resolver: ResolverConfig::default(),
+        }
+    }
+
+    #[test]
+    fn test_address_args() {
+        assert_eq!(
+            address_args(&config(None)).join(" "),
+            "addr add 10.0.0.10/24 dev snx-tun"
+        );
+        assert_eq!(
+            address_args(&config(Some("10.0.0.1"))).join(" "),
+            "addr add 10.0.0.10 peer 10.0.0.1 dev snx-tun"
+        );
+    }
+
+    #[test]
+    fn test_route_args() {
+        let config = config(Some("10.0.0.1"));
+
+        assert_eq!(
+            route_args(&config, &config.routes[0]).join(" "),
+            "-4 route replace 0.0.0.0/0 via 10.0.0.1 dev snx-tun"
+        );
+        assert_eq!(
+            route_args(&config, &config.routes[1]).join(" "),
+            "-6 route replace ::/0 dev snx-tun"
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! The nftables table shared by the kill switch, the MSS clamping and the DNS redirection. Each feature owns its
+//! chains and sets and replaces only them, so that all can be enabled and removed independently.
+
+use std::{fmt::Write, io, process::Stdio};
+
+use anyhow::Context;
+use tokio::io::AsyncWriteExt;
+
+// Own table, the user rules are never touched. Declaring an object before deleting it
+// makes both operations succeed whether or not it already exists.
+pub(super) const NFT_TABLE: &str = "inet snx-rs";
+
+/// Start of a ruleset which replaces the given chains and interval sets of the shared table.
+pub(super) fn begin_ruleset(chains: &[&str], sets: &[(&str, &str)]) -> String {
+    let mut ruleset = format!("add table {NFT_TABLE}\n");
+
+    // the chains are deleted first, their rules may reference the sets
+    for chain in chains {
+        let _ = writeln!(ruleset, "add chain {NFT_TABLE} {chain}");
+        let _ = writeln!(ruleset, "delete chain {NFT_TABLE} {chain}");
+    }
+
+    for (set, addr_type) in sets {
+        let _ = writeln!(
+            ruleset,
+            "add set {NFT_TABLE} {set} {{ type {addr_type}; flags interval; }}"
+        );
+        let _ = writeln!(ruleset, "delete set {NFT_TABLE} {set}");
+    }
+
+    ruleset
+}
+
+/// Ruleset which removes the whole table with the objects of all features.
+pub(super) fn delete_table_ruleset() -> String {
+    format!("table {NFT_TABLE}\ndelete table {NFT_TABLE}\n")
+}
+
+/// Apply the ruleset, the error of a missing nft utility is translated by the caller.
+pub(super) async fn run_nft(ruleset: &str, unavailable: fn(io::Error) -> anyhow::Error) -> anyhow::Result<()> {
+    let mut child = tokio::process::Command::new("nft")
+        .args(["-f", "-"])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(unavailable)?;
+
+    child
+        .stdin
+        .take()
+        .context("No stdin")?
+        .write_all(ruleset.as_bytes())
+        .await?;
+
+    let output = child.wait_with_output().await?;
+    anyhow::ensure!(
+        output.status.success(),
+        "nft exited with {}: {}",
+        output.status,
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_begin_ruleset() {
+        let ruleset = begin_ruleset(&["output"], &[("allowed4", "ipv4_addr")]);
+
+        assert_eq!(
+            ruleset,
+            "add table inet snx-rs\n\
+             add chain inet snx-rs output\n\
+             delete chain inet snx-rs output\n\
+             add set inet snx-rs allowed4 { type ipv4_addr; flags interval; }\n\
+             delete set inet snx-rs allowed4\n"
+        );
+    }
+}
@@ -0,0 +1,200 @@
+//! NetworkManager integration over D-Bus: the tun device is left unmanaged and the connectivity state
+//! is refreshed when the tunnel goes up or down.
+
+use std::{
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use anyhow::Context;
+use futures::StreamExt;
+use tracing::{debug, warn};
+use zbus::{Connection, fdo::DBusProxy, names::BusName, zvariant::OwnedObjectPath};
+
+use crate::model::params::NetworkManagerMode;
+
+const NM_SERVICE: &str = "org.freedesktop.NetworkManager";
+
+// NetworkManager picks up a new device asynchronously
+const DEVICE_LOOKUP_ATTEMPTS: u32 = 10;
+const DEVICE_LOOKUP_DELAY: Duration = Duration::from_millis(200);
+
+static MODE: Mutex<NetworkManagerMode> = Mutex::new(NetworkManagerMode::Auto);
+
+pub fn set_mode(mode: NetworkManagerMode) {
+    *MODE.lock().unwrap() = mode;
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum NetworkManagerState {
+    Unknown,
+    Asleep,
+    Disconnected,
+    Disconnecting,
+    Connecting,
+    ConnectedLocal,
+    ConnectedSite,
+    ConnectedGlobal,
+}
+
+impl From<u32> for NetworkManagerState {
+    fn from(value: u32) -> Self {
+        match value {
+            10 => Self::Asleep,
+            20 => Self::Disconnected,
+            30 => Self::Disconnecting,
+            40 => Self::Connecting,
+            50 => Self::ConnectedLocal,
+            60 => Self::ConnectedSite,
+            70 => Self::ConnectedGlobal,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl NetworkManagerState {
+    fn is_online(self) -> bool {
+        matches!(self, Self::ConnectedGlobal)
+    }
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.NetworkManager",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager"
+)]
+trait NetworkManager {
+    #[zbus(property)]
+    fn state(&self) -> zbus::Result<u32>;
+
+    fn get_device_by_ip_iface(&self, iface: &str) -> zbus::Result<OwnedObjectPath>;
+
+    fn check_connectivity(&self) -> zbus::Result<u32>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.NetworkManager.Device",
+    default_service = "org.freedesktop.NetworkManager"
+)]
+trait Device {
+    #[zbus(property)]
+    fn managed(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn set_managed(&self, managed: bool) -> zbus::Result<()>;
+}
+
+async fn is_running(connection: &Connection) -> bool {
+    let Ok(proxy) = DBusProxy::new(connection).await else {
+        return false;
+    };
+    let Ok(name) = BusName::try_from(NM_SERVICE) else {
+        return false;
+    };
+    proxy.name_has_owner(name).await.unwrap_or_default()
+}
+
+// None when the integration is disabled or NetworkManager is not running.
+async fn connect() -> Option<Connection> {
+    let mode = *MODE.lock().unwrap();
+    if mode == NetworkManagerMode::Off {
+        return None;
+    }
+
+    let connection = Connection::system().await.ok()?;
+    if is_running(&connection).await {
+        Some(connection)
+    } else {
+        if mode == NetworkManagerMode::On {
+            warn!("NetworkManager integration is enabled but NetworkManager is not running");
+        }
+        None
+    }
+}
+
+async fn find_device(proxy: &NetworkManagerProxy<'_>, device_name: &str) -> anyhow::Result<OwnedObjectPath> {
+    let mut attempt = 1;
+    loop {
+        match proxy.get_device_by_ip_iface(device_name).await {
+            Ok(path) => return Ok(path),
+            Err(e) if attempt >= DEVICE_LOOKUP_ATTEMPTS => {
+                return Err(e).with_context(|| format!("NetworkManager does not know {device_name}"));
+            }
+            Err(_) => {
+                attempt += 1;
+                tokio::time::sleep(DEVICE_LOOKUP_DELAY).await;
+            }
+        }
+    }
+}
+
+/// Tell NetworkManager to leave the device alone so that it does not fight over its addresses, routes and DNS.
+pub async fn set_unmanaged(device_name: &str) -> anyhow::Result<()> {
+    let Some(connection) = connect().await else {
+        return Ok(());
+    };
+
+    let proxy = NetworkManagerProxy::new(&connection).await?;
+    let path = find_device(&proxy, device_name).await?;
+    let device = DeviceProxy::builder(&connection).path(path)?.build().await?;
+
+    if device.managed().await? {
+        debug!("Setting {} as unmanaged by NetworkManager", device_name);
+        device.set_managed(false).await?;
+    }
+
+    Ok(())
+}
+
+/// Ask NetworkManager to re-check the connectivity, otherwise it may report limited connectivity until its next
+/// periodic check.
+pub async fn check_connectivity() {
+    let Some(connection) = connect().await else {
+        return;
+    };
+
+    let result = match NetworkManagerProxy::new(&connection).await {
+        Ok(proxy) => proxy.check_connectivity().await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(state) => debug!("NetworkManager connectivity: {}", state),
+        Err(e) => debug!("Unable to check NetworkManager connectivity: {}", e),
+    }
+}
+
+/// Follow the NetworkManager state changes, the flag is set when the global connectivity is reached.
+pub async fn monitor_state(online: &'static AtomicBool) -> anyhow::Result<()> {
+    let connection = Connection::system().await?;
+    let proxy = NetworkManagerProxy::new(&connection).await?;
+
+    let mut stream = proxy.receive_state_changed().await;
+    tokio::spawn(async move {
+        while let Some(signal) = stream.next().await {
+            let state: NetworkManagerState = signal.get().await?.into();
+            debug!("NetworkManager state changed to {:?}", state);
+            online.store(state.is_online(), Ordering::SeqCst);
+        }
+
+        Ok::<_, zbus::Error>(())
+    });
+
+    Ok(())
+}
+
+/// Query the current NetworkManager state once in the background.
+pub fn poll_state(online: &'static AtomicBool) {
+    tokio::spawn(async move {
+        let connection = Connection::system().await?;
+        let proxy = NetworkManagerProxy::new(&connection).await?;
+        let state = proxy.state().await?;
+        let state: NetworkManagerState = state.into();
+        debug!("Acquired network state via polling: {:?}", state);
+        online.store(state.is_online(), Ordering::SeqCst);
+        Ok::<_, anyhow::Error>(())
+    });
+}
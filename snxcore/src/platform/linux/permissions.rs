@@ -0,0 +1,226 @@
+//! Startup check of the privileges needed by the selected options, so that an unprivileged run is reported
+//! with the remedies before the authentication instead of failing with EPERM inside the device setup.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use i18n::tr;
+use nix::{
+    errno::Errno,
+    unistd::{AccessFlags, access},
+};
+use tracing::debug;
+
+use crate::{
+    error::PermissionDenied,
+    model::params::{Ipv6BlockMode, TunnelParams, TunnelType},
+    platform::linux::resolver::{self, DnsTarget},
+};
+
+pub(super) const CAP_NET_ADMIN: u32 = 12;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Missing {
+    NetAdmin,
+    ResolvConf(PathBuf),
+    ReadOnly(PathBuf),
+    ResolvconfTool,
+    Nft,
+}
+
+impl Missing {
+    fn remedy(&self) -> String {
+        match self {
+            Self::NetAdmin => {
+                let exe = env::current_exe()
+                    .map(|exe| exe.display().to_string())
+                    .unwrap_or_else(|_| "snx-rs".to_owned());
+                tr!("privilege-net-admin", exe = exe)
+            }
+            Self::ResolvConf(path) => tr!("privilege-resolv-conf", path = path.display().to_string()),
+            Self::ReadOnly(path) => tr!("error-resolv-conf-read-only", path = path.display().to_string()),
+            Self::ResolvconfTool => tr!("privilege-resolvconf-tool"),
+            Self::Nft => tr!("privilege-nft"),
+        }
+    }
+}
+
+struct Privileges {
+    root: bool,
+    net_admin: bool,
+    nft: bool,
+}
+
+impl Privileges {
+    fn current() -> Self {
+        Self {
+            root: nix::unistd::geteuid().is_root(),
+            net_admin: fs::read_to_string("/proc/self/status")
+                .ok()
+                .and_then(|status| effective_capabilities(&status))
+                .is_some_and(|caps| caps & (1 << CAP_NET_ADMIN) != 0),
+            nft: is_in_path("nft"),
+        }
+    }
+}
+
+fn effective_capabilities(status: &str) -> Option<u64> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("CapEff:"))
+        .and_then(|caps| u64::from_str_radix(caps.trim(), 16).ok())
+}
+
+fn is_in_path(name: &str) -> bool {
+    env::var_os("PATH").is_some_and(|path| env::split_paths(&path).any(|dir| dir.join(name).is_file()))
+}
+
+// Only a pre-created device with everything else left to the helper works without CAP_NET_ADMIN.
+pub(super) fn needs_net_admin(params: &TunnelParams, dns: &DnsTarget) -> bool {
+    params.tun_fd.is_none()
+        || !params.no_device_config
+        || !params.no_routing
+        || params.kill_switch
+        || params.forward_source.is_some()
+        || params.block_ipv6 != Ipv6BlockMode::Off
+        || params.tunnel_type == TunnelType::Ipsec
+        || params.netns.is_some()
+        || params.dns_redirect
+        || *dns == DnsTarget::Resolved
+}
+
+fn find_missing<F>(params: &TunnelParams, dns: &DnsTarget, privileges: &Privileges, check_writable: F) -> Vec<Missing>
+where
+    F: Fn(&Path) -> nix::Result<()>,
+{
+    let mut missing = Vec::new();
+
+    if needs_net_admin(params, dns) && !privileges.net_admin {
+        missing.push(Missing::NetAdmin);
+    }
+
+    match dns {
+        DnsTarget::File(path) => match check_writable(path) {
+            Ok(()) => {}
+            Err(Errno::EROFS) => missing.push(Missing::ReadOnly(path.clone())),
+            Err(_) => missing.push(Missing::ResolvConf(path.clone())),
+        },
+        DnsTarget::ResolvconfTool if !privileges.root => missing.push(Missing::ResolvconfTool),
+        _ => {}
+    }
+
+    if (params.kill_switch || params.forward_source.is_some() || params.app_routing_enabled() || params.dns_redirect)
+        && !privileges.nft
+    {
+        missing.push(Missing::Nft);
+    }
+
+    missing
+}
+
+// A file which does not exist yet, like `dns-resolv-path` in an empty volume, is created in its directory.
+fn check_writable(path: &Path) -> nix::Result<()> {
+    match access(path, AccessFlags::W_OK) {
+        Err(Errno::ENOENT) => access(path.parent().unwrap_or(Path::new("/")), AccessFlags::W_OK),
+        result => result,
+    }
+}
+
+/// Report all missing privileges of the selected options in a single error.
+pub fn check_permissions(params: &TunnelParams) -> anyhow::Result<()> {
+    let dns = resolver::dns_target(params);
+    let missing = find_missing(params, &dns, &Privileges::current(), check_writable);
+
+    if missing.is_empty() {
+        debug!("Permission check passed");
+        return Ok(());
+    }
+
+    let mut message = tr!("error-missing-privileges");
+    for item in &missing {
+        message.push_str("\n  - ");
+        message.push_str(&item.remedy());
+    }
+
+    Err(PermissionDenied(message).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNPRIVILEGED: Privileges = Privileges {
+        root: false,
+        net_admin: false,
+        nft: false,
+    };
+
+    #[test]
+    fn test_effective_capabilities() {
+        let status = "Name:\tsnx-rs\nCapInh:\t0000000000000000\nCapEff:\t0000000000001000\n";
+        assert_eq!(effective_capabilities(status), Some(1 << CAP_NET_ADMIN));
+        assert_eq!(effective_capabilities("Name:\tsnx-rs\n"), None);
+    }
+
+    #[test]
+    fn test_find_missing() {
+        let params = TunnelParams {
+            kill_switch: true,
+            ..Default::default()
+        };
+        let dns = DnsTarget::File("/etc/resolv.conf".into());
+
+        assert_eq!(
+            find_missing(&params, &dns, &UNPRIVILEGED, |_| Err(Errno::EACCES)),
+            vec![
+                Missing::NetAdmin,
+                Missing::ResolvConf("/etc/resolv.conf".into()),
+                Missing::Nft
+            ]
+        );
+
+        let privileges = Privileges {
+            root: true,
+            net_admin: true,
+            nft: true,
+        };
+        assert!(find_missing(&params, &dns, &privileges, |_| Ok(())).is_empty());
+
+        // a read-only mount of the container runtime is not a matter of privileges
+        assert_eq!(
+            find_missing(&params, &dns, &privileges, |_| Err(Errno::EROFS)),
+            vec![Missing::ReadOnly("/etc/resolv.conf".into())]
+        );
+
+        let privileges = Privileges {
+            net_admin: true,
+            ..UNPRIVILEGED
+        };
+        assert_eq!(
+            find_missing(&params, &DnsTarget::ResolvconfTool, &privileges, |_| Ok(())),
+            vec![Missing::ResolvconfTool, Missing::Nft]
+        );
+    }
+
+    #[test]
+    fn test_find_missing_passed_device() {
+        // the helper has set up the device, routes and DNS
+        let params = TunnelParams {
+            tunnel_type: TunnelType::Ssl,
+            tun_fd: Some(3),
+            no_device_config: true,
+            no_routing: true,
+            no_dns: true,
+            ..Default::default()
+        };
+        assert!(find_missing(&params, &DnsTarget::None, &UNPRIVILEGED, |_| Err(Errno::EACCES)).is_empty());
+
+        // systemd-resolved accepts the link changes from CAP_NET_ADMIN only
+        assert_eq!(
+            find_missing(&params, &DnsTarget::Resolved, &UNPRIVILEGED, |_| Err(Errno::EACCES)),
+            vec![Missing::NetAdmin]
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Polkit authorization of the command mode service requests, the caller is identified by its process.
+
+use std::collections::HashMap;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use tracing::debug;
+use zbus::{Connection, zvariant};
+
+use crate::platform::{Authority, PeerCredentials};
+
+pub const POLKIT_ALLOW_USER_INTERACTION: u32 = 1;
+
+#[zbus::proxy(
+    interface = "org.freedesktop.PolicyKit1.Authority",
+    default_service = "org.freedesktop.PolicyKit1",
+    default_path = "/org/freedesktop/PolicyKit1/Authority"
+)]
+pub trait PolkitAuthority {
+    fn check_authorization(
+        &self,
+        subject: &(&str, HashMap<&str, zvariant::Value<'_>>),
+        action_id: &str,
+        details: &HashMap<&str, &str>,
+        flags: u32,
+        cancellation_id: &str,
+    ) -> zbus::Result<(bool, bool, HashMap<String, String>)>;
+}
+
+// polkit looks up the start time itself when it is zero
+fn process_subject(pid: i32, uid: u32) -> (&'static str, HashMap<&'static str, zvariant::Value<'static>>) {
+    (
+        "unix-process",
+        HashMap::from([
+            ("pid", zvariant::Value::from(pid as u32)),
+            ("start-time", zvariant::Value::from(0u64)),
+            ("uid", zvariant::Value::from(uid as i32)),
+        ]),
+    )
+}
+
+#[derive(Default)]
+pub struct PolkitProcessAuthority;
+
+impl PolkitProcessAuthority {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Authority for PolkitProcessAuthority {
+    async fn check_authorization(&self, peer: PeerCredentials, action_id: &str) -> anyhow::Result<bool> {
+        let pid = peer.pid.context("No process ID of the peer")?;
+
+        let connection = Connection::system().await?;
+        let authority = PolkitAuthorityProxy::new(&connection).await?;
+        let (authorized, _, _) = authority
+            .check_authorization(
+                &process_subject(pid, peer.uid),
+                action_id,
+                &HashMap::new(),
+                POLKIT_ALLOW_USER_INTERACTION,
+                "",
+            )
+            .await?;
+
+        debug!("Polkit authorization of {} for pid {}: {}", action_id, pid, authorized);
+
+        Ok(authorized)
+    }
+}
@@ -0,0 +1,227 @@
+//! Switch of the standalone tunnel to an unprivileged user once the network is set up. CAP_NET_ADMIN is the
+//! only capability kept, and only when the selected options need it for the reconnects and for the cleanup.
+//!
+//! The capabilities are per thread: the drop must run on the thread which drives the tunnel, the other threads
+//! lose all of them with the switch of the user ids.
+
+use std::{
+    fs::{self, OpenOptions},
+    io,
+    os::unix::fs::OpenOptionsExt,
+    path::Path,
+};
+
+use anyhow::anyhow;
+use i18n::tr;
+use nix::unistd::{Gid, Group, Uid, User};
+use tracing::{debug, warn};
+
+use crate::{
+    model::params::TunnelParams,
+    platform::linux::{
+        permissions::{self, CAP_NET_ADMIN},
+        resolver::{self, DnsTarget},
+    },
+    tunnel,
+};
+
+const LINUX_CAPABILITY_VERSION_3: u32 = 0x2008_0522;
+
+#[repr(C)]
+struct CapHeader {
+    version: u32,
+    pid: libc::c_int,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct CapData {
+    effective: u32,
+    permitted: u32,
+    inheritable: u32,
+}
+
+// Numeric ids must exist in the user database as well, the primary group is taken from there.
+fn find_user(name: &str) -> anyhow::Result<User> {
+    let user = match name.parse() {
+        Ok(uid) => User::from_uid(Uid::from_raw(uid)),
+        Err(_) => User::from_name(name),
+    };
+    user.ok()
+        .flatten()
+        .ok_or_else(|| anyhow!(tr!("error-unknown-run-as", name = name)))
+}
+
+fn find_group(name: &str) -> anyhow::Result<Gid> {
+    if let Ok(gid) = name.parse() {
+        return Ok(Gid::from_raw(gid));
+    }
+    Group::from_name(name)
+        .ok()
+        .flatten()
+        .map(|group| group.gid)
+        .ok_or_else(|| anyhow!(tr!("error-unknown-run-as", name = name)))
+}
+
+fn target_ids(params: &TunnelParams) -> anyhow::Result<Option<(Uid, Gid)>> {
+    let Some(ref name) = params.run_as_user else {
+        return Ok(None);
+    };
+
+    let user = find_user(name)?;
+    let gid = match params.run_as_group {
+        Some(ref group) => find_group(group)?,
+        None => user.gid,
+    };
+
+    Ok(Some((user.uid, gid)))
+}
+
+// Restoring resolv.conf and calling the resolvconf tool need root, CAP_NET_ADMIN is not enough for them.
+fn needs_root(dns: &DnsTarget) -> bool {
+    matches!(dns, DnsTarget::File(_) | DnsTarget::ResolvconfTool)
+}
+
+/// Validate the target user and the selected options before connecting, no-op without `run-as-user`.
+pub fn check_privilege_drop(params: &TunnelParams) -> anyhow::Result<()> {
+    if target_ids(params)?.is_none() {
+        return Ok(());
+    }
+
+    anyhow::ensure!(nix::unistd::geteuid().is_root(), tr!("error-run-as-not-root"));
+    anyhow::ensure!(!needs_root(&resolver::dns_target(params)), tr!("error-run-as-dns"));
+
+    Ok(())
+}
+
+fn check_errno(result: libc::c_long) -> io::Result<()> {
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+fn set_capabilities(caps: u64) -> io::Result<()> {
+    let header = CapHeader {
+        version: LINUX_CAPABILITY_VERSION_3,
+        pid: 0,
+    };
+    let data = [caps as u32, (caps >> 32) as u32].map(|bits| CapData {
+        effective: bits,
+        permitted: bits,
+        inheritable: bits,
+    });
+
+    check_errno(unsafe { libc::syscall(libc::SYS_capset, &header, data.as_ptr()) })
+}
+
+// The state files are created in advance, the directories they are in stay owned by root.
+fn chown_state_file(path: &Path, uid: Uid, gid: Gid) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    OpenOptions::new().append(true).create(true).mode(0o600).open(path)?;
+    std::os::unix::fs::chown(path, Some(uid.as_raw()), Some(gid.as_raw()))
+}
+
+fn switch_user(uid: Uid, gid: Gid, keep_net_admin: bool) -> io::Result<()> {
+    unsafe {
+        if keep_net_admin {
+            check_errno(libc::prctl(libc::PR_SET_KEEPCAPS, 1, 0, 0, 0).into())?;
+        }
+
+        // glibc applies the id changes to all threads of the process
+        check_errno(libc::setgroups(0, std::ptr::null()).into())?;
+        check_errno(libc::setresgid(gid.as_raw(), gid.as_raw(), gid.as_raw()).into())?;
+        check_errno(libc::setresuid(uid.as_raw(), uid.as_raw(), uid.as_raw()).into())?;
+
+        if keep_net_admin {
+            set_capabilities(1 << CAP_NET_ADMIN)?;
+            check_errno(libc::prctl(libc::PR_SET_KEEPCAPS, 0, 0, 0, 0).into())?;
+
+            // inherited by the nft, ip and resolvectl invocations
+            check_errno(
+                libc::prctl(
+                    libc::PR_CAP_AMBIENT,
+                    libc::PR_CAP_AMBIENT_RAISE,
+                    CAP_NET_ADMIN as libc::c_ulong,
+                    0,
+                    0,
+                )
+                .into(),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Switch to `run-as-user` after the network setup. Repeated calls after the switch are no-ops,
+/// the reconnects run with the retained capability.
+pub fn drop_privileges(params: &TunnelParams) -> anyhow::Result<()> {
+    let Some((uid, gid)) = target_ids(params)? else {
+        return Ok(());
+    };
+
+    if nix::unistd::geteuid() == uid {
+        return Ok(());
+    }
+
+    for path in tunnel::state_files(params) {
+        if let Err(e) = chown_state_file(&path, uid, gid) {
+            warn!("Unable to hand over {} to the unprivileged user: {}", path.display(), e);
+        }
+    }
+
+    let keep_net_admin = permissions::needs_net_admin(params, &resolver::dns_target(params));
+
+    switch_user(uid, gid, keep_net_admin).map_err(|e| anyhow!(tr!("error-privilege-drop", error = e.to_string())))?;
+
+    // root must not be recoverable, neither through the saved ids nor through the capabilities
+    if unsafe { libc::setuid(0) } == 0 {
+        return Err(anyhow!(tr!("error-privilege-drop", error = "root is still available")));
+    }
+
+    debug!(
+        "Switched to uid {} gid {}, CAP_NET_ADMIN retained: {}",
+        uid, gid, keep_net_admin
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_needs_root() {
+        assert!(needs_root(&DnsTarget::File("/etc/resolv.conf".into())));
+        assert!(needs_root(&DnsTarget::ResolvconfTool));
+        assert!(!needs_root(&DnsTarget::Resolved));
+        assert!(!needs_root(&DnsTarget::None));
+    }
+
+    #[test]
+    fn test_target_ids() {
+        assert_eq!(target_ids(&TunnelParams::default()).unwrap(), None);
+
+        let params = TunnelParams {
+            run_as_user: Some("root".to_owned()),
+            run_as_group: Some("4242".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(
+            target_ids(&params).unwrap(),
+            Some((Uid::from_raw(0), Gid::from_raw(4242)))
+        );
+
+        let params = TunnelParams {
+            run_as_user: Some("no-such-user-snx-rs".to_owned()),
+            ..Default::default()
+        };
+        assert!(target_ids(&params).is_err());
+    }
+}
@@ -1,66 +1,283 @@
-use std::{fs, io::Write, path::PathBuf};
+#[cfg(feature = "dbus")]
+use std::ffi::CString;
+use std::{fs, io::Write, net::IpAddr, path::PathBuf};
 
-use anyhow::Context;
+use anyhow::{Context, anyhow};
 use async_trait::async_trait;
 use cached::proc_macro::cached;
-use tracing::debug;
-
-use crate::platform::{ResolverConfig, ResolverConfigurator};
-
-const RESOLV_CONF: &str = "/etc/resolv.conf";
+use tracing::{debug, warn};
+#[cfg(feature = "dbus")]
+use zbus::Connection;
+
+use crate::{
+    model::params::{DnsBackend, DnsMode, TunnelParams},
+    platform::{
+        PrintConfigurator, ResolverConfig, ResolverConfigurator,
+        resolv_conf::{
+            DirectConfigurator, RESOLV_CONF, ResolvconfToolConfigurator, explain_read_only, is_resolvconf_managed,
+            resolv_conf_path, resolvconf_flavor,
+        },
+    },
+};
+
+const ORIGINAL_SEARCH_MARKER: &str = "# snx-rs original search:";
+const RESOLVED_STUB_ADDRESSES: &[&str] = &["127.0.0.53", "127.0.0.54"];
+
+#[cfg(feature = "dbus")]
+#[zbus::proxy(
+    interface = "org.freedesktop.resolve1.Manager",
+    default_service = "org.freedesktop.resolve1",
+    default_path = "/org/freedesktop/resolve1"
+)]
+trait ResolvedManager {
+    #[zbus(name = "SetLinkDNS")]
+    fn set_link_dns(&self, ifindex: i32, addresses: &[(i32, Vec<u8>)]) -> zbus::Result<()>;
+
+    fn set_link_domains(&self, ifindex: i32, domains: &[(&str, bool)]) -> zbus::Result<()>;
+
+    fn set_link_default_route(&self, ifindex: i32, enable: bool) -> zbus::Result<()>;
+
+    fn revert_link(&self, ifindex: i32) -> zbus::Result<()>;
+}
 
 #[derive(Clone, Debug, PartialEq)]
 enum ResolverType {
     SystemdResolved,
+    ResolvconfTool,
     ResolvConf(PathBuf),
 }
 
 struct SystemdResolvedConfigurator {
     device: String,
+    mode: DnsMode,
 }
 
-#[async_trait]
-impl ResolverConfigurator for SystemdResolvedConfigurator {
-    async fn configure(&self, config: &ResolverConfig) -> anyhow::Result<()> {
-        let mut args = vec!["domain", &self.device];
+#[cfg(feature = "dbus")]
+impl SystemdResolvedConfigurator {
+    fn ifindex(&self) -> anyhow::Result<i32> {
+        let name = CString::new(self.device.as_str())?;
+        let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+        if index == 0 {
+            Err(anyhow!("No such network interface: {}", self.device))
+        } else {
+            Ok(index as i32)
+        }
+    }
 
-        let search_domains = config
-            .search_domains
+    async fn proxy(&self) -> anyhow::Result<ResolvedManagerProxy<'static>> {
+        let connection = Connection::system().await?;
+        Ok(ResolvedManagerProxy::new(&connection).await?)
+    }
+
+    async fn set_link(&self, domains: &[(&str, bool)], servers: &[IpAddr]) -> anyhow::Result<()> {
+        let ifindex = self.ifindex()?;
+        let proxy = self.proxy().await?;
+
+        let servers = servers
             .iter()
-            .map(|s| s.trim_matches(|c: char| c.is_whitespace() || c == '.'))
-            .filter(|s| !s.is_empty())
+            .map(|s| match s {
+                IpAddr::V4(v4) => (libc::AF_INET, v4.octets().to_vec()),
+                IpAddr::V6(v6) => (libc::AF_INET6, v6.octets().to_vec()),
+            })
             .collect::<Vec<_>>();
 
-        args.extend(search_domains);
+        proxy.set_link_domains(ifindex, domains).await?;
+        proxy
+            .set_link_default_route(ifindex, self.mode == DnsMode::Full)
+            .await?;
+        proxy.set_link_dns(ifindex, &servers).await?;
 
-        crate::util::run_command("resolvectl", args).await?;
-        crate::util::run_command("resolvectl", ["default-route", &self.device, "false"]).await?;
+        Ok(())
+    }
 
-        let mut args = vec!["dns".to_owned(), self.device.clone()];
+    async fn revert_link(&self) -> anyhow::Result<()> {
+        let ifindex = self.ifindex()?;
+        self.proxy().await?.revert_link(ifindex).await?;
+        Ok(())
+    }
+}
 
-        let servers = config.dns_servers.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+// Builds without D-Bus support drive systemd-resolved with resolvectl instead.
+#[cfg(not(feature = "dbus"))]
+impl SystemdResolvedConfigurator {
+    async fn set_link(&self, domains: &[(&str, bool)], servers: &[IpAddr]) -> anyhow::Result<()> {
+        let mut args = vec!["domain".to_owned(), self.device.clone()];
+        args.extend(domains.iter().map(|(domain, routing)| {
+            if *routing {
+                format!("~{domain}")
+            } else {
+                (*domain).to_owned()
+            }
+        }));
+        crate::util::run_command("resolvectl", args).await?;
 
-        args.extend(servers);
+        let default_route = if self.mode == DnsMode::Full { "true" } else { "false" };
+        crate::util::run_command("resolvectl", ["default-route", &self.device, default_route]).await?;
 
+        let mut args = vec!["dns".to_owned(), self.device.clone()];
+        args.extend(servers.iter().map(|s| s.to_string()));
         crate::util::run_command("resolvectl", args).await?;
 
         Ok(())
     }
 
-    async fn cleanup(&self, _config: &ResolverConfig) -> anyhow::Result<()> {
+    async fn revert_link(&self) -> anyhow::Result<()> {
+        crate::util::run_command("resolvectl", ["revert", &self.device]).await?;
         Ok(())
     }
 }
 
-pub fn new_resolver_configurator<S>(device: S) -> anyhow::Result<Box<dyn ResolverConfigurator + Send + Sync>>
+// Domains prefixed with '~' are routing-only domains, they are not used for the search list.
+fn link_domains(config: &ResolverConfig) -> Vec<(String, bool)> {
+    config
+        .search_domains
+        .iter()
+        .map(|s| s.trim_matches(|c: char| c.is_whitespace() || c == '.'))
+        .filter(|s| !s.is_empty())
+        .map(|s| match s.strip_prefix('~') {
+            Some(domain) => (domain.trim_start_matches('.').to_owned(), true),
+            None => (s.to_owned(), false),
+        })
+        .collect()
+}
+
+#[async_trait]
+impl ResolverConfigurator for SystemdResolvedConfigurator {
+    async fn configure(&self, config: &ResolverConfig) -> anyhow::Result<()> {
+        let mut domains = link_domains(config);
+
+        // in full mode the root routing domain makes the link receive all queries
+        if self.mode == DnsMode::Full {
+            domains.push((".".to_owned(), true));
+        }
+
+        let domains = domains.iter().map(|(d, r)| (d.as_str(), *r)).collect::<Vec<_>>();
+
+        debug!(
+            "Configuring systemd-resolved for link {}, servers: {:?}, domains: {:?}",
+            self.device, config.dns_servers, domains
+        );
+
+        self.set_link(&domains, &config.dns_servers).await
+    }
+
+    async fn cleanup(&self, _config: &ResolverConfig) -> anyhow::Result<()> {
+        debug!("Reverting systemd-resolved configuration for link {}", self.device);
+        self.revert_link().await
+    }
+}
+
+pub fn new_resolver_configurator<S>(
+    device: S,
+    params: &TunnelParams,
+) -> anyhow::Result<Box<dyn ResolverConfigurator + Send + Sync>>
 where
     S: AsRef<str>,
 {
-    match detect_resolver(RESOLV_CONF.into())? {
-        ResolverType::SystemdResolved => Ok(Box::new(SystemdResolvedConfigurator {
-            device: device.as_ref().to_owned(),
-        })),
-        ResolverType::ResolvConf(path) => Ok(Box::new(ResolvConfConfigurator { config_path: path })),
+    let device = device.as_ref().to_owned();
+    let mode = params.dns_mode;
+
+    // auto detection order: systemd-resolved, resolvconf, direct file, none
+    match params.dns_backend {
+        DnsBackend::Auto => match detect_resolver(RESOLV_CONF.into()) {
+            Ok(ResolverType::SystemdResolved) => Ok(Box::new(SystemdResolvedConfigurator { device, mode })),
+            Ok(ResolverType::ResolvconfTool) => Ok(Box::new(ResolvconfToolConfigurator::new(&device))),
+            Ok(ResolverType::ResolvConf(path)) => Ok(Box::new(ResolvConfConfigurator { config_path: path })),
+            Err(e) => {
+                warn!("Unable to detect the resolver, DNS will not be configured: {}", e);
+                Ok(Box::new(NoopConfigurator))
+            }
+        },
+        DnsBackend::Resolved => Ok(Box::new(SystemdResolvedConfigurator { device, mode })),
+        DnsBackend::ResolvConf => Ok(Box::new(ResolvconfToolConfigurator::new(&device))),
+        DnsBackend::Direct => Ok(Box::new(
+            DirectConfigurator::new(resolv_conf_path(params)).with_profile(params.profile_key()),
+        )),
+        DnsBackend::None => Ok(Box::new(NoopConfigurator)),
+        DnsBackend::Print => Ok(Box::new(PrintConfigurator)),
+    }
+}
+
+/// DNS mode which is actually applied: split DNS is only possible with systemd-resolved,
+/// other backends replace the system resolvers.
+pub fn effective_dns_mode(params: &TunnelParams) -> DnsMode {
+    let resolved = match params.dns_backend {
+        DnsBackend::Auto => detect_resolver(RESOLV_CONF.into()).is_ok_and(|r| r == ResolverType::SystemdResolved),
+        DnsBackend::Resolved => true,
+        _ => false,
+    };
+
+    if resolved || params.dns_mode == DnsMode::Full {
+        params.dns_mode
+    } else {
+        warn!("Split DNS requires systemd-resolved, all DNS queries will be sent to the tunnel resolvers");
+        DnsMode::Full
+    }
+}
+
+/// DNS backend selected by the parameters, the detected one for `auto`.
+pub fn dns_backend_name(params: &TunnelParams) -> String {
+    match params.dns_backend {
+        DnsBackend::Auto => match detect_resolver(RESOLV_CONF.into()) {
+            Ok(ResolverType::SystemdResolved) => "auto: systemd-resolved".to_owned(),
+            Ok(ResolverType::ResolvconfTool) => "auto: resolvconf".to_owned(),
+            Ok(ResolverType::ResolvConf(path)) => format!("auto: {}", path.display()),
+            Err(_) => "auto: none detected".to_owned(),
+        },
+        DnsBackend::Direct => format!("direct: {}", resolv_conf_path(params).display()),
+        backend => backend.to_string(),
+    }
+}
+
+/// What the selected DNS backend modifies, used by the permission check.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum DnsTarget {
+    None,
+    Resolved,
+    ResolvconfTool,
+    File(PathBuf),
+}
+
+pub(super) fn dns_target(params: &TunnelParams) -> DnsTarget {
+    if params.no_dns {
+        return DnsTarget::None;
+    }
+
+    match params.dns_backend {
+        DnsBackend::Auto => match detect_resolver(RESOLV_CONF.into()) {
+            Ok(ResolverType::SystemdResolved) => DnsTarget::Resolved,
+            Ok(ResolverType::ResolvconfTool) => DnsTarget::ResolvconfTool,
+            Ok(ResolverType::ResolvConf(path)) => DnsTarget::File(path),
+            Err(_) => DnsTarget::None,
+        },
+        DnsBackend::Resolved => DnsTarget::Resolved,
+        DnsBackend::ResolvConf => DnsTarget::ResolvconfTool,
+        DnsBackend::Direct => DnsTarget::File(resolv_conf_path(params)),
+        DnsBackend::None | DnsBackend::Print => DnsTarget::None,
+    }
+}
+
+/// Restore the resolv.conf left behind by the direct DNS backend if the previous run of the profile has crashed.
+pub fn restore_stale_resolv_conf(params: &TunnelParams) {
+    let path = resolv_conf_path(params);
+    if let Err(e) = DirectConfigurator::new(&path)
+        .with_profile(params.profile_key())
+        .restore_stale()
+    {
+        warn!("Unable to restore {}: {}", path.display(), e);
+    }
+}
+
+struct NoopConfigurator;
+
+#[async_trait]
+impl ResolverConfigurator for NoopConfigurator {
+    async fn configure(&self, _config: &ResolverConfig) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn cleanup(&self, _config: &ResolverConfig) -> anyhow::Result<()> {
+        Ok(())
     }
 }
 
@@ -98,12 +315,24 @@ fn read_symlinks(path: PathBuf, depth: u8) -> anyhow::Result<PathBuf> {
 fn detect_resolver(path: PathBuf) -> anyhow::Result<ResolverType> {
     let resolve_conf_path = read_symlinks(path, 10)?;
 
-    let result = if resolve_conf_path
+    let is_systemd_path = resolve_conf_path
         .canonicalize()?
         .components()
-        .any(|component| component.as_os_str().to_str() == Some("systemd"))
-    {
+        .any(|component| component.as_os_str().to_str() == Some("systemd"));
+
+    let conf = fs::read_to_string(&resolve_conf_path).unwrap_or_default();
+    let is_stub = is_stub_resolver(&conf);
+
+    let result = if is_stub {
+        ResolverType::SystemdResolved
+    } else if is_systemd_path {
+        warn!(
+            "{} is managed by systemd-resolved but does not point to the stub resolver, tunnel DNS servers may be bypassed",
+            resolve_conf_path.display()
+        );
         ResolverType::SystemdResolved
+    } else if is_resolvconf_managed(&conf) && resolvconf_flavor().is_some() {
+        ResolverType::ResolvconfTool
     } else {
         ResolverType::ResolvConf(resolve_conf_path)
     };
@@ -113,6 +342,13 @@ fn detect_resolver(path: PathBuf) -> anyhow::Result<ResolverType> {
     Ok(result)
 }
 
+fn is_stub_resolver(conf: &str) -> bool {
+    conf.lines().any(|line| {
+        let mut parts = line.split_whitespace();
+        parts.next() == Some("nameserver") && parts.next().is_some_and(|addr| RESOLVED_STUB_ADDRESSES.contains(&addr))
+    })
+}
+
 struct ResolvConfConfigurator {
     config_path: PathBuf,
 }
@@ -130,7 +366,18 @@ impl ResolvConfConfigurator {
 
         let other_lines = conf
             .lines()
-            .filter(|line| !line.starts_with("nameserver") && !line.starts_with("search"))
+            .filter(|line| {
+                !line.starts_with("nameserver")
+                    && !line.starts_with("search")
+                    && !line.starts_with(ORIGINAL_SEARCH_MARKER)
+            })
+            .collect::<Vec<_>>();
+
+        // pre-connect search lines saved by a previous configure call, empty marker means there were none
+        let saved_search = conf
+            .lines()
+            .filter_map(|line| line.strip_prefix(ORIGINAL_SEARCH_MARKER))
+            .map(|line| line.trim().to_owned())
             .collect::<Vec<_>>();
 
         let new_nameservers = config
@@ -139,11 +386,23 @@ impl ResolvConfConfigurator {
             .map(|s| format!("nameserver {}", s))
             .collect::<Vec<_>>();
 
-        let mut search = conf
-            .lines()
-            .filter(|line| line.starts_with("search"))
-            .map(ToOwned::to_owned)
-            .collect::<Vec<_>>();
+        let mut search = if saved_search.is_empty() {
+            conf.lines()
+                .filter(|line| line.starts_with("search"))
+                .map(ToOwned::to_owned)
+                .collect::<Vec<_>>()
+        } else {
+            saved_search.iter().filter(|s| !s.is_empty()).cloned().collect()
+        };
+
+        let original_search = if search.is_empty() {
+            vec![ORIGINAL_SEARCH_MARKER.to_owned()]
+        } else {
+            search
+                .iter()
+                .map(|s| format!("{} {}", ORIGINAL_SEARCH_MARKER, s))
+                .collect()
+        };
 
         // resolv.conf has no concept of routing domains
         let search_domains = config
@@ -151,16 +410,22 @@ impl ResolvConfConfigurator {
             .iter()
             .map(|s| s.trim_matches(|c: char| c.is_whitespace() || c == '.' || c == '~'))
             .filter(|s| !s.is_empty())
-            .collect::<Vec<_>>()
-            .join(" ");
+            .collect::<Vec<_>>();
 
         if configure {
+            let missing = search_domains
+                .iter()
+                .filter(|d| !search.iter().any(|s| s.split_whitespace().skip(1).any(|e| e == **d)))
+                .copied()
+                .collect::<Vec<_>>();
+
             if search.is_empty() {
-                search.push(format!("search {}", search_domains));
-            } else if !search.iter().any(|s| s.contains(&search_domains)) {
-                search[0] = format!("{} {}", search[0], search_domains);
+                search.push(format!("search {}", missing.join(" ")));
+            } else if !missing.is_empty() {
+                search[0] = format!("{} {}", search[0], missing.join(" "));
             }
-        } else {
+        } else if saved_search.is_empty() {
+            let search_domains = search_domains.join(" ");
             search = search
                 .into_iter()
                 .map(|s| s.replace(&search_domains, "").trim().to_owned())
@@ -171,6 +436,9 @@ impl ResolvConfConfigurator {
         let mut file = fs::File::create(&self.config_path)?;
 
         writeln!(file, "{}", other_lines.join("\n"))?;
+        if configure {
+            writeln!(file, "{}", original_search.join("\n"))?;
+        }
         writeln!(file, "{}", search.join("\n"))?;
         if configure {
             writeln!(file, "{}", new_nameservers.join("\n"))?;
@@ -184,7 +452,8 @@ impl ResolvConfConfigurator {
 #[async_trait]
 impl ResolverConfigurator for ResolvConfConfigurator {
     async fn configure(&self, config: &ResolverConfig) -> anyhow::Result<()> {
-        Ok(self.configure_or_cleanup(config, true)?)
+        self.configure_or_cleanup(config, true)
+            .map_err(|e| explain_read_only(&self.config_path, e))
     }
 
     async fn cleanup(&self, config: &ResolverConfig) -> anyhow::Result<()> {
@@ -346,6 +615,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_detect_resolver_stub_contents() {
+        let dir = tempfile::TempDir::new().unwrap();
+
+        let conf_path = dir.path().join("resolv.conf");
+        fs::write(&conf_path, "# managed\nnameserver 127.0.0.53\noptions edns0 trust-ad\n").unwrap();
+
+        let resolver = detect_resolver(conf_path).expect("Failed to detect resolver");
+        assert_eq!(resolver, ResolverType::SystemdResolved);
+    }
+
+    #[test]
+    fn test_normalize_resolver_config() {
+        let config = ResolverConfig {
+            search_domains: vec![
+                "Corp.Example.".to_owned(),
+                "~dom2.net".to_owned(),
+                "corp.example".to_owned(),
+                " ".to_owned(),
+            ],
+            dns_servers: vec![
+                "10.0.0.2".parse().unwrap(),
+                "10.0.0.1".parse().unwrap(),
+                "0.0.0.0".parse().unwrap(),
+                "10.0.0.2".parse().unwrap(),
+            ],
+            ..Default::default()
+        };
+
+        let config = config.normalized();
+        assert_eq!(config.search_domains, vec!["corp.example", "~dom2.net"]);
+        assert_eq!(
+            config.dns_servers,
+            vec!["10.0.0.2".parse::<IpAddr>().unwrap(), "10.0.0.1".parse().unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_link_domains() {
+        let config = ResolverConfig {
+            search_domains: vec!["dom1.com.".to_owned(), "~dom2.net".to_owned(), " ".to_owned()],
+            dns_servers: vec![],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            link_domains(&config),
+            vec![("dom1.com".to_owned(), false), ("dom2.net".to_owned(), true)]
+        );
+    }
+
     #[tokio::test]
     async fn test_resolv_conf_configurator_setup() {
         let conf = tempfile::NamedTempFile::new().unwrap().into_temp_path();
@@ -358,16 +678,44 @@ mod tests {
         let config = ResolverConfig {
             search_domains: vec!["dom1.com".to_owned(), "dom2.net".to_owned()],
             dns_servers: vec!["192.168.1.1".parse().unwrap(), "192.168.1.2".parse().unwrap()],
+            ..Default::default()
         };
         cut.configure(&config).await.unwrap();
 
         let new_conf = fs::read_to_string(&conf).unwrap();
         assert_eq!(
             new_conf,
-            "# comment\nsearch acme.com dom1.com dom2.net\nnameserver 192.168.1.1\nnameserver 192.168.1.2\nnameserver 10.0.0.1\n"
+            "# comment\n# snx-rs original search: search acme.com\nsearch acme.com dom1.com dom2.net\nnameserver 192.168.1.1\nnameserver 192.168.1.2\nnameserver 10.0.0.1\n"
         );
     }
 
+    #[tokio::test]
+    async fn test_resolv_conf_configurator_restores_search() {
+        let conf = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        let original = "# comment\nsearch dom1.com acme.com\nnameserver 10.0.0.1\n";
+        fs::write(&conf, original).unwrap();
+
+        let cut = ResolvConfConfigurator {
+            config_path: conf.to_owned(),
+        };
+
+        let config = ResolverConfig {
+            search_domains: vec!["dom1.com".to_owned(), "~dom2.net".to_owned()],
+            dns_servers: vec!["192.168.1.1".parse().unwrap()],
+            ..Default::default()
+        };
+
+        cut.configure(&config).await.unwrap();
+        assert!(
+            fs::read_to_string(&conf)
+                .unwrap()
+                .contains("\nsearch dom1.com acme.com dom2.net\n")
+        );
+
+        cut.cleanup(&config).await.unwrap();
+        assert_eq!(fs::read_to_string(&conf).unwrap(), original);
+    }
+
     #[tokio::test]
     async fn test_resolv_conf_configurator_cleanup() {
         let conf = tempfile::NamedTempFile::new().unwrap().into_temp_path();
@@ -380,6 +728,7 @@ mod tests {
         let config = ResolverConfig {
             search_domains: vec!["dom1.com".to_owned(), "dom2.net".to_owned()],
             dns_servers: vec!["192.168.1.1".parse().unwrap(), "192.168.1.2".parse().unwrap()],
+            ..Default::default()
         };
 
         cut.cleanup(&config).await.unwrap();
@@ -0,0 +1,252 @@
+//! Watches the route deletions through rtnetlink and re-installs the tunnel routes removed by another process,
+//! e.g. by NetworkManager or a dhclient renewal. The address and route notifications also drive the network
+//! change detection.
+
+use std::{
+    collections::HashMap,
+    fs,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::{Path, PathBuf},
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use futures::{Stream, StreamExt};
+use ipnet::IpNet;
+use rtnetlink::{
+    Handle,
+    constants::{RTMGRP_IPV4_IFADDR, RTMGRP_IPV4_ROUTE, RTMGRP_IPV6_ROUTE},
+    packet_core::{NetlinkMessage, NetlinkPayload},
+    packet_route::{
+        AddressFamily, RouteNetlinkMessage,
+        route::{RouteAddress, RouteAttribute, RouteMessage},
+    },
+    sys::{AsyncSocket, SocketAddr},
+};
+use tokio::{sync::watch, task::JoinHandle};
+use tracing::{debug, trace, warn};
+
+use crate::platform::linux::{
+    netlink::{self, RouteSpec},
+    routing,
+};
+
+// A route removed more often than this within the window is most likely removed on purpose.
+const MAX_REINSTALLS: usize = 3;
+const REINSTALL_WINDOW: Duration = Duration::from_secs(60);
+
+const RT_TABLE_MAIN: u32 = 254;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static MONITOR: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+
+pub fn set_enabled(flag: bool) {
+    ENABLED.store(flag, Ordering::SeqCst);
+}
+
+/// Start the monitor for the routes recorded in the state file, nothing is done if it is already running
+/// or disabled.
+pub fn start(state_file: &Path) {
+    if !ENABLED.load(Ordering::SeqCst) {
+        return;
+    }
+
+    let mut monitor = MONITOR.lock().unwrap();
+    if monitor.as_ref().is_some_and(|handle| !handle.is_finished()) {
+        return;
+    }
+
+    let state_file = state_file.to_owned();
+    *monitor = Some(tokio::spawn(async move {
+        if let Err(e) = run(state_file).await {
+            warn!("Route monitor stopped: {}", e);
+        }
+    }));
+}
+
+pub fn stop() {
+    if let Some(handle) = MONITOR.lock().unwrap().take() {
+        debug!("Stopping route monitor");
+        handle.abort();
+    }
+}
+
+// The handle must be kept for the lifetime of the stream, the connection task ends when it is dropped.
+fn subscribe(
+    groups: u32,
+) -> anyhow::Result<(
+    Handle,
+    impl Stream<Item = (NetlinkMessage<RouteNetlinkMessage>, SocketAddr)> + Unpin,
+)> {
+    let (mut connection, handle, messages) = rtnetlink::new_connection()?;
+    connection.socket_mut().socket_mut().bind(&SocketAddr::new(0, groups))?;
+    tokio::spawn(connection);
+    Ok((handle, messages))
+}
+
+/// Bump the counter on every IPv4 address or route notification of the kernel.
+pub async fn watch_network_changes(changes: &watch::Sender<u64>) -> anyhow::Result<()> {
+    let (_handle, mut messages) = subscribe(RTMGRP_IPV4_IFADDR | RTMGRP_IPV4_ROUTE)?;
+
+    while let Some((message, _)) = messages.next().await {
+        if let NetlinkPayload::InnerMessage(message) = message.payload {
+            trace!("Network change: {:?}", message);
+            changes.send_modify(|v| *v = v.wrapping_add(1));
+        }
+    }
+
+    Ok(())
+}
+
+async fn run(state_file: PathBuf) -> anyhow::Result<()> {
+    let (_handle, mut messages) = subscribe(RTMGRP_IPV4_ROUTE | RTMGRP_IPV6_ROUTE)?;
+
+    debug!("Route monitor started");
+
+    let ops = netlink::new_netlink_ops();
+    let mut limiter = ReinstallLimiter::default();
+
+    while let Some((message, _)) = messages.next().await {
+        let port = message.header.port_number;
+        let NetlinkPayload::InnerMessage(RouteNetlinkMessage::DelRoute(route)) = message.payload else {
+            continue;
+        };
+        let Some((destination, table)) = route_key(&route) else {
+            continue;
+        };
+
+        // our own deletions are dropped from the state before the route is removed
+        let Some(spec) = routing::owned_routes(&state_file)
+            .into_iter()
+            .find(|r| r.destination.trunc() == destination && r.table.unwrap_or(RT_TABLE_MAIN) == table)
+        else {
+            continue;
+        };
+
+        // the kernel flushes the routes of a device which goes down
+        if !is_device_up(&spec.device) {
+            continue;
+        }
+
+        if !limiter.allow(&spec, Instant::now()) {
+            warn!(
+                "Route {} via {} keeps being removed, not re-installing it",
+                spec.destination, spec.device
+            );
+            continue;
+        }
+
+        warn!(
+            "Route {} via {} in table {} was removed by netlink port {}, re-installing it",
+            spec.destination, spec.device, table, port
+        );
+
+        if let Err(e) = ops.add_route(&spec).await {
+            warn!("Unable to re-install route {}: {}", spec.destination, e);
+        }
+    }
+
+    Ok(())
+}
+
+// Destination and table of a route notification, the default route carries no destination attribute.
+fn route_key(route: &RouteMessage) -> Option<(IpNet, u32)> {
+    let destination = route
+        .attributes
+        .iter()
+        .find_map(|attr| match attr {
+            RouteAttribute::Destination(RouteAddress::Inet(addr)) => Some(IpAddr::V4(*addr)),
+            RouteAttribute::Destination(RouteAddress::Inet6(addr)) => Some(IpAddr::V6(*addr)),
+            _ => None,
+        })
+        .or(match route.header.address_family {
+            AddressFamily::Inet => Some(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+            AddressFamily::Inet6 => Some(IpAddr::V6(Ipv6Addr::UNSPECIFIED)),
+            _ => None,
+        })?;
+
+    // tables above 255 are only given in the attribute
+    let table = route
+        .attributes
+        .iter()
+        .find_map(|attr| match attr {
+            RouteAttribute::Table(table) => Some(*table),
+            _ => None,
+        })
+        .unwrap_or(route.header.table.into());
+
+    Some((
+        IpNet::new(destination, route.header.destination_prefix_length).ok()?,
+        table,
+    ))
+}
+
+fn is_device_up(device: &str) -> bool {
+    fs::read_to_string(format!("/sys/class/net/{device}/flags"))
+        .ok()
+        .and_then(|flags| u32::from_str_radix(flags.trim().trim_start_matches("0x"), 16).ok())
+        .is_some_and(|flags| flags & libc::IFF_UP as u32 != 0)
+}
+
+#[derive(Default)]
+struct ReinstallLimiter {
+    history: HashMap<(IpNet, Option<u32>), Vec<Instant>>,
+}
+
+impl ReinstallLimiter {
+    fn allow(&mut self, route: &RouteSpec, now: Instant) -> bool {
+        let times = self.history.entry((route.destination, route.table)).or_default();
+        times.retain(|time| now.duration_since(*time) < REINSTALL_WINDOW);
+
+        if times.len() >= MAX_REINSTALLS {
+            false
+        } else {
+            times.push(now);
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rtnetlink::RouteMessageBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_route_key() {
+        let message = RouteMessageBuilder::<Ipv4Addr>::new()
+            .destination_prefix("10.0.0.0".parse().unwrap(), 8)
+            .build();
+        assert_eq!(
+            route_key(&message),
+            Some(("10.0.0.0/8".parse().unwrap(), RT_TABLE_MAIN))
+        );
+
+        let message = RouteMessageBuilder::<Ipv4Addr>::new().table_id(4500).build();
+        assert_eq!(route_key(&message), Some(("0.0.0.0/0".parse().unwrap(), 4500)));
+
+        let message = RouteMessageBuilder::<Ipv6Addr>::new()
+            .destination_prefix("fd00::".parse().unwrap(), 64)
+            .build();
+        assert_eq!(route_key(&message), Some(("fd00::/64".parse().unwrap(), RT_TABLE_MAIN)));
+    }
+
+    #[test]
+    fn test_reinstall_limiter() {
+        let mut limiter = ReinstallLimiter::default();
+        let route = RouteSpec::new("10.0.0.0/8".parse().unwrap(), "snx-tun");
+        let other = RouteSpec::new("10.0.0.0/8".parse().unwrap(), "snx-tun").table(4500);
+        let now = Instant::now();
+
+        for _ in 0..MAX_REINSTALLS {
+            assert!(limiter.allow(&route, now));
+        }
+        assert!(!limiter.allow(&route, now));
+        assert!(limiter.allow(&other, now));
+        assert!(limiter.allow(&route, now + REINSTALL_WINDOW));
+    }
+}
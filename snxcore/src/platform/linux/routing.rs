@@ -1,31 +1,227 @@
-use std::{collections::HashSet, net::Ipv4Addr};
+use std::{
+    collections::HashSet,
+    fs,
+    net::{IpAddr, Ipv4Addr},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
 
 use async_trait::async_trait;
-use ipnet::Ipv4Net;
-use tracing::debug;
+use ipnet::{IpNet, Ipv4Net, Ipv6Net};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
 
-use crate::{model::params::TunnelParams, platform::RoutingConfigurator};
+use crate::{
+    model::params::TunnelParams,
+    platform::{
+        GatewayRoute, NetworkInterface, RoutingConfigurator, RoutingPolicy,
+        linux::{
+            netlink::{self, NetlinkOps, RouteSpec},
+            route_monitor,
+        },
+        new_network_interface,
+    },
+};
+
+const ROUTING_STATE_DIR: &str = "/var/run";
+
+// Selected with the parameters of the connecting profile, the tunnels of the other profiles keep their own state.
+static STATE_FILE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Routing state file of the connection profile.
+pub fn routing_state_path(params: &TunnelParams) -> PathBuf {
+    Path::new(ROUTING_STATE_DIR).join(format!("snx-rs.{}.routing", params.profile_key()))
+}
+
+pub(super) fn set_state_file(params: &TunnelParams) {
+    *STATE_FILE.lock().unwrap() = Some(routing_state_path(params));
+}
+
+fn state_file() -> PathBuf {
+    STATE_FILE
+        .lock()
+        .unwrap()
+        .clone()
+        .unwrap_or_else(|| routing_state_path(&TunnelParams::default()))
+}
+
+// Policy rules added for the default route mode, kept on disk so that they can be removed
+// on the next start if the process has crashed. The routes owned by the tunnel are watched by the route monitor.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct RoutingState {
+    destination: Option<Ipv4Addr>,
+    excluded: Vec<Ipv4Net>,
+    #[serde(default)]
+    policy: RoutingPolicy,
+    #[serde(default)]
+    routes: Vec<RouteSpec>,
+}
+
+impl RoutingState {
+    fn load<P: AsRef<Path>>(path: P) -> Option<Self> {
+        serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+    }
+
+    fn save<P: AsRef<Path>>(&self, path: P) {
+        let result = if *self == Self::default() {
+            fs::remove_file(path).or_else(|e| {
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    Ok(())
+                } else {
+                    Err(e)
+                }
+            })
+        } else {
+            serde_json::to_string(self)
+                .map_err(Into::into)
+                .and_then(|s| crate::util::write_private_file(path, s))
+        };
+
+        if let Err(e) = result {
+            warn!("Unable to save routing state: {}", e);
+        }
+    }
+
+    fn update<P: AsRef<Path>, F: FnOnce(&mut Self)>(path: P, f: F) -> Self {
+        let mut state = Self::load(&path).unwrap_or_default();
+        f(&mut state);
+        state.save(&path);
+        state
+    }
+}
+
+/// Routes added by the tunnel which are still in place.
+pub(super) fn owned_routes<P: AsRef<Path>>(path: P) -> Vec<RouteSpec> {
+    RoutingState::load(path).map(|state| state.routes).unwrap_or_default()
+}
+
+/// Remove the policy rules left behind by a previous run of the profile which has not cleaned up.
+pub async fn restore_stale_routing(params: &TunnelParams) {
+    let path = routing_state_path(params);
+    let Some(state) = RoutingState::load(&path) else {
+        return;
+    };
+
+    warn!("Removing stale routing rules from a previous run: {:?}", state);
+
+    if let Some(destination) = state.destination {
+        if state.policy.fwmark.is_some() {
+            let _ = crate::util::run_command("ip", suppress_rule_args("del")).await;
+        }
+        let _ = crate::util::run_command("ip", default_rule_args("del", destination, &state.policy)).await;
+    }
+
+    for route in &state.excluded {
+        let _ = crate::util::run_command("ip", ["rule", "del", "to", &route.to_string(), "table", "main"]).await;
+    }
+
+    let ops = netlink::new_netlink_ops();
+    for route in &state.routes {
+        let _ = ops.delete_route(route).await;
+    }
+
+    RoutingState::default().save(&path);
+}
 
 pub struct LinuxRoutingConfigurator {
     device: String,
+    metric: Option<u32>,
+    peer: Option<Ipv4Addr>,
+    ops: Arc<dyn NetlinkOps>,
+    state_file: PathBuf,
 }
 
 impl LinuxRoutingConfigurator {
-    pub fn new<S: AsRef<str>>(device: S, _address: Ipv4Addr) -> Self {
+    pub fn new<S: AsRef<str>>(device: S, _address: Ipv4Addr, metric: Option<u32>) -> Self {
+        Self::with_ops(device, metric, netlink::new_netlink_ops(), state_file())
+    }
+
+    fn with_ops<S: AsRef<str>, P: AsRef<Path>>(
+        device: S,
+        metric: Option<u32>,
+        ops: Arc<dyn NetlinkOps>,
+        state_file: P,
+    ) -> Self {
         Self {
             device: device.as_ref().to_string(),
+            metric,
+            peer: None,
+            ops,
+            state_file: state_file.as_ref().to_owned(),
         }
     }
 
-    async fn add_route(&self, route: Ipv4Net) -> anyhow::Result<()> {
+    // The same metric must be given when deleting a route, otherwise another route to the prefix may be removed.
+    fn route_spec<N: Into<IpNet>>(&self, destination: N) -> RouteSpec {
+        RouteSpec::new(destination.into(), &self.device).metric(self.metric)
+    }
+
+    // The IPv4 routes of a point-to-point device go through the peer.
+    fn peer_route_spec(&self, destination: IpNet) -> RouteSpec {
+        let gateway = match destination {
+            IpNet::V4(_) => self.peer.map(IpAddr::V4),
+            IpNet::V6(_) => None,
+        };
+        self.route_spec(destination).gateway(gateway)
+    }
+
+    async fn warn_conflicting_routes(&self, route: IpNet) {
+        let family = if route.addr().is_ipv4() { "-4" } else { "-6" };
+
+        if let Ok(output) = crate::util::run_command("ip", [family, "route", "show", "exact", &route.to_string()]).await
+        {
+            for conflict in conflicting_routes(&output, &self.device, self.metric.unwrap_or_default()) {
+                warn!(
+                    "Route {} through {} is shadowed by an existing route with a lower metric: {}",
+                    route, self.device, conflict
+                );
+            }
+        }
+    }
+
+    async fn add_route(&self, route: IpNet) -> anyhow::Result<()> {
         debug!("Adding route: {} via {}", route, self.device);
-        crate::util::run_command("ip", ["route", "add", &route.to_string(), "dev", &self.device]).await?;
+        self.warn_conflicting_routes(route).await;
+
+        self.add_owned_route(self.peer_route_spec(route)).await
+    }
+
+    async fn add_owned_route(&self, route: RouteSpec) -> anyhow::Result<()> {
+        self.ops.add_route(&route).await?;
+
+        RoutingState::update(&self.state_file, |state| {
+            if !state.routes.contains(&route) {
+                state.routes.push(route);
+            }
+        });
+        route_monitor::start(&self.state_file);
+
+        Ok(())
+    }
+
+    fn gateway_route_spec(route: &GatewayRoute) -> RouteSpec {
+        RouteSpec::new(Ipv4Net::from(route.destination).into(), &route.device).gateway(route.gateway.map(IpAddr::V4))
+    }
+
+    // Forgotten before the deletion so that the monitor does not put the route back.
+    async fn delete_owned_route(&self, route: RouteSpec) -> anyhow::Result<()> {
+        let state = RoutingState::update(&self.state_file, |state| state.routes.retain(|r| *r != route));
+        if state.routes.is_empty() {
+            route_monitor::stop();
+        }
+
+        self.ops.delete_route(&route).await?;
         Ok(())
     }
 }
 
 #[async_trait]
 impl RoutingConfigurator for LinuxRoutingConfigurator {
+    fn with_peer(mut self, peer: Option<Ipv4Addr>) -> Self {
+        self.peer = peer;
+        self
+    }
+
     async fn add_routes(&self, routes: &[Ipv4Net], ignore_routes: &[Ipv4Net]) -> anyhow::Result<()> {
         let routes = routes.iter().collect::<HashSet<_>>();
         debug!("Routes to add: {:?}", routes);
@@ -35,66 +231,458 @@ impl RoutingConfigurator for LinuxRoutingConfigurator {
                 debug!("Ignoring route: {}", route);
                 continue;
             }
-            let _ = self.add_route(*route).await;
+            let _ = self.add_route((*route).into()).await;
+        }
+
+        Ok(())
+    }
+
+    async fn add_ipv6_routes(&self, routes: &[Ipv6Net]) -> anyhow::Result<()> {
+        let routes = routes.iter().collect::<HashSet<_>>();
+        debug!("IPv6 routes to add: {:?}", routes);
+
+        for route in routes {
+            let _ = self.add_route((*route).into()).await;
         }
 
         Ok(())
     }
 
-    async fn setup_default_route(&self, destination: Ipv4Addr) -> anyhow::Result<()> {
-        debug!("Setting up default route through {}", self.device);
+    async fn remove_routes(&self, routes: &[IpNet]) -> anyhow::Result<()> {
+        for route in routes {
+            debug!("Removing route: {} via {}", route, self.device);
+            let _ = self.delete_owned_route(self.peer_route_spec(*route)).await;
+        }
+        Ok(())
+    }
+
+    async fn setup_default_route(&self, destination: Ipv4Addr, policy: &RoutingPolicy) -> anyhow::Result<()> {
+        debug!("Setting up default route through {}, {:?}", self.device, policy);
 
-        let port = TunnelParams::IPSEC_KEEPALIVE_PORT.to_string();
-        let dst = destination.to_string();
+        self.add_owned_route(self.route_spec(Ipv4Net::default()).table(policy.table))
+            .await?;
+        crate::util::run_command("ip", default_rule_args("add", destination, policy)).await?;
 
-        crate::util::run_command("ip", ["route", "add", "table", &port, "default", "dev", &self.device]).await?;
-        crate::util::run_command("ip", ["rule", "add", "not", "to", &dst, "table", &port]).await?;
+        // more specific routes of the main table, e.g. the local network, take precedence over the tunnel
+        if policy.fwmark.is_some() {
+            crate::util::run_command("ip", suppress_rule_args("add")).await?;
+        }
+
+        RoutingState::update(&self.state_file, |state| {
+            state.destination = Some(destination);
+            state.policy = *policy;
+        });
 
         Ok(())
     }
 
-    async fn setup_keepalive_route(&self, destination: Ipv4Addr, with_table: bool) -> anyhow::Result<()> {
-        debug!("Setting up keepalive route through {}", self.device);
+    async fn check_default_route(&self, destination: Ipv4Addr, policy: &RoutingPolicy) -> anyhow::Result<bool> {
+        let table = policy.table.to_string();
+
+        let rules = crate::util::run_command("ip", ["rule", "show"]).await?;
+        let routes = crate::util::run_command("ip", ["route", "show", "table", &table]).await?;
+
+        Ok(is_default_route_active(
+            &rules,
+            &routes,
+            &rule_selector(destination, policy),
+            &table,
+            &self.device,
+        ))
+    }
 
-        let port = TunnelParams::IPSEC_KEEPALIVE_PORT.to_string();
-        let dst = destination.to_string();
+    async fn setup_keepalive_route(
+        &self,
+        destination: Ipv4Addr,
+        with_table: bool,
+        policy: &RoutingPolicy,
+    ) -> anyhow::Result<()> {
+        debug!("Setting up keepalive route through {}", self.device);
 
         if with_table {
-            crate::util::run_command("ip", &["route", "add", "table", &port, &dst, "dev", &self.device]).await?;
+            self.add_owned_route(self.route_spec(Ipv4Net::from(destination)).table(policy.table))
+                .await?;
         }
 
-        crate::util::run_command(
-            "ip",
-            &[
-                "rule", "add", "to", &dst, "ipproto", "udp", "dport", &port, "table", &port,
-            ],
-        )
-        .await?;
+        crate::util::run_command("ip", keepalive_rule_args("add", destination, policy)).await?;
 
         Ok(())
     }
 
-    async fn remove_default_route(&self, destination: Ipv4Addr) -> anyhow::Result<()> {
-        let port = TunnelParams::IPSEC_KEEPALIVE_PORT.to_string();
-        let dst = destination.to_string();
+    async fn remove_default_route(&self, destination: Ipv4Addr, policy: &RoutingPolicy) -> anyhow::Result<()> {
+        if policy.fwmark.is_some() {
+            let _ = crate::util::run_command("ip", suppress_rule_args("del")).await;
+        }
+        crate::util::run_command("ip", default_rule_args("del", destination, policy)).await?;
+        let _ = self
+            .delete_owned_route(self.route_spec(Ipv4Net::default()).table(policy.table))
+            .await;
 
-        crate::util::run_command("ip", ["rule", "del", "not", "to", &dst, "table", &port]).await?;
+        RoutingState::update(&self.state_file, |state| state.destination = None);
 
         Ok(())
     }
 
-    async fn remove_keepalive_route(&self, destination: Ipv4Addr) -> anyhow::Result<()> {
-        let port = TunnelParams::IPSEC_KEEPALIVE_PORT.to_string();
-        let dst = destination.to_string();
+    async fn remove_keepalive_route(&self, destination: Ipv4Addr, policy: &RoutingPolicy) -> anyhow::Result<()> {
+        crate::util::run_command("ip", keepalive_rule_args("del", destination, policy)).await?;
+
+        // the keepalive route goes away with the device
+        let route = self.route_spec(Ipv4Net::from(destination)).table(policy.table);
+        RoutingState::update(&self.state_file, |state| state.routes.retain(|r| *r != route));
+
+        Ok(())
+    }
 
-        crate::util::run_command(
-            "ip",
-            &[
-                "rule", "del", "to", &dst, "ipproto", "udp", "dport", &port, "table", &port,
-            ],
-        )
-        .await?;
+    // Excluded prefixes are looked up in the main table before the default route rule is reached.
+    async fn setup_excluded_routes(&self, routes: &[Ipv4Net]) -> anyhow::Result<()> {
+        for route in routes {
+            debug!("Excluding route from the tunnel: {}", route);
+            crate::util::run_command("ip", ["rule", "add", "to", &route.to_string(), "table", "main"]).await?;
+            RoutingState::update(&self.state_file, |state| state.excluded.push(*route));
+        }
+        Ok(())
+    }
 
+    async fn remove_excluded_routes(&self, routes: &[Ipv4Net]) -> anyhow::Result<()> {
+        for route in routes {
+            let _ = crate::util::run_command("ip", ["rule", "del", "to", &route.to_string(), "table", "main"]).await;
+        }
+        RoutingState::update(&self.state_file, |state| state.excluded.retain(|r| !routes.contains(r)));
         Ok(())
     }
+
+    async fn find_gateway_route(&self, destination: Ipv4Addr) -> anyhow::Result<GatewayRoute> {
+        let output = crate::util::run_command("ip", ["-4", "route", "get", &destination.to_string()]).await?;
+
+        if let Some((gateway, device)) = parse_route_get(&output, &self.device) {
+            return Ok(GatewayRoute {
+                destination,
+                gateway,
+                device,
+            });
+        }
+
+        // the lookup is answered by the tunnel, e.g. with the firewall mark policy, the default route is used instead
+        let default = new_network_interface().get_default_gateway(&self.device).await?;
+
+        Ok(GatewayRoute {
+            destination,
+            gateway: Some(default.address),
+            device: default.device,
+        })
+    }
+
+    // Owned by the tunnel, so that it is restored by the route monitor and removed after a crash.
+    async fn add_gateway_route(&self, route: &GatewayRoute) -> anyhow::Result<()> {
+        debug!("Pinning gateway route: {:?}", route);
+        self.add_owned_route(Self::gateway_route_spec(route)).await
+    }
+
+    async fn remove_gateway_route(&self, route: &GatewayRoute) -> anyhow::Result<()> {
+        debug!("Removing gateway route: {:?}", route);
+        self.delete_owned_route(Self::gateway_route_spec(route)).await
+    }
+}
+
+// Next hop and device of the `ip route get` answer, unless it goes through the excluded device.
+fn parse_route_get(output: &str, exclude_device: &str) -> Option<(Option<Ipv4Addr>, String)> {
+    let line = output.lines().next()?;
+    let mut parts = line.split_whitespace();
+
+    if matches!(
+        parts.next(),
+        Some("local" | "broadcast" | "unreachable" | "prohibit" | "blackhole")
+    ) {
+        return None;
+    }
+
+    let mut gateway = None;
+    let mut device = None;
+    while let Some(part) = parts.next() {
+        match part {
+            "via" => gateway = parts.next().and_then(|s| s.parse().ok()),
+            "dev" => device = parts.next(),
+            _ => {}
+        }
+    }
+
+    device
+        .filter(|device| *device != exclude_device)
+        .map(|device| (gateway, device.to_owned()))
+}
+
+// `ip rule show` prints the mark in hex
+fn rule_selector(destination: Ipv4Addr, policy: &RoutingPolicy) -> String {
+    match policy.fwmark {
+        Some(fwmark) => format!("fwmark {:#x}", fwmark),
+        None => format!("to {}", destination),
+    }
+}
+
+fn default_rule_args(action: &str, destination: Ipv4Addr, policy: &RoutingPolicy) -> Vec<String> {
+    let selector = rule_selector(destination, policy);
+    ["rule", action, "not"]
+        .into_iter()
+        .chain(selector.split_whitespace())
+        .chain(["table", &policy.table.to_string()])
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+fn suppress_rule_args(action: &str) -> [&str; 6] {
+    ["rule", action, "table", "main", "suppress_prefixlength", "0"]
+}
+
+fn keepalive_rule_args(action: &str, destination: Ipv4Addr, policy: &RoutingPolicy) -> Vec<String> {
+    let port = TunnelParams::IPSEC_KEEPALIVE_PORT.to_string();
+    [
+        "rule",
+        action,
+        "to",
+        &destination.to_string(),
+        "ipproto",
+        "udp",
+        "dport",
+        &port,
+        "table",
+        &policy.table.to_string(),
+    ]
+    .map(ToOwned::to_owned)
+    .to_vec()
+}
+
+fn is_default_route_active(rules: &str, routes: &str, selector: &str, table: &str, device: &str) -> bool {
+    let rule = format!("{} lookup {}", selector, table);
+    let route = format!("default dev {}", device);
+
+    rules.lines().any(|line| line.contains("not ") && line.contains(&rule))
+        && routes.lines().any(|line| line.starts_with(&route))
+}
+
+// Routes to the same prefix through another device which take precedence over the given metric.
+fn conflicting_routes(output: &str, device: &str, metric: u32) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| {
+            let parts = line.split_whitespace().collect::<Vec<_>>();
+            let value = |key: &str| parts.iter().position(|p| *p == key).and_then(|i| parts.get(i + 1));
+
+            let other_device = value("dev").is_none_or(|dev| *dev != device);
+            let other_metric = value("metric").and_then(|m| m.parse::<u32>().ok()).unwrap_or_default();
+
+            other_device && other_metric < metric
+        })
+        .map(|line| line.trim().to_owned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_route_active() {
+        let rules =
+            "0:\tfrom all lookup local\n32765:\tnot from all to 1.2.3.4 lookup 4500\n32766:\tfrom all lookup main\n";
+        let routes = "default dev snx-tun scope link \n";
+        let selector = "to 1.2.3.4";
+
+        assert!(is_default_route_active(rules, routes, selector, "4500", "snx-tun"));
+        assert!(!is_default_route_active(rules, "", selector, "4500", "snx-tun"));
+        assert!(!is_default_route_active(
+            "32766:\tfrom all lookup main\n",
+            routes,
+            selector,
+            "4500",
+            "snx-tun"
+        ));
+
+        let rules = "0:\tfrom all lookup local\n32764:\tfrom all lookup main suppress_prefixlength 0\n\
+                     32765:\tnot from all fwmark 0x4b58 lookup 4500\n32766:\tfrom all lookup main\n";
+        assert!(is_default_route_active(
+            rules,
+            routes,
+            "fwmark 0x4b58",
+            "4500",
+            "snx-tun"
+        ));
+    }
+
+    #[test]
+    fn test_default_rule_args() {
+        let destination = "1.2.3.4".parse().unwrap();
+
+        let policy = RoutingPolicy {
+            table: 4500,
+            fwmark: None,
+        };
+        assert_eq!(
+            default_rule_args("add", destination, &policy).join(" "),
+            "rule add not to 1.2.3.4 table 4500"
+        );
+
+        let policy = RoutingPolicy {
+            table: 4500,
+            fwmark: Some(19288),
+        };
+        assert_eq!(
+            default_rule_args("del", destination, &policy).join(" "),
+            "rule del not fwmark 0x4b58 table 4500"
+        );
+    }
+
+    #[test]
+    fn test_parse_route_get() {
+        let output = "203.0.113.5 via 192.168.1.1 dev wlp3s0 src 192.168.1.10 uid 1000 \n    cache \n";
+        assert_eq!(
+            parse_route_get(output, "snx-tun"),
+            Some((Some(Ipv4Addr::new(192, 168, 1, 1)), "wlp3s0".to_owned()))
+        );
+
+        let output = "192.168.1.20 dev eth0 src 192.168.1.10 uid 0 \n    cache \n";
+        assert_eq!(parse_route_get(output, "snx-tun"), Some((None, "eth0".to_owned())));
+
+        let output = "203.0.113.5 dev snx-tun src 10.0.0.10 uid 0 \n    cache \n";
+        assert_eq!(parse_route_get(output, "snx-tun"), None);
+
+        assert_eq!(
+            parse_route_get("local 127.0.0.1 dev lo src 127.0.0.1 uid 0\n", "snx-tun"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_conflicting_routes() {
+        let output = "10.0.0.0/8 via 192.168.1.1 dev eth0 proto static metric 50 \n\
+                      10.0.0.0/8 via 192.168.1.1 dev wlan0 proto static metric 600 \n\
+                      10.0.0.0/8 dev snx-tun scope link metric 10 \n";
+
+        assert_eq!(
+            conflicting_routes(output, "snx-tun", 100),
+            vec!["10.0.0.0/8 via 192.168.1.1 dev eth0 proto static metric 50"]
+        );
+        assert!(conflicting_routes(output, "snx-tun", 0).is_empty());
+        assert_eq!(
+            conflicting_routes("10.0.0.0/8 via 192.168.1.1 dev eth0\n", "snx-tun", 1),
+            vec!["10.0.0.0/8 via 192.168.1.1 dev eth0"]
+        );
+    }
+
+    #[test]
+    fn test_routing_state_roundtrip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("routing");
+
+        let state = RoutingState {
+            destination: Some("1.2.3.4".parse().unwrap()),
+            excluded: vec!["192.168.1.0/24".parse().unwrap()],
+            policy: RoutingPolicy {
+                table: 100,
+                fwmark: Some(0x4b58),
+            },
+            routes: vec![RouteSpec::new("0.0.0.0/0".parse().unwrap(), "snx-tun").table(100)],
+        };
+        state.save(&path);
+        assert_eq!(RoutingState::load(&path), Some(state));
+
+        RoutingState::default().save(&path);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_routing_state_path() {
+        let path = |profile: &str| {
+            routing_state_path(&TunnelParams {
+                profile: Some(profile.to_owned()),
+                ..Default::default()
+            })
+        };
+        assert_eq!(path("work"), Path::new("/var/run/snx-rs.work.routing"));
+        assert_ne!(path("work"), path("home"));
+    }
+
+    #[tokio::test]
+    async fn test_routes_through_netlink() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let state_file = dir.path().join("routing");
+        let ops = Arc::new(netlink::mock::MockNetlinkOps::default());
+        let configurator = LinuxRoutingConfigurator::with_ops("snx-tun", Some(100), ops.clone(), &state_file);
+
+        let routes: [Ipv4Net; 2] = ["10.0.0.0/8".parse().unwrap(), "192.168.0.0/16".parse().unwrap()];
+        configurator.add_routes(&routes, &routes[1..]).await.unwrap();
+        configurator.add_routes(&routes[..1], &[]).await.unwrap();
+        assert_eq!(ops.routes.lock().unwrap().len(), 1);
+        assert_eq!(owned_routes(&state_file), ops.routes.lock().unwrap().clone());
+
+        configurator.remove_routes(&[routes[0].into()]).await.unwrap();
+        configurator.remove_routes(&[routes[0].into()]).await.unwrap();
+        assert!(ops.routes.lock().unwrap().is_empty());
+        assert!(owned_routes(&state_file).is_empty());
+
+        assert_eq!(
+            ops.calls.lock().unwrap()[0],
+            "-4 route add 10.0.0.0/8 dev snx-tun metric 100"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_routes_through_peer() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let state_file = dir.path().join("routing");
+        let ops = Arc::new(netlink::mock::MockNetlinkOps::default());
+        let configurator = LinuxRoutingConfigurator::with_ops("snx-tun", None, ops.clone(), &state_file)
+            .with_peer(Some("10.0.0.1".parse().unwrap()));
+
+        configurator
+            .add_routes(&["10.0.0.0/8".parse().unwrap()], &[])
+            .await
+            .unwrap();
+        configurator
+            .add_ipv6_routes(&["fd00::/64".parse().unwrap()])
+            .await
+            .unwrap();
+        configurator
+            .remove_routes(&["10.0.0.0/8".parse().unwrap(), "fd00::/64".parse().unwrap()])
+            .await
+            .unwrap();
+
+        assert!(ops.routes.lock().unwrap().is_empty());
+        assert_eq!(
+            *ops.calls.lock().unwrap(),
+            vec![
+                "-4 route add 10.0.0.0/8 via 10.0.0.1 dev snx-tun",
+                "-6 route add fd00::/64 dev snx-tun",
+                "-4 route del 10.0.0.0/8 via 10.0.0.1 dev snx-tun",
+                "-6 route del fd00::/64 dev snx-tun",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gateway_route() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let state_file = dir.path().join("routing");
+        let ops = Arc::new(netlink::mock::MockNetlinkOps::default());
+        let configurator = LinuxRoutingConfigurator::with_ops("snx-tun", Some(100), ops.clone(), &state_file);
+
+        let route = GatewayRoute {
+            destination: "203.0.113.5".parse().unwrap(),
+            gateway: Some("192.168.1.1".parse().unwrap()),
+            device: "wlp3s0".to_owned(),
+        };
+
+        configurator.add_gateway_route(&route).await.unwrap();
+        assert_eq!(owned_routes(&state_file), ops.routes.lock().unwrap().clone());
+
+        configurator.remove_gateway_route(&route).await.unwrap();
+        assert!(owned_routes(&state_file).is_empty());
+
+        assert_eq!(
+            *ops.calls.lock().unwrap(),
+            vec![
+                "-4 route add 203.0.113.5/32 via 192.168.1.1 dev wlp3s0",
+                "-4 route del 203.0.113.5/32 via 192.168.1.1 dev wlp3s0",
+            ]
+        );
+    }
 }
@@ -6,8 +6,8 @@ use rand::random;
 use tracing::{debug, trace};
 
 use crate::{
-    model::{IpsecSession, params::TunnelParams},
-    platform::{self, IpsecConfigurator, NetworkInterface, RoutingConfigurator},
+    model::IpsecSession,
+    platform::{self, IpsecConfigurator, NetworkInterface, RoutingConfigurator, RoutingPolicy},
     util,
 };
 
@@ -19,6 +19,7 @@ struct XfrmLink<'a> {
     name: &'a str,
     if_id: u32,
     address: Ipv4Net,
+    mtu: u16,
 }
 
 impl XfrmLink<'_> {
@@ -47,15 +48,7 @@ impl XfrmLink<'_> {
         let opt = format!("net.ipv4.conf.{}.forwarding=1", self.name);
         util::run_command("sysctl", ["-qw", &opt]).await?;
 
-        iproute2(&[
-            "link",
-            "set",
-            self.name,
-            "mtu",
-            &TunnelParams::DEFAULT_MTU.to_string(),
-            "up",
-        ])
-        .await?;
+        iproute2(&["link", "set", self.name, "mtu", &self.mtu.to_string(), "up"]).await?;
 
         iproute2(&["addr", "add", &self.address.to_string(), "dev", self.name]).await?;
 
@@ -234,6 +227,8 @@ pub struct XfrmConfigurator {
     src_port: u16,
     dest_ip: Ipv4Addr,
     dest_port: u16,
+    mtu: u16,
+    policy: RoutingPolicy,
 }
 
 impl XfrmConfigurator {
@@ -243,6 +238,8 @@ impl XfrmConfigurator {
         src_port: u16,
         dest_ip: Ipv4Addr,
         dest_port: u16,
+        mtu: u16,
+        policy: RoutingPolicy,
     ) -> anyhow::Result<Self> {
         let if_id = random();
 
@@ -254,6 +251,8 @@ impl XfrmConfigurator {
             if_id,
             src_port,
             dest_port,
+            mtu,
+            policy,
         })
     }
 
@@ -263,6 +262,7 @@ impl XfrmConfigurator {
             if_id: self.if_id,
             address: Ipv4Net::with_netmask(self.ipsec_session.address, self.ipsec_session.netmask)
                 .unwrap_or_else(|_| Ipv4Net::from(self.ipsec_session.address)),
+            mtu: self.mtu,
         }
     }
 
@@ -411,7 +411,8 @@ impl IpsecConfigurator for XfrmConfigurator {
     }
 
     async fn cleanup(&mut self) {
-        let configurator = platform::new_routing_configurator(&self.name, self.ipsec_session.address);
+        // only the policy rules are removed here, the routes go away together with the xfrm device
+        let configurator = platform::new_routing_configurator(&self.name, self.ipsec_session.address, None);
 
         let _ = self
             .configure_xfrm_state(
@@ -441,7 +442,7 @@ impl IpsecConfigurator for XfrmConfigurator {
 
         let _ = self.new_xfrm_link().delete().await;
 
-        let _ = configurator.remove_keepalive_route(self.dest_ip).await;
-        let _ = configurator.remove_default_route(self.dest_ip).await;
+        let _ = configurator.remove_keepalive_route(self.dest_ip, &self.policy).await;
+        let _ = configurator.remove_default_route(self.dest_ip, &self.policy).await;
     }
 }
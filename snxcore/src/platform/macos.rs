@@ -0,0 +1,90 @@
+use std::{net::SocketAddr, time::Duration};
+
+use anyhow::{Context, anyhow};
+use cached::proc_macro::cached;
+pub use keychain::MacKeychain as KeychainImpl;
+pub use resolver::{dns_backend_name, effective_dns_mode, new_resolver_configurator, restore_stale_resolv_conf};
+use tokio::net::{TcpSocket, UdpSocket};
+use uuid::Uuid;
+
+pub use crate::platform::{
+    bsd_common::{
+        net::BsdNetworkInterface as NetworkInterfaceImpl,
+        routing::{BsdRoutingConfigurator as RoutingImpl, restore_stale_routing},
+    },
+    unsupported::{
+        UnsupportedAppRouting as AppRoutingImpl, UnsupportedAuthority as AuthorityImpl,
+        UnsupportedDnsRedirect as DnsRedirectImpl, UnsupportedForwarding as ForwardingImpl,
+        UnsupportedIpsecConfigurator as IpsecImpl, UnsupportedIpv6Blocker as Ipv6BlockerImpl,
+        UnsupportedKillSwitch as KillSwitchImpl, UnsupportedMssClamp as MssClampImpl,
+        UnsupportedNetworkNamespace as NetworkNamespaceImpl, check_privilege_drop, drop_privileges,
+    },
+};
+use crate::{
+    model::params::TunnelParams,
+    platform::{PlatformFeatures, TcpSocketExt, UdpEncap, UdpSocketExt},
+};
+
+mod keychain;
+pub mod resolver;
+
+pub fn init() {}
+
+pub fn apply_params(_params: &TunnelParams) {}
+
+/// The device, routes and DNS settings can only be changed by root.
+pub fn check_permissions(_params: &TunnelParams) -> anyhow::Result<()> {
+    if !nix::unistd::geteuid().is_root() {
+        return Err(crate::error::PermissionDenied(i18n::tr!("error-no-root-privileges")).into());
+    }
+    Ok(())
+}
+
+impl TcpSocketExt for TcpSocket {
+    fn set_mark(&self, _mark: u32) -> anyhow::Result<()> {
+        Err(anyhow!("Socket marks are not supported on macOS"))
+    }
+}
+
+#[async_trait::async_trait]
+impl UdpSocketExt for UdpSocket {
+    fn set_encap(&self, _encap: UdpEncap) -> anyhow::Result<()> {
+        Err(anyhow!("UDP encapsulation is not supported on macOS"))
+    }
+
+    // UDP checksums cannot be disabled per socket on macOS.
+    fn set_no_check(&self, _flag: bool) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn send_receive(&self, data: &[u8], timeout: Duration, target: SocketAddr) -> anyhow::Result<Vec<u8>> {
+        super::udp_send_receive(self, data, timeout, target).await
+    }
+}
+
+#[cached(result = true)]
+pub fn get_machine_uuid() -> anyhow::Result<Uuid> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()?;
+
+    let uuid = parse_platform_uuid(&String::from_utf8_lossy(&output.stdout)).context("No platform UUID")?;
+
+    Ok(Uuid::try_parse(&uuid)?)
+}
+
+fn parse_platform_uuid(ioreg: &str) -> Option<String> {
+    ioreg
+        .lines()
+        .find(|line| line.contains("\"IOPlatformUUID\""))
+        .and_then(|line| line.rsplit('"').nth(1))
+        .map(ToOwned::to_owned)
+}
+
+pub async fn get_features() -> PlatformFeatures {
+    PlatformFeatures {
+        ipsec_native: false,
+        ipsec_keepalive: false,
+        split_dns: true,
+    }
+}
@@ -0,0 +1,45 @@
+use anyhow::Context;
+use security_framework::passwords;
+use tracing::debug;
+
+use crate::platform::Keychain;
+
+const SERVICE_NAME: &str = "snx-rs";
+
+#[derive(Default)]
+pub struct MacKeychain;
+
+impl MacKeychain {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl Keychain for MacKeychain {
+    async fn acquire_password(&self, username: &str) -> anyhow::Result<String> {
+        debug!("Attempting to acquire password from the keychain");
+
+        let username = username.to_owned();
+        let secret = tokio::task::spawn_blocking(move || passwords::get_generic_password(SERVICE_NAME, &username))
+            .await?
+            .context("No item in keychain")?;
+
+        debug!("Password acquired successfully");
+
+        Ok(String::from_utf8_lossy(&secret).into_owned())
+    }
+
+    async fn store_password(&self, username: &str, password: &str) -> anyhow::Result<()> {
+        debug!("Attempting to store user password in the keychain");
+
+        let username = username.to_owned();
+        let password = password.to_owned();
+        tokio::task::spawn_blocking(move || {
+            passwords::set_generic_password(SERVICE_NAME, &username, password.as_bytes())
+        })
+        .await??;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use std::process::Stdio;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use tokio::io::AsyncWriteExt;
+use tracing::debug;
+
+use crate::{
+    model::params::{DnsBackend, DnsMode, TunnelParams},
+    platform::{PrintConfigurator, ResolverConfig, ResolverConfigurator},
+};
+
+// Publishes the tunnel resolvers in the dynamic store, which is picked up by mDNSResponder.
+struct ScutilConfigurator {
+    device: String,
+    mode: DnsMode,
+}
+
+impl ScutilConfigurator {
+    fn key(&self) -> String {
+        format!("State:/Network/Service/snx-rs-{}/DNS", self.device)
+    }
+
+    async fn run_script(&self, script: String) -> anyhow::Result<()> {
+        let mut child = tokio::process::Command::new("scutil").stdin(Stdio::piped()).spawn()?;
+
+        child
+            .stdin
+            .take()
+            .context("No stdin")?
+            .write_all(script.as_bytes())
+            .await?;
+
+        let status = child.wait().await?;
+        anyhow::ensure!(status.success(), "scutil exited with {}", status);
+
+        Ok(())
+    }
+}
+
+fn scutil_script(key: &str, config: &ResolverConfig, mode: DnsMode) -> String {
+    let search_domains = config
+        .search_domains
+        .iter()
+        .filter(|s| !s.starts_with('~'))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let match_domains = match mode {
+        DnsMode::Split => config
+            .search_domains
+            .iter()
+            .map(|s| s.trim_start_matches('~').to_owned())
+            .collect::<Vec<_>>(),
+        // an empty match domain makes the resolver a catch-all one
+        DnsMode::Full => vec![String::from("\"\"")],
+    };
+
+    let servers = config.dns_servers.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+    let mut lines = vec!["d.init".to_owned()];
+    if !servers.is_empty() {
+        lines.push(format!("d.add ServerAddresses * {}", servers.join(" ")));
+    }
+    if !search_domains.is_empty() {
+        lines.push(format!("d.add SearchDomains * {}", search_domains.join(" ")));
+    }
+    if !match_domains.is_empty() {
+        lines.push(format!("d.add SupplementalMatchDomains * {}", match_domains.join(" ")));
+    }
+    lines.push(format!("set {key}"));
+
+    format!("{}\n", lines.join("\n"))
+}
+
+#[async_trait]
+impl ResolverConfigurator for ScutilConfigurator {
+    async fn configure(&self, config: &ResolverConfig) -> anyhow::Result<()> {
+        debug!("Configuring DNS for {} via scutil", self.device);
+        self.run_script(scutil_script(&self.key(), config, self.mode)).await
+    }
+
+    async fn cleanup(&self, _config: &ResolverConfig) -> anyhow::Result<()> {
+        debug!("Removing DNS configuration for {}", self.device);
+        self.run_script(format!("remove {}\n", self.key())).await
+    }
+}
+
+struct NoopConfigurator;
+
+#[async_trait]
+impl ResolverConfigurator for NoopConfigurator {
+    async fn configure(&self, _config: &ResolverConfig) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn cleanup(&self, _config: &ResolverConfig) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// All backends except `none` and `print` are served by the system configuration dynamic store on macOS.
+pub fn new_resolver_configurator<S>(
+    device: S,
+    params: &TunnelParams,
+) -> anyhow::Result<Box<dyn ResolverConfigurator + Send + Sync>>
+where
+    S: AsRef<str>,
+{
+    let device = device.as_ref().to_owned();
+    let mode = params.dns_mode;
+
+    match params.dns_backend {
+        DnsBackend::None => Ok(Box::new(NoopConfigurator)),
+        DnsBackend::Print => Ok(Box::new(PrintConfigurator)),
+        _ => Ok(Box::new(ScutilConfigurator { device, mode })),
+    }
+}
+
+/// DNS backend selected by the parameters, all but `none` and `print` use the dynamic store.
+pub fn dns_backend_name(params: &TunnelParams) -> String {
+    match params.dns_backend {
+        DnsBackend::None | DnsBackend::Print => params.dns_backend.to_string(),
+        backend => format!("{backend}: scutil"),
+    }
+}
+
+/// Split DNS is natively supported through supplemental match domains.
+pub fn effective_dns_mode(params: &TunnelParams) -> DnsMode {
+    params.dns_mode
+}
+
+/// The dynamic store is not persistent, there is nothing to restore after a reboot and the
+/// network journal removes the entries left behind by a crashed session.
+pub fn restore_stale_resolv_conf(_params: &TunnelParams) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scutil_script() {
+        let config = ResolverConfig {
+            search_domains: vec!["corp.example.com".to_owned(), "~internal".to_owned()],
+            dns_servers: vec!["10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            scutil_script("State:/Network/Service/snx-rs-utun4/DNS", &config, DnsMode::Split),
+            "d.init\n\
+             d.add ServerAddresses * 10.0.0.1 10.0.0.2\n\
+             d.add SearchDomains * corp.example.com\n\
+             d.add SupplementalMatchDomains * corp.example.com internal\n\
+             set State:/Network/Service/snx-rs-utun4/DNS\n"
+        );
+
+        assert!(scutil_script("key", &config, DnsMode::Full).contains("d.add SupplementalMatchDomains * \"\"\n"));
+    }
+}
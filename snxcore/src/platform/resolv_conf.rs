@@ -0,0 +1,515 @@
+use std::{
+    fs,
+    io::{self, Write},
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use i18n::tr;
+use once_cell::sync::Lazy;
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, warn};
+
+use crate::{
+    model::params::TunnelParams,
+    platform::{ResolverConfig, ResolverConfigurator},
+};
+
+pub const RESOLV_CONF: &str = "/etc/resolv.conf";
+const DIRECT_MARKER: &str = "# Generated by snx-rs";
+
+/// File written by the direct DNS backend, `dns-resolv-path` or /etc/resolv.conf.
+pub fn resolv_conf_path(params: &TunnelParams) -> PathBuf {
+    params.dns_resolv_path.clone().unwrap_or_else(|| RESOLV_CONF.into())
+}
+
+// In a container resolv.conf is bind-mounted by the runtime: it cannot be replaced (EBUSY),
+// or the mount is read-only (EROFS).
+fn is_read_only_mount(error: &anyhow::Error) -> bool {
+    error.chain().filter_map(|e| e.downcast_ref::<io::Error>()).any(|e| {
+        matches!(
+            e.kind(),
+            io::ErrorKind::ReadOnlyFilesystem | io::ErrorKind::ResourceBusy
+        )
+    })
+}
+
+/// Point to the container options when resolv.conf turns out to be a read-only mount.
+pub fn explain_read_only(path: &Path, error: anyhow::Error) -> anyhow::Error {
+    if is_read_only_mount(&error) {
+        error.context(tr!("error-resolv-conf-read-only", path = path.display().to_string()))
+    } else {
+        error
+    }
+}
+
+/// Search domains from the `search` and `domain` lines of resolv.conf.
+pub fn parse_search_domains(data: &str) -> Vec<String> {
+    data.lines()
+        .filter_map(|line| {
+            line.trim()
+                .strip_prefix("search")
+                .or_else(|| line.trim().strip_prefix("domain"))
+        })
+        .flat_map(|domains| domains.split_whitespace())
+        .map(|domain| domain.to_lowercase())
+        .collect()
+}
+
+/// Implementations of resolvconf(8): openresolv (Alpine, Void, the BSDs) and the Debian script.
+/// The systemd compatibility wrapper accepts the same options as the Debian script.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResolvconfFlavor {
+    OpenResolv,
+    Debian,
+}
+
+impl ResolvconfFlavor {
+    // only openresolv understands --version, the Debian script prints its usage
+    fn from_version_output(output: &str) -> Self {
+        if output.contains("openresolv") {
+            Self::OpenResolv
+        } else {
+            Self::Debian
+        }
+    }
+}
+
+static RESOLVCONF_FLAVOR: Lazy<Option<ResolvconfFlavor>> = Lazy::new(|| {
+    let output = std::process::Command::new("resolvconf")
+        .arg("--version")
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let flavor = ResolvconfFlavor::from_version_output(&text);
+    debug!("Detected resolvconf implementation: {:?}", flavor);
+
+    Some(flavor)
+});
+
+/// Installed resolvconf(8) implementation, `None` when the utility is missing.
+pub fn resolvconf_flavor() -> Option<ResolvconfFlavor> {
+    *RESOLVCONF_FLAVOR
+}
+
+/// Both implementations leave a header mentioning resolvconf in the generated file.
+pub fn is_resolvconf_managed(conf: &str) -> bool {
+    conf.lines()
+        .take_while(|line| line.starts_with('#'))
+        .any(|line| line.contains("resolvconf"))
+}
+
+// Uses the resolvconf(8) utility which merges per-interface records into resolv.conf.
+pub struct ResolvconfToolConfigurator {
+    record: String,
+    flavor: ResolvconfFlavor,
+}
+
+impl ResolvconfToolConfigurator {
+    pub fn new(device: &str) -> Self {
+        Self {
+            record: format!("{device}.snx"),
+            flavor: resolvconf_flavor().unwrap_or(ResolvconfFlavor::Debian),
+        }
+    }
+
+    // The tunnel resolvers receive all queries, openresolv can hide the records of the other interfaces.
+    fn add_args(&self) -> Vec<&str> {
+        match self.flavor {
+            ResolvconfFlavor::OpenResolv => vec!["-x", "-a", &self.record],
+            ResolvconfFlavor::Debian => vec!["-a", &self.record],
+        }
+    }
+
+    // openresolv fails on a missing record unless forced
+    fn delete_args(&self) -> Vec<&str> {
+        match self.flavor {
+            ResolvconfFlavor::OpenResolv => vec!["-f", "-d", &self.record],
+            ResolvconfFlavor::Debian => vec!["-d", &self.record],
+        }
+    }
+}
+
+#[async_trait]
+impl ResolverConfigurator for ResolvconfToolConfigurator {
+    async fn configure(&self, config: &ResolverConfig) -> anyhow::Result<()> {
+        debug!("Adding resolvconf record {} ({:?})", self.record, self.flavor);
+
+        let mut child = tokio::process::Command::new("resolvconf")
+            .args(self.add_args())
+            .stdin(Stdio::piped())
+            .spawn()?;
+
+        let records = format!("{}\n", config.resolv_conf_lines().join("\n"));
+        child
+            .stdin
+            .take()
+            .context("No stdin")?
+            .write_all(records.as_bytes())
+            .await?;
+
+        let status = child.wait().await?;
+        anyhow::ensure!(status.success(), "resolvconf exited with {}", status);
+
+        Ok(())
+    }
+
+    async fn cleanup(&self, _config: &ResolverConfig) -> anyhow::Result<()> {
+        crate::util::run_command("resolvconf", self.delete_args()).await?;
+        Ok(())
+    }
+}
+
+// Replaces resolv.conf on systems without a resolver daemon, the original file is kept
+// in a backup and restored on cleanup or on the next start after a crash. A file which did not exist,
+// like one in a volume shared with other containers, is removed instead.
+pub struct DirectConfigurator {
+    config_path: PathBuf,
+    backup_path: PathBuf,
+    profile: Option<String>,
+}
+
+impl DirectConfigurator {
+    pub fn new<P: AsRef<Path>>(config_path: P) -> Self {
+        let config_path = config_path.as_ref().to_owned();
+        let mut backup_path = config_path.clone().into_os_string();
+        backup_path.push(".snx-rs");
+
+        Self {
+            config_path,
+            backup_path: backup_path.into(),
+            profile: None,
+        }
+    }
+
+    /// Name the profile in the generated file, only the stale file of the same profile is restored.
+    pub fn with_profile<S: AsRef<str>>(mut self, profile: S) -> Self {
+        self.profile = Some(profile.as_ref().to_owned());
+        self
+    }
+
+    fn marker(&self) -> String {
+        match self.profile {
+            Some(ref profile) => format!("{DIRECT_MARKER} for profile {profile}"),
+            None => DIRECT_MARKER.to_owned(),
+        }
+    }
+
+    fn check_regular_file(&self) -> anyhow::Result<()> {
+        let metadata = match fs::symlink_metadata(&self.config_path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        if metadata.is_symlink() {
+            anyhow::bail!(tr!(
+                "error-resolv-conf-symlink",
+                path = self.config_path.display().to_string()
+            ));
+        }
+        Ok(())
+    }
+
+    fn is_generated(&self) -> bool {
+        fs::read_to_string(&self.config_path).is_ok_and(|conf| conf.starts_with(DIRECT_MARKER))
+    }
+
+    // A file without the profile was written by an older version and belongs to any of them.
+    fn is_generated_by_profile(&self) -> bool {
+        fs::read_to_string(&self.config_path).is_ok_and(|conf| {
+            conf.starts_with(&format!("{},", self.marker())) || conf.starts_with(&format!("{DIRECT_MARKER},"))
+        })
+    }
+
+    fn make_config(&self, original: &str, config: &ResolverConfig) -> String {
+        let original_domains = original
+            .lines()
+            .filter_map(|line| line.strip_prefix("search"))
+            .flat_map(|line| line.split_whitespace().map(ToOwned::to_owned));
+
+        let config = ResolverConfig {
+            search_domains: config.search_domains.iter().cloned().chain(original_domains).collect(),
+            dns_servers: config.dns_servers.clone(),
+            ..Default::default()
+        };
+
+        let mut lines = vec![format!(
+            "{}, original saved to {}",
+            self.marker(),
+            self.backup_path.display()
+        )];
+        lines.extend(config.resolv_conf_lines());
+        lines.extend(
+            original
+                .lines()
+                .filter(|line| !line.starts_with('#') && !line.starts_with("search") && !line.trim().is_empty())
+                .map(ToOwned::to_owned),
+        );
+        format!("{}\n", lines.join("\n"))
+    }
+
+    fn restore(&self) -> anyhow::Result<()> {
+        if self.backup_path.exists() {
+            self.check_regular_file()?;
+            debug!(
+                "Restoring {} from {}",
+                self.config_path.display(),
+                self.backup_path.display()
+            );
+            atomic_write(&self.config_path, &fs::read_to_string(&self.backup_path)?)?;
+            fs::remove_file(&self.backup_path)?;
+        } else if self.is_generated() {
+            debug!("Removing {}", self.config_path.display());
+            fs::remove_file(&self.config_path)?;
+        }
+        Ok(())
+    }
+
+    pub fn restore_stale(&self) -> anyhow::Result<()> {
+        if self.is_generated_by_profile() {
+            warn!(
+                "Found stale {} from a previous run, restoring",
+                self.config_path.display()
+            );
+            self.restore()?;
+        }
+        Ok(())
+    }
+
+    fn apply(&self, config: &ResolverConfig) -> anyhow::Result<()> {
+        self.check_regular_file()?;
+
+        let original = if self.is_generated() {
+            // reconfigured, without a backup the file did not exist
+            if self.backup_path.exists() {
+                fs::read_to_string(&self.backup_path)?
+            } else {
+                String::new()
+            }
+        } else if self.config_path.exists() {
+            let original = fs::read_to_string(&self.config_path)?;
+            atomic_write(&self.backup_path, &original)?;
+            original
+        } else {
+            String::new()
+        };
+
+        atomic_write(&self.config_path, &self.make_config(&original, config))
+    }
+}
+
+fn atomic_write(path: &Path, contents: &str) -> anyhow::Result<()> {
+    let mut temp_path = path.to_owned().into_os_string();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    let mut file = fs::File::create(&temp_path)?;
+    file.write_all(contents.as_bytes())?;
+    file.set_permissions(fs::Permissions::from_mode(0o644))?;
+    file.sync_all()?;
+
+    fs::rename(&temp_path, path)?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl ResolverConfigurator for DirectConfigurator {
+    async fn configure(&self, config: &ResolverConfig) -> anyhow::Result<()> {
+        self.apply(config).map_err(|e| explain_read_only(&self.config_path, e))
+    }
+
+    async fn cleanup(&self, _config: &ResolverConfig) -> anyhow::Result<()> {
+        self.restore()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolvconf_flavor() {
+        assert_eq!(
+            ResolvconfFlavor::from_version_output("openresolv 3.13.2\n"),
+            ResolvconfFlavor::OpenResolv
+        );
+        assert_eq!(
+            ResolvconfFlavor::from_version_output("resolvconf: Error: Command not recognized\n"),
+            ResolvconfFlavor::Debian
+        );
+
+        let configurator = ResolvconfToolConfigurator {
+            record: "snx-tun.snx".to_owned(),
+            flavor: ResolvconfFlavor::OpenResolv,
+        };
+        assert_eq!(configurator.add_args(), ["-x", "-a", "snx-tun.snx"]);
+        assert_eq!(configurator.delete_args(), ["-f", "-d", "snx-tun.snx"]);
+
+        let configurator = ResolvconfToolConfigurator {
+            flavor: ResolvconfFlavor::Debian,
+            ..configurator
+        };
+        assert_eq!(configurator.add_args(), ["-a", "snx-tun.snx"]);
+        assert_eq!(configurator.delete_args(), ["-d", "snx-tun.snx"]);
+    }
+
+    #[test]
+    fn test_is_resolvconf_managed() {
+        assert!(is_resolvconf_managed(
+            "# Generated by resolvconf\nnameserver 10.0.0.1\n"
+        ));
+        assert!(is_resolvconf_managed(
+            "# Dynamic resolv.conf(5) file for glibc resolver(3) generated by resolvconf(8)\n"
+        ));
+        assert!(!is_resolvconf_managed("nameserver 10.0.0.1\n# resolvconf\n"));
+    }
+
+    #[test]
+    fn test_parse_search_domains() {
+        let data = "# comment\nnameserver 127.0.0.53\nsearch corp.example.com lan\n";
+        assert_eq!(parse_search_domains(data), vec!["corp.example.com", "lan"]);
+    }
+
+    #[tokio::test]
+    async fn test_direct_configurator() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let conf = dir.path().join("resolv.conf");
+        fs::write(&conf, "# comment\nnameserver 10.0.0.1\nsearch acme.com\n").unwrap();
+
+        let cut = DirectConfigurator::new(&conf);
+
+        let config = ResolverConfig {
+            search_domains: vec!["dom1.com".to_owned(), "~dom2.net".to_owned()],
+            dns_servers: vec!["192.168.1.1".parse().unwrap()],
+            ..Default::default()
+        };
+        cut.configure(&config).await.unwrap();
+
+        let new_conf = fs::read_to_string(&conf).unwrap();
+        assert_eq!(
+            new_conf,
+            format!(
+                "{}, original saved to {}\nsearch dom1.com dom2.net acme.com\nnameserver 192.168.1.1\nnameserver 10.0.0.1\n",
+                DIRECT_MARKER,
+                cut.backup_path.display()
+            )
+        );
+
+        // simulate a crash: a new instance must restore the original file
+        DirectConfigurator::new(&conf).restore_stale().unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&conf).unwrap(),
+            "# comment\nnameserver 10.0.0.1\nsearch acme.com\n"
+        );
+        assert!(!cut.backup_path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_direct_configurator_new_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let conf = dir.path().join("resolv.conf");
+
+        let cut = DirectConfigurator::new(&conf);
+        let config = ResolverConfig {
+            dns_servers: vec!["192.168.1.1".parse().unwrap()],
+            ..Default::default()
+        };
+
+        cut.configure(&config).await.unwrap();
+        cut.configure(&config).await.unwrap();
+        assert!(
+            fs::read_to_string(&conf)
+                .unwrap()
+                .ends_with("\nnameserver 192.168.1.1\n")
+        );
+        assert!(!cut.backup_path.exists());
+
+        cut.cleanup(&config).await.unwrap();
+        assert!(!conf.exists());
+    }
+
+    #[tokio::test]
+    async fn test_direct_configurator_other_profile() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let conf = dir.path().join("resolv.conf");
+        fs::write(&conf, "nameserver 10.0.0.1\n").unwrap();
+
+        let config = ResolverConfig {
+            dns_servers: vec!["192.168.1.1".parse().unwrap()],
+            ..Default::default()
+        };
+        DirectConfigurator::new(&conf)
+            .with_profile("work")
+            .configure(&config)
+            .await
+            .unwrap();
+
+        // the file of a connected profile is left alone by another one
+        DirectConfigurator::new(&conf)
+            .with_profile("home")
+            .restore_stale()
+            .unwrap();
+        assert!(fs::read_to_string(&conf).unwrap().contains("192.168.1.1"));
+
+        DirectConfigurator::new(&conf)
+            .with_profile("work")
+            .restore_stale()
+            .unwrap();
+        assert_eq!(fs::read_to_string(&conf).unwrap(), "nameserver 10.0.0.1\n");
+    }
+
+    #[test]
+    fn test_explain_read_only() {
+        let path = Path::new(RESOLV_CONF);
+
+        let busy = anyhow::Error::from(io::Error::from(io::ErrorKind::ResourceBusy));
+        let error = explain_read_only(path, busy);
+        assert!(error.to_string().contains("dns-resolv-path"));
+        assert!(error.to_string().contains(RESOLV_CONF));
+
+        let denied = anyhow::Error::from(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert!(!explain_read_only(path, denied).to_string().contains("dns-resolv-path"));
+    }
+
+    #[test]
+    fn test_resolv_conf_lines() {
+        let config = ResolverConfig {
+            search_domains: vec!["corp.example.com.".to_owned(), "~dom2.net".to_owned()],
+            dns_servers: vec!["10.0.0.1".parse().unwrap()],
+            ..Default::default()
+        };
+        assert_eq!(
+            config.resolv_conf_lines(),
+            vec!["search corp.example.com dom2.net", "nameserver 10.0.0.1"]
+        );
+        assert!(ResolverConfig::default().resolv_conf_lines().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_direct_configurator_symlink() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let target = dir.path().join("stub-resolv.conf");
+        fs::write(&target, "nameserver 10.0.0.1\n").unwrap();
+
+        let conf = dir.path().join("resolv.conf");
+        std::os::unix::fs::symlink(&target, &conf).unwrap();
+
+        let cut = DirectConfigurator::new(&conf);
+        let config = ResolverConfig::default();
+
+        assert!(cut.configure(&config).await.is_err());
+        assert!(fs::symlink_metadata(&conf).unwrap().is_symlink());
+        assert!(!cut.backup_path.exists());
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    fs,
+    os::fd::{AsFd, OwnedFd},
+};
+
+use nix::{
+    fcntl::{self, FcntlArg, OFlag},
+    sys::stat::Mode,
+    unistd,
+};
+
+pub struct SingleInstance {
+    name: String,
+    handle: Option<OwnedFd>,
+    owner: Option<i32>,
+}
+
+unsafe impl Send for SingleInstance {}
+unsafe impl Sync for SingleInstance {}
+
+impl SingleInstance {
+    pub fn new<N: AsRef<str>>(name: N) -> anyhow::Result<Self> {
+        let fd = fcntl::open(
+            name.as_ref(),
+            OFlag::O_RDWR | OFlag::O_CREAT,
+            Mode::from_bits_truncate(0o600),
+        )?;
+
+        let mut fl = libc::flock {
+            l_type: libc::F_WRLCK as _,
+            l_whence: libc::SEEK_SET as _,
+            l_start: 0,
+            l_len: 0,
+            l_pid: 0,
+        };
+
+        match fcntl::fcntl(fd.as_fd(), FcntlArg::F_SETLK(&fl)) {
+            Ok(_) => Ok(SingleInstance {
+                name: name.as_ref().to_owned(),
+                handle: Some(fd),
+                owner: None,
+            }),
+            Err(_) => {
+                // the kernel reports the process holding the lock, a crashed one does not hold it anymore
+                let owner = fcntl::fcntl(fd.as_fd(), FcntlArg::F_GETLK(&mut fl))
+                    .ok()
+                    .and_then(|_| (fl.l_type != libc::F_UNLCK as _).then_some(fl.l_pid));
+                let _ = unistd::close(fd);
+                Ok(SingleInstance {
+                    name: name.as_ref().to_owned(),
+                    handle: None,
+                    owner,
+                })
+            }
+        }
+    }
+
+    pub fn is_single(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    /// Process ID of the instance holding the lock, if another one does.
+    pub fn owner(&self) -> Option<i32> {
+        self.owner
+    }
+}
+
+impl Drop for SingleInstance {
+    fn drop(&mut self) {
+        // removed while still locked, otherwise a new instance may lock the file which is then unlinked
+        if let Some(handle) = self.handle.take() {
+            let _ = fs::remove_file(&self.name);
+            let _ = unistd::close(handle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        io::{BufRead, BufReader},
+        process::{Command, Stdio},
+        thread,
+        time::Duration,
+    };
+
+    use super::*;
+
+    const LOCK_ENV: &str = "SNX_TEST_HOLD_LOCK";
+
+    // the locks are per process, the other instance is this test binary running the helper below
+    #[test]
+    #[ignore]
+    fn hold_lock() {
+        if let Ok(path) = env::var(LOCK_ENV) {
+            let _instance = SingleInstance::new(path).unwrap();
+            println!("locked");
+            thread::sleep(Duration::from_secs(30));
+        }
+    }
+
+    #[test]
+    fn test_locked_by_another_process() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snx-rs-work.lock");
+        let path = path.to_str().unwrap();
+
+        let mut child = Command::new(env::current_exe().unwrap())
+            .args([
+                "platform::single_instance::tests::hold_lock",
+                "--exact",
+                "--ignored",
+                "--nocapture",
+            ])
+            .env(LOCK_ENV, path)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        assert!(stdout.lines().map_while(Result::ok).any(|line| line == "locked"));
+
+        let instance = SingleInstance::new(path).unwrap();
+        assert!(!instance.is_single());
+        assert_eq!(instance.owner(), Some(child.id() as i32));
+
+        // the lock of a killed process is released by the kernel, the file left behind does not matter
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        let instance = SingleInstance::new(path).unwrap();
+        assert!(instance.is_single());
+        assert_eq!(instance.owner(), None);
+    }
+}
@@ -0,0 +1,267 @@
+use std::net::Ipv4Addr;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+
+use crate::{
+    model::{
+        IpsecSession,
+        params::{Ipv6BlockMode, TunnelParams},
+    },
+    platform::{
+        AppRouteConfig, AppRouting, Authority, DnsRedirect, DnsRedirectConfig, Forwarding, ForwardingConfig,
+        IpsecConfigurator, Ipv6BlockConfig, Ipv6Blocker, Keychain, KillSwitch, KillSwitchConfig, MssClamp,
+        NamespaceConfig, NetworkNamespace, PeerCredentials, RoutingPolicy,
+    },
+};
+
+// Kernel IPSec is only used on Linux, other platforms always fall back to the userspace ESP implementation.
+pub struct UnsupportedIpsecConfigurator;
+
+impl UnsupportedIpsecConfigurator {
+    pub fn new(
+        _name: &str,
+        _ipsec_session: IpsecSession,
+        _src_port: u16,
+        _dest_ip: Ipv4Addr,
+        _dest_port: u16,
+        _mtu: u16,
+        _policy: RoutingPolicy,
+    ) -> anyhow::Result<Self> {
+        Ok(Self)
+    }
+}
+
+#[async_trait]
+impl IpsecConfigurator for UnsupportedIpsecConfigurator {
+    async fn configure(&mut self) -> anyhow::Result<()> {
+        Err(anyhow!("Native IPSec is not supported on this platform"))
+    }
+
+    async fn rekey(&mut self, _session: &IpsecSession) -> anyhow::Result<()> {
+        Err(anyhow!("Native IPSec is not supported on this platform"))
+    }
+
+    async fn cleanup(&mut self) {}
+}
+
+// The password must be entered or configured explicitly on platforms without a supported keychain.
+#[cfg(not(target_os = "macos"))]
+#[derive(Default)]
+pub struct UnsupportedKeychain;
+
+#[cfg(not(target_os = "macos"))]
+impl UnsupportedKeychain {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+#[async_trait]
+impl Keychain for UnsupportedKeychain {
+    async fn acquire_password(&self, _username: &str) -> anyhow::Result<String> {
+        Err(anyhow!("Keychain is not supported on this platform"))
+    }
+
+    async fn store_password(&self, _username: &str, _password: &str) -> anyhow::Result<()> {
+        Err(anyhow!("Keychain is not supported on this platform"))
+    }
+}
+
+// The kill switch is implemented with nftables which is only available on Linux.
+#[derive(Default)]
+pub struct UnsupportedKillSwitch;
+
+impl UnsupportedKillSwitch {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl KillSwitch for UnsupportedKillSwitch {
+    async fn enable(&self, _config: &KillSwitchConfig) -> anyhow::Result<()> {
+        Err(anyhow!("Kill switch is not supported on this platform"))
+    }
+
+    async fn disable(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+// LAN forwarding relies on nftables masquerading which is only available on Linux.
+#[derive(Default)]
+pub struct UnsupportedForwarding;
+
+impl UnsupportedForwarding {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Forwarding for UnsupportedForwarding {
+    async fn enable(&self, _config: &ForwardingConfig) -> anyhow::Result<()> {
+        Err(anyhow!("LAN forwarding is not supported on this platform"))
+    }
+
+    async fn disable(&self, _config: &ForwardingConfig) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+// The other platforms have no per-interface IPv6 switch which could be restored reliably.
+#[derive(Default)]
+pub struct UnsupportedIpv6Blocker;
+
+impl UnsupportedIpv6Blocker {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Ipv6Blocker for UnsupportedIpv6Blocker {
+    async fn prepare(&self, mode: Ipv6BlockMode, device: &str) -> anyhow::Result<Ipv6BlockConfig> {
+        Ok(Ipv6BlockConfig {
+            mode,
+            device: device.to_owned(),
+            interfaces: Vec::new(),
+        })
+    }
+
+    async fn block(&self, _config: &Ipv6BlockConfig) -> anyhow::Result<()> {
+        Err(anyhow!("Blocking IPv6 is not supported on this platform"))
+    }
+
+    async fn unblock(&self, _config: &Ipv6BlockConfig) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+// MSS clamping is done with nftables which is only available on Linux.
+#[derive(Default)]
+pub struct UnsupportedMssClamp;
+
+impl UnsupportedMssClamp {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl MssClamp for UnsupportedMssClamp {
+    async fn enable(&self, _device: &str) -> anyhow::Result<()> {
+        Err(anyhow!("MSS clamping is not supported on this platform"))
+    }
+
+    async fn disable(&self, _device: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+// Named network namespaces are a Linux feature.
+#[derive(Default)]
+pub struct UnsupportedNetworkNamespace;
+
+impl UnsupportedNetworkNamespace {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl NetworkNamespace for UnsupportedNetworkNamespace {
+    async fn exists(&self, _name: &str) -> bool {
+        false
+    }
+
+    async fn create(&self, _name: &str) -> anyhow::Result<()> {
+        Err(anyhow!("Network namespaces are not supported on this platform"))
+    }
+
+    async fn delete(&self, _name: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn configure(&self, _config: &NamespaceConfig) -> anyhow::Result<()> {
+        Err(anyhow!("Network namespaces are not supported on this platform"))
+    }
+
+    async fn cleanup(&self, _config: &NamespaceConfig) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+// The application routing is done with nftables and policy routing which are only available on Linux.
+#[derive(Default)]
+pub struct UnsupportedAppRouting;
+
+impl UnsupportedAppRouting {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl AppRouting for UnsupportedAppRouting {
+    async fn enable(&self, _config: &AppRouteConfig) -> anyhow::Result<()> {
+        Err(anyhow!("Application routing is not supported on this platform"))
+    }
+
+    async fn disable(&self, _config: &AppRouteConfig) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+// The DNS redirection is done with nftables which is only available on Linux.
+#[derive(Default)]
+pub struct UnsupportedDnsRedirect;
+
+impl UnsupportedDnsRedirect {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl DnsRedirect for UnsupportedDnsRedirect {
+    async fn enable(&self, _config: &DnsRedirectConfig) -> anyhow::Result<()> {
+        Err(anyhow!("DNS redirection is not supported on this platform"))
+    }
+
+    async fn disable(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+// The capabilities used to retain the network administration after the switch are Linux-specific.
+pub fn check_privilege_drop(params: &TunnelParams) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        params.run_as_user.is_none(),
+        "Running as an unprivileged user is not supported on this platform"
+    );
+    Ok(())
+}
+
+pub fn drop_privileges(_params: &TunnelParams) -> anyhow::Result<()> {
+    Ok(())
+}
+
+// Without polkit the requests are only checked against the ownership of the session.
+#[derive(Default)]
+pub struct UnsupportedAuthority;
+
+impl UnsupportedAuthority {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Authority for UnsupportedAuthority {
+    async fn check_authorization(&self, _peer: PeerCredentials, _action_id: &str) -> anyhow::Result<bool> {
+        Ok(true)
+    }
+}
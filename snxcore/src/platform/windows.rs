@@ -0,0 +1,82 @@
+use std::{net::SocketAddr, time::Duration};
+
+use anyhow::{Context, anyhow};
+use cached::proc_macro::cached;
+pub use net::WindowsNetworkInterface as NetworkInterfaceImpl;
+pub use resolver::{dns_backend_name, effective_dns_mode, new_resolver_configurator, restore_stale_resolv_conf};
+pub use routing::{WindowsRoutingConfigurator as RoutingImpl, restore_stale_routing};
+use tokio::net::{TcpSocket, UdpSocket};
+use uuid::Uuid;
+
+pub use crate::platform::unsupported::{
+    UnsupportedAppRouting as AppRoutingImpl, UnsupportedAuthority as AuthorityImpl,
+    UnsupportedDnsRedirect as DnsRedirectImpl, UnsupportedForwarding as ForwardingImpl,
+    UnsupportedIpsecConfigurator as IpsecImpl, UnsupportedIpv6Blocker as Ipv6BlockerImpl,
+    UnsupportedKeychain as KeychainImpl, UnsupportedKillSwitch as KillSwitchImpl, UnsupportedMssClamp as MssClampImpl,
+    UnsupportedNetworkNamespace as NetworkNamespaceImpl, check_privilege_drop, drop_privileges,
+};
+use crate::{
+    model::params::TunnelParams,
+    platform::{PlatformFeatures, TcpSocketExt, UdpEncap, UdpSocketExt},
+};
+
+pub mod iphelper;
+pub mod net;
+pub mod resolver;
+mod routing;
+
+const CRYPTOGRAPHY_KEY: &str = r"HKLM\SOFTWARE\Microsoft\Cryptography";
+
+pub fn init() {}
+
+pub fn apply_params(_params: &TunnelParams) {}
+
+// Creating the wintun adapter fails with an access error when not running as administrator.
+pub fn check_permissions(_params: &TunnelParams) -> anyhow::Result<()> {
+    Ok(())
+}
+
+impl TcpSocketExt for TcpSocket {
+    fn set_mark(&self, _mark: u32) -> anyhow::Result<()> {
+        Err(anyhow!("Socket marks are not supported on Windows"))
+    }
+}
+
+#[async_trait::async_trait]
+impl UdpSocketExt for UdpSocket {
+    fn set_encap(&self, _encap: UdpEncap) -> anyhow::Result<()> {
+        Err(anyhow!("UDP encapsulation is not supported on Windows"))
+    }
+
+    fn set_no_check(&self, _flag: bool) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn send_receive(&self, data: &[u8], timeout: Duration, target: SocketAddr) -> anyhow::Result<Vec<u8>> {
+        super::udp_send_receive(self, data, timeout, target).await
+    }
+}
+
+#[cached(result = true)]
+pub fn get_machine_uuid() -> anyhow::Result<Uuid> {
+    let output = std::process::Command::new("reg")
+        .args(["query", CRYPTOGRAPHY_KEY, "/v", "MachineGuid"])
+        .output()?;
+
+    let guid = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("MachineGuid"))
+        .and_then(|line| line.split_whitespace().last())
+        .map(ToOwned::to_owned)
+        .context("No machine GUID")?;
+
+    Ok(Uuid::try_parse(&guid)?)
+}
+
+pub async fn get_features() -> PlatformFeatures {
+    PlatformFeatures {
+        ipsec_native: false,
+        ipsec_keepalive: false,
+        split_dns: false,
+    }
+}
@@ -0,0 +1,301 @@
+//! Thin wrappers over the IP Helper API used to configure the wintun adapter.
+
+use std::{
+    io, mem,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    slice,
+};
+
+use ipnet::{IpNet, Ipv4Net};
+use windows_sys::{
+    Win32::{
+        Foundation::{ERROR_NOT_FOUND, ERROR_OBJECT_ALREADY_EXISTS, NO_ERROR, WIN32_ERROR},
+        NetworkManagement::{
+            IpHelper::{
+                ConvertInterfaceAliasToLuid, ConvertInterfaceLuidToAlias, ConvertInterfaceLuidToGuid,
+                CreateIpForwardEntry2, CreateUnicastIpAddressEntry, DNS_INTERFACE_SETTINGS,
+                DNS_INTERFACE_SETTINGS_VERSION1, DNS_SETTING_IPV6, DNS_SETTING_NAMESERVER, DNS_SETTING_SEARCHLIST,
+                DeleteIpForwardEntry2, DeleteUnicastIpAddressEntry, FreeMibTable, GetIpForwardTable2,
+                GetIpInterfaceEntry, GetUnicastIpAddressTable, InitializeIpForwardEntry, InitializeIpInterfaceEntry,
+                InitializeUnicastIpAddressEntry, MIB_IPFORWARD_ROW2, MIB_IPFORWARD_TABLE2, MIB_IPINTERFACE_ROW,
+                MIB_UNICASTIPADDRESS_ROW, MIB_UNICASTIPADDRESS_TABLE, SetInterfaceDnsSettings, SetIpInterfaceEntry,
+            },
+            Ndis::NET_LUID_LH,
+        },
+        Networking::WinSock::{AF_INET, AF_INET6, IpDadStatePreferred, SOCKADDR_INET},
+    },
+    core::GUID,
+};
+
+// IF_MAX_STRING_SIZE + 1
+const ALIAS_BUFFER_LEN: usize = 257;
+
+pub type InterfaceLuid = NET_LUID_LH;
+
+/// IPv4 default route of the routing table.
+#[derive(Clone, Copy)]
+pub struct DefaultRoute {
+    pub gateway: Ipv4Addr,
+    pub luid: InterfaceLuid,
+    pub metric: u32,
+}
+
+fn check(rc: WIN32_ERROR) -> anyhow::Result<()> {
+    if rc == NO_ERROR {
+        Ok(())
+    } else {
+        Err(io::Error::from_raw_os_error(rc as i32).into())
+    }
+}
+
+fn to_wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(Some(0)).collect()
+}
+
+fn to_sockaddr(addr: IpAddr) -> SOCKADDR_INET {
+    unsafe {
+        let mut sockaddr: SOCKADDR_INET = mem::zeroed();
+        match addr {
+            IpAddr::V4(v4) => {
+                sockaddr.Ipv4.sin_family = AF_INET;
+                sockaddr.Ipv4.sin_addr.S_un.S_addr = u32::from_ne_bytes(v4.octets());
+            }
+            IpAddr::V6(v6) => {
+                sockaddr.Ipv6.sin6_family = AF_INET6;
+                sockaddr.Ipv6.sin6_addr.u.Byte = v6.octets();
+            }
+        }
+        sockaddr
+    }
+}
+
+fn from_sockaddr(sockaddr: &SOCKADDR_INET) -> Option<IpAddr> {
+    unsafe {
+        match sockaddr.si_family {
+            AF_INET => Some(Ipv4Addr::from(sockaddr.Ipv4.sin_addr.S_un.S_addr.to_ne_bytes()).into()),
+            AF_INET6 => Some(Ipv6Addr::from(sockaddr.Ipv6.sin6_addr.u.Byte).into()),
+            _ => None,
+        }
+    }
+}
+
+pub fn same_interface(a: &InterfaceLuid, b: &InterfaceLuid) -> bool {
+    unsafe { a.Value == b.Value }
+}
+
+pub fn interface_luid(alias: &str) -> anyhow::Result<InterfaceLuid> {
+    let alias = to_wide(alias);
+    unsafe {
+        let mut luid: NET_LUID_LH = mem::zeroed();
+        check(ConvertInterfaceAliasToLuid(alias.as_ptr(), &mut luid))?;
+        Ok(luid)
+    }
+}
+
+pub fn interface_alias(luid: &InterfaceLuid) -> anyhow::Result<String> {
+    let mut buf = [0u16; ALIAS_BUFFER_LEN];
+    check(unsafe { ConvertInterfaceLuidToAlias(luid, buf.as_mut_ptr(), buf.len()) })?;
+    let len = buf.iter().position(|c| *c == 0).unwrap_or(buf.len());
+    Ok(String::from_utf16_lossy(&buf[..len]))
+}
+
+fn address_row(luid: &InterfaceLuid, address: IpNet) -> MIB_UNICASTIPADDRESS_ROW {
+    unsafe {
+        let mut row: MIB_UNICASTIPADDRESS_ROW = mem::zeroed();
+        InitializeUnicastIpAddressEntry(&mut row);
+        row.InterfaceLuid = *luid;
+        row.Address = to_sockaddr(address.addr());
+        row.OnLinkPrefixLength = address.prefix_len();
+        row.DadState = IpDadStatePreferred;
+        row
+    }
+}
+
+pub fn add_address(luid: &InterfaceLuid, address: IpNet) -> anyhow::Result<()> {
+    match unsafe { CreateUnicastIpAddressEntry(&address_row(luid, address)) } {
+        ERROR_OBJECT_ALREADY_EXISTS => Ok(()),
+        rc => check(rc),
+    }
+}
+
+pub fn delete_address(luid: &InterfaceLuid, address: IpNet) -> anyhow::Result<()> {
+    match unsafe { DeleteUnicastIpAddressEntry(&address_row(luid, address)) } {
+        ERROR_NOT_FOUND => Ok(()),
+        rc => check(rc),
+    }
+}
+
+/// First IPv4 address assigned to the interface.
+pub fn interface_ipv4_address(luid: &InterfaceLuid) -> anyhow::Result<Option<Ipv4Addr>> {
+    unsafe {
+        let mut table: *mut MIB_UNICASTIPADDRESS_TABLE = std::ptr::null_mut();
+        check(GetUnicastIpAddressTable(AF_INET, &mut table))?;
+
+        let rows = slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize);
+        let result = rows
+            .iter()
+            .filter(|row| same_interface(&row.InterfaceLuid, luid))
+            .find_map(|row| match from_sockaddr(&row.Address) {
+                Some(IpAddr::V4(v4)) => Some(v4),
+                _ => None,
+            });
+
+        FreeMibTable(table as _);
+
+        Ok(result)
+    }
+}
+
+/// IPv4 addresses of all interfaces with their on-link prefixes.
+pub fn ipv4_networks() -> anyhow::Result<Vec<(InterfaceLuid, Ipv4Net)>> {
+    unsafe {
+        let mut table: *mut MIB_UNICASTIPADDRESS_TABLE = std::ptr::null_mut();
+        check(GetUnicastIpAddressTable(AF_INET, &mut table))?;
+
+        let rows = slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize);
+        let result = rows
+            .iter()
+            .filter_map(|row| match from_sockaddr(&row.Address) {
+                Some(IpAddr::V4(v4)) => Ipv4Net::new(v4, row.OnLinkPrefixLength)
+                    .ok()
+                    .map(|net| (row.InterfaceLuid, net)),
+                _ => None,
+            })
+            .collect();
+
+        FreeMibTable(table as _);
+
+        Ok(result)
+    }
+}
+
+fn route_row(luid: &InterfaceLuid, destination: IpNet, next_hop: Option<IpAddr>) -> MIB_IPFORWARD_ROW2 {
+    let next_hop = next_hop.unwrap_or(match destination {
+        IpNet::V4(_) => Ipv4Addr::UNSPECIFIED.into(),
+        IpNet::V6(_) => Ipv6Addr::UNSPECIFIED.into(),
+    });
+
+    unsafe {
+        let mut row: MIB_IPFORWARD_ROW2 = mem::zeroed();
+        InitializeIpForwardEntry(&mut row);
+        row.InterfaceLuid = *luid;
+        row.DestinationPrefix.Prefix = to_sockaddr(destination.network());
+        row.DestinationPrefix.PrefixLength = destination.prefix_len();
+        row.NextHop = to_sockaddr(next_hop);
+        row.Metric = 0;
+        row
+    }
+}
+
+/// Add a route through the interface, an on-link route is created when there is no next hop.
+/// The metric is added to the interface metric, zero is used when it is not given.
+pub fn add_route(
+    luid: &InterfaceLuid,
+    destination: IpNet,
+    next_hop: Option<IpAddr>,
+    metric: Option<u32>,
+) -> anyhow::Result<()> {
+    let mut row = route_row(luid, destination, next_hop);
+    row.Metric = metric.unwrap_or_default();
+
+    match unsafe { CreateIpForwardEntry2(&row) } {
+        ERROR_OBJECT_ALREADY_EXISTS => Ok(()),
+        rc => check(rc),
+    }
+}
+
+pub fn delete_route(luid: &InterfaceLuid, destination: IpNet, next_hop: Option<IpAddr>) -> anyhow::Result<()> {
+    match unsafe { DeleteIpForwardEntry2(&route_row(luid, destination, next_hop)) } {
+        ERROR_NOT_FOUND => Ok(()),
+        rc => check(rc),
+    }
+}
+
+/// IPv4 default routes ordered by metric.
+pub fn default_routes() -> anyhow::Result<Vec<DefaultRoute>> {
+    unsafe {
+        let mut table: *mut MIB_IPFORWARD_TABLE2 = std::ptr::null_mut();
+        check(GetIpForwardTable2(AF_INET, &mut table))?;
+
+        let rows = slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize);
+        let mut result = rows
+            .iter()
+            .filter(|row| row.DestinationPrefix.PrefixLength == 0)
+            .filter_map(|row| match from_sockaddr(&row.NextHop) {
+                Some(IpAddr::V4(gateway)) if !gateway.is_unspecified() => Some(DefaultRoute {
+                    gateway,
+                    luid: row.InterfaceLuid,
+                    metric: row.Metric,
+                }),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        FreeMibTable(table as _);
+
+        result.sort_by_key(|route| route.metric);
+
+        Ok(result)
+    }
+}
+
+pub fn set_mtu(luid: &InterfaceLuid, mtu: u16) -> anyhow::Result<()> {
+    for family in [AF_INET, AF_INET6] {
+        unsafe {
+            let mut row: MIB_IPINTERFACE_ROW = mem::zeroed();
+            InitializeIpInterfaceEntry(&mut row);
+            row.Family = family;
+            row.InterfaceLuid = *luid;
+
+            // the IPv6 stack may be disabled on the adapter
+            if GetIpInterfaceEntry(&mut row) != NO_ERROR {
+                continue;
+            }
+
+            row.NlMtu = mtu.into();
+            if family == AF_INET {
+                row.SitePrefixLength = 0;
+            }
+            check(SetIpInterfaceEntry(&mut row))?;
+        }
+    }
+    Ok(())
+}
+
+/// Set the per-interface name servers and search list, empty lists clear the settings.
+pub fn set_dns(luid: &InterfaceLuid, servers: &[IpAddr], search_domains: &[String]) -> anyhow::Result<()> {
+    let guid = unsafe {
+        let mut guid: GUID = mem::zeroed();
+        check(ConvertInterfaceLuidToGuid(luid, &mut guid))?;
+        guid
+    };
+
+    for ipv6 in [false, true] {
+        let servers = servers
+            .iter()
+            .filter(|server| server.is_ipv6() == ipv6)
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        let mut name_server = to_wide(&servers.join(","));
+        let mut search_list = to_wide(&search_domains.join(","));
+
+        unsafe {
+            let mut settings: DNS_INTERFACE_SETTINGS = mem::zeroed();
+            settings.Version = DNS_INTERFACE_SETTINGS_VERSION1;
+            settings.NameServer = name_server.as_mut_ptr();
+            settings.Flags = u64::from(DNS_SETTING_NAMESERVER);
+
+            if ipv6 {
+                settings.Flags |= u64::from(DNS_SETTING_IPV6);
+            } else {
+                settings.SearchList = search_list.as_mut_ptr();
+                settings.Flags |= u64::from(DNS_SETTING_SEARCHLIST);
+            }
+
+            check(SetInterfaceDnsSettings(guid, &settings))?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use std::{
+    net::Ipv4Addr,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use anyhow::{Context, anyhow};
+use async_trait::async_trait;
+use ipnet::{Ipv4Net, Ipv6Net};
+use once_cell::sync::Lazy;
+use tokio::sync::watch;
+use tracing::{debug, trace};
+
+use crate::{
+    platform::{DefaultGateway, LocalNetwork, NetworkInterface, windows::iphelper},
+    util,
+};
+
+static ONLINE_STATE: AtomicBool = AtomicBool::new(true);
+static NETWORK_CHANGES: Lazy<watch::Sender<u64>> = Lazy::new(|| watch::Sender::new(0));
+
+const NETWORK_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+fn default_route(exclude_device: &str) -> anyhow::Result<Option<(Ipv4Addr, String, Option<Ipv4Addr>)>> {
+    for route in iphelper::default_routes()? {
+        let device = iphelper::interface_alias(&route.luid)?;
+        if device != exclude_device {
+            let address = iphelper::interface_ipv4_address(&route.luid)?;
+            return Ok(Some((route.gateway, device, address)));
+        }
+    }
+    Ok(None)
+}
+
+// The `SSID` lines of `netsh wlan show interfaces`, the `BSSID` ones are skipped.
+fn parse_netsh_ssids(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(key, _)| key.trim() == "SSID")
+        .map(|(_, ssid)| ssid.trim().to_owned())
+        .filter(|ssid| !ssid.is_empty())
+        .collect()
+}
+
+#[derive(Default)]
+pub struct WindowsNetworkInterface;
+
+impl WindowsNetworkInterface {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl NetworkInterface for WindowsNetworkInterface {
+    // The default route is polled, a change of the gateway or of the local address is reported as a network change.
+    async fn start_network_state_monitoring(&self) -> anyhow::Result<()> {
+        tokio::spawn(async move {
+            let mut last = None;
+            loop {
+                let current = tokio::task::spawn_blocking(|| default_route("").ok().flatten())
+                    .await
+                    .ok()
+                    .flatten();
+
+                let online = current.is_some();
+                if ONLINE_STATE.swap(online, Ordering::SeqCst) != online {
+                    debug!("Network online state changed to {}", online);
+                }
+
+                if current != last {
+                    trace!("Network change: {:?}", current);
+                    NETWORK_CHANGES.send_modify(|v| *v = v.wrapping_add(1));
+                    last = current;
+                }
+
+                tokio::time::sleep(NETWORK_POLL_INTERVAL).await;
+            }
+        });
+
+        Ok(())
+    }
+
+    async fn get_default_ip(&self) -> anyhow::Result<Ipv4Addr> {
+        default_route("")?
+            .and_then(|(_, _, address)| address)
+            .ok_or_else(|| anyhow!(i18n::tr!("error-cannot-determine-ip")))
+    }
+
+    async fn get_default_gateway(&self, exclude_device: &str) -> anyhow::Result<DefaultGateway> {
+        let (address, device, local_address) = default_route(exclude_device)?.context("No default gateway")?;
+
+        Ok(DefaultGateway {
+            address,
+            device,
+            mac: None,
+            local_address,
+        })
+    }
+
+    // The system search list is applied by the DNS client to all interfaces, there is nothing to merge.
+    async fn get_search_domains(&self, _exclude_device: &str) -> anyhow::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_local_networks(&self, exclude_device: &str) -> anyhow::Result<Vec<LocalNetwork>> {
+        let mut result = Vec::new();
+        for (luid, network) in iphelper::ipv4_networks()? {
+            let device = iphelper::interface_alias(&luid)?;
+            if device != exclude_device && !network.addr().is_loopback() {
+                result.push(LocalNetwork {
+                    device,
+                    network: network.trunc(),
+                });
+            }
+        }
+        Ok(result)
+    }
+
+    async fn get_wireless_ssids(&self) -> Vec<String> {
+        util::run_command("netsh", ["wlan", "show", "interfaces"])
+            .await
+            .map(|output| parse_netsh_ssids(&output))
+            .unwrap_or_default()
+    }
+
+    // The wintun adapter is removed when the tun device is dropped.
+    async fn delete_device(&self, _device_name: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn configure_device(&self, _device_name: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn replace_ip_address(
+        &self,
+        device_name: &str,
+        old_address: Ipv4Net,
+        new_address: Ipv4Net,
+    ) -> anyhow::Result<()> {
+        let luid = iphelper::interface_luid(device_name)?;
+        iphelper::add_address(&luid, new_address.into())?;
+        iphelper::delete_address(&luid, old_address.into())?;
+        Ok(())
+    }
+
+    async fn add_ipv6_address(&self, device_name: &str, address: Ipv6Net) -> anyhow::Result<()> {
+        let luid = iphelper::interface_luid(device_name)?;
+        iphelper::add_address(&luid, address.into())
+    }
+
+    async fn set_mtu(&self, device_name: &str, mtu: u16) -> anyhow::Result<()> {
+        let luid = iphelper::interface_luid(device_name)?;
+        iphelper::set_mtu(&luid, mtu)
+    }
+
+    async fn update_connectivity(&self) {}
+
+    fn is_online(&self) -> bool {
+        ONLINE_STATE.load(Ordering::SeqCst)
+    }
+
+    fn network_changes(&self) -> watch::Receiver<u64> {
+        NETWORK_CHANGES.subscribe()
+    }
+
+    fn poll_online(&self) {
+        let online = default_route("").is_ok_and(|route| route.is_some());
+        debug!("Acquired network state via polling: {}", online);
+        ONLINE_STATE.store(online, Ordering::SeqCst);
+    }
+}
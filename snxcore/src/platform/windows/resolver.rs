@@ -0,0 +1,139 @@
+use std::net::Ipv4Addr;
+
+use async_trait::async_trait;
+use tracing::{debug, warn};
+
+use crate::{
+    model::params::{DnsBackend, DnsMode, TunnelParams},
+    platform::{PrintConfigurator, ResolverConfig, ResolverConfigurator, windows::iphelper},
+    util,
+};
+
+// Sets the name servers, the search list and the WINS servers of the tunnel adapter.
+struct InterfaceDnsConfigurator {
+    device: String,
+}
+
+#[async_trait]
+impl ResolverConfigurator for InterfaceDnsConfigurator {
+    async fn configure(&self, config: &ResolverConfig) -> anyhow::Result<()> {
+        debug!("Configuring DNS for {}", self.device);
+
+        // the search list has no concept of routing domains
+        let search_domains = config
+            .search_domains
+            .iter()
+            .map(|s| s.trim_start_matches('~').to_owned())
+            .collect::<Vec<_>>();
+
+        let luid = iphelper::interface_luid(&self.device)?;
+        iphelper::set_dns(&luid, &config.dns_servers, &search_domains)?;
+
+        if let Err(e) = self.set_wins(&config.wins_servers).await {
+            warn!("Unable to set WINS servers for {}: {}", self.device, e);
+        }
+
+        Ok(())
+    }
+
+    async fn cleanup(&self, config: &ResolverConfig) -> anyhow::Result<()> {
+        debug!("Removing DNS configuration for {}", self.device);
+        let luid = iphelper::interface_luid(&self.device)?;
+        iphelper::set_dns(&luid, &[], &[])?;
+
+        if !config.wins_servers.is_empty() {
+            let _ = self.set_wins(&[]).await;
+        }
+
+        Ok(())
+    }
+}
+
+impl InterfaceDnsConfigurator {
+    // There is no IP helper function for the NetBIOS name servers of an adapter.
+    async fn set_wins(&self, servers: &[Ipv4Addr]) -> anyhow::Result<()> {
+        let name = format!("name={}", self.device);
+
+        let Some((first, rest)) = servers.split_first() else {
+            util::run_command(
+                "netsh",
+                ["interface", "ipv4", "delete", "winsservers", &name, "address=all"],
+            )
+            .await?;
+            return Ok(());
+        };
+
+        debug!("Setting WINS servers for {}: {:?}", self.device, servers);
+
+        let address = format!("address={first}");
+        util::run_command(
+            "netsh",
+            [
+                "interface",
+                "ipv4",
+                "set",
+                "winsservers",
+                &name,
+                "source=static",
+                &address,
+            ],
+        )
+        .await?;
+
+        for server in rest {
+            let address = format!("address={server}");
+            util::run_command("netsh", ["interface", "ipv4", "add", "winsservers", &name, &address]).await?;
+        }
+
+        Ok(())
+    }
+}
+
+struct NoopConfigurator;
+
+#[async_trait]
+impl ResolverConfigurator for NoopConfigurator {
+    async fn configure(&self, _config: &ResolverConfig) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn cleanup(&self, _config: &ResolverConfig) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// All backends except `none` and `print` configure the DNS settings of the tunnel adapter on Windows.
+pub fn new_resolver_configurator<S>(
+    device: S,
+    params: &TunnelParams,
+) -> anyhow::Result<Box<dyn ResolverConfigurator + Send + Sync>>
+where
+    S: AsRef<str>,
+{
+    let device = device.as_ref().to_owned();
+
+    match params.dns_backend {
+        DnsBackend::None => Ok(Box::new(NoopConfigurator)),
+        DnsBackend::Print => Ok(Box::new(PrintConfigurator)),
+        _ => Ok(Box::new(InterfaceDnsConfigurator { device })),
+    }
+}
+
+/// DNS backend selected by the parameters, all but `none` and `print` use the adapter settings.
+pub fn dns_backend_name(params: &TunnelParams) -> String {
+    match params.dns_backend {
+        DnsBackend::None | DnsBackend::Print => params.dns_backend.to_string(),
+        backend => format!("{backend}: adapter"),
+    }
+}
+
+/// Per-interface name servers are queried for all names, split DNS is not available.
+pub fn effective_dns_mode(params: &TunnelParams) -> DnsMode {
+    if params.dns_mode != DnsMode::Full {
+        warn!("Split DNS is not supported on Windows, all DNS queries will be sent to the tunnel resolvers");
+    }
+    DnsMode::Full
+}
+
+/// The adapter DNS settings disappear together with the adapter, there is nothing to restore.
+pub fn restore_stale_resolv_conf(_params: &TunnelParams) {}
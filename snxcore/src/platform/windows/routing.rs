@@ -0,0 +1,176 @@
+use std::{collections::HashSet, net::Ipv4Addr};
+
+use async_trait::async_trait;
+use ipnet::{IpNet, Ipv4Net, Ipv6Net};
+use tracing::debug;
+
+use crate::{
+    model::params::TunnelParams,
+    platform::{GatewayRoute, RoutingConfigurator, RoutingPolicy, windows::iphelper},
+};
+
+// Two halves of the address space take precedence over the system default route without replacing it.
+const DEFAULT_ROUTE_HALVES: [Ipv4Net; 2] = [
+    Ipv4Net::new_assert(Ipv4Addr::new(0, 0, 0, 0), 1),
+    Ipv4Net::new_assert(Ipv4Addr::new(128, 0, 0, 0), 1),
+];
+
+/// Routes are kept in the main routing table, the network journal reverts them after a crash.
+pub async fn restore_stale_routing(_params: &TunnelParams) {}
+
+pub struct WindowsRoutingConfigurator {
+    device: String,
+    metric: Option<u32>,
+}
+
+impl WindowsRoutingConfigurator {
+    pub fn new<S: AsRef<str>>(device: S, _address: Ipv4Addr, metric: Option<u32>) -> Self {
+        Self {
+            device: device.as_ref().to_string(),
+            metric,
+        }
+    }
+
+    fn add_route(&self, route: IpNet) -> anyhow::Result<()> {
+        debug!("Adding route: {} via {}", route, self.device);
+        iphelper::add_route(&iphelper::interface_luid(&self.device)?, route, None, self.metric)
+    }
+
+    fn original_gateway(&self) -> anyhow::Result<iphelper::DefaultRoute> {
+        let luid = iphelper::interface_luid(&self.device).ok();
+
+        iphelper::default_routes()?
+            .into_iter()
+            .find(|route| luid.is_none_or(|luid| !iphelper::same_interface(&route.luid, &luid)))
+            .ok_or_else(|| anyhow::anyhow!("No default gateway"))
+    }
+}
+
+#[async_trait]
+impl RoutingConfigurator for WindowsRoutingConfigurator {
+    async fn add_routes(&self, routes: &[Ipv4Net], ignore_routes: &[Ipv4Net]) -> anyhow::Result<()> {
+        let routes = routes.iter().collect::<HashSet<_>>();
+        debug!("Routes to add: {:?}", routes);
+
+        for route in routes {
+            if ignore_routes.iter().any(|ignore| ignore == route) {
+                debug!("Ignoring route: {}", route);
+                continue;
+            }
+            let _ = self.add_route((*route).into());
+        }
+
+        Ok(())
+    }
+
+    async fn add_ipv6_routes(&self, routes: &[Ipv6Net]) -> anyhow::Result<()> {
+        let routes = routes.iter().collect::<HashSet<_>>();
+        debug!("IPv6 routes to add: {:?}", routes);
+
+        for route in routes {
+            let _ = self.add_route((*route).into());
+        }
+
+        Ok(())
+    }
+
+    async fn remove_routes(&self, routes: &[IpNet]) -> anyhow::Result<()> {
+        let luid = iphelper::interface_luid(&self.device)?;
+        for route in routes {
+            debug!("Removing route: {} via {}", route, self.device);
+            let _ = iphelper::delete_route(&luid, *route, None);
+        }
+        Ok(())
+    }
+
+    async fn setup_default_route(&self, _destination: Ipv4Addr, _policy: &RoutingPolicy) -> anyhow::Result<()> {
+        debug!("Setting up default route through {}", self.device);
+
+        // the VPN server itself stays reachable through the pinned gateway route
+        for half in DEFAULT_ROUTE_HALVES {
+            self.add_route(half.into())?;
+        }
+
+        Ok(())
+    }
+
+    async fn check_default_route(&self, _destination: Ipv4Addr, _policy: &RoutingPolicy) -> anyhow::Result<bool> {
+        // the halves are more specific than any default route, they are active as long as the adapter exists
+        Ok(iphelper::interface_luid(&self.device).is_ok())
+    }
+
+    async fn setup_keepalive_route(
+        &self,
+        _destination: Ipv4Addr,
+        _with_table: bool,
+        _policy: &RoutingPolicy,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn remove_default_route(&self, _destination: Ipv4Addr, _policy: &RoutingPolicy) -> anyhow::Result<()> {
+        if let Ok(luid) = iphelper::interface_luid(&self.device) {
+            for half in DEFAULT_ROUTE_HALVES {
+                let _ = iphelper::delete_route(&luid, half.into(), None);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn remove_keepalive_route(&self, _destination: Ipv4Addr, _policy: &RoutingPolicy) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    // Excluded prefixes are more specific than the default route halves and go through the original gateway.
+    async fn setup_excluded_routes(&self, routes: &[Ipv4Net]) -> anyhow::Result<()> {
+        if routes.is_empty() {
+            return Ok(());
+        }
+
+        let gateway = self.original_gateway()?;
+
+        for route in routes {
+            debug!("Excluding route from the tunnel: {}", route);
+            iphelper::add_route(&gateway.luid, (*route).into(), Some(gateway.gateway.into()), None)?;
+        }
+        Ok(())
+    }
+
+    async fn remove_excluded_routes(&self, routes: &[Ipv4Net]) -> anyhow::Result<()> {
+        let gateway = self.original_gateway()?;
+        for route in routes {
+            let _ = iphelper::delete_route(&gateway.luid, (*route).into(), Some(gateway.gateway.into()));
+        }
+        Ok(())
+    }
+
+    async fn find_gateway_route(&self, destination: Ipv4Addr) -> anyhow::Result<GatewayRoute> {
+        let gateway = self.original_gateway()?;
+
+        Ok(GatewayRoute {
+            destination,
+            gateway: Some(gateway.gateway),
+            device: iphelper::interface_alias(&gateway.luid)?,
+        })
+    }
+
+    async fn add_gateway_route(&self, route: &GatewayRoute) -> anyhow::Result<()> {
+        debug!("Pinning gateway route: {:?}", route);
+        iphelper::add_route(
+            &iphelper::interface_luid(&route.device)?,
+            Ipv4Net::from(route.destination).into(),
+            route.gateway.map(Into::into),
+            None,
+        )
+    }
+
+    async fn remove_gateway_route(&self, route: &GatewayRoute) -> anyhow::Result<()> {
+        debug!("Removing gateway route: {:?}", route);
+        iphelper::delete_route(
+            &iphelper::interface_luid(&route.device)?,
+            Ipv4Net::from(route.destination).into(),
+            route.gateway.map(Into::into),
+        )
+    }
+}
@@ -1,8 +1,6 @@
 use std::io::{IsTerminal, Write, stderr, stdin};
 
-use anyhow::anyhow;
-
-use crate::model::PromptInfo;
+use crate::{error::NoTty, model::PromptInfo};
 
 #[async_trait::async_trait]
 pub trait SecurePrompt {
@@ -28,7 +26,7 @@ impl SecurePrompt for TtyPrompt {
                     passterm::Stream::Stderr,
                 )?)
             } else {
-                Err(anyhow!(i18n::tr!("error-no-tty")))
+                Err(anyhow::Error::from(NoTty))
             }
         })
         .await??)
@@ -46,7 +44,7 @@ impl SecurePrompt for TtyPrompt {
                 stdin().read_line(&mut line)?;
                 Ok(line.trim().to_owned())
             } else {
-                Err(anyhow!(i18n::tr!("error-no-tty")))
+                Err(anyhow::Error::from(NoTty))
             }
         })
         .await??)
@@ -0,0 +1,119 @@
+//! Sockets passed by systemd socket activation with the semantics of sd_listen_fds(3): the service manager owns
+//! the socket, so that the clients can connect before the service is started and the unit declares its permissions.
+
+use std::{
+    env,
+    os::{
+        fd::{FromRawFd, OwnedFd, RawFd},
+        unix::net::UnixListener,
+    },
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use anyhow::anyhow;
+use tracing::debug;
+
+const LISTEN_FDS: &str = "LISTEN_FDS";
+const LISTEN_FDNAMES: &str = "LISTEN_FDNAMES";
+const LISTEN_PID: &str = "LISTEN_PID";
+const LISTEN_FDS_START: RawFd = 3;
+
+// a descriptor must be owned once, it is closed on drop
+static TAKEN: AtomicBool = AtomicBool::new(false);
+
+// The passed descriptors with their names, the ones of a parent process are left alone.
+fn listen_fds(fds: Option<&str>, names: Option<&str>, pid: Option<&str>, own_pid: u32) -> Vec<(RawFd, String)> {
+    if pid.is_none_or(|pid| pid.parse::<u32>().ok() != Some(own_pid)) {
+        return Vec::new();
+    }
+
+    let count = fds.and_then(|fds| fds.parse::<RawFd>().ok()).unwrap_or_default();
+    let mut names = names.unwrap_or_default().split(':');
+
+    (0..count.max(0))
+        .map(|i| {
+            let name = names.next().filter(|name| !name.is_empty()).unwrap_or("unknown");
+            (LISTEN_FDS_START + i, name.to_owned())
+        })
+        .collect()
+}
+
+fn is_stream_socket(fd: RawFd) -> bool {
+    let mut kind: libc::c_int = 0;
+    let mut len = size_of::<libc::c_int>() as libc::socklen_t;
+    let rc = unsafe { libc::getsockopt(fd, libc::SOL_SOCKET, libc::SO_TYPE, (&raw mut kind).cast(), &mut len) };
+    rc == 0 && kind == libc::SOCK_STREAM
+}
+
+/// Take the passed socket of the given name, or the only passed socket whatever its name, `None` if the process
+/// was not socket activated. The descriptors are not inherited by the child processes.
+pub fn take_listener(name: &str) -> anyhow::Result<Option<UnixListener>> {
+    let fds = listen_fds(
+        env::var(LISTEN_FDS).ok().as_deref(),
+        env::var(LISTEN_FDNAMES).ok().as_deref(),
+        env::var(LISTEN_PID).ok().as_deref(),
+        std::process::id(),
+    );
+
+    for (fd, _) in &fds {
+        unsafe { libc::fcntl(*fd, libc::F_SETFD, libc::FD_CLOEXEC) };
+    }
+
+    let selected = match fds.as_slice() {
+        [] => return Ok(None),
+        [(fd, _)] => *fd,
+        _ => fds
+            .iter()
+            .find(|(_, fd_name)| fd_name == name)
+            .map(|(fd, _)| *fd)
+            .ok_or_else(|| anyhow!("None of the {} activated sockets is named {}", fds.len(), name))?,
+    };
+
+    if TAKEN.swap(true, Ordering::SeqCst) {
+        return Ok(None);
+    }
+
+    if !is_stream_socket(selected) {
+        anyhow::bail!("The activated socket {} is not a stream socket", selected);
+    }
+
+    let listener = UnixListener::from(unsafe { OwnedFd::from_raw_fd(selected) });
+    // fails for the sockets of other address families
+    let address = listener.local_addr()?;
+
+    debug!("Using the activated socket {:?}", address);
+
+    Ok(Some(listener))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listen_fds() {
+        assert_eq!(
+            listen_fds(Some("2"), Some("control:other"), Some("100"), 100),
+            vec![(3, "control".to_owned()), (4, "other".to_owned())]
+        );
+        assert_eq!(
+            listen_fds(Some("1"), None, Some("100"), 100),
+            vec![(3, "unknown".to_owned())]
+        );
+        assert!(listen_fds(Some("1"), None, Some("200"), 100).is_empty());
+        assert!(listen_fds(Some("1"), None, None, 100).is_empty());
+        assert!(listen_fds(None, None, Some("100"), 100).is_empty());
+    }
+
+    #[test]
+    fn test_stream_socket() {
+        use std::os::fd::AsRawFd;
+
+        let dir = tempfile::tempdir().unwrap();
+        let listener = UnixListener::bind(dir.path().join("control.sock")).unwrap();
+        let datagram = std::os::unix::net::UnixDatagram::unbound().unwrap();
+
+        assert!(is_stream_socket(listener.as_raw_fd()));
+        assert!(!is_stream_socket(datagram.as_raw_fd()));
+    }
+}
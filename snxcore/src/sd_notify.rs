@@ -0,0 +1,172 @@
+//! Service notifications for systemd units of `Type=notify`: readiness, reloads, status lines and watchdog pings.
+//! Everything is a no-op unless the process was started with `NOTIFY_SOCKET` set.
+
+use std::{env, future::Future, time::Duration};
+
+use tokio::time::{Interval, MissedTickBehavior};
+use tracing::debug;
+
+const NOTIFY_SOCKET: &str = "NOTIFY_SOCKET";
+const WATCHDOG_USEC: &str = "WATCHDOG_USEC";
+const WATCHDOG_PID: &str = "WATCHDOG_PID";
+
+/// Send the newline-separated assignments to the service manager.
+pub fn notify(state: &str) {
+    let Some(path) = env::var_os(NOTIFY_SOCKET) else {
+        return;
+    };
+
+    if let Err(e) = imp::send(&path, state) {
+        debug!("Unable to notify the service manager: {}", e);
+    }
+}
+
+pub fn ready() {
+    notify("READY=1");
+}
+
+/// Must be followed by `ready()` once the new configuration is in effect.
+pub fn reloading() {
+    notify(&format!("RELOADING=1\nMONOTONIC_USEC={}", imp::monotonic_usec()));
+}
+
+pub fn status(text: &str) {
+    // the status is a single line
+    notify(&format!("STATUS={}", text.replace('\n', " ")));
+}
+
+// Pings are sent at half of the timeout, the watchdog is meant for this process only if the pid matches.
+fn watchdog_interval(usec: Option<&str>, pid: Option<&str>, own_pid: u32) -> Option<Duration> {
+    if pid.is_some_and(|pid| pid.parse::<u32>().ok() != Some(own_pid)) {
+        return None;
+    }
+
+    match usec?.parse::<u64>() {
+        Ok(usec) if usec > 0 => Some(Duration::from_micros(usec / 2)),
+        _ => None,
+    }
+}
+
+/// Watchdog pings driven by the run loop, so that a hung loop stops them and the unit gets restarted.
+pub struct Watchdog {
+    interval: Option<Interval>,
+}
+
+impl Watchdog {
+    pub fn from_env() -> Self {
+        let interval = watchdog_interval(
+            env::var(WATCHDOG_USEC).ok().as_deref(),
+            env::var(WATCHDOG_PID).ok().as_deref(),
+            std::process::id(),
+        )
+        .filter(|_| env::var_os(NOTIFY_SOCKET).is_some())
+        .map(|period| {
+            debug!("Watchdog ping interval: {:?}", period);
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            interval
+        });
+
+        Self { interval }
+    }
+
+    /// Wait for the next ping and send it, never completes with the watchdog disabled.
+    pub async fn tick(&mut self) {
+        match self.interval {
+            Some(ref mut interval) => {
+                interval.tick().await;
+                notify("WATCHDOG=1");
+            }
+            None => futures::future::pending().await,
+        }
+    }
+
+    /// Keep pinging while waiting for the future outside of the run loop.
+    pub async fn keep_alive<F: Future>(&mut self, f: F) -> F::Output {
+        tokio::pin!(f);
+        loop {
+            tokio::select! {
+                output = &mut f => return output,
+                _ = self.tick() => {}
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::{
+        ffi::OsStr,
+        io,
+        os::{
+            linux::net::SocketAddrExt,
+            unix::{
+                ffi::OsStrExt,
+                net::{SocketAddr, UnixDatagram},
+            },
+        },
+    };
+
+    // a leading '@' is the abstract namespace
+    pub fn send(path: &OsStr, state: &str) -> io::Result<()> {
+        let addr = match path.as_bytes().strip_prefix(b"@") {
+            Some(name) => SocketAddr::from_abstract_name(name)?,
+            None => SocketAddr::from_pathname(path)?,
+        };
+        UnixDatagram::unbound()?.send_to_addr(state.as_bytes(), &addr)?;
+        Ok(())
+    }
+
+    pub fn monotonic_usec() -> u64 {
+        let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+        unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) };
+        ts.tv_sec as u64 * 1_000_000 + ts.tv_nsec as u64 / 1000
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use std::{ffi::OsStr, io};
+
+    pub fn send(_path: &OsStr, _state: &str) -> io::Result<()> {
+        Ok(())
+    }
+
+    pub fn monotonic_usec() -> u64 {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchdog_interval() {
+        assert_eq!(
+            watchdog_interval(Some("30000000"), None, 100),
+            Some(Duration::from_secs(15))
+        );
+        assert_eq!(
+            watchdog_interval(Some("30000000"), Some("100"), 100),
+            Some(Duration::from_secs(15))
+        );
+        assert_eq!(watchdog_interval(Some("30000000"), Some("200"), 100), None);
+        assert_eq!(watchdog_interval(Some("0"), None, 100), None);
+        assert_eq!(watchdog_interval(None, None, 100), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_send() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notify.sock");
+        let socket = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+
+        imp::send(path.as_os_str(), "READY=1").unwrap();
+
+        let mut buf = [0u8; 64];
+        let size = socket.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..size], b"READY=1");
+    }
+}
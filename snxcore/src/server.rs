@@ -1,39 +1,86 @@
 use std::{
+    collections::HashSet,
     fs::Permissions,
-    os::unix::fs::PermissionsExt,
+    os::unix::fs::{MetadataExt, PermissionsExt},
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
 };
 
 use anyhow::{Context, anyhow};
-use futures::{FutureExt, SinkExt, StreamExt};
+use futures::{SinkExt, StreamExt};
 use i18n::tr;
-use tokio::sync::{Mutex, RwLock, mpsc};
-use tracing::{debug, warn};
+use tokio::{
+    sync::{Mutex, RwLock, mpsc},
+    task::JoinHandle,
+};
+use tracing::{Instrument, debug, info, warn};
 
 use crate::{
+    failover::{self, GatewayFailover},
     model::{
-        ConnectionStatus, SessionState, TunnelServiceRequest, TunnelServiceResponse, VpnSession, params::TunnelParams,
+        ConnectionStatus, SessionState, TunnelServiceRequest, TunnelServiceResponse, TunnelStats, VpnSession,
+        params::TunnelParams,
+    },
+    platform::{
+        self, Authority, NetworkInterface, POLKIT_ACTION_CONNECT, POLKIT_ACTION_DISCONNECT, POLKIT_ACTION_STATUS,
+        PeerCredentials,
     },
+    sd_notify,
+    trusted::TrustedNetworkDetector,
     tunnel::{self, TunnelConnector, TunnelEvent},
 };
 
 pub const DEFAULT_LISTEN_PATH: &str = "/var/run/snx-rs.sock";
 
 const MAX_PACKET_SIZE: usize = 1_000_000;
+const STATS_SUMMARY_INTERVAL: Duration = Duration::from_secs(3600);
 
-#[derive(Default)]
 struct ConnectionState {
     connection_status: RwLock<ConnectionStatus>,
     session: Mutex<Option<Arc<VpnSession>>>,
     connector: Mutex<Option<Box<dyn TunnelConnector + Send>>>,
+    stats: RwLock<TunnelStats>,
+    params: Mutex<Option<Arc<TunnelParams>>>,
+    trusted_monitor: Mutex<Option<JoinHandle<()>>>,
+    suspended: AtomicBool,
+    owner: Mutex<Option<u32>>,
+}
+
+impl Default for ConnectionState {
+    fn default() -> Self {
+        Self {
+            connection_status: RwLock::default(),
+            session: Mutex::default(),
+            connector: Mutex::default(),
+            stats: RwLock::new(TunnelStats::new()),
+            params: Mutex::default(),
+            trusted_monitor: Mutex::default(),
+            suspended: AtomicBool::new(false),
+            owner: Mutex::default(),
+        }
+    }
 }
 
 impl ConnectionState {
     async fn reset(&self) {
         *self.session.lock().await = None;
         *self.connector.lock().await = None;
-        *self.connection_status.write().await = ConnectionStatus::Disconnected
+        *self.connection_status.write().await = if self.suspended.load(Ordering::SeqCst) {
+            ConnectionStatus::TrustedNetwork
+        } else {
+            ConnectionStatus::Disconnected
+        };
+    }
+
+    async fn stop_trusted_monitor(&self) {
+        if let Some(handle) = self.trusted_monitor.lock().await.take() {
+            handle.abort();
+        }
+        self.suspended.store(false, Ordering::SeqCst);
     }
 }
 
@@ -41,9 +88,96 @@ struct CancelState {
     sender: Option<mpsc::Sender<()>>,
 }
 
+/// Sender of a request. The service itself resumes the sessions after a trusted network is lost.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Peer {
+    Service,
+    User(PeerCredentials),
+}
+
+impl Peer {
+    fn is_privileged(self) -> bool {
+        match self {
+            Self::Service => true,
+            Self::User(cred) => cred.uid == 0,
+        }
+    }
+
+    // root may act on any session, the other users only on their own one
+    fn may_control(self, owner: Option<u32>) -> bool {
+        match self {
+            Self::User(cred) if !self.is_privileged() => owner.is_none_or(|owner| cred.uid == owner),
+            _ => true,
+        }
+    }
+}
+
+fn polkit_action(req: &TunnelServiceRequest) -> &'static str {
+    match req {
+        TunnelServiceRequest::GetStatus => POLKIT_ACTION_STATUS,
+        TunnelServiceRequest::Disconnect => POLKIT_ACTION_DISCONNECT,
+        TunnelServiceRequest::Connect(_)
+        | TunnelServiceRequest::ChallengeCode(..)
+        | TunnelServiceRequest::ResetStats => POLKIT_ACTION_CONNECT,
+    }
+}
+
+// The scripts run as root, so an unprivileged user may only name the ones which belong to root,
+// and the certificate files are read by the service, so they must be readable by the user anyway.
+fn unprivileged_problems(params: &TunnelParams, uid: u32) -> Vec<String> {
+    let readable_by =
+        |path: &Path| std::fs::metadata(path).is_ok_and(|meta| meta.uid() == uid || meta.mode() & 0o004 != 0);
+
+    let mut problems = Vec::new();
+
+    for (option, script) in [("script-up", &params.script_up), ("script-down", &params.script_down)] {
+        if script
+            .as_deref()
+            .is_some_and(|path| !std::fs::metadata(path).is_ok_and(|meta| meta.uid() == 0))
+        {
+            problems.push(tr!("error-option-not-permitted", option = option));
+        }
+    }
+
+    if params.cert_path.as_deref().is_some_and(|path| !readable_by(path)) {
+        problems.push(tr!("error-option-not-permitted", option = "cert-path"));
+    }
+
+    if params.ca_cert.iter().any(|path| !readable_by(path)) {
+        problems.push(tr!("error-option-not-permitted", option = "ca-cert"));
+    }
+
+    // a descriptor number means nothing in the service process
+    if params.tun_fd.is_some() {
+        problems.push(tr!("error-option-not-permitted", option = "tun-fd"));
+    }
+
+    problems
+}
+
+// A persisted IKE session of the gateway is reused if there is one.
+async fn authenticate(
+    params: Arc<TunnelParams>,
+) -> anyhow::Result<(Box<dyn TunnelConnector + Send + Sync>, Arc<VpnSession>)> {
+    let mut connector = tunnel::new_tunnel_connector(params.clone()).await?;
+
+    let session = if params.ike_persist {
+        debug!("Attempting to load IKE session");
+        match connector.restore_session().await {
+            Ok(session) => session,
+            Err(_) => connector.authenticate().await?,
+        }
+    } else {
+        connector.authenticate().await?
+    };
+
+    Ok((connector, session))
+}
+
 pub struct CommandServer {
     listen_path: PathBuf,
     connection_state: Arc<ConnectionState>,
+    authority: Arc<dyn Authority + Send + Sync>,
 }
 
 impl Default for CommandServer {
@@ -57,6 +191,7 @@ impl CommandServer {
         Self {
             listen_path: listen_path.as_ref().to_owned(),
             connection_state: Arc::new(ConnectionState::default()),
+            authority: Arc::new(platform::new_authority()),
         }
     }
 
@@ -67,12 +202,22 @@ impl CommandServer {
         let socket = tokio::net::UnixListener::bind(&self.listen_path)?;
         std::fs::set_permissions(&self.listen_path, Permissions::from_mode(0o777))?;
 
+        sd_notify::ready();
+        let mut watchdog = sd_notify::Watchdog::from_env();
+
         let (event_sender, mut event_receiver) = mpsc::channel::<TunnelEvent>(16);
 
         let cancel_state = Arc::new(Mutex::new(CancelState { sender: None }));
 
+        let mut summary_interval = tokio::time::interval(STATS_SUMMARY_INTERVAL);
+        summary_interval.tick().await;
+
         loop {
             tokio::select! {
+                _ = summary_interval.tick() => {
+                    info!("Tunnel statistics: {}", self.connection_state.stats.read().await);
+                }
+                _ = watchdog.tick() => {}
                 event = event_receiver.recv() => {
                     if let Some(event) = event {
                         let result = if let Some(connector) = self.connection_state.connector.lock().await.as_mut() {
@@ -81,37 +226,92 @@ impl CommandServer {
                             Ok(())
                         };
 
-                        if result.is_err() {
+                        if let Err(ref e) = result {
+                            self.connection_state.stats.write().await.on_error(e.to_string());
                             cancel_state.lock().await.sender = None;
                             self.connection_state.reset().await;
                         }
 
                         match event {
                             TunnelEvent::Connected(info) => {
+                                self.connection_state.stats.write().await.on_connected();
                                 *self.connection_state.connection_status.write().await = ConnectionStatus::connected(info);
                             }
                             TunnelEvent::Disconnected => {
+                                self.connection_state.stats.write().await.on_disconnected();
                                 cancel_state.lock().await.sender = None;
                                 self.connection_state.reset().await;
                             }
                             TunnelEvent::Rekeyed(address) => {
+                                self.connection_state.stats.write().await.on_rekey();
                                 let mut guard = self.connection_state.connection_status.write().await;
                                 if let ConnectionStatus::Connected(ref mut info) = *guard {
                                    info.ip_address = address;
                                 }
                             }
+                            TunnelEvent::DnsChecked(results) => {
+                                let mut guard = self.connection_state.connection_status.write().await;
+                                if let ConnectionStatus::Connected(ref mut info) = *guard {
+                                   info.dns_check = results;
+                                }
+                            }
+                            TunnelEvent::TrustedNetworkDetected => {
+                                if !self.connection_state.suspended.swap(true, Ordering::SeqCst) {
+                                    info!("Trusted network detected, suspending VPN connection");
+                                    if let Some(sender) = cancel_state.lock().await.sender.take() {
+                                        let _ = sender.send(()).await;
+                                    }
+                                    if let Some(connector) = self.connection_state.connector.lock().await.as_mut() {
+                                        let _ = connector.terminate_tunnel(true).await;
+                                    }
+                                    if let Some(params) = self.connection_state.params.lock().await.as_ref() {
+                                        tunnel::remove_kill_switch(params).await;
+                                    }
+                                    tunnel::remove_persistent_devices().await;
+                                    self.connection_state.reset().await;
+                                }
+                            }
+                            TunnelEvent::TrustedNetworkLost => {
+                                if self.connection_state.suspended.swap(false, Ordering::SeqCst) {
+                                    info!("Trusted network lost, resuming VPN connection");
+                                    self.connection_state.reset().await;
+
+                                    if let Some(params) = self.connection_state.params.lock().await.clone() {
+                                        let mut handler = ServerHandler::new(
+                                            self.connection_state.clone(),
+                                            cancel_state.clone(),
+                                            event_sender.clone(),
+                                            Peer::Service,
+                                            self.authority.clone(),
+                                        )
+                                        .await;
+                                        tokio::spawn(async move { handler.resume(params).await });
+                                    }
+                                }
+                            }
                             _ => {}
                         }
                     }
                 }
                 result = socket.accept() => {
                     let (stream, _) = result?;
+                    let peer = match stream.peer_cred() {
+                        Ok(cred) => Peer::User(PeerCredentials {
+                            uid: cred.uid(),
+                            pid: cred.pid(),
+                        }),
+                        Err(e) => {
+                            warn!("Unable to get the peer credentials, closing the connection: {}", e);
+                            continue;
+                        }
+                    };
                     let sender = event_sender.clone();
                     let state = self.connection_state.clone();
 
                     let cancel_state = cancel_state.clone();
+                    let authority = self.authority.clone();
                     tokio::spawn(async move {
-                        let mut handler = ServerHandler::new(state, cancel_state, sender).await;
+                        let mut handler = ServerHandler::new(state, cancel_state, sender, peer, authority).await;
                         handler.handle(stream).await
                     });
                 }
@@ -126,6 +326,10 @@ struct ServerHandler {
     event_sender: mpsc::Sender<TunnelEvent>,
     cancel_sender: mpsc::Sender<()>,
     cancel_receiver: mpsc::Receiver<()>,
+    peer: Peer,
+    authority: Arc<dyn Authority + Send + Sync>,
+    // the positive answers are kept for the connection, the status is polled
+    authorized: HashSet<&'static str>,
 }
 
 impl ServerHandler {
@@ -133,6 +337,8 @@ impl ServerHandler {
         state: Arc<ConnectionState>,
         cancel_state: Arc<Mutex<CancelState>>,
         event_sender: mpsc::Sender<TunnelEvent>,
+        peer: Peer,
+        authority: Arc<dyn Authority + Send + Sync>,
     ) -> Self {
         let (cancel_sender, cancel_receiver) = mpsc::channel(16);
         Self {
@@ -141,6 +347,42 @@ impl ServerHandler {
             event_sender,
             cancel_sender,
             cancel_receiver,
+            peer,
+            authority,
+            authorized: HashSet::new(),
+        }
+    }
+
+    async fn authorize(&mut self, action_id: &'static str) -> anyhow::Result<()> {
+        let cred = match self.peer {
+            Peer::User(cred) if !self.peer.is_privileged() => cred,
+            _ => return Ok(()),
+        };
+
+        if self.authorized.contains(action_id) {
+            return Ok(());
+        }
+
+        if self.authority.check_authorization(cred, action_id).await? {
+            self.authorized.insert(action_id);
+            Ok(())
+        } else {
+            anyhow::bail!(tr!("error-dbus-not-authorized", action = action_id))
+        }
+    }
+
+    async fn check_owner(&self) -> anyhow::Result<()> {
+        // a disconnected session belongs to nobody
+        if !self.is_connected().await {
+            return Ok(());
+        }
+
+        let owner = *self.state.owner.lock().await;
+        match owner {
+            Some(owner) if !self.peer.may_control(Some(owner)) => {
+                anyhow::bail!(tr!("error-session-not-owned", uid = owner))
+            }
+            _ => Ok(()),
         }
     }
 
@@ -167,10 +409,26 @@ impl ServerHandler {
             }
         };
 
+        let authorized = self.authorize(polkit_action(&req)).await;
+        if let Err(e) = authorized {
+            warn!("Request from {:?} not authorized: {}", self.peer, e);
+            return TunnelServiceResponse::Error(e.to_string());
+        }
+
+        // the status is public, everything else needs the ownership of the session
+        if !matches!(req, TunnelServiceRequest::GetStatus) {
+            let allowed = self.check_owner().await;
+            if let Err(e) = allowed {
+                warn!("Request from {:?} rejected: {}", self.peer, e);
+                return TunnelServiceResponse::Error(e.to_string());
+            }
+        }
+
         match req {
             TunnelServiceRequest::Connect(params) => match self.connect(Arc::new(params)).await {
                 Ok(response) => response,
                 Err(e) => {
+                    self.state.stats.write().await.on_error(e.to_string());
                     self.state.reset().await;
                     TunnelServiceResponse::Error(e.to_string())
                 }
@@ -180,14 +438,20 @@ impl ServerHandler {
                 Err(e) => TunnelServiceResponse::Error(e.to_string()),
             },
             TunnelServiceRequest::GetStatus => TunnelServiceResponse::ConnectionStatus(self.get_status().await),
-            TunnelServiceRequest::ChallengeCode(code, _) => match self.challenge_code(&code).await {
-                Ok(response) => response,
-                Err(e) => {
-                    warn!("Challenge code error: {:#}", e);
-                    self.state.reset().await;
-                    TunnelServiceResponse::Error(e.to_string())
+            TunnelServiceRequest::ResetStats => {
+                self.state.stats.write().await.reset();
+                TunnelServiceResponse::Ok
+            }
+            TunnelServiceRequest::ChallengeCode(code, params) => {
+                match self.challenge_code(&code, Arc::new(params)).await {
+                    Ok(response) => response,
+                    Err(e) => {
+                        warn!("Challenge code error: {:#}", e);
+                        self.state.reset().await;
+                        TunnelServiceResponse::Error(e.to_string())
+                    }
                 }
-            },
+            }
         }
     }
 
@@ -195,7 +459,11 @@ impl ServerHandler {
         *self.state.connection_status.read().await != ConnectionStatus::Disconnected
     }
 
-    async fn connect_for_session(&mut self, session: Arc<VpnSession>) -> anyhow::Result<TunnelServiceResponse> {
+    async fn connect_for_session(
+        &mut self,
+        session: Arc<VpnSession>,
+        params: Arc<TunnelParams>,
+    ) -> anyhow::Result<TunnelServiceResponse> {
         *self.state.session.lock().await = Some(session.clone());
         if let SessionState::PendingChallenge(ref challenge) = session.state {
             debug!("Pending multi-factor, awaiting for it");
@@ -211,11 +479,17 @@ impl ServerHandler {
             let tunnel = connector.create_tunnel(session, command_sender).await?;
 
             let sender = self.event_sender.clone();
-            tokio::spawn(async move {
-                if let Err(e) = tunnel.run(command_receiver, sender).await {
-                    warn!("Tunnel error: {}", e);
+            let state = self.state.clone();
+            let span = tunnel::session_span(&params, &session);
+            tokio::spawn(
+                async move {
+                    if let Err(e) = tunnel.run(command_receiver, sender).await {
+                        warn!("Tunnel error: {}", e);
+                        state.stats.write().await.on_error(e.to_string());
+                    }
                 }
-            });
+                .instrument(span),
+            );
 
             Ok(TunnelServiceResponse::Ok)
         } else {
@@ -229,48 +503,118 @@ impl ServerHandler {
                 "Another connection is already in progress!".to_owned(),
             ))
         } else {
-            self.state.reset().await;
-            *self.state.connection_status.write().await = ConnectionStatus::Connecting;
-            self.cancel_state.lock().await.sender = Some(self.cancel_sender.clone());
-
-            let mut connector = tunnel::new_tunnel_connector(params.clone()).await?;
-            let fut = if params.ike_persist {
-                debug!("Attempting to load IKE session");
-                match connector.restore_session().await {
-                    Ok(session) => futures::future::ready(Ok(session)).boxed(),
-                    Err(_) => connector.authenticate(),
+            if let Peer::User(PeerCredentials { uid, .. }) = self.peer {
+                if !self.peer.is_privileged() {
+                    let problems = unprivileged_problems(&params, uid);
+                    if !problems.is_empty() {
+                        return Ok(TunnelServiceResponse::Error(problems.join("\n")));
+                    }
                 }
-            } else {
-                connector.authenticate()
-            };
+                *self.state.owner.lock().await = Some(uid);
+            }
+
+            self.state.stop_trusted_monitor().await;
+            *self.state.params.lock().await = Some(params.clone());
+
+            let detector = TrustedNetworkDetector::new(params.clone());
+            if detector.is_enabled() {
+                let trusted = detector.is_trusted().await;
+                self.start_trusted_monitor(detector, trusted).await;
 
-            let session = tokio::select! {
+                if trusted {
+                    info!("Trusted network detected, not connecting");
+                    self.state.suspended.store(true, Ordering::SeqCst);
+                    self.state.reset().await;
+                    let _ = self.event_sender.send(TunnelEvent::TrustedNetworkDetected).await;
+                    return Ok(TunnelServiceResponse::Ok);
+                }
+            }
+
+            self.start_connection(params).await
+        }
+    }
+
+    async fn start_connection(&mut self, params: Arc<TunnelParams>) -> anyhow::Result<TunnelServiceResponse> {
+        self.state.reset().await;
+        *self.state.connection_status.write().await = ConnectionStatus::Connecting;
+        self.cancel_state.lock().await.sender = Some(self.cancel_sender.clone());
+
+        // the gateways of the list are tried in order until one of them answers
+        let mut failover = GatewayFailover::new(params);
+        let (connector, session, params) = loop {
+            let params = failover.params();
+            let result = tokio::select! {
                 _ = self.cancel_receiver.recv() => anyhow::bail!(tr!("error-connection-cancelled")),
-                res = fut => res?
+                res = authenticate(params.clone()).instrument(tunnel::auth_span(&params)) => res
             };
 
-            *self.state.connector.lock().await = Some(connector);
+            match result {
+                Ok((connector, session)) => break (connector, session, params),
+                Err(e) => {
+                    let failed = failover.gateway().to_owned();
+                    if !failover::is_failover_error(&e) || !failover.next_gateway() {
+                        return Err(e);
+                    }
+                    warn!(
+                        "Connection to gateway {} failed, trying {}: {}",
+                        failed,
+                        failover.gateway(),
+                        e
+                    );
+                }
+            }
+        };
+
+        *self.state.connector.lock().await = Some(connector);
 
-            Ok(self.connect_for_session(session).await?)
+        self.connect_for_session(session, params).await
+    }
+
+    async fn resume(&mut self, params: Arc<TunnelParams>) {
+        if let Err(e) = self.start_connection(params).await {
+            warn!("Unable to resume connection: {:#}", e);
+            self.state.stats.write().await.on_error(e.to_string());
+            self.state.reset().await;
         }
     }
 
-    async fn challenge_code(&mut self, code: &str) -> anyhow::Result<TunnelServiceResponse> {
+    async fn start_trusted_monitor(&self, detector: TrustedNetworkDetector, trusted: bool) {
+        let sender = self.event_sender.clone();
+        let handle = tokio::spawn(async move {
+            let mut trusted = trusted;
+            loop {
+                trusted = detector.wait_for_change(trusted).await;
+                let event = if trusted {
+                    TunnelEvent::TrustedNetworkDetected
+                } else {
+                    TunnelEvent::TrustedNetworkLost
+                };
+                if sender.send(event).await.is_err() {
+                    break;
+                }
+            }
+        });
+        *self.state.trusted_monitor.lock().await = Some(handle);
+    }
+
+    async fn challenge_code(&mut self, code: &str, params: Arc<TunnelParams>) -> anyhow::Result<TunnelServiceResponse> {
         let session = self.state.session.lock().await.clone().context("No session")?;
 
         let new_session = if let Some(connector) = self.state.connector.lock().await.as_mut() {
             tokio::select! {
                 _ = self.cancel_receiver.recv() => anyhow::bail!(tr!("error-connection-cancelled")),
-                res = connector.challenge_code(session, code) => res?
+                res = connector.challenge_code(session, code).instrument(tunnel::auth_span(&params)) => res?
             }
         } else {
             anyhow::bail!(tr!("error-no-connector-for-challenge-code"))
         };
 
-        self.connect_for_session(new_session).await
+        self.connect_for_session(new_session, params).await
     }
 
     async fn disconnect(&mut self) -> anyhow::Result<()> {
+        self.state.stop_trusted_monitor().await;
+
         if let Some(sender) = self.cancel_state.lock().await.sender.take() {
             debug!("Disconnecting current session");
             let _ = sender.send(()).await;
@@ -280,12 +624,134 @@ impl ServerHandler {
             connector.delete_session().await;
             let _ = connector.terminate_tunnel(true).await;
         }
+        // the credentials are kept in memory only while the session exists
+        if let Some(params) = self.state.params.lock().await.take() {
+            tunnel::remove_kill_switch(&params).await;
+        }
+        tunnel::remove_persistent_devices().await;
+        *self.state.owner.lock().await = None;
         self.state.reset().await;
 
         Ok(())
     }
 
     async fn get_status(&self) -> ConnectionStatus {
-        self.state.connection_status.read().await.clone()
+        let mut status = self.state.connection_status.read().await.clone();
+        if let ConnectionStatus::Connected(ref mut info) = status {
+            info.stats = self.state.stats.read().await.clone();
+            // a device moved into a network namespace is not visible here
+            info.traffic = platform::new_network_interface()
+                .get_traffic_counters(&info.interface_name)
+                .await;
+        }
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(uid: u32) -> Peer {
+        Peer::User(PeerCredentials { uid, pid: Some(4242) })
+    }
+
+    #[test]
+    fn test_peer_may_control() {
+        assert!(user(1000).may_control(None));
+        assert!(user(1000).may_control(Some(1000)));
+        assert!(!user(1001).may_control(Some(1000)));
+        assert!(user(0).may_control(Some(1000)));
+        assert!(Peer::Service.may_control(Some(1000)));
+    }
+
+    // answers from the list, records the questions
+    #[derive(Default)]
+    struct MockAuthority {
+        allowed: Vec<&'static str>,
+        asked: std::sync::Mutex<Vec<(u32, String)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Authority for MockAuthority {
+        async fn check_authorization(&self, peer: PeerCredentials, action_id: &str) -> anyhow::Result<bool> {
+            self.asked.lock().unwrap().push((peer.uid, action_id.to_owned()));
+            Ok(self.allowed.contains(&action_id))
+        }
+    }
+
+    async fn new_handler(peer: Peer, authority: Arc<MockAuthority>) -> ServerHandler {
+        let (event_sender, _) = mpsc::channel(1);
+        ServerHandler::new(
+            Arc::new(ConnectionState::default()),
+            Arc::new(Mutex::new(CancelState { sender: None })),
+            event_sender,
+            peer,
+            authority,
+        )
+        .await
+    }
+
+    fn request(req: &TunnelServiceRequest) -> Vec<u8> {
+        serde_json::to_vec(req).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_polkit_authorization() {
+        let authority = Arc::new(MockAuthority {
+            allowed: vec![POLKIT_ACTION_STATUS],
+            ..Default::default()
+        });
+        let mut handler = new_handler(user(1000), authority.clone()).await;
+
+        let status = handler.handle_packet(&request(&TunnelServiceRequest::GetStatus)).await;
+        assert!(matches!(status, TunnelServiceResponse::ConnectionStatus(_)));
+
+        // the answer is kept for the connection
+        handler.handle_packet(&request(&TunnelServiceRequest::GetStatus)).await;
+
+        let disconnect = handler.handle_packet(&request(&TunnelServiceRequest::Disconnect)).await;
+        assert!(matches!(disconnect, TunnelServiceResponse::Error(ref e) if e.contains(POLKIT_ACTION_DISCONNECT)));
+
+        assert_eq!(
+            *authority.asked.lock().unwrap(),
+            vec![
+                (1000, POLKIT_ACTION_STATUS.to_owned()),
+                (1000, POLKIT_ACTION_DISCONNECT.to_owned())
+            ]
+        );
+
+        // root is not asked about
+        let mut handler = new_handler(user(0), authority.clone()).await;
+        let reset = handler.handle_packet(&request(&TunnelServiceRequest::ResetStats)).await;
+        assert!(matches!(reset, TunnelServiceResponse::Ok));
+        assert_eq!(authority.asked.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_unprivileged_problems() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("up.sh");
+        std::fs::write(&script, "#!/bin/sh\n").unwrap();
+        let owner = std::fs::metadata(&script).unwrap().uid();
+
+        let params = TunnelParams {
+            script_up: Some(script.clone()),
+            cert_path: Some(script),
+            tun_fd: Some(3),
+            ..Default::default()
+        };
+
+        let problems = unprivileged_problems(&params, owner);
+        let expected_script = if owner == 0 { 0 } else { 1 };
+        assert_eq!(problems.len(), expected_script + 1);
+        assert!(problems.last().unwrap().contains("tun-fd"));
+
+        // owned by somebody else and not world-readable
+        let mut params = params;
+        params.tun_fd = None;
+        params.script_up = None;
+        std::fs::set_permissions(params.cert_path.as_ref().unwrap(), Permissions::from_mode(0o600)).unwrap();
+        assert!(unprivileged_problems(&params, owner + 1)[0].contains("cert-path"));
     }
 }
@@ -1,7 +1,20 @@
-use std::{collections::VecDeque, sync::Arc};
+use std::{collections::VecDeque, net::Ipv4Addr, sync::Arc, time::Duration};
 
+use anyhow::anyhow;
 use cached::proc_macro::cached;
-use tracing::trace;
+use i18n::tr;
+use itertools::Itertools;
+use openssl::{
+    hash::MessageDigest,
+    x509::{X509, X509NameRef},
+};
+use serde::Serialize;
+use tokio::net::TcpStream;
+use tokio_native_tls::{
+    TlsStream,
+    native_tls::{Certificate, TlsConnector},
+};
+use tracing::{trace, warn};
 
 use crate::{
     ccc::CccHttpClient,
@@ -10,18 +23,36 @@ use crate::{
         params::TunnelParams,
         proto::{LoginDisplayLabelSelect, LoginOption, ServerInfoResponse},
     },
+    proxy,
     sexpr::SExpression,
+    util,
 };
 
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
 pub async fn get_uncached(params: &TunnelParams) -> anyhow::Result<ServerInfoResponse> {
     let client = CccHttpClient::new(Arc::new(params.clone()), None);
 
-    let info = client.get_server_info().await?;
+    let reply = client.get_server_info().await?;
+
+    parse_server_info(&reply, &params.server_name)
+}
 
-    info.get("CCCserverResponse:ResponseData")
-        .cloned()
-        .unwrap_or(SExpression::Null)
-        .try_into()
+// Gateways which refuse the unauthenticated queries answer with an empty data section.
+fn parse_server_info(reply: &SExpression, server_name: &str) -> anyhow::Result<ServerInfoResponse> {
+    match reply.get("CCCserverResponse:ResponseData") {
+        Some(data @ SExpression::Object(_, fields)) if !fields.is_empty() => data.clone().try_into(),
+        _ => {
+            let code = reply
+                .get_value::<u32>("CCCserverResponse:ResponseHeader:return_code")
+                .unwrap_or_default();
+            Err(anyhow!(tr!(
+                "error-gateway-info-refused",
+                server = server_name,
+                error_code = code
+            )))
+        }
+    }
 }
 
 #[cached(
@@ -69,3 +100,337 @@ pub async fn get_login_option(params: &TunnelParams) -> anyhow::Result<Option<Lo
 
     Ok(result)
 }
+
+/// Login option as offered to the unauthenticated client.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GatewayLoginOption {
+    pub id: String,
+    pub display_name: String,
+    pub factors: Vec<String>,
+}
+
+/// What the gateway tells before the login, shown by the `info` command.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GatewayInfo {
+    pub server_name: String,
+    pub server_ip: Ipv4Addr,
+    pub protocol_version: u32,
+    pub client_enabled: bool,
+    pub available_client_version: u32,
+    pub supported_protocols: Vec<String>,
+    pub preferred_protocol: String,
+    pub ipsec_transport: String,
+    pub tcpt_port: u16,
+    pub natt_port: u16,
+    pub saml_enabled: bool,
+    pub login_options: Vec<GatewayLoginOption>,
+    pub internal_ca_fingerprints: Vec<String>,
+    pub certificate: Option<CertificateSummary>,
+}
+
+impl GatewayInfo {
+    fn new(
+        server_name: &str,
+        info: ServerInfoResponse,
+        certificate: Option<CertificateSummary>,
+    ) -> anyhow::Result<Self> {
+        let internal_ca_fingerprints = info
+            .connectivity_info
+            .internal_ca_fingerprint
+            .values()
+            .map(|fingerprint| Ok(String::from_utf8_lossy(&util::snx_decrypt(fingerprint.as_bytes())?).into_owned()))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let mut options = info
+            .login_options_data
+            .map(|data| data.login_options_list)
+            .unwrap_or_default()
+            .into_values()
+            .filter(|option| option.show_realm != 0)
+            .collect::<Vec<_>>();
+
+        if options.is_empty() {
+            options.push(LoginOption::unspecified());
+        }
+
+        let login_options = options
+            .into_iter()
+            .map(|option| GatewayLoginOption {
+                id: option.id,
+                display_name: option.display_name,
+                factors: option.factors.into_values().map(|factor| factor.factor_type).collect(),
+            })
+            .collect::<Vec<_>>();
+
+        // the identity provider factor is the SAML login
+        let saml_enabled = login_options
+            .iter()
+            .flat_map(|option| &option.factors)
+            .any(|factor| factor == "identity_provider");
+
+        Ok(Self {
+            server_name: server_name.to_owned(),
+            server_ip: info.connectivity_info.server_ip,
+            protocol_version: info.protocol_version.protocol_version,
+            client_enabled: info.connectivity_info.client_enabled,
+            available_client_version: info.upgrade_configuration.available_client_version,
+            supported_protocols: info.connectivity_info.supported_data_tunnel_protocols,
+            preferred_protocol: info.connectivity_info.connectivity_type,
+            ipsec_transport: info.connectivity_info.ipsec_transport,
+            tcpt_port: info.connectivity_info.tcpt_port,
+            natt_port: info.connectivity_info.natt_port,
+            saml_enabled,
+            login_options,
+            internal_ca_fingerprints,
+            certificate,
+        })
+    }
+
+    pub fn to_values(&self) -> Vec<(String, String)> {
+        let mut values = vec![
+            ("login-options-server-address".to_owned(), self.server_name.clone()),
+            ("login-options-server-ip".to_owned(), self.server_ip.to_string()),
+            (
+                "login-options-protocol-version".to_owned(),
+                self.protocol_version.to_string(),
+            ),
+            (
+                "login-options-client-enabled".to_owned(),
+                self.client_enabled.to_string(),
+            ),
+            (
+                "login-options-supported-protocols".to_owned(),
+                self.supported_protocols.join(", "),
+            ),
+            (
+                "login-options-preferred-protocol".to_owned(),
+                self.preferred_protocol.clone(),
+            ),
+            ("login-options-ipsec-transport".to_owned(), self.ipsec_transport.clone()),
+            ("login-options-tcpt-port".to_owned(), self.tcpt_port.to_string()),
+            ("login-options-natt-port".to_owned(), self.natt_port.to_string()),
+            ("login-options-saml-enabled".to_owned(), self.saml_enabled.to_string()),
+        ];
+
+        for fingerprint in &self.internal_ca_fingerprints {
+            values.push(("login-options-internal-ca-fingerprint".to_owned(), fingerprint.clone()));
+        }
+
+        if let Some(ref cert) = self.certificate {
+            values.push(("login-options-certificate-subject".to_owned(), cert.subject.clone()));
+            values.push(("login-options-certificate-issuer".to_owned(), cert.issuer.clone()));
+            values.push(("login-options-certificate-expires".to_owned(), cert.not_after.clone()));
+            values.push((
+                "login-options-certificate-fingerprint".to_owned(),
+                cert.fingerprint.clone(),
+            ));
+            if let Some(ref error) = cert.verify_error {
+                values.push(("login-options-certificate-error".to_owned(), error.clone()));
+            }
+        }
+
+        for option in &self.login_options {
+            values.push((
+                format!("[{}]", option.display_name),
+                format!("{} ({})", option.id, option.factors.join(", ")),
+            ));
+        }
+
+        values
+    }
+
+    pub fn print(&self) -> String {
+        let values = self
+            .to_values()
+            .into_iter()
+            .map(|(key, value)| {
+                // the login options are labeled with their display names
+                if key.starts_with('[') {
+                    (key, value)
+                } else {
+                    (i18n::translate(&key), value)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let label_width = values
+            .iter()
+            .map(|(label, _)| label.chars().count())
+            .max()
+            .unwrap_or_default();
+
+        values
+            .iter()
+            .map(|(label, value)| format!("{label:>label_width$}: {value}"))
+            .join("\n")
+    }
+}
+
+/// Query the gateway capabilities without logging in. The certificate is only informational,
+/// the query goes on without it when it cannot be fetched.
+pub async fn get_gateway_info(params: &TunnelParams) -> anyhow::Result<GatewayInfo> {
+    let info = get_uncached(params).await?;
+
+    let certificate = match get_certificate(params).await {
+        Ok(certificate) => Some(certificate),
+        Err(e) => {
+            warn!("Unable to fetch the certificate of {}: {}", params.server_name, e);
+            None
+        }
+    };
+
+    GatewayInfo::new(&params.server_name, info, certificate)
+}
+
+/// Certificate presented by the gateway, shown by the setup wizard before anything is sent to it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CertificateSummary {
+    pub subject: String,
+    pub issuer: String,
+    pub not_after: String,
+    pub fingerprint: String,
+    /// Why the certificate is rejected by the system trust store and the configured CA certificates.
+    pub verify_error: Option<String>,
+}
+
+fn format_name(name: &X509NameRef) -> String {
+    name.entries()
+        .map(|entry| {
+            let value = entry
+                .data()
+                .as_utf8()
+                .map(|s| s.to_string())
+                .unwrap_or_else(|_| String::from_utf8_lossy(entry.data().as_slice()).into_owned());
+            format!("{}={}", entry.object().nid().short_name().unwrap_or("?"), value)
+        })
+        .join(", ")
+}
+
+fn summarize_certificate(cert: &X509, verify_error: Option<String>) -> anyhow::Result<CertificateSummary> {
+    let fingerprint = cert
+        .digest(MessageDigest::sha256())?
+        .iter()
+        .map(|b| format!("{b:02X}"))
+        .join(":");
+
+    Ok(CertificateSummary {
+        subject: format_name(cert.subject_name()),
+        issuer: format_name(cert.issuer_name()),
+        not_after: cert.not_after().to_string(),
+        fingerprint,
+        verify_error,
+    })
+}
+
+async fn tls_connect(params: &TunnelParams, verify: bool) -> anyhow::Result<TlsStream<TcpStream>> {
+    let mut builder = TlsConnector::builder();
+
+    for ca_cert in &params.ca_cert {
+        let data = tokio::fs::read(ca_cert).await?;
+        let cert = Certificate::from_pem(&data).or_else(|_| Certificate::from_der(&data))?;
+        builder.add_root_certificate(cert);
+    }
+
+    builder.danger_accept_invalid_certs(!verify);
+
+    let tls: tokio_native_tls::TlsConnector = builder.build()?.into();
+
+    let connect = async {
+        let tcp = proxy::connect(params).await?;
+        Ok::<_, anyhow::Error>(tls.connect(params.server_host(), tcp).await?)
+    };
+
+    tokio::time::timeout(PROBE_TIMEOUT, connect).await?
+}
+
+/// Fetch the gateway certificate without checking it, then check it separately, so that
+/// an untrusted certificate can still be shown to the user.
+pub async fn get_certificate(params: &TunnelParams) -> anyhow::Result<CertificateSummary> {
+    let stream = tls_connect(params, false).await?;
+
+    let cert = stream
+        .get_ref()
+        .peer_certificate()?
+        .ok_or_else(|| anyhow!("No certificate presented by {}", params.server_name))?;
+    let cert = X509::from_der(&cert.to_der()?)?;
+
+    let verify_error = tls_connect(params, true).await.err().map(|e| e.to_string());
+
+    summarize_certificate(&cert, verify_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{
+        asn1::Asn1Time,
+        pkey::PKey,
+        rsa::Rsa,
+        x509::{X509Builder, X509NameBuilder},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_gateway_info() {
+        let data = std::fs::read_to_string("tests/server_info.txt").unwrap();
+        let reply = data.parse::<SExpression>().unwrap();
+
+        let info = parse_server_info(&reply, "vpn.example.com").unwrap();
+        let info = GatewayInfo::new("vpn.example.com", info, None).unwrap();
+
+        assert_eq!(info.server_ip, Ipv4Addr::new(203, 0, 113, 10));
+        assert_eq!(info.protocol_version, 100);
+        assert!(info.client_enabled);
+        assert_eq!(info.supported_protocols, vec!["IPSec", "SSL", "L2TP"]);
+        assert_eq!(info.natt_port, 4500);
+        assert_eq!(info.internal_ca_fingerprints.len(), 1);
+        assert!(info.saml_enabled);
+
+        // the hidden options are not offered
+        let ids = info.login_options.iter().map(|o| o.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["vpn_Azure_SAML", "vpn_Standard"]);
+        assert_eq!(info.login_options[1].factors, vec!["password"]);
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["login_options"][0]["display_name"], "Azure SSO");
+    }
+
+    #[test]
+    fn test_gateway_info_refused() {
+        let reply = "(CCCserverResponse :ResponseHeader (:id (1) :type (ClientHello) :session_id () :return_code (500)) :ResponseData ())"
+            .parse::<SExpression>()
+            .unwrap();
+
+        let error = parse_server_info(&reply, "vpn.example.com").unwrap_err();
+        assert!(error.to_string().contains("vpn.example.com"));
+    }
+
+    #[test]
+    fn test_summarize_certificate() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", "vpn.example.com").unwrap();
+        name.append_entry_by_text("O", "Example").unwrap();
+        let name = name.build();
+
+        let mut builder = X509Builder::new().unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder.set_not_before(&Asn1Time::from_unix(0).unwrap()).unwrap();
+        builder
+            .set_not_after(&Asn1Time::from_unix(1_893_456_000).unwrap())
+            .unwrap();
+        builder.sign(&key, MessageDigest::sha256()).unwrap();
+        let cert = builder.build();
+
+        let summary = summarize_certificate(&cert, Some("self-signed certificate".to_owned())).unwrap();
+
+        assert_eq!(summary.subject, "CN=vpn.example.com, O=Example");
+        assert_eq!(summary.issuer, summary.subject);
+        assert_eq!(summary.not_after, "Jan  1 00:00:00 2030 GMT");
+        assert_eq!(summary.fingerprint.len(), 32 * 3 - 1);
+        assert_eq!(summary.verify_error.as_deref(), Some("self-signed certificate"));
+    }
+}
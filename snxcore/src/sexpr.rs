@@ -293,7 +293,14 @@ fn parse_value(mut pairs: RulePairs) -> anyhow::Result<SExpression> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::proto::{CccClientRequest, CccClientRequestData, RequestData, RequestHeader, SignoutRequest};
+    use crate::model::{
+        params::TunnelParams,
+        proto::{
+            CccClientRequest, CccClientRequestData, ClientHello, ClientHelloData, DisconnectRequest,
+            DisconnectRequestData, ErrorReply, ErrorReplyData, HelloResponse, LEGACY_PROTOCOL_MINOR_VERSION,
+            PROTOCOL_MINOR_VERSION, RequestData, RequestHeader, SignoutRequest,
+        },
+    };
 
     #[test]
     fn test_parse_client_hello() {
@@ -304,6 +311,30 @@ mod tests {
         println!("{expr}");
     }
 
+    #[test]
+    fn test_encode_client_hello() {
+        let params = TunnelParams {
+            hello_client_type: "SNX_4.2".to_owned(),
+            hello_client_version: "800010003".to_owned(),
+            ..Default::default()
+        };
+
+        for keep_address in [false, true] {
+            let data = ClientHelloData::new(&params, "10.0.0.1", keep_address, "cookie", PROTOCOL_MINOR_VERSION);
+            let expr = SExpression::from(ClientHello { data });
+
+            assert_eq!(
+                expr.get("client_hello:client_version"),
+                Some(&SExpression::Value("800010003".to_string()))
+            );
+            assert_eq!(
+                expr.get("client_hello:optional:client_type"),
+                Some(&SExpression::Value("SNX_4.2".to_string()))
+            );
+            assert!(expr.to_string().contains(":client_type (\"SNX_4.2\")"));
+        }
+    }
+
     #[test]
     fn test_parse_hello_reply() {
         let data = std::fs::read_to_string("tests/hello_reply.txt").unwrap();
@@ -340,6 +371,170 @@ mod tests {
         assert_eq!(from_json, expr);
     }
 
+    #[test]
+    fn test_hello_reply_dialects() {
+        let data = std::fs::read_to_string("tests/hello_reply.txt").unwrap();
+        let expr = data.parse::<SExpression>().unwrap();
+
+        let HelloResponse::Reply(reply) = HelloResponse::parse(&expr, PROTOCOL_MINOR_VERSION).unwrap() else {
+            panic!("Expected hello reply");
+        };
+        assert_eq!(reply.range.len(), 2);
+        assert_eq!(reply.timeouts.keepalive, 20);
+        assert_eq!(reply.protocol_version_string(), "1.1");
+
+        let data = std::fs::read_to_string("tests/hello_reply_legacy.txt").unwrap();
+        let expr = data.parse::<SExpression>().unwrap();
+
+        assert!(matches!(
+            HelloResponse::parse(&expr, PROTOCOL_MINOR_VERSION).unwrap(),
+            HelloResponse::Rejected(_)
+        ));
+
+        let HelloResponse::Reply(reply) = HelloResponse::parse(&expr, LEGACY_PROTOCOL_MINOR_VERSION).unwrap() else {
+            panic!("Expected hello reply");
+        };
+        assert_eq!(reply.office_mode.ipaddr, "10.0.0.10");
+        assert!(reply.range.is_empty());
+        assert_eq!(reply.timeouts.keepalive, 20);
+        assert_eq!(reply.protocol_version_string(), "1.0");
+    }
+
+    #[test]
+    fn test_hello_reply_lease() {
+        let data = std::fs::read_to_string("tests/hello_reply.txt").unwrap();
+        let expr = data.parse::<SExpression>().unwrap();
+        let HelloResponse::Reply(reply) = HelloResponse::parse(&expr, PROTOCOL_MINOR_VERSION).unwrap() else {
+            panic!("Expected hello reply");
+        };
+        assert_eq!(reply.timeouts.om_lease, None);
+
+        let data = std::fs::read_to_string("tests/hello_reply_lease.txt").unwrap();
+        let expr = data.parse::<SExpression>().unwrap();
+        let HelloResponse::Reply(reply) = HelloResponse::parse(&expr, PROTOCOL_MINOR_VERSION).unwrap() else {
+            panic!("Expected hello reply");
+        };
+        assert_eq!(reply.timeouts.om_lease, Some(2));
+    }
+
+    #[test]
+    fn test_hello_reply_ipv6() {
+        let data = std::fs::read_to_string("tests/hello_reply_ipv6.txt").unwrap();
+        let expr = data.parse::<SExpression>().unwrap();
+
+        let HelloResponse::Reply(reply) = HelloResponse::parse(&expr, PROTOCOL_MINOR_VERSION).unwrap() else {
+            panic!("Expected hello reply");
+        };
+        assert_eq!(reply.office_mode.ipv6_network(), Some("fd00:10::a/64".parse().unwrap()));
+        assert_eq!(
+            reply.office_mode.dns_servers,
+            Some(vec!["10.0.0.1".parse().unwrap(), "fd00:10::1".parse().unwrap()].into())
+        );
+        assert_eq!(
+            crate::util::ranges_to_subnets_v6(&reply.range_v6).collect::<Vec<_>>(),
+            vec!["fd00:10::/64".parse::<ipnet::Ipv6Net>().unwrap()]
+        );
+
+        let hint = reply.optional.as_ref().and_then(|o| o.mtu);
+        assert_eq!(hint, Some(1300));
+        assert_eq!(TunnelParams::default().effective_mtu(hint), 1300);
+        assert_eq!(TunnelParams::default().effective_mtu(None), TunnelParams::DEFAULT_MTU);
+
+        let data = std::fs::read_to_string("tests/hello_reply.txt").unwrap();
+        let expr = data.parse::<SExpression>().unwrap();
+
+        let HelloResponse::Reply(reply) = HelloResponse::parse(&expr, PROTOCOL_MINOR_VERSION).unwrap() else {
+            panic!("Expected hello reply");
+        };
+        assert!(reply.office_mode.ipv6_network().is_none());
+        assert!(reply.range_v6.is_empty());
+    }
+
+    #[test]
+    fn test_parse_dns_suffix_forms() {
+        for suffix in [
+            "(\"dom1.com, dom2.com\")",
+            "(\"dom1.com dom2.com\")",
+            "(\n: (dom1.com)\n: (dom2.com)\n)",
+        ] {
+            let data = format!(
+                "(hello_reply\n:version (1)\n:protocol_version (1)\n:OM (\n:ipaddr (10.0.0.10)\n:dns_suffix {suffix}\n)\n)"
+            );
+            let expr = data.parse::<SExpression>().unwrap();
+
+            let HelloResponse::Reply(reply) = HelloResponse::parse(&expr, LEGACY_PROTOCOL_MINOR_VERSION).unwrap()
+            else {
+                panic!("Expected hello reply");
+            };
+            assert_eq!(
+                reply.office_mode.dns_suffix.unwrap().0,
+                vec!["dom1.com".to_owned(), "dom2.com".to_owned()]
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_dns_servers_forms() {
+        for servers in [
+            "(\"10.0.0.1,10.0.0.2,fd00::1\")",
+            "(\"10.0.0.1, 10.0.0.2; fd00::1\")",
+            "(\n: (10.0.0.1)\n: (10.0.0.2)\n: (fd00::1)\n)",
+        ] {
+            let data = format!(
+                "(hello_reply\n:version (1)\n:protocol_version (1)\n:OM (\n:ipaddr (10.0.0.10)\n:dns_servers {servers}\n)\n)"
+            );
+            let expr = data.parse::<SExpression>().unwrap();
+
+            let HelloResponse::Reply(reply) = HelloResponse::parse(&expr, LEGACY_PROTOCOL_MINOR_VERSION).unwrap()
+            else {
+                panic!("Expected hello reply");
+            };
+            assert_eq!(
+                reply.office_mode.dns_servers.unwrap().0,
+                vec![
+                    "10.0.0.1".parse::<std::net::IpAddr>().unwrap(),
+                    "10.0.0.2".parse().unwrap(),
+                    "fd00::1".parse().unwrap()
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_error_reply() {
+        let expr = "(error\n\t:code (7)\n\t:message (\"Gateway error\"))"
+            .parse::<SExpression>()
+            .unwrap();
+
+        assert_eq!(expr.object_name(), Some("error"));
+
+        let reply = expr.try_into::<ErrorReply>().unwrap();
+        assert_eq!(reply.data.code, 7);
+        assert_eq!(reply.data.message.as_deref(), Some("Gateway error"));
+
+        let error = reply.data.to_error().to_string();
+        assert!(error.contains('7'));
+        assert!(error.ends_with(": Gateway error"));
+
+        // without a message only the code is reported
+        let error = ErrorReplyData { code: 7, message: None }.to_error().to_string();
+        assert_eq!(error, i18n::tr!("error-gateway-error", code = 7));
+    }
+
+    #[test]
+    fn test_encode_disconnect_request() {
+        let data = std::fs::read_to_string("tests/disconnect_request.txt").unwrap();
+        let expected = data.parse::<SExpression>().unwrap();
+
+        let expr = SExpression::from(DisconnectRequest {
+            data: DisconnectRequestData::user_disconnect(),
+        });
+
+        assert_eq!(expr.object_name(), Some("disconnect"));
+        assert_eq!(expr, expected);
+        assert_eq!(expr.to_string().parse::<SExpression>().unwrap(), expected);
+    }
+
     #[test]
     fn test_parse_client_request() {
         let data = std::fs::read_to_string("tests/client_request.txt").unwrap();
@@ -0,0 +1,276 @@
+//! Import of the settings of the legacy snx client. Its `~/.snxrc` holds one `key value` pair per line, the
+//! hand-written files also use `key=value` and quoted values. The keys which have an equivalent are converted
+//! to the options of a TOML profile, every other line is reported back.
+
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use directories_next::UserDirs;
+
+use crate::util;
+
+const DEFAULT_PORT: &str = "443";
+
+/// Option converted from the legacy settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedOption {
+    pub key: &'static str,
+    pub value: String,
+    /// The legacy lines the option comes from.
+    pub source: Vec<String>,
+}
+
+/// Setting of the legacy file without an equivalent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnmappedSetting {
+    pub line: usize,
+    pub key: String,
+}
+
+/// Outcome of the import.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SnxrcImport {
+    pub options: Vec<ImportedOption>,
+    pub unmapped: Vec<UnmappedSetting>,
+}
+
+/// Location of the settings of the legacy client, `~/.snxrc`.
+pub fn default_path() -> Option<PathBuf> {
+    UserDirs::new().map(|dirs| dirs.home_dir().join(".snxrc"))
+}
+
+/// Read the legacy settings, the relative certificate paths are resolved against the directory of the file.
+pub fn load(path: &Path) -> anyhow::Result<SnxrcImport> {
+    let data = fs::read_to_string(path)?;
+    Ok(parse(&data, path.parent()))
+}
+
+// `key value`, `key=value` and `key = value`, the value optionally in quotes
+fn split_line(line: &str) -> (&str, &str) {
+    let Some(pos) = line.find(|c: char| c == '=' || c.is_whitespace()) else {
+        return (line, "");
+    };
+
+    let rest = line[pos..].trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest).trim();
+
+    let value = [('"', '"'), ('\'', '\'')]
+        .iter()
+        .find_map(|(open, close)| rest.strip_prefix(*open).and_then(|v| v.strip_suffix(*close)))
+        .unwrap_or(rest);
+
+    (&line[..pos], value)
+}
+
+fn is_enabled(value: &str) -> bool {
+    ["yes", "true", "on", "1"]
+        .iter()
+        .any(|enabled| value.eq_ignore_ascii_case(enabled))
+}
+
+fn resolve_path(value: &str, base_dir: Option<&Path>) -> PathBuf {
+    if let Some(rest) = value.strip_prefix("~/") {
+        if let Some(dirs) = UserDirs::new() {
+            return dirs.home_dir().join(rest);
+        }
+    }
+
+    match base_dir {
+        Some(dir) if Path::new(value).is_relative() => dir.join(value),
+        _ => PathBuf::from(value),
+    }
+}
+
+/// Convert the legacy settings. The keys are case-insensitive, the lines starting with `#` or `;` are comments.
+pub fn parse(data: &str, base_dir: Option<&Path>) -> SnxrcImport {
+    let mut result = SnxrcImport::default();
+    let mut port = None;
+
+    for (index, line) in data.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        let (key, value) = split_line(line);
+        let mut add = |key, value: String| {
+            result.options.push(ImportedOption {
+                key,
+                value,
+                source: vec![line.to_owned()],
+            });
+        };
+
+        match key.to_ascii_lowercase().as_str() {
+            "server" if !value.is_empty() => add("server-name", value.to_owned()),
+            "username" | "user" if !value.is_empty() => add("user-name", value.to_owned()),
+            "realm" if !value.is_empty() => add("login-type", value.to_owned()),
+            "certificate" | "cert" if !value.is_empty() => {
+                add("cert-type", "pkcs12".to_owned());
+                add("cert-path", resolve_path(value, base_dir).display().to_string());
+            }
+            "debug" if is_enabled(value) => add("log-level", "debug".to_owned()),
+            "debug" => {}
+            "port" if !value.is_empty() => port = Some((index + 1, line.to_owned(), value.to_owned())),
+            _ => result.unmapped.push(UnmappedSetting {
+                line: index + 1,
+                key: key.to_owned(),
+            }),
+        }
+    }
+
+    // the legacy client keeps the port apart from the server, it is a part of the server name here
+    if let Some((line, source, port)) = port {
+        match result.options.iter_mut().find(|option| option.key == "server-name") {
+            Some(server) => {
+                if port != DEFAULT_PORT && !server.value.contains(':') {
+                    server.value = format!("{}:{}", server.value, port);
+                }
+                server.source.push(source);
+            }
+            None => result.unmapped.push(UnmappedSetting {
+                line,
+                key: "port".to_owned(),
+            }),
+        }
+    }
+
+    result
+}
+
+impl SnxrcImport {
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|option| option.key == key)
+            .map(|option| option.value.as_str())
+    }
+
+    /// The TOML configuration of the imported options, in the section of the profile if one is given.
+    /// Every option is preceded by the legacy lines it comes from, the settings which were not imported
+    /// are listed in the header.
+    pub fn to_toml(&self, source: &Path, profile: Option<&str>) -> String {
+        let mut result = format!("# Imported from {}\n", source.display());
+        for unmapped in &self.unmapped {
+            let _ = writeln!(result, "# Not imported, line {}: {}", unmapped.line, unmapped.key);
+        }
+
+        if let Some(profile) = profile {
+            let _ = writeln!(result, "\n[profile.{}]", toml::Value::String(profile.to_owned()));
+        }
+
+        for option in &self.options {
+            result.push('\n');
+            for line in &option.source {
+                let _ = writeln!(result, "# {line}");
+            }
+            let _ = writeln!(result, "{} = {}", option.key, util::plain_value_to_toml(&option.value));
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> SnxrcImport {
+        let data = fs::read_to_string(format!("tests/{name}")).unwrap();
+        parse(&data, Some(Path::new("/home/john")))
+    }
+
+    fn unmapped(result: &SnxrcImport) -> Vec<(usize, &str)> {
+        result
+            .unmapped
+            .iter()
+            .map(|unmapped| (unmapped.line, unmapped.key.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_split_line() {
+        assert_eq!(split_line("server vpn.example.com"), ("server", "vpn.example.com"));
+        assert_eq!(split_line("server\tvpn.example.com "), ("server", "vpn.example.com"));
+        assert_eq!(split_line("server=vpn.example.com"), ("server", "vpn.example.com"));
+        assert_eq!(
+            split_line("server = \"vpn.example.com\""),
+            ("server", "vpn.example.com")
+        );
+        assert_eq!(
+            split_line("certificate '/home/john/my cert.p12'"),
+            ("certificate", "/home/john/my cert.p12")
+        );
+        assert_eq!(split_line("reauth"), ("reauth", ""));
+    }
+
+    #[test]
+    fn test_parse_basic() {
+        let result = fixture("snxrc_basic.txt");
+
+        assert_eq!(result.value("server-name"), Some("vpn.example.com"));
+        assert_eq!(result.value("user-name"), Some("john.doe"));
+        assert_eq!(result.value("log-level"), None);
+        assert_eq!(unmapped(&result), [(3, "reauth")]);
+    }
+
+    #[test]
+    fn test_parse_certificate() {
+        let result = fixture("snxrc_certificate.txt");
+
+        assert_eq!(result.value("server-name"), Some("vpn.example.com:4433"));
+        assert_eq!(result.value("cert-type"), Some("pkcs12"));
+        assert_eq!(result.value("cert-path"), Some("/home/john/certs/john.p12"));
+        assert_eq!(result.value("log-level"), Some("debug"));
+        assert_eq!(result.value("user-name"), None);
+        assert!(result.unmapped.is_empty());
+    }
+
+    #[test]
+    fn test_parse_hand_written() {
+        let result = fixture("snxrc_hand_written.txt");
+
+        assert_eq!(result.value("server-name"), Some("vpn.example.com"));
+        assert_eq!(result.value("user-name"), Some("Jane Doe"));
+        assert_eq!(result.value("login-type"), Some("vpn_Username_Password"));
+        assert_eq!(result.value("cert-path"), Some("/etc/snx/jane.p12"));
+        assert_eq!(unmapped(&result), [(7, "sslversion"), (9, "proxy")]);
+
+        // the default port is left out, the source lines are kept for the comments
+        let server = result
+            .options
+            .iter()
+            .find(|option| option.key == "server-name")
+            .unwrap();
+        assert_eq!(server.source, ["SERVER=vpn.example.com", "Port=443"]);
+    }
+
+    #[test]
+    fn test_parse_port_without_server() {
+        let result = parse("port 4433\nusername john\n", None);
+        assert_eq!(result.value("server-name"), None);
+        assert_eq!(unmapped(&result), [(1, "port")]);
+    }
+
+    #[test]
+    fn test_to_toml() {
+        let result = fixture("snxrc_hand_written.txt");
+        let source = Path::new("/home/jane/.snxrc");
+
+        let toml = result.to_toml(source, None);
+        assert!(toml.starts_with("# Imported from /home/jane/.snxrc\n# Not imported, line 7: sslversion\n"));
+        assert!(toml.contains("\n# SERVER=vpn.example.com\n# Port=443\nserver-name = \"vpn.example.com\"\n"));
+
+        let config = util::parse_toml_config(&toml).unwrap();
+        assert_eq!(config.values["user-name"], "Jane Doe");
+        assert_eq!(config.values["cert-type"], "pkcs12");
+        assert_eq!(config.values["cert-path"], "/etc/snx/jane.p12");
+
+        let config = util::parse_toml_config(result.to_toml(source, Some("office"))).unwrap();
+        assert!(config.values.is_empty());
+        assert_eq!(config.profiles["office"]["login-type"], "vpn_Username_Password");
+    }
+}
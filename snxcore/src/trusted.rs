@@ -0,0 +1,177 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context;
+use reqwest::{Certificate, tls::TlsInfo};
+use tracing::debug;
+
+use crate::{
+    model::params::{TrustedNetwork, TunnelParams},
+    network_match::NetworkFacts,
+    platform::{self, DefaultGateway, NetworkInterface},
+    util,
+};
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Detects whether the machine is attached to one of the configured trusted networks,
+/// in which case the VPN tunnel should not be established.
+pub struct TrustedNetworkDetector {
+    params: Arc<TunnelParams>,
+}
+
+impl TrustedNetworkDetector {
+    pub fn new(params: Arc<TunnelParams>) -> Self {
+        Self { params }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.params.trusted_networks.is_empty()
+    }
+
+    pub async fn is_trusted(&self) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+
+        let net = platform::new_network_interface();
+        let gateway = net.get_default_gateway(self.params.tunnel_device()).await.ok();
+        let facts = NetworkFacts::collect(self.params.tunnel_device()).await;
+
+        debug!(
+            "Checking trusted networks, gateway: {:?}, network: {:?}",
+            gateway, facts
+        );
+
+        for criterion in &self.params.trusted_networks {
+            let matched = match criterion {
+                TrustedNetwork::SearchDomain(domain) => facts.has_domain(domain),
+                TrustedNetwork::Ssid(ssid) => facts.has_ssid(ssid),
+                TrustedNetwork::Subnet(subnet) => facts.has_subnet(subnet),
+                TrustedNetwork::GatewayMac(mac) => gateway
+                    .as_ref()
+                    .and_then(|g| g.mac.as_deref())
+                    .is_some_and(|m| m.eq_ignore_ascii_case(mac)),
+                TrustedNetwork::ProbeUrl { url, fingerprint } => {
+                    self.probe(url, fingerprint.as_deref(), gateway.as_ref()).await
+                }
+            };
+
+            if matched {
+                debug!("Trusted network criterion matched: {}", criterion);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    async fn probe(&self, url: &str, fingerprint: Option<&str>, gateway: Option<&DefaultGateway>) -> bool {
+        match self.do_probe(url, fingerprint, gateway).await {
+            Ok(()) => true,
+            Err(e) => {
+                debug!("Trusted network probe {} failed: {}", url, e);
+                false
+            }
+        }
+    }
+
+    async fn do_probe(
+        &self,
+        url: &str,
+        fingerprint: Option<&str>,
+        gateway: Option<&DefaultGateway>,
+    ) -> anyhow::Result<()> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(PROBE_TIMEOUT)
+            .tls_info(fingerprint.is_some());
+
+        match fingerprint {
+            // the pinned certificate is the proof, it may be self-signed
+            Some(_) => builder = builder.danger_accept_invalid_certs(true),
+            None => {
+                for ca_cert in &self.params.ca_cert {
+                    let data = tokio::fs::read(ca_cert).await?;
+                    let cert = Certificate::from_pem(&data).or_else(|_| Certificate::from_der(&data))?;
+                    builder = builder.add_root_certificate(cert);
+                }
+            }
+        }
+
+        // the probe must bypass the tunnel, otherwise it will succeed whenever the VPN is up
+        if let Some(gateway) = gateway {
+            builder = bind_to_gateway(builder, gateway);
+        }
+
+        let response = builder.build()?.get(url).send().await?;
+
+        if let Some(expected) = fingerprint {
+            let cert = response
+                .extensions()
+                .get::<TlsInfo>()
+                .and_then(|info| info.peer_certificate())
+                .context("No peer certificate")?;
+            let actual = hex::encode(openssl::sha::sha256(cert));
+            anyhow::ensure!(
+                actual == expected,
+                "Certificate fingerprint {} does not match the expected one",
+                actual
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Wait for network changes until the trusted state differs from `trusted`, returning the new state.
+    pub async fn wait_for_change(&self, trusted: bool) -> bool {
+        let mut changes = platform::new_network_interface().network_changes();
+
+        loop {
+            if changes.changed().await.is_err() {
+                futures::future::pending::<()>().await;
+            }
+
+            util::wait_for_network_settle(&mut changes).await;
+
+            let now = self.is_trusted().await;
+            if now != trusted {
+                return now;
+            }
+        }
+    }
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "fuchsia",
+    target_os = "illumos",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "solaris",
+    target_os = "tvos",
+    target_os = "visionos",
+    target_os = "watchos",
+))]
+fn bind_to_gateway(builder: reqwest::ClientBuilder, gateway: &DefaultGateway) -> reqwest::ClientBuilder {
+    builder.interface(&gateway.device)
+}
+
+// Without the interface binding the source address selects the route, at least on the hosts with the weak model.
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "fuchsia",
+    target_os = "illumos",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "solaris",
+    target_os = "tvos",
+    target_os = "visionos",
+    target_os = "watchos",
+)))]
+fn bind_to_gateway(builder: reqwest::ClientBuilder, gateway: &DefaultGateway) -> reqwest::ClientBuilder {
+    match gateway.local_address {
+        Some(address) => builder.local_address(std::net::IpAddr::V4(address)),
+        None => builder,
+    }
+}
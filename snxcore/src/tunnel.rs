@@ -1,26 +1,49 @@
-use std::sync::Arc;
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    sync::Arc,
+    time::Duration,
+};
 
 use async_trait::async_trait;
 use bytes::Bytes;
-use ipnet::Ipv4Net;
+use ipnet::{IpNet, Ipv4Net};
 use tokio::sync::mpsc;
+use tracing::{Span, debug, info, warn};
 
 use crate::{
     model::{
-        params::{TunnelParams, TunnelType},
+        params::{Ipv6BlockMode, TunnelParams, TunnelType},
         *,
     },
-    tunnel::{ipsec::connector::IpsecTunnelConnector, ssl::connector::CccTunnelConnector},
+    platform::{
+        self, AppRouteConfig, AppRouting, DnsRedirect, DnsRedirectConfig, GatewayRoute, Ipv6Blocker, KillSwitch,
+        MssClamp, NamespaceConfig, NetworkInterface, NetworkNamespace, RoutingConfigurator,
+    },
+    tunnel::{
+        ipsec::connector::IpsecTunnelConnector,
+        journal::{NetworkChange, NetworkJournal},
+        ssl::connector::CccTunnelConnector,
+    },
+    util,
 };
 
 pub mod device;
+mod dns_check;
+mod dns_client;
+mod domain_routes;
+mod dry_run;
+mod hooks;
 mod ipsec;
+pub mod journal;
+mod pmtu;
 mod ssl;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum TunnelCommand {
     Terminate(bool),
     ReKey(IpsecSession),
+    /// Reloaded configuration, limited to the options applied without a reconnect.
+    Reconfigure(Arc<TunnelParams>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -30,6 +53,31 @@ pub enum TunnelEvent {
     RekeyCheck,
     RemoteControlData(Bytes),
     Rekeyed(Ipv4Net),
+    SessionExpiring(Duration),
+    TrustedNetworkDetected,
+    TrustedNetworkLost,
+    DnsChecked(Vec<DnsCheckResult>),
+    /// Whether the last keepalive round trip succeeded, sent when it changes.
+    Keepalive(bool),
+}
+
+/// Reports the outcome of the keepalive round trips to the session, only the changes are sent.
+pub(crate) struct KeepaliveReporter {
+    events: mpsc::Sender<TunnelEvent>,
+    last: Option<bool>,
+}
+
+impl KeepaliveReporter {
+    pub(crate) fn new(events: mpsc::Sender<TunnelEvent>) -> Self {
+        Self { events, last: None }
+    }
+
+    pub(crate) async fn report(&mut self, ok: bool) {
+        if self.last != Some(ok) {
+            self.last = Some(ok);
+            let _ = self.events.send(TunnelEvent::Keepalive(ok)).await;
+        }
+    }
 }
 
 #[async_trait]
@@ -56,9 +104,357 @@ pub trait TunnelConnector {
     async fn handle_tunnel_event(&mut self, event: TunnelEvent) -> anyhow::Result<()>;
 }
 
+/// Span for the authentication phase, the session id is not known yet.
+pub fn auth_span(params: &TunnelParams) -> Span {
+    tracing::info_span!("auth", gateway = %params.server_name, profile = %params.profile_name())
+}
+
+/// Span for the tunnel lifetime: hello exchange, run loop and the tasks spawned from it.
+pub fn session_span(params: &TunnelParams, session: &VpnSession) -> Span {
+    tracing::info_span!(
+        "session",
+        session_id = %session.ccc_session_id,
+        gateway = %params.server_name,
+        profile = %params.profile_name()
+    )
+}
+
 pub async fn new_tunnel_connector(params: Arc<TunnelParams>) -> anyhow::Result<Box<dyn TunnelConnector + Send + Sync>> {
+    // done before the authentication, so that the user does not log in only to hit EPERM
+    if !params.skip_permission_check && !params.dry_run {
+        platform::check_permissions(&params)?;
+    }
+
+    // leftovers of a crashed session of the same profile would conflict with the new tunnel
+    if !params.dry_run {
+        journal::restore_stale(&params).await;
+    }
+
+    platform::apply_params(&params);
+
     match params.tunnel_type {
         TunnelType::Ssl => Ok(Box::new(CccTunnelConnector::new(params).await?)),
         TunnelType::Ipsec => Ok(Box::new(IpsecTunnelConnector::new(params).await?)),
     }
 }
+
+/// Files written by the tunnel after the network setup, they must stay writable when the privileges are dropped.
+pub(crate) fn state_files(params: &TunnelParams) -> Vec<std::path::PathBuf> {
+    let mut files = vec![journal::journal_path(params)];
+
+    if params.tunnel_type == TunnelType::Ipsec && params.ike_persist {
+        files.push(ipsec::connector::ike_session_path(params));
+    }
+
+    files.extend(params.log_file.clone());
+
+    files
+}
+
+/// The kill switch outlives the tunnel so that nothing leaks between reconnects,
+/// it is removed only when the user disconnects.
+pub async fn remove_kill_switch(params: &TunnelParams) {
+    if !params.kill_switch {
+        return;
+    }
+
+    if let Err(e) = platform::new_kill_switch().disable().await {
+        warn!("Unable to remove kill switch: {}", e);
+    }
+}
+
+// The transport to the gateway must never be routed into the tunnel, also when the acquired ranges cover it.
+// Only the default route mode depends on it, otherwise a failed lookup is just logged.
+async fn pin_gateway_route<C: RoutingConfigurator + Sync>(
+    params: &TunnelParams,
+    configurator: &C,
+    device: &str,
+    address: Ipv4Addr,
+    destination: Ipv4Addr,
+    journal: &NetworkJournal,
+) -> anyhow::Result<Option<GatewayRoute>> {
+    let result = async {
+        let route = configurator.find_gateway_route(destination).await?;
+        journal.record(NetworkChange::GatewayRoute {
+            device: device.to_owned(),
+            address,
+            route: route.clone(),
+        });
+        configurator.add_gateway_route(&route).await?;
+        anyhow::Ok(route)
+    }
+    .await;
+
+    match result {
+        Ok(route) => Ok(Some(route)),
+        Err(e) if params.default_route => Err(e),
+        Err(e) => {
+            warn!("Unable to pin the route to the gateway {}: {}", destination, e);
+            Ok(None)
+        }
+    }
+}
+
+/// Where the static routes of the running tunnel are installed, for a configuration reload.
+pub(crate) struct StaticRoutes {
+    pub device: String,
+    pub address: Ipv4Addr,
+    pub peer: Option<Ipv4Addr>,
+    /// Never routed through the tunnel: the gateway and the conflicting local networks.
+    pub exclusions: Vec<Ipv4Net>,
+    /// Installed from the gateway ranges, they stay when a static route with the same prefix is dropped.
+    pub acquired: Vec<Ipv4Net>,
+}
+
+impl StaticRoutes {
+    fn routes(&self, params: &TunnelParams) -> Vec<Ipv4Net> {
+        let mut routes = params.add_routes.clone();
+        routes.extend(params.include_routes_v4(true));
+
+        let mut exclusions = params.exclude_routes.clone();
+        exclusions.extend(&self.exclusions);
+
+        util::exclude_routes(&routes, &exclusions)
+    }
+
+    /// Add the static routes of the reloaded configuration and remove the ones it no longer has.
+    pub(crate) async fn update(&self, old: &TunnelParams, new: &TunnelParams, journal: &NetworkJournal) {
+        let old_routes = self.routes(old);
+        let new_routes = self.routes(new);
+
+        let added = new_routes
+            .iter()
+            .filter(|route| !old_routes.contains(route))
+            .copied()
+            .collect::<Vec<_>>();
+        let removed = old_routes
+            .iter()
+            .filter(|route| !new_routes.contains(route) && !self.acquired.contains(route))
+            .map(|route| IpNet::V4(*route))
+            .collect::<Vec<_>>();
+
+        let configurator =
+            platform::new_routing_configurator(&self.device, self.address, new.route_metric).with_peer(self.peer);
+
+        if !removed.is_empty() {
+            debug!("Removing {} routes from {}", removed.len(), self.device);
+            if let Err(e) = configurator.remove_routes(&removed).await {
+                warn!("Unable to remove the routes from {}: {}", self.device, e);
+            }
+            journal.forget_routes(&removed);
+        }
+
+        if !added.is_empty() {
+            debug!("Adding {} routes through {}", added.len(), self.device);
+            journal.record(NetworkChange::Routes {
+                device: self.device.clone(),
+                address: self.address,
+                routes: added.iter().copied().map(IpNet::V4).collect(),
+                metric: new.route_metric,
+                peer: self.peer,
+            });
+            let _ = configurator.add_routes(&added, &new.ignore_routes).await;
+        }
+    }
+}
+
+// Done after the routes are set up, the change is reverted together with them.
+async fn block_ipv6(params: &TunnelParams, device: &str, journal: &NetworkJournal) -> anyhow::Result<()> {
+    if params.block_ipv6 == Ipv6BlockMode::Off {
+        return Ok(());
+    }
+
+    let blocker = platform::new_ipv6_blocker();
+    let config = blocker.prepare(params.block_ipv6, device).await?;
+    journal.record(NetworkChange::Ipv6Block { config: config.clone() });
+    blocker.block(&config).await
+}
+
+// A missing nft only loses the clamping, the tunnel itself works without it.
+async fn clamp_mss(params: &TunnelParams, device: &str, journal: &NetworkJournal) {
+    if !params.mss_clamp_enabled() {
+        return;
+    }
+
+    journal.record(NetworkChange::MssClamp {
+        device: device.to_owned(),
+    });
+    if let Err(e) = platform::new_mss_clamp().enable(device).await {
+        warn!("Unable to clamp the TCP MSS through {}: {}", device, e);
+    }
+}
+
+// The marked traffic of the selected applications goes through the tunnel table. The system resolver is not one
+// of them, so the tunnel DNS servers are routed through the tunnel for everyone and split DNS keeps working.
+async fn setup_app_routing(
+    params: &TunnelParams,
+    device: &str,
+    address: Ipv4Addr,
+    peer: Option<Ipv4Addr>,
+    dns_servers: &[IpAddr],
+    journal: &NetworkJournal,
+) -> anyhow::Result<()> {
+    if params.no_routing || !params.app_routing_enabled() {
+        return Ok(());
+    }
+
+    let dns_routes = dns_servers
+        .iter()
+        .filter_map(|server| match server {
+            IpAddr::V4(v4) => Some(Ipv4Net::from(*v4)),
+            IpAddr::V6(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    if !dns_routes.is_empty() {
+        journal.record(NetworkChange::Routes {
+            device: device.to_owned(),
+            address,
+            routes: dns_routes.iter().copied().map(IpNet::V4).collect(),
+            metric: params.route_metric,
+            peer,
+        });
+        let _ = platform::new_routing_configurator(device, address, params.route_metric)
+            .with_peer(peer)
+            .add_routes(&dns_routes, &params.ignore_routes)
+            .await;
+    }
+
+    let config = AppRouteConfig::new(params, device);
+    journal.record(NetworkChange::AppRoute { config: config.clone() });
+    platform::new_app_routing().enable(&config).await
+}
+
+// Opt-in only: queries to the hardcoded public resolvers go to the tunnel DNS server instead of leaking
+// the internal names outside of the tunnel.
+async fn redirect_dns(
+    params: &TunnelParams,
+    device: &str,
+    dns_servers: &[IpAddr],
+    journal: &NetworkJournal,
+) -> anyhow::Result<()> {
+    if !params.dns_redirect {
+        return Ok(());
+    }
+
+    let Some(config) = DnsRedirectConfig::new(params, device, dns_servers) else {
+        warn!("No tunnel DNS servers, the DNS queries are not redirected");
+        return Ok(());
+    };
+
+    warn!(
+        "Redirecting all DNS queries on port 53 to {}, DNS over HTTPS and DNS over TLS are not redirected",
+        config
+            .server
+            .map(IpAddr::V4)
+            .into_iter()
+            .chain(config.server_v6.map(IpAddr::V6))
+            .map(|server| server.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    journal.record(NetworkChange::DnsRedirect { config: config.clone() });
+    platform::new_dns_redirect().enable(&config).await
+}
+
+// Replaces the host side routing and DNS setup when the tunnel runs in a network namespace.
+// A namespace which existed before is joined and kept, only the one created here is deleted on teardown.
+async fn setup_namespace(config: NamespaceConfig, journal: &NetworkJournal) -> anyhow::Result<()> {
+    let netns = platform::new_network_namespace();
+
+    if netns.exists(&config.name).await {
+        info!("Using existing network namespace {}", config.name);
+    } else {
+        info!("Creating network namespace {}", config.name);
+        journal.record(NetworkChange::Namespace {
+            name: config.name.clone(),
+        });
+        netns.create(&config.name).await?;
+    }
+
+    journal.record(NetworkChange::NamespaceDevice { config: config.clone() });
+    netns.configure(&config).await
+}
+
+// The resolver set passed to the DNS backends, the same for all of them.
+fn log_resolver_config(params: &TunnelParams, config: &platform::ResolverConfig) {
+    info!(
+        "Effective DNS servers {:?}, search domains {:?} ({} DNS override)",
+        config.dns_servers, config.search_domains, params.dns_override
+    );
+}
+
+/// Persistent tun devices survive reconnects, the ones created by this process are deleted on explicit disconnect.
+pub async fn remove_persistent_devices() {
+    for name in device::take_persistent_devices() {
+        if let Err(e) = platform::new_network_interface().delete_device(&name).await {
+            warn!("Unable to delete tun device {}: {}", name, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use tracing::{Instrument, debug, level_filters::LevelFilter};
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct CaptureWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for CaptureWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_span_fields() {
+        let writer = CaptureWriter::default();
+        let make_writer = writer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(LevelFilter::TRACE)
+            .with_ansi(false)
+            .with_writer(move || make_writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let params = TunnelParams {
+            server_name: "vpn.example.com".to_owned(),
+            config_file: "/etc/snx-rs/work.conf".into(),
+            ..Default::default()
+        };
+        let session = VpnSession {
+            ccc_session_id: "12345".to_owned(),
+            ..VpnSession::empty()
+        };
+
+        async {
+            debug!("outer message");
+            tokio::spawn(async { debug!("spawned message") }.in_current_span())
+                .await
+                .unwrap();
+        }
+        .instrument(session_span(&params, &session))
+        .await;
+
+        let output = String::from_utf8(writer.0.lock().unwrap().clone()).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            assert!(line.contains("session{session_id=12345 gateway=vpn.example.com profile=work}"));
+        }
+    }
+}
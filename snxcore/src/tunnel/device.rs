@@ -1,48 +1,398 @@
-use std::net::Ipv4Addr;
+#[cfg(target_os = "linux")]
+use std::{collections::HashMap, path::Path, sync::Mutex};
+use std::{io, pin::Pin};
 
-use tracing::debug;
-use tun::AbstractDevice;
+use async_trait::async_trait;
+use futures::{Sink, Stream};
+#[cfg(unix)]
+use i18n::tr;
+#[cfg(target_os = "linux")]
+use once_cell::sync::Lazy;
+#[cfg(target_os = "openbsd")]
+pub use openbsd::TunDevice;
+use tracing::{debug, warn};
+#[cfg(all(unix, not(target_os = "openbsd")))]
+pub use unix::TunDevice;
+#[cfg(windows)]
+pub use windows::TunDevice;
 
-use crate::model::params::TunnelParams;
+use crate::{model::params::TunnelParams, util};
 
-pub struct TunDevice {
-    inner: Option<tun::AsyncDevice>,
-    dev_name: String,
+#[cfg(test)]
+pub mod loopback;
+#[cfg(target_os = "linux")]
+mod multiqueue;
+#[cfg(target_os = "openbsd")]
+mod openbsd;
+#[cfg(all(unix, not(target_os = "openbsd")))]
+mod unix;
+#[cfg(windows)]
+mod windows;
+
+#[cfg(target_os = "linux")]
+const SYS_CLASS_NET: &str = "/sys/class/net";
+#[cfg(any(target_os = "linux", target_os = "openbsd"))]
+const BUFFER_SIZE: usize = 65536;
+
+// Persistent devices by configured name, they outlive the tunnels and are reattached on reconnect.
+// The flag tells whether the device was created by this process and must be deleted on disconnect.
+#[cfg(target_os = "linux")]
+static PERSISTENT_DEVICES: Lazy<Mutex<HashMap<String, (String, bool)>>> = Lazy::new(Default::default);
+
+pub type PacketSink = Pin<Box<dyn Sink<Vec<u8>, Error = io::Error> + Send>>;
+pub type PacketStream = Pin<Box<dyn Stream<Item = io::Result<Vec<u8>>> + Send>>;
+
+/// Framed IP packet device which carries the tunnel data path: the tun device or an in-memory one in tests.
+#[async_trait]
+pub trait PacketDevice: Send {
+    fn name(&self) -> &str;
+
+    fn mtu(&self) -> u16;
+
+    /// Replace the local address with a point-to-point one.
+    async fn set_peer(&mut self, ip_address: std::net::Ipv4Addr, peer: std::net::Ipv4Addr) -> anyhow::Result<()>;
+
+    /// Split the device into a sink and a stream of plain IP packets, it can be done only once.
+    fn split(&mut self) -> anyhow::Result<(PacketSink, PacketStream)>;
 }
 
-impl TunDevice {
-    pub fn new(name: &str, ip_address: Ipv4Addr, netmask: Option<Ipv4Addr>) -> anyhow::Result<Self> {
-        let mut config = tun::Configuration::default();
+#[async_trait]
+impl PacketDevice for TunDevice {
+    fn name(&self) -> &str {
+        TunDevice::name(self)
+    }
 
-        config.address(ip_address).mtu(TunnelParams::DEFAULT_MTU).up();
+    fn mtu(&self) -> u16 {
+        TunDevice::mtu(self)
+    }
 
-        if cfg!(target_os = "macos") {
-            debug!("Ignoring tun device name on macOS: {name}");
-        } else {
-            config.tun_name(name);
-        }
+    async fn set_peer(&mut self, ip_address: std::net::Ipv4Addr, peer: std::net::Ipv4Addr) -> anyhow::Result<()> {
+        TunDevice::set_peer(self, ip_address, peer).await
+    }
 
-        if let Some(netmask) = netmask {
-            config.netmask(netmask);
-        }
+    fn split(&mut self) -> anyhow::Result<(PacketSink, PacketStream)> {
+        TunDevice::split(self)
+    }
+}
 
-        let dev = tun::create_as_async(&config)?;
+#[cfg(target_os = "linux")]
+fn device_exists(name: &str) -> bool {
+    Path::new(SYS_CLASS_NET).join(name).exists()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn device_exists(name: &str) -> bool {
+    std::ffi::CString::new(name).is_ok_and(|name| unsafe { libc::if_nametoindex(name.as_ptr()) } != 0)
+}
+
+#[cfg(windows)]
+fn device_exists(name: &str) -> bool {
+    crate::platform::iphelper::interface_luid(name).is_ok()
+}
+
+/// Create the tun device for the tunnel, or take the one passed by a privileged helper.
+pub async fn open_device(
+    params: &TunnelParams,
+    name: &str,
+    ip_address: std::net::Ipv4Addr,
+    netmask: Option<std::net::Ipv4Addr>,
+    mtu: u16,
+) -> anyhow::Result<TunDevice> {
+    match params.tun_fd {
+        Some(fd) => TunDevice::from_fd(fd, ip_address, netmask, mtu, !params.no_device_config).await,
+        None => {
+            // unknown users and groups are reported before the device is created
+            let access = DeviceAccess::new(params)?;
+            let mut device =
+                TunDevice::new(name, ip_address, netmask, mtu, params.persistent_if, params.tun_queues).await?;
+            if access != DeviceAccess::default() {
+                device.set_access(&access)?;
+            }
+            Ok(device)
+        }
+    }
+}
 
-        let dev_name = dev.tun_name()?;
+/// Owner, group and persistence flags of a created tun device, so that an unprivileged process can reopen it.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DeviceAccess {
+    pub owner: Option<u32>,
+    pub group: Option<u32>,
+    pub persist: bool,
+}
 
-        debug!("Created tun device: {dev_name}");
+impl DeviceAccess {
+    #[cfg(unix)]
+    pub fn new(params: &TunnelParams) -> anyhow::Result<Self> {
+        Ok(Self {
+            owner: params.tun_owner.as_deref().map(resolve_user).transpose()?,
+            group: params.tun_group.as_deref().map(resolve_group).transpose()?,
+            persist: params.tun_persist,
+        })
+    }
 
+    #[cfg(windows)]
+    pub fn new(params: &TunnelParams) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            params.tun_owner.is_none() && params.tun_group.is_none(),
+            "The tun device owner and group are not supported on Windows"
+        );
         Ok(Self {
-            inner: Some(dev),
-            dev_name,
+            persist: params.tun_persist,
+            ..Default::default()
         })
     }
+}
+
+// Numeric ids are taken as is, they do not need to exist in the user database.
+#[cfg(unix)]
+fn resolve_user(name: &str) -> anyhow::Result<u32> {
+    if let Ok(uid) = name.parse() {
+        return Ok(uid);
+    }
+    nix::unistd::User::from_name(name)
+        .ok()
+        .flatten()
+        .map(|user| user.uid.as_raw())
+        .ok_or_else(|| anyhow::anyhow!(tr!("error-unknown-tun-owner", name = name)))
+}
+
+#[cfg(unix)]
+pub(crate) fn resolve_group(name: &str) -> anyhow::Result<u32> {
+    if let Ok(gid) = name.parse() {
+        return Ok(gid);
+    }
+    nix::unistd::Group::from_name(name)
+        .ok()
+        .flatten()
+        .map(|group| group.gid.as_raw())
+        .ok_or_else(|| anyhow::anyhow!(tr!("error-unknown-tun-owner", name = name)))
+}
+
+/// Configure the device as point-to-point with the gateway peer, the local address is kept when there is
+/// no usable peer or the configuration fails. Returns the configured peer.
+pub async fn configure_peer(
+    params: &TunnelParams,
+    device: &mut TunDevice,
+    ip_address: std::net::Ipv4Addr,
+    peer: Option<std::net::Ipv4Addr>,
+) -> Option<std::net::Ipv4Addr> {
+    let peer = peer.filter(|peer| *peer != ip_address && !peer.is_unspecified())?;
+
+    if params.no_device_config {
+        debug!(
+            "The address of {} is managed externally, ignoring peer {}",
+            device.name(),
+            peer
+        );
+        return None;
+    }
 
-    pub fn name(&self) -> &str {
-        &self.dev_name
+    match device.set_peer(ip_address, peer).await {
+        Ok(()) => {
+            debug!("Configured {} as point-to-point with peer {}", device.name(), peer);
+            Some(peer)
+        }
+        Err(e) => {
+            warn!("Unable to configure peer {} on {}: {}", peer, device.name(), e);
+            None
+        }
     }
+}
+
+/// Expand the `%d` placeholder of the configured interface name with the first free index.
+pub fn resolve_device_name(template: &str) -> anyhow::Result<String> {
+    util::expand_device_name(template, device_exists)
+}
+
+#[cfg(target_os = "linux")]
+fn persistent_device_name(template: &str) -> Option<String> {
+    PERSISTENT_DEVICES
+        .lock()
+        .unwrap()
+        .get(template)
+        .map(|(name, _)| name.clone())
+}
+
+// A reconnect attaches to the device registered for the template instead of expanding it again,
+// returns the name and whether the device is already there.
+#[cfg(target_os = "linux")]
+fn select_persistent_device<F>(template: &str, exists: F) -> anyhow::Result<(String, bool)>
+where
+    F: Fn(&str) -> bool,
+{
+    let name = match persistent_device_name(template) {
+        Some(name) => name,
+        None => util::expand_device_name(template, &exists)?,
+    };
+    let existing = exists(&name);
+    Ok((name, existing))
+}
+
+#[cfg(target_os = "linux")]
+fn register_persistent_device(template: &str, name: &str, created: bool) {
+    PERSISTENT_DEVICES
+        .lock()
+        .unwrap()
+        .entry(template.to_owned())
+        .or_insert_with(|| (name.to_owned(), created));
+}
+
+// A device made persistent with `tun-persist` outlives the process and is never deleted.
+#[cfg(target_os = "linux")]
+fn keep_persistent_device(name: &str) {
+    for (device, created) in PERSISTENT_DEVICES.lock().unwrap().values_mut() {
+        if device == name {
+            *created = false;
+        }
+    }
+}
+
+// Nonblocking reads and writes of whole packets on a raw tun file descriptor.
+#[cfg(any(target_os = "linux", target_os = "openbsd"))]
+async fn read_packet(fd: &tokio::io::unix::AsyncFd<std::fs::File>) -> io::Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut buf = vec![0u8; BUFFER_SIZE];
+    loop {
+        let mut guard = fd.readable().await?;
+        match guard.try_io(|inner| inner.get_ref().read(&mut buf)) {
+            Ok(result) => {
+                let size = result?;
+                buf.truncate(size);
+                return Ok(buf);
+            }
+            Err(_would_block) => continue,
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "openbsd"))]
+async fn write_packet(fd: &tokio::io::unix::AsyncFd<std::fs::File>, data: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+
+    loop {
+        let mut guard = fd.writable().await?;
+        match guard.try_io(|inner| inner.get_ref().write(data)) {
+            Ok(result) => return result.map(|_| ()),
+            Err(_would_block) => continue,
+        }
+    }
+}
+
+/// Forget the persistent devices, returns the names of the ones created by this process.
+pub fn take_persistent_devices() -> Vec<String> {
+    #[cfg(target_os = "linux")]
+    {
+        PERSISTENT_DEVICES
+            .lock()
+            .unwrap()
+            .drain()
+            .filter_map(|(_, (name, created))| created.then_some(name))
+            .collect()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    Vec::new()
+}
+
+// utun and BSD tun devices prepend every packet with a 4-byte address family in network byte order.
+#[cfg(any(target_os = "macos", target_os = "freebsd", target_os = "openbsd", test))]
+mod af_header {
+    use std::io;
+
+    // address family numbers differ between the systems, the target ones are used
+    pub const AF_INET: u32 = libc::AF_INET as u32;
+    pub const AF_INET6: u32 = libc::AF_INET6 as u32;
+    const HEADER_LEN: usize = 4;
+
+    pub fn add_header(packet: Vec<u8>) -> Vec<u8> {
+        let family = match packet.first().map(|b| b >> 4) {
+            Some(6) => AF_INET6,
+            _ => AF_INET,
+        };
+
+        let mut data = Vec::with_capacity(packet.len() + HEADER_LEN);
+        data.extend_from_slice(&family.to_be_bytes());
+        data.extend_from_slice(&packet);
+        data
+    }
+
+    pub fn strip_header(mut data: Vec<u8>) -> io::Result<Vec<u8>> {
+        if data.len() < HEADER_LEN {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Short tun packet"));
+        }
+        data.drain(0..HEADER_LEN);
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_af_header() {
+        let v4 = vec![0x45, 0, 0, 20];
+        let data = af_header::add_header(v4.clone());
+        assert_eq!(&data[..4], &af_header::AF_INET.to_be_bytes());
+        assert_eq!(af_header::strip_header(data).unwrap(), v4);
+
+        let v6 = vec![0x60, 0, 0, 0];
+        let data = af_header::add_header(v6.clone());
+        assert_eq!(&data[..4], &af_header::AF_INET6.to_be_bytes());
+        assert_eq!(af_header::strip_header(data).unwrap(), v6);
+
+        assert!(af_header::strip_header(vec![0, 0]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_device_access() {
+        assert_eq!(resolve_user("1000").unwrap(), 1000);
+        assert_eq!(resolve_user("root").unwrap(), 0);
+        assert_eq!(resolve_group("1000").unwrap(), 1000);
+        assert!(resolve_user("no-such-user-snx-rs").is_err());
+        assert!(resolve_group("no-such-group-snx-rs").is_err());
+
+        let params = TunnelParams {
+            tun_owner: Some("1000".to_owned()),
+            tun_persist: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            DeviceAccess::new(&params).unwrap(),
+            DeviceAccess {
+                owner: Some(1000),
+                group: None,
+                persist: true,
+            }
+        );
+        assert_eq!(
+            DeviceAccess::new(&TunnelParams::default()).unwrap(),
+            DeviceAccess::default()
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_persistent_device_reuse() {
+        const TEMPLATE: &str = "snxreuse%d";
+
+        let (name, existing) = select_persistent_device(TEMPLATE, |name| name == "snxreuse0").unwrap();
+        assert_eq!((name.as_str(), existing), ("snxreuse1", false));
+        register_persistent_device(TEMPLATE, &name, !existing);
+
+        // the reconnects attach to the created device, the template is not expanded to a free name again
+        let exists = |name: &str| name == "snxreuse0" || name == "snxreuse1";
+        for _ in 0..2 {
+            let (name, existing) = select_persistent_device(TEMPLATE, exists).unwrap();
+            assert_eq!((name.as_str(), existing), ("snxreuse1", true));
+            register_persistent_device(TEMPLATE, &name, !existing);
+        }
 
-    pub fn take_inner(&mut self) -> Option<tun::AsyncDevice> {
-        self.inner.take()
+        // deleted on disconnect as it was created by this process
+        assert!(take_persistent_devices().contains(&name));
+        assert!(persistent_device_name(TEMPLATE).is_none());
     }
 }
@@ -0,0 +1,71 @@
+//! In-memory packet device for the data path tests, the packets are exchanged with a handle
+//! instead of the kernel.
+
+use std::{io, net::Ipv4Addr};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use futures::{
+    SinkExt, StreamExt,
+    channel::mpsc::{self, Receiver, Sender},
+};
+
+use super::{PacketDevice, PacketSink, PacketStream};
+
+pub struct LoopbackDevice {
+    name: String,
+    mtu: u16,
+    channels: Option<(Sender<Vec<u8>>, Receiver<Vec<u8>>)>,
+}
+
+/// The other end of the device: packets sent here are read from the device, packets written to the device
+/// are received here.
+pub struct LoopbackHandle {
+    pub sender: Sender<Vec<u8>>,
+    pub receiver: Receiver<Vec<u8>>,
+}
+
+impl LoopbackDevice {
+    /// The capacity bounds the packets which are queued in each direction.
+    pub fn new(name: &str, mtu: u16, capacity: usize) -> (Self, LoopbackHandle) {
+        let (device_sender, handle_receiver) = mpsc::channel(capacity);
+        let (handle_sender, device_receiver) = mpsc::channel(capacity);
+
+        let device = Self {
+            name: name.to_owned(),
+            mtu,
+            channels: Some((device_sender, device_receiver)),
+        };
+
+        let handle = LoopbackHandle {
+            sender: handle_sender,
+            receiver: handle_receiver,
+        };
+
+        (device, handle)
+    }
+}
+
+#[async_trait]
+impl PacketDevice for LoopbackDevice {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn mtu(&self) -> u16 {
+        self.mtu
+    }
+
+    async fn set_peer(&mut self, _ip_address: Ipv4Addr, _peer: Ipv4Addr) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn split(&mut self) -> anyhow::Result<(PacketSink, PacketStream)> {
+        let (sender, receiver) = self.channels.take().context("No loopback device")?;
+
+        let sink = sender.sink_map_err(|e| io::Error::new(io::ErrorKind::BrokenPipe, e));
+        let stream = receiver.map(Ok::<_, io::Error>);
+
+        Ok((Box::pin(sink), Box::pin(stream)))
+    }
+}
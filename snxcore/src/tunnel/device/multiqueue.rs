@@ -0,0 +1,108 @@
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    os::{
+        fd::{AsRawFd, RawFd},
+        unix::fs::OpenOptionsExt,
+    },
+    sync::Arc,
+};
+
+use anyhow::Context;
+use tokio::{io::unix::AsyncFd, sync::mpsc};
+
+use super::{PacketSink, PacketStream, read_packet, write_packet};
+
+const TUN_CLONE_DEVICE: &str = "/dev/net/tun";
+
+// _IOW('T', 202, int) from linux/if_tun.h
+const TUNSETIFF: libc::c_ulong = 0x4004_54ca;
+
+const CHANNEL_SIZE: usize = 1024;
+
+/// Queues of a tun device opened with IFF_MULTI_QUEUE, the kernel spreads the outgoing flows across them.
+pub struct MultiQueue {
+    queues: Vec<File>,
+}
+
+impl MultiQueue {
+    /// Create the device with the first queue and attach the others to it.
+    /// Kernels without multi-queue support reject the flag with EINVAL.
+    pub fn open(name: &str, count: u16) -> io::Result<Self> {
+        let queues = (0..count).map(|_| open_queue(name)).collect::<io::Result<Vec<_>>>()?;
+        Ok(Self { queues })
+    }
+
+    /// Single queue opened by someone else, e.g. a privileged helper which passed the descriptor.
+    pub fn from_file(file: File) -> Self {
+        Self { queues: vec![file] }
+    }
+
+    /// The device-wide ioctls can be issued on any queue.
+    pub fn as_raw_fd(&self) -> RawFd {
+        self.queues[0].as_raw_fd()
+    }
+
+    /// Every queue is read by its own task so that the reads scale across cores, the packets of all queues
+    /// end up in one stream. The replies are written to the first queue.
+    pub fn split(self) -> anyhow::Result<(PacketSink, PacketStream)> {
+        let queues = self
+            .queues
+            .into_iter()
+            .map(|file| AsyncFd::new(file).map(Arc::new))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let (sender, receiver) = mpsc::channel(CHANNEL_SIZE);
+        for fd in &queues {
+            tokio::spawn(read_queue(fd.clone(), sender.clone()));
+        }
+
+        let writer = queues.into_iter().next().context("No tun queues")?;
+
+        let sink = futures::sink::unfold(writer, |fd, packet: Vec<u8>| async move {
+            write_packet(&fd, &packet).await?;
+            Ok::<_, io::Error>(fd)
+        });
+
+        let stream = futures::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|result| (result, receiver))
+        });
+
+        Ok((Box::pin(sink), Box::pin(stream)))
+    }
+}
+
+fn open_queue(name: &str) -> io::Result<File> {
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(TUN_CLONE_DEVICE)?;
+
+    let mut ifr: libc::ifreq = unsafe { std::mem::zeroed() };
+    for (dst, src) in ifr.ifr_name.iter_mut().zip(name.bytes()) {
+        *dst = src as libc::c_char;
+    }
+    ifr.ifr_ifru.ifru_flags = (libc::IFF_TUN | libc::IFF_NO_PI | libc::IFF_MULTI_QUEUE) as libc::c_short;
+
+    if unsafe { libc::ioctl(file.as_raw_fd(), TUNSETIFF, &ifr) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(file)
+}
+
+// The task ends when the tunnel drops the stream or when the queue fails.
+async fn read_queue(fd: Arc<AsyncFd<File>>, sender: mpsc::Sender<io::Result<Vec<u8>>>) {
+    loop {
+        let result = tokio::select! {
+            result = read_packet(&fd) => result,
+            _ = sender.closed() => break,
+        };
+
+        let failed = result.is_err();
+        if sender.send(result).await.is_err() || failed {
+            break;
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    net::Ipv4Addr,
+    os::unix::fs::OpenOptionsExt,
+    process::Command,
+    sync::Arc,
+};
+
+use anyhow::Context;
+use tokio::io::unix::AsyncFd;
+use tracing::{debug, warn};
+
+use super::{DeviceAccess, PacketSink, PacketStream, af_header, device_exists, read_packet, write_packet};
+
+// tun(4) devices are cloned on open, the name must follow the tunN pattern
+const MAX_DEVICE_INDEX: u32 = 256;
+
+/// OpenBSD tun device, it is destroyed when the file descriptor is closed.
+pub struct TunDevice {
+    inner: Option<File>,
+    dev_name: String,
+    mtu: u16,
+}
+
+impl TunDevice {
+    pub async fn new(
+        name: &str,
+        ip_address: Ipv4Addr,
+        netmask: Option<Ipv4Addr>,
+        mtu: u16,
+        persistent: bool,
+        queues: u16,
+    ) -> anyhow::Result<Self> {
+        if persistent || queues > 1 {
+            warn!("Persistent and multi-queue tun devices are only supported on Linux");
+        }
+
+        debug!("Ignoring tun device name on OpenBSD: {name}");
+
+        let dev_name = (0..MAX_DEVICE_INDEX)
+            .map(|index| format!("tun{index}"))
+            .find(|name| !device_exists(name))
+            .context(i18n::tr!("error-no-free-device-name", name = "tun%d"))?;
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(format!("/dev/{dev_name}"))?;
+
+        let address = ip_address.to_string();
+        let netmask = netmask.unwrap_or(Ipv4Addr::BROADCAST).to_string();
+        let mtu_value = mtu.to_string();
+
+        let status = Command::new("ifconfig")
+            .args([
+                &dev_name, "inet", &address, &address, "netmask", &netmask, "mtu", &mtu_value, "up",
+            ])
+            .status()?;
+        anyhow::ensure!(status.success(), "ifconfig exited with {}", status);
+
+        debug!("Created tun device: {dev_name}");
+
+        Ok(Self {
+            inner: Some(file),
+            dev_name,
+            mtu,
+        })
+    }
+
+    pub async fn from_fd(
+        _fd: i32,
+        _ip_address: Ipv4Addr,
+        _netmask: Option<Ipv4Addr>,
+        _mtu: u16,
+        _configure: bool,
+    ) -> anyhow::Result<Self> {
+        anyhow::bail!("Passing a tun file descriptor is only supported on Linux")
+    }
+
+    pub fn name(&self) -> &str {
+        &self.dev_name
+    }
+
+    pub fn mtu(&self) -> u16 {
+        self.mtu
+    }
+
+    pub fn set_access(&mut self, _access: &DeviceAccess) -> anyhow::Result<()> {
+        anyhow::bail!("The tun device owner, group and persistence are only supported on Linux")
+    }
+
+    /// Set the gateway peer as the destination address instead of the local one.
+    pub async fn set_peer(&mut self, ip_address: Ipv4Addr, peer: Ipv4Addr) -> anyhow::Result<()> {
+        let status = Command::new("ifconfig")
+            .args([&self.dev_name, "inet", &ip_address.to_string(), &peer.to_string()])
+            .status()?;
+        anyhow::ensure!(status.success(), "ifconfig exited with {}", status);
+        Ok(())
+    }
+
+    pub fn is_persistent(&self) -> bool {
+        false
+    }
+
+    /// Split the device into a sink and a stream of plain IP packets.
+    pub fn split(&mut self) -> anyhow::Result<(PacketSink, PacketStream)> {
+        let fd = Arc::new(AsyncFd::new(self.inner.take().context("No tun device")?)?);
+
+        let stream = futures::stream::unfold(fd.clone(), |fd| async move {
+            let result = read_packet(&fd).await.and_then(af_header::strip_header);
+            Some((result, fd))
+        });
+
+        let sink = futures::sink::unfold(fd, |fd, packet: Vec<u8>| async move {
+            write_packet(&fd, &af_header::add_header(packet)).await?;
+            Ok::<_, io::Error>(fd)
+        });
+
+        Ok((Box::pin(sink), Box::pin(stream)))
+    }
+}
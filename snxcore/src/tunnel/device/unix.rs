@@ -0,0 +1,448 @@
+use std::net::Ipv4Addr;
+
+use anyhow::Context;
+use futures::StreamExt;
+use i18n::tr;
+use tracing::{debug, warn};
+use tun::AbstractDevice;
+
+#[cfg(any(target_os = "macos", target_os = "freebsd"))]
+use super::af_header;
+#[cfg(target_os = "linux")]
+use super::keep_persistent_device;
+#[cfg(target_os = "linux")]
+use super::multiqueue::MultiQueue;
+use super::{DeviceAccess, PacketSink, PacketStream, device_exists, resolve_device_name};
+#[cfg(target_os = "linux")]
+use crate::platform::netlink;
+
+enum Inner {
+    Single(tun::AsyncDevice),
+    #[cfg(target_os = "linux")]
+    Multi(MultiQueue),
+}
+
+pub struct TunDevice {
+    inner: Option<Inner>,
+    dev_name: String,
+    mtu: u16,
+    persistent: bool,
+}
+
+impl TunDevice {
+    pub async fn new(
+        name: &str,
+        ip_address: Ipv4Addr,
+        netmask: Option<Ipv4Addr>,
+        mtu: u16,
+        persistent: bool,
+        queues: u16,
+    ) -> anyhow::Result<Self> {
+        #[cfg(target_os = "linux")]
+        if persistent {
+            if queues > 1 {
+                warn!("Multi-queue mode is not supported for persistent tun devices, using a single queue");
+            }
+            return Self::new_persistent(name, ip_address, netmask, mtu);
+        }
+
+        #[cfg(target_os = "linux")]
+        if queues > 1 {
+            match Self::new_multi_queue(name, ip_address, netmask, mtu, queues).await {
+                Ok(device) => return Ok(device),
+                Err(e) => warn!("Unable to create multi-queue tun device, using a single queue: {}", e),
+            }
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        if persistent || queues > 1 {
+            warn!("Persistent and multi-queue tun devices are only supported on Linux");
+        }
+
+        let mut config = tun::Configuration::default();
+
+        config.address(ip_address).mtu(mtu).up();
+
+        if cfg!(target_os = "macos") {
+            debug!("Ignoring tun device name on macOS: {name}");
+            // utun is a point-to-point interface which needs a destination address
+            config.destination(ip_address);
+        } else {
+            let name = resolve_device_name(name)?;
+            anyhow::ensure!(!device_exists(&name), tr!("error-device-name-in-use", name = name));
+            config.tun_name(name);
+        }
+
+        #[cfg(target_os = "macos")]
+        config.platform_config(|config| {
+            config.packet_information(true);
+        });
+
+        if let Some(netmask) = netmask {
+            config.netmask(netmask);
+        }
+
+        let dev = tun::create_as_async(&config)?;
+
+        // multi-af mode makes FreeBSD prefix every packet with the address family, IPv6 is not passed otherwise
+        #[cfg(target_os = "freebsd")]
+        set_multi_af(&dev)?;
+
+        let dev_name = dev.tun_name()?;
+
+        debug!("Created tun device: {dev_name}");
+
+        Ok(Self {
+            inner: Some(Inner::Single(dev)),
+            dev_name,
+            mtu,
+            persistent: false,
+        })
+    }
+
+    // The tun crate opens a single queue only, the queues are opened directly and configured through netlink.
+    #[cfg(target_os = "linux")]
+    async fn new_multi_queue(
+        name: &str,
+        ip_address: Ipv4Addr,
+        netmask: Option<Ipv4Addr>,
+        mtu: u16,
+        queues: u16,
+    ) -> anyhow::Result<Self> {
+        let name = resolve_device_name(name)?;
+        anyhow::ensure!(!device_exists(&name), tr!("error-device-name-in-use", name = name));
+
+        let dev = MultiQueue::open(&name, queues)?;
+
+        let address = ipnet::Ipv4Net::with_netmask(ip_address, netmask.unwrap_or(Ipv4Addr::BROADCAST))?;
+        let ops = netlink::new_netlink_ops();
+        ops.add_address(&name, address.into()).await?;
+        ops.set_mtu(&name, mtu).await?;
+        ops.set_link_up(&name).await?;
+
+        debug!("Created tun device {name} with {queues} queues");
+
+        Ok(Self {
+            inner: Some(Inner::Multi(dev)),
+            dev_name: name,
+            mtu,
+            persistent: false,
+        })
+    }
+
+    // The device is created once with the persist flag, later tunnels attach to it by name
+    // and only change the address if the gateway assigned a different one.
+    #[cfg(target_os = "linux")]
+    fn new_persistent(
+        template: &str,
+        ip_address: Ipv4Addr,
+        netmask: Option<Ipv4Addr>,
+        mtu: u16,
+    ) -> anyhow::Result<Self> {
+        let (name, existing) = super::select_persistent_device(template, device_exists)?;
+
+        let mut config = tun::Configuration::default();
+        config.tun_name(&name).mtu(mtu).up();
+
+        if !existing {
+            config.address(ip_address);
+            if let Some(netmask) = netmask {
+                config.netmask(netmask);
+            }
+        }
+
+        let mut dev = tun::create_as_async(&config)?;
+
+        if existing {
+            update_address(&mut dev, ip_address, netmask)?;
+            debug!("Attached to persistent tun device: {name}");
+        } else {
+            set_persist(&dev)?;
+            debug!("Created persistent tun device: {name}");
+        }
+
+        super::register_persistent_device(template, &name, !existing);
+
+        Ok(Self {
+            inner: Some(Inner::Single(dev)),
+            dev_name: name,
+            mtu,
+            persistent: true,
+        })
+    }
+
+    /// Use a tun device opened by a privileged helper. The descriptor is duplicated, so that the
+    /// reconnected tunnels can use it again. The addresses are configured only when requested.
+    #[cfg(target_os = "linux")]
+    pub async fn from_fd(
+        fd: i32,
+        ip_address: Ipv4Addr,
+        netmask: Option<Ipv4Addr>,
+        mtu: u16,
+        configure: bool,
+    ) -> anyhow::Result<Self> {
+        use std::os::fd::{AsRawFd, BorrowedFd};
+
+        let fd = unsafe { BorrowedFd::borrow_raw(fd) }.try_clone_to_owned()?;
+        let name = tun_fd_name(&fd).map_err(|e| anyhow::anyhow!(tr!("error-invalid-tun-fd", message = e)))?;
+
+        let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL) };
+        if flags < 0 || unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFL, flags | libc::O_NONBLOCK) } < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        if configure {
+            let address = ipnet::Ipv4Net::with_netmask(ip_address, netmask.unwrap_or(Ipv4Addr::BROADCAST))?;
+            let ops = netlink::new_netlink_ops();
+            ops.replace_ipv4_address(&name, address, None).await?;
+            ops.set_mtu(&name, mtu).await?;
+            ops.set_link_up(&name).await?;
+        }
+
+        debug!("Using tun device {name} from the passed descriptor");
+
+        Ok(Self {
+            inner: Some(Inner::Multi(MultiQueue::from_file(std::fs::File::from(fd)))),
+            dev_name: name,
+            mtu,
+            persistent: true,
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub async fn from_fd(
+        _fd: i32,
+        _ip_address: Ipv4Addr,
+        _netmask: Option<Ipv4Addr>,
+        _mtu: u16,
+        _configure: bool,
+    ) -> anyhow::Result<Self> {
+        anyhow::bail!("Passing a tun file descriptor is only supported on Linux")
+    }
+
+    pub fn name(&self) -> &str {
+        &self.dev_name
+    }
+
+    pub fn mtu(&self) -> u16 {
+        self.mtu
+    }
+
+    /// Replace the local address with a point-to-point one, the netmask is not used then.
+    #[cfg(target_os = "linux")]
+    pub async fn set_peer(&mut self, ip_address: Ipv4Addr, peer: Ipv4Addr) -> anyhow::Result<()> {
+        set_peer_address(netlink::new_netlink_ops().as_ref(), &self.dev_name, ip_address, peer).await
+    }
+
+    /// Apply the owner, group and persistence flags, the device must be owned by the calling process.
+    #[cfg(target_os = "linux")]
+    pub fn set_access(&mut self, access: &DeviceAccess) -> anyhow::Result<()> {
+        use std::os::fd::AsRawFd;
+
+        // _IOW('T', 204, int) and _IOW('T', 206, int) from linux/if_tun.h
+        const TUNSETOWNER: libc::c_ulong = 0x4004_54cc;
+        const TUNSETGROUP: libc::c_ulong = 0x4004_54ce;
+
+        let fd = match self.inner.as_ref().context("No tun device")? {
+            Inner::Single(dev) => dev.as_raw_fd(),
+            Inner::Multi(queues) => queues.as_raw_fd(),
+        };
+
+        if let Some(owner) = access.owner {
+            tun_ioctl(fd, &self.dev_name, "TUNSETOWNER", TUNSETOWNER, owner as libc::c_int)?;
+        }
+        if let Some(group) = access.group {
+            tun_ioctl(fd, &self.dev_name, "TUNSETGROUP", TUNSETGROUP, group as libc::c_int)?;
+        }
+        if access.persist {
+            tun_ioctl(fd, &self.dev_name, "TUNSETPERSIST", TUNSETPERSIST, 1)?;
+            keep_persistent_device(&self.dev_name);
+            self.persistent = true;
+        }
+
+        debug!("Applied {:?} to tun device {}", access, self.dev_name);
+
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn set_access(&mut self, _access: &DeviceAccess) -> anyhow::Result<()> {
+        anyhow::bail!("The tun device owner, group and persistence are only supported on Linux")
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub async fn set_peer(&mut self, _ip_address: Ipv4Addr, _peer: Ipv4Addr) -> anyhow::Result<()> {
+        anyhow::bail!("Point-to-point configuration is only supported on Linux and OpenBSD")
+    }
+
+    /// Persistent and externally provided devices are not deleted when the tunnel is torn down.
+    pub fn is_persistent(&self) -> bool {
+        self.persistent
+    }
+
+    /// Split the device into a sink and a stream of plain IP packets.
+    pub fn split(&mut self) -> anyhow::Result<(PacketSink, PacketStream)> {
+        match self.inner.take().context("No tun device")? {
+            Inner::Single(dev) => split_single(dev),
+            #[cfg(target_os = "linux")]
+            Inner::Multi(queues) => queues.split(),
+        }
+    }
+}
+
+fn split_single(dev: tun::AsyncDevice) -> anyhow::Result<(PacketSink, PacketStream)> {
+    let (sink, stream) = dev.into_framed().split();
+
+    #[cfg(any(target_os = "macos", target_os = "freebsd"))]
+    let (sink, stream) = (
+        futures::SinkExt::with(sink, |packet| {
+            futures::future::ready(Ok::<_, std::io::Error>(af_header::add_header(packet)))
+        }),
+        stream.map(|data| data.and_then(af_header::strip_header)),
+    );
+
+    Ok((Box::pin(sink), Box::pin(stream)))
+}
+
+#[cfg(target_os = "freebsd")]
+fn set_multi_af(dev: &tun::AsyncDevice) -> anyhow::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    // _IOW('t', 96, int) from net/if_tun.h
+    const TUNSIFHEAD: libc::c_ulong = 0x8004_7460;
+
+    let enable: libc::c_int = 1;
+    let rc = unsafe { libc::ioctl(dev.as_raw_fd(), TUNSIFHEAD, &enable) };
+    if rc != 0 {
+        anyhow::bail!("TUNSIFHEAD failed: {}", std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+// The passed descriptor must be a tun device without the packet information header.
+#[cfg(target_os = "linux")]
+fn tun_fd_name(fd: &std::os::fd::OwnedFd) -> Result<String, String> {
+    use std::os::fd::AsRawFd;
+
+    // _IOR('T', 210, unsigned int) from linux/if_tun.h
+    const TUNGETIFF: libc::c_ulong = 0x8004_54d2;
+
+    let mut ifr: libc::ifreq = unsafe { std::mem::zeroed() };
+    if unsafe { libc::ioctl(fd.as_raw_fd(), TUNGETIFF, &mut ifr) } < 0 {
+        return Err(std::io::Error::last_os_error().to_string());
+    }
+
+    let flags = libc::c_int::from(unsafe { ifr.ifr_ifru.ifru_flags });
+    if flags & libc::IFF_TUN == 0 || flags & libc::IFF_NO_PI == 0 {
+        return Err(format!("unexpected device flags {flags:#x}"));
+    }
+
+    let name = ifr
+        .ifr_name
+        .iter()
+        .take_while(|&&c| c != 0)
+        .map(|&c| c as u8 as char)
+        .collect();
+
+    Ok(name)
+}
+
+// The previous address stays in place until the point-to-point one is added, and is kept if that fails.
+#[cfg(target_os = "linux")]
+async fn set_peer_address(
+    ops: &dyn netlink::NetlinkOps,
+    device: &str,
+    ip_address: Ipv4Addr,
+    peer: Ipv4Addr,
+) -> anyhow::Result<()> {
+    ops.replace_ipv4_address(device, ip_address.into(), Some(peer))
+        .await
+        .with_context(|| format!("Unable to set address {ip_address} with peer {peer} on {device}"))
+}
+
+// _IOW('T', 203, int) from linux/if_tun.h
+#[cfg(target_os = "linux")]
+const TUNSETPERSIST: libc::c_ulong = 0x4004_54cb;
+
+#[cfg(target_os = "linux")]
+fn set_persist(dev: &tun::AsyncDevice) -> anyhow::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    tun_ioctl(dev.as_raw_fd(), &dev.tun_name()?, "TUNSETPERSIST", TUNSETPERSIST, 1)
+}
+
+// The kernel requires CAP_NET_ADMIN for the ownership and persistence requests.
+#[cfg(target_os = "linux")]
+fn tun_ioctl(
+    fd: std::os::fd::RawFd,
+    name: &str,
+    request_name: &str,
+    request: libc::c_ulong,
+    value: libc::c_int,
+) -> anyhow::Result<()> {
+    if unsafe { libc::ioctl(fd, request, value) } == 0 {
+        return Ok(());
+    }
+
+    let error = std::io::Error::last_os_error();
+    if error.kind() == std::io::ErrorKind::PermissionDenied {
+        anyhow::bail!(tr!("error-tun-ioctl-permission", request = request_name, name = name))
+    } else {
+        anyhow::bail!(tr!(
+            "error-tun-ioctl",
+            request = request_name,
+            name = name,
+            message = error.to_string()
+        ))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn update_address(dev: &mut tun::AsyncDevice, ip_address: Ipv4Addr, netmask: Option<Ipv4Addr>) -> anyhow::Result<()> {
+    let netmask = netmask.unwrap_or(Ipv4Addr::BROADCAST);
+    let current = (dev.address().ok(), dev.netmask().ok());
+
+    if current != (Some(ip_address.into()), Some(netmask.into())) {
+        debug!(
+            "Changing address of {} from {:?} to {}/{}",
+            dev.tun_name()?,
+            current.0,
+            ip_address,
+            netmask
+        );
+        dev.set_network_address(ip_address, netmask, None)?;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::platform::netlink::mock::MockNetlinkOps;
+
+    #[tokio::test]
+    async fn test_set_peer_address() {
+        let ops = MockNetlinkOps::default();
+        set_peer_address(&ops, "snx-tun", Ipv4Addr::new(10, 0, 0, 10), Ipv4Addr::new(10, 0, 0, 1))
+            .await
+            .unwrap();
+
+        // a single replace, the device is never left without an address
+        assert_eq!(
+            *ops.calls.lock().unwrap(),
+            ["addr replace 10.0.0.10 peer 10.0.0.1/32 dev snx-tun"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_peer_address_failed() {
+        let ops = MockNetlinkOps {
+            fail_replace: true,
+            ..Default::default()
+        };
+        let error = set_peer_address(&ops, "snx-tun", Ipv4Addr::new(10, 0, 0, 10), Ipv4Addr::new(10, 0, 0, 1))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("snx-tun"));
+    }
+}
@@ -0,0 +1,146 @@
+use std::{io, net::Ipv4Addr, sync::Arc, thread};
+
+use anyhow::Context;
+use futures::{SinkExt, StreamExt, channel::mpsc, executor::block_on};
+use i18n::tr;
+use ipnet::Ipv4Net;
+use tracing::{debug, warn};
+
+use super::{DeviceAccess, PacketSink, PacketStream, device_exists, resolve_device_name};
+use crate::platform::iphelper;
+
+const TUNNEL_TYPE: &str = "snx-rs";
+const CHANNEL_SIZE: usize = 1024;
+
+/// Wintun adapter, it is removed from the system when the device is dropped.
+pub struct TunDevice {
+    session: Arc<wintun::Session>,
+    _adapter: Arc<wintun::Adapter>,
+    dev_name: String,
+    mtu: u16,
+}
+
+impl TunDevice {
+    pub async fn new(
+        name: &str,
+        ip_address: Ipv4Addr,
+        netmask: Option<Ipv4Addr>,
+        mtu: u16,
+        persistent: bool,
+        queues: u16,
+    ) -> anyhow::Result<Self> {
+        if persistent || queues > 1 {
+            warn!("Persistent and multi-queue tun devices are only supported on Linux");
+        }
+
+        let name = resolve_device_name(name)?;
+        anyhow::ensure!(!device_exists(&name), tr!("error-device-name-in-use", name = name));
+
+        let wintun = unsafe { wintun::load() }.context("Unable to load wintun.dll")?;
+        let adapter = wintun::Adapter::create(&wintun, &name, TUNNEL_TYPE, None)?;
+
+        let address = match netmask {
+            Some(netmask) => Ipv4Net::with_netmask(ip_address, netmask)?,
+            None => Ipv4Net::from(ip_address),
+        };
+
+        let luid = iphelper::interface_luid(&name)?;
+        iphelper::add_address(&luid, address.into())?;
+        iphelper::set_mtu(&luid, mtu)?;
+
+        let session = Arc::new(adapter.start_session(wintun::MAX_RING_CAPACITY)?);
+
+        debug!("Created wintun adapter: {name}");
+
+        Ok(Self {
+            session,
+            _adapter: adapter,
+            dev_name: name,
+            mtu,
+        })
+    }
+
+    pub async fn from_fd(
+        _fd: i32,
+        _ip_address: Ipv4Addr,
+        _netmask: Option<Ipv4Addr>,
+        _mtu: u16,
+        _configure: bool,
+    ) -> anyhow::Result<Self> {
+        anyhow::bail!("Passing a tun file descriptor is only supported on Linux")
+    }
+
+    pub fn name(&self) -> &str {
+        &self.dev_name
+    }
+
+    pub fn mtu(&self) -> u16 {
+        self.mtu
+    }
+
+    pub fn set_access(&mut self, _access: &DeviceAccess) -> anyhow::Result<()> {
+        anyhow::bail!("The tun device owner, group and persistence are only supported on Linux")
+    }
+
+    pub async fn set_peer(&mut self, _ip_address: Ipv4Addr, _peer: Ipv4Addr) -> anyhow::Result<()> {
+        anyhow::bail!("Point-to-point configuration is not supported by Wintun adapters")
+    }
+
+    pub fn is_persistent(&self) -> bool {
+        false
+    }
+
+    /// Split the device into a sink and a stream of plain IP packets.
+    /// Wintun packets carry no header, the blocking session is served by two threads.
+    pub fn split(&mut self) -> anyhow::Result<(PacketSink, PacketStream)> {
+        let (mut inbound_tx, inbound_rx) = mpsc::channel::<io::Result<Vec<u8>>>(CHANNEL_SIZE);
+        let reader = self.session.clone();
+
+        thread::spawn(move || {
+            loop {
+                match reader.receive_blocking() {
+                    Ok(packet) => {
+                        if block_on(inbound_tx.send(Ok(packet.bytes().to_vec()))).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        debug!("Wintun session closed: {}", e);
+                        break;
+                    }
+                }
+            }
+        });
+
+        let (outbound_tx, mut outbound_rx) = mpsc::channel::<Vec<u8>>(CHANNEL_SIZE);
+        let writer = self.session.clone();
+
+        thread::spawn(move || {
+            while let Some(data) = block_on(outbound_rx.next()) {
+                let Ok(size) = u16::try_from(data.len()) else {
+                    warn!(bytes = data.len(), "Dropping oversized packet");
+                    continue;
+                };
+
+                match writer.allocate_send_packet(size) {
+                    Ok(mut packet) => {
+                        packet.bytes_mut().copy_from_slice(&data);
+                        writer.send_packet(packet);
+                    }
+                    Err(e) => warn!("Unable to allocate wintun packet: {}", e),
+                }
+            }
+        });
+
+        let sink = outbound_tx.sink_map_err(|e| io::Error::new(io::ErrorKind::BrokenPipe, e));
+
+        Ok((Box::pin(sink), Box::pin(inbound_rx)))
+    }
+}
+
+impl Drop for TunDevice {
+    fn drop(&mut self) {
+        debug!("Shutting down wintun adapter: {}", self.dev_name);
+        let _ = self.session.shutdown();
+    }
+}
@@ -0,0 +1,121 @@
+//! Post-connect DNS self-test: the probe name is resolved by the system resolver and by every tunnel DNS server
+//! directly, the system answers must come from the tunnel servers, otherwise the queries leak outside the tunnel.
+
+use std::{
+    collections::HashSet,
+    net::{IpAddr, Ipv4Addr},
+    sync::Arc,
+};
+
+use tokio::sync::mpsc;
+use tracing::{Instrument, debug, info, warn};
+
+use crate::{
+    model::{DnsCheckResult, params::TunnelParams},
+    tunnel::{TunnelEvent, dns_client},
+};
+
+const SYSTEM_RESOLVER: &str = "system";
+
+/// Run the self-test in the background when a probe name is configured, the results are sent as an event.
+pub fn spawn(params: Arc<TunnelParams>, dns_servers: Vec<IpAddr>, event_sender: mpsc::Sender<TunnelEvent>) {
+    let Some(name) = params.dns_probe_name.clone() else {
+        return;
+    };
+
+    if params.no_dns || dns_servers.is_empty() {
+        debug!("DNS is not configured for the tunnel, skipping the DNS self-test");
+        return;
+    }
+
+    tokio::spawn(
+        async move {
+            let results = run(&name, &dns_servers).await;
+            let _ = event_sender.send(TunnelEvent::DnsChecked(results)).await;
+        }
+        .in_current_span(),
+    );
+}
+
+async fn run(name: &str, dns_servers: &[IpAddr]) -> Vec<DnsCheckResult> {
+    let mut results = Vec::new();
+    let mut tunnel_answers = HashSet::new();
+
+    for server in dns_servers {
+        let passed = match dns_client::query(*server, name).await {
+            Ok(answers) if !answers.is_empty() => {
+                debug!("DNS server {} resolved {} to {:?}", server, name, answers);
+                tunnel_answers.extend(answers);
+                true
+            }
+            Ok(_) => {
+                warn!("DNS server {} has no address for {}", server, name);
+                false
+            }
+            Err(e) => {
+                warn!("DNS server {} failed to resolve {}: {}", server, name, e);
+                false
+            }
+        };
+        results.push(DnsCheckResult {
+            resolver: server.to_string(),
+            passed,
+        });
+    }
+
+    let system_answers = match tokio::net::lookup_host((name, 0)).await {
+        Ok(addrs) => addrs
+            .filter_map(|addr| match addr.ip() {
+                IpAddr::V4(v4) => Some(v4),
+                IpAddr::V6(_) => None,
+            })
+            .collect::<HashSet<_>>(),
+        Err(e) => {
+            warn!("System resolver failed to resolve {}: {}", name, e);
+            HashSet::new()
+        }
+    };
+    debug!("System resolver resolved {} to {:?}", name, system_answers);
+
+    let passed = is_answered_by_tunnel(&system_answers, &tunnel_answers);
+    results.push(DnsCheckResult {
+        resolver: SYSTEM_RESOLVER.to_owned(),
+        passed,
+    });
+
+    if passed {
+        info!("DNS self-test passed, {} is resolved through the tunnel", name);
+    } else {
+        warn!(
+            "DNS self-test FAILED: {} is not resolved through the tunnel DNS servers, queries may leak to another resolver",
+            name
+        );
+    }
+
+    results
+}
+
+// Round-robin records may give different subsets of the addresses to each query.
+fn is_answered_by_tunnel(system: &HashSet<Ipv4Addr>, tunnel: &HashSet<Ipv4Addr>) -> bool {
+    !system.is_empty() && system.iter().any(|addr| tunnel.contains(addr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answered_by_tunnel() {
+        let tunnel = HashSet::from([Ipv4Addr::new(10, 0, 0, 5), Ipv4Addr::new(10, 0, 0, 6)]);
+
+        assert!(is_answered_by_tunnel(
+            &HashSet::from([Ipv4Addr::new(10, 0, 0, 6)]),
+            &tunnel
+        ));
+        assert!(!is_answered_by_tunnel(
+            &HashSet::from([Ipv4Addr::new(1, 2, 3, 4)]),
+            &tunnel
+        ));
+        assert!(!is_answered_by_tunnel(&HashSet::new(), &tunnel));
+    }
+}
@@ -0,0 +1,182 @@
+//! Minimal DNS client which queries a given server directly, bypassing the system resolver.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+use anyhow::{Context, anyhow};
+use tokio::net::UdpSocket;
+
+const DNS_PORT: u16 = 53;
+const QUERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+const TYPE_A: u16 = 1;
+const CLASS_IN: u16 = 1;
+
+/// Query the A records of the name from the server, an unknown name gives no addresses.
+pub async fn query(server: IpAddr, name: &str) -> anyhow::Result<Vec<Ipv4Addr>> {
+    let bind_addr: SocketAddr = match server {
+        IpAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        IpAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(bind_addr).await?;
+    socket.connect((server, DNS_PORT)).await?;
+
+    let id = rand::random::<u16>();
+    socket.send(&build_query(id, name)?).await?;
+
+    let mut buf = [0u8; 1500];
+    let size = tokio::time::timeout(QUERY_TIMEOUT, socket.recv(&mut buf))
+        .await
+        .context("Timeout")??;
+
+    parse_response(id, &buf[..size])
+}
+
+fn build_query(id: u16, name: &str) -> anyhow::Result<Vec<u8>> {
+    let mut query = Vec::with_capacity(512);
+    query.extend(id.to_be_bytes());
+    // recursion desired, one question
+    query.extend([0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+
+    for label in name.trim_end_matches('.').split('.') {
+        anyhow::ensure!(!label.is_empty() && label.len() < 64, "Invalid DNS name: {}", name);
+        query.push(label.len() as u8);
+        query.extend(label.as_bytes());
+    }
+    query.push(0);
+
+    query.extend(TYPE_A.to_be_bytes());
+    query.extend(CLASS_IN.to_be_bytes());
+
+    Ok(query)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        let end = self.pos + len;
+        let data = self
+            .data
+            .get(self.pos..end)
+            .ok_or_else(|| anyhow!("Truncated DNS response"))?;
+        self.pos = end;
+        Ok(data)
+    }
+
+    fn u16(&mut self) -> anyhow::Result<u16> {
+        let data = self.take(2)?;
+        Ok(u16::from_be_bytes([data[0], data[1]]))
+    }
+
+    // Compressed names end with a two-byte pointer.
+    fn skip_name(&mut self) -> anyhow::Result<()> {
+        loop {
+            let len = self.take(1)?[0];
+            match len {
+                0 => return Ok(()),
+                len if len & 0xc0 == 0xc0 => {
+                    self.take(1)?;
+                    return Ok(());
+                }
+                len => {
+                    self.take(len.into())?;
+                }
+            }
+        }
+    }
+}
+
+fn parse_response(id: u16, data: &[u8]) -> anyhow::Result<Vec<Ipv4Addr>> {
+    let mut reader = Reader { data, pos: 0 };
+
+    anyhow::ensure!(reader.u16()? == id, "DNS response ID mismatch");
+
+    let flags = reader.u16()?;
+    match flags & 0x000f {
+        0 => {}
+        // NXDOMAIN
+        3 => return Ok(Vec::new()),
+        rcode => anyhow::bail!("DNS error code {}", rcode),
+    }
+
+    let questions = reader.u16()?;
+    let answers = reader.u16()?;
+    reader.take(4)?;
+
+    for _ in 0..questions {
+        reader.skip_name()?;
+        reader.take(4)?;
+    }
+
+    let mut result = Vec::new();
+    for _ in 0..answers {
+        reader.skip_name()?;
+        let rtype = reader.u16()?;
+        let class = reader.u16()?;
+        reader.take(4)?;
+        let len = reader.u16()?;
+        let rdata = reader.take(len.into())?;
+
+        // CNAME records are followed by the A records of the target
+        if rtype == TYPE_A && class == CLASS_IN && rdata.len() == 4 {
+            result.push(Ipv4Addr::new(rdata[0], rdata[1], rdata[2], rdata[3]));
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(id: u16, rcode: u8, answers: &[[u8; 4]]) -> Vec<u8> {
+        let mut data = build_query(id, "probe.corp.example").unwrap();
+        data[2] = 0x81;
+        data[3] = 0x80 | rcode;
+        data[7] = answers.len() as u8;
+
+        // CNAME first, pointing at the question name
+        data[7] += 1;
+        data.extend([0xc0, 0x0c, 0x00, 0x05, 0x00, 0x01, 0, 0, 0, 60, 0x00, 0x02, 0xc0, 0x0c]);
+
+        for answer in answers {
+            data.extend([0xc0, 0x0c, 0x00, 0x01, 0x00, 0x01, 0, 0, 0, 60, 0x00, 0x04]);
+            data.extend(answer);
+        }
+        data
+    }
+
+    #[test]
+    fn test_build_query() {
+        let query = build_query(0x1234, "probe.corp.").unwrap();
+        assert_eq!(
+            query,
+            [
+                0x12, 0x34, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 5, b'p', b'r', b'o', b'b',
+                b'e', 4, b'c', b'o', b'r', b'p', 0, 0x00, 0x01, 0x00, 0x01
+            ]
+        );
+        assert!(build_query(1, "probe..corp").is_err());
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let data = response(7, 0, &[[10, 0, 0, 5], [10, 0, 0, 6]]);
+        assert_eq!(
+            parse_response(7, &data).unwrap(),
+            vec![Ipv4Addr::new(10, 0, 0, 5), Ipv4Addr::new(10, 0, 0, 6)]
+        );
+
+        assert!(parse_response(8, &data).is_err());
+        assert!(parse_response(7, &data[..data.len() - 2]).is_err());
+        assert!(parse_response(7, &response(7, 3, &[])).unwrap().is_empty());
+        assert!(parse_response(7, &response(7, 2, &[])).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+//! Host routes for the `route-domains` names, resolved through the tunnel DNS servers in the background
+//! so that a slow or failing resolution never blocks the tunnel.
+
+use std::{
+    collections::{HashMap, HashSet},
+    net::{IpAddr, Ipv4Addr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use ipnet::{IpNet, Ipv4Net};
+use tokio::task::JoinHandle;
+use tracing::{Instrument, debug, warn};
+
+use crate::{
+    model::params::TunnelParams,
+    platform::{self, RoutingConfigurator},
+    tunnel::{
+        dns_client,
+        journal::{NetworkChange, NetworkJournal},
+    },
+};
+
+const RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+// Addresses which are no longer returned are kept for a while, DNS load balancers rotate the answers.
+const GRACE_PERIOD: Duration = Duration::from_secs(600);
+
+/// Background resolution task, it is stopped when dropped. The routes stay in the journal
+/// and are removed with the rest of the tunnel configuration.
+pub struct DomainRoutes {
+    handle: JoinHandle<()>,
+}
+
+impl Drop for DomainRoutes {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+pub struct DomainRoutesConfig {
+    pub device: String,
+    pub address: Ipv4Addr,
+    pub dns_servers: Vec<IpAddr>,
+    pub peer: Option<Ipv4Addr>,
+    pub exclusions: Vec<Ipv4Net>,
+}
+
+impl DomainRoutes {
+    /// None when no route domains are configured.
+    pub fn spawn(params: Arc<TunnelParams>, config: DomainRoutesConfig, journal: Arc<NetworkJournal>) -> Option<Self> {
+        if params.route_domains.is_empty() {
+            return None;
+        }
+
+        if config.dns_servers.is_empty() {
+            warn!("No tunnel DNS servers to resolve the route domains");
+            return None;
+        }
+
+        let handle = tokio::spawn(run(params, config, journal).in_current_span());
+
+        Some(Self { handle })
+    }
+}
+
+async fn run(params: Arc<TunnelParams>, config: DomainRoutesConfig, journal: Arc<NetworkJournal>) {
+    let configurator =
+        platform::new_routing_configurator(&config.device, config.address, params.route_metric).with_peer(config.peer);
+    let mut table = DomainRouteTable::default();
+
+    loop {
+        let mut failed = false;
+
+        for name in &params.route_domains {
+            match resolve(&config.dns_servers, name).await {
+                Ok(addresses) => {
+                    debug!("Route domain {} resolved to {:?}", name, addresses);
+                    table.resolved(name, addresses, Instant::now());
+                }
+                Err(e) => {
+                    warn!("Unable to resolve route domain {}: {}", name, e);
+                    failed = true;
+                }
+            }
+        }
+
+        let (added, expired) = table.update(Instant::now());
+
+        let added = added
+            .into_iter()
+            .map(Ipv4Net::from)
+            .filter(|route| !config.exclusions.iter().any(|e| e.contains(route)))
+            .collect::<Vec<_>>();
+
+        if !added.is_empty() {
+            journal.record(NetworkChange::Routes {
+                device: config.device.clone(),
+                address: config.address,
+                routes: added.iter().copied().map(IpNet::V4).collect(),
+                metric: params.route_metric,
+                peer: config.peer,
+            });
+            let _ = configurator.add_routes(&added, &params.ignore_routes).await;
+        }
+
+        if !expired.is_empty() {
+            debug!("Removing expired route domain addresses: {:?}", expired);
+            let routes = expired.into_iter().map(|a| IpNet::V4(a.into())).collect::<Vec<_>>();
+            let _ = configurator.remove_routes(&routes).await;
+        }
+
+        let interval = if failed {
+            RETRY_INTERVAL
+        } else if params.route_domains_refresh.is_zero() {
+            break;
+        } else {
+            params.route_domains_refresh
+        };
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+// The servers are tried in order until one of them answers.
+async fn resolve(servers: &[IpAddr], name: &str) -> anyhow::Result<Vec<Ipv4Addr>> {
+    let mut result = Err(anyhow::anyhow!("No DNS servers"));
+
+    for server in servers {
+        result = dns_client::query(*server, name).await;
+        if result.is_ok() {
+            break;
+        }
+    }
+
+    result
+}
+
+/// Addresses of the route domains with the time they were last returned.
+#[derive(Default)]
+struct DomainRouteTable {
+    last_seen: HashMap<Ipv4Addr, Instant>,
+    names: HashMap<String, Vec<Ipv4Addr>>,
+    installed: HashSet<Ipv4Addr>,
+}
+
+impl DomainRouteTable {
+    fn resolved(&mut self, name: &str, addresses: Vec<Ipv4Addr>, now: Instant) {
+        for address in &addresses {
+            self.last_seen.insert(*address, now);
+        }
+        self.names.insert(name.to_owned(), addresses);
+    }
+
+    // Returns the addresses which need a route and the ones whose route must be removed.
+    fn update(&mut self, now: Instant) -> (Vec<Ipv4Addr>, Vec<Ipv4Addr>) {
+        let current = self.names.values().flatten().copied().collect::<HashSet<_>>();
+
+        let expired = self
+            .installed
+            .iter()
+            .filter(|address| {
+                !current.contains(address)
+                    && self
+                        .last_seen
+                        .get(address)
+                        .is_none_or(|seen| now.duration_since(*seen) >= GRACE_PERIOD)
+            })
+            .copied()
+            .collect::<Vec<_>>();
+
+        for address in &expired {
+            self.installed.remove(address);
+            self.last_seen.remove(address);
+        }
+
+        let added = current
+            .into_iter()
+            .filter(|address| self.installed.insert(*address))
+            .collect::<Vec<_>>();
+
+        (added, expired)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_route_table() {
+        let mut table = DomainRouteTable::default();
+        let a1 = Ipv4Addr::new(10, 0, 0, 1);
+        let a2 = Ipv4Addr::new(10, 0, 0, 2);
+        let now = Instant::now();
+
+        table.resolved("git.corp.example", vec![a1], now);
+        table.resolved("jira.corp.example", vec![a1, a2], now);
+        let (mut added, expired) = table.update(now);
+        added.sort();
+        assert_eq!(added, vec![a1, a2]);
+        assert!(expired.is_empty());
+
+        // nothing new
+        assert_eq!(table.update(now), (vec![], vec![]));
+
+        // a2 is not returned anymore, it is kept during the grace period
+        table.resolved("jira.corp.example", vec![a1], now + Duration::from_secs(60));
+        assert_eq!(table.update(now + Duration::from_secs(60)), (vec![], vec![]));
+        assert_eq!(table.update(now + GRACE_PERIOD), (vec![], vec![a2]));
+
+        // returned again
+        table.resolved("jira.corp.example", vec![a2], now + GRACE_PERIOD);
+        assert_eq!(table.update(now + GRACE_PERIOD), (vec![a2], vec![]));
+    }
+}
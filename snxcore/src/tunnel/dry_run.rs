@@ -0,0 +1,113 @@
+//! Dry run: the tunnel is negotiated with the gateway, but the device, routes, DNS and firewall are left alone.
+//! Every skipped step is logged with the values it would have used.
+
+use ipnet::{Ipv4Net, Ipv6Net};
+use itertools::Itertools;
+use tracing::info;
+
+use crate::{model::params::TunnelParams, platform::ResolverConfig};
+
+pub(crate) struct DryRun<'a> {
+    pub params: &'a TunnelParams,
+    pub device: &'a str,
+    pub address: Ipv4Net,
+    pub ipv6_address: Option<Ipv6Net>,
+    pub routes: &'a [Ipv4Net],
+    pub resolver_config: &'a ResolverConfig,
+}
+
+impl DryRun<'_> {
+    fn steps(&self) -> Vec<String> {
+        let mut steps = vec![format!(
+            "create the device {} with the address {}",
+            self.device, self.address
+        )];
+
+        if let Some(address) = self.ipv6_address {
+            steps.push(format!("assign the IPv6 address {address}"));
+        }
+
+        if let Some(ref netns) = self.params.netns {
+            steps.push(format!("move the device into the network namespace {netns}"));
+        }
+
+        if !self.params.no_routing {
+            if self.params.app_routing_enabled() {
+                steps.push(format!(
+                    "route the traffic of the selected applications through {}",
+                    self.device
+                ));
+            } else if self.params.default_route {
+                steps.push(format!("set the default route through {}", self.device));
+            }
+        }
+
+        if !self.routes.is_empty() {
+            steps.push(format!("add the routes {}", self.routes.iter().join(", ")));
+        }
+
+        if !self.params.no_dns && !self.resolver_config.dns_servers.is_empty() {
+            steps.push(format!(
+                "configure the DNS servers {} with the search domains {}",
+                self.resolver_config.dns_servers.iter().join(", "),
+                self.resolver_config.search_domains.iter().join(", ")
+            ));
+        }
+
+        if self.params.kill_switch {
+            steps.push("enable the kill switch".to_owned());
+        }
+
+        if let Some(ref script) = self.params.script_up {
+            steps.push(format!("run the up script {}", script.display()));
+        }
+
+        steps
+    }
+
+    pub fn log(&self) {
+        for step in self.steps() {
+            info!("Dry run, would {}", step);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps() {
+        let params = TunnelParams {
+            default_route: true,
+            kill_switch: true,
+            ..Default::default()
+        };
+        let resolver_config = ResolverConfig {
+            search_domains: vec!["corp.example.com".to_owned()],
+            dns_servers: vec!["10.0.0.1".parse().unwrap()],
+            wins_servers: Vec::new(),
+        };
+        let routes = vec!["10.1.0.0/16".parse().unwrap()];
+
+        let dry_run = DryRun {
+            params: &params,
+            device: "snx-tun",
+            address: "10.0.0.10/24".parse().unwrap(),
+            ipv6_address: None,
+            routes: &routes,
+            resolver_config: &resolver_config,
+        };
+
+        assert_eq!(
+            dry_run.steps(),
+            vec![
+                "create the device snx-tun with the address 10.0.0.10/24",
+                "set the default route through snx-tun",
+                "add the routes 10.1.0.0/16",
+                "configure the DNS servers 10.0.0.1 with the search domains corp.example.com",
+                "enable the kill switch",
+            ]
+        );
+    }
+}
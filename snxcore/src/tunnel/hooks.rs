@@ -0,0 +1,155 @@
+use std::{path::Path, process::Stdio, time::Duration};
+
+use itertools::Itertools;
+use tracing::{debug, warn};
+
+use crate::model::{ConnectionInfo, params::TunnelParams};
+
+fn script_env(event: &str, info: &ConnectionInfo) -> Vec<(&'static str, String)> {
+    vec![
+        ("SNX_EVENT", event.to_owned()),
+        ("SNX_INTERFACE", info.interface_name.clone()),
+        ("SNX_ADDRESS", info.ip_address.addr().to_string()),
+        ("SNX_PREFIX", info.ip_address.to_string()),
+        (
+            "SNX_ADDRESS6",
+            info.ipv6_address.map(|a| a.to_string()).unwrap_or_default(),
+        ),
+        ("SNX_DNS_SERVERS", info.dns_servers.iter().join(" ")),
+        (
+            "SNX_SEARCH_DOMAINS",
+            info.search_domains.iter().map(|d| d.trim_start_matches('~')).join(" "),
+        ),
+        ("SNX_WINS_SERVERS", info.wins_servers.iter().join(" ")),
+        ("SNX_GATEWAY", info.server_name.clone()),
+        ("SNX_PEER", info.peer_address.map(|a| a.to_string()).unwrap_or_default()),
+    ]
+}
+
+async fn run_script(script: &Path, event: &str, info: &ConnectionInfo, timeout: Duration) -> anyhow::Result<()> {
+    debug!("Running {} script: {}", event, script.display());
+
+    // the script is killed when the timeout drops the future
+    let child = tokio::process::Command::new(script)
+        .envs(script_env(event, info))
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .status();
+
+    let status = tokio::time::timeout(timeout, child)
+        .await
+        .map_err(|_| anyhow::anyhow!("{} timed out after {} seconds", script.display(), timeout.as_secs()))??;
+
+    anyhow::ensure!(status.success(), "{} exited with {}", script.display(), status);
+
+    Ok(())
+}
+
+/// Run the up script once the tunnel is fully configured, a failure is fatal only when configured so.
+pub async fn run_up_script(params: &TunnelParams, info: &ConnectionInfo) -> anyhow::Result<()> {
+    let Some(ref script) = params.script_up else {
+        return Ok(());
+    };
+
+    match run_script(script, "up", info, params.script_timeout).await {
+        Err(e) if params.script_up_fatal => Err(e),
+        Err(e) => {
+            warn!("Up script failed: {}", e);
+            Ok(())
+        }
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Run the down script before the tunnel is torn down, failures are only logged.
+pub async fn run_down_script(params: &TunnelParams, info: &ConnectionInfo) {
+    let Some(ref script) = params.script_down else {
+        return;
+    };
+
+    if let Err(e) = run_script(script, "down", info, params.script_timeout).await {
+        warn!("Down script failed: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+
+    fn connection_info() -> ConnectionInfo {
+        ConnectionInfo {
+            server_name: "vpn.example.com".to_owned(),
+            interface_name: "snx-tun".to_owned(),
+            ip_address: "10.0.0.10/24".parse().unwrap(),
+            dns_servers: vec!["10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap()],
+            search_domains: vec!["example.com".to_owned(), "~corp.example.com".to_owned()],
+            wins_servers: vec!["10.0.0.5".parse().unwrap()],
+            peer_address: Some("10.0.0.1".parse().unwrap()),
+            ..Default::default()
+        }
+    }
+
+    fn write_script(dir: &Path, body: &str) -> std::path::PathBuf {
+        let path = dir.join("hook.sh");
+        std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_script_env() {
+        let env = script_env("up", &connection_info());
+
+        assert!(env.contains(&("SNX_INTERFACE", "snx-tun".to_owned())));
+        assert!(env.contains(&("SNX_ADDRESS", "10.0.0.10".to_owned())));
+        assert!(env.contains(&("SNX_DNS_SERVERS", "10.0.0.1 10.0.0.2".to_owned())));
+        assert!(env.contains(&("SNX_SEARCH_DOMAINS", "example.com corp.example.com".to_owned())));
+        assert!(env.contains(&("SNX_WINS_SERVERS", "10.0.0.5".to_owned())));
+        assert!(env.contains(&("SNX_GATEWAY", "vpn.example.com".to_owned())));
+        assert!(env.contains(&("SNX_PEER", "10.0.0.1".to_owned())));
+    }
+
+    #[tokio::test]
+    async fn test_run_up_script() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let out = dir.path().join("out");
+        let script = write_script(
+            dir.path(),
+            &format!("echo \"$SNX_EVENT $SNX_INTERFACE\" > {}", out.display()),
+        );
+
+        let params = TunnelParams {
+            script_up: Some(script),
+            ..Default::default()
+        };
+        run_up_script(&params, &connection_info()).await.unwrap();
+
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "up snx-tun\n");
+    }
+
+    #[tokio::test]
+    async fn test_run_up_script_failure() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let script = write_script(dir.path(), "exit 1");
+
+        let mut params = TunnelParams {
+            script_up: Some(script),
+            ..Default::default()
+        };
+        assert!(run_up_script(&params, &connection_info()).await.is_ok());
+
+        params.script_up_fatal = true;
+        assert!(run_up_script(&params, &connection_info()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_script_timeout() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let script = write_script(dir.path(), "sleep 10");
+
+        let result = run_script(&script, "down", &connection_info(), Duration::from_millis(100)).await;
+        assert!(result.is_err());
+    }
+}
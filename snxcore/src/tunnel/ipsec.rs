@@ -1,6 +1,9 @@
+use std::net::IpAddr;
+
 use crate::{
     model::{IpsecSession, params::TunnelParams},
     platform::ResolverConfig,
+    tunnel,
 };
 
 pub mod connector;
@@ -9,10 +12,10 @@ pub mod keepalive;
 pub mod natt;
 
 pub fn make_resolver_config(session: &IpsecSession, params: &TunnelParams) -> ResolverConfig {
-    let search_domains = session
-        .domains
-        .iter()
-        .chain(&params.search_domains)
+    let search_domains = params
+        .dns_override
+        .apply(&session.domains, &params.search_domains)
+        .into_iter()
         .filter(|s| {
             !s.is_empty()
                 && !params
@@ -20,19 +23,83 @@ pub fn make_resolver_config(session: &IpsecSession, params: &TunnelParams) -> Re
                     .iter()
                     .any(|d| d.to_lowercase() == s.trim_matches('~').to_lowercase())
         })
-        .cloned()
         .collect::<Vec<_>>();
 
-    let dns_servers = session
-        .dns
-        .iter()
-        .chain(&params.dns_servers)
-        .filter(|s| !params.ignore_dns_servers.iter().any(|d| *d == **s))
-        .cloned()
+    let dns_servers = params
+        .dns_override
+        .apply(&session.dns, &params.dns_servers)
+        .into_iter()
+        .filter(|s| !params.ignore_dns_servers.iter().any(|d| *d == *s))
+        .map(IpAddr::V4)
         .collect::<Vec<_>>();
 
-    ResolverConfig {
+    let config = ResolverConfig {
         search_domains,
         dns_servers,
+        wins_servers: session.wins.clone(),
+    }
+    .normalized();
+
+    tunnel::log_resolver_config(params, &config);
+
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::params::DnsOverride;
+
+    #[test]
+    fn test_resolver_config_override() {
+        let session = IpsecSession {
+            dns: vec!["10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap()],
+            domains: vec!["corp.example.com".to_owned()],
+            ..IpsecSession::default()
+        };
+
+        let mut params = TunnelParams {
+            dns_servers: vec!["192.168.1.1".parse().unwrap(), "10.0.0.2".parse().unwrap()],
+            search_domains: vec!["lab.example.com".to_owned()],
+            ..Default::default()
+        };
+
+        params.dns_override = DnsOverride::Gateway;
+        let config = make_resolver_config(&session, &params);
+        assert_eq!(
+            config.dns_servers,
+            vec!["10.0.0.1".parse::<IpAddr>().unwrap(), "10.0.0.2".parse().unwrap()]
+        );
+        assert_eq!(config.search_domains, vec!["corp.example.com"]);
+
+        params.dns_override = DnsOverride::Config;
+        let config = make_resolver_config(&session, &params);
+        assert_eq!(
+            config.dns_servers,
+            vec!["192.168.1.1".parse::<IpAddr>().unwrap(), "10.0.0.2".parse().unwrap()]
+        );
+        assert_eq!(config.search_domains, vec!["lab.example.com"]);
+
+        params.dns_override = DnsOverride::Merged;
+        let config = make_resolver_config(&session, &params);
+        assert_eq!(
+            config.dns_servers,
+            vec![
+                "192.168.1.1".parse::<IpAddr>().unwrap(),
+                "10.0.0.2".parse().unwrap(),
+                "10.0.0.1".parse().unwrap()
+            ]
+        );
+        assert_eq!(config.search_domains, vec!["lab.example.com", "corp.example.com"]);
+
+        // the ignored values are removed whatever the source
+        params.ignore_dns_servers = vec!["10.0.0.2".parse().unwrap()];
+        params.ignore_search_domains = vec!["corp.example.com".to_owned()];
+        let config = make_resolver_config(&session, &params);
+        assert_eq!(
+            config.dns_servers,
+            vec!["192.168.1.1".parse::<IpAddr>().unwrap(), "10.0.0.1".parse().unwrap()]
+        );
+        assert_eq!(config.search_domains, vec!["lab.example.com"]);
     }
 }
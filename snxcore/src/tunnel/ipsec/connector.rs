@@ -21,18 +21,19 @@ use tokio::{net::UdpSocket, sync::mpsc::Sender};
 use tracing::{debug, trace, warn};
 
 use crate::{
+    error::AuthRejected,
     model::{
         IpsecSession, MfaChallenge, MfaType, SessionState, VpnSession,
         params::{CertType, TransportType, TunnelParams},
         proto::{AuthenticationRealm, ClientLoggingData},
     },
     platform::{self, NetworkInterface},
-    server_info,
+    proxy, server_info,
     sexpr::SExpression,
     tunnel::{
         TunnelCommand, TunnelConnector, TunnelEvent, VpnTunnel,
         ipsec::{
-            imp::{native::NativeIpsecTunnel, tcpt::TcptIpsecTunnel, udp::UdpIpsecTunnel},
+            imp::{dry_run::DryRunIpsecTunnel, native::NativeIpsecTunnel, tcpt::TcptIpsecTunnel, udp::UdpIpsecTunnel},
             natt::NattProber,
         },
     },
@@ -45,6 +46,10 @@ const DEFAULT_ESP_LIFETIME: Duration = Duration::from_secs(3600);
 
 const SESSIONS_PATH: &str = "/var/cache/snx-rs/sessions";
 
+pub(crate) fn ike_session_path(params: &TunnelParams) -> PathBuf {
+    Path::new(SESSIONS_PATH).join(&params.server_name)
+}
+
 fn get_challenge_attribute_type(payload: &AttributesPayload) -> ConfigAttributeType {
     payload
         .attributes
@@ -114,7 +119,8 @@ impl IpsecTunnelConnector {
         socket
             .connect(format!(
                 "{}:{}",
-                params.server_name, server_info.connectivity_info.natt_port
+                params.server_host(),
+                server_info.connectivity_info.natt_port
             ))
             .await?;
 
@@ -125,7 +131,8 @@ impl IpsecTunnelConnector {
         let prober = NattProber::new(socket.peer_addr()?, params.port_knock);
 
         let esp_transport = if prober.probe().await.is_ok() {
-            if platform::get_features().await.ipsec_native {
+            // the xfrm interface cannot be moved into a network namespace, the userspace ESP is used instead
+            if platform::get_features().await.ipsec_native && params.netns.is_none() {
                 TransportType::Native
             } else {
                 TransportType::Udp
@@ -234,6 +241,12 @@ impl IpsecTunnelConnector {
             .map(Into::into)
             .collect();
 
+        self.ipsec_session.wins = get_long_attributes(&om_reply, ConfigAttributeType::Ipv4Nbns)
+            .into_iter()
+            .flat_map(|b| b.reader().read_u32::<BigEndian>().ok())
+            .map(Into::into)
+            .collect();
+
         let features = platform::get_features().await;
 
         self.ipsec_session.domains = get_long_attribute(&om_reply, ConfigAttributeType::InternalDomainName)
@@ -260,6 +273,7 @@ impl IpsecTunnelConnector {
         );
         debug!("OM DNS servers: {:?}", self.ipsec_session.dns);
         debug!("OM search domains: {:?}", self.ipsec_session.domains);
+        debug!("OM WINS servers: {:?}", self.ipsec_session.wins);
 
         self.do_esp_proposal().await?;
 
@@ -299,7 +313,7 @@ impl IpsecTunnelConnector {
             }
             Some(status) => {
                 warn!("IPSec authentication failed, status: {}", status);
-                Err(anyhow!(tr!("error-auth-failed")))
+                Err(AuthRejected(tr!("error-auth-failed")).into())
             }
             None => {
                 let attr = get_challenge_attribute_type(&id_reply);
@@ -454,7 +468,7 @@ impl IpsecTunnelConnector {
     }
 
     fn session_file_name(&self) -> PathBuf {
-        Path::new(SESSIONS_PATH).join(&self.params.server_name)
+        ike_session_path(&self.params)
     }
 
     fn save_ike_session(&mut self) -> anyhow::Result<()> {
@@ -470,10 +484,11 @@ impl IpsecTunnelConnector {
         let data = self.service.session().save(&office_mode)?;
         let dir = Path::new(SESSIONS_PATH);
 
+        // the file is private, the directory stays open for the one handed over to the run-as-user
         std::fs::create_dir_all(dir)?;
 
         let filename = self.session_file_name();
-        std::fs::write(&filename, &data)?;
+        util::write_private_file(&filename, &data)?;
 
         debug!("Saved IKE session to: {}", filename.display());
 
@@ -482,6 +497,11 @@ impl IpsecTunnelConnector {
 
     fn load_ike_session(&mut self) -> anyhow::Result<OfficeMode> {
         let filename = self.session_file_name();
+        let needs_check = !self.params.insecure_permissions && filename.exists();
+        if let Some(Err(e)) = needs_check.then(|| util::check_secret_file(&filename)) {
+            warn!("Not restoring the IKE session: {}", e);
+            return Err(e);
+        }
         let data = std::fs::read(&filename)?;
         let office_mode = self.service.session().load(&data)?;
 
@@ -532,7 +552,7 @@ impl IpsecTunnelConnector {
 
         let ikev1_session = Box::new(Ikev1Session::new(identity, SessionType::Initiator)?);
 
-        let tcpt_address = format!("{}:{}", params.server_name, server_info.connectivity_info.tcpt_port)
+        let tcpt_address = format!("{}:{}", params.server_host(), server_info.connectivity_info.tcpt_port)
             .to_socket_addrs()?
             .next()
             .context("No address!")?;
@@ -678,6 +698,23 @@ impl TunnelConnector for IpsecTunnelConnector {
         command_sender: Sender<TunnelCommand>,
     ) -> anyhow::Result<Box<dyn VpnTunnel + Send>> {
         self.command_sender = Some(command_sender);
+
+        if let Some(server) = proxy::select(&self.params) {
+            warn!(
+                "The IPsec tunnel connects to the gateway directly, only the SSL tunnel goes through the proxy {}",
+                server.address()
+            );
+        }
+
+        // the native transport configures the kernel SA as soon as it is created
+        if self.params.dry_run {
+            return Ok(Box::new(DryRunIpsecTunnel::new(
+                self.params.clone(),
+                session,
+                self.esp_transport,
+            )));
+        }
+
         let result: anyhow::Result<Box<dyn VpnTunnel + Send>> = match self.esp_transport {
             TransportType::Native => Ok(Box::new(NativeIpsecTunnel::create(self.params.clone(), session).await?)),
             TransportType::Tcpt => Ok(Box::new(TcptIpsecTunnel::create(self.params.clone(), session).await?)),
@@ -717,6 +754,21 @@ impl TunnelConnector for IpsecTunnelConnector {
             TunnelEvent::Rekeyed(_) => {
                 debug!("Tunnel rekeyed");
             }
+            TunnelEvent::SessionExpiring(remaining) => {
+                debug!("Session expiring in {} seconds", remaining.as_secs());
+            }
+            TunnelEvent::TrustedNetworkDetected => {
+                debug!("Trusted network detected");
+            }
+            TunnelEvent::TrustedNetworkLost => {
+                debug!("Trusted network lost");
+            }
+            TunnelEvent::DnsChecked(results) => {
+                debug!("DNS self-test results: {:?}", results);
+            }
+            TunnelEvent::Keepalive(ok) => {
+                debug!("Keepalive succeeded: {}", ok);
+            }
         }
         Ok(())
     }
@@ -1,3 +1,4 @@
+pub mod dry_run;
 pub mod native;
 pub mod tcpt;
 pub mod tun;
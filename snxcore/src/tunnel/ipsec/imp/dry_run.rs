@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::Local;
+use ipnet::{IpNet, Ipv4Net};
+use tokio::sync::mpsc;
+use tracing::debug;
+
+use crate::{
+    ccc::CccHttpClient,
+    model::{
+        ConnectionInfo, TunnelStats, VpnSession,
+        params::{TransportType, TunnelParams},
+    },
+    platform, server_info,
+    tunnel::{TunnelCommand, TunnelEvent, VpnTunnel, dry_run::DryRun},
+    util,
+};
+
+// The SA is negotiated by the connector, the tunnel only fetches the routes and logs what it would configure.
+pub(crate) struct DryRunIpsecTunnel {
+    params: Arc<TunnelParams>,
+    session: Arc<VpnSession>,
+    esp_transport: TransportType,
+}
+
+impl DryRunIpsecTunnel {
+    pub(crate) fn new(params: Arc<TunnelParams>, session: Arc<VpnSession>, esp_transport: TransportType) -> Self {
+        Self {
+            params,
+            session,
+            esp_transport,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl VpnTunnel for DryRunIpsecTunnel {
+    async fn run(
+        self: Box<Self>,
+        _command_receiver: mpsc::Receiver<TunnelCommand>,
+        event_sender: mpsc::Sender<TunnelEvent>,
+    ) -> anyhow::Result<()> {
+        debug!("Running IPSec tunnel in the dry run mode");
+
+        let session = self.session.ipsec_session.as_ref().context("No IPSec session!")?;
+
+        let server_info = server_info::get(&self.params).await?;
+        let client = CccHttpClient::new(self.params.clone(), Some(self.session.clone()));
+        let client_settings = client.get_client_settings().await?;
+
+        let gateway_address = util::resolve_ipv4_host(&format!(
+            "{}:{}",
+            self.params.server_host(),
+            server_info.connectivity_info.natt_port
+        ))?;
+
+        let default_name = if self.esp_transport == TransportType::Native {
+            TunnelParams::DEFAULT_IPSEC_IF_NAME
+        } else {
+            TunnelParams::DEFAULT_SSL_IF_NAME
+        };
+        let device_name = self.params.if_name.as_deref().unwrap_or(default_name);
+
+        let address = Ipv4Net::with_netmask(session.address, session.netmask)?;
+        let resolver_config = crate::tunnel::ipsec::make_resolver_config(session, &self.params);
+
+        let mut exclusions = self.params.exclude_routes.clone();
+        exclusions.push(Ipv4Net::from(gateway_address));
+
+        let routes = self.params.tunnel_routes(
+            util::ranges_to_subnets(&client_settings.updated_policies.range.settings),
+            false,
+            &exclusions,
+        );
+
+        DryRun {
+            params: &self.params,
+            device: device_name,
+            address,
+            ipv6_address: None,
+            routes: &routes,
+            resolver_config: &resolver_config,
+        }
+        .log();
+
+        let mut all_routes = routes.into_iter().map(IpNet::V4).collect::<Vec<_>>();
+        if !self.params.no_routing && self.params.default_route {
+            all_routes.insert(0, IpNet::V4(Ipv4Net::default()));
+        }
+
+        let info = ConnectionInfo {
+            since: Some(Local::now()),
+            server_name: self.params.server_name.clone(),
+            username: self.session.username.clone().unwrap_or_default(),
+            login_type: self.params.login_type.clone(),
+            tunnel_type: self.params.tunnel_type,
+            transport_type: self.esp_transport,
+            ip_address: address,
+            ipv6_address: None,
+            dns_servers: resolver_config.dns_servers,
+            search_domains: resolver_config.search_domains,
+            wins_servers: resolver_config.wins_servers,
+            interface_name: device_name.to_owned(),
+            dns_configured: !self.params.no_dns,
+            dns_mode: (!self.params.no_dns).then(|| platform::effective_dns_mode(&self.params)),
+            dns_override: (!self.params.no_dns).then_some(self.params.dns_override),
+            routing_configured: !self.params.no_routing,
+            default_route: self.params.default_route,
+            dns_check: Vec::new(),
+            forward_source: self.params.forward_source,
+            peer_address: None,
+            stats: TunnelStats::default(),
+            hello_reply: None,
+            protocol_version: None,
+            profile: self.params.profile.clone(),
+            routes: all_routes,
+            traffic: None,
+            reauth_interval: None,
+        };
+
+        let _ = event_sender.send(TunnelEvent::Connected(info)).await;
+
+        Ok(())
+    }
+}
@@ -9,18 +9,27 @@ use std::{
 
 use anyhow::Context;
 use chrono::Local;
-use ipnet::Ipv4Net;
+use ipnet::{IpNet, Ipv4Net};
 use tokio::{net::UdpSocket, sync::mpsc, time::MissedTickBehavior};
-use tracing::debug;
+use tracing::{debug, warn};
 
 use crate::{
     ccc::CccHttpClient,
-    model::{ConnectionInfo, VpnSession, params::TunnelParams},
-    platform::{self, IpsecConfigurator, ResolverConfig, RoutingConfigurator, UdpEncap, UdpSocketExt},
+    model::{ConnectionInfo, TunnelStats, VpnSession, params::TunnelParams},
+    platform::{
+        self, Forwarding, ForwardingConfig, IpsecConfigurator, KillSwitch, KillSwitchConfig, NetworkInterface,
+        ResolverConfig, RoutingConfigurator, RoutingPolicy, UdpEncap, UdpSocketExt,
+    },
     server_info,
     tunnel::{
-        TunnelCommand, TunnelEvent, VpnTunnel,
-        ipsec::{keepalive::KeepaliveRunner, natt::start_natt_listener},
+        self, StaticRoutes, TunnelCommand, TunnelEvent, VpnTunnel, device, dns_check,
+        domain_routes::{DomainRoutes, DomainRoutesConfig},
+        hooks,
+        ipsec::{
+            keepalive::{KeepaliveRunner, KeepaliveSettings},
+            natt::start_natt_listener,
+        },
+        journal::{NetworkChange, NetworkJournal, PlatformReverter},
     },
     util,
 };
@@ -28,6 +37,7 @@ use crate::{
 pub(crate) struct NativeIpsecTunnel {
     configurator: Box<dyn IpsecConfigurator + Send + Sync>,
     keepalive_runner: KeepaliveRunner,
+    keepalive_settings: KeepaliveSettings,
     natt_socket: Arc<UdpSocket>,
     ready: Arc<AtomicBool>,
     params: Arc<TunnelParams>,
@@ -35,6 +45,9 @@ pub(crate) struct NativeIpsecTunnel {
     device_name: String,
     gateway_address: Ipv4Addr,
     subnets: Vec<Ipv4Net>,
+    journal: Arc<NetworkJournal>,
+    connection_info: Option<ConnectionInfo>,
+    domain_routes: Option<DomainRoutes>,
 }
 
 impl NativeIpsecTunnel {
@@ -50,7 +63,8 @@ impl NativeIpsecTunnel {
 
         let gateway_address = util::resolve_ipv4_host(&format!(
             "{}:{}",
-            params.server_name, server_info.connectivity_info.natt_port
+            params.server_host(),
+            server_info.connectivity_info.natt_port
         ))?;
 
         debug!(
@@ -59,23 +73,27 @@ impl NativeIpsecTunnel {
         );
 
         let ready = Arc::new(AtomicBool::new(false));
+        let keepalive_settings = KeepaliveSettings::new(
+            params.keepalive_jitter,
+            !params.no_keepalive && platform::get_features().await.ipsec_keepalive,
+            params.timeouts.keepalive,
+        );
         let keepalive_runner = KeepaliveRunner::new(
             server_info.connectivity_info.server_ip,
-            if params.no_keepalive || !platform::get_features().await.ipsec_keepalive {
-                Arc::new(AtomicBool::new(false))
-            } else {
-                ready.clone()
-            },
+            keepalive_settings.clone(),
+            ready.clone(),
         );
 
         let natt_socket = UdpSocket::bind("0.0.0.0:0").await?;
         natt_socket.set_encap(UdpEncap::EspInUdp)?;
 
-        let device_name = params
-            .if_name
-            .as_deref()
-            .unwrap_or(TunnelParams::DEFAULT_IPSEC_IF_NAME)
-            .to_owned();
+        let device_name =
+            device::resolve_device_name(params.if_name.as_deref().unwrap_or(TunnelParams::DEFAULT_IPSEC_IF_NAME))?;
+
+        let journal = Arc::new(NetworkJournal::new(&params));
+        journal.record(NetworkChange::Device {
+            name: device_name.clone(),
+        });
 
         let mut configurator = platform::new_ipsec_configurator(
             &device_name,
@@ -83,6 +101,8 @@ impl NativeIpsecTunnel {
             natt_socket.local_addr()?.port(),
             gateway_address,
             server_info.connectivity_info.natt_port,
+            params.effective_mtu(None),
+            RoutingPolicy::new(&params, false),
         )?;
 
         configurator.configure().await?;
@@ -91,6 +111,7 @@ impl NativeIpsecTunnel {
         Ok(Self {
             configurator: Box::new(configurator),
             keepalive_runner,
+            keepalive_settings,
             natt_socket: Arc::new(natt_socket),
             ready,
             params,
@@ -98,17 +119,27 @@ impl NativeIpsecTunnel {
             device_name,
             gateway_address,
             subnets,
+            journal,
+            domain_routes: None,
+            connection_info: None,
         })
     }
 
     async fn setup_dns(&self, resolver_config: &ResolverConfig, cleanup: bool) -> anyhow::Result<()> {
         debug!("Configuring resolver: {:?}", resolver_config);
 
-        let resolver = platform::new_resolver_configurator(&self.device_name)?;
+        let resolver = platform::new_resolver_configurator(&self.device_name, &self.params)?;
 
         if cleanup {
             resolver.cleanup(resolver_config).await?;
         } else {
+            self.journal.record(NetworkChange::Dns {
+                device: self.device_name.clone(),
+                backend: self.params.dns_backend,
+                mode: self.params.dns_mode,
+                resolv_path: self.params.dns_resolv_path.clone(),
+                config: resolver_config.clone(),
+            });
             resolver.configure(resolver_config).await?;
         }
 
@@ -118,41 +149,108 @@ impl NativeIpsecTunnel {
     async fn setup_routing(&self) -> anyhow::Result<()> {
         let session = self.session.ipsec_session.as_ref().context("No IPSec session!")?;
 
-        let configurator = platform::new_routing_configurator(&self.device_name, session.address);
-
-        let mut subnets = self.params.add_routes.clone();
+        let configurator =
+            platform::new_routing_configurator(&self.device_name, session.address, self.params.route_metric);
 
+        // the IPSec sockets are not marked, the default route table is selected by the gateway address
+        let policy = RoutingPolicy::new(&self.params, false);
         let mut default_route_set = false;
 
         if !self.params.no_routing {
-            if self.params.default_route {
-                configurator.setup_default_route(self.gateway_address).await?;
+            tunnel::pin_gateway_route(
+                &self.params,
+                &configurator,
+                &self.device_name,
+                session.address,
+                self.gateway_address,
+                &self.journal,
+            )
+            .await?;
+
+            if self.params.app_routing_enabled() {
+                debug!(
+                    "Only the traffic of the selected applications is routed through {}",
+                    self.device_name
+                );
+            } else if self.params.default_route {
+                self.journal.record(NetworkChange::DefaultRoute {
+                    device: self.device_name.clone(),
+                    address: session.address,
+                    destination: self.gateway_address,
+                    metric: self.params.route_metric,
+                    policy,
+                });
+                configurator.setup_default_route(self.gateway_address, &policy).await?;
+                self.journal.record(NetworkChange::ExcludedRoutes {
+                    device: self.device_name.clone(),
+                    address: session.address,
+                    routes: self.params.exclude_routes.clone(),
+                });
+                configurator.setup_excluded_routes(&self.params.exclude_routes).await?;
                 default_route_set = true;
-            } else {
-                subnets.extend(&self.subnets);
             }
         }
 
+        self.journal.record(NetworkChange::KeepaliveRoute {
+            device: self.device_name.clone(),
+            address: session.address,
+            destination: self.gateway_address,
+            policy,
+        });
         configurator
-            .setup_keepalive_route(self.gateway_address, !default_route_set)
+            .setup_keepalive_route(self.gateway_address, !default_route_set, &policy)
             .await?;
 
-        subnets.retain(|s| !s.contains(&self.gateway_address));
+        let mut exclusions = self.params.exclude_routes.clone();
+        exclusions.push(Ipv4Net::from(self.gateway_address));
+
+        let subnets = self
+            .params
+            .tunnel_routes(self.subnets.iter().copied(), false, &exclusions);
+
+        if self.params.kill_switch {
+            let config = KillSwitchConfig::new(&self.device_name, &subnets, default_route_set, &exclusions);
+            platform::new_kill_switch().enable(&config).await?;
+        }
 
         if !subnets.is_empty() {
+            self.journal.record(NetworkChange::Routes {
+                device: self.device_name.clone(),
+                address: session.address,
+                routes: subnets.iter().copied().map(IpNet::V4).collect(),
+                metric: self.params.route_metric,
+                peer: None,
+            });
             let _ = configurator.add_routes(&subnets, &self.params.ignore_routes).await;
         }
 
+        if let Some(source) = self.params.forward_source {
+            let config = ForwardingConfig::new(&self.device_name, source, session.address, &subnets)?;
+            warn!(
+                "Forwarding {} through the tunnel, the gateway sees all forwarded traffic coming from {}",
+                config.source, session.address
+            );
+            self.journal
+                .record(NetworkChange::Forwarding { config: config.clone() });
+            platform::new_forwarding().enable(&config).await?;
+        }
+
+        tunnel::clamp_mss(&self.params, &self.device_name, &self.journal).await;
+
+        tunnel::block_ipv6(&self.params, &self.device_name, &self.journal).await?;
+
         Ok(())
     }
 
     async fn cleanup(&mut self) {
-        if !self.params.no_dns {
-            if let Some(session) = self.session.ipsec_session.as_ref() {
-                let config = crate::tunnel::ipsec::make_resolver_config(session, &self.params);
-                let _ = self.setup_dns(&config, true).await;
-            }
+        if let Some(info) = self.connection_info.take() {
+            hooks::run_down_script(&self.params, &info).await;
         }
+
+        // stopped first, so that no routes are added after the rollback
+        self.domain_routes = None;
+        self.journal.rollback(&PlatformReverter).await;
+        platform::new_network_interface().update_connectivity().await;
         self.configurator.cleanup().await;
     }
 }
@@ -178,6 +276,24 @@ impl VpnTunnel for NativeIpsecTunnel {
             self.setup_dns(&resolver_config, false).await?;
         }
 
+        tunnel::setup_app_routing(
+            &self.params,
+            &self.device_name,
+            session.address,
+            None,
+            &resolver_config.dns_servers,
+            &self.journal,
+        )
+        .await?;
+
+        tunnel::redirect_dns(
+            &self.params,
+            &self.device_name,
+            &resolver_config.dns_servers,
+            &self.journal,
+        )
+        .await?;
+
         let ip_address = Ipv4Net::with_netmask(session.address, session.netmask)?;
 
         let info = ConnectionInfo {
@@ -188,13 +304,48 @@ impl VpnTunnel for NativeIpsecTunnel {
             tunnel_type: self.params.tunnel_type,
             transport_type: session.transport_type,
             ip_address,
+            ipv6_address: None,
             dns_servers: resolver_config.dns_servers,
             search_domains: resolver_config.search_domains,
+            wins_servers: resolver_config.wins_servers,
             interface_name: self.device_name.clone(),
             dns_configured: !self.params.no_dns,
+            dns_mode: (!self.params.no_dns).then(|| platform::effective_dns_mode(&self.params)),
+            dns_override: (!self.params.no_dns).then_some(self.params.dns_override),
             routing_configured: !self.params.no_routing,
             default_route: self.params.default_route,
+            dns_check: Vec::new(),
+            forward_source: self.params.forward_source,
+            peer_address: None,
+            stats: TunnelStats::default(),
+            hello_reply: None,
+            protocol_version: None,
+            profile: self.params.profile.clone(),
+            routes: self.journal.routes(),
+            traffic: None,
+            reauth_interval: None,
         };
+        hooks::run_up_script(&self.params, &info).await?;
+        platform::new_network_interface().update_connectivity().await;
+        self.connection_info = Some(info.clone());
+
+        let mut exclusions = self.params.exclude_routes.clone();
+        exclusions.push(Ipv4Net::from(self.gateway_address));
+
+        self.domain_routes = DomainRoutes::spawn(
+            self.params.clone(),
+            DomainRoutesConfig {
+                device: self.device_name.clone(),
+                address: session.address,
+                dns_servers: info.dns_servers.clone(),
+                peer: None,
+                exclusions,
+            },
+            self.journal.clone(),
+        );
+
+        dns_check::spawn(self.params.clone(), info.dns_servers.clone(), event_sender.clone());
+
         let _ = event_sender.send(TunnelEvent::Connected(info)).await;
 
         let sender = event_sender.clone();
@@ -230,6 +381,22 @@ impl VpnTunnel for NativeIpsecTunnel {
                         let address = Ipv4Net::with_netmask(session.address, session.netmask).unwrap_or(ip_address);
                         let _ = event_sender.send(TunnelEvent::Rekeyed(address)).await;
                     }
+                    TunnelCommand::Reconfigure(params) => {
+                        debug!("Applying the reloaded configuration");
+                        let routes = StaticRoutes {
+                            device: self.device_name.clone(),
+                            address: ip_address.addr(),
+                            peer: None,
+                            exclusions: vec![Ipv4Net::from(self.gateway_address)],
+                            acquired: self.params.acquired_routes(self.subnets.iter().copied()),
+                        };
+                        routes.update(&self.params, &params, &self.journal).await;
+                        self.keepalive_settings.update(
+                            params.keepalive_jitter,
+                            !params.no_keepalive && platform::get_features().await.ipsec_keepalive,
+                        );
+                        self.params = params;
+                    }
                 }
             }
         };
@@ -239,7 +406,7 @@ impl VpnTunnel for NativeIpsecTunnel {
                 Ok(())
             }
 
-            err = self.keepalive_runner.run() => {
+            err = self.keepalive_runner.run(event_sender.clone()) => {
                 debug!("Terminating IPSec tunnel due to keepalive failure");
                 err
             }
@@ -53,7 +53,8 @@ pub(crate) struct TcptIpsecTunnel(Box<TunIpsecTunnel>);
 
 impl TcptIpsecTunnel {
     pub(crate) async fn create(params: Arc<TunnelParams>, session: Arc<VpnSession>) -> anyhow::Result<Self> {
-        let mut tcp = tokio::net::TcpStream::connect((params.server_name.as_str(), 443)).await?;
+        let connect = tokio::net::TcpStream::connect((params.server_host(), params.server_port()));
+        let mut tcp = tokio::time::timeout(params.timeouts.connect, connect).await??;
 
         tcp.handshake(TcptDataType::Esp).await?;
 
@@ -16,20 +16,31 @@ use futures::{
     pin_mut,
 };
 use i18n::tr;
-use ipnet::Ipv4Net;
+use ipnet::{IpNet, Ipv4Net};
 use isakmp::esp::{EspCodec, EspEncapType};
 use tokio::time::MissedTickBehavior;
-use tracing::{debug, error, warn};
+use tracing::{Instrument, debug, error, info, warn};
 
 use crate::{
     ccc::CccHttpClient,
     model::{
-        ConnectionInfo, VpnSession,
+        ConnectionInfo, TunnelStats, VpnSession,
         params::{TransportType, TunnelParams},
     },
-    platform::{self, NetworkInterface, ResolverConfig, RoutingConfigurator, new_resolver_configurator},
+    platform::{
+        self, Forwarding, ForwardingConfig, KillSwitch, KillSwitchConfig, NamespaceConfig, NetworkInterface,
+        ResolverConfig, RoutingConfigurator, RoutingPolicy, new_resolver_configurator,
+    },
     server_info,
-    tunnel::{TunnelCommand, TunnelEvent, VpnTunnel, device::TunDevice, ipsec::keepalive::KeepaliveRunner},
+    tunnel::{
+        self, StaticRoutes, TunnelCommand, TunnelEvent, VpnTunnel,
+        device::{self, TunDevice},
+        dns_check,
+        domain_routes::{DomainRoutes, DomainRoutesConfig},
+        hooks,
+        ipsec::keepalive::{KeepaliveRunner, KeepaliveSettings},
+        journal::{NetworkChange, NetworkJournal, PlatformReverter},
+    },
     util,
 };
 
@@ -49,6 +60,9 @@ pub(crate) struct TunIpsecTunnel {
     encap_type: EspEncapType,
     esp_transport: TransportType,
     subnets: Vec<Ipv4Net>,
+    journal: Arc<NetworkJournal>,
+    connection_info: Option<ConnectionInfo>,
+    domain_routes: Option<DomainRoutes>,
 }
 
 impl TunIpsecTunnel {
@@ -70,7 +84,7 @@ impl TunIpsecTunnel {
             _ => (server_info.connectivity_info.tcpt_port, EspEncapType::None),
         };
 
-        let gateway_address = util::resolve_ipv4_host(&format!("{}:{}", params.server_name, port))?;
+        let gateway_address = util::resolve_ipv4_host(&format!("{}:{}", params.server_host(), port))?;
 
         debug!(
             "Resolved gateway address: {}, acquired internal address: {}",
@@ -81,6 +95,8 @@ impl TunIpsecTunnel {
 
         ready.store(true, Ordering::SeqCst);
 
+        let journal = Arc::new(NetworkJournal::new(&params));
+
         Ok(Self {
             params,
             session,
@@ -92,6 +108,9 @@ impl TunIpsecTunnel {
             encap_type,
             esp_transport,
             subnets,
+            journal,
+            domain_routes: None,
+            connection_info: None,
         })
     }
 
@@ -102,48 +121,109 @@ impl TunIpsecTunnel {
     }
 
     async fn cleanup(&mut self) {
-        if let Some(device) = self.tun_device.take() {
-            if let Some(session) = self.session.ipsec_session.as_ref() {
-                let configurator = platform::new_routing_configurator(device.name(), session.address);
-                let _ = configurator.remove_default_route(self.gateway_address).await;
-                let _ = configurator.remove_keepalive_route(self.gateway_address).await;
-                if !self.params.no_dns {
-                    let config = crate::tunnel::ipsec::make_resolver_config(session, &self.params);
-                    let _ = self.setup_dns(&config, device.name(), true).await;
-                }
-                let _ = platform::new_network_interface().delete_device(device.name()).await;
-            }
+        if let Some(info) = self.connection_info.take() {
+            hooks::run_down_script(&self.params, &info).await;
         }
+
+        // stopped first, so that no routes are added after the rollback
+        self.domain_routes = None;
+        self.journal.rollback(&PlatformReverter).await;
+        platform::new_network_interface().update_connectivity().await;
+        self.tun_device = None;
     }
 
     pub async fn setup_routing(&self, dev_name: &str) -> anyhow::Result<()> {
         let session = self.session.ipsec_session.as_ref().context("No IPSec session!")?;
 
-        let configurator = platform::new_routing_configurator(dev_name, session.address);
-
-        let mut subnets = self.params.add_routes.clone();
+        let configurator = platform::new_routing_configurator(dev_name, session.address, self.params.route_metric);
 
+        // the IPSec sockets are not marked, the default route table is selected by the gateway address
+        let policy = RoutingPolicy::new(&self.params, false);
         let mut default_route_set = false;
 
         if !self.params.no_routing {
-            if self.params.default_route {
-                configurator.setup_default_route(self.gateway_address).await?;
+            tunnel::pin_gateway_route(
+                &self.params,
+                &configurator,
+                dev_name,
+                session.address,
+                self.gateway_address,
+                &self.journal,
+            )
+            .await?;
+
+            if self.params.app_routing_enabled() {
+                debug!(
+                    "Only the traffic of the selected applications is routed through {}",
+                    dev_name
+                );
+            } else if self.params.default_route {
+                self.journal.record(NetworkChange::DefaultRoute {
+                    device: dev_name.to_owned(),
+                    address: session.address,
+                    destination: self.gateway_address,
+                    metric: self.params.route_metric,
+                    policy,
+                });
+                configurator.setup_default_route(self.gateway_address, &policy).await?;
+                self.journal.record(NetworkChange::ExcludedRoutes {
+                    device: dev_name.to_owned(),
+                    address: session.address,
+                    routes: self.params.exclude_routes.clone(),
+                });
+                configurator.setup_excluded_routes(&self.params.exclude_routes).await?;
                 default_route_set = true;
-            } else {
-                subnets.extend(&self.subnets);
             }
         }
 
+        self.journal.record(NetworkChange::KeepaliveRoute {
+            device: dev_name.to_owned(),
+            address: session.address,
+            destination: self.gateway_address,
+            policy,
+        });
         configurator
-            .setup_keepalive_route(self.gateway_address, !default_route_set)
+            .setup_keepalive_route(self.gateway_address, !default_route_set, &policy)
             .await?;
 
-        subnets.retain(|s| !s.contains(&self.gateway_address));
+        let mut exclusions = self.params.exclude_routes.clone();
+        exclusions.push(Ipv4Net::from(self.gateway_address));
+
+        let subnets = self
+            .params
+            .tunnel_routes(self.subnets.iter().copied(), false, &exclusions);
+
+        if self.params.kill_switch {
+            let config = KillSwitchConfig::new(dev_name, &subnets, default_route_set, &exclusions);
+            platform::new_kill_switch().enable(&config).await?;
+        }
 
         if !subnets.is_empty() {
+            self.journal.record(NetworkChange::Routes {
+                device: dev_name.to_owned(),
+                address: session.address,
+                routes: subnets.iter().copied().map(IpNet::V4).collect(),
+                metric: self.params.route_metric,
+                peer: None,
+            });
             let _ = configurator.add_routes(&subnets, &self.params.ignore_routes).await;
         }
 
+        if let Some(source) = self.params.forward_source {
+            let config = ForwardingConfig::new(dev_name, source, session.address, &subnets)?;
+            warn!(
+                "Forwarding {} through the tunnel, the gateway sees all forwarded traffic coming from {}",
+                config.source, session.address
+            );
+            self.journal
+                .record(NetworkChange::Forwarding { config: config.clone() });
+            platform::new_forwarding().enable(&config).await?;
+        }
+
+        tunnel::clamp_mss(&self.params, dev_name, &self.journal).await;
+
+        tunnel::block_ipv6(&self.params, dev_name, &self.journal).await?;
+
         Ok(())
     }
 
@@ -153,11 +233,18 @@ impl TunIpsecTunnel {
         dev_name: &str,
         cleanup: bool,
     ) -> anyhow::Result<()> {
-        let resolver = new_resolver_configurator(dev_name)?;
+        let resolver = new_resolver_configurator(dev_name, &self.params)?;
 
         if cleanup {
             resolver.cleanup(resolver_config).await?;
         } else {
+            self.journal.record(NetworkChange::Dns {
+                device: dev_name.to_owned(),
+                backend: self.params.dns_backend,
+                mode: self.params.dns_mode,
+                resolv_path: self.params.dns_resolv_path.clone(),
+                config: resolver_config.clone(),
+            });
             resolver.configure(resolver_config).await?;
         }
 
@@ -187,10 +274,23 @@ impl VpnTunnel for TunIpsecTunnel {
             anyhow::bail!(tr!("error-no-ipsec-session"));
         };
 
-        let mut tun = TunDevice::new(name_hint, ipsec_session.address, Some(ipsec_session.netmask))?;
+        let mtu = self.params.effective_mtu(None);
+        let mut tun = device::open_device(
+            &self.params,
+            name_hint,
+            ipsec_session.address,
+            Some(ipsec_session.netmask),
+            mtu,
+        )
+        .await?;
         let tun_name = tun.name().to_owned();
 
-        self.setup_routing(&tun_name).await?;
+        info!("Tunnel device {} MTU is {}", tun_name, mtu);
+
+        // a persistent device is deleted only on explicit disconnect
+        if !tun.is_persistent() {
+            self.journal.record(NetworkChange::Device { name: tun_name.clone() });
+        }
 
         let session = self
             .session
@@ -200,13 +300,43 @@ impl VpnTunnel for TunIpsecTunnel {
 
         let resolver_config = crate::tunnel::ipsec::make_resolver_config(session, &self.params);
 
-        if !self.params.no_dns {
-            self.setup_dns(&resolver_config, &tun_name, false).await?;
-        }
+        if let Some(ref netns) = self.params.netns {
+            let config = NamespaceConfig::new(
+                &self.params,
+                netns,
+                &tun_name,
+                mtu,
+                Ipv4Net::with_netmask(session.address, session.netmask)?,
+                None,
+                None,
+                &resolver_config,
+            );
+            tunnel::setup_namespace(config, &self.journal).await?;
+        } else {
+            self.setup_routing(&tun_name).await?;
+
+            if !self.params.no_dns {
+                self.setup_dns(&resolver_config, &tun_name, false).await?;
+            }
 
-        let _ = platform::new_network_interface().configure_device(&tun_name).await;
+            tunnel::setup_app_routing(
+                &self.params,
+                &tun_name,
+                session.address,
+                None,
+                &resolver_config.dns_servers,
+                &self.journal,
+            )
+            .await?;
 
-        let (mut tun_sender, mut tun_receiver) = tun.take_inner().context("No tun device")?.into_framed().split();
+            tunnel::redirect_dns(&self.params, &tun_name, &resolver_config.dns_servers, &self.journal).await?;
+
+            if !self.params.no_device_config {
+                let _ = platform::new_network_interface().configure_device(&tun_name).await;
+            }
+        }
+
+        let (mut tun_sender, mut tun_receiver) = tun.split()?;
 
         self.tun_device = Some(tun);
 
@@ -245,25 +375,28 @@ impl VpnTunnel for TunIpsecTunnel {
 
         let esp_codec = esp_codec_in.clone();
 
-        tokio::spawn(async move {
-            while let Some(item) = snx_receiver.next().await {
-                let codec = esp_codec.clone();
-                let result = tokio::task::spawn_blocking(move || codec.read().unwrap().decode(&item));
+        tokio::spawn(
+            async move {
+                while let Some(item) = snx_receiver.next().await {
+                    let codec = esp_codec.clone();
+                    let result = tokio::task::spawn_blocking(move || codec.read().unwrap().decode(&item));
 
-                match result.await {
-                    Ok(Ok(packet)) => {
-                        let _ = tun_sender.send(packet.into()).await;
-                    }
-                    Ok(Err(e)) => {
-                        error!("Failed to decode packet: {}", e);
-                    }
-                    Err(e) => {
-                        error!("Failed to spawn blocking task: {}", e);
+                    match result.await {
+                        Ok(Ok(packet)) => {
+                            let _ = tun_sender.send(packet.into()).await;
+                        }
+                        Ok(Err(e)) => {
+                            error!("Failed to decode packet: {}", e);
+                        }
+                        Err(e) => {
+                            error!("Failed to spawn blocking task: {}", e);
+                        }
                     }
                 }
+                Ok::<_, anyhow::Error>(())
             }
-            Ok::<_, anyhow::Error>(())
-        });
+            .in_current_span(),
+        );
 
         let session = self.session.ipsec_session.as_ref().context("No IPSec session!")?;
 
@@ -277,21 +410,75 @@ impl VpnTunnel for TunIpsecTunnel {
             tunnel_type: self.params.tunnel_type,
             transport_type: session.transport_type,
             ip_address,
+            ipv6_address: None,
             dns_servers: resolver_config.dns_servers,
             search_domains: resolver_config.search_domains,
+            wins_servers: resolver_config.wins_servers,
             interface_name: tun_name.clone(),
             dns_configured: !self.params.no_dns,
+            dns_mode: (!self.params.no_dns).then(|| platform::effective_dns_mode(&self.params)),
+            dns_override: (!self.params.no_dns).then_some(self.params.dns_override),
             routing_configured: !self.params.no_routing,
             default_route: self.params.default_route,
+            dns_check: Vec::new(),
+            forward_source: self.params.forward_source,
+            peer_address: None,
+            stats: TunnelStats::default(),
+            hello_reply: None,
+            protocol_version: None,
+            profile: self.params.profile.clone(),
+            routes: self.journal.routes(),
+            traffic: None,
+            reauth_interval: None,
         };
+        hooks::run_up_script(&self.params, &info).await?;
+        platform::new_network_interface().update_connectivity().await;
+        self.connection_info = Some(info.clone());
+
+        // the route domains and the DNS self-test are resolved in the host namespace
+        if self.params.netns.is_none() {
+            let mut exclusions = self.params.exclude_routes.clone();
+            exclusions.push(Ipv4Net::from(self.gateway_address));
+
+            self.domain_routes = DomainRoutes::spawn(
+                self.params.clone(),
+                DomainRoutesConfig {
+                    device: tun_name.clone(),
+                    address: session.address,
+                    dns_servers: info.dns_servers.clone(),
+                    peer: None,
+                    exclusions,
+                },
+                self.journal.clone(),
+            );
+
+            dns_check::spawn(self.params.clone(), info.dns_servers.clone(), event_sender.clone());
+        }
+
         let _ = event_sender.send(TunnelEvent::Connected(info)).await;
         let ready = self.ready.clone();
 
         let esp_codec_in = esp_codec_in.clone();
         let esp_codec_out = esp_codec_out.clone();
 
-        let params = self.params.clone();
+        let server_info = server_info::get(&self.params).await?;
+
+        let keepalive_settings = KeepaliveSettings::new(
+            self.params.keepalive_jitter,
+            !self.params.no_keepalive && platform::get_features().await.ipsec_keepalive,
+            self.params.timeouts.keepalive,
+        );
+        let keepalive_runner = KeepaliveRunner::new(
+            server_info.connectivity_info.server_ip,
+            keepalive_settings.clone(),
+            ready.clone(),
+        );
+
+        let mut params = self.params.clone();
         let session = self.session.clone();
+        let journal = self.journal.clone();
+        let acquired = self.params.acquired_routes(self.subnets.iter().copied());
+        let gateway_address = self.gateway_address;
 
         let command_fut = async {
             while let Some(cmd) = command_receiver.recv().await {
@@ -328,7 +515,12 @@ impl VpnTunnel for TunIpsecTunnel {
                                 "IP address changed from {} to {}, replacing it for device {}",
                                 ip_address, new_address, tun_name
                             );
-                            if let Err(e) = platform::new_network_interface()
+                            if params.no_device_config || params.netns.is_some() {
+                                warn!(
+                                    "The address of {} is managed externally and must be changed to {}",
+                                    tun_name, new_address
+                                );
+                            } else if let Err(e) = platform::new_network_interface()
                                 .replace_ip_address(&tun_name, ip_address, new_address)
                                 .await
                             {
@@ -341,23 +533,28 @@ impl VpnTunnel for TunIpsecTunnel {
 
                         let _ = event_sender.send(TunnelEvent::Rekeyed(new_address)).await;
                     }
+                    TunnelCommand::Reconfigure(new_params) => {
+                        debug!("Applying the reloaded configuration");
+                        let routes = StaticRoutes {
+                            device: tun_name.clone(),
+                            address: ip_address.addr(),
+                            peer: None,
+                            exclusions: vec![Ipv4Net::from(gateway_address)],
+                            acquired: acquired.clone(),
+                        };
+                        routes.update(&params, &new_params, &journal).await;
+                        keepalive_settings.update(
+                            new_params.keepalive_jitter,
+                            !new_params.no_keepalive && platform::get_features().await.ipsec_keepalive,
+                        );
+                        params = new_params;
+                    }
                 }
             }
         };
         pin_mut!(command_fut);
 
-        let server_info = server_info::get(&params).await?;
-
-        let keepalive_runner = KeepaliveRunner::new(
-            server_info.connectivity_info.server_ip,
-            if self.params.no_keepalive || !platform::get_features().await.ipsec_keepalive {
-                Arc::new(AtomicBool::new(false))
-            } else {
-                ready.clone()
-            },
-        );
-
-        let ka_run = keepalive_runner.run();
+        let ka_run = keepalive_runner.run(event_sender.clone());
         pin_mut!(ka_run);
 
         let result = loop {
@@ -2,22 +2,24 @@ use std::{
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
     sync::{
         Arc,
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU8, Ordering},
     },
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::anyhow;
+use tokio::sync::mpsc;
 use tracing::{debug, trace, warn};
 
 use crate::{
     model::params::TunnelParams,
     platform::{self, NetworkInterface, UdpSocketExt},
+    tunnel::{KeepaliveReporter, TunnelEvent},
+    util,
 };
 
 const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(20);
 const KEEPALIVE_RETRY_INTERVAL: Duration = Duration::from_secs(5);
-const KEEPALIVE_TIMEOUT: Duration = Duration::from_secs(5);
 const KEEPALIVE_MAX_RETRIES: u32 = 5;
 
 // picked from wireshark logs
@@ -39,17 +41,43 @@ fn make_keepalive_packet() -> [u8; 84] {
     data
 }
 
+/// Keepalive options shared with the run loop of the tunnel, so that a reloaded configuration applies
+/// to the running keepalive.
+#[derive(Clone)]
+pub struct KeepaliveSettings {
+    jitter: Arc<AtomicU8>,
+    enabled: Arc<AtomicBool>,
+    timeout: Duration,
+}
+
+impl KeepaliveSettings {
+    pub fn new(jitter: u8, enabled: bool, timeout: Duration) -> Self {
+        Self {
+            jitter: Arc::new(AtomicU8::new(jitter)),
+            enabled: Arc::new(AtomicBool::new(enabled)),
+            timeout,
+        }
+    }
+
+    pub fn update(&self, jitter: u8, enabled: bool) {
+        self.jitter.store(jitter, Ordering::SeqCst);
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+}
+
 pub struct KeepaliveRunner {
     dst: Ipv4Addr,
+    settings: KeepaliveSettings,
     ready: Arc<AtomicBool>,
 }
 
 impl KeepaliveRunner {
-    pub fn new(dst: Ipv4Addr, ready: Arc<AtomicBool>) -> Self {
-        Self { dst, ready }
+    pub fn new(dst: Ipv4Addr, settings: KeepaliveSettings, ready: Arc<AtomicBool>) -> Self {
+        Self { dst, settings, ready }
     }
 
-    pub async fn run(&self) -> anyhow::Result<()> {
+    pub async fn run(&self, events: mpsc::Sender<TunnelEvent>) -> anyhow::Result<()> {
+        let mut reporter = KeepaliveReporter::new(events);
         let udp = tokio::net::UdpSocket::bind("0.0.0.0:0").await?;
 
         let target = SocketAddr::V4(SocketAddrV4::new(self.dst, TunnelParams::IPSEC_KEEPALIVE_PORT));
@@ -62,17 +90,19 @@ impl KeepaliveRunner {
 
         loop {
             if platform::new_network_interface().is_online() {
-                if self.ready.load(Ordering::SeqCst) {
+                if self.settings.enabled.load(Ordering::SeqCst) && self.ready.load(Ordering::SeqCst) {
                     trace!("Sending keepalive to {}", self.dst);
 
                     let data = make_keepalive_packet();
-                    let result = udp.send_receive(&data, KEEPALIVE_TIMEOUT, target).await;
+                    let result = udp.send_receive(&data, self.settings.timeout, target).await;
 
                     if let Ok(reply) = result {
-                        trace!("Received keepalive response from {}, size: {}", self.dst, reply.len());
+                        trace!(bytes = reply.len(), "Received keepalive response from {}", self.dst);
                         num_failures = 0;
+                        reporter.report(true).await;
                     } else {
                         num_failures += 1;
+                        reporter.report(false).await;
                         if num_failures >= KEEPALIVE_MAX_RETRIES {
                             warn!("Maximum number of keepalive retries reached, exiting");
                             break;
@@ -88,8 +118,13 @@ impl KeepaliveRunner {
                 platform::new_network_interface().poll_online();
             }
 
+            // retries are part of the failure detection window and are not randomized
             let interval = if num_failures == 0 {
-                KEEPALIVE_INTERVAL
+                util::jitter_duration(
+                    KEEPALIVE_INTERVAL,
+                    self.settings.jitter.load(Ordering::SeqCst),
+                    &mut rand::rng(),
+                )
             } else {
                 KEEPALIVE_RETRY_INTERVAL
             };
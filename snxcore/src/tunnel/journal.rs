@@ -0,0 +1,621 @@
+use std::{
+    fs,
+    net::Ipv4Addr,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+use ipnet::{IpNet, Ipv4Net};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::{
+    model::params::{DnsBackend, DnsMode, TunnelParams},
+    platform::{
+        self, AppRouteConfig, AppRouting, DnsRedirect, DnsRedirectConfig, Forwarding, ForwardingConfig, GatewayRoute,
+        Ipv6BlockConfig, Ipv6Blocker, MssClamp, NamespaceConfig, NetworkInterface, NetworkNamespace, ResolverConfig,
+        RoutingConfigurator, RoutingPolicy,
+    },
+    util,
+};
+
+const JOURNAL_PREFIX: &str = "snx-rs.";
+const JOURNAL_SUFFIX: &str = ".journal";
+
+// Bumped on incompatible changes of the file layout, the changes themselves are read one by one
+// so that a kind unknown to this version does not prevent the others from being reverted.
+const JOURNAL_VERSION: u32 = 1;
+
+// Kept on persistent storage: resolv.conf survives a power loss or a kernel panic, /var/run does not.
+#[cfg(unix)]
+fn journal_dir() -> PathBuf {
+    PathBuf::from("/var/lib/snx-rs")
+}
+
+#[cfg(windows)]
+fn journal_dir() -> PathBuf {
+    std::env::temp_dir()
+}
+
+/// Network configuration change made by the tunnel, recorded so that it can be reverted after a crash.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "kebab-case")]
+pub enum NetworkChange {
+    Device {
+        name: String,
+    },
+    Routes {
+        device: String,
+        address: Ipv4Addr,
+        routes: Vec<IpNet>,
+        #[serde(default)]
+        metric: Option<u32>,
+        #[serde(default)]
+        peer: Option<Ipv4Addr>,
+    },
+    DefaultRoute {
+        device: String,
+        address: Ipv4Addr,
+        destination: Ipv4Addr,
+        #[serde(default)]
+        metric: Option<u32>,
+        #[serde(default)]
+        policy: RoutingPolicy,
+    },
+    KeepaliveRoute {
+        device: String,
+        address: Ipv4Addr,
+        destination: Ipv4Addr,
+        #[serde(default)]
+        policy: RoutingPolicy,
+    },
+    ExcludedRoutes {
+        device: String,
+        address: Ipv4Addr,
+        routes: Vec<Ipv4Net>,
+    },
+    Dns {
+        device: String,
+        backend: DnsBackend,
+        mode: DnsMode,
+        #[serde(default)]
+        resolv_path: Option<PathBuf>,
+        config: ResolverConfig,
+    },
+    Forwarding {
+        config: ForwardingConfig,
+    },
+    Ipv6Block {
+        config: Ipv6BlockConfig,
+    },
+    MssClamp {
+        device: String,
+    },
+    GatewayRoute {
+        device: String,
+        address: Ipv4Addr,
+        route: GatewayRoute,
+    },
+    AppRoute {
+        config: AppRouteConfig,
+    },
+    // only recorded for a namespace created by the tunnel, a pre-existing one is left in place
+    Namespace {
+        name: String,
+    },
+    NamespaceDevice {
+        config: NamespaceConfig,
+    },
+    DnsRedirect {
+        config: DnsRedirectConfig,
+    },
+}
+
+#[async_trait]
+pub trait NetworkReverter {
+    async fn revert(&self, change: &NetworkChange) -> anyhow::Result<()>;
+}
+
+/// Reverts the changes using the platform network configurators.
+pub struct PlatformReverter;
+
+#[async_trait]
+impl NetworkReverter for PlatformReverter {
+    async fn revert(&self, change: &NetworkChange) -> anyhow::Result<()> {
+        match change {
+            NetworkChange::Device { name } => platform::new_network_interface().delete_device(name).await,
+            NetworkChange::Routes {
+                device,
+                address,
+                routes,
+                metric,
+                peer,
+            } => {
+                platform::new_routing_configurator(device, *address, *metric)
+                    .with_peer(*peer)
+                    .remove_routes(routes)
+                    .await
+            }
+            NetworkChange::DefaultRoute {
+                device,
+                address,
+                destination,
+                metric,
+                policy,
+            } => {
+                platform::new_routing_configurator(device, *address, *metric)
+                    .remove_default_route(*destination, policy)
+                    .await
+            }
+            NetworkChange::KeepaliveRoute {
+                device,
+                address,
+                destination,
+                policy,
+            } => {
+                platform::new_routing_configurator(device, *address, None)
+                    .remove_keepalive_route(*destination, policy)
+                    .await
+            }
+            NetworkChange::ExcludedRoutes {
+                device,
+                address,
+                routes,
+            } => {
+                platform::new_routing_configurator(device, *address, None)
+                    .remove_excluded_routes(routes)
+                    .await
+            }
+            NetworkChange::Dns {
+                device,
+                backend,
+                mode,
+                resolv_path,
+                config,
+            } => {
+                let params = TunnelParams {
+                    dns_backend: *backend,
+                    dns_mode: *mode,
+                    dns_resolv_path: resolv_path.clone(),
+                    ..Default::default()
+                };
+                platform::new_resolver_configurator(device, &params)?
+                    .cleanup(config)
+                    .await
+            }
+            NetworkChange::Forwarding { config } => platform::new_forwarding().disable(config).await,
+            NetworkChange::Ipv6Block { config } => platform::new_ipv6_blocker().unblock(config).await,
+            NetworkChange::MssClamp { device } => platform::new_mss_clamp().disable(device).await,
+            NetworkChange::GatewayRoute { device, address, route } => {
+                platform::new_routing_configurator(device, *address, None)
+                    .remove_gateway_route(route)
+                    .await
+            }
+            NetworkChange::AppRoute { config } => platform::new_app_routing().disable(config).await,
+            NetworkChange::Namespace { name } => platform::new_network_namespace().delete(name).await,
+            NetworkChange::NamespaceDevice { config } => platform::new_network_namespace().cleanup(config).await,
+            NetworkChange::DnsRedirect { .. } => platform::new_dns_redirect().disable().await,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JournalFile<'a> {
+    version: u32,
+    changes: &'a [NetworkChange],
+}
+
+// Version 0 was a bare list of the changes.
+fn parse_journal(data: &str) -> anyhow::Result<Vec<NetworkChange>> {
+    let (version, entries) = match serde_json::from_str(data)? {
+        serde_json::Value::Array(entries) => (0, entries),
+        serde_json::Value::Object(mut file) => {
+            let version = file
+                .get("version")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or_default();
+            let entries = match file.remove("changes") {
+                Some(serde_json::Value::Array(entries)) => entries,
+                _ => anyhow::bail!("No changes in the journal"),
+            };
+            (version, entries)
+        }
+        _ => anyhow::bail!("Invalid journal format"),
+    };
+
+    if version > u64::from(JOURNAL_VERSION) {
+        warn!(
+            "Network journal version {} is newer than {}, only the known changes are reverted",
+            version, JOURNAL_VERSION
+        );
+    }
+
+    Ok(entries
+        .into_iter()
+        .filter_map(|entry| match serde_json::from_value(entry) {
+            Ok(change) => Some(change),
+            Err(e) => {
+                warn!("Skipping unknown network change in the journal: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// Journal file of the connection profile.
+pub fn journal_path(params: &TunnelParams) -> PathBuf {
+    journal_dir().join(format!("{JOURNAL_PREFIX}{}{JOURNAL_SUFFIX}", params.profile_key()))
+}
+
+/// Persistent journal of the network changes made for a single connection profile.
+/// Every change is written to disk as soon as it is made, so that a crashed session can be reverted on the next start.
+pub struct NetworkJournal {
+    path: PathBuf,
+    changes: Mutex<Vec<NetworkChange>>,
+}
+
+impl NetworkJournal {
+    /// Journal of the connection profile, including the changes left behind by a previous run.
+    pub fn new(params: &TunnelParams) -> Self {
+        Self::with_path(journal_path(params))
+    }
+
+    /// Journal which is neither loaded nor saved, the dry run must not touch the journal of a running tunnel.
+    pub fn detached() -> Self {
+        Self {
+            path: PathBuf::new(),
+            changes: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn with_path<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_owned();
+        let changes = match fs::read_to_string(&path) {
+            // emptied instead of removed by a tunnel without the write access to the directory
+            Ok(data) if data.trim().is_empty() => Vec::new(),
+            Ok(data) => parse_journal(&data).unwrap_or_else(|e| {
+                warn!("Unable to read network journal {}: {}", path.display(), e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+
+        Self {
+            path,
+            changes: Mutex::new(changes),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.lock().unwrap().is_empty()
+    }
+
+    fn has_dns_changes(&self) -> bool {
+        self.changes
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|change| matches!(change, NetworkChange::Dns { .. }))
+    }
+
+    /// Routes through the tunnel device, the default route is given as the unspecified network.
+    pub fn routes(&self) -> Vec<IpNet> {
+        let mut routes = self
+            .changes
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|change| match change {
+                NetworkChange::Routes { routes, .. } => routes.clone(),
+                NetworkChange::DefaultRoute { .. } => vec![IpNet::V4(Ipv4Net::default())],
+                _ => Vec::new(),
+            })
+            .collect::<Vec<_>>();
+
+        // the routes are recorded again when they are refreshed after roaming
+        routes.sort();
+        routes.dedup();
+        routes
+    }
+
+    pub fn record(&self, change: NetworkChange) {
+        debug!("Recording network change: {:?}", change);
+
+        let mut changes = self.changes.lock().unwrap();
+        changes.push(change);
+
+        self.save(&changes);
+    }
+
+    /// Drop the routes removed while the tunnel is up, so that they are neither reported nor reverted.
+    pub fn forget_routes(&self, removed: &[IpNet]) {
+        let mut changes = self.changes.lock().unwrap();
+
+        for change in changes.iter_mut() {
+            if let NetworkChange::Routes { routes, .. } = change {
+                routes.retain(|route| !removed.contains(route));
+            }
+        }
+        changes.retain(|change| !matches!(change, NetworkChange::Routes { routes, .. } if routes.is_empty()));
+
+        self.save(&changes);
+    }
+
+    fn save(&self, changes: &[NetworkChange]) {
+        if self.path.as_os_str().is_empty() {
+            return;
+        }
+
+        let file = JournalFile {
+            version: JOURNAL_VERSION,
+            changes,
+        };
+
+        let result = serde_json::to_string(&file).map_err(Into::into).and_then(|data| {
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            util::write_private_file(&self.path, data)
+        });
+
+        if let Err(e) = result {
+            warn!("Unable to save network journal {}: {}", self.path.display(), e);
+        }
+    }
+
+    /// Revert the recorded changes in reverse order and remove the journal file.
+    pub async fn rollback<R: NetworkReverter + ?Sized>(&self, reverter: &R) {
+        let changes = std::mem::take(&mut *self.changes.lock().unwrap());
+
+        for change in changes.iter().rev() {
+            debug!("Reverting network change: {:?}", change);
+            if let Err(e) = reverter.revert(change).await {
+                debug!("Unable to revert network change: {}", e);
+            }
+        }
+
+        if fs::remove_file(&self.path).is_err() && self.path.exists() {
+            let _ = fs::write(&self.path, "");
+        }
+    }
+}
+
+/// Revert the network changes left behind by a previous run of the same profile which did not exit cleanly.
+pub async fn restore_stale(params: &TunnelParams) {
+    let journal = NetworkJournal::new(params);
+
+    if !journal.is_empty() {
+        restore_journal(&journal).await;
+    }
+}
+
+async fn restore_journal(journal: &NetworkJournal) {
+    if journal.has_dns_changes() {
+        warn!("A previous session left DNS modified, restoring");
+    }
+    warn!("Restoring network configuration from {}", journal.path.display());
+    journal.rollback(&PlatformReverter).await;
+}
+
+/// Revert the network changes left behind by all profiles.
+pub async fn restore_all_stale() {
+    let Ok(entries) = fs::read_dir(journal_dir()) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with(JOURNAL_PREFIX) && name.ends_with(JOURNAL_SUFFIX) {
+            restore_journal(&NetworkJournal::with_path(entry.path())).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::params::Ipv6BlockMode;
+
+    #[derive(Default)]
+    struct MockReverter(Mutex<Vec<NetworkChange>>);
+
+    #[async_trait]
+    impl NetworkReverter for MockReverter {
+        async fn revert(&self, change: &NetworkChange) -> anyhow::Result<()> {
+            self.0.lock().unwrap().push(change.clone());
+            anyhow::ensure!(!matches!(change, NetworkChange::Routes { .. }), "route is gone");
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_journal_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snx-rs.work.journal");
+        let address = "10.0.0.10".parse().unwrap();
+
+        let changes = vec![
+            NetworkChange::Device {
+                name: "snx-tun".to_owned(),
+            },
+            NetworkChange::Routes {
+                device: "snx-tun".to_owned(),
+                address,
+                routes: vec!["10.0.0.0/8".parse().unwrap(), "fd00::/64".parse().unwrap()],
+                metric: Some(100),
+                peer: Some("10.0.0.1".parse().unwrap()),
+            },
+            NetworkChange::GatewayRoute {
+                device: "snx-tun".to_owned(),
+                address,
+                route: GatewayRoute {
+                    destination: "203.0.113.5".parse().unwrap(),
+                    gateway: Some("192.168.1.1".parse().unwrap()),
+                    device: "eth0".to_owned(),
+                },
+            },
+            NetworkChange::DefaultRoute {
+                device: "snx-tun".to_owned(),
+                address,
+                destination: "192.168.1.1".parse().unwrap(),
+                metric: None,
+                policy: RoutingPolicy::default(),
+            },
+            NetworkChange::Dns {
+                device: "snx-tun".to_owned(),
+                backend: DnsBackend::Direct,
+                mode: DnsMode::Split,
+                resolv_path: Some("/run/snx-rs/resolv.conf".into()),
+                config: ResolverConfig {
+                    search_domains: vec!["example.com".to_owned()],
+                    dns_servers: vec!["10.0.0.1".parse().unwrap()],
+                    ..Default::default()
+                },
+            },
+            NetworkChange::Ipv6Block {
+                config: Ipv6BlockConfig {
+                    mode: Ipv6BlockMode::Sysctl,
+                    device: "snx-tun".to_owned(),
+                    interfaces: vec!["eth0".to_owned()],
+                },
+            },
+            NetworkChange::MssClamp {
+                device: "snx-tun".to_owned(),
+            },
+            NetworkChange::AppRoute {
+                config: AppRouteConfig {
+                    device: "snx-tun".to_owned(),
+                    table: 18234,
+                    mark: AppRouteConfig::MARK,
+                    cgroups: vec!["work.slice".to_owned()],
+                    uids: vec![1001],
+                },
+            },
+            NetworkChange::Namespace { name: "vpn".to_owned() },
+            NetworkChange::NamespaceDevice {
+                config: NamespaceConfig {
+                    name: "vpn".to_owned(),
+                    device: "snx-tun".to_owned(),
+                    mtu: 1350,
+                    address: "10.0.0.10/24".parse().unwrap(),
+                    peer: None,
+                    address_v6: None,
+                    routes: vec!["0.0.0.0/0".parse().unwrap()],
+                    resolver: ResolverConfig::default(),
+                },
+            },
+            NetworkChange::DnsRedirect {
+                config: DnsRedirectConfig {
+                    device: "snx-tun".to_owned(),
+                    server: Some("10.0.0.1".parse().unwrap()),
+                    server_v6: None,
+                    forward_source: None,
+                },
+            },
+        ];
+
+        {
+            let journal = NetworkJournal::with_path(&path);
+            assert!(journal.is_empty());
+            for change in &changes {
+                journal.record(change.clone());
+            }
+            // dropped without rollback, simulating a crash
+        }
+
+        let journal = NetworkJournal::with_path(&path);
+        assert!(!journal.is_empty());
+        assert_eq!(
+            journal.routes(),
+            vec![
+                "0.0.0.0/0".parse::<IpNet>().unwrap(),
+                "10.0.0.0/8".parse().unwrap(),
+                "fd00::/64".parse().unwrap()
+            ]
+        );
+
+        let reverter = MockReverter::default();
+        journal.rollback(&reverter).await;
+
+        let reverted = reverter.0.into_inner().unwrap();
+        assert_eq!(reverted, changes.into_iter().rev().collect::<Vec<_>>());
+
+        assert!(journal.is_empty());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_parse_journal() {
+        let device = NetworkChange::Device {
+            name: "snx-tun".to_owned(),
+        };
+
+        // written before the file was versioned
+        let legacy = r#"[{"change":"device","name":"snx-tun"}]"#;
+        assert_eq!(parse_journal(legacy).unwrap(), vec![device.clone()]);
+
+        // a change kind added by a later version is skipped, the rest is still reverted
+        let newer = r#"{"version":9,"changes":[{"change":"device","name":"snx-tun"},{"change":"tunnel-rule","id":5}]}"#;
+        assert_eq!(parse_journal(newer).unwrap(), vec![device]);
+
+        assert!(parse_journal(r#"{"version":1}"#).is_err());
+        assert!(parse_journal("garbage").is_err());
+    }
+
+    #[test]
+    fn test_emptied_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snx-rs.work.journal");
+        fs::write(&path, "").unwrap();
+
+        assert!(NetworkJournal::with_path(&path).is_empty());
+    }
+
+    #[test]
+    fn test_forget_routes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snx-rs.work.journal");
+        let journal = NetworkJournal::with_path(&path);
+
+        for routes in [vec!["10.0.0.0/8", "172.16.0.0/12"], vec!["192.168.0.0/16"]] {
+            journal.record(NetworkChange::Routes {
+                device: "snx-tun".to_owned(),
+                address: "10.0.0.10".parse().unwrap(),
+                routes: routes.into_iter().map(|route| route.parse().unwrap()).collect(),
+                metric: None,
+                peer: None,
+            });
+        }
+
+        journal.forget_routes(&["172.16.0.0/12".parse().unwrap(), "192.168.0.0/16".parse().unwrap()]);
+
+        let expected = vec!["10.0.0.0/8".parse::<IpNet>().unwrap()];
+        assert_eq!(journal.routes(), expected);
+        assert_eq!(NetworkJournal::with_path(&path).routes(), expected);
+        assert_eq!(journal.changes.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_journal_dns_after_crash() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("snx-rs.work.journal");
+
+        {
+            let journal = NetworkJournal::with_path(&path);
+            journal.record(NetworkChange::Dns {
+                device: "snx-tun".to_owned(),
+                backend: DnsBackend::Resolved,
+                mode: DnsMode::Full,
+                resolv_path: None,
+                config: ResolverConfig::default(),
+            });
+        }
+
+        let data = fs::read_to_string(&path).unwrap();
+        assert!(data.starts_with(&format!(r#"{{"version":{JOURNAL_VERSION},"#)));
+
+        let journal = NetworkJournal::with_path(&path);
+        assert!(journal.has_dns_changes());
+    }
+}
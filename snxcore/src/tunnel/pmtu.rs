@@ -0,0 +1,229 @@
+//! Path MTU discovery for the local traffic: packets which do not fit into the tunnel are answered with
+//! ICMP "fragmentation needed" or ICMPv6 "packet too big" instead of being dropped silently by the gateway.
+
+const IPV4_HEADER_LEN: usize = 20;
+const IPV6_HEADER_LEN: usize = 40;
+const ICMP_HEADER_LEN: usize = 8;
+
+const IPPROTO_ICMP: u8 = 1;
+const IPPROTO_ICMPV6: u8 = 58;
+
+const ICMP_DEST_UNREACH: u8 = 3;
+const ICMP_FRAG_NEEDED: u8 = 4;
+const ICMPV6_PACKET_TOO_BIG: u8 = 2;
+
+const IPV4_DONT_FRAGMENT: u16 = 0x4000;
+const IPV4_FRAGMENT_OFFSET: u16 = 0x1fff;
+
+// The error must itself fit into the IPv6 minimum MTU.
+const IPV6_MIN_MTU: usize = 1280;
+
+const REPLY_TTL: u8 = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PacketSize {
+    Fits,
+    /// The packet is dropped, with the ICMP error to write back to the device unless it must not be answered.
+    TooBig(Option<Vec<u8>>),
+}
+
+/// Check the packet read from the device against the tunnel MTU. IPv4 packets without the DF flag are let through,
+/// the gateway fragments them.
+pub fn check_size(packet: &[u8], mtu: u16) -> PacketSize {
+    if packet.len() <= usize::from(mtu) {
+        return PacketSize::Fits;
+    }
+
+    match packet.first().map(|b| b >> 4) {
+        Some(4) => check_ipv4(packet, mtu),
+        Some(6) => PacketSize::TooBig(packet_too_big(packet, mtu)),
+        _ => PacketSize::Fits,
+    }
+}
+
+fn check_ipv4(packet: &[u8], mtu: u16) -> PacketSize {
+    let header_len = usize::from(packet[0] & 0x0f) * 4;
+    if header_len < IPV4_HEADER_LEN || packet.len() < header_len {
+        return PacketSize::Fits;
+    }
+
+    let flags = u16::from_be_bytes([packet[6], packet[7]]);
+    if flags & IPV4_DONT_FRAGMENT == 0 {
+        return PacketSize::Fits;
+    }
+
+    // errors are never sent about the other ICMP errors or the non-first fragments
+    let is_icmp_error =
+        packet[9] == IPPROTO_ICMP && packet.get(header_len).is_some_and(|t| matches!(t, 3 | 4 | 5 | 11 | 12));
+    if is_icmp_error || flags & IPV4_FRAGMENT_OFFSET != 0 {
+        return PacketSize::TooBig(None);
+    }
+
+    PacketSize::TooBig(Some(frag_needed(packet, header_len, mtu)))
+}
+
+// The error comes from the destination of the packet: a local source address arriving on the device
+// would be dropped as a martian.
+fn frag_needed(packet: &[u8], header_len: usize, mtu: u16) -> Vec<u8> {
+    let quoted = &packet[..packet.len().min(header_len + 8)];
+    let total_len = IPV4_HEADER_LEN + ICMP_HEADER_LEN + quoted.len();
+
+    let mut reply = Vec::with_capacity(total_len);
+    reply.extend_from_slice(&[0x45, 0]);
+    reply.extend_from_slice(&(total_len as u16).to_be_bytes());
+    reply.extend_from_slice(&[0, 0, 0, 0, REPLY_TTL, IPPROTO_ICMP, 0, 0]);
+    reply.extend_from_slice(&packet[16..20]);
+    reply.extend_from_slice(&packet[12..16]);
+    let header_checksum = checksum(&reply, 0);
+    reply[10..12].copy_from_slice(&header_checksum.to_be_bytes());
+
+    reply.extend_from_slice(&[ICMP_DEST_UNREACH, ICMP_FRAG_NEEDED, 0, 0, 0, 0]);
+    reply.extend_from_slice(&mtu.to_be_bytes());
+    reply.extend_from_slice(quoted);
+    let icmp_checksum = checksum(&reply[IPV4_HEADER_LEN..], 0);
+    reply[IPV4_HEADER_LEN + 2..IPV4_HEADER_LEN + 4].copy_from_slice(&icmp_checksum.to_be_bytes());
+
+    reply
+}
+
+fn packet_too_big(packet: &[u8], mtu: u16) -> Option<Vec<u8>> {
+    if packet.len() < IPV6_HEADER_LEN {
+        return None;
+    }
+
+    // ICMPv6 errors have the types below 128, extension headers before them are not looked into
+    if packet[6] == IPPROTO_ICMPV6 && packet.get(IPV6_HEADER_LEN).is_some_and(|t| *t < 128) {
+        return None;
+    }
+
+    let quoted = &packet[..packet.len().min(IPV6_MIN_MTU - IPV6_HEADER_LEN - ICMP_HEADER_LEN)];
+    let payload_len = ICMP_HEADER_LEN + quoted.len();
+
+    let mut reply = Vec::with_capacity(IPV6_HEADER_LEN + payload_len);
+    reply.extend_from_slice(&[0x60, 0, 0, 0]);
+    reply.extend_from_slice(&(payload_len as u16).to_be_bytes());
+    reply.extend_from_slice(&[IPPROTO_ICMPV6, REPLY_TTL]);
+    reply.extend_from_slice(&packet[24..40]);
+    reply.extend_from_slice(&packet[8..24]);
+
+    reply.extend_from_slice(&[ICMPV6_PACKET_TOO_BIG, 0, 0, 0]);
+    reply.extend_from_slice(&u32::from(mtu).to_be_bytes());
+    reply.extend_from_slice(quoted);
+
+    // pseudo-header: addresses, upper-layer length and next header
+    let mut pseudo = ones_complement_sum(&reply[8..40], 0);
+    pseudo += payload_len as u32;
+    pseudo += u32::from(IPPROTO_ICMPV6);
+    let icmp_checksum = checksum(&reply[IPV6_HEADER_LEN..], pseudo);
+    reply[IPV6_HEADER_LEN + 2..IPV6_HEADER_LEN + 4].copy_from_slice(&icmp_checksum.to_be_bytes());
+
+    Some(reply)
+}
+
+fn ones_complement_sum(data: &[u8], initial: u32) -> u32 {
+    data.chunks(2).fold(initial, |sum, chunk| {
+        let word = u16::from_be_bytes([chunk[0], chunk.get(1).copied().unwrap_or_default()]);
+        sum + u32::from(word)
+    })
+}
+
+fn checksum(data: &[u8], initial: u32) -> u16 {
+    let mut sum = ones_complement_sum(data, initial);
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ipv4_packet(len: usize, dont_fragment: bool) -> Vec<u8> {
+        let mut packet = vec![0u8; len];
+        packet[0] = 0x45;
+        packet[2..4].copy_from_slice(&(len as u16).to_be_bytes());
+        if dont_fragment {
+            packet[6] = 0x40;
+        }
+        packet[8] = 64;
+        packet[9] = 6;
+        packet[12..16].copy_from_slice(&[10, 0, 0, 10]);
+        packet[16..20].copy_from_slice(&[10, 1, 2, 3]);
+        packet
+    }
+
+    fn ipv6_packet(len: usize) -> Vec<u8> {
+        let mut packet = vec![0u8; len];
+        packet[0] = 0x60;
+        packet[4..6].copy_from_slice(&((len - IPV6_HEADER_LEN) as u16).to_be_bytes());
+        packet[6] = 17;
+        packet[7] = 64;
+        packet[8] = 0xfd;
+        packet[23] = 10;
+        packet[24] = 0xfd;
+        packet[39] = 1;
+        packet
+    }
+
+    #[test]
+    fn test_ipv4_fits() {
+        assert_eq!(check_size(&ipv4_packet(1350, true), 1350), PacketSize::Fits);
+        // the gateway fragments it
+        assert_eq!(check_size(&ipv4_packet(1400, false), 1350), PacketSize::Fits);
+    }
+
+    #[test]
+    fn test_ipv4_frag_needed() {
+        let packet = ipv4_packet(1400, true);
+        let PacketSize::TooBig(Some(reply)) = check_size(&packet, 1350) else {
+            panic!("Expected ICMP reply");
+        };
+
+        assert_eq!(reply.len(), 20 + 8 + 28);
+        assert_eq!(checksum(&reply[..20], 0), 0);
+        assert_eq!(&reply[12..16], &[10, 1, 2, 3]);
+        assert_eq!(&reply[16..20], &[10, 0, 0, 10]);
+        assert_eq!(reply[9], IPPROTO_ICMP);
+
+        let icmp = &reply[20..];
+        assert_eq!(checksum(icmp, 0), 0);
+        assert_eq!(&icmp[..2], &[ICMP_DEST_UNREACH, ICMP_FRAG_NEEDED]);
+        assert_eq!(u16::from_be_bytes([icmp[6], icmp[7]]), 1350);
+        assert_eq!(&icmp[8..], &packet[..28]);
+    }
+
+    #[test]
+    fn test_ipv4_icmp_error_not_answered() {
+        let mut packet = ipv4_packet(1400, true);
+        packet[9] = IPPROTO_ICMP;
+        packet[20] = ICMP_DEST_UNREACH;
+        assert_eq!(check_size(&packet, 1350), PacketSize::TooBig(None));
+
+        // echo requests are answered
+        packet[20] = 8;
+        assert!(matches!(check_size(&packet, 1350), PacketSize::TooBig(Some(_))));
+    }
+
+    #[test]
+    fn test_ipv6_packet_too_big() {
+        let packet = ipv6_packet(1500);
+        let PacketSize::TooBig(Some(reply)) = check_size(&packet, 1350) else {
+            panic!("Expected ICMPv6 reply");
+        };
+
+        assert_eq!(reply.len(), IPV6_MIN_MTU);
+        assert_eq!(reply[6], IPPROTO_ICMPV6);
+        assert_eq!(&reply[8..24], &packet[24..40]);
+        assert_eq!(&reply[24..40], &packet[8..24]);
+
+        let icmp = &reply[IPV6_HEADER_LEN..];
+        assert_eq!(icmp[0], ICMPV6_PACKET_TOO_BIG);
+        assert_eq!(u32::from_be_bytes([icmp[4], icmp[5], icmp[6], icmp[7]]), 1350);
+
+        let pseudo = ones_complement_sum(&reply[8..40], 0) + icmp.len() as u32 + u32::from(IPPROTO_ICMPV6);
+        assert_eq!(checksum(icmp, pseudo), 0);
+
+        assert_eq!(check_size(&ipv6_packet(1350), 1350), PacketSize::Fits);
+    }
+}
@@ -1,36 +1,54 @@
 use std::{
-    net::Ipv4Addr,
+    collections::VecDeque,
+    net::{IpAddr, Ipv4Addr},
     sync::{
         Arc,
-        atomic::{AtomicI64, Ordering},
+        atomic::{AtomicI64, AtomicU8, Ordering},
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use anyhow::{Context, anyhow};
 use chrono::Local;
 use codec::{SslPacketCodec, SslPacketType};
 use futures::{
-    SinkExt, StreamExt, TryStreamExt,
+    FutureExt, SinkExt, StreamExt, TryStreamExt,
     channel::mpsc::{self, Receiver, Sender},
+    future::BoxFuture,
     pin_mut,
 };
 use i18n::tr;
-use ipnet::Ipv4Net;
+use ipnet::{IpNet, Ipv4Net, Ipv6Net};
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_native_tls::native_tls::{Certificate, TlsConnector};
-use tracing::{debug, trace, warn};
+use tracing::{Instrument, debug, info, trace, warn};
 
 use crate::{
     ccc::CccHttpClient,
     model::{
-        ConnectionInfo, VpnSession,
-        params::{TransportType, TunnelParams},
-        proto::{ClientHelloData, HelloReply, HelloReplyData, OfficeMode, OptionalRequest},
+        ConnectionInfo, TunnelStats, VpnSession,
+        params::{AddressConflictAction, TransportType, TunnelParams},
+        proto::{
+            ClientHelloData, ControlPacket, DisconnectRequestData, HelloReplyData, HelloResponse,
+            LEGACY_PROTOCOL_MINOR_VERSION, PROTOCOL_MINOR_VERSION,
+        },
+    },
+    platform::{
+        self, Forwarding, ForwardingConfig, GatewayRoute, KillSwitch, KillSwitchConfig, NamespaceConfig,
+        NetworkInterface, ResolverConfig, RoutingConfigurator, RoutingPolicy, TcpSocketExt, new_resolver_configurator,
+    },
+    proxy,
+    tunnel::{
+        self, StaticRoutes, TunnelCommand, TunnelEvent, VpnTunnel,
+        device::{self, PacketDevice, TunDevice},
+        dns_check,
+        domain_routes::{DomainRoutes, DomainRoutesConfig},
+        dry_run::DryRun,
+        hooks,
+        journal::{NetworkChange, NetworkJournal, PlatformReverter},
+        pmtu::{self, PacketSize},
+        ssl::keepalive::KeepaliveRunner,
     },
-    platform::{self, NetworkInterface, ResolverConfig, RoutingConfigurator, new_resolver_configurator},
-    sexpr::SExpression,
-    tunnel::{TunnelCommand, TunnelEvent, VpnTunnel, device::TunDevice, ssl::keepalive::KeepaliveRunner},
     util,
 };
 
@@ -41,10 +59,20 @@ pub mod keepalive;
 const REAUTH_LEEWAY: Duration = Duration::from_secs(60);
 const SEND_TIMEOUT: Duration = Duration::from_secs(120);
 const CHANNEL_SIZE: usize = 1024;
+const HELLO_RETRY_DELAY: Duration = Duration::from_secs(2);
+const HELLO_RECONNECT_DELAY: Duration = Duration::from_secs(3);
+const EXPIRY_WARNINGS: &[Duration] = &[Duration::from_secs(600), Duration::from_secs(60)];
+const ROAM_BUFFER_SIZE: usize = 256;
+const DISCONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const HELLO_CHANNEL_SIZE: usize = 4;
 
 pub type PacketSender = Sender<SslPacketType>;
 pub type PacketReceiver = Receiver<SslPacketType>;
 
+// Opens a new connection to the gateway for the reconnects, the tests replace it with in-memory channels.
+type ChannelConnector =
+    Box<dyn Fn(Arc<TunnelParams>) -> BoxFuture<'static, anyhow::Result<(PacketSender, PacketReceiver)>> + Send + Sync>;
+
 fn make_channel<S>(stream: S) -> (PacketSender, PacketReceiver)
 where
     S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
@@ -66,7 +94,7 @@ where
         futures::future::select(to_wire, from_wire).await;
     };
 
-    tokio::spawn(channel);
+    tokio::spawn(channel.in_current_span());
 
     (tx_out, rx_in)
 }
@@ -80,36 +108,44 @@ pub(crate) struct SslTunnel {
     sender: PacketSender,
     receiver: Option<PacketReceiver>,
     keepalive_counter: Arc<AtomicI64>,
+    keepalive_jitter: Arc<AtomicU8>,
     tun_device: Option<TunDevice>,
+    ipv6_address: Option<Ipv6Net>,
+    peer_address: Option<Ipv4Addr>,
+    mtu: u16,
+    journal: Arc<NetworkJournal>,
+    connection_info: Option<ConnectionInfo>,
+    domain_routes: Option<DomainRoutes>,
+    conflicting_networks: Vec<Ipv4Net>,
+    gateway_route: Option<GatewayRoute>,
     hello_reply: HelloReplyData,
+    hello_reply_raw: Option<serde_json::Value>,
+    protocol_minor_version: u32,
     terminate_sender: Option<Sender<()>>,
+    connector: ChannelConnector,
 }
 
 impl SslTunnel {
     pub(crate) async fn create(params: Arc<TunnelParams>, session: Arc<VpnSession>) -> anyhow::Result<Self> {
-        let tcp = tokio::net::TcpStream::connect((params.server_name.as_str(), 443)).await?;
-
-        let mut builder = TlsConnector::builder();
-
-        for ca_cert in &params.ca_cert {
-            let data = tokio::fs::read(ca_cert).await?;
-            let cert = Certificate::from_pem(&data).or_else(|_| Certificate::from_der(&data))?;
-            builder.add_root_certificate(cert);
-        }
-
-        if params.ignore_server_cert {
-            warn!("Disabling all certificate checks!!!");
-            builder.danger_accept_invalid_certs(true);
-        }
-
-        let tls: tokio_native_tls::TlsConnector = builder.build()?.into();
-        let stream = tls.connect(params.server_name.as_str(), tcp).await?;
-
-        let (sender, receiver) = make_channel(stream);
+        let (sender, receiver) = Self::connect(&params).await?;
+        let journal = Arc::new(if params.dry_run {
+            NetworkJournal::detached()
+        } else {
+            NetworkJournal::new(&params)
+        });
 
-        debug!("Tunnel connected");
+        Ok(Self::with_channel(params, session, sender, receiver, journal))
+    }
 
-        Ok(Self {
+    fn with_channel(
+        params: Arc<TunnelParams>,
+        session: Arc<VpnSession>,
+        sender: PacketSender,
+        receiver: PacketReceiver,
+        journal: Arc<NetworkJournal>,
+    ) -> Self {
+        Self {
+            keepalive_jitter: Arc::new(AtomicU8::new(params.keepalive_jitter)),
             params,
             session,
             auth_timeout: Duration::default(),
@@ -119,219 +155,205 @@ impl SslTunnel {
             receiver: Some(receiver),
             keepalive_counter: Arc::new(AtomicI64::default()),
             tun_device: None,
+            ipv6_address: None,
+            peer_address: None,
+            mtu: TunnelParams::DEFAULT_MTU,
+            journal,
+            domain_routes: None,
+            connection_info: None,
+            conflicting_networks: Vec::new(),
+            gateway_route: None,
             hello_reply: HelloReplyData::default(),
+            hello_reply_raw: None,
+            protocol_minor_version: PROTOCOL_MINOR_VERSION,
             terminate_sender: None,
-        })
-    }
-
-    fn new_hello_request(&self, keep_address: bool) -> ClientHelloData {
-        ClientHelloData {
-            client_version: 1,
-            protocol_version: 1,
-            protocol_minor_version: 1,
-            office_mode: OfficeMode {
-                ipaddr: self.ip_address.clone(),
-                keep_address: Some(keep_address),
-                dns_servers: None,
-                dns_suffix: None,
-            },
-            optional: Some(OptionalRequest {
-                client_type: "4".to_string(),
-            }),
-            cookie: self.session.active_key().to_owned(),
+            connector: Box::new(|params: Arc<TunnelParams>| async move { Self::connect(&params).await }.boxed()),
         }
     }
 
-    async fn client_hello(&mut self) -> anyhow::Result<HelloReplyData> {
-        let req = self.new_hello_request(false);
-        trace!("Hello request: {:?}", req);
-        self.send(req).await?;
-
-        let receiver = self.receiver.as_mut().unwrap();
-
-        let reply = receiver.next().await.context("Channel closed!")?;
-
-        let reply = match reply {
-            SslPacketType::Control(expr) => {
-                trace!("Hello reply: {:?}", expr);
-                if matches!(&expr, SExpression::Object(Some(name), _) if name == "disconnect") {
-                    anyhow::bail!(tr!("error-tunnel-disconnected", message = expr));
-                }
-                let hello_reply = expr.try_into::<HelloReply>()?;
-                self.ip_address.clone_from(&hello_reply.data.office_mode.ipaddr);
-                self.auth_timeout = Duration::from_secs(hello_reply.data.timeouts.authentication) - REAUTH_LEEWAY;
-                self.keepalive = Duration::from_secs(hello_reply.data.timeouts.keepalive);
-                hello_reply
+    // Marked packets bypass the default route table, otherwise the tunnel would be routed through itself.
+    // Behind a proxy the socket is connected to the proxy, which opens the tunnel to the gateway.
+    async fn connect_tcp(params: &TunnelParams) -> anyhow::Result<tokio::net::TcpStream> {
+        let connect = async {
+            // nothing is routed through the tunnel in the dry run, and setting the mark needs the privileges
+            let Some(fwmark) = params.fwmark.filter(|_| !params.dry_run) else {
+                return proxy::connect(params).await;
+            };
+
+            let proxy = proxy::select(params);
+            let address = match proxy {
+                Some(ref server) => util::resolve_ipv4_host(&server.address())?,
+                None => util::resolve_ipv4_host(&params.server_address())?,
+            };
+            let port = proxy.as_ref().map_or(params.server_port(), |server| server.port);
+
+            let socket = tokio::net::TcpSocket::new_v4()?;
+            socket.set_mark(fwmark)?;
+            let mut stream = socket.connect((address, port).into()).await?;
+
+            if let Some(server) = proxy {
+                server
+                    .open_tunnel(&mut stream, params.server_host(), params.server_port())
+                    .await?;
             }
-            _ => anyhow::bail!(tr!("error-unexpected-reply")),
+            anyhow::Ok(stream)
         };
 
-        Ok(reply.data)
+        tokio::time::timeout(params.timeouts.connect, connect).await?
     }
 
-    async fn send<P>(&mut self, packet: P) -> anyhow::Result<()>
-    where
-        P: Into<SslPacketType>,
-    {
-        tokio::time::timeout(SEND_TIMEOUT, self.sender.send(packet.into())).await??;
+    async fn connect(params: &TunnelParams) -> anyhow::Result<(PacketSender, PacketReceiver)> {
+        let tcp = Self::connect_tcp(params).await?;
 
-        Ok(())
-    }
+        let mut builder = TlsConnector::builder();
 
-    async fn cleanup(&mut self) {
-        if let Some(mut sender) = self.terminate_sender.take() {
-            let _ = sender.send(()).await;
+        for ca_cert in &params.ca_cert {
+            let data = tokio::fs::read(ca_cert).await?;
+            let cert = Certificate::from_pem(&data).or_else(|_| Certificate::from_der(&data))?;
+            builder.add_root_certificate(cert);
         }
 
-        if let Some(device) = self.tun_device.take() {
-            let ipaddr = self.hello_reply.office_mode.ipaddr.parse().unwrap();
-            let configurator = platform::new_routing_configurator(device.name(), ipaddr);
-
-            if let Ok(dest_ip) = util::resolve_ipv4_host(&format!("{}:443", self.params.server_name)) {
-                let _ = configurator.remove_default_route(dest_ip).await;
-            }
-            if !self.params.no_dns {
-                let config = self.make_resolver_config().await;
-                let _ = self.setup_dns(config, device.name(), true).await;
-            }
-            let _ = platform::new_network_interface().delete_device(device.name()).await;
-            debug!("Signing out");
-            let client = CccHttpClient::new(self.params.clone(), Some(self.session.clone()));
-            let _ = client.signout().await;
+        if params.ignore_server_cert {
+            warn!("Disabling all certificate checks!!!");
+            builder.danger_accept_invalid_certs(true);
         }
-    }
-
-    pub async fn setup_routing(&self, dev_name: &str) -> anyhow::Result<()> {
-        let ipaddr = self.hello_reply.office_mode.ipaddr.parse()?;
-        let configurator = platform::new_routing_configurator(dev_name, ipaddr);
-
-        let dest_ip = util::resolve_ipv4_host(&format!("{}:443", self.params.server_name))?;
-
-        let mut subnets = self.params.add_routes.clone();
 
-        if !self.params.no_routing {
-            if self.params.default_route {
-                configurator.setup_default_route(dest_ip).await?;
-            } else {
-                subnets.extend(util::ranges_to_subnets(&self.hello_reply.range));
-            }
-        }
+        let tls: tokio_native_tls::TlsConnector = builder.build()?.into();
+        let stream = tokio::time::timeout(params.timeouts.handshake, tls.connect(params.server_host(), tcp)).await??;
 
-        subnets.retain(|s| !s.contains(&dest_ip));
+        debug!("Tunnel connected");
 
-        if !subnets.is_empty() {
-            let _ = configurator.add_routes(&subnets, &self.params.ignore_routes).await;
-        }
+        Ok(make_channel(stream))
+    }
 
+    async fn reconnect(&mut self) -> anyhow::Result<()> {
+        let (sender, receiver) = (self.connector)(self.params.clone()).await?;
+        self.sender = sender;
+        self.receiver = Some(receiver);
         Ok(())
     }
 
-    async fn make_resolver_config(&self) -> ResolverConfig {
-        let features = platform::get_features().await;
+    fn new_hello_request(&self, keep_address: bool) -> ClientHelloData {
+        ClientHelloData::new(
+            &self.params,
+            &self.ip_address,
+            keep_address,
+            self.session.active_key(),
+            self.protocol_minor_version,
+        )
+    }
 
-        let acquired_domains = self
-            .hello_reply
-            .office_mode
-            .dns_suffix
-            .as_ref()
-            .map(|s| s.0.as_slice())
-            .unwrap_or_default()
-            .iter()
-            .map(|s| {
-                if self.params.set_routing_domains && features.split_dns {
-                    format!("~{}", s)
-                } else {
-                    s.clone()
-                }
-            })
-            .collect::<Vec<_>>();
+    async fn client_hello(&mut self, keep_address: bool) -> anyhow::Result<HelloReplyData> {
+        let mut retries = 0;
+        let mut reconnects = 0;
 
-        let search_domains = acquired_domains
-            .iter()
-            .chain(self.params.search_domains.iter())
-            .filter(|s| {
-                !s.is_empty()
-                    && !self
-                        .params
-                        .ignore_search_domains
-                        .iter()
-                        .any(|d| d.to_lowercase() == s.trim_matches('~').to_lowercase())
-            })
-            .cloned()
-            .collect::<Vec<_>>();
+        loop {
+            let reply = match self.hello_exchange(keep_address).await? {
+                HelloResponse::Reply(reply) => reply,
+                HelloResponse::Reconnect(reason) => {
+                    if reconnects >= self.params.retries.hello_reconnect {
+                        anyhow::bail!(tr!("error-gateway-reconnect", message = reason));
+                    }
+                    reconnects += 1;
+                    warn!(
+                        "Gateway requested reconnect: {}, attempt {} of {}",
+                        reason, reconnects, self.params.retries.hello_reconnect
+                    );
+                    tokio::time::sleep(HELLO_RECONNECT_DELAY).await;
+                    self.reconnect().await?;
+                    continue;
+                }
+                HelloResponse::Rejected(reason) => {
+                    if self.protocol_minor_version == LEGACY_PROTOCOL_MINOR_VERSION {
+                        anyhow::bail!(tr!("error-hello-rejected", message = reason));
+                    }
+                    warn!(
+                        "Gateway rejected protocol minor version {}: {}, falling back to {}",
+                        self.protocol_minor_version, reason, LEGACY_PROTOCOL_MINOR_VERSION
+                    );
+                    self.protocol_minor_version = LEGACY_PROTOCOL_MINOR_VERSION;
+                    self.reconnect().await?;
+                    continue;
+                }
+            };
 
-        let dns_servers = self
-            .hello_reply
-            .office_mode
-            .dns_servers
-            .clone()
-            .unwrap_or_default()
-            .iter()
-            .chain(self.params.dns_servers.iter())
-            .filter(|s| !self.params.ignore_dns_servers.iter().any(|d| *d == **s))
-            .cloned()
-            .collect::<Vec<_>>();
+            if has_usable_address(&reply) {
+                self.accept_hello_reply(&reply);
+                info!("Negotiated protocol version {}", reply.protocol_version_string());
+                return Ok(reply);
+            }
 
-        ResolverConfig {
-            search_domains,
-            dns_servers,
+            warn!("Gateway assigned unusable address: {}", reply.office_mode.ipaddr);
+            if let Some(ref error) = reply.error {
+                warn!("Gateway error: {}", error);
+            }
+            if retries >= self.params.retries.hello {
+                anyhow::bail!(tr!("error-no-office-mode-address", address = reply.office_mode.ipaddr));
+            }
+            retries += 1;
+            debug!("Retrying hello, attempt {} of {}", retries, self.params.retries.hello);
+            tokio::time::sleep(HELLO_RETRY_DELAY).await;
         }
     }
 
-    pub async fn setup_dns(&self, config: ResolverConfig, dev_name: &str, cleanup: bool) -> anyhow::Result<()> {
-        let resolver = new_resolver_configurator(dev_name)?;
-
-        if cleanup {
-            resolver.cleanup(&config).await?;
-        } else {
-            resolver.configure(&config).await?;
+    fn accept_hello_reply(&mut self, reply: &HelloReplyData) {
+        self.ip_address.clone_from(&reply.office_mode.ipaddr);
+        self.auth_timeout = reauth_timeout(
+            Duration::from_secs(reply.timeouts.authentication),
+            self.params.reauth_interval,
+        );
+        self.keepalive = Duration::from_secs(reply.timeouts.keepalive);
+
+        match reply.timeouts.om_lease {
+            Some(lease) => info!(
+                "Office mode address {} is leased for {} seconds",
+                reply.office_mode.ipaddr, lease
+            ),
+            None => debug!("Gateway did not send the office mode lease time"),
         }
-
-        Ok(())
     }
-}
-
-#[async_trait::async_trait]
-impl VpnTunnel for SslTunnel {
-    async fn run(
-        mut self: Box<Self>,
-        mut command_receiver: tokio::sync::mpsc::Receiver<TunnelCommand>,
-        event_sender: tokio::sync::mpsc::Sender<TunnelEvent>,
-    ) -> anyhow::Result<()> {
-        debug!("Running SSL tunnel for session {}", self.session.ccc_session_id);
-
-        let reply = self.client_hello().await?;
-        trace!("Hello reply: {:?}", reply);
-
-        self.hello_reply = reply;
 
-        let ip_address = self.hello_reply.office_mode.ipaddr.parse()?;
-        let netmask = self.hello_reply.optional.as_ref().and_then(|o| o.subnet.parse().ok());
+    async fn hello_exchange(&mut self, keep_address: bool) -> anyhow::Result<HelloResponse> {
+        let req = self.new_hello_request(keep_address);
+        trace!("Hello request: {:?}", req);
+        self.send(req).await?;
 
-        let name_hint = self
-            .params
-            .if_name
-            .as_deref()
-            .unwrap_or(TunnelParams::DEFAULT_SSL_IF_NAME);
+        let receiver = self.receiver.as_mut().unwrap();
 
-        let mut tun = TunDevice::new(name_hint, ip_address, netmask)?;
-        let tun_name = tun.name().to_owned();
+        let reply = tokio::time::timeout(self.params.timeouts.hello, receiver.next())
+            .await?
+            .context("Channel closed!")?;
 
-        self.setup_routing(&tun_name).await?;
+        let SslPacketType::Control(packet) = reply else {
+            anyhow::bail!(tr!("error-unexpected-reply"));
+        };
+        trace!("Hello reply: {:?}", packet);
 
-        let resolver_config = self.make_resolver_config().await;
+        let raw = match packet {
+            ControlPacket::HelloReply(ref raw) => Some(raw.clone()),
+            _ => None,
+        };
 
-        if !self.params.no_dns {
-            self.setup_dns(resolver_config.clone(), &tun_name, false).await?;
+        if let Some(mut redacted) = raw.clone().filter(|_| self.params.dump_hello) {
+            util::redact_json(&mut redacted);
+            info!("Hello reply: {}", serde_json::to_string_pretty(&redacted)?);
         }
 
-        let _ = platform::new_network_interface().configure_device(&tun_name).await;
-
-        let (mut tun_sender, mut tun_receiver) = tun.take_inner().context("No tun device")?.into_framed().split();
+        let response = HelloResponse::from_packet(packet, self.protocol_minor_version)?;
+        if let HelloResponse::Reply(_) = response {
+            self.hello_reply_raw = raw;
+        }
 
-        self.tun_device = Some(tun);
+        Ok(response)
+    }
 
-        let mut snx_receiver = self.receiver.take().unwrap();
+    fn spawn_receiver(
+        &mut self,
+        mut tun_sender: Sender<Vec<u8>>,
+        mut error_sender: Sender<anyhow::Error>,
+        mut hello_sender: Sender<serde_json::Value>,
+    ) -> anyhow::Result<()> {
+        let mut snx_receiver = self.receiver.take().context("No receiver")?;
 
         let keepalive_counter = self.keepalive_counter.clone();
 
@@ -341,14 +363,20 @@ impl VpnTunnel for SslTunnel {
         let fut = async move {
             while let Some(item) = snx_receiver.next().await {
                 match item {
-                    SslPacketType::Control(expr) => {
-                        debug!("Control packet received");
-                        match expr {
-                            SExpression::Object(Some(name), _) if name == "keepalive" => {
+                    SslPacketType::Control(packet) => {
+                        debug!("Control packet received: {}", packet.name());
+                        match packet {
+                            ControlPacket::Keepalive(_) => {
                                 let _ = keepalive_counter
                                     .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v| (v > 0).then_some(v - 1));
                             }
-                            _ => {}
+                            // the reply to the lease refresh, handled by the data path
+                            ControlPacket::HelloReply(reply) => hello_sender.send(reply).await?,
+                            ControlPacket::Disconnect(data) => {
+                                return Err(anyhow!(tr!("error-tunnel-disconnected", message = data)));
+                            }
+                            ControlPacket::Error(data) => return Err(data.to_error()),
+                            other => debug!("Ignoring the {} packet", other.name()),
                         }
                     }
                     SslPacketType::Data(data) => {
@@ -360,73 +388,1632 @@ impl VpnTunnel for SslTunnel {
             Ok::<_, anyhow::Error>(())
         };
 
-        tokio::spawn(async move {
-            tokio::select! {
-                _ = terminate_receiver.next() => Ok::<_, anyhow::Error>(()),
-                res = fut => res,
+        tokio::spawn(
+            async move {
+                tokio::select! {
+                    _ = terminate_receiver.next() => {},
+                    res = fut => {
+                        if let Err(e) = res {
+                            let _ = error_sender.send(e).await;
+                        }
+                    }
+                }
             }
-        });
+            .in_current_span(),
+        );
 
-        let info = ConnectionInfo {
-            since: Some(Local::now()),
-            server_name: self.params.server_name.clone(),
-            username: self.session.username.clone().unwrap_or_default(),
-            login_type: self.params.login_type.clone(),
-            tunnel_type: self.params.tunnel_type,
-            transport_type: TransportType::Tcpt,
-            ip_address: Ipv4Net::with_netmask(ip_address, netmask.unwrap_or(Ipv4Addr::new(255, 255, 255, 255)))?,
-            dns_servers: resolver_config.dns_servers,
-            search_domains: resolver_config.search_domains,
-            interface_name: tun_name,
-            dns_configured: !self.params.no_dns,
-            routing_configured: !self.params.no_routing,
-            default_route: self.params.default_route,
+        Ok(())
+    }
+
+    fn start_keepalive(&self, event_sender: &tokio::sync::mpsc::Sender<TunnelEvent>) -> BoxFuture<'static, ()> {
+        let keepalive_runner = KeepaliveRunner::new(
+            self.keepalive,
+            self.keepalive_jitter.clone(),
+            self.sender.clone(),
+            self.keepalive_counter.clone(),
+            event_sender.clone(),
+        );
+        Box::pin(async move { keepalive_runner.run().await })
+    }
+
+    // The hello is repeated in the middle of the lease, or after a quarter of it when the previous
+    // refresh was not answered.
+    fn start_lease_refresh(&self, retry: bool) -> BoxFuture<'static, ()> {
+        let Some(lease) = self.hello_reply.timeouts.om_lease.filter(|lease| *lease > 0) else {
+            return Box::pin(futures::future::pending());
         };
 
-        let _ = event_sender.send(TunnelEvent::Connected(info)).await;
+        let delay = Duration::from_secs(lease) / if retry { 4 } else { 2 };
+        debug!("Refreshing the office mode address lease in {:?}", delay);
+
+        Box::pin(tokio::time::sleep(delay))
+    }
+
+    // Forward the packets between the device and the gateway until the tunnel is terminated or fails.
+    // The device is already configured, only its packets and name are used, so that the data path
+    // can be exercised without a tun device.
+    async fn run_data_path<D: PacketDevice>(
+        &mut self,
+        device: &mut D,
+        command_receiver: &mut tokio::sync::mpsc::Receiver<TunnelCommand>,
+        event_sender: &tokio::sync::mpsc::Sender<TunnelEvent>,
+    ) -> anyhow::Result<()> {
+        let tun_name = device.name().to_owned();
+
+        let (tun_sender, mut tun_receiver) = device.split()?;
+
+        let (mut tun_data_sender, tun_data_receiver) = mpsc::channel(CHANNEL_SIZE);
+        tokio::spawn(tun_data_receiver.map(Ok).forward(tun_sender).in_current_span());
+
+        let (error_sender, mut error_receiver) = mpsc::channel(1);
+        let (hello_sender, mut hello_receiver) = mpsc::channel(HELLO_CHANNEL_SIZE);
 
-        let command_fut = command_receiver.recv();
-        pin_mut!(command_fut);
+        self.spawn_receiver(tun_data_sender.clone(), error_sender.clone(), hello_sender.clone())?;
 
-        let keepalive_runner =
-            KeepaliveRunner::new(self.keepalive, self.sender.clone(), self.keepalive_counter.clone());
-        let ka_run = keepalive_runner.run();
-        pin_mut!(ka_run);
+        let mut ka_run = self.start_keepalive(event_sender);
+        let mut lease_run = self.start_lease_refresh(false);
 
-        let result = loop {
+        let network = platform::new_network_interface();
+        let mut network_changes = network.network_changes();
+        let mut default_ip = if self.params.reconnect_on_network_change {
+            network.get_default_ip().await.ok()
+        } else {
+            None
+        };
+
+        // the session ends at the re-authentication timeout, the tunnel is then set up again with a new login
+        let expiry_run = expiry_watcher(self.auth_timeout, event_sender.clone());
+        pin_mut!(expiry_run);
+
+        loop {
             tokio::select! {
-                event = &mut command_fut => match event {
+                command = command_receiver.recv() => match command {
                     Some(TunnelCommand::Terminate(_)) | None => {
+                        self.disconnect().await;
                         break Ok(());
                     }
-                    _ => {}
+                    Some(TunnelCommand::Reconfigure(params)) => self.reconfigure(&tun_name, params).await,
+                    Some(TunnelCommand::ReKey(_)) => {}
                 },
+                Some(e) = error_receiver.next() => {
+                    warn!("Tunnel receiver failed: {}", e);
+                    break Err(e);
+                }
+                () = &mut expiry_run => {
+                    warn!("Session expired, disconnecting");
+                    self.disconnect().await;
+                    break Ok(());
+                }
                 () = &mut ka_run => {
                     warn!("Keepalive failed, exiting");
                     break Err(anyhow!(tr!("error-keepalive-failed")));
                 }
+                () = &mut lease_run => {
+                    debug!("Refreshing the office mode address lease");
+                    let request = self.new_hello_request(true);
+                    self.send(request).await?;
+                    lease_run = self.start_lease_refresh(true);
+                }
+                Some(reply) = hello_receiver.next() => {
+                    match self.refresh_lease(device, reply).await {
+                        Ok(new_address) => {
+                            lease_run = self.start_lease_refresh(false);
+                            if let Some(address) = new_address {
+                                let _ = event_sender.send(TunnelEvent::Rekeyed(address)).await;
+                            }
+                        }
+                        Err(e) => warn!("Unable to refresh the office mode address lease: {}", e),
+                    }
+                }
+                Ok(()) = network_changes.changed(), if self.params.reconnect_on_network_change => {
+                    let roam_started = Instant::now();
+
+                    util::wait_for_network_settle(&mut network_changes).await;
+
+                    if self.params.default_route && self.params.netns.is_none() {
+                        self.check_default_route(&tun_name).await;
+                    }
+
+                    self.refresh_gateway_route(&tun_name).await;
+
+                    let new_default_ip = network.get_default_ip().await.ok();
+                    if new_default_ip.is_none() || new_default_ip == default_ip {
+                        continue;
+                    }
+
+                    warn!("Default address changed from {:?} to {:?}, reconnecting", default_ip, new_default_ip);
+                    default_ip = new_default_ip;
+
+                    // outbound packets are queued while the transport is down, inbound ones are lost
+                    let mut pending = VecDeque::new();
+
+                    let roam_result = {
+                        let roam_fut = self.roam(device);
+                        pin_mut!(roam_fut);
+
+                        loop {
+                            tokio::select! {
+                                result = &mut roam_fut => break result,
+                                result = tun_receiver.next() => {
+                                    if let Some(Ok(item)) = result {
+                                        if pending.len() == ROAM_BUFFER_SIZE {
+                                            pending.pop_front();
+                                        }
+                                        pending.push_back(item);
+                                    } else {
+                                        break Err(anyhow!(tr!("error-receive-failed")));
+                                    }
+                                }
+                            }
+                        }
+                    };
+
+                    match roam_result {
+                        Ok(new_address) => {
+                            self.spawn_receiver(tun_data_sender.clone(), error_sender.clone(), hello_sender.clone())?;
+                            ka_run = self.start_keepalive(event_sender);
+                            lease_run = self.start_lease_refresh(false);
+
+                            debug!("Sending {} packets queued while roaming", pending.len());
+                            for item in pending {
+                                self.send(item).await?;
+                            }
+
+                            info!("Tunnel roamed to the new network in {:?}", roam_started.elapsed());
+
+                            if let Some(address) = new_address {
+                                let _ = event_sender.send(TunnelEvent::Rekeyed(address)).await;
+                            }
+                        }
+                        Err(e) => break Err(e),
+                    }
+                }
 
                 result = tun_receiver.next() => {
                     if let Some(Ok(item)) = result {
-                        self.send(item).await?;
+                        match pmtu::check_size(&item, self.mtu) {
+                            PacketSize::Fits => self.send(item).await?,
+                            PacketSize::TooBig(reply) => {
+                                trace!(bytes = item.len(), mtu = self.mtu, "Dropping packet exceeding the MTU");
+                                if let Some(reply) = reply {
+                                    // best effort, the sender retransmits anyway
+                                    let _ = tun_data_sender.try_send(reply);
+                                }
+                            }
+                        }
                     } else {
                         break Err(anyhow!(tr!("error-receive-failed")));
                     }
                 }
             }
+        }
+    }
+
+    // Re-establish the TLS connection and request the same office mode address.
+    // Returns the new address if the gateway assigned a different one.
+    async fn reconnect_transport<D: PacketDevice>(&mut self, device: &mut D) -> anyhow::Result<Option<Ipv4Net>> {
+        self.reconnect().await?;
+        self.keepalive_counter.store(0, Ordering::SeqCst);
+        let reply = self.client_hello(true).await?;
+
+        self.apply_hello_reply(device, reply).await
+    }
+
+    // The lease refresh is answered on the established connection.
+    // Returns the new address if the gateway assigned a different one.
+    async fn refresh_lease<D: PacketDevice>(
+        &mut self,
+        device: &mut D,
+        raw: serde_json::Value,
+    ) -> anyhow::Result<Option<Ipv4Net>> {
+        let packet = ControlPacket::HelloReply(raw.clone());
+        let HelloResponse::Reply(reply) = HelloResponse::from_packet(packet, self.protocol_minor_version)? else {
+            anyhow::bail!(tr!("error-unexpected-reply"));
         };
 
-        let _ = event_sender.send(TunnelEvent::Disconnected).await;
+        if !has_usable_address(&reply) {
+            anyhow::bail!(tr!("error-no-office-mode-address", address = reply.office_mode.ipaddr));
+        }
 
-        result
+        self.accept_hello_reply(&reply);
+        self.hello_reply_raw = Some(raw);
+
+        self.apply_hello_reply(device, reply).await
     }
-}
 
-impl Drop for SslTunnel {
-    fn drop(&mut self) {
-        debug!("Cleaning up SSL tunnel");
-        std::thread::scope(|s| {
-            s.spawn(|| util::block_on(self.cleanup()));
-        });
+    // Bring the device and routes in line with a new hello reply.
+    async fn apply_hello_reply<D: PacketDevice>(
+        &mut self,
+        device: &mut D,
+        reply: HelloReplyData,
+    ) -> anyhow::Result<Option<Ipv4Net>> {
+        let dev_name = device.name().to_owned();
+        let old_address = self.office_mode_address()?;
+
+        self.hello_reply = reply;
+
+        let new_address = self.office_mode_address()?;
+
+        let mtu = self.effective_mtu();
+        if mtu != self.mtu {
+            info!("Changing tunnel device {} MTU from {} to {}", dev_name, self.mtu, mtu);
+            if let Some(ref netns) = self.params.netns {
+                warn!(
+                    "The MTU of {} in the network namespace {} is not changed",
+                    dev_name, netns
+                );
+            } else if !self.params.no_device_config {
+                platform::new_network_interface().set_mtu(&dev_name, mtu).await?;
+            }
+            self.mtu = mtu;
+        }
+
+        if new_address == old_address {
+            debug!("Tunnel reconnected with the same address {}", new_address);
+            Ok(None)
+        } else {
+            warn!(
+                "Gateway assigned new address {} instead of {}",
+                new_address, old_address
+            );
+            if self.params.no_device_config || self.params.netns.is_some() {
+                warn!(
+                    "The address of {} is managed externally and must be changed to {}",
+                    dev_name, new_address
+                );
+            } else if let Some(peer) = self.peer_address {
+                device.set_peer(new_address.addr(), peer).await?;
+            } else {
+                platform::new_network_interface()
+                    .replace_ip_address(&dev_name, old_address, new_address)
+                    .await?;
+            }
+            if self.params.netns.is_none() {
+                self.refresh_routes(&dev_name, new_address.addr()).await;
+            }
+            Ok(Some(new_address))
+        }
+    }
+
+    // Keep trying to re-establish the transport until the roaming timeout expires.
+    // The tun device, its routes and DNS settings are left untouched in the meantime.
+    async fn roam<D: PacketDevice>(&mut self, device: &mut D) -> anyhow::Result<Option<Ipv4Net>> {
+        let deadline = tokio::time::Instant::now() + self.params.timeouts.reconnect;
+
+        loop {
+            match tokio::time::timeout_at(deadline, self.reconnect_transport(device)).await {
+                Ok(Ok(new_address)) => return Ok(new_address),
+                Ok(Err(e)) => {
+                    warn!("Unable to re-establish tunnel: {}", e);
+                    if tokio::time::Instant::now() + self.params.retries.reconnect_delay >= deadline {
+                        break;
+                    }
+                    tokio::time::sleep(self.params.retries.reconnect_delay).await;
+                }
+                Err(_) => break,
+            }
+        }
+
+        Err(anyhow!(tr!(
+            "error-roaming-timeout",
+            seconds = self.params.timeouts.reconnect.as_secs()
+        )))
+    }
+
+    fn effective_mtu(&self) -> u16 {
+        self.params
+            .effective_mtu(self.hello_reply.optional.as_ref().and_then(|o| o.mtu))
+    }
+
+    fn office_mode_address(&self) -> anyhow::Result<Ipv4Net> {
+        let address = self.hello_reply.office_mode.ipaddr.parse()?;
+        let netmask = self
+            .hello_reply
+            .optional
+            .as_ref()
+            .and_then(|o| o.subnet.parse().ok())
+            .unwrap_or(Ipv4Addr::new(255, 255, 255, 255));
+        Ok(Ipv4Net::with_netmask(address, netmask)?)
+    }
+
+    // Networks which will be reachable through the tunnel: the office mode network and the acquired routes.
+    fn tunnel_networks(&self) -> anyhow::Result<Vec<Ipv4Net>> {
+        let mut networks = vec![self.office_mode_address()?];
+        if !self.params.no_routing && !self.params.default_route {
+            networks.extend(
+                self.params
+                    .acquired_routes(util::ranges_to_subnets(&self.hello_reply.range)),
+            );
+        }
+        Ok(networks)
+    }
+
+    // Overlapping networks make either the LAN or the tunnel unreachable, so they are always reported.
+    // With the renew action the gateway is asked for another address if the office mode address conflicts,
+    // with skip-routes the conflicting local networks are excluded from the tunnel routes.
+    async fn check_address_conflicts(&mut self, exclude_device: &str) -> anyhow::Result<()> {
+        let mut renewals = 0;
+
+        loop {
+            let local = match platform::new_network_interface()
+                .get_local_networks(exclude_device)
+                .await
+            {
+                Ok(local) => local,
+                Err(e) => {
+                    warn!("Unable to acquire local networks: {}", e);
+                    return Ok(());
+                }
+            };
+
+            let office_mode_address = self.office_mode_address()?;
+            let conflicts = util::find_address_conflicts(&self.tunnel_networks()?, &local);
+
+            for conflict in &conflicts {
+                warn!(
+                    "Tunnel network {} overlaps local network {} on {}",
+                    conflict.tunnel, conflict.local.network, conflict.local.device
+                );
+            }
+
+            match self.params.address_conflict {
+                AddressConflictAction::Warn => {}
+                AddressConflictAction::SkipRoutes => {
+                    self.conflicting_networks = conflicts.iter().map(|c| c.local.network).collect();
+                }
+                AddressConflictAction::Renew => {
+                    let address_conflict = conflicts
+                        .iter()
+                        .any(|c| c.local.network.contains(&office_mode_address.addr()));
+                    if address_conflict && renewals < self.params.retries.hello {
+                        renewals += 1;
+                        warn!(
+                            "Requesting a new office mode address instead of {}, attempt {} of {}",
+                            office_mode_address.addr(),
+                            renewals,
+                            self.params.retries.hello
+                        );
+                        // the gateway keeps the conflicting address assigned until the session is closed
+                        self.disconnect().await;
+                        self.wait_for_close().await;
+                        self.ip_address = "0.0.0.0".to_string();
+                        self.reconnect().await?;
+                        self.hello_reply = self.client_hello(false).await?;
+                        continue;
+                    }
+                }
+            }
+
+            return Ok(());
+        }
+    }
+
+    // Tell the gateway that the user is leaving so that it releases the office mode address immediately,
+    // then close the outgoing channel which flushes and shuts down the TLS connection.
+    pub(crate) async fn disconnect(&mut self) {
+        debug!("Sending disconnect request");
+        let request = DisconnectRequestData::user_disconnect();
+        if tokio::time::timeout(DISCONNECT_TIMEOUT, self.sender.send(request.into()))
+            .await
+            .is_err()
+        {
+            warn!("Timeout sending disconnect request");
+        }
+        self.sender.close_channel();
+    }
+
+    // The connection task ends after the disconnect request is flushed, which also ends the incoming packets.
+    async fn wait_for_close(&mut self) {
+        let Some(mut receiver) = self.receiver.take() else {
+            return;
+        };
+
+        let drain = async { while receiver.next().await.is_some() {} };
+        if tokio::time::timeout(DISCONNECT_TIMEOUT, drain).await.is_err() {
+            warn!("Timeout waiting for the connection to close");
+        }
+    }
+
+    async fn send<P>(&mut self, packet: P) -> anyhow::Result<()>
+    where
+        P: Into<SslPacketType>,
+    {
+        tokio::time::timeout(SEND_TIMEOUT, self.sender.send(packet.into())).await??;
+
+        Ok(())
+    }
+
+    async fn cleanup(&mut self) {
+        if let Some(info) = self.connection_info.take() {
+            hooks::run_down_script(&self.params, &info).await;
+        }
+
+        if let Some(mut sender) = self.terminate_sender.take() {
+            let _ = sender.send(()).await;
+        }
+
+        // stopped first, so that no routes are added after the rollback
+        self.domain_routes = None;
+        self.journal.rollback(&PlatformReverter).await;
+        platform::new_network_interface().update_connectivity().await;
+
+        if self.tun_device.take().is_some() {
+            debug!("Signing out");
+            let client = CccHttpClient::new(self.params.clone(), Some(self.session.clone()));
+            let _ = client.signout().await;
+        }
+    }
+
+    // the outer TCP socket carries the mark, see connect
+    fn routing_policy(&self) -> RoutingPolicy {
+        RoutingPolicy::new(&self.params, true)
+    }
+
+    // the gateway itself must never be routed through the tunnel
+    fn route_exclusions(&self, dest_ip: Ipv4Addr) -> Vec<Ipv4Net> {
+        let mut exclusions = self.params.exclude_routes.clone();
+        exclusions.push(Ipv4Net::from(dest_ip));
+        exclusions.extend(&self.conflicting_networks);
+        exclusions
+    }
+
+    pub async fn setup_routing(&mut self, dev_name: &str) -> anyhow::Result<()> {
+        let ipaddr = self.hello_reply.office_mode.ipaddr.parse()?;
+        let configurator =
+            platform::new_routing_configurator(dev_name, ipaddr, self.params.route_metric).with_peer(self.peer_address);
+
+        // behind a proxy the tunnel goes to the proxy, it is pinned instead of the gateway
+        let dest_ip = proxy::resolve_first_hop(&self.params)?;
+
+        if !self.params.no_routing {
+            self.gateway_route =
+                tunnel::pin_gateway_route(&self.params, &configurator, dev_name, ipaddr, dest_ip, &self.journal)
+                    .await?;
+
+            if self.params.app_routing_enabled() {
+                debug!(
+                    "Only the traffic of the selected applications is routed through {}",
+                    dev_name
+                );
+            } else if self.params.default_route {
+                self.journal.record(NetworkChange::DefaultRoute {
+                    device: dev_name.to_owned(),
+                    address: ipaddr,
+                    destination: dest_ip,
+                    metric: self.params.route_metric,
+                    policy: self.routing_policy(),
+                });
+                configurator
+                    .setup_default_route(dest_ip, &self.routing_policy())
+                    .await?;
+                self.journal.record(NetworkChange::ExcludedRoutes {
+                    device: dev_name.to_owned(),
+                    address: ipaddr,
+                    routes: self.params.exclude_routes.clone(),
+                });
+                configurator.setup_excluded_routes(&self.params.exclude_routes).await?;
+            }
+        }
+
+        let exclusions = self.route_exclusions(dest_ip);
+        let subnets = self.params.tunnel_routes(
+            util::ranges_to_subnets(&self.hello_reply.range),
+            self.ipv6_address.is_some(),
+            &exclusions,
+        );
+
+        let default_route = !self.params.no_routing && self.params.default_route;
+        let mut kill_switch_config = KillSwitchConfig::new(dev_name, &subnets, default_route, &exclusions);
+
+        if !subnets.is_empty() {
+            self.journal.record(NetworkChange::Routes {
+                device: dev_name.to_owned(),
+                address: ipaddr,
+                routes: subnets.iter().copied().map(IpNet::V4).collect(),
+                metric: self.params.route_metric,
+                peer: self.peer_address,
+            });
+            let _ = configurator.add_routes(&subnets, &self.params.ignore_routes).await;
+        }
+
+        if self.ipv6_address.is_some() {
+            let mut subnets_v6 = Vec::new();
+
+            if !self.params.no_routing && !self.params.default_route && !self.params.app_routing_enabled() {
+                subnets_v6.extend(
+                    self.params
+                        .acquired_routes(util::ranges_to_subnets_v6(&self.hello_reply.range_v6)),
+                );
+            }
+
+            subnets_v6.extend(self.params.include_routes_v6());
+
+            if !subnets_v6.is_empty() {
+                self.journal.record(NetworkChange::Routes {
+                    device: dev_name.to_owned(),
+                    address: ipaddr,
+                    routes: subnets_v6.iter().copied().map(IpNet::V6).collect(),
+                    metric: self.params.route_metric,
+                    peer: None,
+                });
+                let _ = configurator.add_ipv6_routes(&subnets_v6).await;
+            }
+
+            kill_switch_config
+                .tunneled
+                .extend(subnets_v6.iter().copied().map(IpNet::V6));
+        }
+
+        if self.params.kill_switch {
+            platform::new_kill_switch().enable(&kill_switch_config).await?;
+        }
+
+        if let Some(source) = self.params.forward_source {
+            let config = ForwardingConfig::new(dev_name, source, ipaddr, &subnets)?;
+            warn!(
+                "Forwarding {} through the tunnel, the gateway sees all forwarded traffic coming from {}",
+                config.source, ipaddr
+            );
+            self.journal
+                .record(NetworkChange::Forwarding { config: config.clone() });
+            platform::new_forwarding().enable(&config).await?;
+        }
+
+        tunnel::clamp_mss(&self.params, dev_name, &self.journal).await;
+
+        tunnel::block_ipv6(&self.params, dev_name, &self.journal).await?;
+
+        Ok(())
+    }
+
+    // The pinned route follows the default gateway of the underlying network when roaming.
+    async fn refresh_gateway_route(&mut self, dev_name: &str) {
+        let Some(route) = self.gateway_route.clone() else {
+            return;
+        };
+
+        let current = platform::new_network_interface()
+            .get_default_gateway(dev_name)
+            .await
+            .ok();
+        if current.is_none_or(|gateway| route.gateway == Some(gateway.address) && route.device == gateway.device) {
+            return;
+        }
+
+        let Ok(ipaddr) = self.hello_reply.office_mode.ipaddr.parse() else {
+            return;
+        };
+        let configurator = platform::new_routing_configurator(dev_name, ipaddr, self.params.route_metric);
+
+        debug!("Default gateway changed, moving the route to {}", route.destination);
+        let _ = configurator.remove_gateway_route(&route).await;
+
+        // kept for the next attempt if the new route cannot be added yet
+        match tunnel::pin_gateway_route(
+            &self.params,
+            &configurator,
+            dev_name,
+            ipaddr,
+            route.destination,
+            &self.journal,
+        )
+        .await
+        {
+            Ok(Some(new_route)) => self.gateway_route = Some(new_route),
+            Ok(None) => {}
+            Err(e) => warn!("Unable to pin the route to the gateway {}: {}", route.destination, e),
+        }
+    }
+
+    // Deleting the old address may take the routes of the device with it, and a new address may come
+    // with other ranges.
+    async fn refresh_routes(&self, dev_name: &str, ipaddr: Ipv4Addr) {
+        if self.params.no_routing || self.params.default_route || self.params.app_routing_enabled() {
+            return;
+        }
+
+        let configurator =
+            platform::new_routing_configurator(dev_name, ipaddr, self.params.route_metric).with_peer(self.peer_address);
+
+        let mut exclusions = self.params.exclude_routes.clone();
+        if let Ok(dest_ip) = proxy::resolve_first_hop(&self.params) {
+            exclusions.push(Ipv4Net::from(dest_ip));
+        }
+        exclusions.extend(&self.conflicting_networks);
+
+        let subnets = self.params.tunnel_routes(
+            util::ranges_to_subnets(&self.hello_reply.range),
+            self.ipv6_address.is_some(),
+            &exclusions,
+        );
+        if subnets.is_empty() {
+            return;
+        }
+
+        debug!("Refreshing {} routes through {}", subnets.len(), dev_name);
+        self.journal.record(NetworkChange::Routes {
+            device: dev_name.to_owned(),
+            address: ipaddr,
+            routes: subnets.iter().copied().map(IpNet::V4).collect(),
+            metric: self.params.route_metric,
+            peer: self.peer_address,
+        });
+        let _ = configurator.add_routes(&subnets, &self.params.ignore_routes).await;
+    }
+
+    // The running keepalive picks up the new jitter with its next interval.
+    async fn reconfigure(&mut self, dev_name: &str, params: Arc<TunnelParams>) {
+        debug!("Applying the reloaded configuration");
+
+        if let Ok(address) = self.office_mode_address() {
+            let mut exclusions = self.conflicting_networks.clone();
+            if let Ok(dest_ip) = proxy::resolve_first_hop(&self.params) {
+                exclusions.push(Ipv4Net::from(dest_ip));
+            }
+
+            let routes = StaticRoutes {
+                device: dev_name.to_owned(),
+                address: address.addr(),
+                peer: self.peer_address,
+                exclusions,
+                acquired: self
+                    .params
+                    .acquired_routes(util::ranges_to_subnets(&self.hello_reply.range)),
+            };
+            routes.update(&self.params, &params, &self.journal).await;
+        }
+
+        self.keepalive_jitter.store(params.keepalive_jitter, Ordering::SeqCst);
+        self.params = params;
+    }
+
+    // Another network manager may flush the policy routing while the tunnel is up.
+    async fn check_default_route(&self, dev_name: &str) {
+        let Ok(ipaddr) = self.hello_reply.office_mode.ipaddr.parse() else {
+            return;
+        };
+        let configurator = platform::new_routing_configurator(dev_name, ipaddr, self.params.route_metric);
+
+        if let Ok(dest_ip) = proxy::resolve_first_hop(&self.params) {
+            match configurator.check_default_route(dest_ip, &self.routing_policy()).await {
+                Ok(true) => {}
+                Ok(false) => warn!(
+                    "Default route through {} was changed by another network manager",
+                    dev_name
+                ),
+                Err(e) => warn!("Unable to check the default route: {}", e),
+            }
+        }
+    }
+
+    async fn make_resolver_config(&self) -> ResolverConfig {
+        let features = platform::get_features().await;
+
+        let acquired_domains = self
+            .hello_reply
+            .office_mode
+            .dns_suffix
+            .as_ref()
+            .map(|s| s.0.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|s| {
+                if self.params.set_routing_domains && features.split_dns {
+                    format!("~{}", s)
+                } else {
+                    s.clone()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let search_domains = self
+            .params
+            .dns_override
+            .apply(&acquired_domains, &self.params.search_domains)
+            .into_iter()
+            .filter(|s| {
+                !s.is_empty()
+                    && !self
+                        .params
+                        .ignore_search_domains
+                        .iter()
+                        .any(|d| d.to_lowercase() == s.trim_matches('~').to_lowercase())
+            })
+            .collect::<Vec<_>>();
+
+        let acquired_servers = self
+            .hello_reply
+            .office_mode
+            .dns_servers
+            .clone()
+            .map(Vec::from)
+            .unwrap_or_default();
+        let configured_servers = self
+            .params
+            .dns_servers
+            .iter()
+            .map(|s| IpAddr::V4(*s))
+            .collect::<Vec<_>>();
+
+        let dns_servers = self
+            .params
+            .dns_override
+            .apply(&acquired_servers, &configured_servers)
+            .into_iter()
+            .filter(|s| !self.params.ignore_dns_servers.iter().any(|d| s == d))
+            .collect::<Vec<_>>();
+
+        let config = ResolverConfig {
+            search_domains,
+            dns_servers,
+            wins_servers: self.hello_reply.office_mode.wins_servers.clone().unwrap_or_default(),
+        }
+        .normalized();
+
+        tunnel::log_resolver_config(&self.params, &config);
+
+        config
+    }
+
+    pub async fn setup_dns(&self, config: ResolverConfig, dev_name: &str, cleanup: bool) -> anyhow::Result<()> {
+        let resolver = new_resolver_configurator(dev_name, &self.params)?;
+
+        if cleanup {
+            resolver.cleanup(&config).await?;
+        } else {
+            self.journal.record(NetworkChange::Dns {
+                device: dev_name.to_owned(),
+                backend: self.params.dns_backend,
+                mode: self.params.dns_mode,
+                resolv_path: self.params.dns_resolv_path.clone(),
+                config: config.clone(),
+            });
+            resolver.configure(&config).await?;
+        }
+
+        Ok(())
+    }
+
+    fn make_connection_info(
+        &self,
+        tun_name: &str,
+        ip_address: Ipv4Net,
+        resolver_config: ResolverConfig,
+        routes: Vec<IpNet>,
+    ) -> ConnectionInfo {
+        ConnectionInfo {
+            since: Some(Local::now()),
+            server_name: self.params.server_name.clone(),
+            username: self.session.username.clone().unwrap_or_default(),
+            login_type: self.params.login_type.clone(),
+            tunnel_type: self.params.tunnel_type,
+            transport_type: TransportType::Tcpt,
+            ip_address,
+            ipv6_address: self.ipv6_address,
+            dns_servers: resolver_config.dns_servers,
+            search_domains: resolver_config.search_domains,
+            wins_servers: resolver_config.wins_servers,
+            interface_name: tun_name.to_owned(),
+            dns_configured: !self.params.no_dns,
+            dns_mode: (!self.params.no_dns).then(|| platform::effective_dns_mode(&self.params)),
+            dns_override: (!self.params.no_dns).then_some(self.params.dns_override),
+            routing_configured: !self.params.no_routing,
+            default_route: self.params.default_route,
+            dns_check: Vec::new(),
+            forward_source: self.params.forward_source,
+            peer_address: self.peer_address,
+            stats: TunnelStats::default(),
+            hello_reply: self.hello_reply_raw.clone(),
+            protocol_version: Some(self.hello_reply.protocol_version_string()),
+            profile: self.params.profile.clone(),
+            routes,
+            traffic: None,
+            reauth_interval: (!self.auth_timeout.is_zero()).then_some(self.auth_timeout),
+        }
+    }
+
+    // The gateway sees a complete session, the network configuration is only logged. The disconnect
+    // releases the office mode address, the sign-out is left to the caller.
+    async fn run_dry(&mut self, event_sender: &tokio::sync::mpsc::Sender<TunnelEvent>) -> anyhow::Result<()> {
+        let tun_name = self
+            .params
+            .if_name
+            .clone()
+            .unwrap_or_else(|| TunnelParams::DEFAULT_SSL_IF_NAME.to_owned());
+
+        let ip_address = self.hello_reply.office_mode.ipaddr.parse()?;
+        let netmask = self.hello_reply.optional.as_ref().and_then(|o| o.subnet.parse().ok());
+        let address = Ipv4Net::with_netmask(ip_address, netmask.unwrap_or(Ipv4Addr::new(255, 255, 255, 255)))?;
+
+        self.mtu = self.effective_mtu();
+        self.ipv6_address = self.hello_reply.office_mode.ipv6_network();
+
+        let resolver_config = self.make_resolver_config().await;
+
+        let dest_ip = proxy::resolve_first_hop(&self.params)?;
+        let routes = self.params.tunnel_routes(
+            util::ranges_to_subnets(&self.hello_reply.range),
+            self.ipv6_address.is_some(),
+            &self.route_exclusions(dest_ip),
+        );
+
+        DryRun {
+            params: &self.params,
+            device: &tun_name,
+            address,
+            ipv6_address: self.ipv6_address,
+            routes: &routes,
+            resolver_config: &resolver_config,
+        }
+        .log();
+        info!("Dry run, the tunnel MTU would be {}", self.mtu);
+
+        let mut all_routes = routes.into_iter().map(IpNet::V4).collect::<Vec<_>>();
+        if !self.params.no_routing && self.params.default_route {
+            all_routes.insert(0, IpNet::V4(Ipv4Net::default()));
+        }
+
+        let info = self.make_connection_info(&tun_name, address, resolver_config, all_routes);
+        let _ = event_sender.send(TunnelEvent::Connected(info)).await;
+
+        self.disconnect().await;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl VpnTunnel for SslTunnel {
+    async fn run(
+        mut self: Box<Self>,
+        mut command_receiver: tokio::sync::mpsc::Receiver<TunnelCommand>,
+        event_sender: tokio::sync::mpsc::Sender<TunnelEvent>,
+    ) -> anyhow::Result<()> {
+        debug!("Running SSL tunnel for session {}", self.session.ccc_session_id);
+
+        let reply = self.client_hello(false).await?;
+        trace!("Hello reply: {:?}", reply);
+
+        self.hello_reply = reply;
+
+        if self.params.dry_run {
+            return self.run_dry(&event_sender).await;
+        }
+
+        let name_hint = self
+            .params
+            .if_name
+            .as_deref()
+            .unwrap_or(TunnelParams::DEFAULT_SSL_IF_NAME);
+
+        self.check_address_conflicts(name_hint).await?;
+
+        let ip_address = self.hello_reply.office_mode.ipaddr.parse()?;
+        let netmask = self.hello_reply.optional.as_ref().and_then(|o| o.subnet.parse().ok());
+
+        self.mtu = self.effective_mtu();
+
+        let mut tun = device::open_device(&self.params, name_hint, ip_address, netmask, self.mtu).await?;
+        let tun_name = tun.name().to_owned();
+
+        info!("Tunnel device {} MTU is {}", tun_name, tun.mtu());
+
+        // the gateway internal address, when given, becomes the peer and the next hop of the routes
+        self.peer_address = device::configure_peer(
+            &self.params,
+            &mut tun,
+            ip_address,
+            self.hello_reply.office_mode.gw_internal_ip,
+        )
+        .await;
+
+        // a persistent device is deleted only on explicit disconnect
+        if !tun.is_persistent() {
+            self.journal.record(NetworkChange::Device { name: tun_name.clone() });
+        }
+
+        let resolver_config = self.make_resolver_config().await;
+
+        if let Some(ref netns) = self.params.netns {
+            // the addresses are assigned inside the namespace, the host routing and DNS are not touched
+            self.ipv6_address = self.hello_reply.office_mode.ipv6_network();
+            let config = NamespaceConfig::new(
+                &self.params,
+                netns,
+                &tun_name,
+                self.mtu,
+                Ipv4Net::with_netmask(ip_address, netmask.unwrap_or(Ipv4Addr::new(255, 255, 255, 255)))?,
+                self.peer_address,
+                self.ipv6_address,
+                &resolver_config,
+            );
+            tunnel::setup_namespace(config, &self.journal).await?;
+        } else {
+            // gateways without IPv6 support do not assign an IPv6 office-mode address
+            if let Some(address) = self
+                .hello_reply
+                .office_mode
+                .ipv6_network()
+                .filter(|_| !self.params.no_device_config)
+            {
+                match platform::new_network_interface()
+                    .add_ipv6_address(&tun_name, address)
+                    .await
+                {
+                    Ok(()) => self.ipv6_address = Some(address),
+                    Err(e) => warn!("Unable to configure IPv6 address {}: {}", address, e),
+                }
+            }
+
+            self.setup_routing(&tun_name).await?;
+
+            if !self.params.no_dns {
+                self.setup_dns(resolver_config.clone(), &tun_name, false).await?;
+            }
+
+            tunnel::setup_app_routing(
+                &self.params,
+                &tun_name,
+                ip_address,
+                self.peer_address,
+                &resolver_config.dns_servers,
+                &self.journal,
+            )
+            .await?;
+
+            tunnel::redirect_dns(&self.params, &tun_name, &resolver_config.dns_servers, &self.journal).await?;
+
+            if !self.params.no_device_config {
+                let _ = platform::new_network_interface().configure_device(&tun_name).await;
+            }
+        }
+
+        let info = self.make_connection_info(
+            &tun_name,
+            Ipv4Net::with_netmask(ip_address, netmask.unwrap_or(Ipv4Addr::new(255, 255, 255, 255)))?,
+            resolver_config,
+            self.journal.routes(),
+        );
+
+        hooks::run_up_script(&self.params, &info).await?;
+        platform::new_network_interface().update_connectivity().await;
+        self.connection_info = Some(info.clone());
+
+        // the route domains and the DNS self-test are resolved in the host namespace
+        if self.params.netns.is_none() {
+            let mut exclusions = self.params.exclude_routes.clone();
+            if let Ok(dest_ip) = proxy::resolve_first_hop(&self.params) {
+                exclusions.push(Ipv4Net::from(dest_ip));
+            }
+
+            self.domain_routes = DomainRoutes::spawn(
+                self.params.clone(),
+                DomainRoutesConfig {
+                    device: tun_name.clone(),
+                    address: ip_address,
+                    dns_servers: info.dns_servers.clone(),
+                    peer: self.peer_address,
+                    exclusions,
+                },
+                self.journal.clone(),
+            );
+
+            dns_check::spawn(self.params.clone(), info.dns_servers.clone(), event_sender.clone());
+        }
+
+        let _ = event_sender.send(TunnelEvent::Connected(info)).await;
+
+        let result = self.run_data_path(&mut tun, &mut command_receiver, &event_sender).await;
+
+        // kept open until the cleanup, the signout depends on it
+        self.tun_device = Some(tun);
+
+        let _ = event_sender.send(TunnelEvent::Disconnected).await;
+
+        result
+    }
+}
+
+// The configured interval can only shorten the session, the gateway ends it at its own timeout anyway.
+// Zero disables the expiry, only when the gateway sends no timeout and no interval is configured.
+fn reauth_timeout(gateway_timeout: Duration, interval: Option<Duration>) -> Duration {
+    if gateway_timeout.is_zero() {
+        return interval.unwrap_or_default();
+    }
+
+    // a gateway timeout within the leeway leaves no room for it, the gateway deadline itself is used then
+    let derived = match gateway_timeout.saturating_sub(REAUTH_LEEWAY) {
+        derived if derived.is_zero() => gateway_timeout,
+        derived => derived,
+    };
+
+    match interval {
+        Some(interval) => interval.min(derived),
+        None => derived,
+    }
+}
+
+async fn expiry_watcher(timeout: Duration, event_sender: tokio::sync::mpsc::Sender<TunnelEvent>) {
+    if timeout.is_zero() {
+        return futures::future::pending().await;
+    }
+
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    for remaining in EXPIRY_WARNINGS.iter().filter(|w| **w < timeout) {
+        tokio::time::sleep_until(deadline - *remaining).await;
+        warn!("Session expires in {} seconds", remaining.as_secs());
+        let _ = event_sender.send(TunnelEvent::SessionExpiring(*remaining)).await;
+    }
+
+    tokio::time::sleep_until(deadline).await;
+}
+
+fn has_usable_address(reply: &HelloReplyData) -> bool {
+    reply
+        .office_mode
+        .ipaddr
+        .parse::<Ipv4Addr>()
+        .is_ok_and(is_usable_address)
+}
+
+fn is_usable_address(address: Ipv4Addr) -> bool {
+    !(address.is_unspecified()
+        || address.is_loopback()
+        || address.is_broadcast()
+        || address.is_multicast()
+        || address.is_link_local())
+}
+
+impl Drop for SslTunnel {
+    fn drop(&mut self) {
+        debug!("Cleaning up SSL tunnel");
+        std::thread::scope(|s| {
+            s.spawn(|| util::block_on(self.cleanup()));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::task::JoinHandle;
+
+    use super::*;
+    use crate::{
+        model::{
+            params::Retries,
+            proto::{KeepaliveRequestData, ReconnectReplyData},
+        },
+        sexpr::SExpression,
+        tunnel::device::loopback::{LoopbackDevice, LoopbackHandle},
+    };
+
+    const RECEIVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+    // The gateway end of the SNX channel and the fake device around a running data path.
+    struct TestTunnel {
+        gateway_sender: PacketSender,
+        gateway_receiver: PacketReceiver,
+        device: LoopbackHandle,
+        keepalive_counter: Arc<AtomicI64>,
+        command_sender: tokio::sync::mpsc::Sender<TunnelCommand>,
+        event_receiver: tokio::sync::mpsc::Receiver<TunnelEvent>,
+        data_path: JoinHandle<anyhow::Result<()>>,
+        _dir: tempfile::TempDir,
+    }
+
+    fn start_tunnel(keepalive: Duration, device_capacity: usize) -> TestTunnel {
+        start_tunnel_with(keepalive, device_capacity, |_| {})
+    }
+
+    fn start_tunnel_with<F>(keepalive: Duration, device_capacity: usize, setup: F) -> TestTunnel
+    where
+        F: FnOnce(&mut SslTunnel),
+    {
+        let dir = tempfile::tempdir().unwrap();
+        let params = Arc::new(TunnelParams {
+            reconnect_on_network_change: false,
+            ..Default::default()
+        });
+
+        // both ends are framed, so the packets go through the codec
+        let (client, server) = tokio::io::duplex(4096);
+        let (sender, receiver) = make_channel(client);
+        let (gateway_sender, gateway_receiver) = make_channel(server);
+
+        let mut tunnel = SslTunnel::with_channel(
+            params,
+            Arc::new(VpnSession::empty()),
+            sender,
+            receiver,
+            Arc::new(NetworkJournal::with_path(dir.path().join("journal"))),
+        );
+        tunnel.keepalive = keepalive;
+        setup(&mut tunnel);
+        let keepalive_counter = tunnel.keepalive_counter.clone();
+
+        let (mut device, handle) = LoopbackDevice::new("snx-test", 1350, device_capacity);
+        let (command_sender, mut command_receiver) = tokio::sync::mpsc::channel(1);
+        let (event_sender, event_receiver) = tokio::sync::mpsc::channel(16);
+
+        let data_path = tokio::spawn(async move {
+            tunnel
+                .run_data_path(&mut device, &mut command_receiver, &event_sender)
+                .await
+        });
+
+        TestTunnel {
+            gateway_sender,
+            gateway_receiver,
+            device: handle,
+            keepalive_counter,
+            command_sender,
+            event_receiver,
+            data_path,
+            _dir: dir,
+        }
+    }
+
+    // Minimal IPv4 header, the identification field tells the packets apart.
+    fn ip_packet(id: u16) -> Vec<u8> {
+        let mut packet = vec![0u8; 20];
+        packet[0] = 0x45;
+        packet[2..4].copy_from_slice(&20u16.to_be_bytes());
+        packet[4..6].copy_from_slice(&id.to_be_bytes());
+        packet[8] = 64;
+        packet[9] = 17;
+        packet[12..16].copy_from_slice(&[10, 0, 0, 10]);
+        packet[16..20].copy_from_slice(&[10, 0, 0, 1]);
+        packet
+    }
+
+    async fn next_gateway_packet(tunnel: &mut TestTunnel) -> SslPacketType {
+        tokio::time::timeout(RECEIVE_TIMEOUT, tunnel.gateway_receiver.next())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    async fn next_gateway_data(tunnel: &mut TestTunnel) -> Vec<u8> {
+        loop {
+            if let SslPacketType::Data(data) = next_gateway_packet(tunnel).await {
+                return data;
+            }
+        }
+    }
+
+    async fn next_device_packet(tunnel: &mut TestTunnel) -> Vec<u8> {
+        tokio::time::timeout(RECEIVE_TIMEOUT, tunnel.device.receiver.next())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_data_path() {
+        let mut tunnel = start_tunnel(Duration::from_secs(3600), 16);
+
+        for id in 0..10 {
+            tunnel.device.sender.send(ip_packet(id)).await.unwrap();
+        }
+        for id in 0..10 {
+            assert_eq!(next_gateway_data(&mut tunnel).await, ip_packet(id));
+        }
+
+        for id in 10..20 {
+            tunnel.gateway_sender.send(ip_packet(id).into()).await.unwrap();
+        }
+        for id in 10..20 {
+            assert_eq!(next_device_packet(&mut tunnel).await, ip_packet(id));
+        }
+
+        tunnel
+            .command_sender
+            .send(TunnelCommand::Terminate(false))
+            .await
+            .unwrap();
+
+        loop {
+            match next_gateway_packet(&mut tunnel).await {
+                SslPacketType::Control(ControlPacket::Keepalive(_)) => {}
+                SslPacketType::Control(ControlPacket::Disconnect(request)) => {
+                    assert_eq!(request.code, DisconnectRequestData::USER_DISCONNECT);
+                    break;
+                }
+                other => panic!("Expected disconnect request, got {other:?}"),
+            }
+        }
+
+        assert!(tunnel.data_path.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_data_path_packet_too_big() {
+        let mut tunnel = start_tunnel(Duration::from_secs(3600), 16);
+
+        let mut packet = ip_packet(1);
+        packet.resize(usize::from(TunnelParams::DEFAULT_MTU) + 100, 0);
+        packet[2..4].copy_from_slice(&(packet.len() as u16).to_be_bytes());
+        packet[6] = 0x40;
+        tunnel.device.sender.send(packet.clone()).await.unwrap();
+        tunnel.device.sender.send(ip_packet(2)).await.unwrap();
+
+        // the oversized packet never reaches the gateway
+        assert_eq!(next_gateway_data(&mut tunnel).await, ip_packet(2));
+
+        let reply = next_device_packet(&mut tunnel).await;
+        assert_eq!(&reply[12..16], &packet[16..20]);
+        assert_eq!(&reply[16..20], &packet[12..16]);
+        assert_eq!(&reply[20..22], &[3, 4]);
+        assert_eq!(u16::from_be_bytes([reply[26], reply[27]]), TunnelParams::DEFAULT_MTU);
+        assert_eq!(&reply[28..], &packet[..28]);
+    }
+
+    #[tokio::test]
+    async fn test_data_path_lease_refresh() {
+        let mut tunnel = start_tunnel_with(Duration::from_secs(3600), 16, |tunnel| {
+            tunnel.params = Arc::new(TunnelParams {
+                reconnect_on_network_change: false,
+                no_device_config: true,
+                no_routing: true,
+                ..Default::default()
+            });
+            tunnel.hello_reply.office_mode.ipaddr = "10.0.0.10".to_owned();
+            tunnel.hello_reply.timeouts.om_lease = Some(2);
+        });
+
+        // the hello is repeated on the same connection in the middle of the lease
+        let request = loop {
+            if let SslPacketType::Control(ControlPacket::ClientHello(request)) = next_gateway_packet(&mut tunnel).await
+            {
+                break request;
+            }
+        };
+        assert_eq!(request.office_mode.keep_address, Some(true));
+
+        let data = std::fs::read_to_string("tests/hello_reply_lease.txt").unwrap();
+        let reply = ControlPacket::parse(&data.parse::<SExpression>().unwrap()).unwrap();
+        tunnel.gateway_sender.send(reply.into()).await.unwrap();
+
+        let event = tokio::time::timeout(RECEIVE_TIMEOUT, tunnel.event_receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let TunnelEvent::Rekeyed(address) = event else {
+            panic!("Expected address change");
+        };
+        assert_eq!(address, "10.0.0.20/24".parse().unwrap());
+
+        // the data path keeps running
+        tunnel.device.sender.send(ip_packet(1)).await.unwrap();
+        assert_eq!(next_gateway_data(&mut tunnel).await, ip_packet(1));
+    }
+
+    #[tokio::test]
+    async fn test_data_path_keepalive() {
+        let mut tunnel = start_tunnel(Duration::from_secs(3600), 16);
+
+        // the first request is sent when the data path starts
+        let SslPacketType::Control(ControlPacket::Keepalive(request)) = next_gateway_packet(&mut tunnel).await else {
+            panic!("Expected keepalive request");
+        };
+        assert_eq!(request.id, "0");
+        assert_eq!(tunnel.keepalive_counter.load(Ordering::SeqCst), 1);
+
+        // any data from the gateway proves that the tunnel is alive
+        tunnel.gateway_sender.send(ip_packet(1).into()).await.unwrap();
+        assert_eq!(next_device_packet(&mut tunnel).await, ip_packet(1));
+        assert_eq!(tunnel.keepalive_counter.load(Ordering::SeqCst), 0);
+
+        tunnel.keepalive_counter.store(1, Ordering::SeqCst);
+        tunnel
+            .gateway_sender
+            .send(KeepaliveRequestData { id: "0".to_owned() }.into())
+            .await
+            .unwrap();
+
+        // the reply is processed before the following data packet
+        tunnel.gateway_sender.send(ip_packet(2).into()).await.unwrap();
+        assert_eq!(next_device_packet(&mut tunnel).await, ip_packet(2));
+        assert_eq!(tunnel.keepalive_counter.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_data_path_control_packets() {
+        let mut tunnel = start_tunnel(Duration::from_secs(3600), 16);
+
+        // the packets without a meaning for the data path are skipped
+        let unknown = ControlPacket::Unknown {
+            name: "client_settings_update".to_owned(),
+            value: serde_json::json!({ "(client_settings_update": { "policy": "default" } }),
+        };
+        tunnel.gateway_sender.send(unknown.into()).await.unwrap();
+        tunnel.gateway_sender.send(ip_packet(1).into()).await.unwrap();
+        assert_eq!(next_device_packet(&mut tunnel).await, ip_packet(1));
+
+        let request = DisconnectRequestData {
+            code: "12".to_owned(),
+            message: Some("Session terminated by the administrator".to_owned()),
+        };
+        tunnel.gateway_sender.send(request.into()).await.unwrap();
+
+        let error = tokio::time::timeout(RECEIVE_TIMEOUT, tunnel.data_path)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("12: Session terminated by the administrator")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_data_path_keepalive_failure() {
+        let tunnel = start_tunnel(Duration::from_millis(50), 16);
+
+        // the gateway never replies
+        let result = tokio::time::timeout(RECEIVE_TIMEOUT, tunnel.data_path)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_data_path_backpressure() {
+        const MAX_PACKETS: u16 = 10000;
+
+        let mut tunnel = start_tunnel(Duration::from_secs(3600), 1);
+
+        // nobody reads the device, the gateway is blocked once all queues are full
+        let mut sent = 0;
+        while sent < MAX_PACKETS {
+            let send = tunnel.gateway_sender.send(ip_packet(sent).into());
+            if tokio::time::timeout(Duration::from_millis(200), send).await.is_err() {
+                break;
+            }
+            sent += 1;
+        }
+        assert!(sent < MAX_PACKETS);
+
+        // nothing is dropped
+        for id in 0..sent {
+            assert_eq!(next_device_packet(&mut tunnel).await, ip_packet(id));
+        }
+
+        assert!(!tunnel.data_path.is_finished());
+    }
+
+    #[test]
+    fn test_reauth_timeout() {
+        const HOUR: Duration = Duration::from_secs(3600);
+
+        assert_eq!(reauth_timeout(8 * HOUR, None), 8 * HOUR - REAUTH_LEEWAY);
+        assert_eq!(reauth_timeout(8 * HOUR, Some(HOUR)), HOUR);
+
+        // never beyond the gateway timeout minus the leeway
+        assert_eq!(
+            reauth_timeout(Duration::from_secs(600), Some(HOUR)),
+            Duration::from_secs(540)
+        );
+
+        // a gateway timeout within the leeway is used as is
+        assert_eq!(
+            reauth_timeout(Duration::from_secs(30), Some(HOUR)),
+            Duration::from_secs(30)
+        );
+        assert_eq!(reauth_timeout(Duration::from_secs(60), None), Duration::from_secs(60));
+        assert_eq!(
+            reauth_timeout(Duration::from_secs(30), Some(Duration::from_secs(10))),
+            Duration::from_secs(10)
+        );
+
+        // no timeout from the gateway, only the configured one applies
+        assert_eq!(reauth_timeout(Duration::ZERO, None), Duration::ZERO);
+        assert_eq!(reauth_timeout(Duration::ZERO, Some(HOUR)), HOUR);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_expiry_with_reauth_interval() {
+        let timeout = reauth_timeout(Duration::from_secs(8 * 3600), Some(Duration::from_secs(3600)));
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(4);
+
+        let started = tokio::time::Instant::now();
+        expiry_watcher(timeout, sender).await;
+        assert_eq!(started.elapsed(), Duration::from_secs(3600));
+
+        assert_eq!(
+            receiver.recv().await,
+            Some(TunnelEvent::SessionExpiring(Duration::from_secs(600)))
+        );
+        assert_eq!(
+            receiver.recv().await,
+            Some(TunnelEvent::SessionExpiring(Duration::from_secs(60)))
+        );
+        assert_eq!(receiver.recv().await, None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_expiry_clamped_to_gateway() {
+        // the interval is longer than the gateway allows, the session ends the leeway before the gateway deadline
+        let timeout = reauth_timeout(Duration::from_secs(600), Some(Duration::from_secs(3600)));
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(4);
+
+        let started = tokio::time::Instant::now();
+        expiry_watcher(timeout, sender).await;
+        assert_eq!(started.elapsed(), Duration::from_secs(540));
+
+        // the 10 minute warning does not fit into the session
+        assert_eq!(
+            receiver.recv().await,
+            Some(TunnelEvent::SessionExpiring(Duration::from_secs(60)))
+        );
+        assert_eq!(receiver.recv().await, None);
+
+        // a gateway timeout within the leeway still ends the session, at the gateway deadline
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(4);
+        let timeout = reauth_timeout(Duration::from_secs(30), Some(Duration::from_secs(3600)));
+        let started = tokio::time::Instant::now();
+        expiry_watcher(timeout, sender).await;
+        assert_eq!(started.elapsed(), Duration::from_secs(30));
+        assert_eq!(receiver.recv().await, None);
+    }
+
+    // A tunnel before the hello exchange, with the gateway end of its channel.
+    fn hello_tunnel(params: TunnelParams) -> (SslTunnel, PacketSender, PacketReceiver, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let (client, server) = tokio::io::duplex(4096);
+        let (sender, receiver) = make_channel(client);
+        let (gateway_sender, gateway_receiver) = make_channel(server);
+
+        let tunnel = SslTunnel::with_channel(
+            Arc::new(params),
+            Arc::new(VpnSession::empty()),
+            sender,
+            receiver,
+            Arc::new(NetworkJournal::with_path(dir.path().join("journal"))),
+        );
+
+        (tunnel, gateway_sender, gateway_receiver, dir)
+    }
+
+    fn hello_reply(address: &str) -> SslPacketType {
+        let data = std::fs::read_to_string("tests/hello_reply.txt")
+            .unwrap()
+            .replace("10.0.0.10", address);
+        ControlPacket::parse(&data.parse::<SExpression>().unwrap())
+            .unwrap()
+            .into()
+    }
+
+    async fn next_hello_request(receiver: &mut PacketReceiver) {
+        let packet = tokio::time::timeout(RECEIVE_TIMEOUT, receiver.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(
+            matches!(packet, SslPacketType::Control(ControlPacket::ClientHello(_))),
+            "Expected hello request, got {packet:?}"
+        );
+    }
+
+    #[test]
+    fn test_is_usable_address() {
+        assert!(is_usable_address(Ipv4Addr::new(10, 0, 0, 10)));
+        assert!(is_usable_address(Ipv4Addr::new(172, 16, 1, 1)));
+
+        assert!(!is_usable_address(Ipv4Addr::UNSPECIFIED));
+        assert!(!is_usable_address(Ipv4Addr::BROADCAST));
+        assert!(!is_usable_address(Ipv4Addr::LOCALHOST));
+        assert!(!is_usable_address(Ipv4Addr::new(224, 0, 0, 1)));
+        assert!(!is_usable_address(Ipv4Addr::new(169, 254, 1, 1)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_client_hello_retries_unusable_address() {
+        let (mut tunnel, mut gateway_sender, mut gateway_receiver, _dir) = hello_tunnel(TunnelParams::default());
+        let client = tokio::spawn(async move {
+            let result = tunnel.client_hello(false).await;
+            (tunnel, result)
+        });
+
+        for address in ["0.0.0.0", "255.255.255.255", "10.0.0.10"] {
+            next_hello_request(&mut gateway_receiver).await;
+            gateway_sender.send(hello_reply(address)).await.unwrap();
+        }
+
+        let (tunnel, result) = client.await.unwrap();
+        assert_eq!(result.unwrap().office_mode.ipaddr, "10.0.0.10");
+        assert_eq!(tunnel.ip_address, "10.0.0.10");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_client_hello_retry_limit() {
+        let params = TunnelParams {
+            retries: Retries {
+                hello: 2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (mut tunnel, mut gateway_sender, mut gateway_receiver, _dir) = hello_tunnel(params);
+        let client = tokio::spawn(async move {
+            let result = tunnel.client_hello(false).await;
+            (tunnel, result)
+        });
+
+        // the first request and two retries
+        for _ in 0..3 {
+            next_hello_request(&mut gateway_receiver).await;
+            gateway_sender.send(hello_reply("0.0.0.0")).await.unwrap();
+        }
+
+        let (tunnel, result) = client.await.unwrap();
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            tr!("error-no-office-mode-address", address = "0.0.0.0")
+        );
+
+        // no further request is sent
+        let next = tokio::time::timeout(RECEIVE_TIMEOUT, gateway_receiver.next()).await;
+        assert!(next.is_err());
+        drop(tunnel);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_client_hello_reconnect_limit() {
+        let params = TunnelParams {
+            retries: Retries {
+                hello_reconnect: 2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (mut tunnel, gateway_sender, gateway_receiver, _dir) = hello_tunnel(params);
+
+        // every reconnect hands the gateway end of the new connection to the test
+        let (connections_sender, mut connections) = tokio::sync::mpsc::unbounded_channel();
+        tunnel.connector = Box::new(move |_: Arc<TunnelParams>| {
+            let (client, server) = tokio::io::duplex(4096);
+            let _ = connections_sender.send(make_channel(server));
+            futures::future::ready(anyhow::Ok(make_channel(client))).boxed()
+        });
+
+        let client = tokio::spawn(async move {
+            let result = tunnel.client_hello(false).await;
+            (tunnel, result)
+        });
+
+        let reconnect = || -> SslPacketType {
+            ControlPacket::Reconnect(ReconnectReplyData {
+                message: Some("Gateway is busy".to_owned()),
+            })
+            .into()
+        };
+
+        // the first connection and one per allowed reconnect
+        let (mut gateway_sender, mut gateway_receiver) = (gateway_sender, gateway_receiver);
+        for attempt in 0..=2 {
+            if attempt > 0 {
+                (gateway_sender, gateway_receiver) = tokio::time::timeout(RECEIVE_TIMEOUT, connections.recv())
+                    .await
+                    .unwrap()
+                    .unwrap();
+            }
+            next_hello_request(&mut gateway_receiver).await;
+            gateway_sender.send(reconnect()).await.unwrap();
+        }
+
+        let (tunnel, result) = client.await.unwrap();
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            tr!("error-gateway-reconnect", message = "Gateway is busy")
+        );
+
+        // no connection is opened beyond the configured attempts
+        drop(tunnel);
+        assert!(connections.recv().await.is_none());
     }
 }
@@ -2,60 +2,57 @@ use std::fmt;
 
 use anyhow::anyhow;
 use bytes::{Buf, BufMut, BytesMut};
-use serde::Serialize;
 use tokio_util::codec::{Decoder, Encoder};
 
 use crate::{
-    model::proto::{
-        ClientHello, ClientHelloData, DisconnectRequest, DisconnectRequestData, KeepaliveRequest, KeepaliveRequestData,
-    },
+    model::proto::{ClientHelloData, ControlPacket, DisconnectRequestData, KeepaliveRequestData},
     sexpr::SExpression,
 };
 
 pub enum SslPacketType {
-    Control(SExpression),
+    Control(ControlPacket),
     Data(Vec<u8>),
 }
 
 impl fmt::Debug for SslPacketType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            SslPacketType::Control(expr) => write!(f, "CONTROL: {}", expr.object_name().unwrap_or("???")),
+            SslPacketType::Control(packet) => {
+                let name = packet.name();
+                write!(f, "CONTROL: {}", if name.is_empty() { "???" } else { name })
+            }
             SslPacketType::Data(data) => write!(f, "DATA: {} bytes", data.len()),
         }
     }
 }
 
-impl SslPacketType {
-    pub fn control<T>(data: T) -> Self
-    where
-        T: Serialize + Default,
-    {
-        SslPacketType::Control(data.into())
-    }
-}
-
 impl From<Vec<u8>> for SslPacketType {
     fn from(value: Vec<u8>) -> Self {
         SslPacketType::Data(value)
     }
 }
 
+impl From<ControlPacket> for SslPacketType {
+    fn from(value: ControlPacket) -> Self {
+        SslPacketType::Control(value)
+    }
+}
+
 impl From<ClientHelloData> for SslPacketType {
     fn from(value: ClientHelloData) -> Self {
-        SslPacketType::control(ClientHello { data: value })
+        SslPacketType::Control(value.into())
     }
 }
 
 impl From<KeepaliveRequestData> for SslPacketType {
     fn from(value: KeepaliveRequestData) -> Self {
-        SslPacketType::control(KeepaliveRequest { data: value })
+        SslPacketType::Control(value.into())
     }
 }
 
 impl From<DisconnectRequestData> for SslPacketType {
     fn from(value: DisconnectRequestData) -> Self {
-        SslPacketType::control(DisconnectRequest { data: value })
+        SslPacketType::Control(value.into())
     }
 }
 
@@ -81,7 +78,8 @@ impl Decoder for SslPacketCodec {
             1 => {
                 let s_data = String::from_utf8_lossy(&src[8..8 + len]).into_owned();
                 src.advance(8 + len);
-                Ok(Some(SslPacketType::Control(s_data.parse()?)))
+                let expr = s_data.parse::<SExpression>()?;
+                Ok(Some(SslPacketType::Control(ControlPacket::parse(&expr)?)))
             }
             2 => {
                 let data = src[8..8 + len].to_vec();
@@ -98,8 +96,8 @@ impl Encoder<SslPacketType> for SslPacketCodec {
 
     fn encode(&mut self, item: SslPacketType, dst: &mut BytesMut) -> Result<(), Self::Error> {
         let (data, packet_type) = match item {
-            SslPacketType::Control(expr) => {
-                let mut data = expr.to_string().into_bytes();
+            SslPacketType::Control(packet) => {
+                let mut data = packet.into_sexpr().to_string().into_bytes();
                 data.push(b'\x00');
                 (data, 1u32)
             }
@@ -118,3 +116,124 @@ impl Encoder<SslPacketType> for SslPacketCodec {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::proto::{
+        ClientSettingsData, ErrorReplyData, NetworkRange, OfficeMode, OptionalRequest, PoliciesAndVersions,
+        ReconnectReplyData,
+    };
+
+    fn round_trip(packet: ControlPacket) -> ControlPacket {
+        let mut data = BytesMut::new();
+        SslPacketCodec.encode(packet.into(), &mut data).unwrap();
+
+        match SslPacketCodec.decode(&mut data).unwrap() {
+            Some(SslPacketType::Control(packet)) => {
+                assert!(data.is_empty());
+                packet
+            }
+            other => panic!("Expected control packet, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_round_trip_client_hello() {
+        let packet = ControlPacket::ClientHello(ClientHelloData {
+            client_version: "1".to_owned(),
+            protocol_version: 1,
+            protocol_minor_version: 1,
+            office_mode: OfficeMode {
+                ipaddr: "10.0.0.10".to_owned(),
+                keep_address: Some(true),
+                ..Default::default()
+            },
+            optional: Some(OptionalRequest {
+                client_type: "4".to_owned(),
+            }),
+            cookie: "x1234567890".to_owned(),
+        });
+        assert_eq!(round_trip(packet.clone()), packet);
+    }
+
+    #[test]
+    fn test_round_trip_hello_reply() {
+        let data = std::fs::read_to_string("tests/hello_reply.txt").unwrap();
+        let packet = ControlPacket::parse(&data.parse().unwrap()).unwrap();
+        assert_eq!(packet.name(), "hello_reply");
+        assert_eq!(round_trip(packet.clone()), packet);
+    }
+
+    #[test]
+    fn test_round_trip_keepalive() {
+        let packet = ControlPacket::Keepalive(KeepaliveRequestData { id: "0".to_owned() });
+        assert_eq!(round_trip(packet.clone()), packet);
+    }
+
+    #[test]
+    fn test_round_trip_disconnect() {
+        let packet = ControlPacket::Disconnect(DisconnectRequestData::user_disconnect());
+        assert_eq!(round_trip(packet.clone()), packet);
+    }
+
+    #[test]
+    fn test_round_trip_error() {
+        let packet = ControlPacket::Error(ErrorReplyData {
+            code: 1,
+            message: Some("Session expired".to_owned()),
+        });
+        assert_eq!(round_trip(packet.clone()), packet);
+    }
+
+    #[test]
+    fn test_round_trip_reconnect() {
+        let packet = ControlPacket::Reconnect(ReconnectReplyData {
+            message: Some("Gateway is busy".to_owned()),
+        });
+        assert_eq!(round_trip(packet.clone()), packet);
+    }
+
+    #[test]
+    fn test_round_trip_client_settings() {
+        let packet = ControlPacket::ClientSettings(ClientSettingsData {
+            requested_policies_and_current_versions: PoliciesAndVersions {
+                range: vec![NetworkRange {
+                    from: "10.0.0.0".parse().unwrap(),
+                    to: "10.255.255.255".parse().unwrap(),
+                }],
+                nemo_client_1: Some("default".to_owned()),
+            },
+        });
+        assert_eq!(round_trip(packet.clone()), packet);
+    }
+
+    #[test]
+    fn test_round_trip_unknown() {
+        let packet = ControlPacket::Unknown {
+            name: "client_settings_update".to_owned(),
+            value: serde_json::json!({ "(client_settings_update": { "policy": "default" } }),
+        };
+        assert_eq!(round_trip(packet.clone()), packet);
+    }
+
+    #[test]
+    fn test_decode_lenient_fields() {
+        // the keepalive reply of the gateway may come without the identifier
+        let expr = "(keepalive\n\t:sequence (5))".parse().unwrap();
+        assert_eq!(
+            ControlPacket::parse(&expr).unwrap(),
+            ControlPacket::Keepalive(KeepaliveRequestData::default())
+        );
+
+        let expr = "(error\n\t:code (1)\n\t:message (\"Gateway error\"))".parse().unwrap();
+        let ControlPacket::Error(reply) = ControlPacket::parse(&expr).unwrap() else {
+            panic!("Expected error reply");
+        };
+        assert_eq!(reply.code, 1);
+
+        // the content of a known packet must match
+        let expr = "(error\n\t:code (\"unknown\"))".parse().unwrap();
+        assert!(ControlPacket::parse(&expr).is_err());
+    }
+}
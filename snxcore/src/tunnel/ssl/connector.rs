@@ -8,6 +8,7 @@ use tracing::{debug, warn};
 
 use crate::{
     ccc::CccHttpClient,
+    error::AuthRejected,
     model::{
         MfaChallenge, MfaType, SessionState, VpnSession,
         params::{CertType, TunnelParams},
@@ -47,7 +48,7 @@ impl CccTunnelConnector {
             "done" => {}
             other => {
                 warn!("Authn status: {}", other);
-                anyhow::bail!(tr!("error-auth-failed"));
+                return Err(AuthRejected(tr!("error-auth-failed")).into());
             }
         }
 
@@ -59,7 +60,7 @@ impl CccTunnelConnector {
                     _ => tr!("error-auth-failed"),
                 };
                 warn!("{}", msg);
-                anyhow::bail!(msg);
+                return Err(AuthRejected(msg).into());
             }
         };
 
@@ -157,6 +158,21 @@ impl TunnelConnector for CccTunnelConnector {
             TunnelEvent::Rekeyed(_) => {
                 debug!("Tunnel rekeyed");
             }
+            TunnelEvent::SessionExpiring(remaining) => {
+                debug!("Session expiring in {} seconds", remaining.as_secs());
+            }
+            TunnelEvent::TrustedNetworkDetected => {
+                debug!("Trusted network detected");
+            }
+            TunnelEvent::TrustedNetworkLost => {
+                debug!("Trusted network lost");
+            }
+            TunnelEvent::DnsChecked(results) => {
+                debug!("DNS self-test results: {:?}", results);
+            }
+            TunnelEvent::Keepalive(ok) => {
+                debug!("Keepalive succeeded: {}", ok);
+            }
         }
         Ok(())
     }
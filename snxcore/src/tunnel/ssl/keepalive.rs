@@ -1,18 +1,20 @@
 use std::{
     sync::{
         Arc,
-        atomic::{AtomicI64, Ordering},
+        atomic::{AtomicI64, AtomicU8, Ordering},
     },
     time::Duration,
 };
 
 use futures::{SinkExt, channel::oneshot};
-use tracing::{trace, warn};
+use tokio::sync::mpsc;
+use tracing::{Instrument, trace, warn};
 
 use crate::{
     model::proto::KeepaliveRequestData,
     platform::{self, NetworkInterface},
-    tunnel::ssl::PacketSender,
+    tunnel::{KeepaliveReporter, TunnelEvent, ssl::PacketSender},
+    util,
 };
 
 const KEEPALIVE_MAX_RETRIES: i64 = 3;
@@ -20,16 +22,26 @@ const SEND_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub struct KeepaliveRunner {
     interval: Duration,
+    jitter: Arc<AtomicU8>,
     sender: PacketSender,
     keepalive_counter: Arc<AtomicI64>,
+    events: mpsc::Sender<TunnelEvent>,
 }
 
 impl KeepaliveRunner {
-    pub fn new(interval: Duration, sender: PacketSender, counter: Arc<AtomicI64>) -> Self {
+    pub fn new(
+        interval: Duration,
+        jitter: Arc<AtomicU8>,
+        sender: PacketSender,
+        counter: Arc<AtomicI64>,
+        events: mpsc::Sender<TunnelEvent>,
+    ) -> Self {
         Self {
             interval,
+            jitter,
             sender,
             keepalive_counter: counter,
+            events,
         }
     }
 
@@ -37,35 +49,49 @@ impl KeepaliveRunner {
         let (stop_sender, stop_receiver) = oneshot::channel();
 
         let interval = self.interval;
+        let jitter = self.jitter.clone();
         let keepalive_counter = self.keepalive_counter.clone();
         let mut sender = self.sender.clone();
+        let mut reporter = KeepaliveReporter::new(self.events.clone());
 
-        tokio::spawn(async move {
-            loop {
-                if platform::new_network_interface().is_online() {
-                    if keepalive_counter.load(Ordering::SeqCst) >= KEEPALIVE_MAX_RETRIES {
-                        let msg = "No response for keepalive packets, tunnel appears stuck";
-                        warn!(msg);
-                        break;
-                    }
+        tokio::spawn(
+            async move {
+                let mut sent = false;
+                loop {
+                    if platform::new_network_interface().is_online() {
+                        // the requests without a reply, the counter is reset by any received data
+                        let unanswered = keepalive_counter.load(Ordering::SeqCst);
+                        if sent {
+                            reporter.report(unanswered == 0).await;
+                        }
+
+                        if unanswered >= KEEPALIVE_MAX_RETRIES {
+                            let msg = "No response for keepalive packets, tunnel appears stuck";
+                            warn!(msg);
+                            break;
+                        }
 
-                    let req = KeepaliveRequestData { id: "0".to_string() };
-                    trace!("Keepalive request: {:?}", req);
+                        let req = KeepaliveRequestData { id: "0".to_string() };
+                        trace!("Keepalive request: {:?}", req);
 
-                    keepalive_counter.fetch_add(1, Ordering::SeqCst);
+                        keepalive_counter.fetch_add(1, Ordering::SeqCst);
+                        sent = true;
 
-                    match tokio::time::timeout(SEND_TIMEOUT, sender.send(req.into())).await {
-                        Ok(Ok(())) => {}
-                        _ => {
-                            warn!("Cannot send keepalive packet, exiting");
-                            break;
+                        match tokio::time::timeout(SEND_TIMEOUT, sender.send(req.into())).await {
+                            Ok(Ok(())) => {}
+                            _ => {
+                                warn!("Cannot send keepalive packet, exiting");
+                                break;
+                            }
                         }
                     }
+                    let delay = util::jitter_duration(interval, jitter.load(Ordering::SeqCst), &mut rand::rng());
+                    tokio::time::sleep(delay).await;
                 }
-                tokio::time::sleep(interval).await;
+                let _ = stop_sender.send(());
             }
-            let _ = stop_sender.send(());
-        });
+            .in_current_span(),
+        );
 
         let _ = stop_receiver.await;
     }
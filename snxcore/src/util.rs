@@ -1,26 +1,32 @@
 use std::{
     collections::HashMap,
     ffi::OsStr,
-    fmt,
+    fmt, fs,
     future::Future,
+    io::{self, Write},
     net::{IpAddr, Ipv4Addr, ToSocketAddrs},
     path::Path,
     process::Output,
+    time::Duration,
 };
 
 use anyhow::{Context, anyhow};
 use cached::proc_macro::cached;
-use ipnet::{Ipv4Net, Ipv4Subnets};
+use i18n::tr;
+use ipnet::{Ipv4Net, Ipv4Subnets, Ipv6Net, Ipv6Subnets};
 use itertools::Itertools;
-use tokio::process::Command;
-use tracing::trace;
+use rand::Rng;
+use tokio::{process::Command, sync::watch};
+use tracing::{level_filters::LevelFilter, trace, warn};
+use tracing_subscriber::filter::Targets;
 use uuid::Uuid;
 
 use crate::{
     model::{
         params::TunnelParams,
-        proto::{LoginOption, NetworkRange},
+        proto::{Ipv6NetworkRange, NetworkRange},
     },
+    platform::LocalNetwork,
     server_info,
 };
 
@@ -101,83 +107,117 @@ pub fn ranges_to_subnets(ranges: &[NetworkRange]) -> impl Iterator<Item = Ipv4Ne
     ranges.iter().flat_map(|r| Ipv4Subnets::new(r.from, r.to, 0))
 }
 
-pub async fn print_login_options(params: &TunnelParams) -> anyhow::Result<()> {
-    let info = server_info::get(params).await?;
-
-    let mut values = vec![
-        ("login-options-server-address".to_owned(), params.server_name.clone()),
-        (
-            "login-options-server-ip".to_owned(),
-            info.connectivity_info.server_ip.to_string(),
-        ),
-        (
-            "login-options-client-enabled".to_owned(),
-            info.connectivity_info.client_enabled.to_string(),
-        ),
-        (
-            "login-options-supported-protocols".to_owned(),
-            info.connectivity_info.supported_data_tunnel_protocols.join(", "),
-        ),
-        (
-            "login-options-preferred-protocol".to_owned(),
-            info.connectivity_info.connectivity_type,
-        ),
-        (
-            "login-options-tcpt-port".to_owned(),
-            info.connectivity_info.tcpt_port.to_string(),
-        ),
-        (
-            "login-options-natt-port".to_owned(),
-            info.connectivity_info.natt_port.to_string(),
-        ),
-    ];
-
-    for fingerprint in info.connectivity_info.internal_ca_fingerprint.values() {
-        values.push((
-            "login-options-internal-ca-fingerprint".to_owned(),
-            String::from_utf8_lossy(&snx_decrypt(fingerprint.as_bytes())?).into_owned(),
-        ));
-    }
-
-    let mut options_list = info
-        .login_options_data
-        .map(|data| data.login_options_list)
-        .unwrap_or_default();
+pub fn ranges_to_subnets_v6(ranges: &[Ipv6NetworkRange]) -> impl Iterator<Item = Ipv6Net> + '_ {
+    ranges.iter().flat_map(|r| Ipv6Subnets::new(r.from, r.to, 0))
+}
 
-    if options_list.is_empty() {
-        options_list.insert(String::new(), LoginOption::unspecified());
+/// Subtract the excluded prefixes from the routes. Routes which contain an exclusion are split
+/// into the smallest set of more specific prefixes covering the rest of the route.
+pub fn exclude_routes(routes: &[Ipv4Net], exclusions: &[Ipv4Net]) -> Vec<Ipv4Net> {
+    fn subtract(route: Ipv4Net, exclusion: &Ipv4Net) -> Vec<Ipv4Net> {
+        if exclusion.contains(&route) {
+            Vec::new()
+        } else if !route.contains(exclusion) {
+            vec![route]
+        } else {
+            route
+                .subnets(route.prefix_len() + 1)
+                .into_iter()
+                .flatten()
+                .flat_map(|half| subtract(half, exclusion))
+                .collect()
+        }
     }
 
-    for opt in options_list.into_values().filter(|opt| opt.show_realm != 0) {
-        let factors = opt.factors.into_values().map(|factor| factor.factor_type).join(", ");
-        values.push((format!("[{}]", opt.display_name), format!("{} ({})", opt.id, factors)));
-    }
+    exclusions
+        .iter()
+        .fold(
+            routes.iter().map(Ipv4Net::trunc).collect::<Vec<_>>(),
+            |routes, exclusion| routes.into_iter().flat_map(|r| subtract(r, exclusion)).collect(),
+        )
+        .into_iter()
+        .unique()
+        .collect()
+}
 
-    let label_width = values
+/// Tunnel network which overlaps a network reachable through a local interface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddressConflict {
+    pub tunnel: Ipv4Net,
+    pub local: LocalNetwork,
+}
+
+/// Find the overlaps between the office mode network or the tunnel routes and the local networks.
+/// A default route is not a conflict, it is expected to cover all local networks.
+pub fn find_address_conflicts(tunnel: &[Ipv4Net], local: &[LocalNetwork]) -> Vec<AddressConflict> {
+    tunnel
         .iter()
-        .map(|(label, _)| {
-            if label.starts_with("[") {
-                label.chars().count()
-            } else {
-                i18n::translate(label).chars().count()
-            }
+        .filter(|net| net.prefix_len() > 0)
+        .flat_map(|net| {
+            local
+                .iter()
+                .filter(|l| net.contains(&l.network.network()) || l.network.contains(&net.network()))
+                .map(|l| AddressConflict {
+                    tunnel: net.trunc(),
+                    local: l.clone(),
+                })
         })
-        .max()
-        .unwrap_or_default();
-    let mut result = String::new();
-    for (index, (key, value)) in values.iter().enumerate() {
-        let key_str = if key.starts_with("[") {
-            key.clone()
-        } else {
-            i18n::translate(key)
-        };
-        result.push_str(&format!("{:>label_width$}: {}", key_str, value));
-        if index < values.len() - 1 {
-            result.push('\n');
-        }
+        .collect()
+}
+
+// Linux limits interface names to IFNAMSIZ - 1 bytes
+const MAX_DEVICE_NAME_LEN: usize = 15;
+const DEVICE_INDEX_PLACEHOLDER: &str = "%d";
+const MAX_DEVICE_INDEX: u32 = 256;
+
+/// Check that the interface name, or a template with a single `%d` placeholder, is accepted by the kernel.
+pub fn validate_device_name(name: &str) -> anyhow::Result<()> {
+    let candidate = name.replacen(DEVICE_INDEX_PLACEHOLDER, "0", 1);
+
+    let valid = !candidate.is_empty()
+        && candidate.len() <= MAX_DEVICE_NAME_LEN
+        && candidate != "."
+        && candidate != ".."
+        && !candidate.contains(|c: char| c == '/' || c == ':' || c == '%' || c.is_whitespace() || c.is_control());
+
+    anyhow::ensure!(valid, tr!("error-invalid-device-name", name = name));
+
+    Ok(())
+}
+
+/// Expand the interface name template, replacing `%d` with the lowest index which is not in use.
+/// Names without the placeholder are returned as is.
+pub fn expand_device_name<F>(template: &str, in_use: F) -> anyhow::Result<String>
+where
+    F: Fn(&str) -> bool,
+{
+    validate_device_name(template)?;
+
+    if !template.contains(DEVICE_INDEX_PLACEHOLDER) {
+        return Ok(template.to_owned());
     }
 
-    println!("{}", result);
+    (0..MAX_DEVICE_INDEX)
+        .map(|index| template.replacen(DEVICE_INDEX_PLACEHOLDER, &index.to_string(), 1))
+        .take_while(|name| name.len() <= MAX_DEVICE_NAME_LEN)
+        .find(|name| !in_use(name))
+        .ok_or_else(|| anyhow!(tr!("error-no-free-device-name", name = template)))
+}
+
+/// Check that the network namespace name can be used as a file name under /run/netns and /etc/netns.
+pub fn validate_namespace_name(name: &str) -> anyhow::Result<()> {
+    let valid = !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(|c: char| c == '/' || c.is_whitespace() || c.is_control());
+
+    anyhow::ensure!(valid, tr!("error-invalid-netns-name", name = name));
+
+    Ok(())
+}
+
+pub async fn print_login_options(params: &TunnelParams) -> anyhow::Result<()> {
+    println!("{}", server_info::get_gateway_info(params).await?.print());
     Ok(())
 }
 
@@ -221,10 +261,427 @@ pub fn parse_config<S: AsRef<str>>(config: S) -> anyhow::Result<HashMap<String,
     Ok(result)
 }
 
+const TOML_PROFILES_KEY: &str = "profile";
+const TOML_DEFAULT_PROFILE_KEY: &str = "default-profile";
+
+/// Options of a configuration file: the global ones and the named profiles which inherit them.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ConfigSections {
+    pub values: HashMap<String, String>,
+    pub default_profile: Option<String>,
+    pub profiles: HashMap<String, HashMap<String, String>>,
+}
+
+/// Parse the TOML configuration into the same options as the plain configuration files,
+/// the arrays become comma-separated lists. The `[profile.<name>]` tables are the named profiles,
+/// the `default-profile` key selects one of them. The parse error tells the line and column.
+pub fn parse_toml_config<S: AsRef<str>>(config: S) -> anyhow::Result<ConfigSections> {
+    let mut table = config.as_ref().parse::<toml::Table>()?;
+
+    let default_profile = match table.remove(TOML_DEFAULT_PROFILE_KEY) {
+        Some(toml::Value::String(name)) => Some(name),
+        Some(_) => anyhow::bail!("{} must be a string", TOML_DEFAULT_PROFILE_KEY),
+        None => None,
+    };
+
+    let mut profiles = HashMap::new();
+    match table.remove(TOML_PROFILES_KEY) {
+        Some(toml::Value::Table(sections)) => {
+            for (name, section) in sections {
+                match section {
+                    toml::Value::Table(section) => {
+                        profiles.insert(name, toml_table_to_config(section));
+                    }
+                    _ => warn!("Ignoring profile which is not a table: {}", name),
+                }
+            }
+        }
+        Some(_) => warn!("Ignoring option with unsupported value: {}", TOML_PROFILES_KEY),
+        None => {}
+    }
+
+    Ok(ConfigSections {
+        values: toml_table_to_config(table),
+        default_profile,
+        profiles,
+    })
+}
+
+fn toml_table_to_config(table: toml::Table) -> HashMap<String, String> {
+    let mut result = HashMap::new();
+    add_toml_table(&mut result, "", table);
+    result
+}
+
+// The nested tables such as `[timeouts]` are the sections of the dotted options, `timeouts.connect`.
+fn add_toml_table(result: &mut HashMap<String, String>, prefix: &str, table: toml::Table) {
+    for (k, v) in table {
+        let k = format!("{prefix}{k}");
+        if let toml::Value::Table(section) = v {
+            add_toml_table(result, &format!("{k}."), section);
+            continue;
+        }
+        match toml_value_to_string(&v) {
+            Some(v) if v.is_empty() => {}
+            Some(v) => {
+                result.insert(k, v);
+            }
+            None => warn!("Ignoring option with unsupported value: {}", k),
+        }
+    }
+}
+
+fn toml_value_to_string(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(s) => Some(s.clone()),
+        toml::Value::Integer(i) => Some(i.to_string()),
+        toml::Value::Float(f) => Some(f.to_string()),
+        toml::Value::Boolean(b) => Some(b.to_string()),
+        toml::Value::Datetime(d) => Some(d.to_string()),
+        toml::Value::Array(values) => values
+            .iter()
+            .map(toml_value_to_string)
+            .collect::<Option<Vec<_>>>()
+            .map(|values| values.join(",")),
+        toml::Value::Table(_) => None,
+    }
+}
+
+pub(crate) fn plain_value_to_toml(value: &str) -> toml::Value {
+    if let Ok(b) = value.parse::<bool>() {
+        toml::Value::Boolean(b)
+    } else {
+        match value.parse::<i64>() {
+            Ok(i) if i.to_string() == value => toml::Value::Integer(i),
+            _ => toml::Value::String(value.to_owned()),
+        }
+    }
+}
+
+pub(crate) fn plain_config_lines(config: &str) -> impl Iterator<Item = (&str, &str)> {
+    config
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .filter(|(_, v)| !v.is_empty())
+}
+
+/// Convert the plain configuration to TOML. Only the values which read back unchanged are written
+/// as booleans and numbers, everything else is a string.
+pub fn config_to_toml<S: AsRef<str>>(config: S) -> String {
+    let mut result = String::new();
+
+    for (k, v) in plain_config_lines(config.as_ref()) {
+        result.push_str(&format!("{k} = {}\n", plain_value_to_toml(v)));
+    }
+
+    result
+}
+
+/// Replace the given profile of the existing TOML configuration with the plain configuration,
+/// the global options and the other profiles are kept.
+pub fn update_toml_profile<S: AsRef<str>>(existing: &str, config: S, profile: &str) -> anyhow::Result<String> {
+    let mut table = existing.parse::<toml::Table>()?;
+
+    let section = plain_config_lines(config.as_ref())
+        .map(|(k, v)| (k.to_owned(), plain_value_to_toml(v)))
+        .collect::<toml::Table>();
+
+    let profiles = table
+        .entry(TOML_PROFILES_KEY)
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    let Some(profiles) = profiles.as_table_mut() else {
+        anyhow::bail!("{} must be a table", TOML_PROFILES_KEY);
+    };
+    profiles.insert(profile.to_owned(), toml::Value::Table(section));
+
+    Ok(toml::to_string(&table)?)
+}
+
+/// Set the given options of the existing TOML configuration, in the section of the profile if one is given.
+/// The other options, sections and profiles are kept.
+pub fn set_toml_options(existing: &str, options: &[(&str, &str)], profile: Option<&str>) -> anyhow::Result<String> {
+    let mut table = existing.parse::<toml::Table>()?;
+
+    let section = match profile {
+        Some(profile) => {
+            let profiles = table
+                .entry(TOML_PROFILES_KEY)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            let Some(profiles) = profiles.as_table_mut() else {
+                anyhow::bail!("{} must be a table", TOML_PROFILES_KEY);
+            };
+            let section = profiles
+                .entry(profile)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            let Some(section) = section.as_table_mut() else {
+                anyhow::bail!("{}.{} must be a table", TOML_PROFILES_KEY, profile);
+            };
+            section
+        }
+        None => &mut table,
+    };
+
+    for (k, v) in options {
+        section.insert((*k).to_owned(), plain_value_to_toml(v));
+    }
+
+    Ok(toml::to_string(&table)?)
+}
+
+/// Set the given options of the existing plain configuration, replacing their lines and keeping the other ones.
+pub fn set_plain_options(existing: &str, options: &[(&str, &str)]) -> String {
+    let mut result = String::new();
+
+    for line in existing.lines() {
+        let key = line.split_once('=').map(|(k, _)| k.trim());
+        if !key.is_some_and(|key| options.iter().any(|(k, _)| *k == key)) {
+            result.push_str(line);
+            result.push('\n');
+        }
+    }
+
+    for (k, v) in options {
+        result.push_str(&format!("{k}={v}\n"));
+    }
+
+    result
+}
+
+pub const MAX_JITTER_PERCENT: u8 = 50;
+
+/// Randomize the given duration by +/- `percent` to avoid synchronized timers across many clients.
+pub fn jitter_duration<R: Rng>(duration: Duration, percent: u8, rng: &mut R) -> Duration {
+    let percent = percent.min(MAX_JITTER_PERCENT) as i64;
+    if percent == 0 {
+        return duration;
+    }
+    let millis = duration.as_millis() as i64;
+    let delta = millis * rng.random_range(-percent..=percent) / 100;
+    Duration::from_millis((millis + delta).max(0) as u64)
+}
+
+const REDACTED_KEYS: &[&str] = &["cookie", "password", "secret", "token", "key"];
+
+/// Replace values of credential-like fields with a placeholder, for dumping protocol data into logs.
+pub fn redact_json(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                let k = k.to_lowercase();
+                if REDACTED_KEYS.iter().any(|r| k.contains(r)) && !v.is_object() && !v.is_array() {
+                    *v = serde_json::Value::String("***".to_owned());
+                } else {
+                    redact_json(v);
+                }
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// Parse the value of `log-filter` or of the `set-log-level` command: a single level applies to all modules,
+/// otherwise it is a comma-separated list of `target=level` directives with an optional default level.
+pub fn parse_log_filter(filter: &str) -> anyhow::Result<Targets> {
+    let filter = filter.trim();
+
+    // a bare word would be taken for a target enabled at every level
+    if !filter.contains(['=', ',']) {
+        let level = filter
+            .parse::<LevelFilter>()
+            .map_err(|_| anyhow!(tr!("error-invalid-log-level", level = filter)))?;
+        return Ok(Targets::new().with_default(level));
+    }
+
+    filter
+        .parse::<Targets>()
+        .map_err(|e| anyhow!(tr!("error-invalid-log-filter", filter = filter, error = e.to_string())))
+}
+
+// group and other permission bits
+#[cfg(unix)]
+const MODE_NOT_OWNER: u32 = 0o077;
+
+/// Refuse a file holding secrets which other users could read or swap: it must be accessible by the owner only,
+/// and a symbolic link may only point into its own directory.
+#[cfg(unix)]
+pub fn check_secret_file(path: &Path) -> anyhow::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if fs::symlink_metadata(path)?.file_type().is_symlink() {
+        let target = fs::canonicalize(path)?;
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => fs::canonicalize(dir)?,
+            _ => std::env::current_dir()?,
+        };
+        if target.parent() != Some(dir.as_path()) {
+            anyhow::bail!(tr!(
+                "error-secret-file-symlink",
+                path = path.display().to_string(),
+                target = target.display().to_string()
+            ));
+        }
+    }
+
+    let mode = fs::metadata(path)?.permissions().mode();
+    if mode & MODE_NOT_OWNER != 0 {
+        anyhow::bail!(tr!(
+            "error-secret-file-permissions",
+            path = path.display().to_string(),
+            mode = format!("{:o}", mode & 0o777)
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn check_secret_file(_path: &Path) -> anyhow::Result<()> {
+    Ok(())
+}
+
+/// Write a file holding secrets: a new file is created with mode 0600 instead of the one of the umask,
+/// an existing one is restricted to its owner before the data is written. A symbolic link is not followed.
+#[cfg(unix)]
+pub fn write_private_file<P: AsRef<Path>, D: AsRef<[u8]>>(path: P, data: D) -> io::Result<()> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .custom_flags(libc::O_NOFOLLOW)
+        .open(path)?;
+
+    if file.metadata()?.permissions().mode() & MODE_NOT_OWNER != 0 {
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+
+    file.write_all(data.as_ref())
+}
+
+#[cfg(not(unix))]
+pub fn write_private_file<P: AsRef<Path>, D: AsRef<[u8]>>(path: P, data: D) -> io::Result<()> {
+    fs::File::create(path)?.write_all(data.as_ref())
+}
+
+/// Create the directory with its missing parents, accessible by the owner only.
+#[cfg(unix)]
+pub fn create_private_dir<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+
+    fs::DirBuilder::new().recursive(true).mode(0o700).create(path)
+}
+
+#[cfg(not(unix))]
+pub fn create_private_dir<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    fs::create_dir_all(path)
+}
+
+const NETWORK_SETTLE_DELAY: Duration = Duration::from_secs(3);
+
+/// Debounce bursts of route and address changes, e.g. during Wi-Fi roaming.
+pub async fn wait_for_network_settle(changes: &mut watch::Receiver<u64>) {
+    while let Ok(Ok(())) = tokio::time::timeout(NETWORK_SETTLE_DELAY, changes.changed()).await {}
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn nets(s: &[&str]) -> Vec<Ipv4Net> {
+        s.iter().map(|n| n.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_device_name() {
+        assert!(validate_device_name("snx0").is_ok());
+        assert!(validate_device_name("snx-tun%d").is_ok());
+        assert!(validate_device_name("").is_err());
+        assert!(validate_device_name("averyverylongname").is_err());
+        assert!(validate_device_name("snx/0").is_err());
+        assert!(validate_device_name("snx 0").is_err());
+        assert!(validate_device_name("snx%d%d").is_err());
+
+        let in_use = |name: &str| name == "snx0" || name == "snx1";
+        assert_eq!(expand_device_name("snx%d", in_use).unwrap(), "snx2");
+        assert_eq!(expand_device_name("snx0", in_use).unwrap(), "snx0");
+        assert!(expand_device_name("snx-tunnel-%d", |_| true).is_err());
+    }
+
+    #[test]
+    fn test_namespace_name() {
+        assert!(validate_namespace_name("vpn").is_ok());
+        assert!(validate_namespace_name("").is_err());
+        assert!(validate_namespace_name("..").is_err());
+        assert!(validate_namespace_name("../vpn").is_err());
+        assert!(validate_namespace_name("vpn ns").is_err());
+    }
+
+    #[test]
+    fn test_exclude_routes() {
+        let routes = nets(&["10.0.0.0/8", "172.16.0.0/16"]);
+
+        assert_eq!(exclude_routes(&routes, &[]), routes);
+        assert_eq!(
+            exclude_routes(&routes, &nets(&["172.16.0.0/12"])),
+            nets(&["10.0.0.0/8"])
+        );
+        assert_eq!(
+            exclude_routes(&routes, &nets(&["10.128.0.0/9", "192.168.0.0/16"])),
+            nets(&["10.0.0.0/9", "172.16.0.0/16"])
+        );
+
+        let result = exclude_routes(&nets(&["10.0.0.0/8"]), &nets(&["10.1.2.0/24"]));
+        assert_eq!(result.len(), 16);
+        assert!(
+            result
+                .iter()
+                .all(|r| !r.contains(&"10.1.2.1".parse::<Ipv4Addr>().unwrap()))
+        );
+        assert!(result.contains(&"10.1.3.0/24".parse().unwrap()));
+        assert!(result.contains(&"10.128.0.0/9".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_exclude_gateway_address() {
+        let gateway = Ipv4Net::from("10.0.0.1".parse::<Ipv4Addr>().unwrap());
+        let result = exclude_routes(&nets(&["10.0.0.0/30"]), &[gateway]);
+        assert_eq!(result, nets(&["10.0.0.0/32", "10.0.0.2/31"]));
+    }
+
+    #[test]
+    fn test_find_address_conflicts() {
+        let local = vec![
+            LocalNetwork {
+                device: "wlan0".to_owned(),
+                network: "192.168.1.0/24".parse().unwrap(),
+            },
+            LocalNetwork {
+                device: "docker0".to_owned(),
+                network: "172.17.0.0/16".parse().unwrap(),
+            },
+        ];
+
+        assert!(find_address_conflicts(&nets(&["10.0.0.0/8", "0.0.0.0/0"]), &local).is_empty());
+
+        let conflicts = find_address_conflicts(&nets(&["192.168.1.77/32", "172.16.0.0/12"]), &local);
+        assert_eq!(
+            conflicts,
+            vec![
+                AddressConflict {
+                    tunnel: "192.168.1.77/32".parse().unwrap(),
+                    local: local[0].clone(),
+                },
+                AddressConflict {
+                    tunnel: "172.16.0.0/12".parse().unwrap(),
+                    local: local[1].clone(),
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_encode_decode() {
         let username = "testuser";
@@ -248,6 +705,150 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_toml_config() {
+        let config = "# comment\nserver-name = \"vpn.example.com\"\ndefault-route = true\nmtu = 1350\n\
+                      search-domains = [\"a.com\", \"b.com\"]\nlog-level = \"\"\n[timeouts]\nconnect = 5\n";
+        let parsed = parse_toml_config(config).unwrap();
+        assert_eq!(
+            parsed.values,
+            HashMap::from([
+                ("server-name".to_owned(), "vpn.example.com".to_owned()),
+                ("default-route".to_owned(), "true".to_owned()),
+                ("mtu".to_owned(), "1350".to_owned()),
+                ("search-domains".to_owned(), "a.com,b.com".to_owned()),
+                ("timeouts.connect".to_owned(), "5".to_owned()),
+            ])
+        );
+        assert!(parsed.profiles.is_empty());
+
+        let error = parse_toml_config("server-name = \"vpn\"\ndefault-route = \n").unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn test_parse_toml_profiles() {
+        let config = "default-profile = \"work\"\nlogin-type = \"vpn\"\n\
+                      [profile.work]\nserver-name = \"work.example.com\"\n\
+                      [profile.lab]\nserver-name = \"lab.example.com\"\nlogin-type = \"vpn_Cert\"\n\
+                      [profile.lab.retries]\nhello = 5\n";
+        let parsed = parse_toml_config(config).unwrap();
+
+        assert_eq!(parsed.default_profile.as_deref(), Some("work"));
+        assert_eq!(
+            parsed.values,
+            HashMap::from([("login-type".to_owned(), "vpn".to_owned())])
+        );
+        assert_eq!(parsed.profiles.len(), 2);
+        assert_eq!(parsed.profiles["lab"]["login-type"], "vpn_Cert");
+        assert_eq!(parsed.profiles["lab"]["retries.hello"], "5");
+        assert_eq!(parsed.profiles["work"]["server-name"], "work.example.com");
+    }
+
+    #[test]
+    fn test_config_to_toml() {
+        let config = "server-name=vpn.example.com\nuser-name=007\ndefault-route=false\nmtu=1350\nlog-level=\n\
+                      timeouts.connect=5\n";
+        let toml = config_to_toml(config);
+        assert_eq!(
+            toml,
+            "server-name = \"vpn.example.com\"\nuser-name = \"007\"\ndefault-route = false\nmtu = 1350\n\
+             timeouts.connect = 5\n"
+        );
+        assert_eq!(parse_toml_config(&toml).unwrap().values, parse_config(config).unwrap());
+    }
+
+    #[test]
+    fn test_update_toml_profile() {
+        let existing = "login-type = \"vpn\"\n[profile.work]\nserver-name = \"work.example.com\"\n\
+                        [profile.lab]\nserver-name = \"lab.example.com\"\n";
+        let updated = update_toml_profile(existing, "server-name=new.example.com\nmtu=1350\n", "work").unwrap();
+        let parsed = parse_toml_config(updated).unwrap();
+
+        assert_eq!(parsed.values["login-type"], "vpn");
+        assert_eq!(parsed.profiles["work"]["server-name"], "new.example.com");
+        assert_eq!(parsed.profiles["work"]["mtu"], "1350");
+        assert_eq!(parsed.profiles["lab"]["server-name"], "lab.example.com");
+    }
+
+    #[test]
+    fn test_set_options() {
+        let existing = "login-type = \"vpn\"\n[profile.lab]\nserver-name = \"lab.example.com\"\n";
+        let updated = set_toml_options(existing, &[("server-name", "new.example.com")], Some("work")).unwrap();
+        let parsed = parse_toml_config(updated).unwrap();
+
+        assert_eq!(parsed.values["login-type"], "vpn");
+        assert_eq!(parsed.profiles["work"]["server-name"], "new.example.com");
+        assert_eq!(parsed.profiles["lab"]["server-name"], "lab.example.com");
+
+        let updated = set_toml_options(existing, &[("user-name", "12345")], None).unwrap();
+        let parsed = parse_toml_config(updated).unwrap();
+        assert_eq!(parsed.values["user-name"], "12345");
+        assert_eq!(parsed.values["login-type"], "vpn");
+
+        let updated = set_plain_options(
+            "# comment\nserver-name=\nmtu=1350\n",
+            &[("server-name", "vpn.example.com")],
+        );
+        assert_eq!(updated, "# comment\nmtu=1350\nserver-name=vpn.example.com\n");
+    }
+
+    #[test]
+    fn test_redact_json() {
+        let mut value = serde_json::json!({
+            "(hello_reply": {
+                "cookie": "abcdef",
+                "OM": { "ipaddr": "10.0.0.1", "keep_address": true },
+                "range": [{ "from": "10.0.0.0", "session_key": "1234" }]
+            }
+        });
+        redact_json(&mut value);
+        assert_eq!(value["(hello_reply"]["cookie"], "***");
+        assert_eq!(value["(hello_reply"]["OM"]["ipaddr"], "10.0.0.1");
+        assert_eq!(value["(hello_reply"]["OM"]["keep_address"], true);
+        assert_eq!(value["(hello_reply"]["range"][0]["session_key"], "***");
+    }
+
+    #[test]
+    fn test_jitter_duration() {
+        use rand::{SeedableRng, rngs::StdRng};
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let interval = Duration::from_secs(20);
+
+        let samples = (0..1000)
+            .map(|_| jitter_duration(interval, 10, &mut rng))
+            .collect::<Vec<_>>();
+
+        let min = samples.iter().min().unwrap();
+        let max = samples.iter().max().unwrap();
+
+        assert!(*min >= Duration::from_secs(18));
+        assert!(*max <= Duration::from_secs(22));
+        assert!(*min < Duration::from_millis(18500));
+        assert!(*max > Duration::from_millis(21500));
+
+        assert_eq!(jitter_duration(interval, 0, &mut rng), interval);
+        assert!(jitter_duration(interval, 200, &mut rng) <= Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_parse_log_filter() {
+        use tracing::Level;
+
+        let filter = parse_log_filter("debug").unwrap();
+        assert_eq!(filter.default_level(), Some(LevelFilter::DEBUG));
+
+        let filter = parse_log_filter("info,snxcore::tunnel=warn,snxcore::platform::linux::resolver=trace").unwrap();
+        assert!(filter.would_enable("snx_rs", &Level::INFO));
+        assert!(!filter.would_enable("snx_rs", &Level::DEBUG));
+        assert!(!filter.would_enable("snxcore::tunnel::ssl", &Level::INFO));
+        assert!(filter.would_enable("snxcore::platform::linux::resolver", &Level::TRACE));
+
+        assert!(parse_log_filter("loud").is_err());
+        assert!(parse_log_filter("snxcore::tunnel=loud").is_err());
+    }
+
     #[test]
     fn parse_range() {
         let ipaddr = "10.0.10.10".parse::<Ipv4Addr>().unwrap();
@@ -263,4 +864,59 @@ mod tests {
             assert_eq!(subnet.to_string(), "10.0.0.0/8");
         }
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_secret_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snx-rs.conf");
+        fs::write(&path, "password=secret\n").unwrap();
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        let error = check_secret_file(&path).unwrap_err().to_string();
+        assert!(error.contains("644"));
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+        assert!(check_secret_file(&path).is_err());
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        check_secret_file(&path).unwrap();
+
+        // a link next to the file is fine, a link into another directory is not
+        let link = dir.path().join("current.conf");
+        std::os::unix::fs::symlink(&path, &link).unwrap();
+        check_secret_file(&link).unwrap();
+
+        let other = tempfile::tempdir().unwrap();
+        let link = other.path().join("snx-rs.conf");
+        std::os::unix::fs::symlink(&path, &link).unwrap();
+        assert!(check_secret_file(&link).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_private_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let sessions = dir.path().join("cache/sessions");
+        create_private_dir(&sessions).unwrap();
+        assert_eq!(fs::metadata(&sessions).unwrap().permissions().mode() & 0o777, 0o700);
+
+        let path = sessions.join("vpn.example.com");
+        write_private_file(&path, b"session").unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+
+        // an existing file left readable by an earlier version is restricted
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        write_private_file(&path, b"new session").unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(fs::read(&path).unwrap(), b"new session");
+
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&path, &link).unwrap();
+        assert!(write_private_file(&link, b"elsewhere").is_err());
+    }
 }
@@ -21,6 +21,13 @@ pub struct CmdlineParams {
         help = "Configuration file to use [default: $HOME/.config/snx-rs/snx-rs.conf]"
     )]
     config_file: Option<PathBuf>,
+    #[clap(
+        long = "profile",
+        short = 'P',
+        global = true,
+        help = "Named profile of the TOML configuration file to use"
+    )]
+    profile: Option<String>,
     #[clap(subcommand)]
     command: SnxCommand,
 }
@@ -37,6 +44,8 @@ enum SnxCommand {
     Status,
     #[clap(name = "info", about = "Show server information")]
     Info,
+    #[clap(name = "reset-stats", about = "Reset tunnel statistics")]
+    ResetStats,
     #[clap(name = "completions", about = "Generate shell completions")]
     Completions {
         #[clap(
@@ -55,6 +64,7 @@ impl From<SnxCommand> for ServiceCommand {
             SnxCommand::Reconnect => ServiceCommand::Reconnect,
             SnxCommand::Status => ServiceCommand::Status,
             SnxCommand::Info => ServiceCommand::Info,
+            SnxCommand::ResetStats => ServiceCommand::ResetStats,
             SnxCommand::Completions { .. } => unreachable!("Handled separately in main"),
         }
     }
@@ -94,7 +104,11 @@ async fn main() -> anyhow::Result<()> {
         .clone()
         .unwrap_or_else(TunnelParams::default_config_path);
 
-    let tunnel_params = Arc::new(TunnelParams::load(config_file).unwrap_or_default());
+    let tunnel_params = match params.profile {
+        Some(ref profile) => TunnelParams::load_profile(config_file, Some(profile))?,
+        None => TunnelParams::load(config_file).unwrap_or_default(),
+    };
+    let tunnel_params = Arc::new(tunnel_params);
 
     let subscriber = tracing_subscriber::fmt()
         .with_max_level(